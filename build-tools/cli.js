@@ -204,7 +204,7 @@ program
 
 program
     .command('toolchain')
-    .description('Package local emsdk + cmake into Tauri resources for bundling')
+    .description('Package engine source, cmake and the runtime shell into Tauri resources for bundling')
     .option('--no-strip', 'Skip stripping unnecessary files')
     .option('--no-archive', 'Skip creating tar.gz archive')
     .action(async (options) => {
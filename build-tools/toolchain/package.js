@@ -1,7 +1,8 @@
 #!/usr/bin/env node
 
 /**
- * Packages engine source and cmake into Tauri resources for editor bundling.
+ * Packages engine source, cmake and the desktop runtime shell into Tauri
+ * resources for editor bundling.
  * All paths and versions are driven by toolchain.manifest.json.
  *
  * Usage: node package.js
@@ -256,6 +257,47 @@ async function packageCmake(manifest) {
     }
 }
 
+// =============================================================================
+// Runtime shell packaging
+// =============================================================================
+
+const SHELL_MANIFEST = path.join(ROOT_DIR, 'desktop/runtime-shell/Cargo.toml');
+const SHELL_TARGET_DIR = path.join(ROOT_DIR, 'desktop/runtime-shell/target');
+
+function buildShell(target) {
+    const targetArg = target ? ` --target ${target}` : '';
+    execSync(`cargo build --release --manifest-path "${SHELL_MANIFEST}"${targetArg}`, { stdio: 'inherit' });
+    return path.join(SHELL_TARGET_DIR, target ?? '', 'release', 'esengine-runtime');
+}
+
+/**
+ * Builds the runtime shell that desktop exports are stamped into, for the
+ * host platform: a universal binary on macOS, an .exe on Windows.
+ */
+async function packageRuntimeShell() {
+    const platform = os.platform();
+    if (platform !== 'darwin' && platform !== 'win32') {
+        log('Runtime shell: skipped (desktop exports target Windows and macOS)');
+        return;
+    }
+
+    log('Building runtime shell...');
+    const shellDir = path.join(OUTPUT_DIR, 'runtime-shell', platform === 'darwin' ? 'macos' : 'windows');
+    await rm(shellDir, { recursive: true, force: true });
+    await mkdir(shellDir, { recursive: true });
+
+    if (platform === 'darwin') {
+        const dest = path.join(shellDir, 'esengine-runtime');
+        const slices = ['aarch64-apple-darwin', 'x86_64-apple-darwin'].map(buildShell);
+        execSync(`lipo -create -output "${dest}" ${slices.map(s => `"${s}"`).join(' ')}`, { stdio: 'inherit' });
+        await chmod(dest, 0o755);
+    } else {
+        await cp(`${buildShell()}.exe`, path.join(shellDir, 'esengine-runtime.exe'));
+    }
+
+    log(`Runtime shell: ${formatSize(await dirSize(shellDir))}`);
+}
+
 // =============================================================================
 // Main
 // =============================================================================
@@ -266,6 +308,7 @@ async function main() {
 
     await packageEngineSource(manifest);
     await packageCmake(manifest);
+    await packageRuntimeShell();
 
     const totalSize = await dirSize(OUTPUT_DIR);
     log(`\nDone! Total toolchain: ${formatSize(totalSize)}`);
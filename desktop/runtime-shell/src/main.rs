@@ -0,0 +1,253 @@
+//! Runtime shell — plays an exported desktop game in a native WebView
+//!
+//! The editor's desktop export stamps this executable with the game's web
+//! build and window configuration (see `packaging::payload` in the editor):
+//!
+//! ```text
+//! [zip of web build][config JSON][zip len: u64 LE][config len: u64 LE][MAGIC]
+//! ```
+//!
+//! - the payload follows the executable itself on Windows; a macOS bundle
+//!   keeps it in `Contents/Resources/game.espayload` so the binary stays
+//!   signed
+//! - the web build is unpacked into memory and served over the `esgame`
+//!   protocol, starting at `index.html`
+
+#![cfg_attr(windows, windows_subsystem = "windows")]
+
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use tao::dpi::LogicalSize;
+use tao::event::{Event, WindowEvent};
+use tao::event_loop::{ControlFlow, EventLoop};
+use tao::window::{Fullscreen, Window, WindowBuilder};
+use wry::http::{header, Request, Response, StatusCode};
+use wry::{WebView, WebViewBuilder};
+
+const MAGIC: &[u8; 8] = b"ESPAYLD1";
+const TRAILER_LEN: usize = 8 + 8 + MAGIC.len();
+const BUNDLE_FILE: &str = "game.espayload";
+const PROTOCOL: &str = "esgame";
+
+/// WebView2 exposes custom protocols as `http://<scheme>.localhost`.
+#[cfg(windows)]
+const START_URL: &str = "http://esgame.localhost/index.html";
+#[cfg(not(windows))]
+const START_URL: &str = "esgame://localhost/index.html";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Mirrors the editor's `ShellWindowConfig`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct WindowConfig {
+    title: String,
+    width: u32,
+    height: u32,
+    resizable: bool,
+    fullscreen: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "Game".to_string(),
+            width: 1280,
+            height: 720,
+            resizable: true,
+            fullscreen: false,
+        }
+    }
+}
+
+struct Game {
+    /// Web build files by path relative to its root.
+    files: HashMap<String, Vec<u8>>,
+    window: WindowConfig,
+}
+
+// =============================================================================
+// Main
+// =============================================================================
+
+fn main() {
+    let game = match load() {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("[runtime] {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = run(game) {
+        eprintln!("[runtime] {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(game: Game) -> Result<(), String> {
+    let event_loop = EventLoop::new();
+    let config = &game.window;
+    let mut builder = WindowBuilder::new()
+        .with_title(&config.title)
+        .with_inner_size(LogicalSize::new(config.width, config.height))
+        .with_resizable(config.resizable);
+    if config.fullscreen {
+        builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+    let window = builder.build(&event_loop).map_err(|e| format!("Failed to create window: {}", e))?;
+
+    // The files live as long as the process; leaking them lets responses borrow.
+    let files: &'static HashMap<String, Vec<u8>> = Box::leak(Box::new(game.files));
+    let webview = build_webview(
+        &window,
+        WebViewBuilder::new()
+            .with_custom_protocol(PROTOCOL.to_string(), move |_, request| serve(files, &request))
+            .with_url(START_URL),
+    )
+    .map_err(|e| format!("Failed to create WebView: {}", e))?;
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        let _ = &webview;
+        if let Event::WindowEvent { event: WindowEvent::CloseRequested, .. } = event {
+            *control_flow = ControlFlow::Exit;
+        }
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn build_webview(window: &Window, builder: WebViewBuilder) -> wry::Result<WebView> {
+    builder.build(window)
+}
+
+#[cfg(target_os = "linux")]
+fn build_webview(window: &Window, builder: WebViewBuilder) -> wry::Result<WebView> {
+    use tao::platform::unix::WindowExtUnix;
+    use wry::WebViewBuilderExtUnix;
+    builder.build_gtk(window.default_vbox().ok_or(wry::Error::MessageSender)?)
+}
+
+// =============================================================================
+// Payload
+// =============================================================================
+
+/// Reads the payload after the executable, else from the macOS bundle.
+fn load() -> Result<Game, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate executable: {}", e))?;
+    let data = std::fs::read(&exe).map_err(|e| format!("Failed to read {}: {}", exe.display(), e))?;
+    if data.ends_with(MAGIC) {
+        return parse(&data);
+    }
+    let bundled = bundle_payload(&exe).ok_or_else(|| "This executable carries no game".to_string())?;
+    let data = std::fs::read(&bundled).map_err(|_| "This executable carries no game".to_string())?;
+    parse(&data)
+}
+
+/// `<Name>.app/Contents/Resources/BUNDLE_FILE` for `<Name>.app/Contents/MacOS/<Name>`.
+fn bundle_payload(exe: &Path) -> Option<PathBuf> {
+    let contents = exe.parent()?.parent()?;
+    Some(contents.join("Resources").join(BUNDLE_FILE))
+}
+
+fn parse(data: &[u8]) -> Result<Game, String> {
+    if data.len() < TRAILER_LEN || !data.ends_with(MAGIC) {
+        return Err("Game payload is missing its trailer".to_string());
+    }
+    let trailer = data.len() - TRAILER_LEN;
+    let zip_len = read_len(&data[trailer..trailer + 8]);
+    let config_len = read_len(&data[trailer + 8..trailer + 16]);
+    let config_start = trailer.checked_sub(config_len).ok_or("Game payload is truncated")?;
+    let zip_start = config_start.checked_sub(zip_len).ok_or("Game payload is truncated")?;
+
+    let window = serde_json::from_slice(&data[config_start..trailer])
+        .map_err(|e| format!("Invalid window configuration: {}", e))?;
+    let mut archive = zip::ZipArchive::new(Cursor::new(&data[zip_start..config_start]))
+        .map_err(|e| format!("Invalid game archive: {}", e))?;
+    let mut files = HashMap::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| format!("Invalid game archive: {}", e))?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut bytes).map_err(|e| format!("Failed to unpack {}: {}", file.name(), e))?;
+        files.insert(file.name().to_string(), bytes);
+    }
+    Ok(Game { files, window })
+}
+
+fn read_len(bytes: &[u8]) -> usize {
+    let mut le = [0u8; 8];
+    le.copy_from_slice(bytes);
+    u64::from_le_bytes(le) as usize
+}
+
+// =============================================================================
+// Protocol
+// =============================================================================
+
+fn serve(files: &'static HashMap<String, Vec<u8>>, request: &Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let path = percent_decode(request.uri().path().trim_start_matches('/'));
+    let path = if path.is_empty() { "index.html".to_string() } else { path };
+    let response = Response::builder().header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    let response = match files.get(&path) {
+        Some(bytes) => response
+            .header(header::CONTENT_TYPE, mime_type(&path))
+            .body(Cow::Borrowed(bytes.as_slice())),
+        None => response.status(StatusCode::NOT_FOUND).body(Cow::Borrowed(&[][..])),
+    };
+    response.unwrap_or_else(|_| Response::new(Cow::Borrowed(&[][..])))
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn mime_type(path: &str) -> &'static str {
+    match path.rsplit('.').next() {
+        Some("html") => "text/html",
+        Some("js") | Some("mjs") => "application/javascript",
+        Some("wasm") => "application/wasm",
+        Some("json") => "application/json",
+        Some("css") => "text/css",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("txt") => "text/plain",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("ogg") => "audio/ogg",
+        Some("aac") => "audio/aac",
+        Some("flac") => "audio/flac",
+        Some("webm") => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
@@ -1,3 +1,168 @@
+use std::path::{Path, PathBuf};
+
+/// Files embedded in the editor, by the name `embedded_assets.rs` includes
+/// them under. Each is stored zstd-compressed, after its size as 8
+/// little-endian bytes.
+const EMBEDDED: &[(&str, &str)] = &[
+    ("ENGINE_JS", "../public/wasm/esengine.js"),
+    ("ENGINE_WASM", "../public/wasm/esengine.wasm"),
+    ("ESBUILD_WASM", "../public/esbuild.wasm"),
+    ("SDK_ESM_JS", "../public/sdk/esm/esengine.bundled.js"),
+    ("SDK_ESM_JS_MAP", "../public/sdk/esm/esengine.bundled.js.map"),
+    ("SDK_ESM_DTS", "../public/sdk/esm/esengine.d.ts"),
+    ("SDK_WASM_JS", "../public/sdk/esm/wasm.js"),
+    ("SDK_WASM_DTS", "../public/sdk/esm/wasm.d.ts"),
+    ("SDK_WASM_JS_MAP", "../public/sdk/esm/wasm.js.map"),
+    ("SDK_SPINE_JS", "../public/sdk/esm/spine/index.js"),
+    ("SDK_SPINE_JS_MAP", "../public/sdk/esm/spine/index.js.map"),
+    ("SDK_SHARED_INDEX_JS", "../public/sdk/esm/shared/index.js"),
+    ("SDK_SHARED_INDEX_JS_MAP", "../public/sdk/esm/shared/index.js.map"),
+    ("SDK_SHARED_MATERIAL_JS", "../public/sdk/esm/shared/material.js"),
+    ("SDK_SHARED_MATERIAL_JS_MAP", "../public/sdk/esm/shared/material.js.map"),
+    ("SDK_SHARED_SPINEMODULELOADER_JS", "../public/sdk/esm/shared/SpineModuleLoader.js"),
+    ("SDK_SHARED_SPINEMODULELOADER_JS_MAP", "../public/sdk/esm/shared/SpineModuleLoader.js.map"),
+    ("SDK_SHARED_WASM_DTS", "../public/sdk/esm/shared/wasm.d.ts"),
+    ("SDK_SHARED_APP_DTS", "../public/sdk/esm/shared/app.d.ts"),
+    ("SDK_PHYSICS_DTS", "../public/sdk/esm/physics/index.d.ts"),
+    ("SDK_SPINE_DTS", "../public/sdk/esm/spine/index.d.ts"),
+    ("SDK_WECHAT_JS", "../public/sdk/cjs/esengine.wechat.js"),
+    ("EDITOR_DTS", "../../editor/dist/index.d.ts"),
+];
+
+/// Optional runtimes, embedded only when their cargo feature is enabled;
+/// `runtimes.rs` downloads the others on first use.
+const OPTIONAL: &[(&str, &str, &str)] = &[
+    ("spine38", "SPINE38_JS", "../public/wasm/spine38.js"),
+    ("spine38", "SPINE38_WASM", "../public/wasm/spine38.wasm"),
+    ("spine41", "SPINE41_JS", "../public/wasm/spine41.js"),
+    ("spine41", "SPINE41_WASM", "../public/wasm/spine41.wasm"),
+    ("spine42", "SPINE42_JS", "../public/wasm/spine42.js"),
+    ("spine42", "SPINE42_WASM", "../public/wasm/spine42.wasm"),
+    ("physics", "PHYSICS_JS", "../public/wasm/physics.js"),
+    ("physics", "PHYSICS_WASM", "../public/wasm/physics.wasm"),
+    ("rapier", "RAPIER_JS", "../public/wasm/physics-rapier.js"),
+    ("rapier", "RAPIER_WASM", "../public/wasm/physics-rapier.wasm"),
+];
+
+/// Scripting reference pages, packed with the samples into `DOCS`.
+const DOCS_DIR: &str = "../../docs/astro/src/content/docs";
+/// Example projects; their `src/` scripts are the samples.
+const EXAMPLES_DIR: &str = "../../examples";
+
 fn main() {
+    pack_docs();
+    compress_embedded();
     tauri_build::build();
 }
+
+/// Writes `$OUT_DIR/docs.tar`: `docs/<page>.mdx`, `samples/<example>/<file>.ts`
+/// and an `index.json` listing both, with each page's title.
+fn pack_docs() {
+    println!("cargo:rerun-if-changed={}", DOCS_DIR);
+    println!("cargo:rerun-if-changed={}", EXAMPLES_DIR);
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.mode(tar::HeaderMode::Deterministic);
+    let mut append = |name: &str, data: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, name, data)
+            .unwrap_or_else(|e| panic!("failed to pack {}: {}", name, e));
+    };
+
+    let mut pages = Vec::new();
+    for path in files_in(Path::new(DOCS_DIR)) {
+        if !matches!(path.extension().and_then(|ext| ext.to_str()), Some("md" | "mdx")) {
+            continue;
+        }
+        let rel = path.strip_prefix(DOCS_DIR).unwrap().to_string_lossy().replace('\\', "/");
+        let data = std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+        let title = front_matter_title(&String::from_utf8_lossy(&data)).unwrap_or_else(|| rel.clone());
+        append(&format!("docs/{}", rel), &data);
+        pages.push(serde_json::json!({ "path": format!("docs/{}", rel), "title": title }));
+    }
+    let mut samples = Vec::new();
+    for path in files_in(Path::new(EXAMPLES_DIR)) {
+        let rel = path.strip_prefix(EXAMPLES_DIR).unwrap().to_string_lossy().replace('\\', "/");
+        let Some((example, file)) = rel.split_once("/src/") else {
+            continue;
+        };
+        if !file.ends_with(".ts") {
+            continue;
+        }
+        let data = std::fs::read(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+        append(&format!("samples/{}/{}", example, file), &data);
+        samples.push(serde_json::json!({ "path": format!("samples/{}/{}", example, file), "example": example }));
+    }
+    let index = serde_json::json!({ "pages": pages, "samples": samples });
+    append("index.json", &serde_json::to_vec_pretty(&index).unwrap());
+
+    let data = builder.into_inner().expect("failed to finish the docs archive");
+    let target = Path::new(&std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo")).join("docs.tar");
+    if std::fs::read(&target).ok().as_deref() != Some(data.as_slice()) {
+        std::fs::write(&target, data).unwrap_or_else(|e| panic!("failed to write {}: {}", target.display(), e));
+    }
+}
+
+/// Every file under `dir`, sorted so the archive is reproducible.
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.file_name().is_some_and(|name| name == "node_modules") {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// The `title:` of a page's front matter.
+fn front_matter_title(text: &str) -> Option<String> {
+    let body = text.strip_prefix("---")?;
+    let front = &body[..body.find("\n---")?];
+    let title = front.lines().find_map(|line| line.strip_prefix("title:"))?;
+    Some(title.trim().trim_matches(['"', '\'']).to_string())
+}
+
+/// Compresses `EMBEDDED`, the `OPTIONAL` files of enabled features and the
+/// docs archive into `$OUT_DIR/embedded/`. Release builds use a slow,
+/// strong level; debug builds a fast one.
+fn compress_embedded() {
+    let out_dir = Path::new(&std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo")).join("embedded");
+    std::fs::create_dir_all(&out_dir).expect("failed to create the embedded asset folder");
+    let level = if std::env::var("PROFILE").as_deref() == Ok("release") { 19 } else { 3 };
+
+    let enabled = OPTIONAL
+        .iter()
+        .filter(|(feature, _, _)| std::env::var_os(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_some())
+        .map(|(_, name, source)| (*name, *source));
+    let docs = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo") + "/docs.tar";
+    let generated = std::iter::once(("DOCS", docs.as_str()));
+    for (name, source) in EMBEDDED.iter().copied().chain(enabled).chain(generated) {
+        println!("cargo:rerun-if-changed={}", source);
+        let data = std::fs::read(source).unwrap_or_else(|e| panic!("failed to read {}: {}", source, e));
+        let compressed =
+            zstd::bulk::compress(&data, level).unwrap_or_else(|e| panic!("failed to compress {}: {}", source, e));
+        let mut out = Vec::with_capacity(8 + compressed.len());
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        out.extend_from_slice(&compressed);
+        let target = out_dir.join(format!("{}.zst", name));
+        // Rewriting an unchanged file would rebuild the crate for nothing.
+        if std::fs::read(&target).ok().as_deref() != Some(out.as_slice()) {
+            std::fs::write(&target, out).unwrap_or_else(|e| panic!("failed to write {}: {}", target.display(), e));
+        }
+    }
+}
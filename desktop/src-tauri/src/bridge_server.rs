@@ -4,6 +4,7 @@
 //! forwards them to the editor frontend via Tauri events,
 //! and returns the response.
 
+use crate::i18n::BackendError;
 use serde_json::{json, Value};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -40,7 +41,7 @@ impl BridgeServer {
         }
     }
 
-    pub fn start(&mut self, app_handle: AppHandle, project_path: Option<String>) -> Result<u16, String> {
+    pub fn start(&mut self, app_handle: AppHandle, project_path: Option<String>) -> Result<u16, BackendError> {
         if self.server.is_some() {
             return Ok(self.port);
         }
@@ -489,16 +490,22 @@ fn respond_cors_preflight(request: tiny_http::Request) -> Result<(), std::io::Er
 // Port Binding
 // =============================================================================
 
-fn try_bind(base_port: u16) -> Result<Server, String> {
+fn try_bind(base_port: u16) -> Result<Server, BackendError> {
+    let mut last_error = String::new();
     for offset in 0..MAX_PORT_ATTEMPTS {
-        let port = base_port + offset;
-        match Server::http(format!("127.0.0.1:{}", port)) {
+        match Server::http(format!("127.0.0.1:{}", base_port + offset)) {
             Ok(server) => return Ok(server),
-            Err(_) if offset < MAX_PORT_ATTEMPTS - 1 => continue,
-            Err(e) => return Err(format!("Failed to bind port {}-{}: {}", base_port, base_port + MAX_PORT_ATTEMPTS - 1, e)),
+            Err(e) => last_error = e.to_string(),
         }
     }
-    Err("Failed to bind any port".to_string())
+    Err(BackendError::new(
+        "bridge.bindFailed",
+        [
+            ("start", base_port.to_string()),
+            ("end", (base_port + MAX_PORT_ATTEMPTS - 1).to_string()),
+            ("error", last_error),
+        ],
+    ))
 }
 
 // =============================================================================
@@ -579,7 +586,7 @@ fn cleanup_stale_bridge_files() {
 }
 
 #[cfg(target_os = "windows")]
-fn is_process_alive(pid: u32) -> bool {
+pub(crate) fn is_process_alive(pid: u32) -> bool {
     use std::ptr::null_mut;
     const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
     const STILL_ACTIVE: u32 = 259;
@@ -601,7 +608,7 @@ fn is_process_alive(pid: u32) -> bool {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn is_process_alive(pid: u32) -> bool {
+pub(crate) fn is_process_alive(pid: u32) -> bool {
     std::process::Command::new("kill")
         .args(["-0", &pid.to_string()])
         .stdout(std::process::Stdio::null())
@@ -0,0 +1,420 @@
+//! Script bundler — compiles project scripts inside the editor binary
+//!
+//! Bundles `src/**/*.ts` and installed engine plugins into one classic script
+//! with no Node/npm toolchain. TypeScript is parsed with oxc and lowered by
+//! text edits; modules are linked through a small CommonJS-style runtime
+//! (`runtime.js`). Unused exports, and modules nothing uses that have no side
+//! effects, are left out (`shake`), and minification strips comments,
+//! indentation and blank lines and renames local bindings to short names.
+//! `watch` rebuilds a project's scripts whenever they change.
+
+mod resolve;
+mod shake;
+mod transform;
+pub mod watch;
+
+use crate::export::assets;
+use crate::jobs::{JobCategory, JobSpec};
+use oxc_span::SourceType;
+use resolve::Resolved;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shake::{ModuleInfo, Shake};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use transform::{json, TransformError};
+
+const RUNTIME_TEMPLATE: &str = include_str!("runtime.js");
+const DEFAULT_OUTPUT: &str = ".esengine/build/user-scripts.js";
+
+/// Directories skipped when discovering scripts, mirroring the editor's ScriptLoader.
+const IGNORED_DIRS: &[&str] = &["node_modules", "dist", "build", "editor"];
+const SKIP_PACKAGES: &[&str] = &["esengine", "@esengine/editor"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleOptions {
+    pub project_dir: String,
+    /// Defaults to `.esengine/build/user-scripts.js` inside the project.
+    #[serde(default)]
+    pub output_path: Option<String>,
+    #[serde(default)]
+    pub minify: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptError {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleResult {
+    pub success: bool,
+    pub output_path: Option<String>,
+    pub size: u64,
+    /// Bundled module ids in load order.
+    pub modules: Vec<String>,
+    pub errors: Vec<ScriptError>,
+}
+
+pub(crate) struct Bundle {
+    pub code: String,
+    pub modules: Vec<String>,
+    /// Modules transformed for this bundle rather than taken from the cache.
+    pub transformed: usize,
+}
+
+/// Analyzed and transformed modules kept between builds, so a rebuild only
+/// transforms the files whose source, or whose shaking, changed.
+#[derive(Default)]
+pub(crate) struct ModuleCache {
+    modules: HashMap<PathBuf, CachedModule>,
+    minify: bool,
+}
+
+struct CachedModule {
+    source: String,
+    /// `None` for JSON modules.
+    info: Option<ModuleInfo>,
+    /// Resolved imports by specifier; `None` for shims.
+    deps: HashMap<String, Option<PathBuf>>,
+    /// The last transform, with the shaking it was done for.
+    code: Option<(Shake, String)>,
+}
+
+impl ModuleCache {
+    /// Forgets everything; needed when files appear or disappear, since
+    /// imports may then resolve differently.
+    pub fn clear(&mut self) {
+        self.modules.clear();
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn bundle_scripts(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    options: BundleOptions,
+) -> Result<BundleResult, String> {
+    let spec = JobSpec::new(JobCategory::Build, "Bundle scripts").project(&options.project_dir);
+    state.jobs.run(&app, spec, move |_| {
+        let project_dir = PathBuf::from(&options.project_dir);
+        let bundle = match bundle_project(&project_dir, options.minify) {
+            Ok(Some(bundle)) => bundle,
+            Ok(None) => {
+                return Ok(BundleResult {
+                    success: true,
+                    output_path: None,
+                    size: 0,
+                    modules: Vec::new(),
+                    errors: Vec::new(),
+                })
+            }
+            Err(errors) => {
+                return Ok(BundleResult {
+                    success: false,
+                    output_path: None,
+                    size: 0,
+                    modules: Vec::new(),
+                    errors,
+                })
+            }
+        };
+
+        let output = options
+            .output_path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| project_dir.join(DEFAULT_OUTPUT));
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&output, &bundle.code).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+
+        Ok(BundleResult {
+            success: true,
+            output_path: Some(output.to_string_lossy().to_string()),
+            size: bundle.code.len() as u64,
+            modules: bundle.modules,
+            errors: Vec::new(),
+        })
+    })
+    .await
+}
+
+// =============================================================================
+// Bundling
+// =============================================================================
+
+/// Bundles the project's scripts and plugins. Returns `None` when there are none.
+pub(crate) fn bundle_project(project_dir: &Path, minify: bool) -> Result<Option<Bundle>, Vec<ScriptError>> {
+    bundle_project_cached(project_dir, minify, &mut ModuleCache::default())
+}
+
+/// `bundle_project`, reusing the modules in `cache` whose source is unchanged.
+pub(crate) fn bundle_project_cached(
+    project_dir: &Path,
+    minify: bool,
+    cache: &mut ModuleCache,
+) -> Result<Option<Bundle>, Vec<ScriptError>> {
+    if cache.minify != minify {
+        cache.clear();
+        cache.minify = minify;
+    }
+    let mut entries = discover_plugins(project_dir);
+    let src = project_dir.join("src");
+    if src.is_dir() {
+        entries.extend(
+            assets::walk_files(&src)
+                .into_iter()
+                .filter(|p| p.extension().is_some_and(|e| e == "ts") && !is_ignored(&src, p)),
+        );
+    }
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    // Every module reachable from the entries is analyzed, so the plan can
+    // tell which of them are used.
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut scanned: Vec<(String, PathBuf)> = Vec::new();
+    let mut pending: VecDeque<PathBuf> = entries.iter().cloned().collect();
+    while let Some(path) = pending.pop_front() {
+        let id = module_id(project_dir, &path);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let source = match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                errors.push(ScriptError { file: id, line: 0, column: 0, message: e.to_string() });
+                continue;
+            }
+        };
+        if cache.modules.get(&path).is_none_or(|cached| cached.source != source) {
+            match scan(project_dir, &path, source) {
+                Ok(cached) => {
+                    cache.modules.insert(path.clone(), cached);
+                }
+                Err((source, problems)) => {
+                    errors.extend(script_errors(&id, &source, problems));
+                    continue;
+                }
+            }
+        }
+        pending.extend(cache.modules[&path].deps.values().flatten().cloned());
+        scanned.push((id, path));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let index: HashMap<&str, usize> = scanned.iter().enumerate().map(|(i, (id, _))| (id.as_str(), i)).collect();
+    let nodes: Vec<shake::Node> = scanned
+        .iter()
+        .map(|(_, path)| {
+            let cached = &cache.modules[path];
+            let deps = cached
+                .deps
+                .iter()
+                .filter_map(|(specifier, dep)| {
+                    Some((specifier.as_str(), *index.get(module_id(project_dir, dep.as_ref()?).as_str())?))
+                })
+                .collect();
+            shake::Node { info: cached.info.as_ref(), deps }
+        })
+        .collect();
+    let entry_ids: Vec<String> = entries.iter().map(|p| module_id(project_dir, p)).collect();
+    let roots: Vec<usize> = entry_ids.iter().filter_map(|id| index.get(id.as_str()).copied()).collect();
+    let plan = shake::plan(&nodes, &roots);
+    drop(nodes);
+
+    let mut modules: BTreeMap<String, String> = BTreeMap::new();
+    let mut order = Vec::new();
+    let mut transformed = 0;
+    for ((id, path), shake) in scanned.iter().zip(plan) {
+        let Some(shake) = shake else { continue };
+        let Some(cached) = cache.modules.get_mut(path) else { continue };
+        let code = match &cached.code {
+            Some((done, code)) if *done == shake => code.clone(),
+            _ => {
+                transformed += 1;
+                match transform_module(project_dir, path, cached, minify, &shake) {
+                    Ok(code) => {
+                        cached.code = Some((shake, code.clone()));
+                        code
+                    }
+                    Err(problems) => {
+                        errors.extend(script_errors(id, &cached.source, problems));
+                        String::new()
+                    }
+                }
+            }
+        };
+        order.push(id.clone());
+        modules.insert(id.clone(), code);
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let entry_ids: Vec<String> = entry_ids.into_iter().filter(|id| modules.contains_key(id)).collect();
+    Ok(Some(Bundle { code: link(&modules, &entry_ids), modules: order, transformed }))
+}
+
+/// Analyzes a module and resolves its imports. On failure the source is
+/// handed back with the problems.
+fn scan(project_dir: &Path, path: &Path, source: String) -> Result<CachedModule, (String, Vec<TransformError>)> {
+    if is_json(path) {
+        return Ok(CachedModule { source, info: None, deps: HashMap::new(), code: None });
+    }
+    let info = match shake::analyze(&source, source_type(path)) {
+        Ok(info) => info,
+        Err(problems) => return Err((source, problems)),
+    };
+    let mut deps = HashMap::new();
+    let mut problems = Vec::new();
+    for (offset, specifier) in &info.specifiers {
+        match resolve::resolve(project_dir, path, specifier) {
+            Ok(Resolved::File(dep)) => {
+                deps.insert(specifier.clone(), Some(dep));
+            }
+            Ok(Resolved::Shim(_)) => {
+                deps.insert(specifier.clone(), None);
+            }
+            Err(message) => problems.push(TransformError { offset: *offset, message }),
+        }
+    }
+    if !problems.is_empty() {
+        return Err((source, problems));
+    }
+    Ok(CachedModule { source, info: Some(info), deps, code: None })
+}
+
+fn transform_module(
+    project_dir: &Path,
+    path: &Path,
+    cached: &CachedModule,
+    minify: bool,
+    shake: &Shake,
+) -> Result<String, Vec<TransformError>> {
+    if is_json(path) {
+        return Ok(format!("module.exports = {};", cached.source.trim()));
+    }
+    let mut resolve = |specifier: &str| match resolve::resolve(project_dir, path, specifier)? {
+        Resolved::File(dep) => Ok(module_id(project_dir, &dep)),
+        Resolved::Shim(name) => Ok(name),
+    };
+    transform::transform(&cached.source, source_type(path), minify, shake, &mut resolve)
+}
+
+fn script_errors<'e>(
+    id: &'e str,
+    source: &'e str,
+    problems: Vec<TransformError>,
+) -> impl Iterator<Item = ScriptError> + 'e {
+    problems.into_iter().map(move |p| {
+        let (line, column) = line_column(source, p.offset);
+        ScriptError { file: id.to_string(), line, column, message: p.message }
+    })
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "json")
+}
+
+fn source_type(path: &Path) -> SourceType {
+    SourceType::from_path(path).unwrap_or_default().with_module(true)
+}
+
+fn link(modules: &BTreeMap<String, String>, entries: &[String]) -> String {
+    let mut defs = String::new();
+    for (id, code) in modules {
+        defs.push_str(&format!(
+            "        {}: function (exports, require, module) {{\n{}\n}},\n",
+            json(id),
+            code
+        ));
+    }
+    let mut seen = std::collections::HashSet::new();
+    let calls: String = entries
+        .iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .map(|id| format!("    __require({});\n", json(id)))
+        .collect();
+    RUNTIME_TEMPLATE.replace("{{MODULES}}", &defs).replace("{{ENTRIES}}", &calls)
+}
+
+/// Installed packages marked as engine plugins (`"esengine": { "type": "plugin" }`).
+fn discover_plugins(project_dir: &Path) -> Vec<PathBuf> {
+    let node_modules = project_dir.join("node_modules");
+    let mut packages = Vec::new();
+    for entry in read_dirs(&node_modules) {
+        let name = entry.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if name.starts_with('@') {
+            packages.extend(read_dirs(&entry));
+        } else {
+            packages.push(entry);
+        }
+    }
+
+    packages
+        .into_iter()
+        .filter_map(|dir| {
+            let name = assets::relative_path(&node_modules, &dir);
+            if SKIP_PACKAGES.contains(&name.as_str()) {
+                return None;
+            }
+            let manifest: Value = serde_json::from_str(&std::fs::read_to_string(dir.join("package.json")).ok()?).ok()?;
+            if manifest.pointer("/esengine/type").and_then(|t| t.as_str()) != Some("plugin") {
+                return None;
+            }
+            let main = dir.join(manifest.get("main")?.as_str()?);
+            main.is_file().then_some(main)
+        })
+        .collect()
+}
+
+fn read_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn is_ignored(src: &Path, path: &Path) -> bool {
+    let rel = assets::relative_path(src, path);
+    let mut dirs = rel.split('/').collect::<Vec<_>>();
+    dirs.pop();
+    dirs.iter().any(|d| d.starts_with('.') || IGNORED_DIRS.contains(d))
+}
+
+/// Normalized project-relative path, so each file is bundled once.
+fn module_id(project_dir: &Path, path: &Path) -> String {
+    let rel = assets::relative_path(project_dir, path);
+    assets::normalize_path(&rel).unwrap_or(rel)
+}
+
+/// 1-based line and 0-based column of a byte offset.
+fn line_column(source: &str, offset: u32) -> (u32, u32) {
+    let before = &source[..(offset as usize).min(source.len())];
+    let line = before.matches('\n').count() as u32 + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) as u32;
+    (line, column)
+}
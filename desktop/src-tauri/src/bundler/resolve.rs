@@ -0,0 +1,159 @@
+//! Module resolution — relative files, `node_modules` packages and SDK shims
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Specifiers provided at runtime through `__esengine_shim__`.
+const SHIM_MODULES: &[&str] = &["esengine", "@esengine/editor"];
+
+/// Extensions tried, in order, for extensionless imports.
+const EXTENSIONS: &[&str] = &["ts", "js", "mjs", "cjs", "json"];
+
+/// Export conditions checked when a package uses the `exports` field.
+const CONDITIONS: &[&str] = &["browser", "import", "module", "default", "require"];
+
+pub enum Resolved {
+    File(PathBuf),
+    Shim(String),
+}
+
+pub fn resolve(project_dir: &Path, from: &Path, specifier: &str) -> Result<Resolved, String> {
+    let base_dir = from.parent().unwrap_or(project_dir);
+    if specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with('/') {
+        let base = if specifier.starts_with('/') {
+            PathBuf::from(specifier)
+        } else {
+            base_dir.join(specifier)
+        };
+        return resolve_file(&base).map(Resolved::File).ok_or_else(|| not_found(specifier));
+    }
+
+    let (package, subpath) = split_package(specifier);
+    // Runtimes register each shim package once; subpaths like
+    // `esengine/physics` are entry points of the same SDK.
+    if SHIM_MODULES.contains(&package) {
+        return Ok(Resolved::Shim(package.to_string()));
+    }
+    for dir in base_dir.ancestors() {
+        let package_dir = dir.join("node_modules").join(package);
+        if package_dir.is_dir() {
+            let entry = match subpath {
+                Some(sub) => resolve_file(&package_dir.join(sub)),
+                None => resolve_package(&package_dir),
+            };
+            return entry.map(Resolved::File).ok_or_else(|| not_found(specifier));
+        }
+        if dir == project_dir {
+            break;
+        }
+    }
+    Err(format!("Cannot resolve package '{}'. Is it installed in node_modules?", specifier))
+}
+
+fn not_found(specifier: &str) -> String {
+    format!("Cannot resolve '{}'", specifier)
+}
+
+/// `@scope/name/sub` → (`@scope/name`, `sub`)
+fn split_package(specifier: &str) -> (&str, Option<&str>) {
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((i, _)) => (&specifier[..i], Some(&specifier[i + 1..])),
+        None => (specifier, None),
+    }
+}
+
+fn resolve_file(base: &Path) -> Option<PathBuf> {
+    if base.is_file() {
+        return Some(base.to_path_buf());
+    }
+    let name = base.file_name()?.to_string_lossy().to_string();
+    // TypeScript sources import each other with the emitted `.js` extension.
+    if let Some(stem) = name.strip_suffix(".js") {
+        let ts = base.with_file_name(format!("{}.ts", stem));
+        if ts.is_file() {
+            return Some(ts);
+        }
+    }
+    for ext in EXTENSIONS {
+        let candidate = base.with_file_name(format!("{}.{}", name, ext));
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    if base.is_dir() {
+        if base.join("package.json").is_file() {
+            return resolve_package(base);
+        }
+        return EXTENSIONS
+            .iter()
+            .map(|ext| base.join(format!("index.{}", ext)))
+            .find(|p| p.is_file());
+    }
+    None
+}
+
+/// Entry file of a package from `exports`, `module` or `main`.
+fn resolve_package(package_dir: &Path) -> Option<PathBuf> {
+    let manifest: Value = std::fs::read_to_string(package_dir.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(Value::Null);
+
+    let exported = manifest.get("exports").and_then(|e| match e {
+        Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => map.get(".").and_then(pick_condition),
+        other => pick_condition(other),
+    });
+    let entry = exported
+        .or_else(|| manifest.get("module").and_then(|v| v.as_str()).map(String::from))
+        .or_else(|| manifest.get("main").and_then(|v| v.as_str()).map(String::from))
+        .unwrap_or_else(|| "index.js".to_string());
+    let path = package_dir.join(&entry);
+    if path.is_dir() {
+        return EXTENSIONS
+            .iter()
+            .map(|ext| path.join(format!("index.{}", ext)))
+            .find(|p| p.is_file());
+    }
+    resolve_file(&path)
+}
+
+fn pick_condition(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => CONDITIONS
+            .iter()
+            .find_map(|c| map.get(*c))
+            .and_then(pick_condition),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shim(specifier: &str) -> Option<String> {
+        let project = Path::new("/project");
+        match resolve(project, &project.join("src/main.ts"), specifier) {
+            Ok(Resolved::Shim(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn sdk_subpaths_resolve_to_the_sdk_shim() {
+        assert_eq!(shim("esengine").as_deref(), Some("esengine"));
+        assert_eq!(shim("esengine/physics").as_deref(), Some("esengine"));
+        assert_eq!(shim("esengine/spine").as_deref(), Some("esengine"));
+        assert_eq!(shim("@esengine/editor/gizmos").as_deref(), Some("@esengine/editor"));
+    }
+
+    #[test]
+    fn packages_split_after_their_scope() {
+        assert_eq!(split_package("lodash"), ("lodash", None));
+        assert_eq!(split_package("lodash/fp/map"), ("lodash", Some("fp/map")));
+        assert_eq!(split_package("@scope/name"), ("@scope/name", None));
+        assert_eq!(split_package("@scope/name/sub"), ("@scope/name", Some("sub")));
+    }
+}
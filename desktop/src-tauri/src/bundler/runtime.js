@@ -0,0 +1,160 @@
+(() => {
+    const __defs = {
+{{MODULES}}    };
+    const __cache = {};
+    const __shim = globalThis.__esengine_shim__ || {};
+    function __require(id) {
+        const cached = __cache[id];
+        if (cached) return cached.exports;
+        const def = __defs[id];
+        if (!def) {
+            if (id in __shim) return __shim[id];
+            throw new Error('Cannot find module "' + id + '"');
+        }
+        const module = (__cache[id] = { exports: {} });
+        def(module.exports, __require, module);
+        return module.exports;
+    }
+    function __export(target, getters) {
+        Object.defineProperty(target, '__esModule', { value: true });
+        for (const name in getters) {
+            Object.defineProperty(target, name, { get: getters[name], enumerable: true });
+        }
+    }
+    function __reexport(target, source) {
+        for (const name in source) {
+            if (name !== 'default' && !Object.prototype.hasOwnProperty.call(target, name)) {
+                Object.defineProperty(target, name, { get: () => source[name], enumerable: true });
+            }
+        }
+    }
+    function __default(m) {
+        return m && m.__esModule ? m.default : m;
+    }
+    function __decorator() {
+        const metadataKey = Symbol.metadata || Symbol.for('Symbol.metadata');
+        const keys = [];
+        const initializers = [];
+        const fieldExtras = [];
+        const staticExtras = [];
+        const instanceExtras = [];
+        const classExtras = [];
+        let metadata;
+        function defineMetadata(cls) {
+            if (metadata === undefined) {
+                const parent = Object.getPrototypeOf(cls);
+                metadata = Object.create((parent && parent[metadataKey]) || null);
+            }
+            const desc = { value: metadata, writable: true, enumerable: true, configurable: true };
+            Object.defineProperty(cls, metadataKey, desc);
+        }
+        function fn(value, what) {
+            if (typeof value !== 'function') throw new TypeError(what + ' must be a function');
+            return value;
+        }
+        function run(list, self) {
+            for (const init of list) init.call(self);
+        }
+        // Applies decorators closest-first; `accept` takes each returned value.
+        function decorate(decorators, value, context, extras, accept) {
+            for (let i = decorators.length - 1; i >= 0; i--) {
+                let done = false;
+                const result = decorators[i](value, Object.assign({}, context, {
+                    metadata,
+                    addInitializer(init) {
+                        if (done) throw new TypeError('addInitializer cannot be called after decoration has finished');
+                        extras.push(fn(init, 'An initializer'));
+                    },
+                }));
+                done = true;
+                if (result !== undefined) value = accept(result, value);
+            }
+            return value;
+        }
+        function publicAccess(key, kind) {
+            const access = { has: (o) => key in o };
+            if (kind !== 'setter') access.get = (o) => o[key];
+            if (kind !== 'method' && kind !== 'getter') access.set = (o, v) => { o[key] = v; };
+            return access;
+        }
+        const state = {
+            keys,
+            // The class its class decorators returned.
+            cls: undefined,
+            key(i, key) {
+                keys[i] = key;
+                return key;
+            },
+            // Elements are `[kind, key, static, decorators, private access]`, decorated
+            // static methods and accessors first, then instance ones, then fields.
+            elements(cls, elements) {
+                defineMetadata(cls);
+                for (let phase = 0; phase < 4; phase++) {
+                    elements.forEach(([kind, key, isStatic, decorators, access], i) => {
+                        if ((kind === 'field' ? 2 : 0) + (isStatic ? 0 : 1) !== phase) return;
+                        const context = {
+                            kind,
+                            name: key,
+                            static: isStatic,
+                            private: !!access,
+                            access: access || publicAccess(key, kind),
+                        };
+                        if (kind === 'field') {
+                            const inits = (initializers[i] = []);
+                            decorate(decorators, undefined, context, (fieldExtras[i] = []), (init) => {
+                                inits.unshift(fn(init, 'A field decorator result'));
+                            });
+                            return;
+                        }
+                        const target = isStatic ? cls : cls.prototype;
+                        const desc = Object.getOwnPropertyDescriptor(target, key);
+                        const extras = isStatic ? staticExtras : instanceExtras;
+                        if (kind === 'accessor') {
+                            const inits = (initializers[i] = []);
+                            const pair = { get: desc.get, set: desc.set };
+                            const value = decorate(decorators, pair, context, extras, (result, value) => {
+                                if (typeof result !== 'object' || result === null) {
+                                    throw new TypeError('An accessor decorator must return an object');
+                                }
+                                if (result.init !== undefined) inits.unshift(fn(result.init, 'init'));
+                                return {
+                                    get: result.get === undefined ? value.get : fn(result.get, 'get'),
+                                    set: result.set === undefined ? value.set : fn(result.set, 'set'),
+                                };
+                            });
+                            desc.get = value.get;
+                            desc.set = value.set;
+                        } else {
+                            const slot = kind === 'method' ? 'value' : kind === 'getter' ? 'get' : 'set';
+                            const what = 'A ' + kind + ' decorator result';
+                            desc[slot] = decorate(decorators, desc[slot], context, extras, (result) => fn(result, what));
+                        }
+                        Object.defineProperty(target, key, desc);
+                    });
+                }
+                run(staticExtras, cls);
+            },
+            instance(self) {
+                run(instanceExtras, self);
+            },
+            init(self, i, value) {
+                for (const init of initializers[i] || []) value = init.call(self, value);
+                return value;
+            },
+            extra(self, i) {
+                run(fieldExtras[i] || [], self);
+            },
+            decorateClass(cls, decorators) {
+                defineMetadata(cls);
+                const context = { kind: 'class', name: cls.name };
+                cls = decorate(decorators, cls, context, classExtras, (result) => fn(result, 'A class decorator result'));
+                defineMetadata(cls);
+                return (state.cls = cls);
+            },
+            finish(cls) {
+                run(classExtras, cls);
+            },
+        };
+        return state;
+    }
+{{ENTRIES}}})();
@@ -0,0 +1,587 @@
+//! Tree shaking — leaving out code nothing uses
+//!
+//! Each module is analyzed once for its top-level statements: the names they
+//! declare and use, whether running them has side effects, and what they
+//! import. `plan` then works out from the entries what each bundled module
+//! must keep:
+//!
+//! - a statement is kept when it has side effects or declares something a
+//!   kept statement or a used export refers to
+//! - a module is bundled when something uses one of its exports, or when it
+//!   has side effects (its own or those of a module it imports); an entry
+//!   without side effects is left out
+//! - an import none of whose bindings are used becomes a bare `__require`
+//!   when the imported module has side effects, and is dropped otherwise
+//! - `import * as ns`, `import()` and CommonJS `require` use every export;
+//!   CommonJS and JSON modules are bundled whole
+
+use super::transform::TransformError;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::*;
+use oxc_ast_visit::{walk, Visit};
+use oxc_ecmascript::side_effects::{MayHaveSideEffects, MayHaveSideEffectsContext, PropertyReadSideEffects};
+use oxc_ecmascript::GlobalContext;
+use oxc_parser::Parser;
+use oxc_semantic::{Scoping, SemanticBuilder};
+use oxc_span::{GetSpan, SourceType};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// What a module keeps of its source, as decided by `plan`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shake {
+    /// Top-level statements left out, by start offset.
+    pub dropped: BTreeSet<u32>,
+    /// Imports and re-exports kept only for the side effects of their module.
+    pub bare: BTreeSet<u32>,
+    /// Exports other modules use; `None` when all of them are.
+    pub exports: Option<BTreeSet<String>>,
+}
+
+pub struct ModuleInfo {
+    is_esm: bool,
+    statements: Vec<TopLevel>,
+    /// Exported name → local binding, for the module's own declarations.
+    exports: HashMap<String, String>,
+    /// Every import specifier with the offset of its string literal.
+    pub specifiers: Vec<(u32, String)>,
+}
+
+struct TopLevel {
+    start: u32,
+    kind: Kind,
+    declares: Vec<String>,
+    /// Top-level bindings referenced as values.
+    uses: Vec<String>,
+    side_effects: bool,
+    /// Specifiers of `import()` and `require` calls.
+    dynamic: Vec<String>,
+}
+
+enum Kind {
+    Code,
+    /// `import ... from`, with `(local, imported)` bindings.
+    Import { source: String, bindings: Vec<(String, Imported)> },
+    /// `export { a as b } from` and `export * as ns from`, with `(exported, imported)` names.
+    ReExport { source: String, names: Vec<(String, Imported)> },
+    /// `export * from`
+    ExportAll { source: String },
+}
+
+enum Imported {
+    Name(String),
+    Namespace,
+}
+
+/// A module of the bundle graph as `plan` sees it.
+pub struct Node<'m> {
+    /// `None` for JSON modules.
+    pub info: Option<&'m ModuleInfo>,
+    /// Bundled modules by specifier; shims have none.
+    pub deps: HashMap<&'m str, usize>,
+}
+
+// =============================================================================
+// Analysis
+// =============================================================================
+
+/// Analyzes the top-level statements of one module.
+pub fn analyze(source: &str, source_type: SourceType) -> Result<ModuleInfo, Vec<TransformError>> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    if !parsed.errors.is_empty() {
+        return Err(parsed
+            .errors
+            .iter()
+            .map(|e| TransformError {
+                offset: e.labels.as_ref().and_then(|l| l.first()).map(|l| l.offset() as u32).unwrap_or(0),
+                message: e.message.to_string(),
+            })
+            .collect());
+    }
+
+    let program = &parsed.program;
+    let scoping = SemanticBuilder::new().build(program).semantic.into_scoping();
+    let mut info = ModuleInfo {
+        is_esm: program.body.iter().any(|s| s.is_module_declaration()),
+        statements: Vec::new(),
+        exports: HashMap::new(),
+        specifiers: Vec::new(),
+    };
+    let side_effects = SideEffects { scoping: &scoping };
+    for stmt in &program.body {
+        let mut refs = References { scoping: &scoping, is_esm: info.is_esm, uses: Vec::new(), dynamic: Vec::new() };
+        refs.visit_statement(stmt);
+        info.specifiers.extend(refs.dynamic.iter().cloned());
+        let Some((kind, declares, pure)) = top_level(stmt, &mut info, &side_effects) else { continue };
+        let mut uses: Vec<String> = refs.uses.into_iter().filter(|name| !declares.contains(name)).collect();
+        uses.sort();
+        uses.dedup();
+        info.statements.push(TopLevel {
+            start: stmt.span().start,
+            kind,
+            declares,
+            uses,
+            side_effects: !pure,
+            dynamic: refs.dynamic.into_iter().map(|(_, specifier)| specifier).collect(),
+        });
+    }
+    Ok(info)
+}
+
+/// The kind, declared names and purity of a top-level statement, recording
+/// its exports and static specifiers. `None` for statements that produce no
+/// code or only name exports, which the transform handles on its own.
+fn top_level(stmt: &Statement, info: &mut ModuleInfo, ctx: &SideEffects) -> Option<(Kind, Vec<String>, bool)> {
+    let mut specifier = |literal: &StringLiteral| {
+        info.specifiers.push((literal.span.start, literal.value.to_string()));
+        literal.value.to_string()
+    };
+    if !info.is_esm {
+        return Some((Kind::Code, Vec::new(), false));
+    }
+    match stmt {
+        Statement::ImportDeclaration(import) => {
+            if import.import_kind.is_type() {
+                return None;
+            }
+            let mut bindings = Vec::new();
+            if let Some(list) = &import.specifiers {
+                for s in list {
+                    let binding = match s {
+                        ImportDeclarationSpecifier::ImportSpecifier(s) if s.import_kind.is_type() => continue,
+                        ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                            (s.local.name.to_string(), Imported::Name(s.imported.name().to_string()))
+                        }
+                        ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+                            (s.local.name.to_string(), Imported::Name("default".to_string()))
+                        }
+                        ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                            (s.local.name.to_string(), Imported::Namespace)
+                        }
+                    };
+                    bindings.push(binding);
+                }
+                if bindings.is_empty() && !list.is_empty() {
+                    return None;
+                }
+            }
+            let declares = bindings.iter().map(|(local, _)| local.clone()).collect();
+            Some((Kind::Import { source: specifier(&import.source), bindings }, declares, true))
+        }
+        Statement::ExportAllDeclaration(export) => {
+            if export.export_kind.is_type() {
+                return None;
+            }
+            let source = specifier(&export.source);
+            let kind = match &export.exported {
+                Some(name) => Kind::ReExport { source, names: vec![(name.name().to_string(), Imported::Namespace)] },
+                None => Kind::ExportAll { source },
+            };
+            Some((kind, Vec::new(), true))
+        }
+        Statement::ExportNamedDeclaration(export) => {
+            if export.export_kind.is_type() {
+                return None;
+            }
+            let values = export.specifiers.iter().filter(|s| !s.export_kind.is_type());
+            if let Some(source) = &export.source {
+                let names = values
+                    .map(|s| (s.exported.name().to_string(), Imported::Name(s.local.name().to_string())))
+                    .collect();
+                return Some((Kind::ReExport { source: specifier(source), names }, Vec::new(), true));
+            }
+            for s in values {
+                info.exports.insert(s.exported.name().to_string(), s.local.name().to_string());
+            }
+            let (declares, pure) = declaration(export.declaration.as_ref()?, ctx)?;
+            for name in &declares {
+                info.exports.insert(name.clone(), name.clone());
+            }
+            Some((Kind::Code, declares, pure))
+        }
+        Statement::ExportDefaultDeclaration(export) => {
+            let decl = &export.declaration;
+            let (local, pure) = match decl {
+                ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => return None,
+                ExportDefaultDeclarationKind::FunctionDeclaration(f) if f.body.is_none() => return None,
+                ExportDefaultDeclarationKind::FunctionDeclaration(f) => (f.id.as_ref().map(|id| id.name), true),
+                ExportDefaultDeclarationKind::ClassDeclaration(c) => {
+                    (c.id.as_ref().map(|id| id.name), !c.may_have_side_effects(ctx))
+                }
+                _ => (None, decl.as_expression().is_some_and(|e| !e.get_inner_expression().may_have_side_effects(ctx))),
+            };
+            let local = local.map_or_else(|| "__default_export".to_string(), |name| name.to_string());
+            info.exports.insert("default".to_string(), local.clone());
+            Some((Kind::Code, vec![local], pure))
+        }
+        Statement::TSExportAssignment(_) | Statement::TSNamespaceExportDeclaration(_) => {
+            Some((Kind::Code, Vec::new(), false))
+        }
+        _ => match stmt.as_declaration() {
+            Some(decl) => declaration(decl, ctx).map(|(declares, pure)| (Kind::Code, declares, pure)),
+            None => Some((Kind::Code, Vec::new(), false)),
+        },
+    }
+}
+
+/// The names a declaration binds and whether it is free of side effects;
+/// `None` for declarations that produce no code.
+fn declaration(decl: &Declaration, ctx: &SideEffects) -> Option<(Vec<String>, bool)> {
+    let pure = match decl {
+        Declaration::VariableDeclaration(v) if v.declare => return None,
+        Declaration::VariableDeclaration(v) => v.declarations.iter().all(|d| {
+            let init = d.init.as_ref().map(Expression::get_inner_expression);
+            !d.id.may_have_side_effects(ctx) && !init.is_some_and(|init| init.may_have_side_effects(ctx))
+        }),
+        Declaration::FunctionDeclaration(f) if f.is_typescript_syntax() => return None,
+        Declaration::FunctionDeclaration(_) => true,
+        Declaration::ClassDeclaration(c) if c.declare => return None,
+        Declaration::ClassDeclaration(c) => !c.may_have_side_effects(ctx),
+        Declaration::TSEnumDeclaration(e) if e.declare => return None,
+        Declaration::TSEnumDeclaration(e) => e
+            .body
+            .members
+            .iter()
+            .all(|m| !m.initializer.as_ref().is_some_and(|i| i.may_have_side_effects(ctx))),
+        Declaration::TSInterfaceDeclaration(_) | Declaration::TSTypeAliasDeclaration(_) => return None,
+        // Kept, so the transform reports them.
+        Declaration::TSModuleDeclaration(_) | Declaration::TSImportEqualsDeclaration(_) => false,
+        Declaration::TSGlobalDeclaration(_) => return None,
+    };
+    let declares = match decl {
+        Declaration::VariableDeclaration(v) => v
+            .declarations
+            .iter()
+            .flat_map(|d| d.id.get_binding_identifiers())
+            .map(|id| id.name.to_string())
+            .collect(),
+        Declaration::FunctionDeclaration(f) => f.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::ClassDeclaration(c) => c.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::TSEnumDeclaration(e) => vec![e.id.name.to_string()],
+        _ => Vec::new(),
+    };
+    Some((declares, pure))
+}
+
+/// Collects the top-level bindings a statement refers to as values, and the
+/// modules it loads at run time.
+struct References<'s> {
+    scoping: &'s Scoping,
+    is_esm: bool,
+    uses: Vec<String>,
+    dynamic: Vec<(u32, String)>,
+}
+
+impl<'a> Visit<'a> for References<'_> {
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        let Some(reference) = it.reference_id.get().map(|id| self.scoping.get_reference(id)) else { return };
+        let Some(symbol) = reference.symbol_id() else { return };
+        if reference.is_value() && self.scoping.symbol_scope_id(symbol) == self.scoping.root_scope_id() {
+            self.uses.push(self.scoping.symbol_name(symbol).to_string());
+        }
+    }
+
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        if let Expression::StringLiteral(literal) = &it.source {
+            self.dynamic.push((literal.span.start, literal.value.to_string()));
+        }
+        walk::walk_import_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        if !self.is_esm && it.callee.is_specific_id("require") && it.arguments.len() == 1 {
+            if let Some(Argument::StringLiteral(literal)) = it.arguments.first() {
+                self.dynamic.push((literal.span.start, literal.value.to_string()));
+            }
+        }
+        walk::walk_call_expression(self, it);
+    }
+}
+
+/// Side effect rules: `/* @__PURE__ */` calls are pure, while reading a
+/// global or a property may run code.
+struct SideEffects<'s> {
+    scoping: &'s Scoping,
+}
+
+impl<'a> GlobalContext<'a> for SideEffects<'_> {
+    fn is_global_reference(&self, reference: &IdentifierReference<'a>) -> bool {
+        reference.reference_id.get().is_none_or(|id| self.scoping.get_reference(id).symbol_id().is_none())
+    }
+}
+
+impl<'a> MayHaveSideEffectsContext<'a> for SideEffects<'_> {
+    fn annotations(&self) -> bool {
+        true
+    }
+
+    fn manual_pure_functions(&self, _: &Expression) -> bool {
+        false
+    }
+
+    fn property_read_side_effects(&self) -> PropertyReadSideEffects {
+        PropertyReadSideEffects::All
+    }
+
+    fn unknown_global_side_effects(&self) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+enum Work<'m> {
+    Include(usize),
+    Export(usize, &'m str),
+    All(usize),
+    Use(usize, &'m str),
+    Live(usize, usize),
+}
+
+#[derive(Default)]
+struct State<'m> {
+    included: bool,
+    all: bool,
+    exports: BTreeSet<&'m str>,
+    live: HashSet<usize>,
+}
+
+/// What each module keeps, for the bundle of `entries`; `None` for modules
+/// left out.
+pub fn plan(nodes: &[Node], entries: &[usize]) -> Vec<Option<Shake>> {
+    let impure = side_effects(nodes);
+    let dep = |m: usize, specifier: &str| nodes[m].deps.get(specifier).copied();
+    let mut states: Vec<State> = nodes.iter().map(|_| State::default()).collect();
+    let mut work: VecDeque<Work> = entries.iter().filter(|m| impure[**m]).map(|m| Work::Include(*m)).collect();
+
+    while let Some(item) = work.pop_front() {
+        match item {
+            Work::Include(m) => {
+                if std::mem::replace(&mut states[m].included, true) {
+                    continue;
+                }
+                let Some(info) = nodes[m].info else { continue };
+                for (i, stmt) in info.statements.iter().enumerate() {
+                    if stmt.side_effects {
+                        work.push_back(Work::Live(m, i));
+                    }
+                    if let Some(d) = static_source(stmt).and_then(|source| dep(m, source)) {
+                        if impure[d] {
+                            work.push_back(Work::Include(d));
+                        }
+                    }
+                }
+            }
+            Work::All(m) => {
+                work.push_back(Work::Include(m));
+                let state = &mut states[m];
+                if std::mem::replace(&mut state.all, true) {
+                    continue;
+                }
+                let Some(info) = nodes[m].info.filter(|info| info.is_esm) else { continue };
+                work.extend(info.exports.values().map(|local| Work::Use(m, local.as_str())));
+                for (i, stmt) in info.statements.iter().enumerate() {
+                    match &stmt.kind {
+                        Kind::ReExport { source, names } => {
+                            work.push_back(Work::Live(m, i));
+                            if let Some(d) = dep(m, source) {
+                                work.extend(names.iter().map(|(_, imported)| request(d, imported)));
+                            }
+                        }
+                        Kind::ExportAll { source } => {
+                            work.push_back(Work::Live(m, i));
+                            work.extend(dep(m, source).map(Work::All));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Work::Export(m, name) => {
+                work.push_back(Work::Include(m));
+                let state = &mut states[m];
+                if state.all || !state.exports.insert(name) {
+                    continue;
+                }
+                let Some(info) = nodes[m].info.filter(|info| info.is_esm) else { continue };
+                if let Some(local) = info.exports.get(name) {
+                    work.push_back(Work::Use(m, local.as_str()));
+                    continue;
+                }
+                let re_export = info.statements.iter().enumerate().find_map(|(i, stmt)| match &stmt.kind {
+                    Kind::ReExport { source, names } => {
+                        names.iter().find(|(exported, _)| exported == name).map(|(_, imported)| (i, source, imported))
+                    }
+                    _ => None,
+                });
+                if let Some((i, source, imported)) = re_export {
+                    work.push_back(Work::Live(m, i));
+                    work.extend(dep(m, source).map(|d| request(d, imported)));
+                    continue;
+                }
+                if name == "default" {
+                    continue;
+                }
+                for (i, stmt) in info.statements.iter().enumerate() {
+                    if let Kind::ExportAll { source } = &stmt.kind {
+                        work.push_back(Work::Live(m, i));
+                        work.extend(dep(m, source).map(|d| Work::Export(d, name)));
+                    }
+                }
+            }
+            Work::Use(m, name) => {
+                let Some(info) = nodes[m].info else { continue };
+                for (i, stmt) in info.statements.iter().enumerate() {
+                    if !stmt.declares.iter().any(|declared| declared == name) {
+                        continue;
+                    }
+                    // Each binding of an import is requested on its own.
+                    if let Kind::Import { source, bindings } = &stmt.kind {
+                        let imported = bindings.iter().find(|(local, _)| local == name).map(|(_, imported)| imported);
+                        work.extend(dep(m, source).zip(imported).map(|(d, imported)| request(d, imported)));
+                    }
+                    work.push_back(Work::Live(m, i));
+                }
+            }
+            Work::Live(m, i) => {
+                let Some(info) = nodes[m].info else { continue };
+                if !states[m].live.insert(i) {
+                    continue;
+                }
+                let stmt = &info.statements[i];
+                if !info.is_esm {
+                    work.extend(stmt.dynamic.iter().filter_map(|s| dep(m, s)).map(Work::All));
+                    continue;
+                }
+                match &stmt.kind {
+                    Kind::Import { source, .. } | Kind::ReExport { source, .. } | Kind::ExportAll { source } => {
+                        work.extend(dep(m, source).map(Work::Include));
+                    }
+                    Kind::Code => {}
+                }
+                work.extend(stmt.uses.iter().map(|name| Work::Use(m, name.as_str())));
+                work.extend(stmt.dynamic.iter().filter_map(|s| dep(m, s)).map(Work::All));
+            }
+        }
+    }
+
+    nodes
+        .iter()
+        .zip(states)
+        .map(|(node, state)| {
+            if !state.included {
+                return None;
+            }
+            let Some(info) = node.info.filter(|info| info.is_esm) else { return Some(Shake::default()) };
+            let mut shake = Shake {
+                exports: (!state.all).then(|| state.exports.iter().map(|name| name.to_string()).collect()),
+                ..Shake::default()
+            };
+            for (i, stmt) in info.statements.iter().enumerate() {
+                if state.live.contains(&i) {
+                    continue;
+                }
+                match static_source(stmt).and_then(|source| node.deps.get(source)) {
+                    Some(d) if impure[*d] => shake.bare.insert(stmt.start),
+                    _ => shake.dropped.insert(stmt.start),
+                };
+            }
+            Some(shake)
+        })
+        .collect()
+}
+
+/// Whether loading each module has side effects, its own or those of a
+/// module it imports.
+fn side_effects(nodes: &[Node]) -> Vec<bool> {
+    let mut impure: Vec<bool> = nodes
+        .iter()
+        .map(|node| node.info.is_some_and(|info| !info.is_esm || info.statements.iter().any(|s| s.side_effects)))
+        .collect();
+    loop {
+        let mut changed = false;
+        for (m, node) in nodes.iter().enumerate() {
+            let Some(info) = node.info.filter(|_| !impure[m]) else { continue };
+            let imports_impure = info
+                .statements
+                .iter()
+                .filter_map(|stmt| node.deps.get(static_source(stmt)?))
+                .any(|d| impure[*d]);
+            if imports_impure {
+                impure[m] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            return impure;
+        }
+    }
+}
+
+fn static_source(stmt: &TopLevel) -> Option<&str> {
+    match &stmt.kind {
+        Kind::Import { source, .. } | Kind::ReExport { source, .. } | Kind::ExportAll { source } => Some(source),
+        Kind::Code => None,
+    }
+}
+
+fn request<'m>(module: usize, imported: &'m Imported) -> Work<'m> {
+    match imported {
+        Imported::Name(name) => Work::Export(module, name),
+        Imported::Namespace => Work::All(module),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plans modules given as `(source, [(specifier, module)])`, the first being the entry.
+    fn plan_sources(modules: &[(&str, &[(&str, usize)])]) -> Vec<Option<Shake>> {
+        let infos: Vec<ModuleInfo> = modules
+            .iter()
+            .map(|(source, _)| {
+                analyze(source, SourceType::ts().with_module(true)).unwrap_or_else(|_| panic!("{}", source))
+            })
+            .collect();
+        let nodes: Vec<Node> = modules
+            .iter()
+            .zip(&infos)
+            .map(|((_, deps), info)| Node { info: Some(info), deps: deps.iter().copied().collect() })
+            .collect();
+        plan(&nodes, &[0])
+    }
+
+    #[test]
+    fn modules_with_side_effects_are_kept_when_unused() {
+        let entry = "import { unused } from './effect';\nimport { pure } from './pure';\nconsole.log('entry');\n";
+        let effect = "console.log('loaded');\nexport const unused = 1;\n";
+        let pure = "export const pure = 1;\n";
+        let plan = plan_sources(&[(entry, &[("./effect", 1), ("./pure", 2)]), (effect, &[]), (pure, &[])]);
+
+        let kept = plan[0].as_ref().expect("entry is kept");
+        assert!(kept.bare.contains(&0));
+        assert!(kept.dropped.contains(&(entry.find("import { pure }").unwrap() as u32)));
+        let effect_shake = plan[1].as_ref().expect("module with side effects is kept");
+        assert!(!effect_shake.dropped.contains(&0));
+        assert!(effect_shake.dropped.contains(&(effect.find("export const").unwrap() as u32)));
+        assert!(plan[2].is_none());
+    }
+
+    #[test]
+    fn unused_exports_are_dropped() {
+        let entry = "import { a } from './lib';\nconsole.log(a);\n";
+        let lib = "export const a = 1;\nexport function b() {}\nwindow.ready = true;\n";
+        let plan = plan_sources(&[(entry, &[("./lib", 1)]), (lib, &[])]);
+
+        let shake = plan[1].as_ref().expect("lib is kept");
+        assert_eq!(shake.exports, Some(["a".to_string()].into_iter().collect()));
+        assert!(shake.dropped.contains(&(lib.find("export function b").unwrap() as u32)));
+        assert!(!shake.dropped.contains(&(lib.find("window.ready").unwrap() as u32)));
+    }
+}
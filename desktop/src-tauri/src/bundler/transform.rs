@@ -0,0 +1,1374 @@
+//! Per-module transform: TypeScript stripping and module linking
+//!
+//! Works on the source text rather than re-printing the AST. Type-only syntax
+//! is blanked out (or removed when minifying), enums, parameter properties,
+//! auto-accessors and standard decorators are lowered, and ES module syntax
+//! is rewritten against the bundle runtime (`__require`, `__export`,
+//! `__reexport`, `__default`, `__decorator`). An import statement only
+//! loads its module; references to imported bindings read the module's
+//! exports where they are used, so bindings stay live and import cycles do
+//! not touch an export before its module ran. Minifying also renames local
+//! bindings of the result to short names.
+
+use super::shake::Shake;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::*;
+use oxc_ast::AstKind;
+use oxc_ast_visit::{walk, Visit};
+use oxc_parser::Parser;
+use oxc_semantic::{Scoping, SemanticBuilder, SymbolId};
+use oxc_span::{GetSpan, SourceType, Span};
+use std::collections::{HashMap, HashSet};
+
+/// Class member and parameter modifiers that have no runtime meaning.
+const TS_MODIFIERS: &[&str] = &["public", "private", "protected", "readonly", "declare", "abstract", "override"];
+
+/// Characters of minified names; digits cannot start one.
+const NAME_START: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_$";
+const NAME_REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_$0123456789";
+
+/// Words a minified name must not be: keywords, and names the bundle
+/// wrapper provides.
+const RESERVED: &[&str] = &[
+    "do", "if", "in", "for", "let", "new", "try", "var", "case", "else", "enum", "eval", "null", "this", "true",
+    "void", "with", "await", "break", "catch", "class", "const", "false", "super", "throw", "while", "yield",
+    "delete", "export", "import", "public", "return", "static", "switch", "typeof", "default", "extends",
+    "finally", "package", "private", "continue", "debugger", "function", "arguments", "interface", "protected",
+    "implements", "instanceof", "exports", "require", "module",
+];
+
+/// A problem at a byte offset of the module source.
+pub struct TransformError {
+    pub offset: u32,
+    pub message: String,
+}
+
+/// Transforms one module into the body of its bundle wrapper, leaving out
+/// what `shake` drops. `resolve` maps an import specifier to a module id.
+pub fn transform(
+    source: &str,
+    source_type: SourceType,
+    minify: bool,
+    shake: &Shake,
+    resolve: &mut dyn FnMut(&str) -> Result<String, String>,
+) -> Result<String, Vec<TransformError>> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    if !parsed.errors.is_empty() {
+        return Err(parsed
+            .errors
+            .iter()
+            .map(|e| TransformError {
+                offset: e
+                    .labels
+                    .as_ref()
+                    .and_then(|l| l.first())
+                    .map(|l| l.offset() as u32)
+                    .unwrap_or(0),
+                message: e.message.to_string(),
+            })
+            .collect());
+    }
+
+    let program = &parsed.program;
+    let is_esm = program.body.iter().any(|s| s.is_module_declaration());
+    let imports = imported_bindings(program, shake);
+    let mut t = Transformer {
+        source,
+        minify,
+        is_esm,
+        resolve,
+        edits: Vec::new(),
+        exports: Vec::new(),
+        templates: Vec::new(),
+        errors: Vec::new(),
+        next_import: imports.bindings.len(),
+        imports,
+        next_decorated: 0,
+        next_accessor: 0,
+        export_start: None,
+    };
+
+    for stmt in &program.body {
+        let span = stmt.span();
+        if shake.dropped.contains(&span.start) {
+            t.remove_span(span);
+            continue;
+        }
+        if shake.bare.contains(&span.start) {
+            t.bare_require(stmt);
+            continue;
+        }
+        match stmt.as_module_declaration() {
+            Some(decl) => t.module_declaration(decl),
+            None => t.visit_statement(stmt),
+        }
+    }
+    t.edits.append(&mut t.imports.references);
+    if minify {
+        t.minify(program);
+    }
+    if !t.errors.is_empty() {
+        return Err(t.errors);
+    }
+
+    let mut code = String::new();
+    if is_esm {
+        let getters: Vec<String> = t
+            .exports
+            .iter()
+            .filter(|(name, _)| shake.exports.as_ref().is_none_or(|used| used.contains(name)))
+            .map(|(name, expr)| format!("{}: () => {}", property_key(name), expr))
+            .collect();
+        code.push_str(&format!("\"use strict\";\n__export(exports, {{ {} }});\n", getters.join(", ")));
+    }
+    code.push_str(&apply_edits(source, t.edits));
+    if minify {
+        code = mangle(&code, is_esm);
+    }
+    Ok(code)
+}
+
+struct Edit {
+    start: u32,
+    end: u32,
+    text: String,
+}
+
+/// Applies edits in source order. Edits nested inside an earlier one are dropped.
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by_key(|e| (e.start, e.end));
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0u32;
+    for edit in edits {
+        if edit.start < cursor {
+            continue;
+        }
+        out.push_str(&source[cursor as usize..edit.start as usize]);
+        out.push_str(&edit.text);
+        cursor = edit.end;
+    }
+    out.push_str(&source[cursor as usize..]);
+    out
+}
+
+fn property_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap_or_default()
+    }
+}
+
+struct Transformer<'s, 'r> {
+    source: &'s str,
+    minify: bool,
+    is_esm: bool,
+    resolve: &'r mut dyn FnMut(&str) -> Result<String, String>,
+    edits: Vec<Edit>,
+    /// `(exported name, expression)` pairs exposed through getters.
+    exports: Vec<(String, String)>,
+    templates: Vec<Span>,
+    errors: Vec<TransformError>,
+    next_import: usize,
+    imports: Imports,
+    next_decorated: usize,
+    next_accessor: usize,
+    /// Start of the `export` statement around the class declaration about to
+    /// be visited, where its decorator state is declared.
+    export_start: Option<u32>,
+}
+
+impl Transformer<'_, '_> {
+    fn replace(&mut self, start: u32, end: u32, text: impl Into<String>) {
+        self.edits.push(Edit { start, end, text: text.into() });
+    }
+
+    /// Removes a range. Without minification the text is blanked so line and
+    /// column numbers stay intact.
+    fn remove(&mut self, start: u32, end: u32) {
+        let removed = &self.source[start as usize..end as usize];
+        let text = if self.minify {
+            if removed.contains('\n') { "\n".to_string() } else { String::new() }
+        } else {
+            removed.chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect()
+        };
+        self.replace(start, end, text);
+    }
+
+    fn remove_span(&mut self, span: Span) {
+        self.remove(span.start, span.end);
+    }
+
+    /// Removes the given characters (`?`, `!`) between two offsets.
+    fn remove_chars(&mut self, start: u32, end: u32, chars: &[char]) {
+        let text = &self.source[start as usize..end as usize];
+        for (i, c) in text.char_indices() {
+            if chars.contains(&c) {
+                let at = start + i as u32;
+                self.remove(at, at + 1);
+            }
+        }
+    }
+
+    /// Removes TypeScript modifier keywords appearing as whole words in a range.
+    fn remove_modifiers(&mut self, start: u32, end: u32) {
+        let text = &self.source[start as usize..end as usize];
+        let mut offset = 0;
+        for word in text.split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '$') {
+            if TS_MODIFIERS.contains(&word) {
+                let at = start + offset as u32;
+                // Swallow trailing whitespace so `private x` does not become ` x`.
+                let tail = text[offset + word.len()..]
+                    .chars()
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .count();
+                self.remove(at, at + (word.len() + tail) as u32);
+            }
+            offset += word.len() + 1;
+        }
+    }
+
+    fn error(&mut self, offset: u32, message: impl Into<String>) {
+        self.errors.push(TransformError { offset, message: message.into() });
+    }
+
+    fn resolve_specifier(&mut self, literal: &StringLiteral) -> Option<String> {
+        match (self.resolve)(literal.value.as_str()) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                self.error(literal.span.start, e);
+                None
+            }
+        }
+    }
+
+    fn import_binding(&mut self) -> String {
+        self.next_import += 1;
+        format!("__m{}", self.next_import)
+    }
+
+    /// The source of `span` with `edits` and the rewritten import references
+    /// inside it applied, for code copied elsewhere.
+    fn source_text(&self, span: Span, mut edits: Vec<Edit>) -> String {
+        let inside = self.imports.references.iter().filter(|e| e.start >= span.start && e.end <= span.end);
+        edits.extend(inside.map(|e| Edit { start: e.start, end: e.end, text: e.text.clone() }));
+        for edit in &mut edits {
+            edit.start -= span.start;
+            edit.end -= span.start;
+        }
+        apply_edits(&self.source[span.start as usize..span.end as usize], edits)
+    }
+
+    // =========================================================================
+    // Module syntax
+    // =========================================================================
+
+    fn module_declaration(&mut self, decl: &ModuleDeclaration) {
+        match decl {
+            ModuleDeclaration::ImportDeclaration(import) => self.import_declaration(import),
+            ModuleDeclaration::ExportNamedDeclaration(export) => self.export_named(export),
+            ModuleDeclaration::ExportDefaultDeclaration(export) => self.export_default(export),
+            ModuleDeclaration::ExportAllDeclaration(export) => {
+                if export.export_kind.is_type() {
+                    return self.remove_span(export.span);
+                }
+                let Some(id) = self.resolve_specifier(&export.source) else { return };
+                match &export.exported {
+                    Some(name) => {
+                        let binding = self.import_binding();
+                        self.exports.push((name.name().to_string(), binding.clone()));
+                        self.replace(export.span.start, export.span.end, format!("const {} = __require({});", binding, json(&id)));
+                    }
+                    None => {
+                        self.replace(export.span.start, export.span.end, format!("__reexport(exports, __require({}));", json(&id)));
+                    }
+                }
+            }
+            ModuleDeclaration::TSExportAssignment(export) => {
+                self.error(export.span.start, "`export =` is not supported; use `export default` instead");
+            }
+            ModuleDeclaration::TSNamespaceExportDeclaration(export) => self.remove_span(export.span),
+        }
+    }
+
+    fn import_declaration(&mut self, import: &ImportDeclaration) {
+        if import.import_kind.is_type() {
+            return self.remove_span(import.span);
+        }
+        let specifiers: Vec<&ImportDeclarationSpecifier> = match &import.specifiers {
+            Some(list) => {
+                let values: Vec<_> = list
+                    .iter()
+                    .filter(|s| !matches!(s, ImportDeclarationSpecifier::ImportSpecifier(s) if s.import_kind.is_type()))
+                    .collect();
+                // `import { type A } from './a'` is elided entirely.
+                if values.is_empty() && !list.is_empty() {
+                    return self.remove_span(import.span);
+                }
+                values
+            }
+            None => Vec::new(),
+        };
+        let Some(id) = self.resolve_specifier(&import.source) else { return };
+        let code = match self.imports.bindings.get(&import.span.start) {
+            Some(binding) if !specifiers.is_empty() => format!("const {} = __require({});", binding, json(&id)),
+            _ => format!("__require({});", json(&id)),
+        };
+        self.replace(import.span.start, import.span.end, code);
+    }
+
+    /// An import or re-export kept only to load its module.
+    fn bare_require(&mut self, stmt: &Statement) {
+        let source = match stmt {
+            Statement::ImportDeclaration(import) => &import.source,
+            Statement::ExportAllDeclaration(export) => &export.source,
+            Statement::ExportNamedDeclaration(export) => match &export.source {
+                Some(source) => source,
+                None => return,
+            },
+            _ => return,
+        };
+        let Some(id) = self.resolve_specifier(source) else { return };
+        let span = stmt.span();
+        self.replace(span.start, span.end, format!("__require({});", json(&id)));
+    }
+
+    fn export_named(&mut self, export: &ExportNamedDeclaration) {
+        if export.export_kind.is_type() {
+            return self.remove_span(export.span);
+        }
+        if let Some(decl) = &export.declaration {
+            if is_type_only(decl) {
+                return self.remove_span(export.span);
+            }
+            for name in declared_names(decl) {
+                self.exports.push((name.clone(), name));
+            }
+            self.remove(export.span.start, decl.span().start);
+            if matches!(decl, Declaration::ClassDeclaration(_)) {
+                self.export_start = Some(export.span.start);
+            }
+            return self.visit_declaration(decl);
+        }
+
+        let specifiers: Vec<&ExportSpecifier> =
+            export.specifiers.iter().filter(|s| !s.export_kind.is_type()).collect();
+        match &export.source {
+            Some(source) => {
+                let Some(id) = self.resolve_specifier(source) else { return };
+                let binding = self.import_binding();
+                for s in specifiers {
+                    let expr = format!("{}[{}]", binding, json(&s.local.name()));
+                    self.exports.push((s.exported.name().to_string(), expr));
+                }
+                self.replace(export.span.start, export.span.end, format!("const {} = __require({});", binding, json(&id)));
+            }
+            None => {
+                for s in specifiers {
+                    let local = s.local.name();
+                    let imported = self.imports.expressions.get(local.as_str()).cloned();
+                    let expr = imported.unwrap_or_else(|| local.to_string());
+                    self.exports.push((s.exported.name().to_string(), expr));
+                }
+                self.remove_span(export.span);
+            }
+        }
+    }
+
+    fn export_default(&mut self, export: &ExportDefaultDeclaration) {
+        let decl = &export.declaration;
+        let named = match decl {
+            ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => return self.remove_span(export.span),
+            ExportDefaultDeclarationKind::FunctionDeclaration(f) if f.body.is_none() => {
+                return self.remove_span(export.span);
+            }
+            ExportDefaultDeclarationKind::FunctionDeclaration(f) => f.id.as_ref().map(|id| id.name.to_string()),
+            ExportDefaultDeclarationKind::ClassDeclaration(c) => c.id.as_ref().map(|id| id.name.to_string()),
+            _ => None,
+        };
+        let start = decl.span().start;
+        match &named {
+            Some(name) => {
+                self.exports.push(("default".to_string(), name.clone()));
+                self.remove(export.span.start, start);
+            }
+            None => {
+                self.exports.push(("default".to_string(), "__default_export".to_string()));
+                self.replace(export.span.start, start, "const __default_export = ");
+            }
+        }
+        if matches!(decl, ExportDefaultDeclarationKind::ClassDeclaration(_)) {
+            self.export_start = Some(export.span.start);
+        }
+        self.visit_export_default_declaration_kind(decl);
+        // Expressions keep their own semicolon; declarations need one.
+        let is_declaration = matches!(
+            decl,
+            ExportDefaultDeclarationKind::FunctionDeclaration(_) | ExportDefaultDeclarationKind::ClassDeclaration(_)
+        );
+        if named.is_none() && is_declaration {
+            self.replace(export.span.end, export.span.end, ";");
+        }
+    }
+
+    // =========================================================================
+    // TypeScript lowering
+    // =========================================================================
+
+    /// `enum E { A, B = 5 }` → `var E; (function (E) { ... })(E || (E = {}));`
+    ///
+    /// Constant members are folded like TypeScript does, so `B = A << 1`
+    /// becomes `2`. Other initializers are kept, with references to members
+    /// of the enum rewritten to `E.A`.
+    fn lower_enum(&mut self, decl: &TSEnumDeclaration) {
+        let name = decl.id.name.as_str();
+        let members: Vec<String> = decl.body.members.iter().map(|m| m.id.static_name().to_string()).collect();
+        let mut values: HashMap<String, EnumValue> = HashMap::new();
+        let mut body = String::new();
+        let mut previous: Option<(String, Option<EnumValue>)> = None;
+        for member in &decl.body.members {
+            let member_name = member.id.static_name().to_string();
+            let key = json(&member_name);
+            let value = match &member.initializer {
+                Some(init) => enum_constant(init, name, &values),
+                None => match &previous {
+                    None => Some(EnumValue::Number(0.0)),
+                    Some((_, Some(EnumValue::Number(n)))) => Some(EnumValue::Number(n + 1.0)),
+                    Some(_) => None,
+                },
+            };
+            match &value {
+                Some(EnumValue::String(s)) => body.push_str(&format!("{}[{}] = {}; ", name, key, json(s))),
+                Some(EnumValue::Number(n)) => {
+                    body.push_str(&format!("{n}[{n}[{k}] = {v}] = {k}; ", n = name, k = key, v = js_number(*n)));
+                }
+                None => {
+                    let expr = match (&member.initializer, &previous) {
+                        (Some(init), _) => self.enum_initializer(init, name, &members),
+                        (None, Some((prev, _))) => format!("{}[{}] + 1", name, json(prev)),
+                        (None, None) => "0".to_string(),
+                    };
+                    body.push_str(&format!("{n}[{n}[{k}] = {v}] = {k}; ", n = name, k = key, v = expr));
+                }
+            }
+            if let Some(value) = &value {
+                values.insert(member_name.clone(), value.clone());
+            }
+            previous = Some((member_name, value));
+        }
+        let code = format!("var {n}; (function ({n}) {{ {b}}})({n} || ({n} = {{}}));", n = name, b = body);
+        self.replace(decl.span.start, decl.span.end, code);
+    }
+
+    /// The source of a non-constant member initializer, with bare references
+    /// to members qualified by the enum, as they are in scope inside it.
+    fn enum_initializer(&self, init: &Expression, name: &str, members: &[String]) -> String {
+        let mut refs = MemberRefs { members, spans: Vec::new() };
+        refs.visit_expression(init);
+        let edits = refs.spans.into_iter().map(|at| Edit { start: at.start, end: at.end, text: format!("{}.", name) });
+        self.source_text(init.span(), edits.collect())
+    }
+
+    /// Lowers standard decorators onto the bundle runtime's `__decorator`.
+    /// Decorators are applied by a static block opening the class body, so
+    /// before static fields are initialized. A class with class decorators
+    /// becomes `let C = (() => { var C = class { ... }; ... })()`, so
+    /// references to `C` inside it see the class its decorators returned.
+    fn decorate_class(&mut self, class: &Class, export_start: Option<u32>) {
+        let elements: Vec<&ClassElement> = class
+            .body
+            .body
+            .iter()
+            .filter(|e| !e.is_typescript_syntax() && !element_decorators(e).is_empty())
+            .collect();
+        let Some(first) = class.decorators.first().or_else(|| elements.first().map(|e| &element_decorators(e)[0]))
+        else {
+            return;
+        };
+        if class.r#type == ClassType::ClassExpression {
+            let message = "Decorators are only supported on class declarations by the built-in bundler";
+            return self.error(first.span.start, message);
+        }
+        self.next_decorated += 1;
+        let state = format!("__d{}", self.next_decorated);
+        let start = [export_start, class.decorators.first().map(|d| d.span.start), Some(class.span.start)]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(class.span.start);
+        let binding = class.id.as_ref().map(|id| id.name.to_string()).unwrap_or_else(|| "__default_export".to_string());
+        if let Some(last) = class.decorators.last() {
+            let wrapper = format!("(() => {{ var {} = __decorator(); var {} = ", state, binding);
+            match &class.id {
+                Some(id) => {
+                    self.replace(start, start, format!("let {} = {}", binding, wrapper));
+                    self.remove_span(id.span);
+                }
+                None => {
+                    let at = last.span.end.max(class.span.start);
+                    self.replace(at, at, wrapper);
+                }
+            }
+            let end = if class.id.is_some() { ";" } else { "" };
+            self.replace(class.span.end, class.span.end, format!("; return {} = {}.cls; }})(){}", binding, state, end));
+        } else {
+            self.replace(start, start, format!("var {} = __decorator(); ", state));
+        }
+
+        let mut descriptors = Vec::new();
+        let mut instance_initializers = false;
+        for (i, element) in elements.iter().enumerate() {
+            let (kind, key, computed, is_static) = match element {
+                ClassElement::MethodDefinition(m) => {
+                    let kind = match m.kind {
+                        MethodDefinitionKind::Method => "method",
+                        MethodDefinitionKind::Get => "getter",
+                        MethodDefinitionKind::Set => "setter",
+                        MethodDefinitionKind::Constructor => {
+                            self.error(m.span.start, "Decorators are not valid on constructors");
+                            continue;
+                        }
+                    };
+                    (kind, &m.key, m.computed, m.r#static)
+                }
+                ClassElement::PropertyDefinition(p) => ("field", &p.key, p.computed, p.r#static),
+                ClassElement::AccessorProperty(p) => ("accessor", &p.key, p.computed, p.r#static),
+                _ => continue,
+            };
+            let decorators = element_decorators(element);
+            let list = self.decorator_list(decorators);
+            for decorator in decorators {
+                self.remove_span(decorator.span);
+            }
+            let key_span = key.span();
+            let (name, access) = match key {
+                PropertyKey::PrivateIdentifier(private) if kind == "field" => {
+                    let n = &private.name;
+                    let access =
+                        format!(", {{ has: (o) => #{n} in o, get: (o) => o.#{n}, set: (o, v) => {{ o.#{n} = v; }} }}");
+                    (json(&format!("#{}", n)), access)
+                }
+                PropertyKey::PrivateIdentifier(_) => {
+                    let message = "Decorators on private methods and accessors are not supported by the built-in bundler";
+                    self.error(key_span.start, message);
+                    continue;
+                }
+                _ if computed && kind == "accessor" => continue,
+                _ if computed => {
+                    self.replace(key_span.start, key_span.start, format!("{}.key({}, ", state, i));
+                    self.replace(key_span.end, key_span.end, ")");
+                    (format!("{}.keys[{}]", state, i), String::new())
+                }
+                _ => (json(&key.static_name().unwrap_or_default()), String::new()),
+            };
+            descriptors.push(format!("[{}, {}, {}, [{}]{}]", json(kind), name, is_static, list, access));
+            let static_prefix = if is_static { "static " } else { "" };
+
+            match element {
+                ClassElement::PropertyDefinition(p) => {
+                    self.wrap_initializer(&state, i, &p.value, p.type_annotation.as_deref(), key_span, computed);
+                    let slot = format!("; {}#{}_{} = {}.extra(this, {});", static_prefix, state, i, state, i);
+                    self.replace(p.span.end, p.span.end, slot);
+                }
+                ClassElement::AccessorProperty(p) => {
+                    self.wrap_initializer(&state, i, &p.value, p.type_annotation.as_deref(), key_span, computed);
+                }
+                _ => {}
+            }
+            if kind != "field" && !is_static {
+                instance_initializers = true;
+            }
+        }
+
+        let mut head = String::new();
+        if !descriptors.is_empty() {
+            head.push_str(&format!("{}.elements(this, [{}]); ", state, descriptors.join(", ")));
+        }
+        if !class.decorators.is_empty() {
+            let list = self.decorator_list(&class.decorators);
+            for decorator in &class.decorators {
+                self.remove_span(decorator.span);
+            }
+            head.push_str(&format!("{} = {}.decorateClass(this, [{}]); ", binding, state, list));
+            let tail = format!(" static {{ {}.finish({}); }}", state, binding);
+            self.replace(class.body.span.end - 1, class.body.span.end - 1, tail);
+        }
+        let mut head = format!(" static {{ {}}}", head);
+        if instance_initializers {
+            head.push_str(&format!(" #{} = {}.instance(this);", state, state));
+        }
+        self.replace(class.body.span.start + 1, class.body.span.start + 1, head);
+    }
+
+    /// Decorator expressions as written, in source order.
+    fn decorator_list(&self, decorators: &[Decorator]) -> String {
+        decorators.iter().map(|d| self.source_text(d.expression.span(), Vec::new())).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Passes a decorated field's initial value through the initializers its
+    /// decorators returned.
+    fn wrap_initializer(
+        &mut self,
+        state: &str,
+        index: usize,
+        value: &Option<Expression>,
+        type_annotation: Option<&TSTypeAnnotation>,
+        key: Span,
+        computed: bool,
+    ) {
+        match value {
+            Some(value) => {
+                let span = value.span();
+                self.replace(span.start, span.start, format!("{}.init(this, {}, ", state, index));
+                self.replace(span.end, span.end, ")");
+            }
+            None => {
+                let end = match type_annotation {
+                    Some(annotation) => annotation.span.end,
+                    None if computed => {
+                        let rest = &self.source[key.end as usize..];
+                        key.end + rest.find(']').map(|i| i as u32 + 1).unwrap_or(0)
+                    }
+                    None => key.end,
+                };
+                self.replace(end, end, format!(" = {}.init(this, {})", state, index));
+            }
+        }
+    }
+
+    /// `accessor x = 1` → a private backing field with a getter and setter.
+    fn lower_accessor(&mut self, p: &AccessorProperty) {
+        if p.computed {
+            let message = "Auto-accessors with computed names are not supported by the built-in bundler";
+            return self.error(p.key.span().start, message);
+        }
+        self.next_accessor += 1;
+        let backing = format!("#__a{}", self.next_accessor);
+        let key = p.key.span();
+        let name = &self.source[key.start as usize..key.end as usize];
+        let prefix = if p.r#static { "static " } else { "" };
+        let accessors = format!(
+            "; {p}get {k}() {{ return this.{b}; }} {p}set {k}(v) {{ this.{b} = v; }}",
+            p = prefix,
+            k = name,
+            b = backing
+        );
+        self.replace(decorators_end(&p.decorators, p.span.start), key.start, prefix);
+        self.replace(key.start, key.end, backing);
+        if p.definite {
+            let end = p.type_annotation.as_ref().map(|t| t.span.start).unwrap_or(p.span.end);
+            self.remove_chars(key.end, end, &['!']);
+        }
+        self.replace(p.span.end, p.span.end, accessors);
+    }
+
+    /// Statements inserted at the start of a constructor for parameter properties.
+    fn parameter_properties(&mut self, ctor: &Function) {
+        let assignments: Vec<String> = ctor
+            .params
+            .items
+            .iter()
+            .filter(|p| p.accessibility.is_some() || p.readonly || p.r#override)
+            .filter_map(|p| match &p.pattern {
+                BindingPattern::BindingIdentifier(id) => Some(id.name.to_string()),
+                BindingPattern::AssignmentPattern(a) => a.left.get_identifier_name().map(|n| n.to_string()),
+                _ => None,
+            })
+            .map(|name| format!("this.{0} = {0};", name))
+            .collect();
+        let Some(body) = &ctor.body else { return };
+        if assignments.is_empty() {
+            return;
+        }
+        // Derived classes must assign after `super()`.
+        let at = body
+            .statements
+            .iter()
+            .find(|s| matches!(s, Statement::ExpressionStatement(e) if matches!(&e.expression, Expression::CallExpression(c) if matches!(c.callee, Expression::Super(_)))))
+            .map(|s| s.span().end)
+            .unwrap_or(body.span.start + 1);
+        self.replace(at, at, format!(" {}", assignments.join(" ")));
+    }
+
+    // =========================================================================
+    // Minification
+    // =========================================================================
+
+    /// Drops comments, indentation and blank lines outside template literals.
+    fn minify(&mut self, program: &Program) {
+        for comment in &program.comments {
+            let text = &self.source[comment.span.start as usize..comment.span.end as usize];
+            let replacement = if text.contains('\n') { "\n" } else { " " };
+            self.replace(comment.span.start, comment.span.end, replacement);
+        }
+
+        let mut line_start = 0usize;
+        for line in self.source.split_inclusive('\n') {
+            let start = line_start as u32;
+            line_start += line.len();
+            if self.templates.iter().any(|t| t.start < start && start < t.end) {
+                continue;
+            }
+            let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+            if line.trim().is_empty() {
+                self.replace(start, start + line.len() as u32, "");
+            } else if indent > 0 {
+                self.replace(start, start + indent as u32, "");
+            }
+        }
+    }
+}
+
+/// Renames the local bindings of a transformed module to the shortest names
+/// free in their scope. Names stay apart from those of enclosing scopes, so
+/// no binding shadows another it refers to. Class names are kept, since
+/// decorators and serialization read them, and a module using `eval` or
+/// `with` is left as it is.
+fn mangle(code: &str, is_esm: bool) -> String {
+    let allocator = Allocator::default();
+    let source_type = if is_esm { SourceType::mjs() } else { SourceType::cjs() };
+    let parsed = Parser::new(&allocator, code, source_type).parse();
+    if !parsed.errors.is_empty() {
+        return code.to_string();
+    }
+    let semantic = SemanticBuilder::new().build(&parsed.program).semantic;
+    let scoping = semantic.scoping();
+    if scoping.root_unresolved_references().contains_key("eval") {
+        return code.to_string();
+    }
+
+    let mut reserved: HashSet<String> = RESERVED.iter().map(|word| word.to_string()).collect();
+    reserved.extend(scoping.root_unresolved_references().keys().map(|name| name.to_string()));
+    let kept: HashSet<SymbolId> = scoping
+        .symbol_ids()
+        .filter(|symbol| {
+            scoping.symbol_flags(*symbol).is_class()
+                || matches!(
+                    semantic.nodes().kind(scoping.symbol_declaration(*symbol)),
+                    AstKind::VariableDeclarator(d) if matches!(d.init, Some(Expression::ClassExpression(_)))
+                )
+        })
+        .collect();
+    reserved.extend(kept.iter().map(|symbol| scoping.symbol_name(*symbol).to_string()));
+
+    // Scopes come parents first; each continues numbering where its parent
+    // stopped, and siblings reuse the same names.
+    let mut names = ShortNames { names: Vec::new(), next: 0, reserved };
+    let mut renamed: HashMap<SymbolId, String> = HashMap::new();
+    let mut scope_end = HashMap::new();
+    for scope in scoping.scope_descendants_from_root() {
+        let mut slot = scoping.scope_parent_id(scope).and_then(|parent| scope_end.get(&parent).copied()).unwrap_or(0);
+        let mut symbols: Vec<SymbolId> = scoping.iter_bindings_in(scope).filter(|s| !kept.contains(s)).collect();
+        symbols.sort();
+        for symbol in symbols {
+            renamed.insert(symbol, names.get(slot).to_string());
+            slot += 1;
+        }
+        scope_end.insert(scope, slot);
+    }
+
+    let mut renamer =
+        Renamer { scoping, renamed: &renamed, shorthand: HashSet::new(), edits: Vec::new(), has_with: false };
+    renamer.visit_program(&parsed.program);
+    if renamer.has_with {
+        return code.to_string();
+    }
+    apply_edits(code, renamer.edits)
+}
+
+/// Minified names in order, skipping reserved words.
+struct ShortNames {
+    names: Vec<String>,
+    next: usize,
+    reserved: HashSet<String>,
+}
+
+impl ShortNames {
+    fn get(&mut self, slot: usize) -> &str {
+        while self.names.len() <= slot {
+            let mut n = self.next;
+            self.next += 1;
+            let mut name = String::from(NAME_START[n % NAME_START.len()] as char);
+            n /= NAME_START.len();
+            while n > 0 {
+                n -= 1;
+                name.push(NAME_REST[n % NAME_REST.len()] as char);
+                n /= NAME_REST.len();
+            }
+            if !self.reserved.contains(&name) {
+                self.names.push(name);
+            }
+        }
+        &self.names[slot]
+    }
+}
+
+/// Rewrites bindings and references to their minified names. Shorthand
+/// properties are spelled out, so `{ a }` keeps its key.
+struct Renamer<'s> {
+    scoping: &'s Scoping,
+    renamed: &'s HashMap<SymbolId, String>,
+    /// Starts of identifiers that are also a shorthand property's key.
+    shorthand: HashSet<u32>,
+    edits: Vec<Edit>,
+    has_with: bool,
+}
+
+impl Renamer<'_> {
+    fn rename(&mut self, symbol: Option<SymbolId>, span: Span, original: &str) {
+        let Some(name) = symbol.and_then(|symbol| self.renamed.get(&symbol)) else { return };
+        let text = if self.shorthand.contains(&span.start) { format!("{}: {}", original, name) } else { name.clone() };
+        self.edits.push(Edit { start: span.start, end: span.end, text });
+    }
+}
+
+impl<'a> Visit<'a> for Renamer<'_> {
+    fn visit_binding_identifier(&mut self, it: &BindingIdentifier<'a>) {
+        self.rename(it.symbol_id.get(), it.span, &it.name);
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        let symbol = it.reference_id.get().and_then(|id| self.scoping.get_reference(id).symbol_id());
+        self.rename(symbol, it.span, &it.name);
+    }
+
+    fn visit_object_property(&mut self, it: &ObjectProperty<'a>) {
+        if it.shorthand {
+            self.shorthand.insert(it.value.span().start);
+        }
+        walk::walk_object_property(self, it);
+    }
+
+    fn visit_binding_property(&mut self, it: &BindingProperty<'a>) {
+        if it.shorthand {
+            self.shorthand.insert(it.key.span().start);
+        }
+        walk::walk_binding_property(self, it);
+    }
+
+    fn visit_assignment_target_property_identifier(&mut self, it: &AssignmentTargetPropertyIdentifier<'a>) {
+        self.shorthand.insert(it.binding.span.start);
+        walk::walk_assignment_target_property_identifier(self, it);
+    }
+
+    fn visit_with_statement(&mut self, it: &WithStatement<'a>) {
+        self.has_with = true;
+        walk::walk_with_statement(self, it);
+    }
+}
+
+impl<'a> Visit<'a> for Transformer<'_, '_> {
+    fn visit_declaration(&mut self, it: &Declaration<'a>) {
+        match it {
+            Declaration::TSEnumDeclaration(e) if !e.declare => self.lower_enum(e),
+            Declaration::TSModuleDeclaration(m) if !m.declare && !is_type_only_namespace(m) => {
+                self.error(m.span.start, "Namespaces are not supported by the built-in bundler; use modules instead");
+            }
+            Declaration::TSImportEqualsDeclaration(d) => {
+                self.error(d.span.start, "`import =` is not supported; use an `import` statement instead");
+            }
+            Declaration::ClassDeclaration(c) if !c.declare => self.visit_class(c),
+            _ if is_type_only(it) => self.remove_span(it.span()),
+            _ => walk::walk_declaration(self, it),
+        }
+    }
+
+    fn visit_ts_type_annotation(&mut self, it: &TSTypeAnnotation<'a>) {
+        self.remove_span(it.span);
+    }
+
+    fn visit_ts_type_parameter_declaration(&mut self, it: &TSTypeParameterDeclaration<'a>) {
+        self.remove_span(it.span);
+    }
+
+    fn visit_ts_type_parameter_instantiation(&mut self, it: &TSTypeParameterInstantiation<'a>) {
+        self.remove_span(it.span);
+    }
+
+    fn visit_ts_as_expression(&mut self, it: &TSAsExpression<'a>) {
+        self.remove(it.expression.span().end, it.span.end);
+        self.visit_expression(&it.expression);
+    }
+
+    fn visit_ts_satisfies_expression(&mut self, it: &TSSatisfiesExpression<'a>) {
+        self.remove(it.expression.span().end, it.span.end);
+        self.visit_expression(&it.expression);
+    }
+
+    fn visit_ts_non_null_expression(&mut self, it: &TSNonNullExpression<'a>) {
+        self.remove(it.expression.span().end, it.span.end);
+        self.visit_expression(&it.expression);
+    }
+
+    fn visit_ts_type_assertion(&mut self, it: &TSTypeAssertion<'a>) {
+        self.remove(it.span.start, it.expression.span().start);
+        self.visit_expression(&it.expression);
+    }
+
+    fn visit_ts_instantiation_expression(&mut self, it: &TSInstantiationExpression<'a>) {
+        self.remove_span(it.type_arguments.span);
+        self.visit_expression(&it.expression);
+    }
+
+    fn visit_ts_this_parameter(&mut self, it: &TSThisParameter<'a>) {
+        let rest = &self.source[it.span.end as usize..];
+        let comma = rest.find(|c: char| !c.is_whitespace()).filter(|i| rest[*i..].starts_with(','));
+        let end = comma.map(|i| it.span.end + i as u32 + 1).unwrap_or(it.span.end);
+        self.remove(it.span.start, end);
+    }
+
+    /// Decorator expressions are copied as written by `decorate_class`.
+    fn visit_decorator(&mut self, _: &Decorator<'a>) {}
+
+    fn visit_class(&mut self, it: &Class<'a>) {
+        let head_end = it
+            .id
+            .as_ref()
+            .map(|id| id.span.start)
+            .unwrap_or(it.body.span.start);
+        if it.r#abstract {
+            self.remove_modifiers(it.span.start, head_end);
+        }
+        if let (Some(first), Some(last)) = (it.implements.first(), it.implements.last()) {
+            let head = &self.source[..first.span.start as usize];
+            if let Some(keyword) = head.rfind("implements") {
+                self.remove(keyword as u32, last.span.end);
+            }
+        }
+        let export_start = self.export_start.take();
+        self.decorate_class(it, export_start);
+        for element in &it.body.body {
+            if let ClassElement::AccessorProperty(p) = element {
+                if !element.is_typescript_syntax() {
+                    self.lower_accessor(p);
+                }
+            }
+        }
+        walk::walk_class(self, it);
+    }
+
+    fn visit_class_element(&mut self, it: &ClassElement<'a>) {
+        if it.is_typescript_syntax() {
+            return self.remove_span(it.span());
+        }
+        match it {
+            ClassElement::MethodDefinition(m) => {
+                self.remove_modifiers(decorators_end(&m.decorators, m.span.start), m.key.span().start);
+                if m.optional {
+                    self.remove_chars(m.key.span().end, m.value.params.span.start, &['?']);
+                }
+                if m.kind == MethodDefinitionKind::Constructor {
+                    self.parameter_properties(&m.value);
+                }
+            }
+            ClassElement::PropertyDefinition(p) => {
+                self.remove_modifiers(decorators_end(&p.decorators, p.span.start), p.key.span().start);
+                let end = p
+                    .type_annotation
+                    .as_ref()
+                    .map(|t| t.span.start)
+                    .or_else(|| p.value.as_ref().map(|v| v.span().start))
+                    .unwrap_or(p.span.end);
+                if p.optional || p.definite {
+                    self.remove_chars(p.key.span().end, end, &['?', '!']);
+                }
+            }
+            _ => {}
+        }
+        walk::walk_class_element(self, it);
+    }
+
+    fn visit_formal_parameter(&mut self, it: &FormalParameter<'a>) {
+        if let Some(decorator) = it.decorators.first() {
+            self.error(decorator.span.start, "Parameter decorators are not supported by the built-in bundler");
+        }
+        let pattern = it.pattern.span();
+        self.remove_modifiers(decorators_end(&it.decorators, it.span.start), pattern.start);
+        if it.optional {
+            let end = it
+                .type_annotation
+                .as_ref()
+                .map(|t| t.span.start)
+                .or_else(|| it.initializer.as_ref().map(|i| i.span().start))
+                .unwrap_or(it.span.end);
+            self.remove_chars(pattern.end, end, &['?']);
+        }
+        walk::walk_formal_parameter(self, it);
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        if it.definite {
+            if let Some(annotation) = &it.type_annotation {
+                self.remove_chars(it.id.span().end, annotation.span.start, &['!']);
+            }
+        }
+        walk::walk_variable_declarator(self, it);
+    }
+
+    fn visit_template_literal(&mut self, it: &TemplateLiteral<'a>) {
+        self.templates.push(it.span);
+        walk::walk_template_literal(self, it);
+    }
+
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        if let Expression::StringLiteral(literal) = &it.source {
+            if let Some(id) = self.resolve_specifier(literal) {
+                let code = format!("Promise.resolve().then(() => __require({}))", json(&id));
+                return self.replace(it.span.start, it.span.end, code);
+            }
+        }
+        walk::walk_import_expression(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        if !self.is_esm && it.callee.is_specific_id("require") && it.arguments.len() == 1 {
+            if let Some(Argument::StringLiteral(literal)) = it.arguments.first() {
+                if let Some(id) = self.resolve_specifier(literal) {
+                    self.replace(literal.span.start, literal.span.end, json(&id));
+                }
+                return;
+            }
+        }
+        walk::walk_call_expression(self, it);
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+pub fn json(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// Module bindings of the import statements kept, and the rewritten
+/// references to what they import.
+#[derive(Default)]
+struct Imports {
+    /// `__mN` by import statement start.
+    bindings: HashMap<u32, String>,
+    /// Expression reading each imported binding, by local name.
+    expressions: HashMap<String, String>,
+    /// Edits rewriting value references to imported bindings.
+    references: Vec<Edit>,
+}
+
+/// `import { a as b } from './x'` reads `a` where `b` is used: `__m1.a`, or
+/// `(0, __m1.a)(...)` when called so `this` is not the module.
+fn imported_bindings(program: &Program, shake: &Shake) -> Imports {
+    let semantic = SemanticBuilder::new().build(program).semantic;
+    let scoping = semantic.scoping();
+    let nodes = semantic.nodes();
+    let mut imports = Imports::default();
+    for stmt in &program.body {
+        let Statement::ImportDeclaration(import) = stmt else { continue };
+        let start = import.span.start;
+        if import.import_kind.is_type() || shake.dropped.contains(&start) || shake.bare.contains(&start) {
+            continue;
+        }
+        let specifiers: Vec<&ImportDeclarationSpecifier> = import
+            .specifiers
+            .iter()
+            .flatten()
+            .filter(|s| !matches!(s, ImportDeclarationSpecifier::ImportSpecifier(s) if s.import_kind.is_type()))
+            .collect();
+        if specifiers.is_empty() {
+            continue;
+        }
+        let binding = format!("__m{}", imports.bindings.len() + 1);
+        for specifier in specifiers {
+            let (local, expr) = match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                    let imported = s.imported.name();
+                    let key = property_key(&imported);
+                    let expr = if key == imported.as_str() {
+                        format!("{}.{}", binding, key)
+                    } else {
+                        format!("{}[{}]", binding, key)
+                    };
+                    (&s.local, expr)
+                }
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => (&s.local, format!("__default({})", binding)),
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => (&s.local, binding.clone()),
+            };
+            let is_member = matches!(specifier, ImportDeclarationSpecifier::ImportSpecifier(_));
+            let Some(symbol) = local.symbol_id.get() else { continue };
+            for reference in scoping.get_resolved_reference_ids(symbol).iter().map(|id| scoping.get_reference(*id)) {
+                if !reference.is_value() {
+                    continue;
+                }
+                let AstKind::IdentifierReference(ident) = nodes.kind(reference.node_id()) else { continue };
+                let span = ident.span;
+                let text = match nodes.parent_kind(reference.node_id()) {
+                    AstKind::ObjectProperty(p) if p.shorthand => format!("{}: {}", local.name, expr),
+                    AstKind::CallExpression(c) if is_member && c.callee.span() == span => format!("(0, {})", expr),
+                    AstKind::TaggedTemplateExpression(t) if is_member && t.tag.span() == span => {
+                        format!("(0, {})", expr)
+                    }
+                    AstKind::NewExpression(n) if !is_member && n.callee.span() == span => format!("({})", expr),
+                    _ => expr.clone(),
+                };
+                imports.references.push(Edit { start: span.start, end: span.end, text });
+            }
+            imports.expressions.insert(local.name.to_string(), expr);
+        }
+        imports.bindings.insert(start, binding);
+    }
+    imports
+}
+
+fn decorators_end(decorators: &[Decorator], fallback: u32) -> u32 {
+    decorators.last().map(|d| d.span.end).unwrap_or(fallback)
+}
+
+#[derive(Clone)]
+enum EnumValue {
+    Number(f64),
+    String(String),
+}
+
+/// The value of a constant enum expression: literals, members of the enum
+/// declared so far, and the unary and binary operators TypeScript folds.
+fn enum_constant(expr: &Expression, enum_name: &str, values: &HashMap<String, EnumValue>) -> Option<EnumValue> {
+    let number = |expr: &Expression| match enum_constant(expr, enum_name, values)? {
+        EnumValue::Number(n) => Some(n),
+        EnumValue::String(_) => None,
+    };
+    match expr {
+        Expression::NumericLiteral(n) => Some(EnumValue::Number(n.value)),
+        Expression::StringLiteral(s) => Some(EnumValue::String(s.value.to_string())),
+        Expression::TemplateLiteral(t) if t.expressions.is_empty() => {
+            Some(EnumValue::String(t.quasis.first()?.value.cooked?.to_string()))
+        }
+        Expression::ParenthesizedExpression(p) => enum_constant(&p.expression, enum_name, values),
+        Expression::Identifier(id) => values.get(id.name.as_str()).cloned(),
+        Expression::StaticMemberExpression(m) if m.object.is_specific_id(enum_name) => {
+            values.get(m.property.name.as_str()).cloned()
+        }
+        Expression::ComputedMemberExpression(m) if m.object.is_specific_id(enum_name) => match &m.expression {
+            Expression::StringLiteral(key) => values.get(key.value.as_str()).cloned(),
+            _ => None,
+        },
+        Expression::UnaryExpression(u) => {
+            let n = number(&u.argument)?;
+            match u.operator {
+                UnaryOperator::UnaryPlus => Some(EnumValue::Number(n)),
+                UnaryOperator::UnaryNegation => Some(EnumValue::Number(-n)),
+                UnaryOperator::BitwiseNot => Some(EnumValue::Number(f64::from(!to_int32(n)))),
+                _ => None,
+            }
+        }
+        Expression::BinaryExpression(b) => {
+            let left = enum_constant(&b.left, enum_name, values)?;
+            let right = enum_constant(&b.right, enum_name, values)?;
+            let (l, r) = match (left, right) {
+                (EnumValue::Number(l), EnumValue::Number(r)) => (l, r),
+                (left, right) if b.operator == BinaryOperator::Addition => {
+                    return Some(EnumValue::String(format!("{}{}", enum_string(&left), enum_string(&right))));
+                }
+                _ => return None,
+            };
+            let shift = to_int32(r) as u32 & 31;
+            let value = match b.operator {
+                BinaryOperator::Addition => l + r,
+                BinaryOperator::Subtraction => l - r,
+                BinaryOperator::Multiplication => l * r,
+                BinaryOperator::Division => l / r,
+                BinaryOperator::Remainder => l % r,
+                BinaryOperator::Exponential => l.powf(r),
+                BinaryOperator::ShiftLeft => f64::from(to_int32(l).wrapping_shl(shift)),
+                BinaryOperator::ShiftRight => f64::from(to_int32(l) >> shift),
+                BinaryOperator::ShiftRightZeroFill => f64::from((to_int32(l) as u32) >> shift),
+                BinaryOperator::BitwiseOR => f64::from(to_int32(l) | to_int32(r)),
+                BinaryOperator::BitwiseXOR => f64::from(to_int32(l) ^ to_int32(r)),
+                BinaryOperator::BitwiseAnd => f64::from(to_int32(l) & to_int32(r)),
+                _ => return None,
+            };
+            Some(EnumValue::Number(value))
+        }
+        _ => None,
+    }
+}
+
+fn enum_string(value: &EnumValue) -> String {
+    match value {
+        EnumValue::Number(n) => js_number(*n),
+        EnumValue::String(s) => s.clone(),
+    }
+}
+
+/// JavaScript's ToInt32.
+fn to_int32(n: f64) -> i32 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(4_294_967_296.0) as u32 as i32
+}
+
+/// A number as JavaScript prints it.
+fn js_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if n == 0.0 {
+        "0".to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Finds bare references to enum members inside an initializer.
+struct MemberRefs<'m> {
+    members: &'m [String],
+    spans: Vec<Span>,
+}
+
+impl<'a> Visit<'a> for MemberRefs<'_> {
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        if self.members.iter().any(|m| m == it.name.as_str()) {
+            self.spans.push(Span::new(it.span.start, it.span.start));
+        }
+    }
+}
+
+fn element_decorators<'e, 'a>(element: &'e ClassElement<'a>) -> &'e [Decorator<'a>] {
+    match element {
+        ClassElement::MethodDefinition(m) => &m.decorators,
+        ClassElement::PropertyDefinition(p) => &p.decorators,
+        ClassElement::AccessorProperty(p) => &p.decorators,
+        _ => &[],
+    }
+}
+
+/// Declarations that produce no runtime code.
+fn is_type_only(decl: &Declaration) -> bool {
+    match decl {
+        Declaration::VariableDeclaration(v) => v.declare,
+        Declaration::FunctionDeclaration(f) => f.is_typescript_syntax(),
+        Declaration::ClassDeclaration(c) => c.declare,
+        Declaration::TSEnumDeclaration(e) => e.declare,
+        Declaration::TSModuleDeclaration(m) => m.declare || is_type_only_namespace(m),
+        Declaration::TSImportEqualsDeclaration(_) => false,
+        _ => true,
+    }
+}
+
+/// Namespaces holding only types are erased by TypeScript.
+fn is_type_only_namespace(decl: &TSModuleDeclaration) -> bool {
+    match &decl.body {
+        None => true,
+        Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => is_type_only_namespace(inner),
+        Some(TSModuleDeclarationBody::TSModuleBlock(block)) => block.body.iter().all(|s| match s {
+            Statement::ExportNamedDeclaration(e) => e.declaration.as_ref().is_none_or(is_type_only),
+            _ => s.as_declaration().is_some_and(is_type_only),
+        }),
+    }
+}
+
+/// Names bound by an exported declaration.
+fn declared_names(decl: &Declaration) -> Vec<String> {
+    match decl {
+        Declaration::VariableDeclaration(v) => v
+            .declarations
+            .iter()
+            .flat_map(|d| d.id.get_binding_identifiers())
+            .map(|id| id.name.to_string())
+            .collect(),
+        Declaration::FunctionDeclaration(f) => f.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::ClassDeclaration(c) => c.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::TSEnumDeclaration(e) => vec![e.id.name.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform_ts(source: &str, minify: bool) -> String {
+        let mut resolve = |specifier: &str| Ok(specifier.trim_start_matches("./").to_string());
+        match transform(source, SourceType::ts().with_module(true), minify, &Shake::default(), &mut resolve) {
+            Ok(code) => code,
+            Err(errors) => panic!("{}", errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; ")),
+        }
+    }
+
+    fn compact(code: &str) -> String {
+        code.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn imports_in_a_cycle_are_not_read_at_load() {
+        let code = transform_ts("import { B } from './b';\nexport class A { peer() { return new B(); } }\n", false);
+        assert!(code.contains("const __m1 = __require(\"b\");\n"), "{}", code);
+        assert!(code.contains("return new __m1.B();"), "{}", code);
+    }
+
+    #[test]
+    fn imported_bindings_are_read_where_used() {
+        let source = "import def, { a, f as g } from './m';\nimport * as ns from './n';\n\
+                      console.log(a, { a }, ns.x, new def());\ng();\ng`t`;\n";
+        let code = compact(&transform_ts(source, false));
+        assert!(code.contains("const __m1 = __require(\"m\"); const __m2 = __require(\"n\");"), "{}", code);
+        assert!(code.contains("console.log(__m1.a, { a: __m1.a }, __m2.x, new (__default(__m1))());"), "{}", code);
+        assert!(code.contains("(0, __m1.f)(); (0, __m1.f)`t`;"), "{}", code);
+    }
+
+    #[test]
+    fn exported_let_stays_live() {
+        let code = compact(&transform_ts("export let count = 0;\nexport function inc() { count++; }\n", false));
+        assert!(code.contains("__export(exports, { count: () => count, inc: () => inc });"), "{}", code);
+        assert!(code.contains("function inc() { count++; }"), "{}", code);
+
+        let code = compact(&transform_ts("import { count } from './counter';\nexport { count as total };\n", false));
+        assert!(code.contains("__export(exports, { total: () => __m1.count });"), "{}", code);
+    }
+
+    #[test]
+    fn constant_enum_members_are_folded() {
+        let code = transform_ts("enum E { A = 1 << 2, B, C = 'x' + A, D = f(A), F }\n", false);
+        assert!(code.contains("E[E[\"A\"] = 4] = \"A\"; "), "{}", code);
+        assert!(code.contains("E[E[\"B\"] = 5] = \"B\"; "), "{}", code);
+        assert!(code.contains("E[\"C\"] = \"x4\"; "), "{}", code);
+        assert!(code.contains("E[E[\"D\"] = f(E.A)] = \"D\"; "), "{}", code);
+        assert!(code.contains("E[E[\"F\"] = E[\"D\"] + 1] = \"F\"; "), "{}", code);
+    }
+
+    #[test]
+    fn decorators_and_accessors_are_lowered() {
+        let source = "import { sealed, log } from './decorators';\n\
+                      @sealed class C { @log run() {} accessor size = 1; }\n";
+        let code = compact(&transform_ts(source, false));
+        assert!(code.contains("let C = (() => { var __d1 = __decorator(); var C ="), "{}", code);
+        assert!(code.contains("__d1.elements(this, [[\"method\", \"run\", false, [__m1.log]]]);"), "{}", code);
+        assert!(code.contains("C = __d1.decorateClass(this, [__m1.sealed]);"), "{}", code);
+        assert!(code.contains("get size() { return this.#__a1; } set size(v) { this.#__a1 = v; }"), "{}", code);
+        assert!(!code.contains('@'), "{}", code);
+    }
+
+    #[test]
+    fn mangling_keeps_exported_and_global_names() {
+        let code = "__export(exports, { total: () => total });\n\
+                    function total(values) { let sum = 0; for (const value of values) sum += value; \
+                    return sum + offset; }\n\
+                    class Player {}\n";
+        let mangled = mangle(code, true);
+        assert!(mangled.contains("__export(exports, { total: () =>"), "{}", mangled);
+        assert!(mangled.contains("+ offset;"), "{}", mangled);
+        assert!(mangled.contains("class Player {}"), "{}", mangled);
+        assert!(!mangled.contains("values") && !mangled.contains("sum"), "{}", mangled);
+    }
+
+    #[test]
+    fn mangling_leaves_modules_using_eval() {
+        let code = "function read(name) { const local = 1; return eval(name); }\n";
+        assert_eq!(mangle(code, true), code);
+    }
+}
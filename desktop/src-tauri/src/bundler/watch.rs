@@ -0,0 +1,266 @@
+//! Script watch mode — rebuilds a project's scripts on save
+//!
+//! `start_script_watch` bundles the project once, then watches `src/` and
+//! rebuilds after every burst of changes, transforming only the modules
+//! whose source changed. Each build is reported as a `script-build` event
+//! with its diagnostics. When the bundle differs from the last one it is
+//! written out, copied into a prepared preview and the project's preview
+//! pages reload; the editor does not have to rebuild the preview. The watch
+//! ends with `stop_script_watch` or when the project is closed.
+
+use super::{bundle_project_cached, is_ignored, ModuleCache, ScriptError, DEFAULT_OUTPUT};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Changes closer together than this are built once.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+const PREVIEW_OUTPUT: &str = ".esengine/preview/user-scripts.js";
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "json"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Script watches by project directory.
+#[derive(Default)]
+pub struct ScriptWatchers {
+    watches: Mutex<HashMap<PathBuf, ScriptWatch>>,
+}
+
+struct ScriptWatch {
+    output: PathBuf,
+    minify: bool,
+    /// Dropping it ends the build thread.
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptWatchInfo {
+    pub project_dir: String,
+    pub output_path: String,
+    pub minify: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScriptBuild {
+    project_dir: String,
+    success: bool,
+    errors: Vec<ScriptError>,
+    output_path: Option<String>,
+    /// Modules in the bundle, and how many of them were transformed again.
+    modules: usize,
+    transformed: usize,
+    /// Whether the output differs from the previous build's.
+    changed: bool,
+    duration_ms: u64,
+}
+
+impl ScriptWatchers {
+    /// Ends the watch of a closed project.
+    pub fn stop(&self, project_dir: &Path) -> bool {
+        self.watches.lock().unwrap_or_else(|e| e.into_inner()).remove(project_dir).is_some()
+    }
+}
+
+// =============================================================================
+// Building
+// =============================================================================
+
+struct Builder {
+    app: AppHandle,
+    project_dir: PathBuf,
+    output: PathBuf,
+    minify: bool,
+    cache: ModuleCache,
+    last: Option<String>,
+}
+
+impl Builder {
+    fn build(&mut self) {
+        let started = Instant::now();
+        let result = bundle_project_cached(&self.project_dir, self.minify, &mut self.cache);
+        let mut event = ScriptBuild {
+            project_dir: self.project_dir.to_string_lossy().to_string(),
+            success: true,
+            errors: Vec::new(),
+            output_path: None,
+            modules: 0,
+            transformed: 0,
+            changed: false,
+            duration_ms: 0,
+        };
+        match result {
+            Ok(Some(bundle)) => {
+                event.modules = bundle.modules.len();
+                event.transformed = bundle.transformed;
+                event.changed = self.last.as_deref() != Some(bundle.code.as_str());
+                if event.changed {
+                    match self.write(&bundle.code) {
+                        Ok(()) => {
+                            event.output_path = Some(self.output.to_string_lossy().to_string());
+                            self.last = Some(bundle.code);
+                            self.reload_preview();
+                        }
+                        Err(e) => {
+                            event.success = false;
+                            event.errors.push(ScriptError { file: String::new(), line: 0, column: 0, message: e });
+                        }
+                    }
+                } else {
+                    event.output_path = Some(self.output.to_string_lossy().to_string());
+                }
+            }
+            Ok(None) => {}
+            Err(errors) => {
+                event.success = false;
+                event.errors = errors;
+            }
+        }
+        event.duration_ms = started.elapsed().as_millis() as u64;
+        let _ = self.app.emit("script-build", event);
+    }
+
+    fn write(&self, code: &str) -> Result<(), String> {
+        if let Some(parent) = self.output.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&self.output, code).map_err(|e| format!("Failed to write {}: {}", self.output.display(), e))?;
+        // Only a preview the editor already prepared is kept current.
+        let preview = self.project_dir.join(PREVIEW_OUTPUT);
+        if preview.parent().is_some_and(Path::is_dir) && preview != self.output {
+            std::fs::write(&preview, code).map_err(|e| format!("Failed to write {}: {}", preview.display(), e))?;
+        }
+        Ok(())
+    }
+
+    fn reload_preview(&self) {
+        let Some(state) = self.app.try_state::<crate::AppState>() else {
+            return;
+        };
+        let servers = state.preview_servers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(server) = servers.get(&self.project_dir) {
+            server.notify_reload();
+        }
+    }
+}
+
+/// Whether a change can affect the bundle, and whether it added or removed
+/// files, after which imports may resolve differently.
+fn classify(project_dir: &Path, event: &notify::Event) -> (bool, bool) {
+    if event.kind.is_access() {
+        return (false, false);
+    }
+    let src = project_dir.join("src");
+    let relevant = event.paths.iter().any(|path| {
+        path.extension().and_then(|e| e.to_str()).is_some_and(|e| SCRIPT_EXTENSIONS.contains(&e))
+            && !is_ignored(&src, path)
+    });
+    let structural = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(notify::event::ModifyKind::Name(_))
+    );
+    (relevant || structural, structural)
+}
+
+fn run(mut builder: Builder, rx: mpsc::Receiver<notify::Event>) {
+    builder.build();
+    while let Ok(event) = rx.recv() {
+        let (mut rebuild, mut structural) = classify(&builder.project_dir, &event);
+        // Editors save in several steps; wait for the burst to end.
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(event) => {
+                    let (relevant, moved) = classify(&builder.project_dir, &event);
+                    rebuild |= relevant;
+                    structural |= moved;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if structural {
+            builder.cache.clear();
+        }
+        if rebuild {
+            builder.build();
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Starts rebuilding `project_dir`'s scripts on change; the first build runs
+/// right away. Starting a watch that is already running restarts it with
+/// the new options.
+#[tauri::command]
+pub fn start_script_watch(
+    app: AppHandle,
+    watchers: State<'_, ScriptWatchers>,
+    project_dir: String,
+    output_path: Option<String>,
+    minify: Option<bool>,
+) -> Result<ScriptWatchInfo, String> {
+    let dir = PathBuf::from(&project_dir);
+    let src = dir.join("src");
+    if !src.is_dir() {
+        return Err(format!("No src folder in {}", project_dir));
+    }
+    let output = output_path.map(PathBuf::from).unwrap_or_else(|| dir.join(DEFAULT_OUTPUT));
+    let minify = minify.unwrap_or(false);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            let _ = tx.send(event);
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher.watch(&src, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+
+    let builder = Builder {
+        app: app.clone(),
+        project_dir: dir.clone(),
+        output: output.clone(),
+        minify,
+        cache: ModuleCache::default(),
+        last: None,
+    };
+    std::thread::spawn(move || run(builder, rx));
+
+    // Replacing an earlier watch drops its watcher, which ends its thread.
+    watchers
+        .watches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(dir, ScriptWatch { output: output.clone(), minify, _watcher: watcher });
+    eprintln!("[ScriptWatch] Watching {}", src.display());
+    Ok(ScriptWatchInfo { project_dir, output_path: output.to_string_lossy().to_string(), minify })
+}
+
+#[tauri::command]
+pub fn stop_script_watch(watchers: State<'_, ScriptWatchers>, project_dir: String) -> bool {
+    watchers.stop(Path::new(&project_dir))
+}
+
+#[tauri::command]
+pub fn list_script_watches(watchers: State<'_, ScriptWatchers>) -> Vec<ScriptWatchInfo> {
+    watchers
+        .watches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(dir, watch)| ScriptWatchInfo {
+            project_dir: dir.to_string_lossy().to_string(),
+            output_path: watch.output.to_string_lossy().to_string(),
+            minify: watch.minify,
+        })
+        .collect()
+}
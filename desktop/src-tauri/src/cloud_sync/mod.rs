@@ -0,0 +1,490 @@
+//! Cloud project sync — keeps a project in step with S3 or WebDAV storage
+//!
+//! The remote holds content-addressed objects (`objects/<sha256>`) and a
+//! `sync-manifest.json` mapping project paths to hashes, so uploads never
+//! overwrite anything another machine might be reading, and the manifest
+//! write is the only step that publishes changes. Each machine keeps the
+//! manifest of its last sync in `.esengine/sync-state.json` as the common
+//! base: a file changed only locally is uploaded, one changed only remotely
+//! is downloaded, and one changed on both sides keeps both copies, the
+//! remote one saved next to it as `name.conflict-<host>-<time>.ext`. Local
+//! deletions coming from the remote go to the trash.
+
+mod webdav;
+
+use crate::deploy::{request, s3::S3Config, wildcard_match, ObjectStore};
+use crate::export::archive::{sha256_file, ChecksumEntry, ChecksumManifest};
+use crate::export::assets::{relative_path, walk_files};
+use crate::project::write_atomic;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::{AppHandle, Emitter};
+use webdav::WebDavConfig;
+
+const REMOTE_MANIFEST: &str = "sync-manifest.json";
+const OBJECTS_DIR: &str = "objects/";
+const STATE_FILE: &str = ".esengine/sync-state.json";
+const MANIFEST_VERSION: u32 = 1;
+/// Never synced: editor state, VCS data, build output and dependencies.
+const IGNORED: &[&str] = &[".esengine/*", ".git/*", ".svn/*", "build/*", "node_modules/*", "*.DS_Store", "*Thumbs.db"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncOptions {
+    pub project_dir: String,
+    pub target: SyncTarget,
+    /// Key prefix inside the bucket or collection.
+    #[serde(default)]
+    pub prefix: String,
+    /// Extra project-relative patterns to leave out; `*` matches anything.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Computes what would happen without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "provider")]
+pub enum SyncTarget {
+    #[serde(rename = "s3")]
+    S3(S3Config),
+    #[serde(rename = "webdav")]
+    WebDav(WebDavConfig),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub path: String,
+    /// Where the remote version was saved.
+    pub copy: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncResult {
+    pub uploaded: Vec<String>,
+    pub downloaded: Vec<String>,
+    /// Removed locally because they were deleted remotely.
+    pub deleted_local: Vec<String>,
+    /// Removed remotely because they were deleted locally.
+    pub deleted_remote: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    pub unchanged: usize,
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct SyncProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+enum Action {
+    Upload(String),
+    Download(String),
+    DeleteLocal(String),
+    /// Keep local at `path`, save remote as `copy`.
+    Conflict { path: String, copy: String },
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn sync_project(app: AppHandle, options: SyncOptions) -> Result<SyncResult, String> {
+    let project_dir = PathBuf::from(&options.project_dir);
+    if !project_dir.join(crate::project::PROJECT_FILE).is_file() {
+        return Err(format!("Not a project folder: {}", options.project_dir));
+    }
+    let client = reqwest::Client::new();
+    let remote = Remote {
+        client: &client,
+        target: &options.target,
+        prefix: normalize_prefix(&options.prefix),
+    };
+
+    emit_progress(&app, "scan", "Hashing project files...", 0.0);
+    let ignore: Vec<String> = IGNORED.iter().map(|p| p.to_string()).chain(options.ignore.clone()).collect();
+    let scan_dir = project_dir.clone();
+    let local = tokio::task::spawn_blocking(move || scan(&scan_dir, &ignore))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e))??;
+
+    emit_progress(&app, "compare", "Fetching remote manifest...", 0.1);
+    let remote_bytes = remote.get(REMOTE_MANIFEST).await?;
+    let remote_files = match &remote_bytes {
+        Some(bytes) => hashes(
+            &serde_json::from_slice(bytes).map_err(|e| format!("Invalid remote {}: {}", REMOTE_MANIFEST, e))?,
+        ),
+        None => BTreeMap::new(),
+    };
+    let state_key = remote.describe();
+    let base = read_state(&project_dir, &state_key);
+
+    let (actions, unchanged) = plan(&local, &remote_files, &base);
+    let mut result = SyncResult {
+        unchanged,
+        dry_run: options.dry_run,
+        ..SyncResult::default()
+    };
+    for action in &actions {
+        match action {
+            Action::Upload(path) if local.contains_key(path) => result.uploaded.push(path.clone()),
+            Action::Upload(path) => result.deleted_remote.push(path.clone()),
+            Action::Download(path) => result.downloaded.push(path.clone()),
+            Action::DeleteLocal(path) => result.deleted_local.push(path.clone()),
+            Action::Conflict { path, copy } => result.conflicts.push(SyncConflict {
+                path: path.clone(),
+                copy: copy.clone(),
+            }),
+        }
+    }
+    if options.dry_run {
+        emit_progress(&app, "complete", "Dry run complete", 1.0);
+        return Ok(result);
+    }
+
+    // Objects first; nothing is visible to other machines until the
+    // manifest is written.
+    let total = actions.len().max(1);
+    let done = AtomicUsize::new(0);
+    let (remote_ref, app_ref, done_ref) = (&remote, &app, &done);
+    let (project_ref, local_ref, remote_files_ref) = (&project_dir, &local, &remote_files);
+    // Collected first: a lazy map over borrowed actions is not Send.
+    let transfers: Vec<_> = actions
+        .iter()
+        .map(|action| async move {
+            let label = apply(action, remote_ref, project_ref, local_ref, remote_files_ref).await?;
+            let done = done_ref.fetch_add(1, Ordering::Relaxed) + 1;
+            emit_progress(
+                app_ref,
+                "transfer",
+                &format!("Synced {} ({}/{})", label, done, total),
+                0.15 + 0.8 * done as f32 / total as f32,
+            );
+            Ok::<(), String>(())
+        })
+        .collect();
+    let mut transfers = stream::iter(transfers).buffer_unordered(options.concurrency.max(1));
+    while let Some(outcome) = transfers.next().await {
+        outcome?;
+    }
+
+    // The result of the sync: every path as it now is on both sides.
+    let mut merged = remote_files.clone();
+    for action in &actions {
+        match action {
+            Action::Upload(path) => match local.get(path) {
+                Some(hash) => merged.insert(path.clone(), hash.clone()),
+                None => merged.remove(path),
+            },
+            Action::DeleteLocal(path) => merged.remove(path),
+            Action::Download(_) => None,
+            Action::Conflict { path, copy } => {
+                merged.insert(copy.clone(), remote_files[path].clone());
+                merged.insert(path.clone(), local[path].clone())
+            }
+        };
+    }
+    let manifest = manifest_of(&project_dir, &merged);
+    let data = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+
+    if remote.get(REMOTE_MANIFEST).await? != remote_bytes {
+        return Err("The project was synced from another machine meanwhile; sync again".to_string());
+    }
+    remote.put(REMOTE_MANIFEST, data).await?;
+    write_state(&project_dir, &state_key, &manifest)?;
+
+    emit_progress(
+        &app,
+        "complete",
+        &format!(
+            "Uploaded {}, downloaded {}, {} conflict(s)",
+            result.uploaded.len(),
+            result.downloaded.len(),
+            result.conflicts.len()
+        ),
+        1.0,
+    );
+    Ok(result)
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+/// Three-way comparison of local, remote and last-synced hashes.
+fn plan(
+    local: &BTreeMap<String, String>,
+    remote: &BTreeMap<String, String>,
+    base: &BTreeMap<String, String>,
+) -> (Vec<Action>, usize) {
+    let paths: BTreeSet<&String> = local.keys().chain(remote.keys()).chain(base.keys()).collect();
+    let mut actions = Vec::new();
+    let mut unchanged = 0;
+    for path in paths {
+        let (l, r, b) = (local.get(path), remote.get(path), base.get(path));
+        if l == r {
+            if l.is_some() {
+                unchanged += 1;
+            }
+            continue;
+        }
+        let action = if l == b {
+            match r {
+                Some(_) => Action::Download(path.clone()),
+                None => Action::DeleteLocal(path.clone()),
+            }
+        } else if r == b {
+            Action::Upload(path.clone())
+        } else {
+            // Changed on both sides. A deletion loses to an edit.
+            match (l, r) {
+                (None, Some(_)) => Action::Download(path.clone()),
+                (Some(_), None) => Action::Upload(path.clone()),
+                _ => Action::Conflict {
+                    path: path.clone(),
+                    copy: conflict_name(path, local, remote),
+                },
+            }
+        };
+        actions.push(action);
+    }
+    (actions, unchanged)
+}
+
+/// `name.conflict-<host>-<time>.ext`, unused on either side.
+fn conflict_name(path: &str, local: &BTreeMap<String, String>, remote: &BTreeMap<String, String>) -> String {
+    let stamp = crate::project::now_iso8601().replace(['-', ':'], "");
+    let host: String = crate::project::lock::hostname()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let (dir, name) = path.rsplit_once('/').map_or(("", path), |(d, n)| (d, n));
+    let (stem, ext) = name.rsplit_once('.').map_or((name, String::new()), |(s, e)| (s, format!(".{}", e)));
+    (0..)
+        .map(|n| {
+            let suffix = if n == 0 { String::new() } else { format!("-{}", n) };
+            let file = format!("{}.conflict-{}-{}{}{}", stem, host, &stamp[..15], suffix, ext);
+            if dir.is_empty() {
+                file
+            } else {
+                format!("{}/{}", dir, file)
+            }
+        })
+        .find(|candidate| !local.contains_key(candidate) && !remote.contains_key(candidate))
+        .expect("unbounded range")
+}
+
+// =============================================================================
+// Remote
+// =============================================================================
+
+struct Remote<'a> {
+    client: &'a reqwest::Client,
+    target: &'a SyncTarget,
+    prefix: String,
+}
+
+impl Remote<'_> {
+    /// Identifies the remote in the local sync state.
+    fn describe(&self) -> String {
+        let location = match self.target {
+            SyncTarget::S3(config) => format!("s3:{}", config.url("")),
+            SyncTarget::WebDav(config) => format!("webdav:{}/", config.describe()),
+        };
+        format!("{}{}", location, self.prefix)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let key = format!("{}{}", self.prefix, key);
+        match self.target {
+            SyncTarget::S3(config) => {
+                let response =
+                    request(self.client, config, reqwest::Method::GET, &key, BTreeMap::new(), Vec::new()).await?;
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                let response = s3_check(response).await?;
+                Ok(Some(response.bytes().await.map_err(|e| e.to_string())?.to_vec()))
+            }
+            SyncTarget::WebDav(config) => config.get(self.client, &key).await,
+        }
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), String> {
+        let key = format!("{}{}", self.prefix, key);
+        match self.target {
+            SyncTarget::S3(config) => {
+                let mut headers = BTreeMap::new();
+                headers.insert("content-type".to_string(), "application/octet-stream".to_string());
+                let response = request(self.client, config, reqwest::Method::PUT, &key, headers, data).await?;
+                s3_check(response).await.map(|_| ())
+            }
+            SyncTarget::WebDav(config) => config.put(self.client, &key, data).await,
+        }
+        .map_err(|e| format!("Failed to upload {}: {}", key, e))
+    }
+}
+
+async fn s3_check(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    Err(format!("HTTP {}: {}", status, crate::deploy::error_message(&text)))
+}
+
+/// Fetches the object for `hash` into `path`, checking its contents.
+async fn download(remote: &Remote<'_>, project_dir: &Path, path: &str, hash: &str) -> Result<(), String> {
+    let data = remote
+        .get(&object_key(hash))
+        .await?
+        .ok_or_else(|| format!("The remote is missing the contents of {}", path))?;
+    if format!("{:x}", Sha256::digest(&data)) != hash {
+        return Err(format!("Downloaded contents of {} are corrupt", path));
+    }
+    let destination = project_dir.join(path);
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_atomic(&destination, &data)
+}
+
+/// Carries out one action; returns the path it was about.
+async fn apply<'a>(
+    action: &'a Action,
+    remote: &Remote<'_>,
+    project_dir: &Path,
+    local: &BTreeMap<String, String>,
+    remote_files: &BTreeMap<String, String>,
+) -> Result<&'a str, String> {
+    match action {
+        Action::Upload(path) => {
+            if let Some(hash) = local.get(path) {
+                let data =
+                    std::fs::read(project_dir.join(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                remote.put(&object_key(hash), data).await?;
+            }
+            Ok(path)
+        }
+        Action::Download(path) => {
+            download(remote, project_dir, path, &remote_files[path]).await?;
+            Ok(path)
+        }
+        Action::DeleteLocal(path) => {
+            trash::delete(project_dir.join(path)).map_err(|e| format!("Failed to delete {}: {}", path, e))?;
+            Ok(path)
+        }
+        Action::Conflict { path, copy } => {
+            let data = std::fs::read(project_dir.join(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            remote.put(&object_key(&local[path]), data).await?;
+            download(remote, project_dir, copy, &remote_files[path]).await?;
+            Ok(path)
+        }
+    }
+}
+
+fn object_key(hash: &str) -> String {
+    format!("{}{}/{}", OBJECTS_DIR, &hash[..2], hash)
+}
+
+// =============================================================================
+// Local state
+// =============================================================================
+
+/// Path -> SHA-256 of every synced file in the project.
+fn scan(project_dir: &Path, ignore: &[String]) -> Result<BTreeMap<String, String>, String> {
+    let mut files = BTreeMap::new();
+    for path in walk_files(project_dir) {
+        let rel = relative_path(project_dir, &path);
+        if ignore.iter().any(|pattern| wildcard_match(pattern, &rel)) {
+            continue;
+        }
+        files.insert(rel, sha256_file(&path)?);
+    }
+    Ok(files)
+}
+
+fn hashes(manifest: &ChecksumManifest) -> BTreeMap<String, String> {
+    manifest.files.iter().map(|f| (f.path.clone(), f.sha256.clone())).collect()
+}
+
+fn manifest_of(project_dir: &Path, files: &BTreeMap<String, String>) -> ChecksumManifest {
+    ChecksumManifest {
+        version: MANIFEST_VERSION,
+        algorithm: "sha256".to_string(),
+        files: files
+            .iter()
+            .map(|(path, sha256)| ChecksumEntry {
+                path: path.clone(),
+                size: std::fs::metadata(project_dir.join(path)).map(|m| m.len()).unwrap_or(0),
+                sha256: sha256.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// The manifest of the last sync with `remote`; empty before the first.
+fn read_state(project_dir: &Path, remote: &str) -> BTreeMap<String, String> {
+    std::fs::read_to_string(project_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str::<BTreeMap<String, ChecksumManifest>>(&s).ok())
+        .and_then(|mut states| states.remove(remote))
+        .map(|manifest| hashes(&manifest))
+        .unwrap_or_default()
+}
+
+fn write_state(project_dir: &Path, remote: &str, manifest: &ChecksumManifest) -> Result<(), String> {
+    let path = project_dir.join(STATE_FILE);
+    let mut states: BTreeMap<String, ChecksumManifest> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    states.insert(remote.to_string(), manifest.clone());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_vec_pretty(&states).map_err(|e| e.to_string())?;
+    write_atomic(&path, &json)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", trimmed)
+    }
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "project-sync-progress",
+        SyncProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
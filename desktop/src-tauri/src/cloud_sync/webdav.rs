@@ -0,0 +1,101 @@
+//! WebDAV storage — Nextcloud, ownCloud, NAS boxes and the like
+
+use crate::deploy::encode_key;
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+#[derive(Debug, Deserialize)]
+pub struct WebDavConfig {
+    /// Collection URL, e.g. `https://cloud.example.com/remote.php/dav/files/me/games`.
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Collections known to exist, so each is created at most once.
+    #[serde(skip)]
+    created: Mutex<HashSet<String>>,
+}
+
+impl Clone for WebDavConfig {
+    fn clone(&self) -> Self {
+        Self {
+            url: self.url.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            created: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl WebDavConfig {
+    pub fn describe(&self) -> String {
+        self.url.trim_end_matches('/').to_string()
+    }
+
+    pub async fn get(&self, client: &reqwest::Client, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self.send(client, Method::GET, key, None).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = check(response).await?;
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    pub async fn put(&self, client: &reqwest::Client, key: &str, data: Vec<u8>) -> Result<(), String> {
+        self.create_parents(client, key).await?;
+        check(self.send(client, Method::PUT, key, Some(data)).await?).await.map(|_| ())
+    }
+
+    /// WebDAV servers do not create intermediate collections on PUT.
+    async fn create_parents(&self, client: &reqwest::Client, key: &str) -> Result<(), String> {
+        let mkcol = Method::from_bytes(b"MKCOL").expect("valid method");
+        let parts: Vec<&str> = key.split('/').collect();
+        for depth in 1..parts.len() {
+            let collection = format!("{}/", parts[..depth].join("/"));
+            if self.created.lock().unwrap_or_else(|e| e.into_inner()).contains(&collection) {
+                continue;
+            }
+            let response = self.send(client, mkcol.clone(), &collection, None).await?;
+            // 405: the collection already exists.
+            if !response.status().is_success() && response.status() != StatusCode::METHOD_NOT_ALLOWED {
+                check(response).await?;
+            }
+            self.created
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(collection);
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        method: Method,
+        key: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response, String> {
+        let url = format!("{}/{}", self.url.trim_end_matches('/'), encode_key(key));
+        let mut request = client.request(method, &url);
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        request.send().await.map_err(|e| format!("{}: {}", url, e))
+    }
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    Err(format!("HTTP {}: {}", status, text.chars().take(200).collect::<String>()))
+}
@@ -125,7 +125,7 @@ fn toolchain_archive_url() -> String {
     )
 }
 
-fn version_ge(actual: &str, required: &str) -> bool {
+pub(crate) fn version_ge(actual: &str, required: &str) -> bool {
     let parse = |s: &str| -> Vec<u32> {
         s.split('.')
             .filter_map(|p| p.trim().parse::<u32>().ok())
@@ -133,9 +133,8 @@ fn version_ge(actual: &str, required: &str) -> bool {
     };
     let a = parse(actual);
     let r = parse(required);
-    for i in 0..r.len() {
+    for (i, &rv) in r.iter().enumerate() {
         let av = a.get(i).copied().unwrap_or(0);
-        let rv = r[i];
         if av > rv { return true; }
         if av < rv { return false; }
     }
@@ -211,7 +210,7 @@ fn get_emcc_version(emsdk_path: &Path) -> Option<String> {
         .lines()
         .find_map(|line| {
             line.split_whitespace()
-                .find(|w| w.chars().next().map_or(false, |c| c.is_ascii_digit()))
+                .find(|w| w.chars().next().is_some_and(|c| c.is_ascii_digit()))
         })
         .map(|s| s.trim_end_matches(|c: char| !c.is_ascii_digit() && c != '.').to_string());
     re_version
@@ -325,7 +324,7 @@ fn find_emsdk_python(emsdk_path: &Path) -> Option<(PathBuf, String)> {
     None
 }
 
-fn which_sync(bin: &str) -> Option<PathBuf> {
+pub(crate) fn which_sync(bin: &str) -> Option<PathBuf> {
     let cmd = if cfg!(windows) { "where" } else { "which" };
     let output = std::process::Command::new(cmd)
         .arg(bin)
@@ -449,13 +448,13 @@ pub fn get_toolchain_status(app: AppHandle) -> ToolchainStatus {
 
     let emscripten_ok = emscripten_version
         .as_deref()
-        .map_or(false, |v| version_ge(v, MIN_EMSCRIPTEN_VERSION));
+        .is_some_and(|v| version_ge(v, MIN_EMSCRIPTEN_VERSION));
     let cmake_ok = cmake_version
         .as_deref()
-        .map_or(false, |v| version_ge(v, MIN_CMAKE_VERSION));
+        .is_some_and(|v| version_ge(v, MIN_CMAKE_VERSION));
     let python_ok = python_version
         .as_deref()
-        .map_or(false, |v| version_ge(v, MIN_PYTHON_VERSION));
+        .is_some_and(|v| version_ge(v, MIN_PYTHON_VERSION));
 
     ToolchainStatus {
         installed: emscripten_ok && cmake_ok && python_ok,
@@ -565,7 +564,7 @@ async fn download_with_progress(app: &AppHandle, url: &str) -> Result<Vec<u8>, S
     Ok(data)
 }
 
-fn extract_zip(data: &[u8], target: &Path) -> Result<(), String> {
+pub(crate) fn extract_zip(data: &[u8], target: &Path) -> Result<(), String> {
     let cursor = std::io::Cursor::new(data);
     let mut archive = zip::ZipArchive::new(cursor).map_err(|e| format!("Invalid zip: {}", e))?;
 
@@ -603,7 +602,7 @@ fn extract_zip(data: &[u8], target: &Path) -> Result<(), String> {
     Ok(())
 }
 
-fn extract_tar_gz(data: &[u8], target: &Path) -> Result<(), String> {
+pub(crate) fn extract_tar_gz(data: &[u8], target: &Path) -> Result<(), String> {
     let cursor = std::io::Cursor::new(data);
     let gz = flate2::read::GzDecoder::new(cursor);
     let mut archive = tar::Archive::new(gz);
@@ -633,7 +632,7 @@ fn extract_tar_gz(data: &[u8], target: &Path) -> Result<(), String> {
             #[cfg(unix)]
             {
                 use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = entry.header().mode().ok() {
+                if let Ok(mode) = entry.header().mode() {
                     std::fs::set_permissions(
                         &out_path,
                         std::fs::Permissions::from_mode(mode),
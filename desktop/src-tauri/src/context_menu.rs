@@ -0,0 +1,187 @@
+//! Native context menus
+//!
+//! `show_context_menu` pops up a native menu built from a JSON description
+//! and resolves to the id of the chosen item, so the editor's right-click
+//! menus look and behave like the platform's instead of the webview's.
+//!
+//! The choice arrives as a menu event. Only one context menu is open at a
+//! time: opening another resolves the previous one to `None`. On macOS and
+//! Windows the popup blocks until it closes, so a menu closed without a
+//! choice resolves to `None` shortly after; GTK menus do not block, and
+//! there a dismissed menu resolves when the next one opens.
+
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, LogicalPosition, Manager, WebviewWindow, Wry};
+use tokio::sync::oneshot;
+
+const ID_PREFIX: &str = "ctx:";
+const MAX_DEPTH: usize = 8;
+
+/// How long after a blocking popup closes its menu event may still arrive.
+const DISMISS_GRACE: std::time::Duration = std::time::Duration::from_millis(150);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMenuItem {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub separator: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Shows a check mark when set.
+    pub checked: Option<bool>,
+    /// Shown next to the label, e.g. `CmdOrCtrl+C`.
+    pub accelerator: Option<String>,
+    /// Makes the item a submenu.
+    pub items: Option<Vec<ContextMenuItem>>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+struct Pending {
+    token: u64,
+    sender: oneshot::Sender<Option<String>>,
+}
+
+/// The context menu waiting for a choice.
+#[derive(Default)]
+pub struct ContextMenus {
+    next_token: AtomicU64,
+    pending: Mutex<Option<Pending>>,
+}
+
+impl ContextMenus {
+    fn open(&self) -> (u64, oneshot::Receiver<Option<String>>) {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        // Dropping the previous sender resolves its menu to `None`.
+        *self.pending.lock().unwrap() = Some(Pending { token, sender });
+        (token, receiver)
+    }
+
+    fn resolve(&self, token: u64, choice: Option<String>) {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.as_ref().is_some_and(|p| p.token == token) {
+            let _ = pending.take().unwrap().sender.send(choice);
+        }
+    }
+}
+
+// =============================================================================
+// Menu
+// =============================================================================
+
+fn item_id(token: u64, id: &str) -> String {
+    format!("{}{}:{}", ID_PREFIX, token, id)
+}
+
+fn build_items(
+    app: &AppHandle,
+    token: u64,
+    items: &[ContextMenuItem],
+    depth: usize,
+) -> Result<Vec<MenuItemKind<Wry>>, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("Context menus nest at most {} levels", MAX_DEPTH));
+    }
+    items
+        .iter()
+        .map(|item| {
+            let built = if item.separator {
+                MenuItemKind::Predefined(PredefinedMenuItem::separator(app).map_err(|e| e.to_string())?)
+            } else if let Some(children) = &item.items {
+                let children = build_items(app, token, children, depth + 1)?;
+                let children: Vec<&dyn IsMenuItem<Wry>> = children.iter().map(|c| c as &dyn IsMenuItem<Wry>).collect();
+                let submenu =
+                    Submenu::with_items(app, &item.label, item.enabled, &children).map_err(|e| e.to_string())?;
+                MenuItemKind::Submenu(submenu)
+            } else if item.id.is_empty() {
+                return Err(format!("Context menu item '{}' has no id", item.label));
+            } else if let Some(checked) = item.checked {
+                let id = item_id(token, &item.id);
+                let check =
+                    CheckMenuItem::with_id(app, id, &item.label, item.enabled, checked, item.accelerator.as_deref())
+                        .map_err(|e| e.to_string())?;
+                MenuItemKind::Check(check)
+            } else {
+                let id = item_id(token, &item.id);
+                let entry = MenuItem::with_id(app, id, &item.label, item.enabled, item.accelerator.as_deref())
+                    .map_err(|e| e.to_string())?;
+                MenuItemKind::MenuItem(entry)
+            };
+            Ok(built)
+        })
+        .collect()
+}
+
+/// Resolves the pending context menu when `id` is one of its items.
+/// Returns false for ids of other menus.
+pub fn handle_event(app: &AppHandle, id: &str) -> bool {
+    let Some((token, item)) = id.strip_prefix(ID_PREFIX).and_then(|rest| rest.split_once(':')) else {
+        return false;
+    };
+    if let (Ok(token), Some(menus)) = (token.parse::<u64>(), app.try_state::<ContextMenus>()) {
+        menus.resolve(token, Some(item.to_string()));
+    }
+    true
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Shows `items` as a native context menu at `x`, `y` (logical pixels in
+/// the window) or at the cursor, and returns the id of the chosen item, or
+/// `None` when the menu was closed without a choice.
+#[tauri::command]
+pub async fn show_context_menu(
+    app: AppHandle,
+    window: WebviewWindow,
+    items: Vec<ContextMenuItem>,
+    x: Option<f64>,
+    y: Option<f64>,
+) -> Result<Option<String>, String> {
+    let menus = app.state::<ContextMenus>();
+    let (token, receiver) = menus.open();
+    // The item references are not Send; they must be gone before the await.
+    let menu = {
+        let built = build_items(&app, token, &items, 0)?;
+        let built: Vec<&dyn IsMenuItem<Wry>> = built.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect();
+        Menu::with_items(&app, &built).map_err(|e| e.to_string())?
+    };
+
+    let popup_window = window.clone();
+    let popup_app = app.clone();
+    window
+        .run_on_main_thread(move || {
+            let shown = match (x, y) {
+                (Some(x), Some(y)) => popup_window.popup_menu_at(&menu, LogicalPosition::new(x, y)),
+                _ => popup_window.popup_menu(&menu),
+            };
+            if let Err(e) = shown {
+                popup_app.state::<ContextMenus>().resolve(token, None);
+                eprintln!("[context-menu] Failed to show context menu: {}", e);
+            } else if cfg!(not(target_os = "linux")) {
+                // The popup has closed; a choice, if any, is on its way.
+                std::thread::spawn(move || {
+                    std::thread::sleep(DISMISS_GRACE);
+                    popup_app.state::<ContextMenus>().resolve(token, None);
+                });
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(receiver.await.unwrap_or(None))
+}
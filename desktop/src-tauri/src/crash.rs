@@ -0,0 +1,448 @@
+//! Crash reporting — panic reports and minidumps of native crashes
+//!
+//! A panic hook records every backend panic with its message, location and
+//! backtrace. Native crashes (a segfault or abort in the webview or a
+//! native library) are caught by `crash-handler`; a process in that state
+//! cannot be trusted to write files, so a monitor process (this binary with
+//! `--crash-monitor`) writes the minidump through `minidumper`.
+//!
+//! Each crash leaves `<app data>/crashes/<id>.json`, and `<id>.dmp` for a
+//! native crash, stamped with the editor and engine versions. On the next
+//! launch the editor lists the new reports in a recovery dialog. Nothing is
+//! sent anywhere unless the user sends a report, or turns on automatic
+//! uploads, to the crash endpoint (`ESENGINE_CRASH_ENDPOINT` at build time,
+//! or one set in the settings). A sent report carries the system it
+//! happened on (see `diagnostics`).
+
+use crate::diagnostics;
+use crate::engines;
+use crate::project::{now_iso8601, write_atomic};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Starts the process as a crash monitor: `--crash-monitor <socket> <crash dir>`.
+pub const MONITOR_FLAG: &str = "--crash-monitor";
+
+const CRASHES_DIR: &str = "crashes";
+const CONFIG_FILE: &str = "crash-reporting.json";
+/// Older reports are removed on startup.
+const MAX_REPORTS: usize = 20;
+const DEFAULT_ENDPOINT: Option<&str> = option_env!("ESENGINE_CRASH_ENDPOINT");
+
+/// `<app data>/crashes/`, set on startup; panics before that are only
+/// printed.
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// The installed native crash handler; dropping it would detach it.
+static HANDLER: Mutex<Option<crash_handler::CrashHandler>> = Mutex::new(None);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrashKind {
+    Panic,
+    Native,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    /// Not yet shown in the recovery dialog.
+    New,
+    Dismissed,
+    Uploaded,
+}
+
+/// `<id>.json` in the crash folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub time: String,
+    pub editor_version: String,
+    pub engine_version: String,
+    pub os: String,
+    pub arch: String,
+    pub message: String,
+    /// `file:line:column` of a panic.
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: Option<String>,
+    /// File name of the minidump of a native crash.
+    pub minidump: Option<String>,
+    pub status: ReportStatus,
+}
+
+/// `<app data>/crash-reporting.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CrashReportingConfig {
+    /// Send new reports on the next launch without asking.
+    pub auto_upload: bool,
+    /// Overrides the built-in endpoint; empty for the default.
+    pub endpoint: String,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, message: String) -> Self {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        let kind_name = match kind {
+            CrashKind::Panic => "panic",
+            CrashKind::Native => "native",
+        };
+        Self {
+            id: format!("{}-{}", millis, kind_name),
+            kind,
+            time: now_iso8601(),
+            editor_version: env!("CARGO_PKG_VERSION").to_string(),
+            engine_version: engines::embedded_version().to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            message,
+            location: None,
+            thread: None,
+            backtrace: None,
+            minidump: None,
+            status: ReportStatus::New,
+        }
+    }
+
+    fn save(&self, dir: &Path) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        write_atomic(&dir.join(format!("{}.json", self.id)), &data)
+    }
+}
+
+// =============================================================================
+// Capture
+// =============================================================================
+
+/// Records panics from here on; called first thing on startup.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(dir) = CRASH_DIR.get() {
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".to_string());
+            let mut report = CrashReport::new(CrashKind::Panic, message);
+            report.location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            report.thread = std::thread::current().name().map(String::from);
+            report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+            match report.save(dir) {
+                Ok(()) => eprintln!("[Crash] Panic recorded as {}", report.id),
+                Err(e) => eprintln!("[Crash] Failed to record a panic: {}", e),
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// Sets the crash folder, prunes old reports and starts the crash monitor
+/// in the background; called on startup.
+pub fn init(app: &AppHandle) {
+    let Ok(app_data) = app.path().app_data_dir() else {
+        return;
+    };
+    let dir = app_data.join(CRASHES_DIR);
+    let _ = std::fs::create_dir_all(&dir);
+    let _ = CRASH_DIR.set(dir.clone());
+    prune(&dir);
+    std::thread::spawn(move || {
+        if let Err(e) = start_monitor(&dir) {
+            eprintln!("[Crash] Native crashes will not be recorded: {}", e);
+        }
+    });
+}
+
+fn start_monitor(dir: &Path) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let socket = std::env::temp_dir().join(format!("esengine-crash-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket);
+    let mut monitor = Command::new(exe)
+        .arg(MONITOR_FLAG)
+        .arg(&socket)
+        .arg(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start the crash monitor: {}", e))?;
+
+    // The monitor needs a moment before it accepts connections.
+    let mut client = None;
+    for _ in 0..50 {
+        if let Ok(connected) = minidumper::Client::with_name(socket.as_path()) {
+            client = Some(connected);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let Some(client) = client else {
+        let _ = monitor.kill();
+        return Err("The crash monitor did not start".to_string());
+    };
+
+    // SAFETY: the closure only asks the monitor for a dump, which is what
+    // the crash-handler docs expect from a crash event.
+    let handler = crash_handler::CrashHandler::attach(unsafe {
+        crash_handler::make_crash_event(move |context: &crash_handler::CrashContext| {
+            crash_handler::CrashEventResult::Handled(client.request_dump(context).is_ok())
+        })
+    })
+    .map_err(|e| format!("Failed to install the crash handler: {}", e))?;
+    // Linux only lets the monitor read this process's memory once allowed.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    handler.set_ptracer(Some(monitor.id()));
+    *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = Some(handler);
+
+    // The monitor exits with this process; reaped so it is not a zombie.
+    std::thread::spawn(move || {
+        let _ = monitor.wait();
+    });
+    Ok(())
+}
+
+// =============================================================================
+// Monitor process
+// =============================================================================
+
+struct MonitorHandler {
+    dir: PathBuf,
+}
+
+impl minidumper::ServerHandler for MonitorHandler {
+    fn create_minidump_file(&self) -> Result<(File, PathBuf), std::io::Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        let report = CrashReport::new(CrashKind::Native, String::new());
+        let path = self.dir.join(format!("{}.dmp", report.id));
+        Ok((File::create(&path)?, path))
+    }
+
+    fn on_minidump_created(&self, result: Result<minidumper::MinidumpBinary, minidumper::Error>) -> minidumper::LoopAction {
+        let mut report = CrashReport::new(CrashKind::Native, "The editor crashed".to_string());
+        match result {
+            Ok(binary) => {
+                // Named after the dump, so the two are found together.
+                if let Some(stem) = binary.path.file_stem() {
+                    report.id = stem.to_string_lossy().to_string();
+                }
+                report.minidump = binary.path.file_name().map(|name| name.to_string_lossy().to_string());
+            }
+            Err(e) => report.message = format!("The editor crashed; writing its minidump failed: {}", e),
+        }
+        if let Err(e) = report.save(&self.dir) {
+            eprintln!("[Crash] Failed to record a crash: {}", e);
+        }
+        minidumper::LoopAction::Exit
+    }
+
+    fn on_message(&self, _kind: u32, _buffer: Vec<u8>) {}
+
+    fn on_client_disconnected(&self, num_clients: usize) -> minidumper::LoopAction {
+        // The editor exited normally.
+        if num_clients == 0 {
+            minidumper::LoopAction::Exit
+        } else {
+            minidumper::LoopAction::Continue
+        }
+    }
+}
+
+/// Runs the crash monitor after `--crash-monitor`; returns the exit code.
+pub fn run_monitor(args: &[String]) -> i32 {
+    let rest: Vec<&String> = args.iter().skip_while(|a| *a != MONITOR_FLAG).skip(1).collect();
+    let (Some(socket), Some(dir)) = (rest.first(), rest.get(1)) else {
+        eprintln!("Usage: esengine-editor {} <socket> <crash dir>", MONITOR_FLAG);
+        return 2;
+    };
+    let socket = PathBuf::from(socket);
+    let mut server = match minidumper::Server::with_name(socket.as_path()) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("[Crash] Failed to start the crash monitor: {}", e);
+            return 1;
+        }
+    };
+    let shutdown = AtomicBool::new(false);
+    let result = server.run(Box::new(MonitorHandler { dir: PathBuf::from(dir) }), &shutdown, None);
+    let _ = std::fs::remove_file(&socket);
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[Crash] Crash monitor failed: {}", e);
+            1
+        }
+    }
+}
+
+// =============================================================================
+// Reports
+// =============================================================================
+
+fn crash_dir() -> Result<&'static PathBuf, String> {
+    CRASH_DIR.get().ok_or_else(|| "Crash reporting is not set up".to_string())
+}
+
+/// Every report, newest first.
+fn reports(dir: &Path) -> Vec<CrashReport> {
+    let mut reports: Vec<CrashReport> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| std::fs::read(entry.path()).ok())
+        .filter_map(|data| serde_json::from_slice(&data).ok())
+        .collect();
+    // Ids start with the time in milliseconds.
+    reports.sort_by_key(|report: &CrashReport| {
+        std::cmp::Reverse(report.id.split('-').next().and_then(|ms| ms.parse::<u128>().ok()).unwrap_or(0))
+    });
+    reports
+}
+
+fn find(dir: &Path, id: &str) -> Result<CrashReport, String> {
+    reports(dir).into_iter().find(|report| report.id == id).ok_or_else(|| format!("No crash report {}", id))
+}
+
+fn remove(dir: &Path, report: &CrashReport) {
+    let _ = std::fs::remove_file(dir.join(format!("{}.json", report.id)));
+    if let Some(minidump) = &report.minidump {
+        let _ = std::fs::remove_file(dir.join(minidump));
+    }
+}
+
+fn prune(dir: &Path) {
+    for report in reports(dir).iter().skip(MAX_REPORTS) {
+        remove(dir, report);
+    }
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(CONFIG_FILE))
+}
+
+fn load_config(app: &AppHandle) -> CrashReportingConfig {
+    config_path(app)
+        .ok()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn endpoint(config: &CrashReportingConfig) -> Option<String> {
+    Some(config.endpoint.trim())
+        .filter(|url| !url.is_empty())
+        .or(DEFAULT_ENDPOINT)
+        .map(String::from)
+}
+
+/// Posts the report, with its minidump base64-encoded and the system it
+/// happened on, as JSON.
+async fn upload(endpoint: &str, dir: &Path, report: &CrashReport) -> Result<(), String> {
+    let minidump = match &report.minidump {
+        Some(name) => std::fs::read(dir.join(name)).ok().map(|data| BASE64.encode(data)),
+        None => None,
+    };
+    let system = diagnostics::system_info(None).await.ok();
+    let body = serde_json::json!({ "report": report, "minidump": minidump, "system": system });
+    let response = reqwest::Client::new()
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(&body).map_err(|e| e.to_string())?)
+        .send()
+        .await
+        .map_err(|e| format!("Upload failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Upload failed: HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+fn set_status(dir: &Path, mut report: CrashReport, status: ReportStatus) -> Result<CrashReport, String> {
+    report.status = status;
+    report.save(dir)?;
+    Ok(report)
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Every crash report, newest first; those with status `new` are from
+/// crashes since the last launch. With automatic uploads on, new reports
+/// are sent first.
+#[tauri::command]
+pub async fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    let dir = crash_dir()?;
+    let config = load_config(&app);
+    if let (true, Some(endpoint)) = (config.auto_upload, endpoint(&config)) {
+        for report in reports(dir).into_iter().filter(|report| report.status == ReportStatus::New) {
+            match upload(&endpoint, dir, &report).await {
+                Ok(()) => {
+                    set_status(dir, report, ReportStatus::Uploaded)?;
+                }
+                Err(e) => eprintln!("[Crash] Failed to send {}: {}", report.id, e),
+            }
+        }
+    }
+    Ok(reports(dir))
+}
+
+/// Sends a report, at the user's request.
+#[tauri::command]
+pub async fn upload_crash_report(app: AppHandle, id: String) -> Result<CrashReport, String> {
+    let dir = crash_dir()?;
+    let endpoint = endpoint(&load_config(&app)).ok_or("No crash report endpoint is configured")?;
+    let report = find(dir, &id)?;
+    upload(&endpoint, dir, &report).await?;
+    eprintln!("[Crash] Sent {}", id);
+    set_status(dir, report, ReportStatus::Uploaded)
+}
+
+/// Marks a report as seen, so the recovery dialog does not show it again.
+#[tauri::command]
+pub fn dismiss_crash_report(id: String) -> Result<CrashReport, String> {
+    let dir = crash_dir()?;
+    let report = find(dir, &id)?;
+    set_status(dir, report, ReportStatus::Dismissed)
+}
+
+/// Removes a report and its minidump.
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> Result<(), String> {
+    let dir = crash_dir()?;
+    remove(dir, &find(dir, &id)?);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_crash_reporting(app: AppHandle) -> CrashReportingConfig {
+    load_config(&app)
+}
+
+/// Opts in to or out of automatic uploads; an empty endpoint restores the
+/// built-in one.
+#[tauri::command]
+pub fn set_crash_reporting(app: AppHandle, config: CrashReportingConfig) -> Result<(), String> {
+    let url = config.endpoint.trim();
+    if !url.is_empty() {
+        reqwest::Url::parse(url).map_err(|e| format!("Invalid crash report endpoint: {}", e))?;
+    }
+    let data = serde_json::to_vec_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&config_path(&app)?, &data)
+}
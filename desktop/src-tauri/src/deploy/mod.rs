@@ -0,0 +1,392 @@
+//! Deployment — uploads build output to object storage / CDN origins
+//!
+//! Each provider implements `ObjectStore`, which only addresses and signs
+//! requests; transfer, retries and progress are shared. Uploads are delta
+//! based: the checksum manifest of the build is compared with the one
+//! uploaded by the previous deploy, and only changed files are sent. Assets
+//! go first, then HTML entry points, then the manifest, so a client never
+//! sees a page that references files not yet uploaded.
+
+mod cos;
+mod oss;
+pub(crate) mod s3;
+
+use crate::export::archive::{self, ChecksumManifest, MANIFEST_FILE};
+use crate::notify;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tauri::{AppHandle, Emitter};
+
+const MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_CACHE_CONTROL: &str = "public, max-age=3600";
+const NO_CACHE: &str = "no-cache";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeployOptions {
+    /// Build output folder to upload.
+    pub dir: String,
+    pub target: DeployTarget,
+    /// Key prefix inside the bucket, e.g. `games/demo/1.2.0`.
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Cache-Control rules; the first matching pattern wins. HTML and JSON
+    /// files default to `no-cache`, everything else to one hour.
+    #[serde(default)]
+    pub cache_rules: Vec<CacheRule>,
+    /// Uploads every file even when the remote manifest says it is unchanged.
+    #[serde(default)]
+    pub force: bool,
+}
+
+fn default_concurrency() -> usize {
+    8
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "provider")]
+pub enum DeployTarget {
+    #[serde(rename = "s3")]
+    S3(s3::S3Config),
+    #[serde(rename = "oss")]
+    Oss(oss::OssConfig),
+    #[serde(rename = "cos")]
+    Cos(cos::CosConfig),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheRule {
+    /// Output-relative path pattern; `*` matches any run of characters.
+    pub pattern: String,
+    pub cache_control: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployResult {
+    /// Public URL of the deployed prefix.
+    pub base_url: String,
+    pub uploaded: Vec<String>,
+    pub skipped: usize,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct DeployProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+/// An object storage provider. Implementations address and sign requests;
+/// `headers` uses lowercase names and already holds `host` and any content
+/// headers that will be sent.
+pub(crate) trait ObjectStore: Send + Sync {
+    fn url(&self, key: &str) -> String;
+    fn sign(&self, method: &str, key: &str, headers: &mut BTreeMap<String, String>, payload: &[u8]) -> Result<(), String>;
+}
+
+impl DeployTarget {
+    fn store(&self) -> Box<dyn ObjectStore> {
+        match self {
+            DeployTarget::S3(config) => Box::new(config.clone()),
+            DeployTarget::Oss(config) => Box::new(config.clone()),
+            DeployTarget::Cos(config) => Box::new(config.clone()),
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn deploy_build(app: AppHandle, options: DeployOptions) -> Result<DeployResult, String> {
+    let result = deploy(&app, options).await;
+    notify::upload_finished(&app, "Deploy", &result);
+    result
+}
+
+async fn deploy(app: &AppHandle, options: DeployOptions) -> Result<DeployResult, String> {
+    let dir = Path::new(&options.dir).to_path_buf();
+    if !dir.is_dir() {
+        return Err(format!("Build output not found: {}", dir.display()));
+    }
+    let store = options.target.store();
+    let prefix = normalize_prefix(&options.prefix);
+    let client = reqwest::Client::new();
+
+    emit_progress(app, "checksums", "Computing checksums...", 0.0);
+    let manifest_dir = dir.clone();
+    let local = tokio::task::spawn_blocking(move || archive::write_manifest(&manifest_dir))
+        .await
+        .map_err(|e| format!("Checksum task failed: {}", e))??;
+
+    let remote = if options.force {
+        None
+    } else {
+        emit_progress(app, "compare", "Fetching remote manifest...", 0.05);
+        fetch_manifest(&client, store.as_ref(), &format!("{}{}", prefix, MANIFEST_FILE)).await
+    };
+    let remote_hashes: HashMap<&str, &str> = remote
+        .as_ref()
+        .map(|m| m.files.iter().map(|f| (f.path.as_str(), f.sha256.as_str())).collect())
+        .unwrap_or_default();
+
+    let changed: Vec<(String, u64)> = local
+        .files
+        .iter()
+        .filter(|f| remote_hashes.get(f.path.as_str()) != Some(&f.sha256.as_str()))
+        .map(|f| (f.path.clone(), f.size))
+        .collect();
+    let skipped = local.files.len() - changed.len();
+    let (pages, assets): (Vec<_>, Vec<_>) = changed.into_iter().partition(|(path, _)| path.ends_with(".html"));
+
+    let total_bytes = assets.iter().chain(&pages).map(|(_, size)| size).sum::<u64>().max(1);
+    let total_files = assets.len() + pages.len();
+    let transfer = Transfer {
+        app,
+        client: &client,
+        store: store.as_ref(),
+        dir: &dir,
+        prefix: &prefix,
+        rules: &options.cache_rules,
+        total_bytes,
+        total_files,
+        sent_bytes: AtomicU64::new(0),
+        sent_files: AtomicUsize::new(0),
+    };
+    let concurrency = options.concurrency.max(1);
+    transfer.upload_all(&assets, concurrency).await?;
+    transfer.upload_all(&pages, concurrency).await?;
+    transfer.upload(MANIFEST_FILE).await?;
+
+    let uploaded: Vec<String> = assets.into_iter().chain(pages).map(|(path, _)| path).collect();
+    emit_progress(
+        app,
+        "complete",
+        &format!("Deployed {} file(s), {} unchanged", uploaded.len(), skipped),
+        1.0,
+    );
+    Ok(DeployResult {
+        base_url: store.url(&prefix),
+        uploaded,
+        skipped,
+        bytes: transfer.sent_bytes.load(Ordering::Relaxed),
+    })
+}
+
+// =============================================================================
+// Transfer
+// =============================================================================
+
+struct Transfer<'a> {
+    app: &'a AppHandle,
+    client: &'a reqwest::Client,
+    store: &'a dyn ObjectStore,
+    dir: &'a Path,
+    prefix: &'a str,
+    rules: &'a [CacheRule],
+    total_bytes: u64,
+    total_files: usize,
+    sent_bytes: AtomicU64,
+    sent_files: AtomicUsize,
+}
+
+impl Transfer<'_> {
+    async fn upload_all(&self, files: &[(String, u64)], concurrency: usize) -> Result<(), String> {
+        // Collected first: a lazy map over borrowed items is not Send.
+        let uploads: Vec<_> = files.iter().map(|(path, _)| self.upload(path)).collect();
+        let mut uploads = stream::iter(uploads).buffer_unordered(concurrency);
+        while let Some(result) = uploads.next().await {
+            result?;
+        }
+        Ok(())
+    }
+
+    async fn upload(&self, rel: &str) -> Result<(), String> {
+        let body = std::fs::read(self.dir.join(rel)).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let key = format!("{}{}", self.prefix, rel);
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), crate::preview_server::get_mime_type(rel).to_string());
+        headers.insert("cache-control".to_string(), cache_control(self.rules, rel));
+
+        let mut attempt = 1;
+        loop {
+            match send(self.client, self.store, reqwest::Method::PUT, &key, headers.clone(), body.clone()).await {
+                Ok(_) => break,
+                Err(_) if attempt < MAX_ATTEMPTS => attempt += 1,
+                Err(e) => return Err(format!("Failed to upload {}: {}", rel, e)),
+            }
+        }
+
+        if rel == MANIFEST_FILE {
+            return Ok(());
+        }
+        let bytes = self.sent_bytes.fetch_add(body.len() as u64, Ordering::Relaxed) + body.len() as u64;
+        let files = self.sent_files.fetch_add(1, Ordering::Relaxed) + 1;
+        emit_progress(
+            self.app,
+            "upload",
+            &format!("Uploaded {} ({}/{})", rel, files, self.total_files),
+            0.1 + 0.85 * (bytes as f32 / self.total_bytes as f32).min(1.0),
+        );
+        Ok(())
+    }
+}
+
+async fn send(
+    client: &reqwest::Client,
+    store: &dyn ObjectStore,
+    method: reqwest::Method,
+    key: &str,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+) -> Result<reqwest::Response, String> {
+    let response = request(client, store, method, key, headers, body).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("HTTP {}: {}", status, error_message(&text)));
+    }
+    Ok(response)
+}
+
+/// Signs and sends a request, leaving the status to the caller.
+pub(crate) async fn request(
+    client: &reqwest::Client,
+    store: &dyn ObjectStore,
+    method: reqwest::Method,
+    key: &str,
+    mut headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+) -> Result<reqwest::Response, String> {
+    let url = store.url(key);
+    let parsed = url::Url::parse(&url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    let host = match (parsed.host_str(), parsed.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => return Err(format!("Invalid URL {}", url)),
+    };
+    headers.insert("host".to_string(), host);
+    store.sign(method.as_str(), key, &mut headers, &body)?;
+
+    let mut request = client.request(method, parsed);
+    for (name, value) in headers.iter().filter(|(name, _)| name.as_str() != "host") {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    request.body(body).send().await.map_err(|e| e.to_string())
+}
+
+/// The previous deploy's manifest, or `None` on a first deploy or any failure.
+async fn fetch_manifest(client: &reqwest::Client, store: &dyn ObjectStore, key: &str) -> Option<ChecksumManifest> {
+    let response = send(client, store, reqwest::Method::GET, key, BTreeMap::new(), Vec::new())
+        .await
+        .ok()?;
+    serde_json::from_slice(&response.bytes().await.ok()?).ok()
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", trimmed)
+    }
+}
+
+fn cache_control(rules: &[CacheRule], rel: &str) -> String {
+    if let Some(rule) = rules.iter().find(|r| wildcard_match(&r.pattern, rel)) {
+        return rule.cache_control.clone();
+    }
+    if rel.ends_with(".html") || rel.ends_with(".json") || rel.ends_with(".webmanifest") {
+        NO_CACHE.to_string()
+    } else {
+        DEFAULT_CACHE_CONTROL.to_string()
+    }
+}
+
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || !text[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Pulls `<Code>`/`<Message>` out of an XML error body.
+pub(crate) fn error_message(body: &str) -> String {
+    let tag = |name: &str| {
+        let start = body.find(&format!("<{}>", name))? + name.len() + 2;
+        let end = body[start..].find(&format!("</{}>", name))? + start;
+        Some(body[start..end].to_string())
+    };
+    match (tag("Code"), tag("Message")) {
+        (Some(code), Some(message)) => format!("{} ({})", message, code),
+        (Some(code), None) => code,
+        _ => body.chars().take(200).collect(),
+    }
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(crate) fn hmac_sha1(key: &[u8], data: &str) -> Vec<u8> {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha1::Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Percent-encodes each segment of an object key, keeping `/`.
+pub(crate) fn encode_key(key: &str) -> String {
+    key.split('/').map(|s| urlencoding::encode(s).into_owned()).collect::<Vec<_>>().join("/")
+}
+
+pub(crate) fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "deploy-progress",
+        DeployProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
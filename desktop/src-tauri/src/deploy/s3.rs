@@ -0,0 +1,88 @@
+//! Amazon S3 and S3-compatible storage — AWS Signature Version 4
+
+use super::{encode_key, hex, hmac_sha256, unix_now, ObjectStore};
+use crate::export::build_info::iso8601_utc;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Config {
+    pub bucket: String,
+    /// e.g. `us-east-1`; use `auto` for Cloudflare R2.
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// S3-compatible endpoint such as `https://<account>.r2.cloudflarestorage.com`.
+    /// Addressed path-style (`<endpoint>/<bucket>/<key>`).
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl S3Config {
+    fn path(&self, key: &str) -> String {
+        match self.endpoint {
+            Some(_) => format!("/{}/{}", self.bucket, encode_key(key)),
+            None => format!("/{}", encode_key(key)),
+        }
+    }
+}
+
+impl ObjectStore for S3Config {
+    fn url(&self, key: &str) -> String {
+        match &self.endpoint {
+            Some(endpoint) => format!("{}{}", endpoint.trim_end_matches('/'), self.path(key)),
+            None => format!("https://{}.s3.{}.amazonaws.com{}", self.bucket, self.region, self.path(key)),
+        }
+    }
+
+    fn sign(&self, method: &str, key: &str, headers: &mut BTreeMap<String, String>, payload: &[u8]) -> Result<(), String> {
+        let (date, timestamp) = amz_date(unix_now());
+        let payload_hash = format!("{:x}", Sha256::digest(payload));
+        headers.insert("x-amz-date".to_string(), timestamp.clone());
+        headers.insert("x-amz-content-sha256".to_string(), payload_hash.clone());
+        if let Some(token) = &self.session_token {
+            headers.insert("x-amz-security-token".to_string(), token.clone());
+        }
+
+        let canonical_headers: String = headers.iter().map(|(k, v)| format!("{}:{}\n", k, v.trim())).collect();
+        let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method,
+            self.path(key),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            timestamp,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let mut signing_key = hmac_sha256(format!("AWS4{}", self.secret_access_key).as_bytes(), &date);
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac_sha256(&signing_key, part);
+        }
+        let signature = hex(&hmac_sha256(&signing_key, &string_to_sign));
+        headers.insert(
+            "authorization".to_string(),
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key_id, scope, signed_headers, signature
+            ),
+        );
+        Ok(())
+    }
+}
+
+/// `(YYYYMMDD, YYYYMMDDTHHMMSSZ)` for a Unix timestamp, in UTC.
+fn amz_date(secs: u64) -> (String, String) {
+    let timestamp = iso8601_utc(secs).replace(['-', ':'], "");
+    (timestamp[..8].to_string(), timestamp)
+}
@@ -0,0 +1,183 @@
+//! Diagnostics — what the editor runs on, for "it's slow on my machine"
+//!
+//! `get_system_info` reports the OS, CPU, memory, GPUs, webview version
+//! and the free space on the drive of the open project. The About dialog
+//! shows it, and crash reports carry it when they are sent.
+//!
+//! GPUs come from the platform's own tools (`Get-CimInstance` on Windows,
+//! `system_profiler` on macOS, `lspci` on Linux); what the webview renders
+//! with may still differ on machines with two. They are looked up once
+//! per launch.
+
+use crate::engines;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+static GPUS: OnceCell<Vec<String>> = OnceCell::const_new();
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+    pub os: String,
+    /// e.g. `Windows 11 Pro (22631)` or `macOS 14.4 Sonoma`.
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub arch: String,
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub gpus: Vec<String>,
+    pub webview_version: Option<String>,
+    pub editor_version: String,
+    pub engine_version: String,
+    /// The drive of the project asked about.
+    pub disk: Option<DiskInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuInfo {
+    pub brand: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryInfo {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskInfo {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+// =============================================================================
+// Collection
+// =============================================================================
+
+/// Runs `program args` and returns its stdout.
+async fn probe(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args).stdin(std::process::Stdio::null()).kill_on_drop(true);
+    #[cfg(windows)]
+    command.creation_flags(crate::process::CREATE_NO_WINDOW);
+    let output = tokio::time::timeout(PROBE_TIMEOUT, command.output()).await.ok()?.ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(windows)]
+async fn find_gpus() -> Vec<String> {
+    let script = "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name }";
+    let output = probe("powershell", &["-NoProfile", "-NonInteractive", "-Command", script]).await;
+    output.unwrap_or_default().lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect()
+}
+
+#[cfg(target_os = "macos")]
+async fn find_gpus() -> Vec<String> {
+    let Some(output) = probe("system_profiler", &["SPDisplaysDataType", "-json"]).await else {
+        return Vec::new();
+    };
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap_or_default();
+    json.get("SPDisplaysDataType")
+        .and_then(|displays| displays.as_array())
+        .map(|displays| {
+            displays
+                .iter()
+                .filter_map(|display| display.get("sppci_model").or_else(|| display.get("_name")))
+                .filter_map(|name| name.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+async fn find_gpus() -> Vec<String> {
+    // `lspci -mm` quotes its fields: slot "class" "vendor" "device" ...
+    let output = probe("lspci", &["-mm"]).await.unwrap_or_default();
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+            let class = fields.first()?;
+            let display = class.contains("VGA") || class.contains("3D") || class.contains("Display");
+            display.then(|| {
+                format!("{} {}", fields.get(1).unwrap_or(&""), fields.get(2).unwrap_or(&"")).trim().to_string()
+            })
+        })
+        .collect()
+}
+
+/// The GPUs, looked up on first use.
+async fn gpus() -> Vec<String> {
+    GPUS.get_or_init(find_gpus).await.clone()
+}
+
+/// The disk whose mount point holds `path`.
+fn disk_of(path: &Path) -> Option<DiskInfo> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskInfo {
+            mount_point: disk.mount_point().to_string_lossy().to_string(),
+            total_bytes: disk.total_space(),
+            available_bytes: disk.available_space(),
+        })
+}
+
+/// Everything but the GPUs, which take a process launch to find.
+fn collect(project_path: Option<&Path>) -> SystemInfo {
+    let refresh = RefreshKind::new().with_cpu(CpuRefreshKind::new()).with_memory(MemoryRefreshKind::new().with_ram());
+    let system = System::new_with_specifics(refresh);
+    let brand = system.cpus().first().map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default();
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version: System::long_os_version(),
+        kernel_version: System::kernel_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu: CpuInfo { brand, physical_cores: system.physical_core_count(), logical_cores: system.cpus().len() },
+        memory: MemoryInfo { total_bytes: system.total_memory(), available_bytes: system.available_memory() },
+        gpus: Vec::new(),
+        webview_version: tauri::webview_version().ok(),
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+        engine_version: engines::embedded_version().to_string(),
+        disk: project_path.and_then(disk_of),
+    }
+}
+
+/// Collects the system information; `project_path` picks the drive whose
+/// free space is reported.
+pub async fn system_info(project_path: Option<String>) -> Result<SystemInfo, String> {
+    let mut info = tokio::task::spawn_blocking(move || collect(project_path.as_deref().map(Path::new)))
+        .await
+        .map_err(|e| format!("System info task failed: {}", e))?;
+    info.gpus = gpus().await;
+    Ok(info)
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn get_system_info(project_path: Option<String>) -> Result<SystemInfo, String> {
+    system_info(project_path).await
+}
@@ -0,0 +1,135 @@
+//! Offline docs — the scripting reference and samples without internet
+//!
+//! `build.rs` packs the documentation pages and the example projects'
+//! scripts into the `DOCS` archive. The first time they are needed they are
+//! extracted to `<app data>/docs/<editor version>/`:
+//!
+//! ```text
+//! index.json                      { "pages": [{ path, title }], "samples": [{ path, example }] }
+//! docs/getting-started/*.mdx
+//! samples/<example>/main.ts
+//! ```
+//!
+//! The webview reads them from the `esassets` protocol under `docs/`, e.g.
+//! `esassets://localhost/docs/index.json`.
+
+use crate::embedded_assets;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+const DOCS_DIR: &str = "docs";
+const INDEX_FILE: &str = "index.json";
+
+/// `<app data>/docs/`, set on startup.
+static ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Held while extracting, so concurrent first reads extract once.
+static EXTRACTING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsPage {
+    /// Path under the docs route, e.g. `docs/guides/physics.mdx`.
+    pub path: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsSample {
+    /// Path under the docs route, e.g. `samples/platformer/main.ts`.
+    pub path: String,
+    pub example: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsIndex {
+    pub pages: Vec<DocsPage>,
+    pub samples: Vec<DocsSample>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocsInfo {
+    /// Where the docs were extracted.
+    pub dir: String,
+    pub index: DocsIndex,
+}
+
+/// Records where docs are extracted; called on startup.
+pub fn init(app: &AppHandle) {
+    if let Ok(dir) = app.path().app_data_dir() {
+        let _ = ROOT.set(dir.join(DOCS_DIR));
+    }
+}
+
+// =============================================================================
+// Extraction
+// =============================================================================
+
+/// The docs of this editor version, extracted on first use. Docs of other
+/// versions are removed then.
+fn extract() -> Result<PathBuf, String> {
+    let root = ROOT.get().ok_or("No folder for the offline docs")?;
+    let dir = root.join(env!("CARGO_PKG_VERSION"));
+    let _guard = EXTRACTING.lock().unwrap_or_else(|e| e.into_inner());
+    // Extracted to a staging folder and renamed, so an index means the
+    // extraction completed.
+    if dir.join(INDEX_FILE).is_file() {
+        return Ok(dir);
+    }
+
+    let staging = root.join(format!("{}.partial", env!("CARGO_PKG_VERSION")));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let mut archive = tar::Archive::new(embedded_assets::DOCS.bytes());
+    archive.unpack(&staging).map_err(|e| format!("Failed to extract the docs: {}", e))?;
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::rename(&staging, &dir).map_err(|e| format!("Failed to move the docs into place: {}", e))?;
+
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            if entry.path() != dir {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+    eprintln!("[Docs] Extracted the offline docs to {}", dir.display());
+    Ok(dir)
+}
+
+/// A docs file by its path under the docs route; `None` when there is none.
+pub fn read(path: &str) -> Result<Option<Vec<u8>>, String> {
+    let rel = Path::new(path);
+    if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Ok(None);
+    }
+    let dir = extract()?;
+    Ok(std::fs::read(dir.join(rel)).ok())
+}
+
+pub fn mime(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => "application/json",
+        Some("md" | "mdx") => "text/markdown; charset=utf-8",
+        Some("ts") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Extracts the offline docs when this editor version has not yet, and
+/// lists their pages and samples.
+#[tauri::command]
+pub async fn extract_docs() -> Result<DocsInfo, String> {
+    tokio::task::spawn_blocking(|| {
+        let dir = extract()?;
+        let data = std::fs::read(dir.join(INDEX_FILE)).map_err(|e| format!("Failed to read the docs index: {}", e))?;
+        let index = serde_json::from_slice(&data).map_err(|e| format!("Invalid docs index: {}", e))?;
+        Ok(DocsInfo { dir: dir.to_string_lossy().to_string(), index })
+    })
+    .await
+    .map_err(|e| format!("Docs task failed: {}", e))?
+}
@@ -0,0 +1,270 @@
+//! Editor settings — preferences kept by the backend, not the webview
+//!
+//! Preferences stored in `localStorage` were lost whenever the webview
+//! cache was cleared. They now live in `<app config>/settings.json`, read
+//! and written only through these commands:
+//!
+//! - typed fields for what the backend knows (theme, the design resolution
+//!   of new projects, recent colors, the dock layout, where the main and
+//!   detached panel windows were, color palettes) and `values` for the
+//!   editor's settings registry, keyed by setting id;
+//! - validated before every write, and written atomically;
+//! - every change is broadcast as an `editor-settings-changed` event with
+//!   the label of the window that made it, so other windows follow along;
+//! - `export_editor_settings` / `import_editor_settings` move them between
+//!   machines as a settings bundle.
+
+use crate::export::profiles::merge_json;
+use crate::global_shortcuts;
+use crate::palettes::{self, Palette};
+use crate::project::settings::DesignResolution;
+use crate::project::write_atomic;
+use crate::windows::WindowLayout;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+const SETTINGS_FILE: &str = "settings.json";
+const BUNDLE_FORMAT: &str = "esengine-editor-settings";
+const THEMES: &[&str] = &["dark", "light", "system"];
+const MAX_RECENT_COLORS: usize = 16;
+const MAX_DESIGN_SIZE: f64 = 16384.0;
+
+/// Held across read-modify-write, so concurrent updates from several
+/// windows do not lose each other's changes.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EditorSettings {
+    pub schema_version: u32,
+    /// One of `THEMES`.
+    pub theme: String,
+    /// Design resolution new projects start with.
+    pub design_resolution: DesignResolution,
+    /// Most recent first, as `#rrggbb` or `#rrggbbaa`.
+    pub recent_colors: Vec<String>,
+    /// Named swatch libraries of the color picker (see `palettes`).
+    pub palettes: Vec<Palette>,
+    /// Dock layout, as the dock saves it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Value>,
+    /// Hide the editor in the tray instead of closing it while jobs run.
+    pub close_to_tray: bool,
+    /// The main window, keyed by monitor setup.
+    pub main_window: BTreeMap<String, WindowLayout>,
+    /// Detached panel windows, keyed by panel id.
+    pub windows: BTreeMap<String, WindowLayout>,
+    /// Values of the settings registry, e.g. `scene.showGrid`.
+    pub values: BTreeMap<String, Value>,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            theme: "dark".to_string(),
+            design_resolution: DesignResolution::default(),
+            recent_colors: Vec::new(),
+            palettes: Vec::new(),
+            layout: None,
+            close_to_tray: true,
+            main_window: BTreeMap::new(),
+            windows: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+/// A file written by `export_editor_settings`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsBundle {
+    format: String,
+    editor_version: String,
+    settings: EditorSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SettingsChanged<'a> {
+    settings: &'a EditorSettings,
+    /// Label of the window that made the change.
+    source: Option<String>,
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_config_dir().map_err(|e| e.to_string())?.join(SETTINGS_FILE))
+}
+
+/// The saved settings; defaults when there are none yet.
+pub fn load(app: &AppHandle) -> Result<EditorSettings, String> {
+    let path = settings_path(app)?;
+    match std::fs::read(&path) {
+        Ok(data) => {
+            serde_json::from_slice(&data).map_err(|e| format!("Invalid editor settings {}: {}", path.display(), e))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(EditorSettings::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn save(app: &AppHandle, settings: &EditorSettings, source: Option<&WebviewWindow>) -> Result<(), String> {
+    validate(settings)?;
+    let data = serde_json::to_vec_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(&settings_path(app)?, &data)?;
+    let event = SettingsChanged { settings, source: source.map(|window| window.label().to_string()) };
+    let _ = app.emit("editor-settings-changed", event);
+    Ok(())
+}
+
+fn validate(settings: &EditorSettings) -> Result<(), String> {
+    let mut issues = Vec::new();
+    if settings.schema_version > SCHEMA_VERSION {
+        issues.push(format!(
+            "schemaVersion: {} is newer than this editor supports ({})",
+            settings.schema_version, SCHEMA_VERSION
+        ));
+    }
+    if !THEMES.contains(&settings.theme.as_str()) {
+        issues.push(format!("theme: '{}' is not one of {}", settings.theme, THEMES.join(", ")));
+    }
+    for (field, size) in [
+        ("designResolution.width", settings.design_resolution.width),
+        ("designResolution.height", settings.design_resolution.height),
+    ] {
+        if !(1.0..=MAX_DESIGN_SIZE).contains(&size) {
+            issues.push(format!("{}: must be between 1 and {}, got {}", field, MAX_DESIGN_SIZE, size));
+        }
+    }
+    if settings.recent_colors.len() > MAX_RECENT_COLORS {
+        issues.push(format!("recentColors: at most {} colors", MAX_RECENT_COLORS));
+    }
+    for color in &settings.recent_colors {
+        if !is_hex_color(color) {
+            issues.push(format!("recentColors: '{}' is not a #rrggbb or #rrggbbaa color", color));
+        }
+    }
+    palettes::validate(&settings.palettes, &mut issues);
+    if settings.layout.as_ref().is_some_and(|layout| !layout.is_object()) {
+        issues.push("layout: must be an object".to_string());
+    }
+    let windows = settings.main_window.iter().map(|(setup, window)| ("mainWindow", setup, window));
+    for (field, key, window) in windows.chain(settings.windows.iter().map(|(id, window)| ("windows", id, window))) {
+        if !(window.width > 0.0 && window.height > 0.0) {
+            issues.push(format!("{}.{}: size must be positive", field, key));
+        }
+        if !(window.x.is_finite() && window.y.is_finite()) {
+            issues.push(format!("{}.{}: position must be finite", field, key));
+        }
+    }
+    if settings.values.keys().any(|id| id.trim().is_empty()) {
+        issues.push("values: setting ids must not be empty".to_string());
+    }
+    global_shortcuts::validate(&settings.values, &mut issues);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid editor settings:\n- {}", issues.join("\n- ")))
+    }
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Applies `change` to the saved settings under the write lock.
+pub fn modify(
+    app: &AppHandle,
+    source: Option<&WebviewWindow>,
+    change: impl FnOnce(&mut EditorSettings) -> Result<(), String>,
+) -> Result<EditorSettings, String> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut settings = load(app)?;
+    change(&mut settings)?;
+    settings.schema_version = SCHEMA_VERSION;
+    save(app, &settings, source)?;
+    Ok(settings)
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_editor_settings(app: AppHandle) -> Result<EditorSettings, String> {
+    load(&app)
+}
+
+/// Merges `changes` (a partial settings object) into the saved settings.
+/// Nothing is written when the result fails validation.
+#[tauri::command]
+pub fn update_editor_settings(app: AppHandle, window: WebviewWindow, changes: Value) -> Result<EditorSettings, String> {
+    modify(&app, Some(&window), |settings| {
+        let mut value = serde_json::to_value(&*settings).map_err(|e| e.to_string())?;
+        merge_json(&mut value, &changes);
+        *settings = serde_json::from_value(value).map_err(|e| format!("Invalid editor settings: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Restores the defaults of the given registry `values` ids, or of
+/// everything when `ids` is omitted.
+#[tauri::command]
+pub fn reset_editor_settings(
+    app: AppHandle,
+    window: WebviewWindow,
+    ids: Option<Vec<String>>,
+) -> Result<EditorSettings, String> {
+    modify(&app, Some(&window), |settings| {
+        match ids {
+            Some(ids) => settings.values.retain(|id, _| !ids.contains(id)),
+            None => *settings = EditorSettings::default(),
+        }
+        Ok(())
+    })
+}
+
+/// Writes the settings to `path` as a bundle another editor can import.
+#[tauri::command]
+pub fn export_editor_settings(app: AppHandle, path: String) -> Result<(), String> {
+    let bundle = SettingsBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings: load(&app)?,
+    };
+    let data = serde_json::to_vec_pretty(&bundle).map_err(|e| e.to_string())?;
+    write_atomic(Path::new(&path), &data)
+}
+
+/// Replaces the settings with a bundle from `export_editor_settings`. The
+/// current settings are kept when the bundle is invalid.
+#[tauri::command]
+pub fn import_editor_settings(app: AppHandle, window: WebviewWindow, path: String) -> Result<EditorSettings, String> {
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let value: Map<String, Value> =
+        serde_json::from_slice(&data).map_err(|e| format!("{} is not a settings bundle: {}", path, e))?;
+    if value.get("format").and_then(Value::as_str) != Some(BUNDLE_FORMAT) {
+        return Err(format!("{} is not an editor settings bundle", path));
+    }
+    let bundle: SettingsBundle =
+        serde_json::from_value(Value::Object(value)).map_err(|e| format!("Invalid settings bundle: {}", e))?;
+    modify(&app, Some(&window), |settings| {
+        *settings = bundle.settings;
+        Ok(())
+    })
+}
@@ -1,51 +1,553 @@
 //! Centralized embedded assets for the editor binary
+//!
+//! The files the frontend loads by name are listed once in `ASSETS`;
+//! `get_embedded_asset` serves any of them and `list_embedded_assets`
+//! describes them all, so a new embedded file needs one entry there and no
+//! new command.
+//! `get_asset_manifest` lists every embedded file with its size, SHA-256
+//! and the version of the build it belongs to, for bug reports.
+//! `get_engine_changes` compares them with the previous editor version's,
+//! recorded in app data, so an update can say whether the engine changed.
+//!
+//! The webview fetches them from the `esassets` protocol
+//! (`esassets://localhost/engine.wasm`, `http://esassets.localhost/...` on
+//! Windows), whose responses borrow the bytes in the binary instead of
+//! copying them through IPC, and which the webview may cache by `ETag`.
+//! Requests are answered on a blocking task, off the webview's thread.
+//! `get_embedded_asset` returns raw bytes for callers that use `invoke`.
+//!
+//! While a local engine build is set (see `engines`), its copies of these
+//! files are served instead. The protocol also serves the offline docs
+//! under `docs/` (see `docs`).
+//!
+//! `build.rs` stores the files zstd-compressed; each is decompressed the
+//! first time it is used and kept in memory from then on.
+
+use crate::docs;
+use crate::engines;
+use crate::project::write_atomic;
+use crate::runtimes;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{ipc, AppHandle, Manager};
+
+/// Scheme of the protocol serving `ASSETS`.
+pub const PROTOCOL: &str = "esassets";
+
+/// A file `build.rs` compressed into the binary: its size as 8
+/// little-endian bytes, then a zstd frame.
+#[derive(Clone, Copy)]
+pub struct Embedded {
+    /// Its name in `build.rs`; consts may be copied, so this, not the
+    /// address of the data, identifies it.
+    name: &'static str,
+    /// Where the file is in an engine bundle, laid out like `public/`.
+    path: &'static str,
+    data: &'static [u8],
+}
+
+macro_rules! embed {
+    ($name:literal, $path:literal) => {
+        Embedded {
+            name: $name,
+            path: $path,
+            data: include_bytes!(concat!(env!("OUT_DIR"), "/embedded/", $name, ".zst")),
+        }
+    };
+}
+
+impl Embedded {
+    pub fn path(self) -> &'static str {
+        self.path
+    }
+
+    pub fn size(self) -> usize {
+        let mut size = [0u8; 8];
+        size.copy_from_slice(&self.data[..8]);
+        u64::from_le_bytes(size) as usize
+    }
+
+    pub fn compressed_size(self) -> usize {
+        self.data.len() - 8
+    }
+
+    /// The contents, decompressed on first use.
+    pub fn bytes(self) -> &'static [u8] {
+        static CACHE: OnceLock<Mutex<HashMap<&'static str, &'static [u8]>>> = OnceLock::new();
+        let mut cache = CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        // Held while decompressing, so concurrent first uses do it once.
+        cache.entry(self.name).or_insert_with(|| {
+            let bytes = zstd::bulk::decompress(&self.data[8..], self.size())
+                .unwrap_or_else(|e| panic!("embedded asset {} is corrupt: {}", self.name, e));
+            // Kept for the life of the editor, like the data it came from.
+            Box::leak(bytes.into_boxed_slice())
+        })
+    }
+
+    /// SHA-256 of the contents, hex encoded; computed once.
+    pub fn sha256(self) -> &'static str {
+        static CACHE: OnceLock<Mutex<HashMap<&'static str, &'static str>>> = OnceLock::new();
+        if let Some(hash) = CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner()).get(self.name) {
+            return hash;
+        }
+        // Hashed unlocked: a first use of a large file need not hold up others.
+        let hash: &'static str = Box::leak(format!("{:x}", Sha256::digest(self.bytes())).into_boxed_str());
+        CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner()).entry(self.name).or_insert(hash)
+    }
+}
 
 // =============================================================================
 // WASM Modules
 // =============================================================================
 
 // Editor runtime only — build assets are compiled dynamically via toolchain
-pub const ENGINE_JS: &[u8] = include_bytes!("../../public/wasm/esengine.js");
-pub const ENGINE_WASM: &[u8] = include_bytes!("../../public/wasm/esengine.wasm");
+pub const ENGINE_JS: Embedded = embed!("ENGINE_JS", "wasm/esengine.js");
+pub const ENGINE_WASM: Embedded = embed!("ENGINE_WASM", "wasm/esengine.wasm");
 
 // =============================================================================
 // ESBuild
 // =============================================================================
 
-pub const ESBUILD_WASM: &[u8] = include_bytes!("../../public/esbuild.wasm");
+pub const ESBUILD_WASM: Embedded = embed!("ESBUILD_WASM", "esbuild.wasm");
 
 // =============================================================================
 // SDK
 // =============================================================================
 
-pub const SDK_ESM_JS: &[u8] = include_bytes!("../../public/sdk/esm/esengine.bundled.js");
-pub const SDK_ESM_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/esengine.bundled.js.map");
-pub const SDK_ESM_DTS: &[u8] = include_bytes!("../../public/sdk/esm/esengine.d.ts");
-pub const SDK_WASM_JS: &[u8] = include_bytes!("../../public/sdk/esm/wasm.js");
-pub const SDK_WASM_DTS: &[u8] = include_bytes!("../../public/sdk/esm/wasm.d.ts");
-pub const SDK_WASM_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/wasm.js.map");
-pub const SDK_SPINE_JS: &[u8] = include_bytes!("../../public/sdk/esm/spine/index.js");
-pub const SDK_SPINE_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/spine/index.js.map");
-pub const SDK_SHARED_INDEX_JS: &[u8] = include_bytes!("../../public/sdk/esm/shared/index.js");
-pub const SDK_SHARED_INDEX_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/shared/index.js.map");
-pub const SDK_SHARED_MATERIAL_JS: &[u8] = include_bytes!("../../public/sdk/esm/shared/material.js");
-pub const SDK_SHARED_MATERIAL_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/shared/material.js.map");
-pub const SDK_SHARED_SPINEMODULELOADER_JS: &[u8] = include_bytes!("../../public/sdk/esm/shared/SpineModuleLoader.js");
-pub const SDK_SHARED_SPINEMODULELOADER_JS_MAP: &[u8] = include_bytes!("../../public/sdk/esm/shared/SpineModuleLoader.js.map");
-pub const SDK_SHARED_WASM_DTS: &[u8] = include_bytes!("../../public/sdk/esm/shared/wasm.d.ts");
-pub const SDK_SHARED_APP_DTS: &[u8] = include_bytes!("../../public/sdk/esm/shared/app.d.ts");
-pub const SDK_PHYSICS_DTS: &[u8] = include_bytes!("../../public/sdk/esm/physics/index.d.ts");
-pub const SDK_SPINE_DTS: &[u8] = include_bytes!("../../public/sdk/esm/spine/index.d.ts");
-pub const SDK_WECHAT_JS: &[u8] = include_bytes!("../../public/sdk/cjs/esengine.wechat.js");
+pub const SDK_ESM_JS: Embedded = embed!("SDK_ESM_JS", "sdk/esm/esengine.bundled.js");
+pub const SDK_ESM_JS_MAP: Embedded = embed!("SDK_ESM_JS_MAP", "sdk/esm/esengine.bundled.js.map");
+pub const SDK_ESM_DTS: Embedded = embed!("SDK_ESM_DTS", "sdk/esm/esengine.d.ts");
+pub const SDK_WASM_JS: Embedded = embed!("SDK_WASM_JS", "sdk/esm/wasm.js");
+pub const SDK_WASM_DTS: Embedded = embed!("SDK_WASM_DTS", "sdk/esm/wasm.d.ts");
+pub const SDK_WASM_JS_MAP: Embedded = embed!("SDK_WASM_JS_MAP", "sdk/esm/wasm.js.map");
+pub const SDK_SPINE_JS: Embedded = embed!("SDK_SPINE_JS", "sdk/esm/spine/index.js");
+pub const SDK_SPINE_JS_MAP: Embedded = embed!("SDK_SPINE_JS_MAP", "sdk/esm/spine/index.js.map");
+pub const SDK_SHARED_INDEX_JS: Embedded = embed!("SDK_SHARED_INDEX_JS", "sdk/esm/shared/index.js");
+pub const SDK_SHARED_INDEX_JS_MAP: Embedded = embed!("SDK_SHARED_INDEX_JS_MAP", "sdk/esm/shared/index.js.map");
+pub const SDK_SHARED_MATERIAL_JS: Embedded = embed!("SDK_SHARED_MATERIAL_JS", "sdk/esm/shared/material.js");
+pub const SDK_SHARED_MATERIAL_JS_MAP: Embedded = embed!("SDK_SHARED_MATERIAL_JS_MAP", "sdk/esm/shared/material.js.map");
+pub const SDK_SHARED_SPINEMODULELOADER_JS: Embedded = embed!("SDK_SHARED_SPINEMODULELOADER_JS", "sdk/esm/shared/SpineModuleLoader.js");
+pub const SDK_SHARED_SPINEMODULELOADER_JS_MAP: Embedded = embed!("SDK_SHARED_SPINEMODULELOADER_JS_MAP", "sdk/esm/shared/SpineModuleLoader.js.map");
+pub const SDK_SHARED_WASM_DTS: Embedded = embed!("SDK_SHARED_WASM_DTS", "sdk/esm/shared/wasm.d.ts");
+pub const SDK_SHARED_APP_DTS: Embedded = embed!("SDK_SHARED_APP_DTS", "sdk/esm/shared/app.d.ts");
+pub const SDK_PHYSICS_DTS: Embedded = embed!("SDK_PHYSICS_DTS", "sdk/esm/physics/index.d.ts");
+pub const SDK_SPINE_DTS: Embedded = embed!("SDK_SPINE_DTS", "sdk/esm/spine/index.d.ts");
+pub const SDK_WECHAT_JS: Embedded = embed!("SDK_WECHAT_JS", "sdk/cjs/esengine.wechat.js");
+pub const SDK_PACKAGE_JSON: &str = include_str!("../../../sdk/package.json");
 
 // =============================================================================
 // Editor Types
 // =============================================================================
 
-pub const EDITOR_DTS: &[u8] = include_bytes!("../../../editor/dist/index.d.ts");
+pub const EDITOR_DTS: Embedded = embed!("EDITOR_DTS", "editor/index.d.ts");
+/// Scripting reference and samples as a tar archive; see `docs`.
+pub const DOCS: Embedded = embed!("DOCS", "docs.tar");
+
+// =============================================================================
+// Optional runtimes (see `runtimes`)
+// =============================================================================
+
+#[cfg(feature = "spine38")]
+pub const SPINE38_JS: Embedded = embed!("SPINE38_JS", "wasm/spine38.js");
+#[cfg(feature = "spine38")]
+pub const SPINE38_WASM: Embedded = embed!("SPINE38_WASM", "wasm/spine38.wasm");
+#[cfg(feature = "spine41")]
+pub const SPINE41_JS: Embedded = embed!("SPINE41_JS", "wasm/spine41.js");
+#[cfg(feature = "spine41")]
+pub const SPINE41_WASM: Embedded = embed!("SPINE41_WASM", "wasm/spine41.wasm");
+#[cfg(feature = "spine42")]
+pub const SPINE42_JS: Embedded = embed!("SPINE42_JS", "wasm/spine42.js");
+#[cfg(feature = "spine42")]
+pub const SPINE42_WASM: Embedded = embed!("SPINE42_WASM", "wasm/spine42.wasm");
+#[cfg(feature = "physics")]
+pub const PHYSICS_JS: Embedded = embed!("PHYSICS_JS", "wasm/physics.js");
+#[cfg(feature = "physics")]
+pub const PHYSICS_WASM: Embedded = embed!("PHYSICS_WASM", "wasm/physics.wasm");
+#[cfg(feature = "rapier")]
+pub const RAPIER_JS: Embedded = embed!("RAPIER_JS", "wasm/physics-rapier.js");
+#[cfg(feature = "rapier")]
+pub const RAPIER_WASM: Embedded = embed!("RAPIER_WASM", "wasm/physics-rapier.wasm");
 
 // =============================================================================
 // Preview HTML
 // =============================================================================
 
 pub const PREVIEW_HTML: &str = include_str!("preview_template.html");
+
+/// Every embedded file, in `build.rs` order.
+pub const ALL: &[Embedded] = &[
+    ENGINE_JS,
+    ENGINE_WASM,
+    ESBUILD_WASM,
+    SDK_ESM_JS,
+    SDK_ESM_JS_MAP,
+    SDK_ESM_DTS,
+    SDK_WASM_JS,
+    SDK_WASM_DTS,
+    SDK_WASM_JS_MAP,
+    SDK_SPINE_JS,
+    SDK_SPINE_JS_MAP,
+    SDK_SHARED_INDEX_JS,
+    SDK_SHARED_INDEX_JS_MAP,
+    SDK_SHARED_MATERIAL_JS,
+    SDK_SHARED_MATERIAL_JS_MAP,
+    SDK_SHARED_SPINEMODULELOADER_JS,
+    SDK_SHARED_SPINEMODULELOADER_JS_MAP,
+    SDK_SHARED_WASM_DTS,
+    SDK_SHARED_APP_DTS,
+    SDK_PHYSICS_DTS,
+    SDK_SPINE_DTS,
+    SDK_WECHAT_JS,
+    EDITOR_DTS,
+    DOCS,
+];
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+pub struct EmbeddedAsset {
+    pub name: &'static str,
+    pub file: Embedded,
+    pub mime: &'static str,
+}
+
+const JS: &str = "text/javascript";
+const WASM: &str = "application/wasm";
+const DTS: &str = "text/plain";
+
+/// Assets the frontend requests by name.
+pub const ASSETS: &[EmbeddedAsset] = &[
+    // Editor runtime
+    EmbeddedAsset { name: "engine.js", file: ENGINE_JS, mime: JS },
+    EmbeddedAsset { name: "engine.wasm", file: ENGINE_WASM, mime: WASM },
+    // SDK
+    EmbeddedAsset { name: "sdk.wechat.js", file: SDK_WECHAT_JS, mime: JS },
+    EmbeddedAsset { name: "sdk.esm.js", file: SDK_ESM_JS, mime: JS },
+    EmbeddedAsset { name: "sdk.esm.dts", file: SDK_ESM_DTS, mime: DTS },
+    EmbeddedAsset { name: "sdk.wasm.js", file: SDK_WASM_JS, mime: JS },
+    EmbeddedAsset { name: "sdk.wasm.dts", file: SDK_WASM_DTS, mime: DTS },
+    EmbeddedAsset { name: "sdk.shared.wasm.dts", file: SDK_SHARED_WASM_DTS, mime: DTS },
+    EmbeddedAsset { name: "sdk.shared.app.dts", file: SDK_SHARED_APP_DTS, mime: DTS },
+    EmbeddedAsset { name: "sdk.physics.dts", file: SDK_PHYSICS_DTS, mime: DTS },
+    EmbeddedAsset { name: "sdk.spine.dts", file: SDK_SPINE_DTS, mime: DTS },
+    EmbeddedAsset { name: "editor.dts", file: EDITOR_DTS, mime: DTS },
+    EmbeddedAsset { name: "esbuild.wasm", file: ESBUILD_WASM, mime: WASM },
+];
+
+/// Build assets served from public/wasm/ via HTTP, not embedded.
+const SERVED_FROM_PUBLIC: &[&str] = &["engine.single.js", "engine.wxgame.js", "engine.wxgame.wasm"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedAssetInfo {
+    pub name: String,
+    pub size: usize,
+    /// Bytes it takes up in the editor binary.
+    pub compressed_size: usize,
+    pub mime: String,
+    /// SHA-256 of the contents, hex encoded.
+    pub hash: String,
+}
+
+/// What the editor ships, for bug reports: its versions and every embedded
+/// file as it is actually used.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetManifest {
+    pub editor_version: String,
+    /// Version of the embedded engine and SDK.
+    pub engine_version: String,
+    /// Local engine build whose files replace embedded ones, if any.
+    pub engine_dev_dir: Option<String>,
+    pub assets: Vec<AssetManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetManifestEntry {
+    /// Path in an engine bundle, e.g. `wasm/esengine.wasm`.
+    pub name: String,
+    pub size: usize,
+    /// SHA-256 of the contents, hex encoded.
+    pub sha256: String,
+    /// Version of the build the file comes from; `None` for third-party
+    /// files such as esbuild.
+    pub version: Option<String>,
+    /// Replaced by the local engine build.
+    pub local: bool,
+}
+
+pub fn find(name: &str) -> Result<&'static EmbeddedAsset, String> {
+    if let Some(asset) = ASSETS.iter().find(|asset| asset.name == name) {
+        return Ok(asset);
+    }
+    if SERVED_FROM_PUBLIC.contains(&name) {
+        return Err(format!("Asset '{}' is served from public/wasm/, not embedded.", name));
+    }
+    if runtimes::file(name).is_some() {
+        return Err(format!("Asset '{}' is an optional runtime; load it with get_runtime_file.", name));
+    }
+    Err(format!("Unknown embedded asset: {}", name))
+}
+
+/// Every registered asset, in `ASSETS` order; hashed once, on first use,
+/// which decompresses them all.
+pub fn infos() -> &'static [EmbeddedAssetInfo] {
+    static INFOS: OnceLock<Vec<EmbeddedAssetInfo>> = OnceLock::new();
+    INFOS.get_or_init(|| {
+        ASSETS
+            .iter()
+            .map(|asset| EmbeddedAssetInfo {
+                name: asset.name.to_string(),
+                size: asset.file.size(),
+                compressed_size: asset.file.compressed_size(),
+                mime: asset.mime.to_string(),
+                hash: asset.file.sha256().to_string(),
+            })
+            .collect()
+    })
+}
+
+fn manifest() -> AssetManifest {
+    let editor_version = env!("CARGO_PKG_VERSION");
+    let engine_version = engines::embedded_version();
+    let assets = ALL
+        .iter()
+        .map(|file| {
+            let version = match file.path().split('/').next() {
+                Some("wasm" | "sdk") => Some(engine_version.to_string()),
+                Some("editor") => Some(editor_version.to_string()),
+                _ => None,
+            };
+            let (size, sha256, local) = match engines::dev_file(file.path()) {
+                Some(data) => (data.len(), format!("{:x}", Sha256::digest(&data)), true),
+                None => (file.size(), file.sha256().to_string(), false),
+            };
+            AssetManifestEntry { name: file.path().to_string(), size, sha256, version, local }
+        })
+        .collect();
+    AssetManifest {
+        editor_version: editor_version.to_string(),
+        engine_version: engine_version.to_string(),
+        engine_dev_dir: engines::dev_dir().map(|dir| dir.to_string_lossy().to_string()),
+        assets,
+    }
+}
+
+// =============================================================================
+// Changes between editor versions
+// =============================================================================
+
+/// `<app data>/asset-history.json`: the embedded files of the running editor
+/// and of the one before it.
+const HISTORY_FILE: &str = "asset-history.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetHistory {
+    current: Option<AssetSnapshot>,
+    previous: Option<AssetSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetSnapshot {
+    editor_version: String,
+    engine_version: String,
+    /// SHA-256 of each embedded file, by its bundle path.
+    assets: BTreeMap<String, String>,
+}
+
+/// What an editor update changed in the embedded engine, SDK and typings.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineChanges {
+    /// `None` on the first run, when there is nothing to compare with.
+    pub from_editor: Option<String>,
+    pub to_editor: String,
+    pub from_engine: Option<String>,
+    pub to_engine: String,
+    /// The engine runtime (`wasm/`) differs, so games behave differently.
+    pub runtime_changed: bool,
+    /// The SDK (`sdk/`) differs.
+    pub sdk_changed: bool,
+    /// Bundle paths, e.g. `wasm/esengine.wasm`.
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+fn snapshot() -> AssetSnapshot {
+    AssetSnapshot {
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+        engine_version: engines::embedded_version().to_string(),
+        assets: ALL.iter().map(|file| (file.path().to_string(), file.sha256().to_string())).collect(),
+    }
+}
+
+/// Compares the embedded files with those of the previous editor version,
+/// recording the running one the first time it is seen. Hashing decompresses
+/// every file, so this runs only when asked.
+fn engine_changes(app_data: &Path) -> Result<EngineChanges, String> {
+    let path = app_data.join(HISTORY_FILE);
+    let mut history: AssetHistory =
+        std::fs::read(&path).ok().and_then(|data| serde_json::from_slice(&data).ok()).unwrap_or_default();
+    let editor_version = env!("CARGO_PKG_VERSION");
+    if history.current.as_ref().map(|current| current.editor_version.as_str()) != Some(editor_version) {
+        history.previous = history.current.take();
+        history.current = Some(snapshot());
+        let data = serde_json::to_vec_pretty(&history).map_err(|e| e.to_string())?;
+        write_atomic(&path, &data)?;
+    }
+    let current = history.current.unwrap_or_else(snapshot);
+    let previous = history.previous;
+
+    let empty = BTreeMap::new();
+    let before = previous.as_ref().map_or(&empty, |previous| &previous.assets);
+    let added: Vec<String> = current.assets.keys().filter(|name| !before.contains_key(*name)).cloned().collect();
+    let removed: Vec<String> = before.keys().filter(|name| !current.assets.contains_key(*name)).cloned().collect();
+    let changed: Vec<String> = current
+        .assets
+        .iter()
+        .filter(|(name, hash)| before.get(*name).is_some_and(|old| old != *hash))
+        .map(|(name, _)| name.clone())
+        .collect();
+    // On the first run every file is "added"; that is not an update.
+    let (added, removed) = if previous.is_some() { (added, removed) } else { (Vec::new(), Vec::new()) };
+    let touched = |prefix: &str| added.iter().chain(&removed).chain(&changed).any(|name| name.starts_with(prefix));
+    Ok(EngineChanges {
+        runtime_changed: touched("wasm/"),
+        sdk_changed: touched("sdk/"),
+        from_editor: previous.as_ref().map(|previous| previous.editor_version.clone()),
+        to_editor: current.editor_version,
+        from_engine: previous.map(|previous| previous.engine_version),
+        to_engine: current.engine_version,
+        added,
+        removed,
+        changed,
+    })
+}
+
+// =============================================================================
+// Protocol
+// =============================================================================
+
+fn mime_of(name: &str) -> &'static str {
+    if name.ends_with(".wasm") {
+        WASM
+    } else {
+        JS
+    }
+}
+
+/// Answers an `esassets` request for `/<name>`.
+pub fn serve(request: &Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let path = request.uri().path().trim_start_matches('/');
+    let name = urlencoding::decode(path).map(|name| name.into_owned()).unwrap_or_else(|_| path.to_string());
+    let response = Response::builder().header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    let not_found = |message: String| {
+        Response::builder()
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .status(StatusCode::NOT_FOUND)
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Cow::Owned(message.into_bytes()))
+            .unwrap_or_default()
+    };
+
+    // A local engine build replaces the asset, and downloaded runtimes are
+    // files too; their contents may change, so their tag is computed every
+    // time.
+    let tagged = |data: Vec<u8>| {
+        let etag = format!("\"{:x}\"", Sha256::digest(&data));
+        (Cow::Owned(data), etag)
+    };
+    // Offline docs, extracted on first use.
+    if let Some(path) = name.strip_prefix("docs/") {
+        return match docs::read(path) {
+            Ok(Some(data)) => response
+                .header(header::CONTENT_TYPE, docs::mime(path))
+                .header(header::CONTENT_LENGTH, data.len())
+                .body(Cow::Owned(data))
+                .unwrap_or_default(),
+            Ok(None) => not_found(format!("No docs file {}", path)),
+            Err(e) => not_found(e),
+        };
+    }
+    let (data, etag, mime): (Cow<'static, [u8]>, String, &str) = match ASSETS.iter().find(|asset| asset.name == name) {
+        Some(asset) => {
+            let (data, etag) = match engines::dev_file(asset.file.path()) {
+                Some(data) => tagged(data),
+                None => (Cow::Borrowed(asset.file.bytes()), format!("\"{}\"", asset.file.sha256())),
+            };
+            (data, etag, asset.mime)
+        }
+        // Runtimes the editor was built without are downloaded with
+        // `get_runtime_file` or `download_runtime` before they are served.
+        None if runtimes::file(&name).is_some() => match runtimes::read(&name) {
+            Ok(Some(Cow::Borrowed(data))) => (Cow::Borrowed(data), format!("\"{:x}\"", Sha256::digest(data)), mime_of(&name)),
+            Ok(Some(Cow::Owned(data))) => {
+                let (data, etag) = tagged(data);
+                (data, etag, mime_of(&name))
+            }
+            Ok(None) => return not_found(format!("Runtime file {} is not downloaded yet.", name)),
+            Err(e) => return not_found(e),
+        },
+        None => return not_found(find(&name).err().unwrap_or_default()),
+    };
+    let response = response.header(header::ETAG, &etag).header(header::CACHE_CONTROL, "no-cache");
+    let cached = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if cached {
+        return response.status(StatusCode::NOT_MODIFIED).body(Cow::Borrowed(&[][..])).unwrap_or_default();
+    }
+    response
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::CONTENT_LENGTH, data.len())
+        .body(data)
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// The asset's bytes as a binary IPC response, not a JSON number array;
+/// the local engine build's copy when there is one.
+#[tauri::command]
+pub fn get_embedded_asset(name: String) -> Result<ipc::Response, String> {
+    let asset = find(&name)?;
+    let data = engines::dev_file(asset.file.path()).unwrap_or_else(|| asset.file.bytes().to_vec());
+    Ok(ipc::Response::new(data))
+}
+
+#[tauri::command]
+pub async fn list_embedded_assets() -> Result<Vec<EmbeddedAssetInfo>, String> {
+    tokio::task::spawn_blocking(|| infos().to_vec())
+        .await
+        .map_err(|e| format!("Asset listing task failed: {}", e))
+}
+
+/// Versions and hashes of every embedded file. The first call hashes them
+/// all, which takes a moment.
+#[tauri::command]
+pub async fn get_asset_manifest() -> Result<AssetManifest, String> {
+    tokio::task::spawn_blocking(manifest)
+        .await
+        .map_err(|e| format!("Asset manifest task failed: {}", e))
+}
+
+/// What changed in the embedded engine since the previous editor version,
+/// for a "what's new" dialog after an update.
+#[tauri::command]
+pub async fn get_engine_changes(app: AppHandle) -> Result<EngineChanges, String> {
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || engine_changes(&app_data))
+        .await
+        .map_err(|e| format!("Engine changes task failed: {}", e))?
+}
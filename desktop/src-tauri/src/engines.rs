@@ -0,0 +1,557 @@
+//! Engine versions — engine and SDK builds besides the embedded one
+//!
+//! A project pins the engine it runs on with `engineVersion` in its settings;
+//! without it, it uses the engine built into the editor. Other versions are
+//! installed as bundles into `<app data>/engines/<version>/`, laid out like
+//! the editor's `public/` folder (`wasm/esengine.js`, `sdk/esm/...`,
+//! `sdk/cjs/esengine.wechat.js`) next to an `engine.json` recording where the
+//! bundle came from.
+//!
+//! `install_engine_version` downloads a bundle (a zip with one top-level
+//! folder), checks its SHA-256 and unpacks it. The preview server, the
+//! exporter and the project SDK files then read a pinned project's engine
+//! and SDK from there instead of the embedded copies.
+//!
+//! Engine contributors can point the editor at a local engine build with
+//! `set_engine_dev_dir`: a `public/`-style folder or the engine repo's
+//! `build/` output. While it is set, every engine and SDK file it contains
+//! replaces the embedded or installed copy, in the editor, the preview
+//! server and exports alike. The folder is watched; after a rebuild, open
+//! previews reload and the editor gets an `engine-dev-changed` event.
+//!
+//! Headless builds have no app data folder; `ESENGINE_ENGINES_DIR` points
+//! them, or the editor, at another folder of installed versions.
+
+use crate::embedded_assets::{self, Embedded};
+use crate::project::settings::ProjectSettings;
+use crate::project::{now_iso8601, write_atomic};
+use futures_util::StreamExt;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const ENGINES_DIR: &str = "engines";
+const MANIFEST_FILE: &str = "engine.json";
+const DIR_ENV: &str = "ESENGINE_ENGINES_DIR";
+const RELEASE_BASE: &str = "https://github.com/esengine/estella/releases/download";
+const DEV_CONFIG_FILE: &str = "engine-dev.json";
+/// A rebuild writes several files; changes closer together reload once.
+const DEV_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Files a bundle needs to run a project at all.
+const REQUIRED_FILES: &[Embedded] =
+    &[embedded_assets::ENGINE_JS, embedded_assets::ENGINE_WASM, embedded_assets::SDK_ESM_JS];
+
+/// The app data folder's `engines/`, set on startup.
+static INSTALL_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// The local engine build in use, if any.
+static DEV_OVERRIDE: Mutex<Option<DevOverride>> = Mutex::new(None);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// `engine.json` of an installed bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    version: String,
+    url: String,
+    sha256: String,
+    installed: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineVersionInfo {
+    pub version: String,
+    /// Built into the editor; cannot be removed.
+    pub embedded: bool,
+    pub path: Option<String>,
+    pub url: Option<String>,
+    pub sha256: Option<String>,
+    pub installed: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct InstallProgress {
+    version: String,
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+/// `engine-dev.json` in the app data folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevConfig {
+    #[serde(default)]
+    dir: Option<String>,
+}
+
+struct DevOverride {
+    dir: PathBuf,
+    /// Dropping it ends the reload thread.
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Clone, Serialize)]
+struct DevChanged {
+    dir: String,
+    paths: Vec<String>,
+}
+
+/// The engine a project runs on.
+#[derive(Debug, Clone)]
+pub enum Engine {
+    Embedded,
+    Installed { version: String, dir: PathBuf },
+}
+
+// =============================================================================
+// Selection
+// =============================================================================
+
+/// Records where installed versions live and resumes watching the local
+/// engine build; called on startup.
+pub fn init(app: &AppHandle) {
+    if let Ok(dir) = app.path().app_data_dir() {
+        let _ = INSTALL_DIR.set(dir.join(ENGINES_DIR));
+    }
+    if let Some(dir) = load_dev_config(app).dir {
+        if let Err(e) = watch_dev_dir(app, PathBuf::from(&dir)) {
+            eprintln!("[Engines] Not using the local engine build in {}: {}", dir, e);
+        }
+    }
+}
+
+fn engines_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = std::env::var_os(DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    INSTALL_DIR
+        .get()
+        .cloned()
+        .ok_or_else(|| format!("No folder for engine versions; set {}", DIR_ENV))
+}
+
+/// Version of the engine built into the editor, from the SDK's package.json.
+pub fn embedded_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        serde_json::from_str::<Value>(embedded_assets::SDK_PACKAGE_JSON)
+            .ok()
+            .and_then(|pkg| pkg["version"].as_str().map(String::from))
+            .unwrap_or_else(|| "0.0.0".to_string())
+    })
+}
+
+/// Checks that `version` is a semantic version, which also keeps it a safe
+/// folder name.
+pub fn parse_version(version: &str) -> Result<String, String> {
+    let version = version.trim().trim_start_matches('v');
+    semver::Version::parse(version)
+        .map(|v| v.to_string())
+        .map_err(|e| format!("'{}' is not an engine version: {}", version, e))
+}
+
+fn version_dir(version: &str) -> Result<PathBuf, String> {
+    Ok(engines_dir()?.join(parse_version(version)?))
+}
+
+impl Engine {
+    /// The engine `project_dir` selects in its settings.
+    pub fn for_project(project_dir: &Path) -> Result<Self, String> {
+        let (settings, _) = ProjectSettings::load(project_dir)?;
+        match settings.engine_version.as_deref() {
+            Some(version) => Self::version(version),
+            None => Ok(Engine::Embedded),
+        }
+    }
+
+    /// The installed `version`; the embedded engine when it is that version.
+    pub fn version(version: &str) -> Result<Self, String> {
+        let version = parse_version(version)?;
+        if version == embedded_version() {
+            return Ok(Engine::Embedded);
+        }
+        let dir = version_dir(&version)?;
+        if !dir.join(MANIFEST_FILE).is_file() {
+            return Err(format!("Engine {} is not installed; install it from the engine versions", version));
+        }
+        Ok(Engine::Installed { version, dir })
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Engine::Embedded => embedded_version(),
+            Engine::Installed { version, .. } => version,
+        }
+    }
+
+    /// This engine's copy of an embedded file, or the local engine build's.
+    pub fn read(&self, file: Embedded) -> Result<Cow<'static, [u8]>, String> {
+        if let Some(data) = dev_file(file.path()) {
+            return Ok(Cow::Owned(data));
+        }
+        match self {
+            Engine::Embedded => Ok(Cow::Borrowed(file.bytes())),
+            Engine::Installed { version, dir } => std::fs::read(dir.join(file.path()))
+                .map(Cow::Owned)
+                .map_err(|e| format!("Engine {} has no {}: {}", version, file.path(), e)),
+        }
+    }
+}
+
+// =============================================================================
+// Local engine build
+// =============================================================================
+
+/// The local engine build folder, when one is set.
+pub fn dev_dir() -> Option<PathBuf> {
+    DEV_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|dev| dev.dir.clone())
+}
+
+/// Where a bundle file may be in a local build: the `public/` layout, or
+/// the engine repo's `build/` output, which keeps web builds in `wasm/web/`.
+fn dev_candidates(path: &str) -> impl Iterator<Item = String> + '_ {
+    let build_layout = path.strip_prefix("wasm/").map(|rest| format!("wasm/web/{}", rest));
+    std::iter::once(path.to_string()).chain(build_layout)
+}
+
+/// The local engine build's copy of the bundle file at `path`.
+pub fn dev_file(path: &str) -> Option<Vec<u8>> {
+    let dir = dev_dir()?;
+    dev_candidates(path).find_map(|rel| std::fs::read(dir.join(rel)).ok())
+}
+
+fn dev_config_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(DEV_CONFIG_FILE)
+}
+
+fn load_dev_config(app: &AppHandle) -> DevConfig {
+    std::fs::read_to_string(dev_config_path(app))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_dev_config(app: &AppHandle, config: &DevConfig) -> Result<(), String> {
+    let path = dev_config_path(app);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_vec_pretty(config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &json)
+}
+
+/// Makes `dir` the local engine build and reloads previews when it changes.
+fn watch_dev_dir(app: &AppHandle, dir: PathBuf) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("Folder not found: {}", dir.display()));
+    }
+    let engine_js = embedded_assets::ENGINE_JS.path();
+    if !dev_candidates(engine_js).any(|rel| dir.join(rel).is_file()) {
+        return Err(format!("No engine build in {}: {} is missing", dir.display(), engine_js));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            let _ = tx.send(event);
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher.watch(&dir, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+
+    let (app, watched) = (app.clone(), dir.clone());
+    std::thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            let mut paths: Vec<PathBuf> = Vec::new();
+            let mut add = |event: notify::Event| {
+                if !event.kind.is_access() {
+                    paths.extend(event.paths);
+                }
+            };
+            add(event);
+            loop {
+                match rx.recv_timeout(DEV_DEBOUNCE) {
+                    Ok(event) => add(event),
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if !paths.is_empty() {
+                dev_changed(&app, &watched, paths);
+            }
+        }
+    });
+
+    // Replacing an earlier folder drops its watcher, which ends its thread.
+    *DEV_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = Some(DevOverride { dir: dir.clone(), _watcher: watcher });
+    eprintln!("[Engines] Using the local engine build in {}", dir.display());
+    Ok(())
+}
+
+fn dev_changed(app: &AppHandle, dir: &Path, mut paths: Vec<PathBuf>) {
+    paths.sort();
+    paths.dedup();
+    eprintln!("[Engines] Local engine build changed ({} files); reloading previews", paths.len());
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        let servers = state.preview_servers.lock().unwrap_or_else(|e| e.into_inner());
+        for server in servers.values() {
+            server.notify_reload();
+        }
+    }
+    let event = DevChanged {
+        dir: dir.to_string_lossy().to_string(),
+        paths: paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+    };
+    let _ = app.emit("engine-dev-changed", event);
+}
+
+// =============================================================================
+// Installing
+// =============================================================================
+
+fn emit_progress(app: &AppHandle, version: &str, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "engine-progress",
+        InstallProgress {
+            version: version.to_string(),
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
+
+/// Where the release of engine `version` publishes `file`.
+pub(crate) fn release_url(version: &str, file: &str) -> String {
+    format!("{}/v{}/{}", RELEASE_BASE, version, file)
+}
+
+/// The SHA-256 published next to a download, as `<url>.sha256`.
+pub(crate) async fn published_checksum(url: &str) -> Result<String, String> {
+    let url = format!("{}.sha256", url);
+    let response = reqwest::get(&url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("No checksum published at {}: HTTP {}", url, response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("Download failed: {}", e))?;
+    text.split_whitespace()
+        .next()
+        .map(str::to_ascii_lowercase)
+        .ok_or_else(|| format!("Empty checksum file at {}", url))
+}
+
+async fn download_bundle(app: &AppHandle, version: &str, url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {}", response.status()));
+    }
+
+    let total_size = response.content_length().unwrap_or(0);
+    let mut data = Vec::with_capacity(total_size as usize);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        data.extend_from_slice(&chunk);
+        if total_size > 0 {
+            let pct = (data.len() as f32 / total_size as f32).min(1.0);
+            emit_progress(
+                app,
+                version,
+                "download",
+                &format!(
+                    "Downloading engine {}... {:.0}/{:.0} MB",
+                    version,
+                    data.len() as f32 / 1_048_576.0,
+                    total_size as f32 / 1_048_576.0
+                ),
+                0.05 + pct * 0.75,
+            );
+        }
+    }
+    Ok(data)
+}
+
+/// Unpacks a verified bundle into `target`, replacing an older copy only
+/// once the new one is complete.
+fn unpack(data: &[u8], target: &Path, manifest: &BundleManifest) -> Result<(), String> {
+    let parent = target.parent().ok_or("Invalid engine folder")?;
+    // Not `with_extension`: "0.5.0-beta" has one already.
+    let staging = parent.join(format!("{}.partial", manifest.version));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let unpacked = crate::compiler::extract_zip(data, &staging).and_then(|_| {
+        if let Some(file) = REQUIRED_FILES.iter().find(|file| !staging.join(file.path()).is_file()) {
+            return Err(format!("The bundle is not an engine build: {} is missing", file.path()));
+        }
+        let json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+        write_atomic(&staging.join(MANIFEST_FILE), &json)
+    });
+    if let Err(e) = unpacked {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    if target.exists() {
+        std::fs::remove_dir_all(target).map_err(|e| format!("Failed to remove the old engine: {}", e))?;
+    }
+    std::fs::rename(&staging, target).map_err(|e| e.to_string())
+}
+
+fn read_manifest(dir: &Path) -> Option<BundleManifest> {
+    let text = std::fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn installed_info(dir: &Path, manifest: BundleManifest) -> EngineVersionInfo {
+    EngineVersionInfo {
+        version: manifest.version,
+        embedded: false,
+        path: Some(dir.to_string_lossy().to_string()),
+        url: Some(manifest.url),
+        sha256: Some(manifest.sha256),
+        installed: Some(manifest.installed),
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// The embedded engine, then the installed versions, newest first.
+#[tauri::command]
+pub async fn list_engine_versions() -> Result<Vec<EngineVersionInfo>, String> {
+    tokio::task::spawn_blocking(|| {
+        let mut installed = Vec::new();
+        let dir = engines_dir()?;
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if let Some(manifest) = read_manifest(&path) {
+                    installed.push(installed_info(&path, manifest));
+                }
+            }
+        }
+        installed.sort_by(|a, b| match (semver::Version::parse(&a.version), semver::Version::parse(&b.version)) {
+            (Ok(a), Ok(b)) => b.cmp(&a),
+            _ => b.version.cmp(&a.version),
+        });
+
+        let mut versions = vec![EngineVersionInfo {
+            version: embedded_version().to_string(),
+            embedded: true,
+            path: None,
+            url: None,
+            sha256: None,
+            installed: None,
+        }];
+        versions.extend(installed);
+        Ok(versions)
+    })
+    .await
+    .map_err(|e| format!("Engine listing task failed: {}", e))?
+}
+
+/// Downloads the bundle of `version`, from the release page unless `url` is
+/// given, and installs it once it matches `sha256` (or the checksum
+/// published beside it). Progress is reported as `engine-progress` events.
+#[tauri::command]
+pub async fn install_engine_version(
+    app: AppHandle,
+    version: String,
+    url: Option<String>,
+    sha256: Option<String>,
+) -> Result<EngineVersionInfo, String> {
+    let version = parse_version(&version)?;
+    if version == embedded_version() {
+        return Err(format!("Engine {} is built into the editor", version));
+    }
+    let target = version_dir(&version)?;
+    let url = url.unwrap_or_else(|| release_url(&version, &format!("esengine-bundle-{}.zip", version)));
+
+    emit_progress(&app, &version, "download", &format!("Downloading engine {}...", version), 0.05);
+    let expected = match sha256 {
+        Some(hash) => hash.trim().to_ascii_lowercase(),
+        None => published_checksum(&url).await?,
+    };
+    let data = download_bundle(&app, &version, &url).await?;
+    let actual = format!("{:x}", Sha256::digest(&data));
+    if actual != expected {
+        return Err(format!("Checksum mismatch for {}: expected {}, got {}", url, expected, actual));
+    }
+
+    emit_progress(&app, &version, "extract", &format!("Extracting engine {}...", version), 0.85);
+    let manifest = BundleManifest { version: version.clone(), url, sha256: actual, installed: now_iso8601() };
+    let (dir, installed) = (target.clone(), manifest.clone());
+    tokio::task::spawn_blocking(move || unpack(&data, &dir, &installed))
+        .await
+        .map_err(|e| format!("Extract task failed: {}", e))??;
+
+    emit_progress(&app, &version, "complete", &format!("Engine {} installed", version), 1.0);
+    eprintln!("[Engines] Installed engine {} to {}", version, target.display());
+    Ok(installed_info(&target, manifest))
+}
+
+/// Deletes an installed version. Projects pinned to it fail to preview and
+/// export until it is installed again or they select another.
+#[tauri::command]
+pub async fn remove_engine_version(version: String) -> Result<(), String> {
+    let version = parse_version(&version)?;
+    if version == embedded_version() {
+        return Err(format!("Engine {} is built into the editor and cannot be removed", version));
+    }
+    let dir = version_dir(&version)?;
+    tokio::task::spawn_blocking(move || {
+        if !dir.join(MANIFEST_FILE).is_file() {
+            return Err(format!("Engine {} is not installed", version));
+        }
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove engine {}: {}", version, e))?;
+        eprintln!("[Engines] Removed engine {}", version);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Engine removal task failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_engine_dev_dir() -> Option<String> {
+    dev_dir().map(|dir| dir.to_string_lossy().to_string())
+}
+
+/// Uses the local engine build in `dir` instead of the embedded and
+/// installed engines, or stops using one when `dir` is `None`. Kept across
+/// restarts.
+#[tauri::command]
+pub fn set_engine_dev_dir(app: AppHandle, dir: Option<String>) -> Result<Option<String>, String> {
+    let dir = dir.filter(|dir| !dir.trim().is_empty());
+    match &dir {
+        Some(dir) => watch_dev_dir(&app, PathBuf::from(dir))?,
+        None => {
+            if DEV_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()).take().is_some() {
+                eprintln!("[Engines] Stopped using the local engine build");
+            }
+        }
+    }
+    save_dev_config(&app, &DevConfig { dir: dir.clone() })?;
+    // Previews switch engines right away, not on the next rebuild.
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        for server in state.preview_servers.lock().unwrap_or_else(|e| e.into_inner()).values() {
+            server.notify_reload();
+        }
+    }
+    Ok(dir)
+}
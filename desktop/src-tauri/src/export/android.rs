@@ -0,0 +1,175 @@
+//! Android export target — WebView wrapper project built with Gradle
+//!
+//! The web build is injected into `app/src/main/assets/game` of a generated
+//! wrapper project and served through `WebViewAssetLoader`. The project is
+//! left in the output so it can be customized and rebuilt by hand.
+
+use super::{icons, web, AndroidOptions, ExportContext};
+use std::path::{Path, PathBuf};
+
+const PROJECT_DIR: &str = "android-project";
+const GAME_ASSETS: &str = "app/src/main/assets/game";
+const LAUNCHER_ICONS: &[(&str, u32)] = &[
+    ("mdpi", 48),
+    ("hdpi", 72),
+    ("xhdpi", 96),
+    ("xxhdpi", 144),
+    ("xxxhdpi", 192),
+];
+
+const TEMPLATES: &[(&str, &str)] = &[
+    ("settings.gradle", include_str!("android_template/settings.gradle")),
+    ("build.gradle", include_str!("android_template/build.gradle")),
+    ("gradle.properties", include_str!("android_template/gradle.properties")),
+    ("app/build.gradle", include_str!("android_template/app_build.gradle")),
+    ("app/src/main/AndroidManifest.xml", include_str!("android_template/AndroidManifest.xml")),
+    ("app/src/main/res/values/strings.xml", include_str!("android_template/strings.xml")),
+];
+const MAIN_ACTIVITY: &str = include_str!("android_template/MainActivity.java");
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    let settings = ctx.options.android.clone();
+    if settings.format != "apk" && settings.format != "aab" {
+        return Err(format!("Unknown Android package format: {}", settings.format));
+    }
+    let package_id = settings.package_id.clone().unwrap_or_else(|| ctx.default_identifier());
+    validate_package_id(&package_id)?;
+
+    let output_dir = ctx.output_dir.clone();
+    let project = output_dir.join(PROJECT_DIR);
+    ctx.output_dir = project.join(GAME_ASSETS);
+    let staged = web::emit(ctx);
+    ctx.output_dir = output_dir;
+    staged?;
+
+    ctx.progress("android", "Scaffolding Android project...", 0.88);
+    scaffold(ctx, &settings, &package_id)?;
+    if settings.skip_build {
+        return Ok(());
+    }
+
+    ctx.progress("gradle", "Running Gradle...", 0.9);
+    let artifact = run_gradle(ctx, &project, &settings)?;
+    let name = format!(
+        "{}.{}",
+        crate::packaging::file_safe_name(&ctx.title()).replace(' ', "_"),
+        settings.format
+    );
+    std::fs::copy(&artifact, ctx.output_dir.join(&name))
+        .map_err(|e| format!("Failed to copy {}: {}", artifact.display(), e))?;
+    ctx.progress("gradle", &format!("Built {}", name), 0.98);
+    Ok(())
+}
+
+fn validate_package_id(id: &str) -> Result<(), String> {
+    let segments: Vec<&str> = id.split('.').collect();
+    let valid = segments.len() >= 2
+        && segments.iter().all(|s| {
+            let mut chars = s.chars();
+            matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid Android package id: {}", id))
+    }
+}
+
+fn scaffold(ctx: &mut ExportContext, settings: &AndroidOptions, package_id: &str) -> Result<(), String> {
+    let version_name = settings
+        .version_name
+        .clone()
+        .or_else(|| ctx.project_str("version").map(String::from))
+        .unwrap_or_else(|| "1.0.0".to_string());
+    let title = ctx.title();
+    let fill = |template: &str| {
+        template
+            .replace("{{PROJECT_NAME}}", &title.replace('"', ""))
+            .replace("{{APP_NAME}}", &web::escape_html(&title))
+            .replace("{{PACKAGE_ID}}", package_id)
+            .replace("{{MIN_SDK}}", &settings.min_sdk.to_string())
+            .replace("{{TARGET_SDK}}", &settings.target_sdk.to_string())
+            .replace("{{VERSION_CODE}}", &settings.version_code.to_string())
+            .replace("{{VERSION_NAME}}", &version_name.replace('\'', ""))
+            .replace("{{ORIENTATION}}", &settings.orientation)
+    };
+
+    for (rel, template) in TEMPLATES {
+        ctx.write_output(&format!("{}/{}", PROJECT_DIR, rel), fill(template).as_bytes())?;
+    }
+    let activity = format!(
+        "{}/app/src/main/java/{}/MainActivity.java",
+        PROJECT_DIR,
+        package_id.replace('.', "/")
+    );
+    ctx.write_output(&activity, fill(MAIN_ACTIVITY).as_bytes())?;
+
+    let icon = match settings.icon_path.as_deref() {
+        Some(path) => icons::load_icon(&ctx.project_dir.join(path).to_string_lossy())?,
+        None => {
+            ctx.warn("No Android icon configured; using a placeholder launcher icon".to_string());
+            image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(192, 192, image::Rgba([32, 32, 32, 255])))
+        }
+    };
+    for (density, size) in LAUNCHER_ICONS {
+        let rel = format!("{}/app/src/main/res/mipmap-{}/ic_launcher.png", PROJECT_DIR, density);
+        ctx.write_output(&rel, &icons::render_png(&icon, *size)?)?;
+    }
+
+    let sdk = settings
+        .sdk_path
+        .clone()
+        .or_else(|| crate::toolchains::android_sdk_dir().map(|(dir, _)| dir.to_string_lossy().to_string()));
+    match sdk {
+        Some(sdk) => {
+            let local = format!("sdk.dir={}\n", sdk.replace('\\', "\\\\").replace(':', "\\:"));
+            ctx.write_output(&format!("{}/local.properties", PROJECT_DIR), local.as_bytes())?;
+        }
+        None if !settings.skip_build => {
+            return Err("Android SDK not found. Set ANDROID_HOME or configure the SDK path.".to_string());
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+fn run_gradle(ctx: &mut ExportContext, project: &Path, settings: &AndroidOptions) -> Result<PathBuf, String> {
+    let gradle = match settings.gradle_path.as_deref() {
+        Some(path) => PathBuf::from(path),
+        None => crate::compiler::which_sync(if cfg!(windows) { "gradle.bat" } else { "gradle" })
+            .ok_or("Gradle not found. Install Gradle or configure its path.")?,
+    };
+
+    let variant = if settings.release { "Release" } else { "Debug" };
+    let task = match settings.format.as_str() {
+        "aab" => format!("bundle{}", variant),
+        _ => format!("assemble{}", variant),
+    };
+
+    let mut env = Vec::new();
+    match &settings.signing {
+        Some(signing) => {
+            env.push(("ORG_GRADLE_PROJECT_esStoreFile".to_string(), signing.keystore_path.clone()));
+            env.push(("ORG_GRADLE_PROJECT_esStorePassword".to_string(), signing.keystore_password.clone()));
+            env.push(("ORG_GRADLE_PROJECT_esKeyAlias".to_string(), signing.key_alias.clone()));
+            env.push(("ORG_GRADLE_PROJECT_esKeyPassword".to_string(), signing.key_password.clone()));
+        }
+        None if settings.release => {
+            ctx.warn("No signing config; the release package is unsigned".to_string());
+        }
+        None => {}
+    }
+
+    ctx.run_tool(&gradle, &[task, "--console=plain".to_string()], project, &env)?;
+
+    let kind = if settings.format == "aab" { "bundle" } else { "apk" };
+    let outputs = project
+        .join("app/build/outputs")
+        .join(kind)
+        .join(variant.to_lowercase());
+    super::assets::walk_files(&outputs)
+        .into_iter()
+        .find(|p| p.extension().is_some_and(|e| e == settings.format.as_str()))
+        .ok_or_else(|| format!("Gradle finished but no .{} was found in {}", settings.format, outputs.display()))
+}
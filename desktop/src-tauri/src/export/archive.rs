@@ -0,0 +1,204 @@
+//! Build packaging — checksum manifest and zip / tar.gz archives
+//!
+//! `manifest.json` lists every output file with its size and SHA-256, for
+//! delta CDN uploads and integrity checks. Archives are written next to the
+//! output folder, never inside it, with fixed timestamps so identical builds
+//! produce identical archives.
+
+use super::{assets, ArchiveOptions};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+const FORMATS: &[&str] = &["zip", "tar.gz"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageOptions {
+    /// Build output folder to package.
+    pub dir: String,
+    #[serde(flatten)]
+    pub archive: ArchiveOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumManifest {
+    pub version: u32,
+    pub algorithm: String,
+    pub files: Vec<ChecksumEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackagedArchive {
+    pub path: String,
+    pub format: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageResult {
+    pub manifest: Option<String>,
+    pub archives: Vec<PackagedArchive>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn package_build(options: PackageOptions) -> Result<PackageResult, String> {
+    tokio::task::spawn_blocking(move || package(Path::new(&options.dir), &options.archive))
+        .await
+        .map_err(|e| format!("Package task failed: {}", e))?
+}
+
+// =============================================================================
+// Packaging
+// =============================================================================
+
+pub(crate) fn package(dir: &Path, options: &ArchiveOptions) -> Result<PackageResult, String> {
+    if let Some(format) = options.formats.iter().find(|f| !FORMATS.contains(&f.as_str())) {
+        return Err(format!("Unknown archive format '{}'. Expected one of: {}", format, FORMATS.join(", ")));
+    }
+    if !dir.is_dir() {
+        return Err(format!("Build output not found: {}", dir.display()));
+    }
+
+    let manifest = if options.checksums {
+        write_manifest(dir)?;
+        Some(dir.join(MANIFEST_FILE).to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    let mut archives = Vec::new();
+    if !options.formats.is_empty() {
+        let archive_dir = match &options.output_dir {
+            Some(path) => PathBuf::from(path),
+            None => dir.parent().map(Path::to_path_buf).unwrap_or_default(),
+        };
+        if archive_dir.starts_with(dir) {
+            return Err("Archives must be written outside the build output folder".to_string());
+        }
+        std::fs::create_dir_all(&archive_dir).map_err(|e| e.to_string())?;
+
+        let name = options
+            .name
+            .clone()
+            .or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "build".to_string());
+        let files = assets::walk_files(dir);
+        for format in &options.formats {
+            let path = archive_dir.join(format!("{}.{}", name, format));
+            match format.as_str() {
+                "zip" => write_zip(dir, &files, &path)?,
+                _ => write_tar_gz(dir, &files, &path)?,
+            }
+            archives.push(PackagedArchive {
+                path: path.to_string_lossy().to_string(),
+                format: format.clone(),
+                size: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                sha256: sha256_file(&path)?,
+            });
+        }
+    }
+
+    Ok(PackageResult { manifest, archives })
+}
+
+/// Writes `manifest.json` at the root of `dir`, covering every other file.
+pub(crate) fn write_manifest(dir: &Path) -> Result<ChecksumManifest, String> {
+    let mut files = Vec::new();
+    for path in assets::walk_files(dir) {
+        let rel = assets::relative_path(dir, &path);
+        if rel == MANIFEST_FILE {
+            continue;
+        }
+        files.push(ChecksumEntry {
+            size: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            sha256: sha256_file(&path)?,
+            path: rel,
+        });
+    }
+
+    let manifest = ChecksumManifest {
+        version: MANIFEST_VERSION,
+        algorithm: "sha256".to_string(),
+        files,
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(MANIFEST_FILE), json).map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))?;
+    Ok(manifest)
+}
+
+fn write_zip(dir: &Path, files: &[PathBuf], dest: &Path) -> Result<(), String> {
+    let file = File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut writer = zip::ZipWriter::new(BufWriter::new(file));
+    for path in files {
+        let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(zip::DateTime::default())
+            .unix_permissions(file_mode(&metadata))
+            .large_file(metadata.len() >= u32::MAX as u64);
+        writer
+            .start_file(assets::relative_path(dir, path), options)
+            .map_err(|e| format!("Failed to write zip: {}", e))?;
+        let mut source = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        std::io::copy(&mut source, &mut writer).map_err(|e| format!("Failed to write zip: {}", e))?;
+    }
+    writer
+        .finish()
+        .and_then(|mut w| w.flush().map_err(Into::into))
+        .map_err(|e| format!("Failed to write zip: {}", e))
+}
+
+fn write_tar_gz(dir: &Path, files: &[PathBuf], dest: &Path) -> Result<(), String> {
+    let file = File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let encoder = flate2::write::GzEncoder::new(BufWriter::new(file), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.mode(tar::HeaderMode::Deterministic);
+    for path in files {
+        builder
+            .append_path_with_name(path, assets::relative_path(dir, path))
+            .map_err(|e| format!("Failed to add {} to archive: {}", path.display(), e))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|mut w| w.flush())
+        .map_err(|e| format!("Failed to write tar.gz: {}", e))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &std::fs::Metadata) -> u32 {
+    0o644
+}
+
+pub(crate) fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
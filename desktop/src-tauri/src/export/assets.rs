@@ -0,0 +1,300 @@
+//! Asset discovery, reference collection and path rewriting for exports
+
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+// =============================================================================
+// Asset Types
+// =============================================================================
+
+/// Extensions recognized as project assets (mirrors the SDK asset type registry).
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "svg",
+    "mp3", "wav", "ogg", "aac", "flac", "webm",
+    "esmaterial", "esshader", "atlas", "skel", "json", "bmfont", "fnt",
+    "esprefab", "esscene", "esanim", "tmj", "estimeline",
+];
+
+/// JSON assets that may reference other assets and need their refs rewritten.
+const JSON_ASSET_EXTENSIONS: &[&str] = &[
+    "esscene", "esprefab", "esmaterial", "esanim", "tmj", "estimeline", "bmfont",
+];
+
+/// Custom JSON-content extensions shipped as `.json` on mini-game platforms,
+/// which only package files with whitelisted suffixes.
+const MINIGAME_JSON_EXTENSIONS: &[&str] = &[
+    "esmaterial", "bmfont", "esprefab", "esanim", "tmj", "estimeline",
+];
+
+/// Custom extensions that must be whitelisted in mini-game pack options.
+pub const MINIGAME_PACK_EXTENSIONS: &[&str] = &[
+    "esmaterial", "atlas", "skel", "bmfont", "fnt", "esprefab", "esanim", "tmj", "estimeline",
+];
+
+pub fn extension_of(path: &str) -> String {
+    path.rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+pub fn is_asset_path(path: &str) -> bool {
+    ASSET_EXTENSIONS.contains(&extension_of(path).as_str())
+}
+
+pub fn is_json_asset(path: &str) -> bool {
+    JSON_ASSET_EXTENSIONS.contains(&extension_of(path).as_str())
+}
+
+/// Output path on mini-game platforms (mirrors the SDK's `toBuildPath`).
+pub fn to_build_path(path: &str) -> String {
+    if !MINIGAME_JSON_EXTENSIONS.contains(&extension_of(path).as_str()) {
+        return path.to_string();
+    }
+    match path.rsplit_once('.') {
+        Some((base, _)) => format!("{}.json", base),
+        None => path.to_string(),
+    }
+}
+
+pub fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+// =============================================================================
+// Asset Database
+// =============================================================================
+
+/// UUID <-> project-relative path mapping built from `.meta` sidecar files.
+#[derive(Default)]
+pub struct AssetDatabase {
+    by_uuid: HashMap<String, String>,
+    by_path: HashMap<String, String>,
+}
+
+impl AssetDatabase {
+    pub fn scan(project_dir: &Path) -> Self {
+        let mut db = Self::default();
+        for meta in walk_files(&project_dir.join("assets")) {
+            let meta_rel = relative_path(project_dir, &meta);
+            let Some(asset_rel) = meta_rel.strip_suffix(".meta") else {
+                continue;
+            };
+            let uuid = std::fs::read_to_string(&meta)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v.get("uuid").and_then(|u| u.as_str()).map(String::from));
+            if let Some(uuid) = uuid {
+                db.by_path.insert(asset_rel.to_string(), uuid.clone());
+                db.by_uuid.insert(uuid, asset_rel.to_string());
+            }
+        }
+        db
+    }
+
+    pub fn path_of(&self, uuid: &str) -> Option<&str> {
+        self.by_uuid.get(uuid).map(String::as_str)
+    }
+
+    pub fn uuid_of(&self, path: &str) -> Option<&str> {
+        self.by_path.get(path).map(String::as_str)
+    }
+}
+
+// =============================================================================
+// Reference Collection
+// =============================================================================
+
+/// Collects every asset transitively referenced from `roots` (project-relative paths).
+pub fn collect_references(
+    project_dir: &Path,
+    db: &AssetDatabase,
+    roots: &[String],
+) -> BTreeSet<String> {
+    let mut found = BTreeSet::new();
+    let mut pending: Vec<String> = roots.to_vec();
+
+    while let Some(rel) = pending.pop() {
+        if !found.insert(rel.clone()) {
+            continue;
+        }
+        let full = project_dir.join(&rel);
+        let base_dir = parent_dir(&rel);
+
+        if is_json_asset(&rel) {
+            let Some(value) = std::fs::read_to_string(&full)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+            else {
+                continue;
+            };
+            let mut refs = Vec::new();
+            collect_json_refs(&value, &mut refs);
+            for r in refs {
+                if let Some(resolved) = resolve_ref(project_dir, db, &base_dir, &r) {
+                    pending.push(resolved);
+                }
+            }
+        } else {
+            for r in text_asset_deps(&full, &rel) {
+                if let Some(resolved) = resolve_ref(project_dir, db, &base_dir, &r) {
+                    pending.push(resolved);
+                }
+            }
+        }
+    }
+
+    found
+}
+
+pub(crate) fn collect_json_refs(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) if is_uuid(s) || is_asset_path(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| collect_json_refs(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_json_refs(v, out)),
+        _ => {}
+    }
+}
+
+/// Resolved page images of a Spine atlas or BMFont descriptor.
+pub fn text_dependencies(project_dir: &Path, db: &AssetDatabase, rel: &str) -> Vec<String> {
+    let base_dir = parent_dir(rel);
+    text_asset_deps(&project_dir.join(rel), rel)
+        .iter()
+        .filter_map(|r| resolve_ref(project_dir, db, &base_dir, r))
+        .collect()
+}
+
+/// Page images referenced by Spine atlases and BMFont descriptors.
+pub(crate) fn text_asset_deps(full: &Path, rel: &str) -> Vec<String> {
+    let ext = extension_of(rel);
+    if ext != "atlas" && ext != "fnt" {
+        return Vec::new();
+    }
+    let Ok(text) = std::fs::read_to_string(full) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if ext == "fnt" {
+                let start = line.find("file=\"")? + 6;
+                let end = line[start..].find('"')? + start;
+                Some(line[start..end].to_string())
+            } else if is_asset_path(line) && !line.contains(':') {
+                Some(line.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolves a UUID or path reference to a project-relative asset path that exists on disk.
+pub fn resolve_ref(
+    project_dir: &Path,
+    db: &AssetDatabase,
+    base_dir: &str,
+    reference: &str,
+) -> Option<String> {
+    if is_uuid(reference) {
+        return db.path_of(reference).map(String::from);
+    }
+    let cleaned = reference.trim_start_matches("./").trim_start_matches('/');
+    let candidates = [normalize_path(cleaned), normalize_path(&format!("{}/{}", base_dir, cleaned))];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|c| !c.is_empty() && project_dir.join(c).is_file())
+}
+
+/// Replaces UUID references with project-relative paths in place.
+pub fn rewrite_refs(value: &mut Value, db: &AssetDatabase) {
+    match value {
+        Value::String(s) if is_uuid(s) => {
+            if let Some(path) = db.path_of(s) {
+                *s = path.to_string();
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| rewrite_refs(v, db)),
+        Value::Object(map) => map.values_mut().for_each(|v| rewrite_refs(v, db)),
+        _ => {}
+    }
+}
+
+/// Replaces string values that exactly match a key in `map`.
+pub fn remap_paths(value: &mut Value, map: &HashMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(mapped) = map.get(s.as_str()) {
+                *s = mapped.clone();
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| remap_paths(v, map)),
+        Value::Object(map_value) => map_value.values_mut().for_each(|v| remap_paths(v, map)),
+        _ => {}
+    }
+}
+
+// =============================================================================
+// Path Helpers
+// =============================================================================
+
+/// Recursively lists files under `dir`, sorted for stable output.
+pub fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Project-relative path with forward slashes.
+pub fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+pub fn parent_dir(rel: &str) -> String {
+    rel.rsplit_once('/').map(|(d, _)| d.to_string()).unwrap_or_default()
+}
+
+/// Resolves `.` and `..` in a relative path, joined with forward slashes.
+/// `None` when the path is absolute or climbs above its root.
+pub fn normalize_path(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
+
+pub fn file_stem(rel: &str) -> String {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    name.rsplit_once('.').map(|(s, _)| s).unwrap_or(name).to_string()
+}
@@ -0,0 +1,144 @@
+//! Build stamping — version, build number, commit and time of an export
+//!
+//! The build number is a per-project counter kept in
+//! `.esengine/build-number.json`; it is reserved when an export starts and
+//! only persisted once the export succeeds. The stamp is exposed to the
+//! game through build variables (`%BUILD_NUMBER%`, `__ESENGINE_BUILD__`) and
+//! written as `buildinfo.json` so crash reports can name the exact build.
+//!
+//! Reproducible builds take their time from `SOURCE_DATE_EPOCH` or the last
+//! commit instead of the clock, and may pin the build number.
+
+use super::{ExportContext, ExportOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+
+const COUNTER_FILE: &str = ".esengine/build-number.json";
+/// Editor state inside the project; changes here do not make a build dirty.
+const STATE_DIR: &str = ".esengine";
+pub const BUILD_INFO_FILE: &str = "buildinfo.json";
+
+#[derive(Debug, Clone, Default)]
+pub struct BuildInfo {
+    pub version: String,
+    pub build_number: u64,
+    /// Short commit hash, when the project is a git checkout.
+    pub commit: Option<String>,
+    /// True when the checkout had uncommitted changes.
+    pub dirty: bool,
+    /// UTC, ISO 8601.
+    pub timestamp: String,
+    /// True when `build_number` was pinned by the caller and the counter is
+    /// left untouched.
+    pub pinned: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuildCounter {
+    last: u64,
+}
+
+impl BuildInfo {
+    pub fn collect(project_dir: &Path, version: &str, options: &ExportOptions) -> Self {
+        let commit = git(project_dir, &["rev-parse", "--short", "HEAD"]);
+        let exclude_state = format!(":(exclude){}", STATE_DIR);
+        let dirty = commit.is_some()
+            && git(project_dir, &["status", "--porcelain", "--", ".", &exclude_state]).is_some_and(|s| !s.is_empty());
+        let time = match source_date_epoch() {
+            Some(time) => time,
+            None if options.reproducible => git(project_dir, &["log", "-1", "--format=%ct"])
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0),
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        };
+        Self {
+            version: version.to_string(),
+            build_number: options.build_number.unwrap_or_else(|| read_counter(project_dir).last + 1),
+            commit,
+            dirty,
+            timestamp: iso8601_utc(time),
+            pinned: options.build_number.is_some(),
+        }
+    }
+
+    /// Records this build number as used.
+    pub fn save_number(&self, project_dir: &Path) -> Result<(), String> {
+        if self.pinned {
+            return Ok(());
+        }
+        let path = project_dir.join(COUNTER_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_vec_pretty(&BuildCounter { last: self.build_number }).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to save build number: {}", e))
+    }
+}
+
+/// Writes `buildinfo.json` to the output root.
+pub(crate) fn write_build_info(ctx: &ExportContext) -> Result<(), String> {
+    let info = &ctx.build_info;
+    let value = json!({
+        "version": info.version,
+        "buildNumber": info.build_number,
+        "commit": info.commit,
+        "dirty": info.dirty,
+        "timestamp": info.timestamp,
+        "target": ctx.options.target,
+        "profile": ctx.options.profile,
+        "debug": ctx.options.debug,
+    });
+    let data = serde_json::to_vec_pretty(&value).map_err(|e| e.to_string())?;
+    ctx.write_output(BUILD_INFO_FILE, &data)
+}
+
+fn read_counter(project_dir: &Path) -> BuildCounter {
+    std::fs::read_to_string(project_dir.join(COUNTER_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn source_date_epoch() -> Option<u64> {
+    std::env::var("SOURCE_DATE_EPOCH").ok()?.trim().parse().ok()
+}
+
+fn git(project_dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(project_dir).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for a Unix timestamp.
+pub(crate) fn iso8601_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days, proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
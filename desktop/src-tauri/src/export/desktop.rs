@@ -0,0 +1,89 @@
+//! Desktop export target — standalone executable via the runtime shell
+
+use super::{assets, icons, web, ExportContext, ExportedFile};
+use crate::packaging::{self, DesktopPackage};
+use std::path::PathBuf;
+
+const STAGING_DIR: &str = ".web";
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    let settings = ctx.options.desktop.clone();
+    if !packaging::DESKTOP_PLATFORMS.contains(&settings.platform.as_str()) {
+        return Err(format!("Unsupported desktop platform: {}", settings.platform));
+    }
+    let shell = resolve_shell(ctx, settings.shell_path.as_deref(), &settings.platform)?;
+
+    // The web build is staged inside the output and packed into the shell.
+    let output_dir = ctx.output_dir.clone();
+    let staging = output_dir.join(STAGING_DIR);
+    ctx.output_dir = staging.clone();
+    let staged = web::emit(ctx);
+    ctx.output_dir = output_dir.clone();
+    staged?;
+
+    ctx.progress("package", &format!("Packaging for {}...", settings.platform), 0.9);
+    let icon = match settings.icon_path.as_deref() {
+        Some(path) => Some(icons::load_icon(&ctx.project_dir.join(path).to_string_lossy())?),
+        None => None,
+    };
+    let title = ctx.title();
+    let mut window = settings.window.clone();
+    if window.title.is_empty() {
+        window.title = title.clone();
+    }
+    let version = settings
+        .version
+        .clone()
+        .or_else(|| ctx.project_str("version").map(String::from))
+        .unwrap_or_else(|| "1.0.0".to_string());
+    let identifier = settings.identifier.clone().unwrap_or_else(|| ctx.default_identifier());
+
+    let result = packaging::package_desktop(
+        &settings.platform,
+        &DesktopPackage {
+            shell: &shell,
+            web_root: &staging,
+            output_dir: &output_dir,
+            app_name: &title,
+            identifier: &identifier,
+            version: &version,
+            icon: icon.as_ref(),
+            window: &window,
+            signing_identity: settings.signing_identity.as_deref(),
+        },
+    );
+    ctx.packaged = assets::walk_files(&staging)
+        .into_iter()
+        .map(|p| ExportedFile {
+            size: std::fs::metadata(&p).map(|m| m.len()).unwrap_or(0),
+            path: assets::relative_path(&staging, &p),
+        })
+        .collect();
+    let _ = std::fs::remove_dir_all(&staging);
+    for warning in result? {
+        ctx.warn(warning);
+    }
+    Ok(())
+}
+
+/// Explicit shell path, then the bundled editor resource, then the dev tree.
+fn resolve_shell(ctx: &ExportContext, custom: Option<&str>, platform: &str) -> Result<PathBuf, String> {
+    if let Some(path) = custom {
+        return Ok(PathBuf::from(path));
+    }
+    let rel = packaging::shell_resource_path(platform);
+    if let Some(resource_dir) = ctx.resource_dir() {
+        let bundled = resource_dir.join(&rel);
+        if bundled.exists() {
+            return Ok(bundled);
+        }
+    }
+    let dev_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(&rel);
+    if dev_path.exists() {
+        return Ok(dev_path);
+    }
+    Err(format!(
+        "Runtime shell for {} not found. Reinstall the editor or provide a custom shell.",
+        platform
+    ))
+}
@@ -0,0 +1,39 @@
+//! Douyin (TikTok) mini-game export target
+//!
+//! Shares the mini-game asset pipeline with WeChat. The default adapter is the
+//! WeChat runtime bundle with `wx` aliased to Douyin's `tt` API; projects with a
+//! dedicated adapter bundle can supply it through `adapter_path`.
+
+use super::minigame::{self, sanitize_name, Platform};
+use super::{ExportContext, MiniGameOptions};
+use crate::embedded_assets;
+use serde_json::{json, Value};
+
+const DOUYIN: Platform = Platform {
+    name: "Douyin",
+    api: "tt",
+    main_package_limit: 4 * 1024 * 1024,
+    total_limit: 20 * 1024 * 1024,
+    adapter: embedded_assets::SDK_WECHAT_JS,
+    adapter_prelude: "if (typeof wx === 'undefined') { globalThis.wx = tt; }",
+    project_config,
+};
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    let settings = ctx.options.douyin.clone();
+    minigame::emit(ctx, &DOUYIN, &settings)
+}
+
+fn project_config(ctx: &ExportContext, settings: &MiniGameOptions) -> Value {
+    json!({
+        "appid": if settings.app_id.is_empty() { "testappid" } else { &settings.app_id },
+        "projectname": sanitize_name(&ctx.title()),
+        "description": ctx.title(),
+        "compileType": "game",
+        "setting": {
+            "es6": true,
+            "minified": !(settings.debug || ctx.options.debug),
+            "urlCheck": false,
+        },
+    })
+}
@@ -0,0 +1,24 @@
+//! Icon rendering for exported games
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+
+pub fn load_icon(path: &str) -> Result<DynamicImage, String> {
+    image::open(path).map_err(|e| format!("Failed to load icon {}: {}", path, e))
+}
+
+/// Renders a square PNG of `size` pixels, letterboxing non-square sources.
+pub fn render_png(source: &DynamicImage, size: u32) -> Result<Vec<u8>, String> {
+    let resized = source.resize(size, size, FilterType::Lanczos3);
+    let mut canvas = image::RgbaImage::new(size, size);
+    let x = (size - resized.width()) / 2;
+    let y = (size - resized.height()) / 2;
+    image::imageops::overlay(&mut canvas, &resized.to_rgba8(), x as i64, y as i64);
+
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(canvas)
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode icon: {}", e))?;
+    Ok(out.into_inner())
+}
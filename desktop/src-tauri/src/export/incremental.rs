@@ -0,0 +1,172 @@
+//! Incremental exports — per-asset build manifest
+//!
+//! Every copied asset is recorded with a stamp of its source and `.meta`
+//! sidecar together with the output files it produced. On the next export to
+//! the same directory, assets whose stamp is unchanged and whose outputs still
+//! exist are skipped. The manifest is discarded, and a full build runs, when
+//! the pipeline version or any export option (target, profile, variables...)
+//! changes.
+
+use super::{assets, ExportOptions};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = ".esengine/export-cache";
+
+/// Bump when asset processing changes so existing outputs are rebuilt.
+const PIPELINE_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuildManifest {
+    version: u32,
+    fingerprint: String,
+    assets: BTreeMap<String, AssetRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AssetRecord {
+    stamp: String,
+    /// Output files, relative to the export root.
+    outputs: Vec<String>,
+}
+
+pub(crate) struct IncrementalBuild {
+    manifest_path: PathBuf,
+    root: PathBuf,
+    fingerprint: String,
+    previous: BTreeMap<String, AssetRecord>,
+    current: BTreeMap<String, AssetRecord>,
+    /// True when the previous output could not be reused.
+    pub full: bool,
+    /// True when this project exported to the folder before.
+    pub owned: bool,
+    pub reused: usize,
+}
+
+impl IncrementalBuild {
+    pub fn load(options: &ExportOptions, project_dir: &Path, output_dir: &Path) -> Self {
+        let manifest_path = manifest_path(project_dir, output_dir);
+        let fingerprint = fingerprint(options);
+
+        let previous = std::fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<BuildManifest>(&s).ok())
+            .filter(|m| m.version == PIPELINE_VERSION && m.fingerprint == fingerprint);
+        let full = options.clean || previous.is_none() || !output_dir.is_dir();
+
+        Self {
+            owned: manifest_path.is_file(),
+            manifest_path,
+            root: output_dir.to_path_buf(),
+            fingerprint,
+            previous: if full { BTreeMap::new() } else { previous.unwrap_or_default().assets },
+            current: BTreeMap::new(),
+            full,
+            reused: 0,
+        }
+    }
+
+    /// Removes every output file that is not a recorded asset output, so
+    /// generated files are always rewritten and nothing stale survives.
+    pub fn prune(&self) -> Result<(), String> {
+        let keep: BTreeSet<&str> = self
+            .previous
+            .values()
+            .flat_map(|r| r.outputs.iter().map(String::as_str))
+            .collect();
+        for file in assets::walk_files(&self.root) {
+            if !keep.contains(assets::relative_path(&self.root, &file).as_str()) {
+                std::fs::remove_file(&file).map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true when `rel` is unchanged since the last build and its output
+    /// `dest` is still present; the asset is recorded either way.
+    pub fn reuse(&mut self, project_dir: &Path, rel: &str, dest: &Path) -> bool {
+        let stamp = stamp(project_dir, rel);
+        let output = assets::relative_path(&self.root, dest);
+        let fresh = self.previous.get(rel).is_some_and(|r| {
+            r.stamp == stamp && r.outputs.len() == 1 && r.outputs[0] == output && dest.is_file()
+        });
+        if fresh {
+            self.reused += 1;
+        }
+        self.current.insert(rel.to_string(), AssetRecord { stamp, outputs: vec![output] });
+        fresh
+    }
+
+    /// Deletes outputs of assets that are no longer exported and writes the manifest.
+    pub fn save(&self) -> Result<(), String> {
+        let live: BTreeSet<&String> = self.current.values().flat_map(|r| r.outputs.iter()).collect();
+        for record in self.previous.values() {
+            for output in record.outputs.iter().filter(|o| !live.contains(o)) {
+                let _ = std::fs::remove_file(self.root.join(output));
+            }
+        }
+
+        let manifest = BuildManifest {
+            version: PIPELINE_VERSION,
+            fingerprint: self.fingerprint.clone(),
+            assets: self.current.clone(),
+        };
+        if let Some(parent) = self.manifest_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+        std::fs::write(&self.manifest_path, json).map_err(|e| format!("Failed to write build manifest: {}", e))
+    }
+}
+
+/// Drops the cached manifest of a throwaway output folder.
+pub fn forget(project_dir: &Path, output_dir: &Path) {
+    let _ = std::fs::remove_file(manifest_path(project_dir, output_dir));
+}
+
+fn manifest_path(project_dir: &Path, output_dir: &Path) -> PathBuf {
+    let key = format!("{:x}", Sha256::digest(output_dir.to_string_lossy().as_bytes()));
+    project_dir.join(CACHE_DIR).join(format!("{}.json", &key[..16]))
+}
+
+/// Hash of the editor version and every export option except those that do
+/// not affect asset outputs.
+fn fingerprint(options: &ExportOptions) -> String {
+    let mut value = serde_json::to_value(options).unwrap_or_default();
+    if let Some(map) = value.as_object_mut() {
+        map.remove("clean");
+        map.remove("archive");
+        map.remove("build_number");
+        map.remove("reproducible");
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(value.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Size and modification time of an asset and its `.meta` sidecar, which
+/// carries the importer settings.
+fn stamp(project_dir: &Path, rel: &str) -> String {
+    let file_stamp = |path: PathBuf| {
+        std::fs::metadata(path)
+            .ok()
+            .map(|m| {
+                let modified = m
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                format!("{}:{}", m.len(), modified)
+            })
+            .unwrap_or_default()
+    };
+    format!(
+        "{}|{}",
+        file_stamp(project_dir.join(rel)),
+        file_stamp(project_dir.join(format!("{}.meta", rel)))
+    )
+}
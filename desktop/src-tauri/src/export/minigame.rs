@@ -0,0 +1,392 @@
+//! Mini-game export pipeline shared by the WeChat and Douyin targets
+//!
+//! Lays out game.js / game.json / project.config.json, splits assets into
+//! subpackages by folder rules or scene usage, loads each scene's
+//! subpackages just before that scene, and verifies the platform package
+//! size limits before reporting success.
+
+use super::{assets, web, ExportContext, MiniGameOptions, SubpackageRule};
+use crate::embedded_assets::Embedded;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const SUBPACKAGE_DIR: &str = "subpackages";
+
+/// Asset path -> subpackage name; `None` keeps the asset in the main package.
+pub(crate) type PackageAssignment = BTreeMap<String, Option<String>>;
+
+/// Platform specifics of a mini-game target.
+pub(crate) struct Platform {
+    pub name: &'static str,
+    /// Global API object (`wx`, `tt`).
+    pub api: &'static str,
+    pub main_package_limit: u64,
+    pub total_limit: u64,
+    /// Runtime adapter bundle shipped as `sdk.js` unless overridden.
+    pub adapter: Embedded,
+    /// Code run before the adapter loads, e.g. to alias platform globals.
+    pub adapter_prelude: &'static str,
+    pub project_config: fn(&ExportContext, &MiniGameOptions) -> Value,
+}
+
+pub(crate) fn emit(ctx: &mut ExportContext, platform: &Platform, settings: &MiniGameOptions) -> Result<(), String> {
+    ctx.progress("packages", "Assigning subpackages...", 0.2);
+    let packages = assign_packages(ctx, &settings.subpackages, settings.split_by_scene);
+    let path_map = package_path_map(&packages);
+    let names: BTreeSet<String> = packages.values().flatten().cloned().collect();
+    let scene_packages = scene_packages(ctx, &packages);
+
+    write_packaged_assets(ctx, &packages, &path_map, 0.25, 0.6)?;
+    for scene in &ctx.scenes {
+        let mut value = ctx.read_rewritten_json(scene)?;
+        assets::remap_paths(&mut value, &path_map);
+        let data = serde_json::to_vec(&value).map_err(|e| e.to_string())?;
+        ctx.write_output(&ctx.scene_output_path(scene), &data)?;
+    }
+    let mut manifest = super::asset_manifest(ctx);
+    assets::remap_paths(&mut manifest, &path_map);
+    let data = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    ctx.write_output("asset-manifest.json", &data)?;
+
+    ctx.progress("runtime", "Writing engine runtime...", 0.7);
+    let (Some(engine_js), Some(engine_wasm)) = (&ctx.options.engine_js_path, &ctx.options.engine_wasm_path) else {
+        return Err("WASM not compiled. Toolchain compilation is required.".to_string());
+    };
+    let engine_js = std::fs::read(engine_js).map_err(|e| format!("Failed to read {}: {}", engine_js, e))?;
+    let engine_wasm = std::fs::read(engine_wasm).map_err(|e| format!("Failed to read {}: {}", engine_wasm, e))?;
+    ctx.write_output("esengine.js", &engine_js)?;
+    ctx.write_output("esengine.wasm", &engine_wasm)?;
+    let adapter = ctx.read_override(&settings.adapter_path, platform.adapter)?;
+    let adapter = super::source_maps::strip(ctx, "sdk.js", &adapter, None);
+    ctx.write_output("sdk.js", &adapter)?;
+    let physics = web::read_physics(ctx)?;
+    if let Some((js, wasm)) = &physics {
+        ctx.write_output("physics.js", js)?;
+        ctx.write_output("physics.wasm", wasm)?;
+    }
+
+    ctx.progress("config", "Generating game.js and project files...", 0.8);
+    let scripts = ctx.user_scripts()?;
+    let game_js = generate_game_js(ctx, platform, scripts.as_deref(), physics.is_some(), &names, &scene_packages);
+    ctx.write_output("game.js", game_js.as_bytes())?;
+    for name in &names {
+        let entry = format!("// Subpackage entry: {}\n", name);
+        ctx.write_output(&format!("{}/game.js", subpackage_root(name)), entry.as_bytes())?;
+    }
+    let game = serde_json::to_string_pretty(&game_json(&settings.orientation, &names)).map_err(|e| e.to_string())?;
+    ctx.write_output("game.json", ctx.substitute_variables(&game).as_bytes())?;
+    write_json(ctx, "project.config.json", &(platform.project_config)(ctx, settings))?;
+    super::build_info::write_build_info(ctx)?;
+
+    ctx.progress("verify", "Checking package size limits...", 0.9);
+    check_size_limits(
+        ctx,
+        settings.main_package_limit.unwrap_or(platform.main_package_limit),
+        settings.total_limit.unwrap_or(platform.total_limit),
+        platform.name,
+    )
+}
+
+// =============================================================================
+// Subpackages
+// =============================================================================
+
+/// Assigns every exported asset to the main package or a subpackage.
+///
+/// Folder rules win; otherwise, with `split_by_scene`, assets used by exactly
+/// one non-startup scene move to that scene's subpackage. Atlas and font pages
+/// always stay with their descriptor so relative page paths keep resolving.
+pub(crate) fn assign_packages(
+    ctx: &ExportContext,
+    rules: &[SubpackageRule],
+    split_by_scene: bool,
+) -> PackageAssignment {
+    let mut scene_users: HashMap<&str, Vec<usize>> = HashMap::new();
+    if split_by_scene {
+        for (i, scene) in ctx.scenes.iter().enumerate() {
+            for rel in assets::collect_references(&ctx.project_dir, &ctx.db, std::slice::from_ref(scene)) {
+                if let Some(asset) = ctx.assets.get(&rel) {
+                    scene_users.entry(asset.as_str()).or_default().push(i);
+                }
+            }
+        }
+    }
+
+    let mut packages: PackageAssignment = ctx
+        .assets
+        .iter()
+        .map(|rel| {
+            let by_rule = rules.iter().find(|r| {
+                r.folders.iter().any(|f| rel.starts_with(&format!("{}/", f.trim_end_matches('/'))))
+            });
+            let package = match by_rule {
+                Some(rule) => Some(sanitize_name(&rule.name)),
+                None => match scene_users.get(rel.as_str()).map(Vec::as_slice) {
+                    Some(&[i]) if i > 0 => Some(format!("scene_{}", sanitize_name(&ctx.scene_name(&ctx.scenes[i])))),
+                    _ => None,
+                },
+            };
+            (rel.clone(), package)
+        })
+        .collect();
+
+    for rel in &ctx.assets {
+        let deps = assets::text_dependencies(&ctx.project_dir, &ctx.db, rel);
+        let owner = packages.get(rel).cloned().flatten();
+        if deps.iter().any(|d| packages.get(d).cloned().flatten() != owner) {
+            for path in deps.iter().chain(std::iter::once(rel)) {
+                if let Some(p) = packages.get_mut(path) {
+                    *p = None;
+                }
+            }
+        }
+    }
+    packages
+}
+
+/// The subpackages holding assets of each scene, by scene name.
+fn scene_packages(ctx: &ExportContext, packages: &PackageAssignment) -> BTreeMap<String, BTreeSet<String>> {
+    ctx.scenes
+        .iter()
+        .map(|scene| {
+            let used = assets::collect_references(&ctx.project_dir, &ctx.db, std::slice::from_ref(scene))
+                .iter()
+                .filter_map(|rel| packages.get(rel).cloned().flatten())
+                .collect();
+            (ctx.scene_name(scene), used)
+        })
+        .collect()
+}
+
+/// Maps asset paths to their location inside a subpackage root.
+pub(crate) fn package_path_map(packages: &PackageAssignment) -> HashMap<String, String> {
+    packages
+        .iter()
+        .filter_map(|(rel, pkg)| pkg.as_ref().map(|name| (rel.clone(), format!("{}/{}", subpackage_root(name), rel))))
+        .collect()
+}
+
+/// Copies assets to their package, using mini-game build paths for custom JSON types.
+pub(crate) fn write_packaged_assets(
+    ctx: &mut ExportContext,
+    packages: &PackageAssignment,
+    path_map: &HashMap<String, String>,
+    progress_from: f32,
+    progress_to: f32,
+) -> Result<(), String> {
+    let total = packages.len().max(1) as f32;
+    for (i, rel) in packages.keys().enumerate() {
+        let dest = assets::to_build_path(path_map.get(rel).unwrap_or(rel));
+        if assets::is_json_asset(rel) {
+            let mut value = ctx.read_rewritten_json(rel)?;
+            assets::remap_paths(&mut value, path_map);
+            let data = serde_json::to_vec(&value).map_err(|e| e.to_string())?;
+            ctx.write_output(&dest, &data)?;
+        } else if !ctx.build.reuse(&ctx.project_dir, rel, &ctx.output_dir.join(&dest)) {
+            let data = std::fs::read(ctx.project_dir.join(rel))
+                .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            ctx.write_output(&dest, &data)?;
+        }
+
+        if i % 16 == 0 {
+            let p = progress_from + (progress_to - progress_from) * (i as f32 / total);
+            ctx.progress("assets", &format!("Copying {}", rel), p);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn subpackage_root(name: &str) -> String {
+    format!("{}/{}", SUBPACKAGE_DIR, name)
+}
+
+pub(crate) fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+// =============================================================================
+// Size Limits
+// =============================================================================
+
+/// Fails with a per-package breakdown when the platform limits are exceeded.
+pub(crate) fn check_size_limits(
+    ctx: &ExportContext,
+    main_limit: u64,
+    total_limit: u64,
+    platform: &str,
+) -> Result<(), String> {
+    let mut main_files: Vec<(String, u64)> = Vec::new();
+    let mut subpackage_sizes: BTreeMap<String, u64> = BTreeMap::new();
+    for file in assets::walk_files(&ctx.output_dir) {
+        let rel = assets::relative_path(&ctx.output_dir, &file);
+        let size = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+        match rel.strip_prefix(&format!("{}/", SUBPACKAGE_DIR)).and_then(|r| r.split_once('/')) {
+            Some((name, _)) => *subpackage_sizes.entry(name.to_string()).or_default() += size,
+            None => main_files.push((rel, size)),
+        }
+    }
+
+    let main_size: u64 = main_files.iter().map(|(_, s)| s).sum();
+    let total_size = main_size + subpackage_sizes.values().sum::<u64>();
+    if main_size <= main_limit && total_size <= total_limit {
+        return Ok(());
+    }
+
+    main_files.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    let mut report = format!("{} package size limits exceeded:\n", platform);
+    report.push_str(&format!(
+        "  main package: {} (limit {})\n",
+        format_size(main_size),
+        format_size(main_limit)
+    ));
+    report.push_str(&format!(
+        "  total: {} (limit {})\n",
+        format_size(total_size),
+        format_size(total_limit)
+    ));
+    report.push_str("Largest files in main package:\n");
+    for (path, size) in main_files.iter().take(10) {
+        report.push_str(&format!("  {:>10}  {}\n", format_size(*size), path));
+    }
+    if !subpackage_sizes.is_empty() {
+        report.push_str("Subpackages:\n");
+        for (name, size) in &subpackage_sizes {
+            report.push_str(&format!("  {:>10}  {}\n", format_size(*size), name));
+        }
+    }
+    Err(report.trim_end().to_string())
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+// =============================================================================
+// Project Files
+// =============================================================================
+
+fn write_json(ctx: &ExportContext, rel: &str, value: &Value) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    ctx.write_output(rel, &data)
+}
+
+pub(crate) fn subpackage_entries(names: &BTreeSet<String>) -> Vec<Value> {
+    names
+        .iter()
+        .map(|name| json!({ "name": name, "root": format!("{}/", subpackage_root(name)) }))
+        .collect()
+}
+
+fn game_json(orientation: &str, names: &BTreeSet<String>) -> Value {
+    let mut game = json!({
+        "deviceOrientation": orientation,
+        "networkTimeout": {
+            "request": 10000,
+            "connectSocket": 10000,
+            "uploadFile": 10000,
+            "downloadFile": 10000,
+        },
+    });
+    if !names.is_empty() {
+        game["subpackages"] = Value::Array(subpackage_entries(names));
+    }
+    game
+}
+
+/// Mirrors the editor's WeChat game.js template, plus platform prelude and subpackage loading.
+///
+/// A scene's subpackages load before the scene does; subpackages no scene
+/// uses load in the background once the first scene is up.
+fn generate_game_js(
+    ctx: &ExportContext,
+    platform: &Platform,
+    user_code: Option<&[u8]>,
+    has_physics: bool,
+    subpackages: &BTreeSet<String>,
+    scene_packages: &BTreeMap<String, BTreeSet<String>>,
+) -> String {
+    let unclaimed: BTreeSet<&String> =
+        subpackages.iter().filter(|name| !scene_packages.values().any(|used| used.contains(*name))).collect();
+    let scene_names: Vec<String> = ctx.scenes.iter().map(|s| ctx.scene_name(s)).collect();
+    let first_scene = scene_names.first().cloned().unwrap_or_default();
+    let config = super::runtime_config(ctx);
+    let physics_config = json!({
+        "gravity": {
+            "x": config.get("physicsGravityX").cloned().unwrap_or(json!(0)),
+            "y": config.get("physicsGravityY").cloned().unwrap_or(json!(-9.81)),
+        },
+        "fixedTimestep": config.get("physicsFixedTimestep"),
+        "subStepCount": config.get("physicsSubStepCount"),
+    });
+    let user_code = user_code.map(String::from_utf8_lossy).unwrap_or_default();
+
+    format!(
+        r#"
+{prelude}
+{defines}
+var ESEngineModule = require('./esengine.js');
+var SDK = require('./sdk.js');
+globalThis.__esengine_sdk = SDK;
+globalThis.__esengine_shim__ = {{ esengine: SDK }};
+
+{user_code}
+
+var subpackageLoads = {{}};
+var scenePackages = {scene_packages};
+
+function loadSubpackage(name) {{
+    if (!subpackageLoads[name]) {{
+        subpackageLoads[name] = new Promise(function(resolve, reject) {{
+            {api}.loadSubpackage({{ name: name, success: resolve, fail: reject }});
+        }}).catch(function(err) {{
+            delete subpackageLoads[name];
+            throw err;
+        }});
+    }}
+    return subpackageLoads[name];
+}}
+
+function loadSceneSubpackages(scene) {{
+    return Promise.all((scenePackages[scene] || []).map(loadSubpackage));
+}}
+
+(async function() {{
+    try {{
+        await SDK.initWeChatRuntime({{
+            engineFactory: ESEngineModule,
+            sceneNames: {scene_names},
+            firstScene: {first_scene},
+            runtimeConfig: {config},
+            physicsConfig: {physics_config},
+            {physics_factory}
+            beforeSceneLoad: loadSceneSubpackages,
+        }});
+        {unclaimed}.forEach(function(name) {{
+            loadSubpackage(name).catch(function(err) {{
+                console.warn('[ESEngine] Failed to load subpackage ' + name + ':', err);
+            }});
+        }});
+    }} catch (err) {{
+        console.error('[ESEngine] Runtime init error:', err);
+    }}
+}})();
+"#,
+        prelude = platform.adapter_prelude,
+        defines = ctx.build_define_script(),
+        api = platform.api,
+        user_code = user_code,
+        scene_packages = json!(scene_packages),
+        unclaimed = json!(unclaimed),
+        scene_names = json!(scene_names),
+        first_scene = json!(first_scene),
+        config = config,
+        physics_config = physics_config,
+        physics_factory = if has_physics { "physicsFactory: require('./physics.js')," } else { "" },
+    )
+}
@@ -0,0 +1,1026 @@
+//! Export pipeline — builds distributable game output from a project
+//!
+//! Runs in staged order (prepare → assets → runtime → html → complete),
+//! reporting each stage through `export-progress` events. Platform specific
+//! output is produced by the targets in the `targets` registry.
+
+mod android;
+pub mod archive;
+pub(crate) mod assets;
+pub(crate) mod build_info;
+mod desktop;
+mod douyin;
+pub(crate) mod icons;
+mod incremental;
+mod minigame;
+mod offline;
+pub mod plugin;
+pub mod profiles;
+mod pwa;
+mod report;
+pub mod reproducible;
+mod single_file;
+mod source_maps;
+pub mod targets;
+mod web;
+mod wechat;
+
+use crate::embedded_assets::Embedded;
+use crate::engines::{self, Engine};
+use crate::jobs::{JobCategory, JobContext, JobSpec};
+use crate::packaging::ShellWindowConfig;
+use crate::scene::prefab::{PrefabCache, PrefabResolver};
+use archive::PackageResult;
+use assets::AssetDatabase;
+use build_info::BuildInfo;
+use incremental::IncrementalBuild;
+use report::SizeReport;
+use targets::TargetRegistry;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub project_dir: String,
+    pub output_dir: String,
+    #[serde(default = "default_target")]
+    pub target: String,
+    /// Project-relative scene paths; the project's default scene is used when empty.
+    #[serde(default)]
+    pub scenes: Vec<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Compiled engine build to ship instead of the embedded runtime.
+    #[serde(default)]
+    pub engine_js_path: Option<String>,
+    #[serde(default)]
+    pub engine_wasm_path: Option<String>,
+    #[serde(default)]
+    pub physics_js_path: Option<String>,
+    #[serde(default)]
+    pub physics_wasm_path: Option<String>,
+    /// Prebuilt user script bundle; scripts are compiled from `src/` when omitted.
+    #[serde(default)]
+    pub scripts_path: Option<String>,
+    #[serde(default)]
+    pub single_file: SingleFileOptions,
+    #[serde(default)]
+    pub offline: OfflineOptions,
+    #[serde(default)]
+    pub wechat: MiniGameOptions,
+    #[serde(default)]
+    pub douyin: MiniGameOptions,
+    #[serde(default)]
+    pub pwa: PwaOptions,
+    #[serde(default)]
+    pub desktop: DesktopOptions,
+    #[serde(default)]
+    pub android: AndroidOptions,
+    /// Build profile this export runs under, if any.
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub debug: bool,
+    /// Substituted as `%NAME%` in generated index.html / game.json and exposed
+    /// to the game as `__ESENGINE_BUILD__`.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Discards the previous output instead of reusing unchanged assets.
+    #[serde(default)]
+    pub clean: bool,
+    /// Checksum manifest and archives produced after the export.
+    #[serde(default)]
+    pub archive: ArchiveOptions,
+    #[serde(default)]
+    pub source_maps: SourceMapOptions,
+    /// Takes the build time from `SOURCE_DATE_EPOCH` or the last commit so
+    /// two exports of the same commit are byte-identical.
+    #[serde(default)]
+    pub reproducible: bool,
+    /// Uses this build number instead of the project's counter, which is
+    /// then left unchanged.
+    #[serde(default)]
+    pub build_number: Option<u64>,
+    /// Settings for plugin targets, keyed by target id.
+    #[serde(default)]
+    pub plugins: BTreeMap<String, Value>,
+}
+
+fn default_target() -> String {
+    "web".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SingleFileOptions {
+    /// Assets up to this size (bytes) are embedded in the HTML.
+    pub inline_limit: u64,
+    /// Embed assets above `inline_limit` too instead of shipping them next to the HTML.
+    pub inline_large_assets: bool,
+    /// Emits a warning when the generated HTML exceeds this size (bytes).
+    pub size_warning: u64,
+}
+
+impl Default for SingleFileOptions {
+    fn default() -> Self {
+        Self {
+            inline_limit: 512 * 1024,
+            inline_large_assets: false,
+            size_warning: 20 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineOptions {
+    /// Target size (bytes) of the `data/chunk-N.js` scripts holding assets
+    /// above the single-file inline limit.
+    pub chunk_size: u64,
+}
+
+impl Default for OfflineOptions {
+    fn default() -> Self {
+        Self { chunk_size: 8 * 1024 * 1024 }
+    }
+}
+
+/// Settings shared by the mini-game targets (WeChat, Douyin).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MiniGameOptions {
+    pub app_id: String,
+    pub orientation: String,
+    /// Explicit folder-to-subpackage assignments, checked before scene usage.
+    pub subpackages: Vec<SubpackageRule>,
+    /// Moves assets used by a single non-startup scene into a per-scene subpackage.
+    pub split_by_scene: bool,
+    /// Overrides the platform's main package limit (bytes).
+    pub main_package_limit: Option<u64>,
+    /// Overrides the platform's total size limit (bytes).
+    pub total_limit: Option<u64>,
+    /// Runtime adapter bundle to ship instead of the platform default.
+    pub adapter_path: Option<String>,
+    pub debug: bool,
+}
+
+impl Default for MiniGameOptions {
+    fn default() -> Self {
+        Self {
+            app_id: String::new(),
+            orientation: "portrait".to_string(),
+            subpackages: Vec::new(),
+            split_by_scene: true,
+            main_package_limit: None,
+            total_limit: None,
+            adapter_path: None,
+            debug: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubpackageRule {
+    pub name: String,
+    /// Project-relative folders whose assets belong to this subpackage.
+    pub folders: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PwaOptions {
+    /// Square source image (absolute or project-relative), ideally 512px or larger.
+    pub icon_path: Option<String>,
+    pub short_name: Option<String>,
+    pub display: String,
+    pub orientation: String,
+    pub background_color: String,
+    pub theme_color: String,
+}
+
+impl Default for PwaOptions {
+    fn default() -> Self {
+        Self {
+            icon_path: None,
+            short_name: None,
+            display: "fullscreen".to_string(),
+            orientation: "any".to_string(),
+            background_color: "#000000".to_string(),
+            theme_color: "#000000".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceMapOptions {
+    /// `strip` drops maps, `private` saves them outside the output, `upload`
+    /// sends them to an error-tracking service. Shipped scripts never
+    /// reference a map.
+    pub mode: String,
+    /// Project-relative folder for `private`; defaults to
+    /// `.esengine/sourcemaps/<target>-<build number>`.
+    pub private_dir: Option<String>,
+    pub upload: Option<SourceMapUpload>,
+}
+
+impl Default for SourceMapOptions {
+    fn default() -> Self {
+        Self {
+            mode: "strip".to_string(),
+            private_dir: None,
+            upload: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceMapUpload {
+    /// Endpoint receiving one multipart POST per map; build variables such as
+    /// `%VERSION%` are substituted.
+    pub url: String,
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    /// Prefix of the uploaded artifact name.
+    #[serde(default = "default_url_prefix")]
+    pub url_prefix: String,
+}
+
+fn default_url_prefix() -> String {
+    "~/".to_string()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveOptions {
+    /// Archives to produce: `zip` and/or `tar.gz`.
+    pub formats: Vec<String>,
+    /// Writes `manifest.json` with the size and SHA-256 of every output file.
+    pub checksums: bool,
+    /// Archive file name without extension; defaults to the output folder name.
+    pub name: Option<String>,
+    /// Archive directory; defaults to the output folder's parent.
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopOptions {
+    /// `windows` or `macos`.
+    pub platform: String,
+    /// Runtime shell executable to stamp instead of the bundled one.
+    pub shell_path: Option<String>,
+    pub icon_path: Option<String>,
+    pub identifier: Option<String>,
+    pub version: Option<String>,
+    pub window: ShellWindowConfig,
+    /// macOS code signing identity, e.g. `Developer ID Application: ...`.
+    pub signing_identity: Option<String>,
+}
+
+impl Default for DesktopOptions {
+    fn default() -> Self {
+        Self {
+            platform: if cfg!(target_os = "macos") { "macos" } else { "windows" }.to_string(),
+            shell_path: None,
+            icon_path: None,
+            identifier: None,
+            version: None,
+            window: ShellWindowConfig::default(),
+            signing_identity: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AndroidOptions {
+    pub package_id: Option<String>,
+    pub version_code: u32,
+    pub version_name: Option<String>,
+    pub icon_path: Option<String>,
+    /// `apk` or `aab`.
+    pub format: String,
+    pub release: bool,
+    pub min_sdk: u32,
+    pub target_sdk: u32,
+    /// `portrait`, `landscape` or `unspecified`.
+    pub orientation: String,
+    /// Android SDK location; falls back to `ANDROID_HOME` / `ANDROID_SDK_ROOT`.
+    pub sdk_path: Option<String>,
+    /// Gradle executable; falls back to `gradle` on PATH.
+    pub gradle_path: Option<String>,
+    pub signing: Option<AndroidSigning>,
+    /// Only scaffold the wrapper project without invoking Gradle.
+    pub skip_build: bool,
+}
+
+impl Default for AndroidOptions {
+    fn default() -> Self {
+        Self {
+            package_id: None,
+            version_code: 1,
+            version_name: None,
+            icon_path: None,
+            format: "apk".to_string(),
+            release: true,
+            min_sdk: 24,
+            target_sdk: 34,
+            orientation: "portrait".to_string(),
+            sdk_path: None,
+            gradle_path: None,
+            signing: None,
+            skip_build: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AndroidSigning {
+    pub keystore_path: String,
+    pub keystore_password: String,
+    pub key_alias: String,
+    pub key_password: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportResult {
+    pub success: bool,
+    pub output_dir: String,
+    pub files: Vec<ExportedFile>,
+    pub total_size: u64,
+    pub size_report: SizeReport,
+    pub package: Option<PackageResult>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ExportProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+const PROJECT_FILE: &str = "project.esproject";
+const ASSET_EXPORT_CONFIG: &str = ".esengine/asset-export.json";
+
+// =============================================================================
+// Export Listener
+// =============================================================================
+
+/// Receives export events. The editor forwards them to the frontend; the
+/// headless CLI prints them.
+pub trait ExportListener: Sync {
+    fn progress(&self, stage: &str, message: &str, progress: f32);
+    /// A line printed by an external build tool.
+    fn output(&self, stream: &str, line: &str);
+    /// Bundled editor resources, when running inside the app bundle.
+    fn resource_dir(&self) -> Option<PathBuf>;
+}
+
+impl ExportListener for AppHandle {
+    fn progress(&self, stage: &str, message: &str, progress: f32) {
+        let _ = self.emit(
+            "export-progress",
+            ExportProgress {
+                stage: stage.to_string(),
+                message: message.to_string(),
+                progress,
+            },
+        );
+    }
+
+    fn output(&self, stream: &str, line: &str) {
+        let _ = self.emit(
+            "export-output",
+            crate::CommandOutput {
+                stream: stream.to_string(),
+                data: line.to_string(),
+            },
+        );
+    }
+
+    fn resource_dir(&self) -> Option<PathBuf> {
+        self.path().resource_dir().ok()
+    }
+}
+
+/// Forwards to the app and mirrors progress on the export's job.
+pub(crate) struct JobListener<'a> {
+    pub app: &'a AppHandle,
+    pub job: &'a JobContext,
+}
+
+impl ExportListener for JobListener<'_> {
+    fn progress(&self, stage: &str, message: &str, progress: f32) {
+        self.app.progress(stage, message, progress);
+        // Warnings are sent with a negative progress.
+        if progress >= 0.0 {
+            self.job.progress(progress, message);
+        }
+    }
+
+    fn output(&self, stream: &str, line: &str) {
+        self.app.output(stream, line);
+    }
+
+    fn resource_dir(&self) -> Option<PathBuf> {
+        self.app.resource_dir()
+    }
+}
+
+// =============================================================================
+// Export Context
+// =============================================================================
+
+pub(crate) struct ExportContext<'a> {
+    listener: &'a dyn ExportListener,
+    pub options: &'a ExportOptions,
+    pub project_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub project: Value,
+    /// Engine and SDK the project selects.
+    pub engine: Engine,
+    pub db: AssetDatabase,
+    /// Project-relative scene paths in load order.
+    pub scenes: Vec<String>,
+    /// Project-relative assets to ship, excluding scenes.
+    pub assets: BTreeSet<String>,
+    pub warnings: Vec<String>,
+    pub build: IncrementalBuild,
+    pub build_info: BuildInfo,
+    /// Source maps collected for the source map policy, as `(name, map)`.
+    pub source_maps: Vec<(String, Vec<u8>)>,
+    /// Contents embedded in a packaged output (single HTML, desktop executable),
+    /// attributed by the size report instead of the opaque output files.
+    pub packaged: Vec<ExportedFile>,
+}
+
+impl ExportContext<'_> {
+    pub fn progress(&self, stage: &str, message: &str, progress: f32) {
+        self.listener.progress(stage, message, progress);
+    }
+
+    pub fn resource_dir(&self) -> Option<PathBuf> {
+        self.listener.resource_dir()
+    }
+
+    pub fn warn(&mut self, message: String) {
+        self.progress("warning", &message, -1.0);
+        self.warnings.push(message);
+    }
+
+    pub fn write_output(&self, rel: &str, data: &[u8]) -> Result<(), String> {
+        let path = self.output_dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", rel, e))
+    }
+
+    pub fn project_str(&self, key: &str) -> Option<&str> {
+        self.project.get(key).and_then(|v| v.as_str())
+    }
+
+    pub fn title(&self) -> String {
+        self.options
+            .title
+            .clone()
+            .or_else(|| self.project_str("name").map(String::from))
+            .unwrap_or_else(|| "ESEngine Game".to_string())
+    }
+
+    /// Runtime name of a scene: its file stem, or its path without the
+    /// extension (and `assets/`) when another exported scene has that stem.
+    pub fn scene_name(&self, scene: &str) -> String {
+        let stem = assets::file_stem(scene);
+        if self.scenes.iter().filter(|other| assets::file_stem(other) == stem).count() <= 1 {
+            return stem;
+        }
+        let path = scene.strip_prefix("assets/").unwrap_or(scene);
+        match path.rsplit_once('.') {
+            Some((name, extension)) if !extension.contains('/') => name.to_string(),
+            _ => path.to_string(),
+        }
+    }
+
+    /// Output name for a scene: `scenes/<name>.json`.
+    pub fn scene_output_path(&self, scene: &str) -> String {
+        format!("scenes/{}.json", self.scene_name(scene))
+    }
+
+    /// Reads a project JSON asset with UUID references rewritten to paths.
+    pub fn read_rewritten_json(&self, rel: &str) -> Result<Value, String> {
+        let text = std::fs::read_to_string(self.project_dir.join(rel))
+            .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let mut value: Value =
+            serde_json::from_str(&text).map_err(|e| format!("Invalid JSON in {}: {}", rel, e))?;
+        assets::rewrite_refs(&mut value, &self.db);
+        Ok(value)
+    }
+
+    /// Resolves an optional override path, falling back to the project
+    /// engine's copy of `file`.
+    pub fn read_override(&self, path: &Option<String>, file: Embedded) -> Result<Vec<u8>, String> {
+        match path {
+            Some(p) => std::fs::read(p).map_err(|e| format!("Failed to read {}: {}", p, e)),
+            None => self.engine.read(file).map(Cow::into_owned),
+        }
+    }
+
+    /// Built-in build variables merged with the user-defined ones.
+    pub fn build_variables(&self) -> BTreeMap<String, String> {
+        let mut vars = BTreeMap::new();
+        vars.insert("PROFILE".to_string(), self.options.profile.clone().unwrap_or_default());
+        vars.insert("TARGET".to_string(), self.options.target.clone());
+        vars.insert("TITLE".to_string(), self.title());
+        vars.insert("VERSION".to_string(), self.build_info.version.clone());
+        vars.insert("BUILD_NUMBER".to_string(), self.build_info.build_number.to_string());
+        vars.insert("COMMIT".to_string(), self.build_info.commit.clone().unwrap_or_default());
+        vars.insert("BUILD_TIME".to_string(), self.build_info.timestamp.clone());
+        vars.insert("DEBUG".to_string(), self.options.debug.to_string());
+        vars.extend(self.options.variables.clone());
+        vars
+    }
+
+    /// Replaces `%NAME%` tokens with build variables; unknown tokens are kept.
+    pub fn substitute_variables(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (name, value) in self.build_variables() {
+            out = out.replace(&format!("%{}%", name), &value);
+        }
+        out
+    }
+
+    /// JavaScript statement exposing the build variables to the game.
+    pub fn build_define_script(&self) -> String {
+        format!(
+            "globalThis.__ESENGINE_BUILD__ = {};",
+            json!(self.build_variables()).to_string().replace("</", "<\\/")
+        )
+    }
+
+    /// Reverse-DNS identifier derived from the title, e.g. `com.esengine.mygame`.
+    pub fn default_identifier(&self) -> String {
+        let slug: String = self
+            .title()
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        let slug = match slug.chars().next() {
+            None => "game".to_string(),
+            Some(c) if c.is_ascii_digit() => format!("game{}", slug),
+            Some(_) => slug,
+        };
+        format!("com.esengine.{}", slug)
+    }
+
+    /// Runs an external build tool, streaming its output to the listener.
+    pub fn run_tool(
+        &self,
+        program: &Path,
+        args: &[String],
+        cwd: &Path,
+        env: &[(String, String)],
+    ) -> Result<(), String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(cwd)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn {}: {}", program.display(), e))?;
+
+        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+        let listener = self.listener;
+        let stderr_tail = std::thread::scope(|scope| {
+            let stderr_thread = scope.spawn(move || {
+                let mut tail = Vec::new();
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    listener.output("stderr", &line);
+                    tail.push(line);
+                }
+                tail
+            });
+            if let Some(stdout) = child.stdout.take() {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    listener.output("stdout", &line);
+                }
+            }
+            stderr_thread.join().unwrap_or_default()
+        });
+
+        let status = child.wait().map_err(|e| e.to_string())?;
+        if status.success() {
+            return Ok(());
+        }
+        let last = stderr_tail.iter().rev().take(10).rev().cloned().collect::<Vec<_>>().join("\n");
+        Err(format!(
+            "{} failed (exit code: {})\n{}",
+            program.display(),
+            status.code().unwrap_or(-1),
+            last
+        ))
+    }
+
+    /// User scripts: the prebuilt bundle if given, otherwise compiled with the
+    /// built-in bundler (minified unless this is a debug build).
+    pub fn user_scripts(&mut self) -> Result<Option<Vec<u8>>, String> {
+        if let Some(p) = self.options.scripts_path.clone() {
+            let code = std::fs::read(&p).map_err(|e| format!("Failed to read scripts {}: {}", p, e))?;
+            let map = std::fs::read(format!("{}.map", p)).ok();
+            return Ok(Some(source_maps::strip(self, "game.js", &code, map.as_deref())));
+        }
+        match crate::bundler::bundle_project(&self.project_dir, !self.options.debug) {
+            Ok(bundle) => Ok(bundle.map(|b| b.code.into_bytes())),
+            Err(errors) => {
+                let details: Vec<String> = errors
+                    .iter()
+                    .map(|e| format!("{}:{}:{}: {}", e.file, e.line, e.column, e.message))
+                    .collect();
+                Err(format!("Script compilation failed:\n{}", details.join("\n")))
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn build_export(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    options: ExportOptions,
+) -> Result<ExportResult, String> {
+    let spec = JobSpec::new(JobCategory::Build, format!("Export {}", options.target)).project(&options.project_dir);
+    let listener_app = app.clone();
+    state
+        .jobs
+        .run(&app, spec, move |job| run_export(&JobListener { app: &listener_app, job }, &options))
+        .await
+}
+
+// =============================================================================
+// Pipeline
+// =============================================================================
+
+pub fn run_export(listener: &dyn ExportListener, options: &ExportOptions) -> Result<ExportResult, String> {
+    let mut ctx = prepare(listener, options)?;
+
+    ctx.progress("assets", "Collecting assets...", 0.1);
+    collect_assets(&mut ctx)?;
+
+    let mut plugin_warnings = Vec::new();
+    let registry = TargetRegistry::discover(&ctx.project_dir, |w| plugin_warnings.push(w));
+    for warning in plugin_warnings {
+        ctx.warn(warning);
+    }
+    let target = registry
+        .get(&options.target)
+        .ok_or_else(|| format!("Unknown export target: {}", options.target))?;
+    target.emit(&mut ctx)?;
+
+    ctx.progress("complete", "Export complete!", 1.0);
+    finish(ctx)
+}
+
+fn prepare<'a>(listener: &'a dyn ExportListener, options: &'a ExportOptions) -> Result<ExportContext<'a>, String> {
+    let project_dir = PathBuf::from(&options.project_dir);
+    let output_dir = PathBuf::from(&options.output_dir);
+
+    let project_file = project_dir.join(PROJECT_FILE);
+    let project: Value = std::fs::read_to_string(&project_file)
+        .map_err(|e| format!("Failed to read {}: {}", project_file.display(), e))
+        .and_then(|s| serde_json::from_str(&s).map_err(|e| format!("Invalid project file: {}", e)))?;
+
+    check_output_dir(&project_dir, &output_dir)?;
+
+    let engine = Engine::for_project(&project_dir)?;
+    if let Engine::Installed { version, .. } = &engine {
+        listener.progress("prepare", &format!("Using engine {}", version), 0.0);
+    }
+    let mut warnings = Vec::new();
+    if let Some(dir) = engines::dev_dir() {
+        let warning = format!("Built with the local engine build in {}", dir.display());
+        listener.progress("warning", &warning, -1.0);
+        warnings.push(warning);
+    }
+
+    let build = IncrementalBuild::load(options, &project_dir, &output_dir);
+    let version = project.get("version").and_then(|v| v.as_str()).unwrap_or("");
+    let build_info = BuildInfo::collect(&project_dir, version, options);
+    let ctx = ExportContext {
+        listener,
+        options,
+        project_dir,
+        output_dir,
+        project,
+        engine,
+        db: AssetDatabase::default(),
+        scenes: Vec::new(),
+        assets: BTreeSet::new(),
+        warnings,
+        build,
+        build_info,
+        source_maps: Vec::new(),
+        packaged: Vec::new(),
+    };
+
+    ctx.progress("prepare", "Preparing output directory...", 0.0);
+    if !ctx.build.full {
+        ctx.build.prune()?;
+    } else if ctx.output_dir.exists() {
+        // Only a folder this project exported to before, or an empty one,
+        // is cleared; anything else may be the user's own files.
+        let empty = std::fs::read_dir(&ctx.output_dir).is_ok_and(|mut entries| entries.next().is_none());
+        if !ctx.build.owned && !empty {
+            return Err(format!(
+                "{} is not empty and was not exported from this project; choose an empty folder",
+                ctx.output_dir.display()
+            ));
+        }
+        std::fs::remove_dir_all(&ctx.output_dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&ctx.output_dir).map_err(|e| e.to_string())?;
+
+    Ok(ctx)
+}
+
+/// Project folders no export may write into.
+const SOURCE_DIRS: &[&str] = &["src", "assets", ".esengine"];
+
+/// Refuses an output folder that contains the project or lies in one of its
+/// source folders, comparing resolved paths so links and `..` cannot hide it.
+fn check_output_dir(project_dir: &Path, output_dir: &Path) -> Result<(), String> {
+    let project = resolve_path(project_dir);
+    let output = resolve_path(output_dir);
+    if project.starts_with(&output) {
+        return Err(format!("Output directory must not contain the project: {}", output_dir.display()));
+    }
+    if let Some(dir) = SOURCE_DIRS.iter().find(|dir| output.starts_with(project.join(dir))) {
+        return Err(format!(
+            "Output directory must not be inside the project's {} folder: {}",
+            dir,
+            output_dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// `path` made absolute with links resolved, for the part that exists.
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(resolved) = std::fs::canonicalize(existing) {
+            return missing.iter().rev().fold(resolved, |path, part| path.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn collect_assets(ctx: &mut ExportContext) -> Result<(), String> {
+    ctx.db = AssetDatabase::scan(&ctx.project_dir);
+
+    let mut scenes = ctx.options.scenes.clone();
+    if scenes.is_empty() {
+        if let Some(default_scene) = ctx.project_str("defaultScene") {
+            scenes.push(default_scene.to_string());
+        }
+    }
+    if scenes.is_empty() {
+        return Err("No scenes to export. Add a scene to the build list.".to_string());
+    }
+    for scene in &scenes {
+        if !ctx.project_dir.join(scene).is_file() {
+            return Err(format!("Scene not found: {}", scene));
+        }
+    }
+
+    let mut found = assets::collect_references(&ctx.project_dir, &ctx.db, &scenes);
+    apply_folder_modes(ctx, &mut found);
+    found.retain(|p| assets::extension_of(p) != "esscene" || scenes.contains(p));
+    for scene in &scenes {
+        found.remove(scene);
+    }
+
+    // Broken variant or nested prefab chains would otherwise only fail at runtime.
+    let broken: Vec<String> = {
+        let prefab_cache = PrefabCache::default();
+        let mut resolver = PrefabResolver::new(&ctx.project_dir, &prefab_cache);
+        found
+            .iter()
+            .filter(|p| assets::extension_of(p) == "esprefab")
+            .filter_map(|p| resolver.resolve(p, &[], 1).err().map(|e| format!("Prefab {}: {}", p, e)))
+            .collect()
+    };
+    for warning in broken {
+        ctx.warn(warning);
+    }
+
+    ctx.progress(
+        "assets",
+        &format!("Found {} scene(s) and {} asset(s)", scenes.len(), found.len()),
+        0.15,
+    );
+    ctx.scenes = scenes;
+    ctx.assets = found;
+    Ok(())
+}
+
+/// Honors the per-folder `always`/`exclude` modes from the Content Browser.
+fn apply_folder_modes(ctx: &ExportContext, found: &mut BTreeSet<String>) {
+    let folders = std::fs::read_to_string(ctx.project_dir.join(ASSET_EXPORT_CONFIG))
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        .and_then(|v| v.get("folders").and_then(|f| f.as_object()).cloned())
+        .unwrap_or_default();
+
+    for (folder, mode) in &folders {
+        let prefix = format!("{}/", folder.trim_end_matches('/'));
+        match mode.as_str() {
+            Some("always") => {
+                for file in assets::walk_files(&ctx.project_dir.join(folder)) {
+                    let rel = assets::relative_path(&ctx.project_dir, &file);
+                    if assets::is_asset_path(&rel) {
+                        found.insert(rel);
+                    }
+                }
+            }
+            Some("exclude") => found.retain(|p| !p.starts_with(&prefix)),
+            _ => {}
+        }
+    }
+}
+
+// =============================================================================
+// Shared Stages
+// =============================================================================
+
+/// Copies collected assets into the output, rewriting references in JSON assets.
+/// Binary assets unchanged since the previous build are left in place.
+pub(crate) fn copy_assets(ctx: &mut ExportContext, progress_from: f32, progress_to: f32) -> Result<(), String> {
+    let total = ctx.assets.len().max(1) as f32;
+    for (i, rel) in ctx.assets.iter().enumerate() {
+        if assets::is_json_asset(rel) {
+            let value = ctx.read_rewritten_json(rel)?;
+            let data = serde_json::to_vec(&value).map_err(|e| e.to_string())?;
+            ctx.write_output(rel, &data)?;
+        } else if !ctx.build.reuse(&ctx.project_dir, rel, &ctx.output_dir.join(rel)) {
+            let data = std::fs::read(ctx.project_dir.join(rel))
+                .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            ctx.write_output(rel, &data)?;
+        }
+
+        if i % 16 == 0 {
+            let p = progress_from + (progress_to - progress_from) * (i as f32 / total);
+            ctx.progress("assets", &format!("Copying {}", rel), p);
+        }
+    }
+    Ok(())
+}
+
+/// Writes every scene as `scenes/<name>.json` with references rewritten.
+pub(crate) fn write_scenes(ctx: &ExportContext) -> Result<(), String> {
+    for scene in &ctx.scenes {
+        let value = ctx.read_rewritten_json(scene)?;
+        let data = serde_json::to_vec(&value).map_err(|e| e.to_string())?;
+        ctx.write_output(&ctx.scene_output_path(scene), &data)?;
+    }
+    Ok(())
+}
+
+/// Generates `asset-manifest.json` in the addressable manifest format used by the SDK.
+pub(crate) fn write_asset_manifest(ctx: &ExportContext) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(&asset_manifest(ctx)).map_err(|e| e.to_string())?;
+    ctx.write_output("asset-manifest.json", &data)
+}
+
+pub(crate) fn asset_manifest(ctx: &ExportContext) -> Value {
+    let mut entries = serde_json::Map::new();
+    for rel in &ctx.assets {
+        let (Some(uuid), Some(kind)) = (ctx.db.uuid_of(rel), addressable_type(rel)) else {
+            continue;
+        };
+        let size = std::fs::metadata(ctx.project_dir.join(rel)).map(|m| m.len()).unwrap_or(0);
+        entries.insert(
+            uuid.to_string(),
+            json!({ "path": rel, "type": kind, "size": size, "labels": [] }),
+        );
+    }
+    json!({
+        "version": "2.0",
+        "groups": {
+            "default": { "bundleMode": "together", "labels": [], "assets": entries },
+        },
+    })
+}
+
+fn addressable_type(rel: &str) -> Option<&'static str> {
+    match assets::extension_of(rel).as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" => Some("texture"),
+        "mp3" | "wav" | "ogg" | "aac" | "flac" | "webm" => Some("audio"),
+        "esmaterial" => Some("material"),
+        "atlas" => Some("binary"),
+        "skel" => Some("spine"),
+        "json" | "tmj" | "estimeline" => Some("json"),
+        "bmfont" | "fnt" => Some("bitmap-font"),
+        "esprefab" => Some("prefab"),
+        _ => None,
+    }
+}
+
+/// Runtime settings forwarded from the project file into the exported game.
+pub(crate) fn runtime_config(ctx: &ExportContext) -> Value {
+    const KEYS: &[&str] = &[
+        "designResolution",
+        "enablePhysics",
+        "physicsGravityX",
+        "physicsGravityY",
+        "physicsFixedTimestep",
+        "physicsSubStepCount",
+        "physicsContactHertz",
+        "physicsContactDampingRatio",
+        "physicsContactSpeed",
+        "physicsBackend",
+        "collisionLayerMasks",
+        "maxDeltaTime",
+        "maxFixedSteps",
+        "textCanvasSize",
+        "defaultFontFamily",
+        "sceneTransitionDuration",
+        "sceneTransitionColor",
+        "canvasScaleMode",
+        "canvasMatchWidthOrHeight",
+    ];
+    let mut config = serde_json::Map::new();
+    for key in KEYS {
+        if let Some(v) = ctx.project.get(*key) {
+            config.insert(key.to_string(), v.clone());
+        }
+    }
+    Value::Object(config)
+}
+
+fn finish(mut ctx: ExportContext) -> Result<ExportResult, String> {
+    source_maps::flush(&mut ctx)?;
+    ctx.build.save()?;
+    if ctx.build.reused > 0 {
+        ctx.progress("complete", &format!("Reused {} unchanged asset(s)", ctx.build.reused), 1.0);
+    }
+
+    let archive = &ctx.options.archive;
+    let package = if archive.checksums || !archive.formats.is_empty() {
+        ctx.progress("package", "Packaging build output...", 1.0);
+        Some(archive::package(&ctx.output_dir, archive)?)
+    } else {
+        None
+    };
+
+    let files: Vec<ExportedFile> = assets::walk_files(&ctx.output_dir)
+        .into_iter()
+        .map(|p| ExportedFile {
+            size: std::fs::metadata(&p).map(|m| m.len()).unwrap_or(0),
+            path: assets::relative_path(&ctx.output_dir, &p),
+        })
+        .collect();
+    let total_size = files.iter().map(|f| f.size).sum();
+    let size_report = report::size_report(&mut ctx, &files)?;
+    ctx.build_info.save_number(&ctx.project_dir)?;
+
+    Ok(ExportResult {
+        success: true,
+        output_dir: ctx.output_dir.to_string_lossy().to_string(),
+        files,
+        total_size,
+        size_report,
+        package,
+        warnings: ctx.warnings,
+    })
+}
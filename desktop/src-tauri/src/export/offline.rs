@@ -0,0 +1,90 @@
+//! Offline HTML5 target — a game that runs by opening `index.html` from disk
+//!
+//! Under `file://` browsers block `fetch`, module scripts loaded from files
+//! and WASM streaming, so the web target needs a server. This target builds
+//! on the single-file layout, where everything runs from inline scripts and
+//! data URLs, and moves assets too large to inline into classic script
+//! chunks under `data/`, which load from disk. A smoke check then rejects
+//! output that would still need a server.
+
+use super::single_file::{self, Overflow};
+use super::ExportContext;
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    let chunk_size = ctx.options.offline.chunk_size.max(1);
+    let output = single_file::build(ctx, Overflow::Chunks(chunk_size))?;
+
+    ctx.progress("verify", "Checking file:// compatibility...", 0.95);
+    let problems = smoke_check(ctx, &output.html);
+    if !problems.is_empty() {
+        return Err(format!("Output would not run from file://:\n{}", problems.join("\n")));
+    }
+    if let Some(scripts) = &output.scripts {
+        let code = String::from_utf8_lossy(scripts);
+        for (pattern, api) in [("fetch(", "fetch()"), ("XMLHttpRequest", "XMLHttpRequest"), ("new Worker(", "Workers")] {
+            if code.contains(pattern) {
+                ctx.warn(format!(
+                    "Game scripts use {}, which cannot load local files under file://",
+                    api
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the page and its files for anything a `file://` page cannot load.
+fn smoke_check(ctx: &ExportContext, html: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for url in attribute_values(html, &["src=", "href="]) {
+        if url.starts_with("data:") || url.starts_with('#') {
+            continue;
+        }
+        if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("//") {
+            problems.push(format!("index.html loads {} from the network", url));
+        } else if url.starts_with('/') {
+            problems.push(format!("index.html uses the root-relative path {}", url));
+        } else if !ctx.output_dir.join(url.split(['?', '#']).next().unwrap_or(url)).is_file() {
+            problems.push(format!("index.html references missing file {}", url));
+        }
+    }
+    if html.contains("<script type=\"module\" src=") {
+        problems.push("index.html loads a module script from a file".to_string());
+    }
+    for part in ctx.packaged.iter().filter(|p| p.path.starts_with("data/")) {
+        if !ctx.output_dir.join(&part.path).is_file() {
+            problems.push(format!("Asset chunk {} was not written", part.path));
+        }
+    }
+    problems
+}
+
+/// Values of the given attributes in the markup, skipping inline script bodies.
+fn attribute_values<'a>(html: &'a str, attributes: &[&str]) -> Vec<&'a str> {
+    let mut parts = html.split("<script");
+    let mut markup: Vec<&str> = parts.next().into_iter().collect();
+    for part in parts {
+        let (tag, rest) = part.split_once('>').unwrap_or((part, ""));
+        markup.push(tag);
+        markup.push(rest.split_once("</script>").map_or("", |(_, after)| after));
+    }
+
+    let mut values = Vec::new();
+    for segment in markup {
+        for attribute in attributes {
+            let mut rest = segment;
+            while let Some(pos) = rest.find(attribute) {
+                rest = &rest[pos + attribute.len()..];
+                let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                    continue;
+                };
+                if let Some(end) = rest[1..].find(quote) {
+                    values.push(&rest[1..=end]);
+                    rest = &rest[end + 1..];
+                }
+            }
+        }
+    }
+    values
+}
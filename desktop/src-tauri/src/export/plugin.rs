@@ -0,0 +1,321 @@
+//! Export target plugins — dynamic library and WASM targets
+//!
+//! A plugin is a folder with a `target.json` manifest:
+//!
+//! ```json
+//! { "id": "acme-launcher", "name": "ACME Launcher", "base": "web", "entry": "acme_launcher" }
+//! ```
+//!
+//! When `base` names a built-in target, its output is written first and the
+//! plugin post-processes it. `entry` is a `.wasm` module or a dynamic library;
+//! without an extension the platform's library naming is applied
+//! (`libacme_launcher.so`, `acme_launcher.dll`, `libacme_launcher.dylib`).
+//!
+//! Both kinds receive a JSON request and return a JSON response:
+//!
+//! - request: `{abi, target, project_dir, output_dir, debug, profile, variables, options}`
+//!   where `options` is `ExportOptions.plugins[<id>]`
+//! - response: `{error?, warnings?}`; a non-empty `error` fails the export
+//!
+//! Dynamic libraries export
+//! `char* esengine_target_emit(const char* request, void* host, void (*log)(void* host, const char* message))`
+//! and `void esengine_target_free(char* response)`, and access the file
+//! system directly.
+//!
+//! WASM modules are sandboxed. They export `memory`,
+//! `esengine_alloc(len: i32) -> i32` and
+//! `esengine_target_emit(ptr: i32, len: i32) -> i64` (response as
+//! `ptr << 32 | len`), and may import from module `esengine`:
+//! `log(ptr, len)`, `read_file(path_ptr, path_len) -> i64` (`-1` when missing)
+//! and `write_file(path_ptr, path_len, data_ptr, data_len) -> i32` (`0` on
+//! success). Paths are relative to the output folder.
+
+use super::targets::{builtin_emit, EmitFn, ExportTarget, MANIFEST_FILE};
+use super::ExportContext;
+use serde::Deserialize;
+use serde_json::json;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Component, Path, PathBuf};
+
+pub const ABI_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+struct PluginManifest {
+    id: String,
+    name: String,
+    /// Built-in target whose output the plugin starts from.
+    #[serde(default)]
+    base: Option<String>,
+    entry: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginResponse {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    warnings: Vec<String>,
+}
+
+enum PluginEntry {
+    Library(PathBuf),
+    Wasm(PathBuf),
+}
+
+pub(crate) struct PluginTarget {
+    manifest: PluginManifest,
+    dir: PathBuf,
+    base: Option<EmitFn>,
+    entry: PluginEntry,
+}
+
+impl PluginTarget {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let manifest: PluginManifest = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e)))?;
+        if manifest.id.is_empty() {
+            return Err("Target id must not be empty".to_string());
+        }
+
+        let base = match manifest.base.as_deref() {
+            Some(base) => Some(builtin_emit(base).ok_or_else(|| format!("Unknown base target: {}", base))?),
+            None => None,
+        };
+        let entry = entry_path(dir, &manifest.entry);
+        if !entry.is_file() {
+            return Err(format!("Plugin entry not found: {}", entry.display()));
+        }
+        let entry = if entry.extension().is_some_and(|e| e == "wasm") {
+            PluginEntry::Wasm(entry)
+        } else {
+            PluginEntry::Library(entry)
+        };
+        Ok(Self { manifest, dir: dir.to_path_buf(), base, entry })
+    }
+}
+
+impl ExportTarget for PluginTarget {
+    fn id(&self) -> &str {
+        &self.manifest.id
+    }
+
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn source(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+
+    fn emit(&self, ctx: &mut ExportContext) -> Result<(), String> {
+        if let Some(base) = self.base {
+            base(ctx)?;
+        }
+        ctx.progress("plugin", &format!("Running {} target...", self.manifest.name), 0.85);
+
+        let request = json!({
+            "abi": ABI_VERSION,
+            "target": self.manifest.id,
+            "project_dir": ctx.project_dir.to_string_lossy(),
+            "output_dir": ctx.output_dir.to_string_lossy(),
+            "debug": ctx.options.debug,
+            "profile": ctx.options.profile,
+            "variables": ctx.build_variables(),
+            "options": ctx.options.plugins.get(&self.manifest.id),
+        })
+        .to_string();
+
+        let raw = match &self.entry {
+            PluginEntry::Library(path) => call_library(ctx, path, &request)?,
+            PluginEntry::Wasm(path) => call_wasm(ctx, path, &request)?,
+        };
+        let response: PluginResponse = if raw.trim().is_empty() {
+            PluginResponse::default()
+        } else {
+            serde_json::from_str(&raw).map_err(|e| format!("Invalid response from {}: {}", self.manifest.id, e))?
+        };
+        for warning in response.warnings {
+            ctx.warn(format!("{}: {}", self.manifest.id, warning));
+        }
+        match response.error.filter(|e| !e.is_empty()) {
+            Some(error) => Err(format!("{} target failed: {}", self.manifest.name, error)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn entry_path(dir: &Path, entry: &str) -> PathBuf {
+    let path = dir.join(entry);
+    if path.extension().is_some() {
+        return path;
+    }
+    let file = format!("{}{}{}", std::env::consts::DLL_PREFIX, entry, std::env::consts::DLL_SUFFIX);
+    path.with_file_name(file)
+}
+
+// =============================================================================
+// Dynamic libraries
+// =============================================================================
+
+type EmitSymbol = unsafe extern "C" fn(*const c_char, *mut c_void, LogCallback) -> *mut c_char;
+type FreeSymbol = unsafe extern "C" fn(*mut c_char);
+type LogCallback = extern "C" fn(*mut c_void, *const c_char);
+
+extern "C" fn library_log(host: *mut c_void, message: *const c_char) {
+    if host.is_null() || message.is_null() {
+        return;
+    }
+    // SAFETY: `host` is the `ExportContext` passed to `esengine_target_emit`,
+    // which is only valid for the duration of that call; `message` is a
+    // NUL-terminated string owned by the plugin.
+    let (ctx, message) = unsafe { (&*(host as *const ExportContext), CStr::from_ptr(message)) };
+    ctx.progress("plugin", &message.to_string_lossy(), 0.9);
+}
+
+fn call_library(ctx: &ExportContext, path: &Path, request: &str) -> Result<String, String> {
+    let request = CString::new(request).map_err(|e| e.to_string())?;
+    // SAFETY: loading a plugin runs its initializers; plugins are trusted
+    // code installed by the user, like editor extensions.
+    let library = unsafe { libloading::Library::new(path) }
+        .map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+    // SAFETY: the symbol signatures are the documented plugin ABI.
+    unsafe {
+        let emit: libloading::Symbol<EmitSymbol> = library
+            .get(b"esengine_target_emit\0")
+            .map_err(|e| format!("Missing esengine_target_emit: {}", e))?;
+        let free: Option<libloading::Symbol<FreeSymbol>> = library.get(b"esengine_target_free\0").ok();
+
+        let host = ctx as *const ExportContext as *mut c_void;
+        let response = emit(request.as_ptr(), host, library_log);
+        if response.is_null() {
+            return Ok(String::new());
+        }
+        let text = CStr::from_ptr(response).to_string_lossy().to_string();
+        if let Some(free) = free {
+            free(response);
+        }
+        Ok(text)
+    }
+}
+
+// =============================================================================
+// WASM modules
+// =============================================================================
+
+struct WasmHost {
+    output_dir: PathBuf,
+    logs: Vec<String>,
+}
+
+fn call_wasm(ctx: &ExportContext, path: &Path, request: &str) -> Result<String, String> {
+    use wasmi::{Caller, Engine, Linker, Module, Store};
+
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &bytes[..]).map_err(|e| format!("Invalid WASM module: {}", e))?;
+    let mut store = Store::new(
+        &engine,
+        WasmHost { output_dir: ctx.output_dir.clone(), logs: Vec::new() },
+    );
+
+    let mut linker = Linker::<WasmHost>::new(&engine);
+    linker
+        .func_wrap("esengine", "log", |mut caller: Caller<'_, WasmHost>, ptr: i32, len: i32| {
+            if let Some(message) = read_guest(&mut caller, ptr, len) {
+                caller.data_mut().logs.push(String::from_utf8_lossy(&message).to_string());
+            }
+        })
+        .and_then(|l| {
+            l.func_wrap(
+                "esengine",
+                "read_file",
+                |mut caller: Caller<'_, WasmHost>, path_ptr: i32, path_len: i32| -> i64 {
+                    let data = read_guest(&mut caller, path_ptr, path_len)
+                        .and_then(|p| guest_path(&caller.data().output_dir, &p))
+                        .and_then(|p| std::fs::read(p).ok());
+                    match data {
+                        Some(data) => write_guest(&mut caller, &data).unwrap_or(-1),
+                        None => -1,
+                    }
+                },
+            )
+        })
+        .and_then(|l| {
+            l.func_wrap(
+                "esengine",
+                "write_file",
+                |mut caller: Caller<'_, WasmHost>, path_ptr: i32, path_len: i32, data_ptr: i32, data_len: i32| -> i32 {
+                    let path = read_guest(&mut caller, path_ptr, path_len)
+                        .and_then(|p| guest_path(&caller.data().output_dir, &p));
+                    let (Some(path), Some(data)) = (path, read_guest(&mut caller, data_ptr, data_len)) else {
+                        return -1;
+                    };
+                    let written = path
+                        .parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .and_then(|_| std::fs::write(&path, data));
+                    if written.is_ok() {
+                        0
+                    } else {
+                        -1
+                    }
+                },
+            )
+        })
+        .map_err(|e| e.to_string())?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| format!("Failed to instantiate {}: {}", path.display(), e))?;
+    let emit = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "esengine_target_emit")
+        .map_err(|e| format!("Missing esengine_target_emit: {}", e))?;
+
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "esengine_alloc")
+        .map_err(|e| format!("Missing esengine_alloc: {}", e))?;
+    let memory = instance.get_memory(&store, "memory").ok_or("Missing exported memory")?;
+    let len = request.len() as i32;
+    let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+    memory
+        .write(&mut store, ptr as usize, request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let result = emit.call(&mut store, (ptr, len));
+    for message in std::mem::take(&mut store.data_mut().logs) {
+        ctx.progress("plugin", &message, 0.9);
+    }
+    let packed = result.map_err(|e| format!("Plugin trapped: {}", e))?;
+
+    let (ptr, len) = ((packed as u64 >> 32) as usize, (packed as u64 & 0xffff_ffff) as usize);
+    let mut response = vec![0; len];
+    memory.read(&store, ptr, &mut response).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&response).to_string())
+}
+
+pub(crate) fn read_guest<T>(caller: &mut wasmi::Caller<'_, T>, ptr: i32, len: i32) -> Option<Vec<u8>> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let mut buffer = vec![0; usize::try_from(len).ok()?];
+    memory.read(&*caller, usize::try_from(ptr).ok()?, &mut buffer).ok()?;
+    Some(buffer)
+}
+
+/// Copies `data` into guest memory allocated with `esengine_alloc`.
+pub(crate) fn write_guest<T>(caller: &mut wasmi::Caller<'_, T>, data: &[u8]) -> Option<i64> {
+    let alloc = caller.get_export("esengine_alloc")?.into_func()?;
+    let ptr = alloc.typed::<i32, i32>(&*caller).ok()?.call(&mut *caller, data.len() as i32).ok()?;
+    let memory = caller.get_export("memory")?.into_memory()?;
+    memory.write(&mut *caller, usize::try_from(ptr).ok()?, data).ok()?;
+    Some(((ptr as u32 as i64) << 32) | data.len() as i64)
+}
+
+/// Resolves a guest path inside the output folder, rejecting escapes.
+fn guest_path(output_dir: &Path, path: &[u8]) -> Option<PathBuf> {
+    let rel = Path::new(std::str::from_utf8(path).ok()?);
+    rel.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        .then(|| output_dir.join(rel))
+}
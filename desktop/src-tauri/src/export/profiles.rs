@@ -0,0 +1,164 @@
+//! Build profiles — named export configurations stored with the project
+//!
+//! Profiles live in `.esengine/build-profiles.json`. Each profile picks a
+//! target and output directory, carries build variables, and may override
+//! any export option through a partial `ExportOptions` JSON object.
+
+use super::{run_export, ExportOptions, ExportResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PROFILES_FILE: &str = ".esengine/build-profiles.json";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildProfiles {
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: Vec<BuildProfile>,
+}
+
+fn default_version() -> String {
+    "1.0".to_string()
+}
+
+impl Default for BuildProfiles {
+    fn default() -> Self {
+        let profile = |name: &str, debug: bool| BuildProfile {
+            name: name.to_string(),
+            target: "web".to_string(),
+            output_dir: format!("build/{}", name),
+            debug,
+            variables: BTreeMap::new(),
+            overrides: Value::Null,
+        };
+        Self {
+            version: default_version(),
+            active: Some("dev".to_string()),
+            profiles: vec![profile("dev", true), profile("release", false)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildProfile {
+    pub name: String,
+    #[serde(default = "default_target")]
+    pub target: String,
+    /// Output directory, relative to the project unless absolute.
+    pub output_dir: String,
+    #[serde(default)]
+    pub debug: bool,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Partial `ExportOptions` merged over the profile defaults.
+    #[serde(default)]
+    pub overrides: Value,
+}
+
+fn default_target() -> String {
+    "web".to_string()
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_build_profiles(project_dir: String) -> Result<BuildProfiles, String> {
+    load_profiles(Path::new(&project_dir))
+}
+
+#[tauri::command]
+pub fn save_build_profiles(project_dir: String, profiles: BuildProfiles) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for profile in &profiles.profiles {
+        if profile.name.trim().is_empty() {
+            return Err("Build profile names must not be empty".to_string());
+        }
+        if !names.insert(profile.name.as_str()) {
+            return Err(format!("Duplicate build profile: {}", profile.name));
+        }
+    }
+
+    let path = Path::new(&project_dir).join(PROFILES_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&profiles).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write build profiles: {}", e))
+}
+
+/// Exports the project with the named profile, or the active one when omitted.
+#[tauri::command]
+pub async fn run_build(
+    app: AppHandle,
+    project_dir: String,
+    profile: Option<String>,
+) -> Result<ExportResult, String> {
+    let options = resolve_profile_options(Path::new(&project_dir), profile.as_deref())?;
+    tokio::task::spawn_blocking(move || run_export(&app, &options))
+        .await
+        .map_err(|e| format!("Build task failed: {}", e))?
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+pub fn load_profiles(project_dir: &Path) -> Result<BuildProfiles, String> {
+    let path = project_dir.join(PROFILES_FILE);
+    if !path.exists() {
+        return Ok(BuildProfiles::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read build profiles: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid build profiles: {}", e))
+}
+
+/// Builds the export options for a profile: defaults, then profile fields, then overrides.
+pub fn resolve_profile_options(project_dir: &Path, name: Option<&str>) -> Result<ExportOptions, String> {
+    let profiles = load_profiles(project_dir)?;
+    let name = name
+        .map(String::from)
+        .or_else(|| profiles.active.clone())
+        .ok_or("No build profile selected")?;
+    let profile = profiles
+        .profiles
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Build profile not found: {}", name))?;
+
+    let output_dir: PathBuf = project_dir.join(&profile.output_dir);
+    let mut options = json!({
+        "project_dir": project_dir.to_string_lossy(),
+        "output_dir": output_dir.to_string_lossy(),
+        "target": profile.target,
+        "profile": profile.name,
+        "debug": profile.debug,
+        "variables": profile.variables,
+    });
+    merge_json(&mut options, &profile.overrides);
+    serde_json::from_value(options).map_err(|e| format!("Invalid overrides in profile {}: {}", name, e))
+}
+
+/// Recursively merges `patch` into `base`; non-object values replace.
+pub(crate) fn merge_json(base: &mut Value, patch: &Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(base.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (_, Value::Null) => {}
+        (base, patch) => *base = patch.clone(),
+    }
+}
@@ -0,0 +1,122 @@
+//! PWA export target — installable, offline-capable web build
+//!
+//! Builds the web target, then adds a web app manifest, icons rendered at the
+//! sizes browsers expect, and a service worker precaching every output file
+//! keyed by content hash.
+
+use super::{assets, icons, web, ExportContext};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+const SW_TEMPLATE: &str = include_str!("pwa_sw_template.js");
+const MANIFEST_FILE: &str = "manifest.webmanifest";
+const SW_FILE: &str = "sw.js";
+const ICON_SIZES: &[u32] = &[72, 96, 128, 144, 152, 192, 384, 512];
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    web::emit(ctx)?;
+    let settings = ctx.options.pwa.clone();
+
+    ctx.progress("pwa", "Generating icons...", 0.88);
+    let icons = write_icons(ctx, settings.icon_path.as_deref())?;
+
+    let title = ctx.title();
+    let short_name = settings.short_name.clone().unwrap_or_else(|| title.clone());
+    let manifest = json!({
+        "name": title,
+        "short_name": short_name,
+        "start_url": "./index.html",
+        "scope": "./",
+        "display": settings.display,
+        "orientation": settings.orientation,
+        "background_color": settings.background_color,
+        "theme_color": settings.theme_color,
+        "icons": icons,
+    });
+    let data = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    ctx.write_output(MANIFEST_FILE, &data)?;
+
+    patch_index_html(ctx, &settings.theme_color, !icons.is_empty())?;
+
+    ctx.progress("pwa", "Generating service worker...", 0.95);
+    write_service_worker(ctx)
+}
+
+fn write_icons(ctx: &mut ExportContext, icon_path: Option<&str>) -> Result<Vec<Value>, String> {
+    let Some(icon_path) = icon_path else {
+        ctx.warn("No PWA icon configured; browsers may not offer to install the game".to_string());
+        return Ok(Vec::new());
+    };
+    let source_path = if std::path::Path::new(icon_path).is_absolute() {
+        icon_path.to_string()
+    } else {
+        ctx.project_dir.join(icon_path).to_string_lossy().to_string()
+    };
+    let source = icons::load_icon(&source_path)?;
+    if source.width() < 512 || source.height() < 512 {
+        ctx.warn(format!(
+            "PWA icon is {}x{}; 512x512 or larger is recommended",
+            source.width(),
+            source.height()
+        ));
+    }
+
+    let mut entries = Vec::new();
+    for &size in ICON_SIZES {
+        let rel = format!("icons/icon-{}.png", size);
+        ctx.write_output(&rel, &icons::render_png(&source, size)?)?;
+        entries.push(json!({
+            "src": format!("./{}", rel),
+            "sizes": format!("{}x{}", size, size),
+            "type": "image/png",
+            "purpose": "any",
+        }));
+    }
+    Ok(entries)
+}
+
+fn patch_index_html(ctx: &ExportContext, theme_color: &str, has_icons: bool) -> Result<(), String> {
+    let path = ctx.output_dir.join("index.html");
+    let html = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read index.html: {}", e))?;
+
+    let mut head = format!(
+        "    <link rel=\"manifest\" href=\"./{}\">\n    <meta name=\"theme-color\" content=\"{}\">\n    <meta name=\"mobile-web-app-capable\" content=\"yes\">\n",
+        MANIFEST_FILE,
+        web::escape_html(theme_color)
+    );
+    if has_icons {
+        head.push_str("    <link rel=\"apple-touch-icon\" href=\"./icons/icon-192.png\">\n");
+    }
+    let register = format!(
+        "    <script>\n        if ('serviceWorker' in navigator) {{\n            window.addEventListener('load', () => navigator.serviceWorker.register('./{}'));\n        }}\n    </script>\n",
+        SW_FILE
+    );
+
+    let html = html
+        .replacen("</head>", &format!("{}</head>", head), 1)
+        .replacen("</body>", &format!("{}</body>", register), 1);
+    ctx.write_output("index.html", html.as_bytes())
+}
+
+/// Precaches every output file; the cache name changes whenever any content does.
+fn write_service_worker(ctx: &ExportContext) -> Result<(), String> {
+    let mut entries = Vec::new();
+    let mut combined = Sha256::new();
+    for file in assets::walk_files(&ctx.output_dir) {
+        let rel = assets::relative_path(&ctx.output_dir, &file);
+        if rel == SW_FILE {
+            continue;
+        }
+        let data = std::fs::read(&file).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let revision = format!("{:x}", Sha256::digest(&data));
+        combined.update(revision.as_bytes());
+        entries.push(json!({ "url": format!("./{}", rel), "revision": &revision[..16] }));
+    }
+    entries.push(json!({ "url": "./", "revision": null }));
+
+    let cache_name = format!("esengine-{}", &format!("{:x}", combined.finalize())[..16]);
+    let sw = SW_TEMPLATE
+        .replace("{{CACHE_NAME}}", &json!(cache_name).to_string())
+        .replace("{{PRECACHE}}", &serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?);
+    ctx.write_output(SW_FILE, sw.as_bytes())
+}
@@ -0,0 +1,29 @@
+// Generated by ESEngine — precaching service worker
+const CACHE_NAME = {{CACHE_NAME}};
+const PRECACHE = {{PRECACHE}};
+
+self.addEventListener('install', (event) => {
+    event.waitUntil(
+        caches.open(CACHE_NAME)
+            .then((cache) => cache.addAll(PRECACHE.map((entry) => new Request(entry.url, { cache: 'reload' }))))
+            .then(() => self.skipWaiting())
+    );
+});
+
+self.addEventListener('activate', (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((keys) => Promise.all(keys
+                .filter((key) => key.startsWith('esengine-') && key !== CACHE_NAME)
+                .map((key) => caches.delete(key))))
+            .then(() => self.clients.claim())
+    );
+});
+
+self.addEventListener('fetch', (event) => {
+    if (event.request.method !== 'GET') return;
+    event.respondWith(
+        caches.match(event.request, { ignoreSearch: true })
+            .then((cached) => cached || fetch(event.request))
+    );
+});
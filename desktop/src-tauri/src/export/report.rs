@@ -0,0 +1,135 @@
+//! Build size report — attributes output bytes to categories and enforces budgets
+//!
+//! Budgets come from the project file, per target with a `default` fallback:
+//! `"buildBudgets": { "wechat": { "total": 4194304, "textures": 2097152, "fail": true } }`.
+//! Sizes are in bytes; exceeding a budget warns, or fails the build when `fail` is set.
+
+use super::minigame::format_size;
+use super::{assets, ExportContext, ExportedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const CATEGORIES: &[&str] = &["runtime", "scripts", "textures", "audio", "fonts", "scenes", "other"];
+
+const LARGEST_FILES: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeReport {
+    /// Bytes on disk.
+    pub total: u64,
+    /// Bytes per category. Packaged targets report their uncompressed
+    /// contents, so the sum can differ from `total`.
+    pub categories: BTreeMap<String, u64>,
+    pub largest: Vec<ExportedFile>,
+    pub budget: Option<SizeBudget>,
+    /// Human-readable budget violations.
+    pub exceeded: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SizeBudget {
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub fail: bool,
+    /// Per-category limits keyed by the names in `CATEGORIES`.
+    #[serde(flatten)]
+    pub categories: BTreeMap<String, u64>,
+}
+
+/// Builds the report for the final output files and applies the target's budget.
+pub(crate) fn size_report(ctx: &mut ExportContext, files: &[ExportedFile]) -> Result<SizeReport, String> {
+    let contents = if ctx.packaged.is_empty() {
+        files.to_vec()
+    } else {
+        std::mem::take(&mut ctx.packaged)
+    };
+    let mut categories: BTreeMap<String, u64> = CATEGORIES.iter().map(|c| (c.to_string(), 0)).collect();
+    for file in &contents {
+        *categories.entry(category_of(&file.path).to_string()).or_default() += file.size;
+    }
+    let mut largest = contents;
+    largest.sort_by_key(|f| std::cmp::Reverse(f.size));
+    largest.truncate(LARGEST_FILES);
+
+    let mut report = SizeReport {
+        total: files.iter().map(|f| f.size).sum(),
+        categories,
+        largest,
+        budget: load_budget(ctx)?,
+        exceeded: Vec::new(),
+    };
+    ctx.progress("report", &summary(&report), 1.0);
+
+    let Some(budget) = report.budget.clone() else {
+        return Ok(report);
+    };
+    if let Some(limit) = budget.total.filter(|limit| report.total > *limit) {
+        report.exceeded.push(format!("Total size {} exceeds budget {}", format_size(report.total), format_size(limit)));
+    }
+    for (category, limit) in &budget.categories {
+        let size = report.categories.get(category).copied().unwrap_or(0);
+        if size > *limit {
+            report.exceeded.push(format!(
+                "{} size {} exceeds budget {}",
+                category,
+                format_size(size),
+                format_size(*limit)
+            ));
+        }
+    }
+
+    if budget.fail && !report.exceeded.is_empty() {
+        return Err(format!("Size budget exceeded for {}:\n{}", ctx.options.target, report.exceeded.join("\n")));
+    }
+    for message in report.exceeded.clone() {
+        ctx.warn(message);
+    }
+    Ok(report)
+}
+
+fn load_budget(ctx: &ExportContext) -> Result<Option<SizeBudget>, String> {
+    let Some(budgets) = ctx.project.get("buildBudgets") else {
+        return Ok(None);
+    };
+    let Some(value) = budgets.get(&ctx.options.target).or_else(|| budgets.get("default")) else {
+        return Ok(None);
+    };
+    let budget: SizeBudget = serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid size budget for {}: {}", ctx.options.target, e))?;
+    if let Some(unknown) = budget.categories.keys().find(|k| !CATEGORIES.contains(&k.as_str())) {
+        return Err(format!(
+            "Unknown size budget category '{}'. Expected one of: total, {}",
+            unknown,
+            CATEGORIES.join(", ")
+        ));
+    }
+    Ok(Some(budget))
+}
+
+fn category_of(path: &str) -> &'static str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let in_dir = |dir: &str| path.split('/').rev().skip(1).any(|d| d == dir);
+    match assets::extension_of(path).as_str() {
+        "wasm" => "runtime",
+        _ if in_dir("sdk") || in_dir("wasm") => "runtime",
+        _ if matches!(name, "esengine.js" | "physics.js" | "sdk.js") => "runtime",
+        "js" | "mjs" => "scripts",
+        "png" | "jpg" | "jpeg" | "webp" | "gif" | "svg" | "bmp" | "ktx" | "ktx2" | "basis" => "textures",
+        "mp3" | "wav" | "ogg" | "aac" | "flac" | "m4a" | "opus" | "webm" => "audio",
+        "ttf" | "otf" | "woff" | "woff2" | "fnt" | "bmfont" => "fonts",
+        "esscene" => "scenes",
+        "json" if in_dir("scenes") => "scenes",
+        _ => "other",
+    }
+}
+
+fn summary(report: &SizeReport) -> String {
+    let parts: Vec<String> = report
+        .categories
+        .iter()
+        .filter(|(_, size)| **size > 0)
+        .map(|(category, size)| format!("{} {}", category, format_size(*size)))
+        .collect();
+    format!("Output size {} ({})", format_size(report.total), parts.join(", "))
+}
@@ -0,0 +1,100 @@
+//! Reproducibility check — exports twice and compares the outputs
+//!
+//! Both builds run clean, in reproducible mode and with the same pinned build
+//! number, into throwaway folders. Their checksum manifests are compared file
+//! by file; any difference points at nondeterminism in the pipeline or in a
+//! target's tooling.
+
+use super::{archive, incremental, run_export, ArchiveOptions, ExportListener, ExportOptions, SourceMapOptions};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReproducibilityReport {
+    pub reproducible: bool,
+    /// Files in the first build.
+    pub files: usize,
+    pub differences: Vec<FileDifference>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDifference {
+    pub path: String,
+    /// SHA-256 in each build; `None` when the file is missing from that build.
+    pub first: Option<String>,
+    pub second: Option<String>,
+}
+
+#[tauri::command]
+pub async fn verify_reproducible(app: AppHandle, options: ExportOptions) -> Result<ReproducibilityReport, String> {
+    tokio::task::spawn_blocking(move || verify(&app, &options))
+        .await
+        .map_err(|e| format!("Verify task failed: {}", e))?
+}
+
+pub fn verify(listener: &dyn ExportListener, options: &ExportOptions) -> Result<ReproducibilityReport, String> {
+    let root = std::env::temp_dir().join(format!("esengine-repro-{}", std::process::id()));
+    let project_dir = PathBuf::from(&options.project_dir);
+    let dirs = [root.join("a"), root.join("b")];
+
+    let result = build_pair(listener, options, &dirs);
+    for dir in &dirs {
+        incremental::forget(&project_dir, dir);
+    }
+    let _ = std::fs::remove_dir_all(&root);
+    let [first, second] = result?;
+
+    let mut differences = Vec::new();
+    for (path, hash) in &first {
+        if second.get(path) != Some(hash) {
+            differences.push(FileDifference {
+                path: path.clone(),
+                first: Some(hash.clone()),
+                second: second.get(path).cloned(),
+            });
+        }
+    }
+    for (path, hash) in second.iter().filter(|(p, _)| !first.contains_key(*p)) {
+        differences.push(FileDifference { path: path.clone(), first: None, second: Some(hash.clone()) });
+    }
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let message = if differences.is_empty() {
+        format!("Builds are identical ({} files)", first.len())
+    } else {
+        format!("{} file(s) differ between builds", differences.len())
+    };
+    listener.progress("complete", &message, 1.0);
+    Ok(ReproducibilityReport { reproducible: differences.is_empty(), files: first.len(), differences })
+}
+
+/// Runs both exports and returns each output's `path -> sha256` map.
+fn build_pair(
+    listener: &dyn ExportListener,
+    options: &ExportOptions,
+    dirs: &[PathBuf; 2],
+) -> Result<[BTreeMap<String, String>; 2], String> {
+    let build_number = options.build_number.unwrap_or(0);
+    let mut hashes = [BTreeMap::new(), BTreeMap::new()];
+    for (i, dir) in dirs.iter().enumerate() {
+        listener.progress("verify", &format!("Build {} of 2...", i + 1), i as f32 / 2.0);
+        let mut build = options.clone();
+        build.output_dir = dir.to_string_lossy().to_string();
+        build.clean = true;
+        build.reproducible = true;
+        build.build_number = Some(build_number);
+        build.archive = ArchiveOptions::default();
+        // Maps are stripped from the output either way; never save or upload them twice.
+        build.source_maps = SourceMapOptions::default();
+        run_export(listener, &build)?;
+        hashes[i] = checksums(dir)?;
+    }
+    Ok(hashes)
+}
+
+fn checksums(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let manifest = archive::write_manifest(dir)?;
+    Ok(manifest.files.into_iter().map(|f| (f.path, f.sha256)).collect())
+}
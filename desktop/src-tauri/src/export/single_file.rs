@@ -0,0 +1,278 @@
+//! Single-file export target — one self-contained HTML with everything inlined
+//!
+//! Engine, SDK modules, scripts, scenes and the asset manifest are always
+//! embedded. Assets are embedded up to the configured inline limit; larger
+//! ones are shipped next to the HTML unless `inline_large_assets` is set.
+//! The offline target reuses this layout with oversized assets in script
+//! chunks instead (see `Overflow`).
+
+use super::{assets, source_maps, web, ExportContext, ExportedFile};
+use crate::embedded_assets;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    ExportAllDeclaration, ExportNamedDeclaration, Expression, ImportDeclaration, ImportExpression, StringLiteral,
+};
+use oxc_ast_visit::{walk, Visit};
+use oxc_parser::Parser;
+use oxc_span::{SourceType, Span};
+use serde_json::{json, Map, Value};
+
+const SINGLE_FILE_TEMPLATE: &str = include_str!("single_file_template.html");
+
+/// Bare specifier prefix used for inlined SDK modules in the import map.
+const INLINE_PREFIX: &str = "@esengine-inline/";
+
+/// Where assets above the inline limit are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Overflow {
+    /// Plain files next to the HTML, loaded with `fetch`.
+    Files,
+    /// `data/chunk-N.js` scripts of about the given size, loaded through
+    /// classic `<script>` tags, which also work under `file://`.
+    Chunks(u64),
+}
+
+pub(crate) struct SingleFileOutput {
+    pub html: String,
+    /// Embedded game scripts.
+    pub scripts: Option<Vec<u8>>,
+}
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    build(ctx, Overflow::Files).map(|_| ())
+}
+
+pub(crate) fn build(ctx: &mut ExportContext, overflow: Overflow) -> Result<SingleFileOutput, String> {
+    let options = ctx.options.single_file.clone();
+
+    ctx.progress("assets", "Embedding assets...", 0.2);
+    let mut inlined = Map::new();
+    let mut parts = Vec::new();
+    let mut external = Vec::new();
+    let mut chunk = Map::new();
+    let mut chunk_size = 0;
+    let mut chunk_refs = Map::new();
+    let total = ctx.assets.len().max(1) as f32;
+    let asset_list: Vec<String> = ctx.assets.iter().cloned().collect();
+    for (i, rel) in asset_list.iter().enumerate() {
+        let data = if assets::is_json_asset(rel) {
+            serde_json::to_vec(&ctx.read_rewritten_json(rel)?).map_err(|e| e.to_string())?
+        } else {
+            std::fs::read(ctx.project_dir.join(rel)).map_err(|e| format!("Failed to read {}: {}", rel, e))?
+        };
+
+        if data.len() as u64 <= options.inline_limit || options.inline_large_assets {
+            let encoded = BASE64.encode(&data);
+            parts.push(part(rel, encoded.len()));
+            inlined.insert(rel.clone(), Value::String(encoded));
+        } else if let Overflow::Chunks(limit) = overflow {
+            let encoded = BASE64.encode(&data);
+            if chunk_size > 0 && chunk_size + encoded.len() as u64 > limit {
+                external.push(write_chunk(ctx, chunk_name(external.len()), &mut chunk)?);
+                chunk_size = 0;
+            }
+            chunk_size += encoded.len() as u64;
+            chunk_refs.insert(rel.clone(), Value::String(chunk_name(external.len())));
+            chunk.insert(rel.clone(), Value::String(encoded));
+        } else {
+            ctx.write_output(rel, &data)?;
+            external.push(part(rel, data.len()));
+        }
+
+        if i % 16 == 0 {
+            ctx.progress("assets", &format!("Embedding {}", rel), 0.2 + 0.4 * (i as f32 / total));
+        }
+    }
+    if !chunk.is_empty() {
+        external.push(write_chunk(ctx, chunk_name(external.len()), &mut chunk)?);
+    }
+    if overflow == Overflow::Files && !external.is_empty() {
+        ctx.warn(format!(
+            "{} asset(s) exceed the inline limit and were written next to index.html",
+            external.len()
+        ));
+    }
+
+    let mut scenes = Vec::new();
+    for scene in &ctx.scenes {
+        let data = ctx.read_rewritten_json(scene)?;
+        parts.push(part(&ctx.scene_output_path(scene), data.to_string().len()));
+        scenes.push(json!({ "name": ctx.scene_name(scene), "data": data }));
+    }
+
+    ctx.progress("runtime", "Embedding engine runtime...", 0.7);
+    let engine_js = ctx.read_override(&ctx.options.engine_js_path, embedded_assets::ENGINE_JS)?;
+    let engine_wasm = ctx.read_override(&ctx.options.engine_wasm_path, embedded_assets::ENGINE_WASM)?;
+    let physics = web::read_physics(ctx)?;
+    let scripts = ctx.user_scripts()?;
+
+    let encoded_len = |bytes: &[u8]| base64::encoded_len(bytes.len(), true).unwrap_or(0);
+    parts.push(part("wasm/esengine.js", encoded_len(&engine_js)));
+    parts.push(part("wasm/esengine.wasm", encoded_len(&engine_wasm)));
+    if let Some((js, wasm)) = &physics {
+        parts.push(part("wasm/physics.js", encoded_len(js)));
+        parts.push(part("wasm/physics.wasm", encoded_len(wasm)));
+    }
+    if let Some(js) = &scripts {
+        parts.push(part("game.js", encoded_len(js)));
+    }
+
+    let data = json!({
+        "config": super::runtime_config(ctx),
+        "manifest": super::asset_manifest(ctx),
+        "scenes": scenes,
+        "firstScene": ctx.scenes.first().map(|s| ctx.scene_name(s)).unwrap_or_default(),
+        "engineJs": BASE64.encode(&engine_js),
+        "engineWasm": BASE64.encode(&engine_wasm),
+        "physicsJs": physics.as_ref().map(|(js, _)| BASE64.encode(js)),
+        "physicsWasm": physics.as_ref().map(|(_, wasm)| BASE64.encode(wasm)),
+        "gameJs": scripts.as_ref().map(|js| BASE64.encode(js)),
+        "assets": inlined,
+        "chunks": chunk_refs,
+    });
+
+    ctx.progress("html", "Generating index.html...", 0.85);
+    let import_map = script_safe(&import_map(ctx)?.to_string());
+    parts.push(part("sdk/import-map", import_map.len()));
+    let html = ctx.substitute_variables(
+        &SINGLE_FILE_TEMPLATE
+            .replace("{{TITLE}}", &web::escape_html(&ctx.title()))
+            .replace("{{BUILD_DEFINES}}", &ctx.build_define_script()),
+    )
+    .replace("{{IMPORT_MAP}}", &import_map)
+    .replace("{{DATA}}", &script_safe(&data.to_string()));
+
+    // Whatever is not an embedded part is page markup, config and the manifest.
+    let embedded: u64 = parts.iter().map(|p| p.size).sum();
+    parts.push(part("index.html", (html.len() as u64).saturating_sub(embedded) as usize));
+    parts.extend(external);
+    ctx.packaged = parts;
+
+    if html.len() as u64 > options.size_warning {
+        ctx.warn(format!(
+            "index.html is {:.1} MB, above the {:.1} MB warning threshold",
+            html.len() as f64 / 1_048_576.0,
+            options.size_warning as f64 / 1_048_576.0
+        ));
+    }
+    ctx.write_output("index.html", html.as_bytes())?;
+    Ok(SingleFileOutput { html, scripts })
+}
+
+fn chunk_name(index: usize) -> String {
+    format!("data/chunk-{}.js", index)
+}
+
+/// Writes the collected assets as a script that hands them to the loader.
+fn write_chunk(ctx: &ExportContext, name: String, assets: &mut Map<String, Value>) -> Result<ExportedFile, String> {
+    let payload = Value::Object(std::mem::take(assets));
+    let code = format!("__esengine_chunk__({});\n", payload);
+    ctx.write_output(&name, code.as_bytes())?;
+    Ok(part(&name, code.len()))
+}
+
+fn part(path: &str, size: usize) -> ExportedFile {
+    ExportedFile { path: path.to_string(), size: size as u64 }
+}
+
+/// Maps the public SDK specifiers and every SDK module to a base64 data URL.
+fn import_map(ctx: &mut ExportContext) -> Result<Value, String> {
+    let mut imports = Map::new();
+    for (path, data, map) in web::SDK_FILES {
+        let (data, map) = (ctx.engine.read(*data)?, ctx.engine.read(*map).ok());
+        let code = source_maps::strip(ctx, path, &data, map.as_deref());
+        let source = rewrite_relative_imports(&String::from_utf8_lossy(&code), path);
+        imports.insert(
+            format!("{}{}", INLINE_PREFIX, path),
+            Value::String(format!("data:text/javascript;base64,{}", BASE64.encode(source))),
+        );
+    }
+    for (specifier, path) in [
+        ("esengine", "sdk/index.js"),
+        ("esengine/wasm", "sdk/wasm.js"),
+        ("esengine/spine", "sdk/spine/index.js"),
+    ] {
+        let target = imports[&format!("{}{}", INLINE_PREFIX, path)].clone();
+        imports.insert(specifier.to_string(), target);
+    }
+    Ok(json!({ "imports": imports }))
+}
+
+/// Data URLs have no base, so relative imports between SDK modules are
+/// rewritten to bare specifiers resolved through the import map. Specifiers
+/// are located by parsing the module, so strings that only look like
+/// imports are left alone.
+fn rewrite_relative_imports(source: &str, module_path: &str) -> String {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, SourceType::mjs()).parse();
+    if parsed.panicked {
+        eprintln!("[export] Failed to parse {}; relative imports are kept", module_path);
+        return source.to_string();
+    }
+    let mut finder = SpecifierFinder::default();
+    finder.visit_program(&parsed.program);
+
+    let base_dir = assets::parent_dir(module_path);
+    let mut out = String::with_capacity(source.len());
+    let mut last = 0;
+    for (span, specifier) in finder.specifiers {
+        if !specifier.starts_with("./") && !specifier.starts_with("../") {
+            continue;
+        }
+        let Some(resolved) = assets::normalize_path(&format!("{}/{}", base_dir, specifier)) else {
+            continue;
+        };
+        let (start, end) = (span.start as usize, span.end as usize);
+        let quote = &source[start..start + 1];
+        out.push_str(&source[last..start]);
+        out.push_str(&format!("{quote}{INLINE_PREFIX}{resolved}{quote}"));
+        last = end;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// The module specifiers of imports, re-exports and `import()` with a
+/// string literal, in source order.
+#[derive(Default)]
+struct SpecifierFinder {
+    /// `(span with quotes, specifier)`
+    specifiers: Vec<(Span, String)>,
+}
+
+impl SpecifierFinder {
+    fn push(&mut self, literal: &StringLiteral) {
+        self.specifiers.push((literal.span, literal.value.to_string()));
+    }
+}
+
+impl<'a> Visit<'a> for SpecifierFinder {
+    fn visit_import_declaration(&mut self, it: &ImportDeclaration<'a>) {
+        self.push(&it.source);
+    }
+
+    fn visit_export_named_declaration(&mut self, it: &ExportNamedDeclaration<'a>) {
+        if let Some(source) = &it.source {
+            self.push(source);
+        }
+        walk::walk_export_named_declaration(self, it);
+    }
+
+    fn visit_export_all_declaration(&mut self, it: &ExportAllDeclaration<'a>) {
+        self.push(&it.source);
+    }
+
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        if let Expression::StringLiteral(literal) = &it.source {
+            self.push(literal);
+        }
+        walk::walk_import_expression(self, it);
+    }
+}
+
+/// Prevents inline payloads from closing their `<script>` element early.
+fn script_safe(s: &str) -> String {
+    s.replace("</", "<\\/")
+}
@@ -0,0 +1,133 @@
+//! Source map policy — keeps maps out of shipped artifacts
+//!
+//! Every script an export writes passes through `strip`, which removes its
+//! `sourceMappingURL` comment. The map itself, from a known sidecar or an
+//! inline data URL, is then dropped (`strip`), saved outside the output
+//! (`private`), or uploaded to an error-tracking service (`upload`).
+
+use super::ExportContext;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::path::Path;
+
+pub const MODES: &[&str] = &["strip", "private", "upload"];
+
+const PRIVATE_DIR: &str = ".esengine/sourcemaps";
+const MULTIPART_BOUNDARY: &str = "----esengine-sourcemap-7d1f9c2b";
+
+/// Removes the trailing `sourceMappingURL` comment from `code` and keeps the
+/// map as `<rel>.map` unless the policy discards it.
+pub(crate) fn strip(ctx: &mut ExportContext, rel: &str, code: &[u8], map: Option<&[u8]>) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(code) else {
+        return code.to_vec();
+    };
+    let (stripped, inline) = strip_comment(text);
+    let map = map.map(<[u8]>::to_vec).or(inline);
+    if let Some(map) = map.filter(|_| ctx.options.source_maps.mode != "strip") {
+        ctx.source_maps.push((format!("{}.map", rel), map));
+    }
+    stripped.into_bytes()
+}
+
+/// Returns the code without its `sourceMappingURL` comment, plus the map if
+/// the comment held it inline as a base64 data URL.
+pub(crate) fn strip_comment(code: &str) -> (String, Option<Vec<u8>>) {
+    let body = code.trim_end();
+    let start = body.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let last = body[start..].trim();
+    let url = ["//# sourceMappingURL=", "//@ sourceMappingURL=", "/*# sourceMappingURL="]
+        .iter()
+        .find_map(|prefix| last.strip_prefix(prefix));
+    let Some(url) = url else {
+        return (code.to_string(), None);
+    };
+
+    let url = url.trim_end_matches("*/").trim();
+    let inline = url
+        .strip_prefix("data:")
+        .and_then(|data| data.split_once(";base64,"))
+        .and_then(|(_, encoded)| BASE64.decode(encoded).ok());
+    let mut stripped = body[..start].to_string();
+    if !stripped.is_empty() && !stripped.ends_with('\n') {
+        stripped.push('\n');
+    }
+    (stripped, inline)
+}
+
+/// Saves or uploads the maps collected during the export.
+pub(crate) fn flush(ctx: &mut ExportContext) -> Result<(), String> {
+    let options = ctx.options.source_maps.clone();
+    if !MODES.contains(&options.mode.as_str()) {
+        return Err(format!(
+            "Unknown source map mode '{}'. Expected one of: {}",
+            options.mode,
+            MODES.join(", ")
+        ));
+    }
+    let maps = std::mem::take(&mut ctx.source_maps);
+    if maps.is_empty() || options.mode == "strip" {
+        return Ok(());
+    }
+
+    if options.mode == "private" {
+        let dir = match &options.private_dir {
+            Some(dir) => ctx.project_dir.join(ctx.substitute_variables(dir)),
+            None => ctx.project_dir.join(PRIVATE_DIR).join(format!(
+                "{}-{}",
+                ctx.options.target, ctx.build_info.build_number
+            )),
+        };
+        for (rel, map) in &maps {
+            write_private(&dir.join(rel), map)?;
+        }
+        ctx.progress("sourcemaps", &format!("Saved {} source map(s) to {}", maps.len(), dir.display()), 1.0);
+        return Ok(());
+    }
+
+    let upload = options.upload.ok_or("Source map upload requires an upload target")?;
+    let url = ctx.substitute_variables(&upload.url);
+    let client = reqwest::Client::new();
+    tauri::async_runtime::block_on(async {
+        for (rel, map) in &maps {
+            let name = format!("{}{}", upload.url_prefix, rel);
+            let mut request = client
+                .post(&url)
+                .header("Content-Type", format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY))
+                .body(multipart_body(&name, rel, map));
+            if let Some(token) = upload.auth_token.as_deref().filter(|t| !t.is_empty()) {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await.map_err(|e| format!("Failed to upload {}: {}", rel, e))?;
+            if !response.status().is_success() {
+                return Err(format!("Failed to upload {}: HTTP {}", rel, response.status()));
+            }
+        }
+        Ok(())
+    })?;
+    ctx.progress("sourcemaps", &format!("Uploaded {} source map(s)", maps.len()), 1.0);
+    Ok(())
+}
+
+fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// `name` and `file` form fields, as expected by Sentry-style artifact APIs.
+fn multipart_body(name: &str, rel: &str, map: &[u8]) -> Vec<u8> {
+    let file_name = rel.rsplit('/').next().unwrap_or(rel);
+    let mut body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\n{name}\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file}\"\r\n\
+         Content-Type: application/json\r\n\r\n",
+        b = MULTIPART_BOUNDARY,
+        name = name,
+        file = file_name
+    )
+    .into_bytes();
+    body.extend_from_slice(map);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
@@ -0,0 +1,167 @@
+//! Export targets — registry of built-in and plugin platforms
+//!
+//! Every platform an export can produce is an `ExportTarget`. The built-in
+//! targets are registered here; third-party targets are discovered as
+//! plugins, each a folder holding a `target.json` manifest and a dynamic
+//! library or WASM module (see `plugin`). Plugins are looked up in the
+//! project's `.esengine/targets/` and in the folders listed in
+//! `ESENGINE_TARGET_PATH`.
+
+use super::plugin::PluginTarget;
+use super::{android, desktop, douyin, offline, pwa, single_file, web, wechat, ExportContext};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+const PROJECT_PLUGIN_DIR: &str = ".esengine/targets";
+const PLUGIN_PATH_ENV: &str = "ESENGINE_TARGET_PATH";
+pub const MANIFEST_FILE: &str = "target.json";
+
+pub(crate) trait ExportTarget: Send + Sync {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    /// Writes the build for this platform into `ctx.output_dir`.
+    fn emit(&self, ctx: &mut ExportContext) -> Result<(), String>;
+    /// Folder the target was loaded from, for plugins.
+    fn source(&self) -> Option<&Path> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetInfo {
+    pub id: String,
+    pub name: String,
+    /// Plugin folder; `None` for built-in targets.
+    pub plugin: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetList {
+    pub targets: Vec<TargetInfo>,
+    /// Plugins that failed to load.
+    pub warnings: Vec<String>,
+}
+
+// =============================================================================
+// Built-in targets
+// =============================================================================
+
+pub(crate) type EmitFn = fn(&mut ExportContext) -> Result<(), String>;
+
+struct BuiltinTarget {
+    id: &'static str,
+    name: &'static str,
+    emit: EmitFn,
+}
+
+impl ExportTarget for BuiltinTarget {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn emit(&self, ctx: &mut ExportContext) -> Result<(), String> {
+        (self.emit)(ctx)
+    }
+}
+
+const BUILTIN: &[BuiltinTarget] = &[
+    BuiltinTarget { id: "web", name: "Web", emit: web::emit },
+    BuiltinTarget { id: "single-file", name: "Single HTML File", emit: single_file::emit },
+    BuiltinTarget { id: "offline", name: "Offline HTML5 (file://)", emit: offline::emit },
+    BuiltinTarget { id: "wechat", name: "WeChat Mini Game", emit: wechat::emit },
+    BuiltinTarget { id: "douyin", name: "Douyin Mini Game", emit: douyin::emit },
+    BuiltinTarget { id: "pwa", name: "Progressive Web App", emit: pwa::emit },
+    BuiltinTarget { id: "desktop", name: "Desktop", emit: desktop::emit },
+    BuiltinTarget { id: "android", name: "Android", emit: android::emit },
+];
+
+/// Emit function of a built-in target, used by plugins that extend one.
+pub(crate) fn builtin_emit(id: &str) -> Option<EmitFn> {
+    BUILTIN.iter().find(|t| t.id == id).map(|t| t.emit)
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+pub(crate) struct TargetRegistry {
+    targets: Vec<Box<dyn ExportTarget>>,
+}
+
+impl TargetRegistry {
+    /// Built-in targets plus the plugins visible from `project_dir`. Plugins
+    /// that fail to load are reported through `warn` and skipped; a plugin
+    /// cannot replace a built-in or an earlier plugin with the same id.
+    pub fn discover(project_dir: &Path, mut warn: impl FnMut(String)) -> Self {
+        let mut targets: Vec<Box<dyn ExportTarget>> = BUILTIN
+            .iter()
+            .map(|t| Box::new(BuiltinTarget { id: t.id, name: t.name, emit: t.emit }) as Box<dyn ExportTarget>)
+            .collect();
+
+        for dir in plugin_dirs(project_dir) {
+            match PluginTarget::load(&dir) {
+                Ok(plugin) if targets.iter().any(|t| t.id() == plugin.id()) => warn(format!(
+                    "Ignoring target plugin {}: target '{}' is already registered",
+                    dir.display(),
+                    plugin.id()
+                )),
+                Ok(plugin) => targets.push(Box::new(plugin)),
+                Err(e) => warn(format!("Failed to load target plugin {}: {}", dir.display(), e)),
+            }
+        }
+        Self { targets }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn ExportTarget> {
+        self.targets.iter().find(|t| t.id() == id).map(|t| t.as_ref())
+    }
+
+    pub fn list(&self) -> Vec<TargetInfo> {
+        self.targets
+            .iter()
+            .map(|t| TargetInfo {
+                id: t.id().to_string(),
+                name: t.name().to_string(),
+                plugin: t.source().map(|p| p.to_string_lossy().to_string()),
+            })
+            .collect()
+    }
+}
+
+/// Plugin folders, project plugins first.
+fn plugin_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![project_dir.join(PROJECT_PLUGIN_DIR)];
+    if let Some(paths) = std::env::var_os(PLUGIN_PATH_ENV) {
+        roots.extend(std::env::split_paths(&paths));
+    }
+
+    let mut dirs = Vec::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.join(MANIFEST_FILE).is_file())
+            .collect();
+        found.sort();
+        dirs.extend(found);
+    }
+    dirs
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_export_targets(project_dir: String) -> TargetList {
+    let mut warnings = Vec::new();
+    let registry = TargetRegistry::discover(Path::new(&project_dir), |w| warnings.push(w));
+    TargetList { targets: registry.list(), warnings }
+}
@@ -0,0 +1,127 @@
+//! Web export target — static site with ES module runtime
+
+use super::ExportContext;
+use crate::embedded_assets::{self, Embedded};
+use crate::project::settings::PhysicsBackend;
+use crate::runtimes;
+use serde_json::json;
+use std::borrow::Cow;
+
+const WEB_TEMPLATE: &str = include_str!("web_template.html");
+
+/// SDK files shipped alongside the engine, mirroring the preview server
+/// layout, with their source maps.
+pub(crate) const SDK_FILES: &[(&str, Embedded, Embedded)] = &[
+    ("sdk/index.js", embedded_assets::SDK_ESM_JS, embedded_assets::SDK_ESM_JS_MAP),
+    ("sdk/wasm.js", embedded_assets::SDK_WASM_JS, embedded_assets::SDK_WASM_JS_MAP),
+    ("sdk/spine/index.js", embedded_assets::SDK_SPINE_JS, embedded_assets::SDK_SPINE_JS_MAP),
+    ("sdk/shared/index.js", embedded_assets::SDK_SHARED_INDEX_JS, embedded_assets::SDK_SHARED_INDEX_JS_MAP),
+    ("sdk/shared/material.js", embedded_assets::SDK_SHARED_MATERIAL_JS, embedded_assets::SDK_SHARED_MATERIAL_JS_MAP),
+    (
+        "sdk/shared/SpineModuleLoader.js",
+        embedded_assets::SDK_SHARED_SPINEMODULELOADER_JS,
+        embedded_assets::SDK_SHARED_SPINEMODULELOADER_JS_MAP,
+    ),
+];
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    super::copy_assets(ctx, 0.2, 0.6)?;
+    super::write_scenes(ctx)?;
+    super::write_asset_manifest(ctx)?;
+    super::build_info::write_build_info(ctx)?;
+
+    ctx.progress("runtime", "Writing engine runtime...", 0.7);
+    let has_physics = emit_runtime(ctx)?;
+
+    let scripts = ctx.user_scripts()?;
+    if let Some(ref code) = scripts {
+        ctx.write_output("game.js", code)?;
+    }
+
+    ctx.progress("html", "Generating index.html...", 0.85);
+    let html = render_index_html(ctx, scripts.is_some(), has_physics);
+    ctx.write_output("index.html", html.as_bytes())
+}
+
+/// Writes engine, SDK and optional physics runtime. Returns whether physics was emitted.
+fn emit_runtime(ctx: &mut ExportContext) -> Result<bool, String> {
+    let engine_js = ctx.read_override(&ctx.options.engine_js_path, embedded_assets::ENGINE_JS)?;
+    let engine_wasm = ctx.read_override(&ctx.options.engine_wasm_path, embedded_assets::ENGINE_WASM)?;
+    ctx.write_output("wasm/esengine.js", &engine_js)?;
+    ctx.write_output("wasm/esengine.wasm", &engine_wasm)?;
+
+    for (path, data, map) in SDK_FILES {
+        let (data, map) = (ctx.engine.read(*data)?, ctx.engine.read(*map).ok());
+        let code = super::source_maps::strip(ctx, path, &data, map.as_deref());
+        ctx.write_output(path, &code)?;
+    }
+
+    match read_physics(ctx)? {
+        Some((js, wasm)) => {
+            ctx.write_output("wasm/physics.js", &js)?;
+            ctx.write_output("wasm/physics.wasm", &wasm)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Compiled physics module as `(js, wasm)` bytes.
+pub(crate) type PhysicsModule = (Vec<u8>, Vec<u8>);
+
+/// Reads the physics module of the project's backend when physics is
+/// enabled. A Box2D module compiled for this build (the `physics_*_path`
+/// options) wins over the editor's; a Rapier one is downloaded when the
+/// editor was built without it.
+pub(crate) fn read_physics(ctx: &mut ExportContext) -> Result<Option<PhysicsModule>, String> {
+    let backend = PhysicsBackend::for_project(&ctx.project_dir);
+    match (&ctx.options.physics_js_path, &ctx.options.physics_wasm_path) {
+        (Some(js), Some(wasm)) if backend == PhysicsBackend::Box2d => {
+            let js = std::fs::read(js).map_err(|e| format!("Failed to read {}: {}", js, e))?;
+            let wasm = std::fs::read(wasm).map_err(|e| format!("Failed to read {}: {}", wasm, e))?;
+            return Ok(Some((js, wasm)));
+        }
+        (Some(_), Some(_)) => {
+            ctx.warn("The compiled Box2D physics module is ignored; the project uses Rapier".to_string());
+        }
+        _ => {}
+    }
+    if !ctx.project.get("enablePhysics").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Ok(None);
+    }
+    let id = backend.runtime_id();
+    let load = |name: String| tauri::async_runtime::block_on(runtimes::load(None, &name)).map(Cow::into_owned);
+    match (load(format!("{}.js", id)), load(format!("{}.wasm", id))) {
+        (Ok(js), Ok(wasm)) => Ok(Some((js, wasm))),
+        (Err(e), _) | (_, Err(e)) => {
+            ctx.warn(format!("Physics is enabled but its module is unavailable: {}", e));
+            Ok(None)
+        }
+    }
+}
+
+pub(crate) fn render_index_html(ctx: &ExportContext, has_scripts: bool, has_physics: bool) -> String {
+    let scenes: Vec<_> = ctx
+        .scenes
+        .iter()
+        .map(|s| json!({ "name": ctx.scene_name(s), "path": format!("./{}", ctx.scene_output_path(s)) }))
+        .collect();
+    let first_scene = ctx.scenes.first().map(|s| ctx.scene_name(s)).unwrap_or_default();
+
+    let html = WEB_TEMPLATE
+        .replace("{{TITLE}}", &escape_html(&ctx.title()))
+        .replace("{{BUILD_DEFINES}}", &ctx.build_define_script())
+        .replace("{{SCENES}}", &json!(scenes).to_string())
+        .replace("{{FIRST_SCENE}}", &json!(first_scene).to_string())
+        .replace("{{CONFIG}}", &super::runtime_config(ctx).to_string())
+        .replace("{{HAS_SCRIPTS}}", if has_scripts { "true" } else { "false" })
+        .replace("{{HAS_PHYSICS}}", if has_physics { "true" } else { "false" });
+    ctx.substitute_variables(&html)
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,48 @@
+//! WeChat mini-game export target
+
+use super::minigame::{self, sanitize_name, Platform};
+use super::{assets, ExportContext, MiniGameOptions};
+use crate::embedded_assets;
+use serde_json::{json, Value};
+
+const WECHAT: Platform = Platform {
+    name: "WeChat",
+    api: "wx",
+    main_package_limit: 4 * 1024 * 1024,
+    total_limit: 30 * 1024 * 1024,
+    adapter: embedded_assets::SDK_WECHAT_JS,
+    adapter_prelude: "",
+    project_config,
+};
+
+pub fn emit(ctx: &mut ExportContext) -> Result<(), String> {
+    let settings = ctx.options.wechat.clone();
+    minigame::emit(ctx, &WECHAT, &settings)
+}
+
+fn project_config(ctx: &ExportContext, settings: &MiniGameOptions) -> Value {
+    let include: Vec<Value> = assets::MINIGAME_PACK_EXTENSIONS
+        .iter()
+        .map(|ext| json!({ "type": "suffix", "value": format!(".{}", ext) }))
+        .collect();
+    json!({
+        "description": ctx.title(),
+        "packOptions": { "ignore": [], "include": include },
+        "setting": {
+            "urlCheck": false,
+            "es6": true,
+            "enhance": true,
+            "postcss": false,
+            "minified": !(settings.debug || ctx.options.debug),
+            "newFeature": true,
+            "nodeModules": false,
+            "uglifyFileName": false,
+            "uploadWithSourceMap": true,
+        },
+        "compileType": "minigame",
+        "libVersion": "3.0.0",
+        "appid": if settings.app_id.is_empty() { "touristappid" } else { &settings.app_id },
+        "projectname": sanitize_name(&ctx.title()),
+        "condition": {},
+    })
+}
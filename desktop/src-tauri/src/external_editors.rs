@@ -0,0 +1,221 @@
+//! External editors — opening assets in the user's own tools
+//!
+//! `open_in_external_editor` opens a file in the tool set up for its asset
+//! type, e.g. scripts in VS Code at a line and images in Aseprite:
+//!
+//! - `externalEditors.<kind>` holds the command line for the kinds in
+//!   `KINDS`; `{file}`, `{line}`, `{dir}` and `{project}` are substituted,
+//!   and words are split on spaces unless quoted
+//! - an empty command line, or a file of no listed kind, opens with the
+//!   system's default application
+//! - the program is looked up like `execute_command` does, with the
+//!   project's tool overrides and the login shell's PATH
+//! - the tool is not managed: it outlives the project and the editor. It is
+//!   tracked until it exits, when `external-editor-closed` is emitted so the
+//!   file can be picked up again; opening a file already open in the same
+//!   tool does not start a second one
+
+use crate::editor_settings;
+use crate::process::env::{login_path_default, CommandEnv};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const SETTING_PREFIX: &str = "externalEditors.";
+const VS_CODE: &str = "code {project} --goto {file}:{line}";
+
+/// Asset kinds as `(kind, extensions, default command line)`.
+const KINDS: &[(&str, &[&str], &str)] = &[
+    ("script", &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"], VS_CODE),
+    ("shader", &["esshader", "glsl", "wgsl", "vert", "frag"], ""),
+    ("data", &["json", "jsonc", "yaml", "yml", "xml", "csv", "txt", "md"], ""),
+    ("image", &["png", "jpg", "jpeg", "webp", "gif", "bmp", "psd", "aseprite", "ase"], ""),
+    ("audio", &["wav", "mp3", "ogg", "flac"], ""),
+];
+
+/// Where macOS apps keep their command line tool, for programs that are
+/// not on the PATH unless the user installed the shell command.
+#[cfg(target_os = "macos")]
+const MAC_APP_TOOLS: &[(&str, &str)] = &[
+    ("code", "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code"),
+    ("aseprite", "/Applications/Aseprite.app/Contents/MacOS/aseprite"),
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedFile {
+    pub path: String,
+    /// The program started; `None` for the system's default application.
+    pub program: Option<String>,
+    pub pid: Option<u32>,
+    pub started: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EditorClosed {
+    path: String,
+    program: String,
+    pid: u32,
+    code: Option<i32>,
+}
+
+/// Tools started by `open_in_external_editor` that are still running.
+#[derive(Default)]
+pub struct ExternalEditors {
+    running: Mutex<BTreeMap<u32, OpenedFile>>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Opens `path` in the external editor of its asset type, at `line` when
+/// the tool takes one (1 otherwise).
+#[tauri::command]
+pub async fn open_in_external_editor(
+    app: AppHandle,
+    path: String,
+    line: Option<u32>,
+    project_dir: Option<String>,
+) -> Result<OpenedFile, String> {
+    tokio::task::spawn_blocking(move || {
+        let file = PathBuf::from(&path);
+        if !file.exists() {
+            return Err(format!("Path not found: {}", file.display()));
+        }
+        let values = editor_settings::load(&app)?.values;
+        let Some(command_line) = command_line(&values, &file) else {
+            open::that(&file).map_err(|e| format!("Failed to open {}: {}", file.display(), e))?;
+            return Ok(OpenedFile { path, program: None, pid: None, started: crate::project::now_iso8601() });
+        };
+
+        let project = project_dir.as_deref().map(Path::new);
+        let mut words = split_words(&command_line)?.into_iter().map(|word| substitute(&word, &file, line, project));
+        let program = words.next().ok_or_else(|| "The external editor command is empty".to_string())?;
+        let env = CommandEnv::new(HashMap::new(), project, login_path_default());
+        let program = resolve(&env, &program)?;
+        let program_name = program.to_string_lossy().to_string();
+
+        let editors = app.state::<ExternalEditors>();
+        let mut running = editors.running.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(opened) = running
+            .values()
+            .find(|opened| opened.path == path && opened.program.as_deref() == Some(program_name.as_str()))
+        {
+            return Ok(opened.clone());
+        }
+
+        let mut command = Command::new(&program);
+        command.args(words).envs(env.vars()).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        if let Some(dir) = project.or(file.parent()) {
+            command.current_dir(dir);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(crate::process::CREATE_NO_WINDOW);
+        }
+        let mut child = command.spawn().map_err(|e| format!("Failed to start {}: {}", program_name, e))?;
+        let pid = child.id();
+        let opened = OpenedFile {
+            path: path.clone(),
+            program: Some(program_name.clone()),
+            pid: Some(pid),
+            started: crate::project::now_iso8601(),
+        };
+        running.insert(pid, opened.clone());
+        drop(running);
+        eprintln!("[external-editors] Opened {} in {}", path, program_name);
+
+        let handle = app.clone();
+        std::thread::spawn(move || {
+            let code = child.wait().ok().and_then(|status| status.code());
+            let editors = handle.state::<ExternalEditors>();
+            editors.running.lock().unwrap_or_else(|e| e.into_inner()).remove(&pid);
+            let _ = handle.emit("external-editor-closed", EditorClosed { path, program: program_name, pid, code });
+        });
+        Ok(opened)
+    })
+    .await
+    .map_err(|e| format!("External editor task failed: {}", e))?
+}
+
+/// Files open in external editors started by the editor.
+#[tauri::command]
+pub fn list_external_editors(editors: State<ExternalEditors>) -> Vec<OpenedFile> {
+    editors.running.lock().unwrap_or_else(|e| e.into_inner()).values().cloned().collect()
+}
+
+// =============================================================================
+// Command line
+// =============================================================================
+
+/// The command line for `file`, or `None` for the system's default
+/// application.
+fn command_line(values: &BTreeMap<String, Value>, file: &Path) -> Option<String> {
+    let ext = file.extension()?.to_string_lossy().to_lowercase();
+    let (kind, _, default) = KINDS.iter().find(|(_, extensions, _)| extensions.contains(&ext.as_str()))?;
+    let configured = values.get(&format!("{}{}", SETTING_PREFIX, kind)).and_then(Value::as_str);
+    Some(configured.unwrap_or(default).trim().to_string()).filter(|line| !line.is_empty())
+}
+
+/// Splits a command line on whitespace; double quotes keep spaces in a word.
+fn split_words(command_line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if quoted {
+        return Err(format!("Unclosed quote in external editor command: {}", command_line));
+    }
+    if started {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+fn substitute(word: &str, file: &Path, line: Option<u32>, project: Option<&Path>) -> String {
+    let dir = file.parent().unwrap_or(file);
+    word.replace("{file}", &file.to_string_lossy())
+        .replace("{line}", &line.unwrap_or(1).max(1).to_string())
+        .replace("{dir}", &dir.to_string_lossy())
+        .replace("{project}", &project.unwrap_or(dir).to_string_lossy())
+}
+
+fn resolve(env: &CommandEnv, program: &str) -> Result<PathBuf, String> {
+    if let Some(path) = env.find(program) {
+        return Ok(path);
+    }
+    #[cfg(target_os = "macos")]
+    if let Some((_, path)) = MAC_APP_TOOLS.iter().find(|(name, path)| *name == program && Path::new(path).is_file()) {
+        return Ok(PathBuf::from(path));
+    }
+    Err(format!("{} not found; set its full path in the external editor settings", program))
+}
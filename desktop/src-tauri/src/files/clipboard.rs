@@ -0,0 +1,82 @@
+//! Pasting clipboard images into the project
+//!
+//! Screenshots and images copied from other apps arrive as raw RGBA pixels;
+//! they are saved as a PNG in the Content Browser's folder and announced
+//! with the same `assets-imported` event as dropped files, so the asset
+//! database and thumbnails pick them up.
+
+use super::import::{unique_destination, ImportResult};
+use crate::export::assets::relative_path;
+use crate::jobs::{JobCategory, JobPriority, JobSpec};
+use image::{ImageFormat, RgbaImage};
+use serde::Serialize;
+use std::io::Cursor;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PastedImage {
+    /// Path of the new PNG, relative to the project.
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Saves the clipboard image as `pasted_<date>_<time>.png` in `folder`.
+#[tauri::command]
+pub async fn paste_clipboard_image(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    project_dir: String,
+    folder: String,
+) -> Result<PastedImage, String> {
+    let spec = JobSpec::new(JobCategory::Import, "Paste image").priority(JobPriority::High).project(&project_dir);
+    let job_app = app.clone();
+    state
+        .jobs
+        .run(&app, spec, move |_| paste(&job_app, Path::new(&project_dir), &folder))
+        .await
+}
+
+fn paste(app: &AppHandle, project_dir: &Path, folder: &str) -> Result<PastedImage, String> {
+    let folder = folder.replace('\\', "/").trim_matches('/').to_string();
+    if folder.split('/').any(|part| part == "..") {
+        return Err(format!("Paste folder is outside the project: {}", folder));
+    }
+    let target = project_dir.join(&folder);
+    if !target.is_dir() {
+        return Err(format!("Paste folder does not exist: {}", folder));
+    }
+
+    let image = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_image())
+        .map_err(|e| match e {
+            arboard::Error::ContentNotAvailable => "The clipboard does not contain an image".to_string(),
+            e => format!("Failed to read the clipboard: {}", e),
+        })?;
+    let (width, height) = (image.width as u32, image.height as u32);
+    let pixels = RgbaImage::from_raw(width, height, image.bytes.into_owned())
+        .ok_or_else(|| "The clipboard image has an unexpected size".to_string())?;
+    let mut png = Vec::new();
+    pixels
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+
+    let stamp = crate::project::now_iso8601();
+    let name = format!("pasted_{}_{}.png", stamp[..10].replace('-', ""), stamp[11..19].replace(':', ""));
+    let destination = unique_destination(&target.join(name));
+    crate::project::write_atomic(&destination, &png)?;
+
+    let path = relative_path(project_dir, &destination);
+    let _ = app.emit(
+        "assets-imported",
+        ImportResult {
+            project_dir: project_dir.to_string_lossy().to_string(),
+            folder,
+            imported: vec![path.clone()],
+            files: vec![path.clone()],
+            failed: Vec::new(),
+        },
+    );
+    Ok(PastedImage { path, width, height })
+}
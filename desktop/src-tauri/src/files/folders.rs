@@ -0,0 +1,481 @@
+//! Folder operations: duplicate, move with merge, and size
+//!
+//! Every operation treats an asset and its `.meta` sidecar as one unit and
+//! keeps going past individual failures, reporting them at the end instead
+//! of leaving the Content Browser with a half-finished operation and a
+//! single error. Duplicates get fresh UUIDs in their sidecars, and
+//! references between the duplicated assets are pointed at the copies;
+//! moves keep UUIDs, so references from elsewhere stay valid.
+
+use super::import::unique_destination;
+use super::DeleteFailure;
+use crate::export::assets::is_json_asset;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const SIZE_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// What to do when a moved file already exists at the destination. Folders
+/// that already exist are always merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Leave both files where they are.
+    Skip,
+    /// Overwrite the destination file.
+    Replace,
+    /// Move under a new name (`name_1.ext`).
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MoveResult {
+    /// Destination paths of moved files.
+    pub moved: Vec<String>,
+    /// Source paths left in place because of a conflict.
+    pub skipped: Vec<String>,
+    pub failed: Vec<DeleteFailure>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DuplicateResult {
+    /// The new file or folder.
+    pub path: String,
+    pub files: usize,
+    pub failed: Vec<DeleteFailure>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FolderSize {
+    pub request_id: String,
+    /// Size of the assets, sidecars excluded.
+    pub bytes: u64,
+    pub files: usize,
+    pub folders: usize,
+    pub meta_bytes: u64,
+    pub meta_files: usize,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Copies a file or folder next to itself as `name_copy`.
+#[tauri::command]
+pub async fn duplicate_path(path: String) -> Result<DuplicateResult, String> {
+    tokio::task::spawn_blocking(move || duplicate(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Duplicate task failed: {}", e))?
+}
+
+/// Moves `paths` into the folder `destination`, merging into folders that
+/// already exist there.
+#[tauri::command]
+pub async fn move_paths(
+    paths: Vec<String>,
+    destination: String,
+    on_conflict: ConflictPolicy,
+) -> Result<MoveResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let destination = PathBuf::from(destination);
+        if !destination.is_dir() {
+            return Err(format!("Destination is not a folder: {}", destination.display()));
+        }
+        let mut result = MoveResult::default();
+        for path in paths {
+            let source = PathBuf::from(&path);
+            if destination.starts_with(&source) {
+                fail(&mut result.failed, &source, "A folder cannot be moved into itself".to_string());
+                continue;
+            }
+            let Some(name) = source.file_name() else {
+                continue;
+            };
+            let target = destination.join(name);
+            if target == source {
+                continue;
+            }
+            move_entry(&source, &target, on_conflict, &mut result);
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Move task failed: {}", e))?
+}
+
+/// Totals a folder's size, emitting `folder-size-progress` with running
+/// totals while it walks.
+#[tauri::command]
+pub async fn get_folder_size(app: AppHandle, path: String, request_id: String) -> Result<FolderSize, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut size = FolderSize {
+            request_id,
+            ..FolderSize::default()
+        };
+        let mut last = Instant::now();
+        measure(Path::new(&path), &mut size, &mut |size| {
+            if last.elapsed() >= SIZE_PROGRESS_INTERVAL {
+                last = Instant::now();
+                let _ = app.emit("folder-size-progress", size);
+            }
+        });
+        Ok(size)
+    })
+    .await
+    .map_err(|e| format!("Size task failed: {}", e))?
+}
+
+// =============================================================================
+// Duplicate
+// =============================================================================
+
+fn duplicate(source: &Path) -> Result<DuplicateResult, String> {
+    if !source.exists() {
+        return Err(format!("Not found: {}", source.display()));
+    }
+    let parent = source.parent().ok_or("Cannot duplicate a root folder")?;
+    let name = copy_name(source);
+    let destination = unique_destination(&parent.join(name));
+
+    let mut result = DuplicateResult {
+        path: destination.to_string_lossy().to_string(),
+        ..DuplicateResult::default()
+    };
+    // Old UUID -> new UUID, for rewriting references among the copies.
+    let mut uuids = HashMap::new();
+    let mut copied = Vec::new();
+    copy_entry(source, &destination, &mut uuids, &mut copied, &mut result.failed);
+    if let Some(meta) = existing_sidecar(source) {
+        copy_meta(&meta, &sidecar(&destination), &mut uuids, &mut result.failed);
+    }
+
+    for file in &copied {
+        if uuids.is_empty() || !is_json_asset(&file.to_string_lossy()) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let rewritten = uuids.iter().fold(text.clone(), |text, (old, new)| text.replace(old.as_str(), new));
+        if rewritten != text {
+            if let Err(e) = std::fs::write(file, rewritten) {
+                fail(&mut result.failed, file, e.to_string());
+            }
+        }
+    }
+    result.files = copied.len();
+    Ok(result)
+}
+
+fn copy_entry(
+    source: &Path,
+    destination: &Path,
+    uuids: &mut HashMap<String, String>,
+    copied: &mut Vec<PathBuf>,
+    failed: &mut Vec<DeleteFailure>,
+) {
+    if source.is_dir() {
+        if let Err(e) = std::fs::create_dir_all(destination) {
+            fail(failed, destination, e.to_string());
+            return;
+        }
+        for entry in sorted_entries(source) {
+            let Some(name) = entry.file_name() else {
+                continue;
+            };
+            let target = destination.join(name);
+            if is_meta(&entry) {
+                copy_meta(&entry, &target, uuids, failed);
+            } else {
+                copy_entry(&entry, &target, uuids, copied, failed);
+            }
+        }
+    } else {
+        match std::fs::copy(source, destination) {
+            Ok(_) => copied.push(destination.to_path_buf()),
+            Err(e) => fail(failed, source, e.to_string()),
+        }
+    }
+}
+
+/// Copies a sidecar with a fresh UUID, keeping the importer settings.
+fn copy_meta(source: &Path, destination: &Path, uuids: &mut HashMap<String, String>, failed: &mut Vec<DeleteFailure>) {
+    let mut meta = match std::fs::read_to_string(source).map(|s| serde_json::from_str::<Value>(&s)) {
+        Ok(Ok(meta)) => meta,
+        // Unreadable sidecars are dropped; the editor regenerates them.
+        _ => return,
+    };
+    if let Some(old) = meta.get("uuid").and_then(Value::as_str).map(str::to_string) {
+        let new = new_uuid(&destination.to_string_lossy());
+        meta["uuid"] = Value::String(new.clone());
+        uuids.insert(old, new);
+    }
+    let written = serde_json::to_vec_pretty(&meta)
+        .map_err(|e| e.to_string())
+        .and_then(|data| std::fs::write(destination, data).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        fail(failed, source, e);
+    }
+}
+
+/// `name_copy.ext` for files, `name_copy` for folders.
+fn copy_name(path: &Path) -> String {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if path.is_dir() {
+        return format!("{}_copy", name);
+    }
+    match path.extension() {
+        Some(ext) => {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            format!("{}_copy.{}", stem, ext.to_string_lossy())
+        }
+        None => format!("{}_copy", name),
+    }
+}
+
+/// A random-looking version 4 UUID; no RNG dependency is needed for
+/// uniqueness within a project.
+pub(crate) fn new_uuid(seed: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let digest = Sha256::digest(format!("{}:{}:{}", seed, nanos, COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let mut bytes: [u8; 16] = digest[..16].try_into().expect("digest is 32 bytes");
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+// =============================================================================
+// Move
+// =============================================================================
+
+fn move_entry(source: &Path, target: &Path, policy: ConflictPolicy, result: &mut MoveResult) {
+    if source.is_dir() && target.is_dir() {
+        // Merge: assets first, each taking its sidecar along, then any
+        // sidecars without an asset. A sidecar whose asset stayed behind,
+        // skipped or failed, stays with it.
+        let entries = sorted_entries(source);
+        for entry in entries.iter().filter(|e| !is_meta(e)) {
+            if let Some(name) = entry.file_name() {
+                move_entry(entry, &target.join(name), policy, result);
+            }
+        }
+        for entry in entries.iter().filter(|e| is_meta(e) && e.exists() && !e.with_extension("").exists()) {
+            if let Some(name) = entry.file_name() {
+                move_file(entry, &target.join(name), policy, result);
+            }
+        }
+        // Only removed when everything inside was moved.
+        let _ = std::fs::remove_dir(source);
+        if let Some(meta) = existing_sidecar(source) {
+            if !source.exists() {
+                move_file(&meta, &sidecar(target), ConflictPolicy::Skip, result);
+            }
+        }
+        return;
+    }
+
+    let target = match (target.exists(), policy) {
+        (false, _) => target.to_path_buf(),
+        (true, ConflictPolicy::Skip) => {
+            result.skipped.push(source.to_string_lossy().to_string());
+            return;
+        }
+        (true, ConflictPolicy::KeepBoth) => unique_destination(target),
+        (true, ConflictPolicy::Replace) => target.to_path_buf(),
+    };
+    // What is replaced is only set aside, and put back if the move fails.
+    let aside = if target.exists() {
+        match set_aside(&target) {
+            Ok(aside) => aside,
+            Err(e) => {
+                fail(&mut result.failed, &target, e.to_string());
+                return;
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    let meta = existing_sidecar(source);
+    if rename_or_copy(source, &target, &mut result.failed) {
+        result.moved.push(target.to_string_lossy().to_string());
+        // The sidecar follows its asset.
+        if let Some(meta) = meta {
+            let _ = rename_or_copy(&meta, &sidecar(&target), &mut result.failed);
+        }
+        for (_, backup) in &aside {
+            if let Err(e) = remove_path(backup) {
+                fail(&mut result.failed, backup, format!("Replaced, but the old copy could not be removed: {}", e));
+            }
+        }
+    } else {
+        restore(&aside, &mut result.failed);
+    }
+}
+
+/// Renames `path` and its sidecar to hidden backups next to them, as
+/// (original, backup) pairs.
+fn set_aside(path: &Path) -> std::io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut aside = Vec::new();
+    for original in std::iter::once(path.to_path_buf()).chain(existing_sidecar(path)) {
+        let name = original.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let backup = unique_destination(&original.with_file_name(format!(".{}.replaced", name)));
+        if let Err(e) = std::fs::rename(&original, &backup) {
+            restore(&aside, &mut Vec::new());
+            return Err(e);
+        }
+        aside.push((original, backup));
+    }
+    Ok(aside)
+}
+
+/// Puts set-aside files back, removing whatever a failed move left there.
+fn restore(aside: &[(PathBuf, PathBuf)], failed: &mut Vec<DeleteFailure>) {
+    for (original, backup) in aside {
+        if original.exists() {
+            let _ = remove_path(original);
+        }
+        if let Err(e) = std::fs::rename(backup, original) {
+            fail(failed, original, format!("Could not be restored from {}: {}", backup.display(), e));
+        }
+    }
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+fn move_file(source: &Path, target: &Path, policy: ConflictPolicy, result: &mut MoveResult) {
+    if target.exists() && policy != ConflictPolicy::Replace {
+        result.skipped.push(source.to_string_lossy().to_string());
+        return;
+    }
+    if rename_or_copy(source, target, &mut result.failed) {
+        result.moved.push(target.to_string_lossy().to_string());
+    }
+}
+
+/// Renames, falling back to copy-and-delete across file systems.
+fn rename_or_copy(source: &Path, target: &Path, failed: &mut Vec<DeleteFailure>) -> bool {
+    if std::fs::rename(source, target).is_ok() {
+        return true;
+    }
+    let mut copied = Vec::new();
+    let before = failed.len();
+    if source.is_dir() {
+        copy_dir_verbatim(source, target, &mut copied, failed);
+    } else if let Err(e) = std::fs::copy(source, target) {
+        fail(failed, source, e.to_string());
+    }
+    if failed.len() > before {
+        return false;
+    }
+    let removed = if source.is_dir() {
+        std::fs::remove_dir_all(source)
+    } else {
+        std::fs::remove_file(source)
+    };
+    if let Err(e) = removed {
+        fail(failed, source, format!("Copied, but the original could not be removed: {}", e));
+    }
+    true
+}
+
+/// Copies a folder as is, sidecars and UUIDs included.
+fn copy_dir_verbatim(source: &Path, target: &Path, copied: &mut Vec<PathBuf>, failed: &mut Vec<DeleteFailure>) {
+    if let Err(e) = std::fs::create_dir_all(target) {
+        fail(failed, target, e.to_string());
+        return;
+    }
+    for entry in sorted_entries(source) {
+        let Some(name) = entry.file_name() else {
+            continue;
+        };
+        let destination = target.join(name);
+        if entry.is_dir() {
+            copy_dir_verbatim(&entry, &destination, copied, failed);
+        } else {
+            match std::fs::copy(&entry, &destination) {
+                Ok(_) => copied.push(destination),
+                Err(e) => fail(failed, &entry, e.to_string()),
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Size
+// =============================================================================
+
+fn measure(path: &Path, size: &mut FolderSize, progress: &mut dyn FnMut(&FolderSize)) {
+    for entry in sorted_entries(path) {
+        let Ok(metadata) = std::fs::symlink_metadata(&entry) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            size.folders += 1;
+            measure(&entry, size, progress);
+        } else if is_meta(&entry) {
+            size.meta_files += 1;
+            size.meta_bytes += metadata.len();
+        } else {
+            size.files += 1;
+            size.bytes += metadata.len();
+        }
+        progress(size);
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn is_meta(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "meta")
+}
+
+fn sidecar(path: &Path) -> PathBuf {
+    let mut meta = path.as_os_str().to_owned();
+    meta.push(".meta");
+    PathBuf::from(meta)
+}
+
+fn existing_sidecar(path: &Path) -> Option<PathBuf> {
+    Some(sidecar(path)).filter(|meta| meta.is_file())
+}
+
+fn fail(failed: &mut Vec<DeleteFailure>, path: &Path, error: String) {
+    failed.push(DeleteFailure {
+        path: path.to_string_lossy().to_string(),
+        error,
+    });
+}
@@ -0,0 +1,252 @@
+//! Importing external files into the project
+//!
+//! Files and folders dropped on the editor window from the OS file manager
+//! are copied into the folder the Content Browser is showing, which the
+//! frontend keeps current through `set_drop_target`. `.meta` sidecars from
+//! elsewhere are never copied: their UUIDs would collide with the source
+//! project, so the frontend's asset database creates fresh ones when it
+//! receives `assets-imported`.
+//!
+//! Native drops are only delivered where the window enables them; on
+//! Windows the webview keeps HTML5 drag and drop, which the editor's own
+//! panels rely on.
+
+use crate::jobs::{JobCategory, JobContext, JobPriority, JobSpec};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, State};
+
+/// Folder names never copied out of a dropped folder.
+const SKIPPED_DIRS: &[&str] = &[".git", ".svn", ".esengine", "node_modules"];
+const SKIPPED_FILES: &[&str] = &[".DS_Store", "Thumbs.db", "desktop.ini"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Where dropped files go: a folder relative to the project.
+#[derive(Default)]
+pub struct DropTarget {
+    target: Mutex<Option<(PathBuf, String)>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportResult {
+    pub project_dir: String,
+    pub folder: String,
+    /// Top-level imported files and folders, relative to the project; the
+    /// Content Browser selects these.
+    pub imported: Vec<String>,
+    /// Every file written, relative to the project.
+    pub files: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+#[derive(Clone, Serialize)]
+struct ImportProgress {
+    current: usize,
+    total: usize,
+    path: String,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Sets the folder dropped files are copied to; `None` while no project is
+/// open.
+#[tauri::command]
+pub fn set_drop_target(target: State<'_, DropTarget>, project_dir: Option<String>, folder: Option<String>) {
+    *target.target.lock().unwrap_or_else(|e| e.into_inner()) =
+        project_dir.map(|dir| (PathBuf::from(dir), folder.unwrap_or_else(|| "assets".to_string())));
+}
+
+/// Copies `paths` into `folder` of the project, emitting the same events
+/// as a drop.
+#[tauri::command]
+pub async fn import_external_files(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    project_dir: String,
+    folder: String,
+    paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    let paths = paths.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+    let spec = JobSpec::new(JobCategory::Import, format!("Import {} item(s)", paths.len())).project(&project_dir);
+    let job_app = app.clone();
+    state
+        .jobs
+        .run(&app, spec, move |job| run(&job_app, Path::new(&project_dir), &folder, &paths, job))
+        .await
+}
+
+// =============================================================================
+// Window drops
+// =============================================================================
+
+/// Handles native drag-and-drop events of the main window.
+pub(crate) fn handle_drag_drop(app: &AppHandle, event: &DragDropEvent) {
+    match event {
+        DragDropEvent::Enter { position, .. } | DragDropEvent::Over { position } => {
+            let _ = app.emit("file-drop-hover", serde_json::json!({ "x": position.x, "y": position.y }));
+        }
+        DragDropEvent::Leave => {
+            let _ = app.emit("file-drop-cancelled", ());
+        }
+        DragDropEvent::Drop { paths, .. } => {
+            let target = app
+                .state::<DropTarget>()
+                .target
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            let Some((project_dir, folder)) = target else {
+                let _ = app.emit("assets-import-failed", "Open a project before importing files");
+                return;
+            };
+            let app = app.clone();
+            let paths = paths.clone();
+            tauri::async_runtime::spawn(async move {
+                let spec = JobSpec::new(JobCategory::Import, format!("Import {} item(s)", paths.len()))
+                    .priority(JobPriority::High)
+                    .project(&project_dir);
+                let job_app = app.clone();
+                let state = app.state::<crate::AppState>();
+                let imported = state
+                    .jobs
+                    .run(&app, spec, move |job| run(&job_app, &project_dir, &folder, &paths, job))
+                    .await;
+                if let Err(e) = imported {
+                    let _ = app.emit("assets-import-failed", e);
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+// =============================================================================
+// Import
+// =============================================================================
+
+/// Copies `paths` into `folder`. A cancelled `job` stops between files; what
+/// was copied by then is still announced.
+fn run(
+    app: &AppHandle,
+    project_dir: &Path,
+    folder: &str,
+    paths: &[PathBuf],
+    job: &JobContext,
+) -> Result<ImportResult, String> {
+    let folder = folder.replace('\\', "/").trim_matches('/').to_string();
+    if folder.split('/').any(|part| part == "..") {
+        return Err(format!("Import folder is outside the project: {}", folder));
+    }
+    let target = project_dir.join(&folder);
+    if !target.is_dir() {
+        return Err(format!("Import folder does not exist: {}", folder));
+    }
+
+    let mut plan = Vec::new();
+    let mut result = ImportResult {
+        project_dir: project_dir.to_string_lossy().to_string(),
+        folder: folder.clone(),
+        ..ImportResult::default()
+    };
+    for source in paths {
+        let Some(name) = source.file_name() else {
+            continue;
+        };
+        if target.starts_with(source) {
+            result.failed.push(ImportFailure {
+                path: source.to_string_lossy().to_string(),
+                error: "A folder cannot be imported into itself".to_string(),
+            });
+            continue;
+        }
+        // Dropping a file onto the folder it is already in is a no-op.
+        if source.parent() == Some(target.as_path()) {
+            continue;
+        }
+        let destination = unique_destination(&target.join(name));
+        result.imported.push(crate::export::assets::relative_path(project_dir, &destination));
+        collect(source, &destination, &mut plan);
+    }
+
+    let total = plan.len();
+    for (index, (source, destination)) in plan.into_iter().enumerate() {
+        if job.is_cancelled() {
+            break;
+        }
+        let rel = crate::export::assets::relative_path(project_dir, &destination);
+        job.progress(index as f32 / total as f32, &rel);
+        let _ = app.emit(
+            "assets-import-progress",
+            ImportProgress {
+                current: index + 1,
+                total,
+                path: rel.clone(),
+            },
+        );
+        let copied = destination
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::copy(&source, &destination).map(|_| ()));
+        match copied {
+            Ok(()) => result.files.push(rel),
+            Err(e) => result.failed.push(ImportFailure {
+                path: source.to_string_lossy().to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let _ = app.emit("assets-imported", &result);
+    Ok(result)
+}
+
+/// Expands `source` into `(file, destination)` pairs, skipping sidecars and
+/// VCS or OS clutter.
+fn collect(source: &Path, destination: &Path, plan: &mut Vec<(PathBuf, PathBuf)>) {
+    let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if source.is_dir() {
+        if SKIPPED_DIRS.contains(&name.as_str()) {
+            return;
+        }
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(source)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if let Some(child) = entry.file_name() {
+                collect(&entry, &destination.join(child), plan);
+            }
+        }
+    } else if source.is_file() && !name.ends_with(".meta") && !SKIPPED_FILES.contains(&name.as_str()) {
+        plan.push((source.to_path_buf(), destination.to_path_buf()));
+    }
+}
+
+/// `path`, or `name_1.ext`, `name_2.ext`, ... when it is taken.
+pub(crate) fn unique_destination(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| parent.join(format!("{}_{}{}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .expect("unbounded range")
+}
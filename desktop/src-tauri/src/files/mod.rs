@@ -0,0 +1,202 @@
+//! Content Browser file operations
+//!
+//! `delete_to_trash` moves files and folders to the recycle bin, taking each
+//! asset's `.meta` sidecar with it. Where the platform exposes the trash
+//! contents (Windows and freedesktop Linux) the deleted items are recorded
+//! under an undo token that `restore_from_trash` puts back; macOS has no
+//! restore API, so no token is returned there. Copying external files into
+//! the project lives in `import`, pasting clipboard images in `clipboard`,
+//! and duplicating, moving and measuring folders in `folders`.
+
+pub mod clipboard;
+pub mod folders;
+pub mod import;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::State;
+
+/// Platforms where the `trash` crate can list and restore trashed items.
+const RESTORE_SUPPORTED: bool = cfg!(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+));
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Trashed paths per undo token.
+#[derive(Default)]
+pub struct TrashRegistry {
+    next_token: AtomicU64,
+    entries: Mutex<HashMap<String, Vec<PathBuf>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeleteResult {
+    /// Paths moved to the recycle bin, sidecars included.
+    pub trashed: Vec<String>,
+    /// Paths removed permanently because the recycle bin was unavailable.
+    pub deleted: Vec<String>,
+    pub failed: Vec<DeleteFailure>,
+    /// Passed to `restore_from_trash` to undo; `None` when restoring is not
+    /// supported or nothing was trashed.
+    pub undo_token: Option<String>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Moves `paths` to the recycle bin. With `permanent_fallback`, paths the
+/// recycle bin rejects (network drives, some removable media) are deleted
+/// permanently instead of failing.
+#[tauri::command]
+pub async fn delete_to_trash(
+    registry: State<'_, TrashRegistry>,
+    paths: Vec<String>,
+    permanent_fallback: bool,
+) -> Result<DeleteResult, String> {
+    let targets = with_sidecars(&paths);
+    let mut result = tokio::task::spawn_blocking(move || delete(&targets, permanent_fallback))
+        .await
+        .map_err(|e| format!("Delete task failed: {}", e))?;
+
+    if RESTORE_SUPPORTED && !result.trashed.is_empty() {
+        let id = registry.next_token.fetch_add(1, Ordering::Relaxed) + 1;
+        let token = format!("trash-{}", id);
+        let trashed = result.trashed.iter().map(PathBuf::from).collect();
+        registry
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(token.clone(), trashed);
+        result.undo_token = Some(token);
+    }
+    Ok(result)
+}
+
+/// Restores the items deleted under `token` to their original locations.
+#[tauri::command]
+pub async fn restore_from_trash(registry: State<'_, TrashRegistry>, token: String) -> Result<Vec<String>, String> {
+    let paths = registry
+        .entries
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&token)
+        .ok_or_else(|| "Nothing to restore: the undo token is unknown or was already used".to_string())?;
+    tokio::task::spawn_blocking(move || restore(&paths))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))?
+}
+
+// =============================================================================
+// Deletion
+// =============================================================================
+
+/// `paths` plus the existing `.meta` sidecar of each, without duplicates.
+fn with_sidecars(paths: &[String]) -> Vec<PathBuf> {
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let path = PathBuf::from(path);
+        let mut meta = path.clone().into_os_string();
+        meta.push(".meta");
+        let meta = PathBuf::from(meta);
+        for candidate in [path, meta] {
+            if candidate.exists() && !targets.contains(&candidate) {
+                targets.push(candidate);
+            }
+        }
+    }
+    // A path inside a folder that is also being deleted goes with the folder.
+    let folders: Vec<PathBuf> = targets.iter().filter(|p| p.is_dir()).cloned().collect();
+    targets.retain(|p| !folders.iter().any(|f| p != f && p.starts_with(f)));
+    targets
+}
+
+fn delete(targets: &[PathBuf], permanent_fallback: bool) -> DeleteResult {
+    let mut result = DeleteResult::default();
+    for path in targets {
+        let display = path.to_string_lossy().to_string();
+        match trash::delete(path) {
+            Ok(()) => result.trashed.push(display),
+            Err(e) if permanent_fallback => match remove_permanently(path) {
+                Ok(()) => result.deleted.push(display),
+                Err(err) => result.failed.push(DeleteFailure {
+                    path: display,
+                    error: format!("{} (recycle bin: {})", err, e),
+                }),
+            },
+            Err(e) => result.failed.push(DeleteFailure {
+                path: display,
+                error: format!("Failed to move to the recycle bin: {}", e),
+            }),
+        }
+    }
+    result
+}
+
+fn remove_permanently(path: &Path) -> Result<(), String> {
+    let removed = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    removed.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+// =============================================================================
+// Restore
+// =============================================================================
+
+#[cfg(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+))]
+fn restore(paths: &[PathBuf]) -> Result<Vec<String>, String> {
+    use trash::os_limited;
+
+    let items = os_limited::list().map_err(|e| format!("Failed to read the recycle bin: {}", e))?;
+    // The same path may have been trashed more than once; take the newest.
+    let mut matched: HashMap<PathBuf, trash::TrashItem> = HashMap::new();
+    for item in items {
+        let original = item.original_path();
+        if !paths.contains(&original) {
+            continue;
+        }
+        let newer = match matched.get(&original) {
+            Some(m) => m.time_deleted < item.time_deleted,
+            None => true,
+        };
+        if newer {
+            matched.insert(original, item);
+        }
+    }
+    if matched.is_empty() {
+        return Err("The deleted items are no longer in the recycle bin".to_string());
+    }
+    if let Some(existing) = matched.keys().find(|p| p.exists()) {
+        return Err(format!("Cannot restore: {} already exists", existing.display()));
+    }
+    let restored = matched.keys().map(|p| p.to_string_lossy().to_string()).collect();
+    os_limited::restore_all(matched.into_values()).map_err(|e| format!("Failed to restore: {}", e))?;
+    Ok(restored)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+)))]
+fn restore(_paths: &[PathBuf]) -> Result<Vec<String>, String> {
+    Err("Restoring from the recycle bin is not supported on this platform".to_string())
+}
@@ -0,0 +1,282 @@
+//! Formatting — scripts, JSON and scenes in one canonical layout
+//!
+//! `format_files` formats files and folders in place:
+//!
+//! - TypeScript and JavaScript with dprint's TypeScript formatter, in the
+//!   style of the templates (single quotes, semicolons)
+//! - `.json` and `.jsonc` with dprint's JSON formatter
+//! - scenes and prefabs are re-serialized the way the editor writes them,
+//!   with their keys in a fixed order: structural keys first (`id`, `name`,
+//!   `parent`, ... and `type` before `data`), vectors as `x, y, z, w`,
+//!   colors as `r, g, b, a` and everything else alphabetically
+//!
+//! Folders are walked like project search walks them, so ignored and
+//! hidden files, `node_modules`, `build` and `dist` are left alone. With
+//! `format.onSave` set, `save_scene` canonicalizes scenes as it saves them.
+
+use crate::editor_settings;
+use crate::project::write_durable;
+use dprint_plugin_typescript::configuration::QuoteStyle;
+use dprint_plugin_typescript::FormatTextOptions;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const ON_SAVE_SETTING: &str = "format.onSave";
+const INDENT_SETTING: &str = "format.indentWidth";
+const LINE_WIDTH_SETTING: &str = "format.lineWidth";
+const DEFAULT_INDENT_WIDTH: u8 = 4;
+const DEFAULT_LINE_WIDTH: u32 = 120;
+/// Folders never formatted even when not ignored.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "build", "dist"];
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+const JSON_EXTENSIONS: &[&str] = &["json", "jsonc"];
+const SCENE_EXTENSIONS: &[&str] = &["esscene", "esprefab"];
+
+/// Key order of a scene or prefab document.
+const DOCUMENT_KEYS: &[&str] = &["version", "name", "rootEntityId", "entities"];
+const ENTITY_KEYS: &[&str] = &["id", "prefabEntityId", "name", "parent", "children", "components", "visible"];
+const COMPONENT_KEYS: &[&str] = &["type", "data"];
+const VECTOR_KEYS: &[&str] = &["x", "y", "z", "w"];
+const COLOR_KEYS: &[&str] = &["r", "g", "b", "a"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FormatReport {
+    /// Files that were rewritten, or with `check` would be.
+    pub formatted: Vec<String>,
+    pub unchanged: usize,
+    pub failed: Vec<FormatFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatFailure {
+    pub path: String,
+    pub error: String,
+}
+
+struct Formatters {
+    typescript: dprint_plugin_typescript::configuration::Configuration,
+    json: dprint_plugin_json::configuration::Configuration,
+}
+
+impl Formatters {
+    fn new(app: &AppHandle) -> Self {
+        let values = editor_settings::load(app).map(|settings| settings.values).unwrap_or_default();
+        let indent_width = values
+            .get(INDENT_SETTING)
+            .and_then(Value::as_u64)
+            .and_then(|n| u8::try_from(n).ok())
+            .unwrap_or(DEFAULT_INDENT_WIDTH);
+        let line_width = values
+            .get(LINE_WIDTH_SETTING)
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(DEFAULT_LINE_WIDTH);
+        Self {
+            typescript: dprint_plugin_typescript::configuration::ConfigurationBuilder::new()
+                .indent_width(indent_width)
+                .line_width(line_width)
+                .quote_style(QuoteStyle::PreferSingle)
+                .build(),
+            json: dprint_plugin_json::configuration::ConfigurationBuilder::new()
+                .indent_width(indent_width)
+                .line_width(line_width)
+                .build(),
+        }
+    }
+
+    /// The formatted contents of `path`, or `None` when they are unchanged.
+    fn format(&self, path: &Path, text: &str) -> Result<Option<String>, String> {
+        let ext = extension(path);
+        let formatted = if SCRIPT_EXTENSIONS.contains(&ext.as_str()) {
+            dprint_plugin_typescript::format_text(FormatTextOptions {
+                path,
+                extension: None,
+                text: text.to_string(),
+                config: &self.typescript,
+                external_formatter: None,
+            })
+            .map_err(|e| e.to_string())?
+        } else if JSON_EXTENSIONS.contains(&ext.as_str()) {
+            dprint_plugin_json::format_text(path, text, &self.json).map_err(|e| e.to_string())?
+        } else if SCENE_EXTENSIONS.contains(&ext.as_str()) {
+            Some(canonicalize_scene(text)?)
+        } else {
+            return Err("Unsupported file type".to_string());
+        };
+        Ok(formatted.filter(|formatted| formatted != text))
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Formats `paths`, files or folders. With `check` nothing is written and
+/// the report lists the files that are not formatted.
+#[tauri::command]
+pub async fn format_files(app: AppHandle, paths: Vec<String>, check: Option<bool>) -> Result<FormatReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let formatters = Formatters::new(&app);
+        let check = check.unwrap_or(false);
+        let mut report = FormatReport::default();
+        for path in paths {
+            let path = PathBuf::from(path);
+            let files = if path.is_dir() { collect_files(&path) } else { vec![path] };
+            for file in files {
+                let display = file.to_string_lossy().to_string();
+                match format_file(&formatters, &file, check) {
+                    Ok(true) => report.formatted.push(display),
+                    Ok(false) => report.unchanged += 1,
+                    Err(error) => report.failed.push(FormatFailure { path: display, error }),
+                }
+            }
+        }
+        if !check && !report.formatted.is_empty() {
+            eprintln!("[format] Formatted {} file(s)", report.formatted.len());
+        }
+        report
+    })
+    .await
+    .map_err(|e| format!("Format task failed: {}", e))
+}
+
+// =============================================================================
+// Formatting
+// =============================================================================
+
+/// Whether `save_scene` should canonicalize scenes.
+pub(crate) fn format_on_save(app: &AppHandle) -> bool {
+    let values = editor_settings::load(app).map(|settings| settings.values).unwrap_or_default();
+    values.get(ON_SAVE_SETTING).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Re-serializes a scene or prefab in canonical key order, indented by two
+/// spaces like the editor writes it.
+pub(crate) fn canonicalize_scene(text: &str) -> Result<String, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut out = String::with_capacity(text.len());
+    write_canonical(&mut out, &value, Level::Document, 0);
+    Ok(out)
+}
+
+/// Whether the file changed (or with `check`, would change).
+fn format_file(formatters: &Formatters, path: &Path, check: bool) -> Result<bool, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+    let Some(formatted) = formatters.format(path, &text)? else {
+        return Ok(false);
+    };
+    if !check {
+        write_durable(path, formatted.as_bytes())?;
+    }
+    Ok(true)
+}
+
+fn collect_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkBuilder::new(dir)
+        .filter_entry(|entry| {
+            !(entry.file_type().is_some_and(|t| t.is_dir())
+                && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let ext = extension(path);
+            [SCRIPT_EXTENSIONS, JSON_EXTENSIONS, SCENE_EXTENSIONS].iter().any(|list| list.contains(&ext.as_str()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+// =============================================================================
+// Canonical scene JSON
+// =============================================================================
+
+/// Where in a scene document an object sits, which decides its key order.
+#[derive(Clone, Copy)]
+enum Level {
+    Document,
+    Entity,
+    Component,
+    Other,
+}
+
+impl Level {
+    fn keys(self, map: &serde_json::Map<String, Value>) -> &'static [&'static str] {
+        match self {
+            Level::Document => DOCUMENT_KEYS,
+            Level::Entity => ENTITY_KEYS,
+            Level::Component => COMPONENT_KEYS,
+            Level::Other if map.keys().all(|k| VECTOR_KEYS.contains(&k.as_str())) => VECTOR_KEYS,
+            Level::Other if map.keys().all(|k| COLOR_KEYS.contains(&k.as_str())) => COLOR_KEYS,
+            Level::Other => &[],
+        }
+    }
+
+    /// The level of the value under `key`, or of the items of its array.
+    fn child(self, key: &str) -> Level {
+        match (self, key) {
+            (Level::Document, "entities") => Level::Entity,
+            (Level::Entity, "components") => Level::Component,
+            _ => Level::Other,
+        }
+    }
+}
+
+/// Writes `value` the way `JSON.stringify(value, null, 2)` lays it out.
+fn write_canonical(out: &mut String, value: &Value, level: Level, depth: usize) {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_canonical(out, item, level, depth + 1);
+            }
+            newline(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            let order = level.keys(map);
+            let rank = |key: &str| order.iter().position(|k| *k == key).unwrap_or(order.len());
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push_str(": ");
+                write_canonical(out, &map[key], level.child(key), depth + 1);
+            }
+            newline(out, depth);
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+fn newline(out: &mut String, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
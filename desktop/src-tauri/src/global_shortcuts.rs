@@ -0,0 +1,236 @@
+//! Global shortcuts — editor actions reachable while another app has focus
+//!
+//! Jumping back to the editor from the preview in a browser, or opening the
+//! developer tools, should not need the editor window focused first. These
+//! actions are registered with the OS as global shortcuts:
+//!
+//! - `ACTIONS` lists them with their default accelerators; the setting
+//!   `globalShortcuts.<action>` remaps one, and an empty value turns it off
+//! - they are registered at startup and again whenever the settings change;
+//!   one the OS refuses, usually because another app holds it, is reported
+//!   to the main window as `global-shortcut-failed` and by
+//!   `list_global_shortcuts`
+//! - focusing the editor, the developer tools and reloading previews are
+//!   handled here; other actions are emitted to the main window as
+//!   `global-shortcut` with the action id, which runs the editor menu item
+//!   of that id
+
+use crate::editor_settings;
+use crate::launch;
+use crate::windows::MAIN_LABEL;
+use crate::AppState;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+const SETTING_PREFIX: &str = "globalShortcuts.";
+
+const FOCUS_EDITOR: &str = "app.focus-editor";
+const TOGGLE_DEVTOOLS: &str = "view.devtools";
+const RELOAD_PREVIEW: &str = "preview.reload";
+
+struct Action {
+    id: &'static str,
+    label: &'static str,
+    accelerator: Option<&'static str>,
+}
+
+const ACTIONS: &[Action] = &[
+    Action { id: FOCUS_EDITOR, label: "Focus Editor", accelerator: Some("CmdOrCtrl+Alt+Shift+E") },
+    Action { id: RELOAD_PREVIEW, label: "Reload Preview", accelerator: Some("CmdOrCtrl+Alt+Shift+R") },
+    Action { id: TOGGLE_DEVTOOLS, label: "Toggle Developer Tools", accelerator: Some("CmdOrCtrl+Alt+Shift+I") },
+    Action { id: "file.preview", label: "Preview", accelerator: None },
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalShortcutInfo {
+    pub id: String,
+    pub label: String,
+    /// `None` when turned off.
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub registered: bool,
+    /// Why the accelerator could not be registered.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShortcutFailed {
+    id: String,
+    label: String,
+    accelerator: String,
+    error: String,
+}
+
+#[derive(Default)]
+struct Registration {
+    /// Registered accelerators as `(accelerator, shortcut)`, by action id.
+    registered: BTreeMap<&'static str, (String, Shortcut)>,
+    /// Actions the OS refused, with the reason.
+    failed: BTreeMap<&'static str, (String, String)>,
+}
+
+/// The shortcuts this editor holds.
+#[derive(Default)]
+pub struct GlobalShortcuts {
+    inner: Mutex<Registration>,
+}
+
+// =============================================================================
+// Registration
+// =============================================================================
+
+/// Registers the shortcuts of the saved settings and follows every change.
+pub fn init(app: &AppHandle) {
+    apply(app);
+    let handle = app.clone();
+    app.listen_any("editor-settings-changed", move |_| {
+        for failed in apply(&handle) {
+            let _ = handle.emit_to(MAIN_LABEL, "global-shortcut-failed", failed);
+        }
+    });
+}
+
+/// Brings the registered shortcuts in line with the settings. Returns the
+/// actions that newly failed to register.
+fn apply(app: &AppHandle) -> Vec<ShortcutFailed> {
+    let values = editor_settings::load(app).map(|settings| settings.values).unwrap_or_default();
+    let wanted: BTreeMap<&'static str, String> =
+        ACTIONS.iter().filter_map(|action| Some((action.id, accelerator(&values, action)?))).collect();
+
+    let global = app.global_shortcut();
+    let state = app.state::<GlobalShortcuts>();
+    let mut current = state.inner.lock().unwrap_or_else(|e| e.into_inner());
+    current.registered.retain(|id, (accelerator, shortcut)| {
+        if wanted.get(id) == Some(&*accelerator) {
+            return true;
+        }
+        if let Err(e) = global.unregister(*shortcut) {
+            eprintln!("[global-shortcuts] Failed to unregister {} of {}: {}", accelerator, id, e);
+        }
+        false
+    });
+
+    let previous = std::mem::take(&mut current.failed);
+    for (id, accelerator) in wanted {
+        if current.registered.contains_key(id) {
+            continue;
+        }
+        let registered = accelerator
+            .parse::<Shortcut>()
+            .map_err(|e| e.to_string())
+            .and_then(|shortcut| global.register(shortcut).map(|()| shortcut).map_err(|e| e.to_string()));
+        match registered {
+            Ok(shortcut) => {
+                current.registered.insert(id, (accelerator, shortcut));
+            }
+            Err(e) => {
+                eprintln!("[global-shortcuts] Failed to register {} for {}: {}", accelerator, id, e);
+                current.failed.insert(id, (accelerator, e));
+            }
+        }
+    }
+
+    current
+        .failed
+        .iter()
+        .filter(|(id, failure)| previous.get(*id) != Some(*failure))
+        .map(|(id, (accelerator, error))| ShortcutFailed {
+            id: id.to_string(),
+            label: label(id).to_string(),
+            accelerator: accelerator.clone(),
+            error: error.clone(),
+        })
+        .collect()
+}
+
+/// The accelerator of `action` in the settings, else its default; `None`
+/// when turned off.
+fn accelerator(values: &BTreeMap<String, Value>, action: &Action) -> Option<String> {
+    match values.get(&format!("{}{}", SETTING_PREFIX, action.id)).and_then(Value::as_str) {
+        Some(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty()),
+        None => action.accelerator.map(str::to_string),
+    }
+}
+
+fn label(id: &str) -> &'static str {
+    ACTIONS.iter().find(|action| action.id == id).map(|action| action.label).unwrap_or_default()
+}
+
+/// Checks the `globalShortcuts.*` values before the settings are saved.
+pub fn validate(values: &BTreeMap<String, Value>, issues: &mut Vec<String>) {
+    let mut taken: BTreeMap<String, &str> = BTreeMap::new();
+    for (key, value) in values.iter().filter(|(key, _)| key.starts_with(SETTING_PREFIX)) {
+        let Some(accelerator) = value.as_str() else {
+            issues.push(format!("{}: must be a string", key));
+            continue;
+        };
+        if accelerator.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = accelerator.trim().parse::<Shortcut>() {
+            issues.push(format!("{}: '{}' is not a valid accelerator ({})", key, accelerator, e));
+        }
+    }
+    for action in ACTIONS {
+        let Some(accelerator) = accelerator(values, action) else {
+            continue;
+        };
+        if let Some(other) = taken.insert(accelerator.to_ascii_lowercase(), action.id) {
+            issues.push(format!("{}{}: '{}' is already used by {}", SETTING_PREFIX, action.id, accelerator, other));
+        }
+    }
+}
+
+// =============================================================================
+// Events
+// =============================================================================
+
+/// Runs the action of a pressed global shortcut.
+pub fn handle(app: &AppHandle, shortcut: &Shortcut) {
+    let id = {
+        let state = app.state::<GlobalShortcuts>();
+        let current = state.inner.lock().unwrap_or_else(|e| e.into_inner());
+        current.registered.iter().find(|(_, (_, registered))| registered == shortcut).map(|(id, _)| *id)
+    };
+    match id {
+        Some(FOCUS_EDITOR) => launch::focus_main(app),
+        Some(TOGGLE_DEVTOOLS) => crate::toggle_devtools(app.clone()),
+        Some(RELOAD_PREVIEW) => crate::notify_preview_reload(app.state::<AppState>(), None),
+        Some(id) => {
+            let _ = app.emit_to(MAIN_LABEL, "global-shortcut", id);
+        }
+        None => {}
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_global_shortcuts(app: AppHandle) -> Result<Vec<GlobalShortcutInfo>, String> {
+    let values = editor_settings::load(&app)?.values;
+    let state = app.state::<GlobalShortcuts>();
+    let current = state.inner.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(ACTIONS
+        .iter()
+        .map(|action| GlobalShortcutInfo {
+            id: action.id.to_string(),
+            label: action.label.to_string(),
+            accelerator: accelerator(&values, action),
+            default_accelerator: action.accelerator.map(str::to_string),
+            registered: current.registered.contains_key(action.id),
+            error: current.failed.get(action.id).map(|(_, error)| error.clone()),
+        })
+        .collect())
+}
@@ -0,0 +1,353 @@
+//! Headless mode — drives the editor from the command line without a window
+//!
+//! `esengine-editor --headless <command>` runs one of a curated set of
+//! editor commands, for CI and QA automation:
+//!
+//! - `open <project>` checks that the project opens and reports its name,
+//!   versions and build profiles
+//! - `check <project> [--strict]` runs the project health check; it fails
+//!   on errors, and with `--strict` on warnings too
+//! - `build <project> [--profile <name>] [--target <id>] [--output <dir>] [--clean]`
+//!   runs asset import and the export pipeline for a build profile
+//! - `run <script.json>` runs several of them against one project
+//!
+//! Everything is printed to stdout as JSON lines: `progress`, `warning` and
+//! `output` events while a command runs, then `{"event": "result", ...}`
+//! with the command's structured result and, when it failed,
+//! `{"event": "error", ...}`. The process exits with 0 on success, 1 when
+//! a command fails and 2 on invalid arguments.
+//!
+//! A script names the project, relative to the script, and the steps to
+//! run in order; it stops at the first failing step unless
+//! `continueOnError` is set:
+//!
+//! ```json
+//! { "project": "../game", "steps": [{ "command": "open" }, { "command": "check" },
+//!   { "command": "build", "profile": "WeChat" }] }
+//! ```
+//!
+//! Each step is framed by `step-start` and `step-end` events, and the final
+//! result lists every step with its outcome and duration.
+//!
+//! A project pinned to an engine version builds against the bundle in
+//! `$ESENGINE_ENGINES_DIR/<version>/`.
+//!
+//! Release builds on Windows use the GUI subsystem and have no console of
+//! their own; redirect stdout to capture the output.
+
+use crate::export::{profiles, run_export, ExportListener};
+use crate::project::check::run_checks;
+use crate::project::settings::ProjectSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub const FLAG: &str = "--headless";
+
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "Usage: esengine-editor --headless open <project>
+       esengine-editor --headless check <project> [--strict]
+       esengine-editor --headless build <project> [--profile <name>] [--target <id>] [--output <dir>] [--clean]
+       esengine-editor --headless run <script.json>";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+enum Step {
+    Open,
+    Check {
+        #[serde(default)]
+        strict: bool,
+    },
+    Build {
+        #[serde(default)]
+        profile: Option<String>,
+        #[serde(default)]
+        target: Option<String>,
+        /// Relative to the current directory.
+        #[serde(default)]
+        output: Option<PathBuf>,
+        #[serde(default)]
+        clean: bool,
+    },
+}
+
+enum Invocation {
+    Single { project: PathBuf, step: Step },
+    Script(PathBuf),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Script {
+    project: PathBuf,
+    #[serde(default)]
+    continue_on_error: bool,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepResult {
+    command: &'static str,
+    ok: bool,
+    duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Step {
+    fn name(&self) -> &'static str {
+        match self {
+            Step::Open => "open",
+            Step::Check { .. } => "check",
+            Step::Build { .. } => "build",
+        }
+    }
+}
+
+// =============================================================================
+// Entry point
+// =============================================================================
+
+/// Runs the command line after `--headless` and returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let invocation = match parse(args) {
+        Ok(invocation) => invocation,
+        Err(message) => {
+            print_event(json!({ "event": "error", "message": message }));
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    match invocation {
+        Invocation::Single { project, step } => {
+            let outcome = project_dir(&project).map(|project| execute(&project, &step));
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(message) => {
+                    print_event(json!({ "event": "error", "message": message }));
+                    return EXIT_FAILED;
+                }
+            };
+            if let Some(result) = &outcome.result {
+                print_event(json!({ "event": "result", "result": result }));
+            }
+            match outcome.error {
+                Some(message) => {
+                    print_event(json!({ "event": "error", "message": message }));
+                    EXIT_FAILED
+                }
+                None => 0,
+            }
+        }
+        Invocation::Script(path) => run_script(&path),
+    }
+}
+
+fn parse(args: &[String]) -> Result<Invocation, String> {
+    let mut rest = args.iter().skip_while(|a| a.as_str() != FLAG).skip(1).map(String::as_str);
+    let command = rest.next().ok_or("Missing command")?;
+    if !matches!(command, "open" | "check" | "build" | "run") {
+        return Err(format!("Unknown command: {}", command));
+    }
+
+    let mut path = None;
+    let mut strict = false;
+    let mut profile = None;
+    let mut target = None;
+    let mut output = None;
+    let mut clean = false;
+    while let Some(arg) = rest.next() {
+        match (command, arg) {
+            ("check", "--strict") => strict = true,
+            ("build", "--profile") => profile = Some(rest.next().ok_or("--profile requires a name")?.to_string()),
+            ("build", "--target") => target = Some(rest.next().ok_or("--target requires a target id")?.to_string()),
+            ("build", "--output") => output = Some(PathBuf::from(rest.next().ok_or("--output requires a directory")?)),
+            ("build", "--clean") => clean = true,
+            (_, flag) if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            (_, value) if path.is_none() => path = Some(PathBuf::from(value)),
+            (_, extra) => return Err(format!("Unexpected argument: {}", extra)),
+        }
+    }
+
+    if command == "run" {
+        return Ok(Invocation::Script(path.ok_or("Missing script")?));
+    }
+    let step = match command {
+        "open" => Step::Open,
+        "check" => Step::Check { strict },
+        _ => Step::Build { profile, target, output, clean },
+    };
+    Ok(Invocation::Single { project: path.ok_or("Missing project directory")?, step })
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+fn execute(project: &Path, step: &Step) -> StepResult {
+    let started = Instant::now();
+    let (result, error) = match step {
+        Step::Open => split(open(project)),
+        Step::Check { strict } => match run_checks(project) {
+            Ok(report) => {
+                let failed = report.errors > 0 || (*strict && report.warnings > 0);
+                let error = failed.then(|| {
+                    format!("Health check found {} error(s) and {} warning(s)", report.errors, report.warnings)
+                });
+                (serde_json::to_value(&report).ok(), error)
+            }
+            Err(e) => (None, Some(e)),
+        },
+        Step::Build { profile, target, output, clean } => {
+            split(build(project, profile.as_deref(), target.as_deref(), output.as_deref(), *clean))
+        }
+    };
+    StepResult {
+        command: step.name(),
+        ok: error.is_none(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        result,
+        error,
+    }
+}
+
+fn split(result: Result<Value, String>) -> (Option<Value>, Option<String>) {
+    match result {
+        Ok(value) => (Some(value), None),
+        Err(e) => (None, Some(e)),
+    }
+}
+
+fn open(project: &Path) -> Result<Value, String> {
+    let (settings, migrated) = ProjectSettings::load(project)?;
+    let profiles = profiles::load_profiles(project)?;
+    Ok(json!({
+        "projectDir": project.to_string_lossy(),
+        "name": settings.name,
+        "version": settings.version,
+        "editorVersion": settings.engine,
+        "engineVersion": settings.engine_version,
+        // Opening it in the editor would upgrade the project file.
+        "needsMigration": migrated,
+        "profiles": profiles.profiles.iter().map(|p| json!({ "name": p.name, "target": p.target })).collect::<Vec<_>>(),
+        "activeProfile": profiles.active,
+    }))
+}
+
+fn build(
+    project: &Path,
+    profile: Option<&str>,
+    target: Option<&str>,
+    output: Option<&Path>,
+    clean: bool,
+) -> Result<Value, String> {
+    let mut options = profiles::resolve_profile_options(project, profile)?;
+    if let Some(target) = target {
+        options.target = target.to_string();
+    }
+    if let Some(output) = output {
+        options.output_dir = std::env::current_dir()
+            .map(|cwd| cwd.join(output))
+            .unwrap_or_else(|_| output.to_path_buf())
+            .to_string_lossy()
+            .to_string();
+    }
+    options.clean |= clean;
+
+    let result = run_export(&StdoutListener, &options)?;
+    serde_json::to_value(&result).map_err(|e| e.to_string())
+}
+
+fn run_script(path: &Path) -> i32 {
+    let script: Script = match std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        .and_then(|text| serde_json::from_str(&text).map_err(|e| format!("Invalid script {}: {}", path.display(), e)))
+    {
+        Ok(script) => script,
+        Err(message) => {
+            print_event(json!({ "event": "error", "message": message }));
+            return EXIT_USAGE;
+        }
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+    let project = match project_dir(&base.join(&script.project)) {
+        Ok(project) => project,
+        Err(message) => {
+            print_event(json!({ "event": "error", "message": message }));
+            return EXIT_FAILED;
+        }
+    };
+
+    let mut results = Vec::new();
+    for (index, step) in script.steps.iter().enumerate() {
+        print_event(json!({ "event": "step-start", "index": index, "command": step.name() }));
+        let outcome = execute(&project, step);
+        print_event(json!({ "event": "step-end", "index": index, "step": outcome }));
+        let failed = !outcome.ok;
+        results.push(outcome);
+        if failed && !script.continue_on_error {
+            break;
+        }
+    }
+
+    let ok = results.len() == script.steps.len() && results.iter().all(|r| r.ok);
+    print_event(json!({ "event": "result", "result": { "ok": ok, "steps": results } }));
+    if ok {
+        0
+    } else {
+        let failed = results.iter().filter(|r| !r.ok).count();
+        print_event(json!({ "event": "error", "message": format!("{} step(s) failed", failed) }));
+        EXIT_FAILED
+    }
+}
+
+/// Accepts either the project folder or its `.esproject` file.
+fn project_dir(path: &Path) -> Result<PathBuf, String> {
+    let path = if path.is_file() { path.parent().unwrap_or(Path::new(".")).to_path_buf() } else { path.to_path_buf() };
+    path.canonicalize().map_err(|e| format!("Project not found at {}: {}", path.display(), e))
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+struct StdoutListener;
+
+impl ExportListener for StdoutListener {
+    fn progress(&self, stage: &str, message: &str, progress: f32) {
+        if stage == "warning" {
+            print_event(json!({ "event": "warning", "message": message }));
+        } else {
+            print_event(json!({ "event": "progress", "stage": stage, "message": message, "progress": progress }));
+        }
+    }
+
+    fn output(&self, stream: &str, line: &str) {
+        print_event(json!({ "event": "output", "stream": stream, "data": line }));
+    }
+
+    fn resource_dir(&self) -> Option<PathBuf> {
+        let exe = std::env::current_exe().ok()?;
+        let dir = exe.parent()?;
+        // macOS bundles keep resources beside the binary's parent folder.
+        let bundled = dir.parent().map(|p| p.join("Resources")).filter(|p| p.is_dir());
+        Some(bundled.unwrap_or_else(|| dir.to_path_buf()))
+    }
+}
+
+fn print_event(event: Value) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", event);
+    let _ = stdout.flush();
+}
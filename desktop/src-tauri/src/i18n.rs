@@ -0,0 +1,168 @@
+//! Backend messages — errors in the editor's language
+//!
+//! Errors the user reads, such as a preview port that cannot be bound or a
+//! command that cannot be started, are returned as a `BackendError`: a
+//! stable `code`, its `params`, and `message`, the code formatted in the
+//! language of the `general.language` setting.
+//!
+//! - catalogs are `locales/<language>.json`, embedded at build time, and map
+//!   codes to templates with `{param}` placeholders
+//! - a code missing from a catalog falls back to English, then to the code
+//! - `get_message_catalog` hands a catalog to the frontend, so it can format
+//!   codes itself, e.g. after the language changed
+
+use crate::editor_settings;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Listener};
+
+const LANGUAGE_SETTING: &str = "general.language";
+const DEFAULT_LANGUAGE: &str = "en";
+/// Code of errors that only carry a message.
+const PLAIN_CODE: &str = "error";
+
+const CATALOGS: &[(&str, &str)] =
+    &[("en", include_str!("../locales/en.json")), ("zh", include_str!("../locales/zh.json"))];
+
+/// The language errors are formatted in; empty until `init`.
+static LANGUAGE: RwLock<String> = RwLock::new(String::new());
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendError {
+    /// e.g. `preview.bindFailed`; `error` when there is only a message.
+    pub code: String,
+    pub params: BTreeMap<String, String>,
+    /// `code` formatted in the editor's language.
+    pub message: String,
+}
+
+impl BackendError {
+    pub fn new<const N: usize>(code: &str, params: [(&str, String); N]) -> Self {
+        let params: BTreeMap<String, String> = params.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        let message = format(&language(), code, &params);
+        Self { code: code.to_string(), params, message }
+    }
+}
+
+impl std::fmt::Display for BackendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Errors not in a catalog yet pass through as they are.
+impl From<String> for BackendError {
+    fn from(message: String) -> Self {
+        Self {
+            code: PLAIN_CODE.to_string(),
+            params: BTreeMap::from([("message".to_string(), message.clone())]),
+            message,
+        }
+    }
+}
+
+impl From<BackendError> for String {
+    fn from(error: BackendError) -> Self {
+        error.message
+    }
+}
+
+// =============================================================================
+// Language
+// =============================================================================
+
+/// Follows `general.language` from the saved settings and every change.
+pub fn init(app: &AppHandle) {
+    if let Ok(settings) = editor_settings::load(app) {
+        set_language(settings.values.get(LANGUAGE_SETTING));
+    }
+    app.listen_any("editor-settings-changed", |event| {
+        if let Ok(changed) = serde_json::from_str::<Value>(event.payload()) {
+            set_language(changed.pointer(&format!("/settings/values/{}", LANGUAGE_SETTING)));
+        }
+    });
+}
+
+fn set_language(value: Option<&Value>) {
+    let language = value.and_then(Value::as_str).unwrap_or(DEFAULT_LANGUAGE);
+    *LANGUAGE.write().unwrap_or_else(|e| e.into_inner()) = language.to_string();
+}
+
+fn language() -> String {
+    let current = LANGUAGE.read().unwrap_or_else(|e| e.into_inner());
+    if current.is_empty() {
+        DEFAULT_LANGUAGE.to_string()
+    } else {
+        current.clone()
+    }
+}
+
+// =============================================================================
+// Catalogs
+// =============================================================================
+
+fn catalogs() -> &'static BTreeMap<&'static str, BTreeMap<String, String>> {
+    static PARSED: OnceLock<BTreeMap<&'static str, BTreeMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .filter_map(|(language, json)| match serde_json::from_str(json) {
+                Ok(catalog) => Some((*language, catalog)),
+                Err(e) => {
+                    eprintln!("[i18n] Invalid catalog {}: {}", language, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Fills the `{param}` placeholders of the template of `code`; unknown
+/// placeholders are kept as they are.
+fn format(language: &str, code: &str, params: &BTreeMap<String, String>) -> String {
+    let template = [language, DEFAULT_LANGUAGE]
+        .iter()
+        .find_map(|language| catalogs().get(language)?.get(code))
+        .map(String::as_str)
+        .unwrap_or(code);
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').and_then(|end| Some((params.get(&after[..end])?, end))) {
+            Some((value, end)) => {
+                message.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = after;
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// The templates of `language` (default: the editor's), with English for
+/// codes it lacks.
+#[tauri::command]
+pub fn get_message_catalog(language: Option<String>) -> BTreeMap<String, String> {
+    let language = language.unwrap_or_else(self::language);
+    let mut catalog = catalogs().get(DEFAULT_LANGUAGE).cloned().unwrap_or_default();
+    if let Some(translated) = catalogs().get(language.as_str()) {
+        catalog.extend(translated.clone());
+    }
+    catalog
+}
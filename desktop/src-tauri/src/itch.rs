@@ -0,0 +1,345 @@
+//! itch.io publishing — managed butler CLI and channel pushes
+//!
+//! butler is downloaded from itch.io's broth service into the app data
+//! directory. The archive must match the SHA-256 broth publishes for that
+//! version before anything is extracted, and the binary is then verified by
+//! running it. Pushes run as managed processes with `--json` output, which
+//! carries machine-readable progress.
+
+use crate::export::{profiles, run_export};
+use crate::notify;
+use crate::process;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::process::Command;
+
+const BROTH_URL: &str = "https://broth.itch.zone/butler";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItchPublishOptions {
+    /// itch.io game as `user/game`.
+    pub game: String,
+    /// Channel name; derived from the export target when omitted.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Folder to push. When omitted, the project is exported with `profile` first.
+    #[serde(default)]
+    pub dir: Option<String>,
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub user_version: Option<String>,
+    /// API key used instead of the credentials stored by `butler login`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Skips the push when the build is identical to the channel's latest.
+    #[serde(default)]
+    pub if_changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ButlerStatus {
+    pub installed: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItchPublishResult {
+    /// Push target as `user/game:channel`.
+    pub target: String,
+    pub dir: String,
+    pub url: String,
+}
+
+#[derive(Clone, Serialize)]
+struct ItchProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn get_butler_status(app: AppHandle) -> ButlerStatus {
+    let path = butler_path(&app);
+    let version = butler_version(&path).await;
+    ButlerStatus {
+        installed: version.is_some(),
+        path: version.as_ref().map(|_| path.to_string_lossy().to_string()),
+        version,
+    }
+}
+
+#[tauri::command]
+pub async fn install_butler(app: AppHandle) -> Result<ButlerStatus, String> {
+    let (channel, version) = latest_version().await?;
+    let base = format!("{}/{}/{}", BROTH_URL, channel, version);
+    let sums = fetch_text(&format!("{}/SHA256SUMS", base)).await?;
+    let data = download(&app, &format!("{}/archive/default", base)).await?;
+
+    emit_progress(&app, "verify", "Checking the butler archive...", 0.75);
+    let hash = format!("{:x}", Sha256::digest(&data));
+    let mut published = sums.lines().filter_map(|line| line.split_whitespace().next());
+    if !published.any(|sum| sum.eq_ignore_ascii_case(&hash)) {
+        return Err(format!("The downloaded butler {} does not match its published SHA-256", version));
+    }
+
+    emit_progress(&app, "install", "Extracting butler...", 0.8);
+    let install_dir = butler_dir(&app);
+    if install_dir.exists() {
+        std::fs::remove_dir_all(&install_dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&install_dir).map_err(|e| e.to_string())?;
+    let target = install_dir.clone();
+    tokio::task::spawn_blocking(move || {
+        zip::ZipArchive::new(std::io::Cursor::new(data))
+            .and_then(|mut archive| archive.extract(&target))
+            .map_err(|e| format!("Invalid butler archive: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Extract task failed: {}", e))??;
+
+    emit_progress(&app, "verify", "Verifying butler...", 0.9);
+    let status = get_butler_status(app.clone()).await;
+    if !status.installed {
+        let _ = std::fs::remove_dir_all(&install_dir);
+        return Err("The downloaded butler failed to run".to_string());
+    }
+    emit_progress(&app, "complete", "butler installed", 1.0);
+    Ok(status)
+}
+
+/// Runs `butler login`, which authorizes through the browser and stores credentials.
+#[tauri::command]
+pub async fn itch_login(app: AppHandle) -> Result<(), String> {
+    let butler = ensure_butler(&app).await?;
+    let mut command = Command::new(&butler);
+    command.args(["login", "--assume-yes"]);
+    let exit = process::run(&app, "butler login", command, |_, _| {}).await?;
+    if !exit.success() {
+        return Err(failure("butler login", &exit));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn publish_itch(app: AppHandle, options: ItchPublishOptions) -> Result<ItchPublishResult, String> {
+    let result = publish(&app, options).await;
+    notify::upload_finished(&app, "itch.io publish", &result);
+    result
+}
+
+async fn publish(app: &AppHandle, options: ItchPublishOptions) -> Result<ItchPublishResult, String> {
+    let (user, game) = options
+        .game
+        .split_once('/')
+        .filter(|(user, game)| !user.is_empty() && !game.is_empty() && !game.contains('/'))
+        .ok_or_else(|| format!("Expected the itch.io game as user/game, got '{}'", options.game))?;
+    let butler = ensure_butler(app).await?;
+
+    let (dir, target) = match &options.dir {
+        Some(dir) => (PathBuf::from(dir), None),
+        None => {
+            let project_dir = options.project_dir.clone().ok_or("Either a folder or a project is required")?;
+            let export = profiles::resolve_profile_options(Path::new(&project_dir), options.profile.as_deref())?;
+            emit_progress(app, "export", &format!("Exporting {}...", export.target), 0.0);
+            let export_app = app.clone();
+            let export_options = export.clone();
+            tokio::task::spawn_blocking(move || run_export(&export_app, &export_options))
+                .await
+                .map_err(|e| format!("Export task failed: {}", e))??;
+            (PathBuf::from(&export.output_dir), Some(export))
+        }
+    };
+    if !dir.exists() {
+        return Err(format!("Nothing to publish at {}", dir.display()));
+    }
+
+    let channel = match (&options.channel, &target) {
+        (Some(channel), _) => channel.clone(),
+        (None, Some(export)) => default_channel(&export.target, &export.desktop.platform).to_string(),
+        (None, None) => return Err("A channel is required when publishing a folder".to_string()),
+    };
+    let push_target = format!("{}/{}:{}", user, game, channel);
+
+    let mut command = Command::new(&butler);
+    command.arg("push").arg(&dir).arg(&push_target).arg("--json");
+    if let Some(version) = options.user_version.as_deref().filter(|v| !v.is_empty()) {
+        command.args(["--userversion", version]);
+    }
+    if options.if_changed {
+        command.arg("--if-changed");
+    }
+    if let Some(key) = options.api_key.as_deref().filter(|k| !k.is_empty()) {
+        command.env("BUTLER_API_KEY", key);
+    }
+
+    emit_progress(app, "push", &format!("Pushing to {}...", push_target), 0.1);
+    let mut error = None;
+    let exit = process::run(app, "butler push", command, |_, line| {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        match event.get("type").and_then(|t| t.as_str()) {
+            Some("progress") => {
+                let progress = event.get("progress").and_then(|p| p.as_f64()).unwrap_or(0.0) as f32;
+                let message = match event.get("eta").and_then(|e| e.as_f64()) {
+                    Some(eta) => format!("Pushing {:.0}% ({:.0}s left)", progress * 100.0, eta),
+                    None => format!("Pushing {:.0}%", progress * 100.0),
+                };
+                emit_progress(app, "push", &message, 0.1 + 0.85 * progress.clamp(0.0, 1.0));
+            }
+            Some("error") => {
+                error = event.get("message").and_then(|m| m.as_str()).map(String::from);
+            }
+            _ => {}
+        }
+    })
+    .await?;
+    if !exit.success() {
+        return Err(error.unwrap_or_else(|| failure("butler push", &exit)));
+    }
+
+    emit_progress(app, "complete", &format!("Published to {}", push_target), 1.0);
+    Ok(ItchPublishResult {
+        target: push_target,
+        dir: dir.to_string_lossy().to_string(),
+        url: format!("https://{}.itch.io/{}", user, game),
+    })
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn butler_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("butler")
+}
+
+fn butler_path(app: &AppHandle) -> PathBuf {
+    butler_dir(app).join(if cfg!(windows) { "butler.exe" } else { "butler" })
+}
+
+async fn butler_version(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return None;
+    }
+    let output = Command::new(path).arg("--version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // butler prints its version to stderr.
+    let text = String::from_utf8_lossy(if output.stdout.is_empty() { &output.stderr } else { &output.stdout })
+        .trim()
+        .to_string();
+    Some(text.split(',').next().unwrap_or(&text).to_string())
+}
+
+async fn ensure_butler(app: &AppHandle) -> Result<PathBuf, String> {
+    let path = butler_path(app);
+    if butler_version(&path).await.is_none() {
+        install_butler(app.clone()).await?;
+    }
+    Ok(path)
+}
+
+/// broth channels for this machine, best first; Apple silicon falls back to
+/// the Intel build under Rosetta.
+fn broth_channels() -> Result<&'static [&'static str], String> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86_64") => Ok(&["windows-amd64"]),
+        ("windows", "x86") => Ok(&["windows-386"]),
+        ("macos", "aarch64") => Ok(&["darwin-arm64", "darwin-amd64"]),
+        ("macos", "x86_64") => Ok(&["darwin-amd64"]),
+        ("linux", "x86_64") => Ok(&["linux-amd64"]),
+        (os, arch) => Err(format!("butler is not available for {}-{}", os, arch)),
+    }
+}
+
+/// The first channel with a published version, and that version.
+async fn latest_version() -> Result<(&'static str, String), String> {
+    let mut last_error = String::new();
+    for channel in broth_channels()? {
+        match fetch_text(&format!("{}/{}/LATEST", BROTH_URL, channel)).await {
+            Ok(version) if !version.trim().is_empty() => return Ok((channel, version.trim().to_string())),
+            Ok(_) => last_error = format!("No butler version is published for {}", channel),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()));
+    }
+    response.text().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))
+}
+
+/// itch.io channel names are matched against platform tags.
+fn default_channel(target: &str, desktop_platform: &str) -> &'static str {
+    match (target, desktop_platform) {
+        ("desktop", "macos") => "osx",
+        ("desktop", _) => "windows",
+        ("android", _) => "android",
+        _ => "html5",
+    }
+}
+
+async fn download(app: &AppHandle, url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {}", response.status()));
+    }
+    let total = response.content_length().unwrap_or(0);
+    let mut data = Vec::with_capacity(total as usize);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        data.extend_from_slice(&chunk);
+        if total > 0 {
+            let fraction = data.len() as f32 / total as f32;
+            emit_progress(
+                app,
+                "download",
+                &format!("Downloading butler... {:.0}%", fraction * 100.0),
+                0.75 * fraction,
+            );
+        }
+    }
+    Ok(data)
+}
+
+fn failure(name: &str, exit: &process::ProcessExit) -> String {
+    let last = exit.tail.iter().rev().take(10).rev().cloned().collect::<Vec<_>>().join("\n");
+    format!("{} failed (exit code: {})\n{}", name, exit.code, last)
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "itch-progress",
+        ItchProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
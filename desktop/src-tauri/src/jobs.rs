@@ -0,0 +1,382 @@
+//! Background jobs — one queue for the work that competes for disk and CPU
+//!
+//! Builds, imports, searches, thumbnails and other long blocking work run
+//! through `JobQueue::run` instead of a bare `spawn_blocking`. Each category
+//! has its own concurrency limit; a job over the limit waits, and when a
+//! slot frees up the waiting job with the highest priority (then the oldest)
+//! gets it. Jobs report progress and check for cancellation through their
+//! `JobContext`. Every state change is emitted as `job-status`, progress as
+//! `job-progress`, and `list_jobs` returns active and recent jobs.
+
+use crate::project::now_iso8601;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::oneshot;
+
+/// Finished jobs kept for `list_jobs`.
+const KEPT_FINISHED: usize = 50;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const CANCELLED: &str = "Cancelled";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobCategory {
+    /// Exports and script bundles.
+    Build,
+    /// Copying files into a project.
+    Import,
+    /// Project search and replace.
+    Search,
+    /// Image decoding and resizing for previews.
+    Thumbnail,
+    /// Other project scans.
+    Io,
+}
+
+impl JobCategory {
+    /// Jobs of the category that may run at once.
+    fn limit(self) -> usize {
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        match self {
+            JobCategory::Build => 1,
+            JobCategory::Import => 2,
+            JobCategory::Search => 2,
+            JobCategory::Thumbnail => (cpus / 2).max(2),
+            JobCategory::Io => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    /// Work nobody is waiting on, e.g. warming caches.
+    Low,
+    #[default]
+    Normal,
+    /// Work the user is watching, e.g. a search as they type.
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: u64,
+    pub category: JobCategory,
+    pub priority: JobPriority,
+    pub label: String,
+    pub project_dir: Option<String>,
+    pub status: JobStatus,
+    /// 0 to 1, once the job reported any.
+    pub progress: Option<f32>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub queued: String,
+}
+
+#[derive(Clone, Serialize)]
+struct JobProgress {
+    id: u64,
+    progress: f32,
+    message: String,
+}
+
+/// What a job is, for the queue and the jobs panel.
+pub struct JobSpec {
+    category: JobCategory,
+    priority: JobPriority,
+    label: String,
+    project_dir: Option<PathBuf>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl JobSpec {
+    pub fn new(category: JobCategory, label: impl Into<String>) -> Self {
+        Self { category, priority: JobPriority::Normal, label: label.into(), project_dir: None, cancel: None }
+    }
+
+    pub fn priority(mut self, priority: JobPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn project(mut self, project_dir: impl Into<PathBuf>) -> Self {
+        self.project_dir = Some(project_dir.into());
+        self
+    }
+
+    /// Uses a flag the work already watches, so cancelling the job and the
+    /// command's own cancel (e.g. `cancel_search`) are the same thing.
+    pub fn cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+/// Handed to the work of a running job.
+pub struct JobContext {
+    id: u64,
+    app: AppHandle,
+    shared: Arc<Mutex<QueueState>>,
+    cancel: Arc<AtomicBool>,
+    last_progress: Mutex<Option<Instant>>,
+}
+
+impl JobContext {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Records progress; events are sent at most every `PROGRESS_INTERVAL`
+    /// except for completion.
+    pub fn progress(&self, progress: f32, message: &str) {
+        let progress = progress.clamp(0.0, 1.0);
+        if let Ok(mut state) = self.shared.lock() {
+            if let Some(entry) = state.jobs.get_mut(&self.id) {
+                entry.info.progress = Some(progress);
+                entry.info.message = Some(message.to_string());
+            }
+        }
+        let mut last = self.last_progress.lock().unwrap_or_else(|e| e.into_inner());
+        if progress < 1.0 && last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+        let _ = self.app.emit("job-progress", JobProgress { id: self.id, progress, message: message.to_string() });
+    }
+}
+
+/// The scheduler; lives in `AppState`.
+#[derive(Default)]
+pub struct JobQueue {
+    shared: Arc<Mutex<QueueState>>,
+    next_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: BTreeMap<u64, JobEntry>,
+    finished: VecDeque<JobInfo>,
+    running: HashMap<JobCategory, usize>,
+    waiting: Vec<Waiter>,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+struct Waiter {
+    id: u64,
+    category: JobCategory,
+    priority: JobPriority,
+    start: oneshot::Sender<()>,
+}
+
+// =============================================================================
+// Scheduling
+// =============================================================================
+
+impl JobQueue {
+    /// Runs `work` on the blocking pool once its category has a free slot.
+    /// A job cancelled while still queued never runs.
+    pub async fn run<T, F>(&self, app: &AppHandle, spec: JobSpec, work: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> Result<T, String> + Send + 'static,
+    {
+        let (context, waiting) = self.enqueue(app, spec);
+        if let Some(start) = waiting {
+            // The sender is dropped when the job is cancelled in the queue.
+            if start.await.is_err() {
+                return Err(CANCELLED.to_string());
+            }
+        }
+        let id = context.id;
+        let cancel = context.cancel.clone();
+        let result = tokio::task::spawn_blocking(move || work(&context))
+            .await
+            .map_err(|e| format!("Job task failed: {}", e))
+            .and_then(|result| result);
+        // Work may stop early on cancel and still return what it had.
+        let status = match &result {
+            _ if cancel.load(Ordering::Relaxed) => JobStatus::Cancelled,
+            Ok(_) => JobStatus::Done,
+            Err(_) => JobStatus::Failed,
+        };
+        self.finish(app, id, status, result.as_ref().err().cloned());
+        result
+    }
+
+    fn enqueue(&self, app: &AppHandle, spec: JobSpec) -> (JobContext, Option<oneshot::Receiver<()>>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel = spec.cancel.unwrap_or_default();
+        let mut info = JobInfo {
+            id,
+            category: spec.category,
+            priority: spec.priority,
+            label: spec.label,
+            project_dir: spec.project_dir.map(|dir| dir.to_string_lossy().to_string()),
+            status: JobStatus::Running,
+            progress: None,
+            message: None,
+            error: None,
+            queued: now_iso8601(),
+        };
+
+        let waiting = {
+            let mut state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            let running = state.running.entry(spec.category).or_default();
+            let waiting = if *running < spec.category.limit() {
+                *running += 1;
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                info.status = JobStatus::Queued;
+                state.waiting.push(Waiter { id, category: spec.category, priority: spec.priority, start: tx });
+                Some(rx)
+            };
+            state.jobs.insert(id, JobEntry { info: info.clone(), cancel: cancel.clone() });
+            waiting
+        };
+        let _ = app.emit("job-status", &info);
+
+        let context = JobContext {
+            id,
+            app: app.clone(),
+            shared: Arc::clone(&self.shared),
+            cancel,
+            last_progress: Mutex::new(None),
+        };
+        (context, waiting)
+    }
+
+    fn finish(&self, app: &AppHandle, id: u64, status: JobStatus, error: Option<String>) {
+        let mut changed = Vec::new();
+        {
+            let mut state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(mut entry) = state.jobs.remove(&id) else {
+                return;
+            };
+            entry.info.status = status;
+            entry.info.error = error.filter(|_| status == JobStatus::Failed);
+            if status == JobStatus::Done {
+                entry.info.progress = Some(1.0);
+            }
+            let category = entry.info.category;
+            if let Some(running) = state.running.get_mut(&category) {
+                *running = running.saturating_sub(1);
+            }
+            changed.push(entry.info.clone());
+            state.push_finished(entry.info);
+            changed.extend(state.dispatch(category));
+        }
+        for info in changed {
+            let _ = app.emit("job-status", &info);
+        }
+    }
+
+    /// Cancels a job: a queued one is dropped, a running one is asked to
+    /// stop. Returns whether the job was still active.
+    pub fn cancel(&self, app: &AppHandle, id: u64) -> bool {
+        let info = {
+            let mut state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(entry) = state.jobs.get(&id) else {
+                return false;
+            };
+            entry.cancel.store(true, Ordering::Relaxed);
+            if entry.info.status != JobStatus::Queued {
+                return true;
+            }
+            // Dropping the waiter's sender ends its `run` with `CANCELLED`.
+            state.waiting.retain(|waiter| waiter.id != id);
+            let Some(mut entry) = state.jobs.remove(&id) else {
+                return true;
+            };
+            entry.info.status = JobStatus::Cancelled;
+            state.push_finished(entry.info.clone());
+            entry.info
+        };
+        let _ = app.emit("job-status", &info);
+        true
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let state = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        state.jobs.values().map(|entry| entry.info.clone()).chain(state.finished.iter().cloned()).collect()
+    }
+}
+
+impl QueueState {
+    fn push_finished(&mut self, info: JobInfo) {
+        self.finished.push_front(info);
+        self.finished.truncate(KEPT_FINISHED);
+    }
+
+    /// Starts waiting jobs of `category` while it has free slots, highest
+    /// priority first and in queue order within a priority. Returns the
+    /// jobs started.
+    fn dispatch(&mut self, category: JobCategory) -> Vec<JobInfo> {
+        let mut started = Vec::new();
+        while self.running.get(&category).copied().unwrap_or(0) < category.limit() {
+            // `max_by_key` keeps the last maximum; scanning in reverse makes
+            // that the earliest queued.
+            let next = self
+                .waiting
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, waiter)| waiter.category == category)
+                .max_by_key(|(_, waiter)| waiter.priority)
+                .map(|(index, _)| index);
+            let Some(index) = next else {
+                break;
+            };
+            let waiter = self.waiting.remove(index);
+            if waiter.start.send(()).is_err() {
+                // Its `run` is gone; forget the job.
+                self.jobs.remove(&waiter.id);
+                continue;
+            }
+            *self.running.entry(category).or_default() += 1;
+            if let Some(entry) = self.jobs.get_mut(&waiter.id) {
+                entry.info.status = JobStatus::Running;
+                started.push(entry.info.clone());
+            }
+        }
+        started
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Active jobs in start order, then recently finished ones, newest first.
+#[tauri::command]
+pub fn list_jobs(state: State<'_, crate::AppState>) -> Vec<JobInfo> {
+    state.jobs.list()
+}
+
+#[tauri::command]
+pub fn cancel_job(app: AppHandle, state: State<'_, crate::AppState>, id: u64) -> bool {
+    state.jobs.cancel(&app, id)
+}
@@ -0,0 +1,308 @@
+//! TypeScript language server — IntelliSense for the built-in script editor
+//!
+//! The script editor is an LSP client; this module runs
+//! `typescript-language-server`, which drives `tsserver`, and relays the
+//! messages in between:
+//!
+//! - `start_language_server` starts the server of a project and returns
+//!   the `initializationOptions` the client passes with `initialize`
+//! - `send_language_server_message` writes one JSON-RPC message to it
+//! - everything the server sends, including its own requests such as
+//!   `workspace/configuration`, arrives as `language-server-message` events;
+//!   `language-server-exited` tells when it stopped
+//!
+//! The server is installed on first use with npm, at pinned versions, into
+//! `<app data>/language-server`, and runs on the Node found for commands
+//! (see `toolchains`). A project with its own `node_modules/typescript` is
+//! checked with that version instead of the bundled one. Before starting,
+//! a missing `tsconfig.json` or SDK type definitions are written, so
+//! scripts resolve `esengine` without any setup.
+
+use crate::process::env::{login_path_default, CommandEnv};
+use crate::project;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+const INSTALL_DIR: &str = "language-server";
+const SERVER_PACKAGE: &str = "typescript-language-server";
+const SERVER_VERSION: &str = "4.3.3";
+const TYPESCRIPT_VERSION: &str = "5.6.3";
+/// Bigger messages are dropped rather than buffered.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageServerInfo {
+    /// Pass as `initializationOptions` of the `initialize` request.
+    pub initialization_options: Value,
+    /// `project` when the project's own TypeScript is used, else `bundled`.
+    pub typescript: String,
+    /// The server was installed with npm by this call.
+    pub installed: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerMessage {
+    project_dir: String,
+    /// One JSON-RPC message, as the server wrote it.
+    message: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerExited {
+    project_dir: String,
+    code: Option<i32>,
+}
+
+struct Server {
+    /// Tells a restarted server from the one it replaced.
+    id: u64,
+    writer: mpsc::UnboundedSender<String>,
+    /// Dropping the server kills the process.
+    child: Child,
+}
+
+/// Running language servers, by project directory.
+#[derive(Default)]
+pub struct LanguageServers {
+    servers: Mutex<HashMap<PathBuf, Server>>,
+}
+
+impl LanguageServers {
+    /// Stops the server of a project closed in the workspace.
+    pub fn stop(&self, project_dir: &Path) {
+        self.servers.lock().unwrap_or_else(|e| e.into_inner()).remove(project_dir);
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Starts the language server of `project_dir`, installing it first if
+/// needed. A running server is restarted, so the client can send a fresh
+/// `initialize`.
+#[tauri::command]
+pub async fn start_language_server(
+    app: AppHandle,
+    servers: State<'_, LanguageServers>,
+    project_dir: String,
+) -> Result<LanguageServerInfo, String> {
+    let dir = PathBuf::from(&project_dir);
+    servers.stop(&dir);
+
+    let setup_dir = dir.clone();
+    tokio::task::spawn_blocking(move || prepare_project(&setup_dir))
+        .await
+        .map_err(|e| format!("Setup task failed: {}", e))??;
+
+    let env = CommandEnv::new(HashMap::new(), Some(&dir), login_path_default());
+    let (cli, installed) = ensure_installed(&app, &env).await?;
+    let node = env.find("node").ok_or("Node.js was not found; install it from the toolchain settings")?;
+
+    let mut command = Command::new(&node);
+    command
+        .arg(&cli)
+        .arg("--stdio")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    env.apply(&mut command);
+    #[cfg(windows)]
+    command.creation_flags(crate::process::CREATE_NO_WINDOW);
+    let mut child = command.spawn().map_err(|e| format!("Failed to start the language server: {}", e))?;
+    let (Some(mut stdin), Some(stdout), Some(stderr)) = (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return Err("Failed to open the language server's stdio".to_string());
+    };
+
+    let (writer, mut outgoing) = mpsc::unbounded_channel::<String>();
+    tauri::async_runtime::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            let header = format!("Content-Length: {}\r\n\r\n", message.len());
+            if stdin.write_all(header.as_bytes()).await.is_err() || stdin.write_all(message.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = stdin.flush().await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            eprintln!("[language-server] {}", line);
+        }
+    });
+
+    // Registered before the reader starts, which unregisters it on exit.
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    servers.servers.lock().unwrap_or_else(|e| e.into_inner()).insert(dir.clone(), Server { id, writer, child });
+
+    let reader_app = app.clone();
+    let reader_dir = dir.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut stdout = BufReader::new(stdout);
+        while let Some(message) = read_message(&mut stdout).await {
+            let event = ServerMessage { project_dir: reader_dir.to_string_lossy().to_string(), message };
+            let _ = reader_app.emit("language-server-message", event);
+        }
+        // Stdout closes when the process ends. A server stopped or
+        // replaced on purpose is no longer registered and reports nothing.
+        let server = {
+            let servers = reader_app.state::<LanguageServers>();
+            let mut servers = servers.servers.lock().unwrap_or_else(|e| e.into_inner());
+            match servers.get(&reader_dir) {
+                Some(server) if server.id == id => servers.remove(&reader_dir),
+                _ => None,
+            }
+        };
+        let Some(mut server) = server else {
+            return;
+        };
+        let code = server.child.wait().await.ok().and_then(|status| status.code());
+        eprintln!("[language-server] Exited with {:?} for {}", code, reader_dir.display());
+        let event = ServerExited { project_dir: reader_dir.to_string_lossy().to_string(), code };
+        let _ = reader_app.emit("language-server-exited", event);
+    });
+
+    eprintln!("[language-server] Started for {}", dir.display());
+
+    let project_typescript = dir.join("node_modules/typescript/lib");
+    let (options, typescript) = if project_typescript.join("tsserver.js").is_file() {
+        (json!({ "tsserver": { "path": project_typescript.to_string_lossy() } }), "project")
+    } else {
+        (json!({}), "bundled")
+    };
+    Ok(LanguageServerInfo { initialization_options: options, typescript: typescript.to_string(), installed })
+}
+
+/// Sends one JSON-RPC message, without the `Content-Length` header.
+#[tauri::command]
+pub fn send_language_server_message(
+    servers: State<'_, LanguageServers>,
+    project_dir: String,
+    message: String,
+) -> Result<(), String> {
+    let servers = servers.servers.lock().unwrap_or_else(|e| e.into_inner());
+    let server = servers.get(Path::new(&project_dir)).ok_or("The language server is not running")?;
+    server.writer.send(message).map_err(|_| "The language server has exited".to_string())
+}
+
+#[tauri::command]
+pub fn stop_language_server(servers: State<'_, LanguageServers>, project_dir: String) {
+    servers.stop(Path::new(&project_dir));
+}
+
+// =============================================================================
+// Setup
+// =============================================================================
+
+/// Writes what the server needs to type-check the project's scripts and
+/// is not there yet.
+fn prepare_project(project_dir: &Path) -> Result<(), String> {
+    if !project_dir.join("tsconfig.json").is_file() {
+        project::write_tsconfig(project_dir)?;
+    }
+    if !project_dir.join(".esengine/sdk/index.d.ts").is_file() {
+        project::write_sdk(project_dir)?;
+    }
+    project::typings::sync(project_dir, false).map(|_| ())
+}
+
+/// The server's entry script, and whether it was installed just now.
+async fn ensure_installed(app: &AppHandle, env: &CommandEnv) -> Result<(PathBuf, bool), String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join(INSTALL_DIR);
+    let package = dir.join("node_modules").join(SERVER_PACKAGE);
+    let cli = package.join("lib/cli.mjs");
+    let version = std::fs::read(package.join("package.json"))
+        .ok()
+        .and_then(|data| serde_json::from_slice::<Value>(&data).ok())
+        .and_then(|manifest| manifest.get("version").and_then(Value::as_str).map(String::from));
+    if cli.is_file() && version.as_deref() == Some(SERVER_VERSION) {
+        return Ok((cli, false));
+    }
+
+    let npm = env.find("npm").ok_or("npm was not found; install Node.js from the toolchain settings")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    eprintln!("[language-server] Installing {}@{}", SERVER_PACKAGE, SERVER_VERSION);
+    let mut command = Command::new(&npm);
+    command
+        .args(["install", "--no-audit", "--no-fund", "--prefix"])
+        .arg(&dir)
+        .arg(format!("{}@{}", SERVER_PACKAGE, SERVER_VERSION))
+        .arg(format!("typescript@{}", TYPESCRIPT_VERSION))
+        .stdin(Stdio::null());
+    env.apply(&mut command);
+    #[cfg(windows)]
+    command.creation_flags(crate::process::CREATE_NO_WINDOW);
+    let output = command.output().await.map_err(|e| format!("Failed to run npm: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+        return Err(format!(
+            "Installing the language server failed:\n{}",
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+        ));
+    }
+    if !cli.is_file() {
+        return Err(format!("The language server is missing from {}", package.display()));
+    }
+    Ok((cli, true))
+}
+
+// =============================================================================
+// Framing
+// =============================================================================
+
+/// Reads one `Content-Length` framed message; `None` once the stream ends.
+async fn read_message<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> Option<String> {
+    loop {
+        let mut length = None;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.ok()? == 0 {
+                return None;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    length = value.trim().parse::<usize>().ok();
+                }
+            }
+        }
+        let Some(length) = length else {
+            continue;
+        };
+        if length > MAX_MESSAGE_BYTES {
+            eprintln!("[language-server] Dropped a message of {} bytes", length);
+            let mut skipped = (&mut *reader).take(length as u64);
+            tokio::io::copy(&mut skipped, &mut tokio::io::sink()).await.ok()?;
+            continue;
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.ok()?;
+        return Some(String::from_utf8_lossy(&body).to_string());
+    }
+}
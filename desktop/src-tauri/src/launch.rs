@@ -0,0 +1,201 @@
+//! Launch requests — opening projects and scenes from outside the editor
+//!
+//! Projects and scenes reach the editor in three ways:
+//!
+//! - as files on the command line, when a `.esproject` or `.esscene` file
+//!   is double-clicked on Windows or Linux. A second editor started this
+//!   way hands its arguments to the running one and exits;
+//! - as `file://` URLs opened on macOS, which the deep-link plugin passes
+//!   on together with links;
+//! - as `esengine://open?path=<file or folder>` links, e.g. from the docs.
+//!   On Windows and Linux the link of a second launch reaches the running
+//!   editor through the deep-link plugin, not its arguments.
+//!
+//! Each becomes an `OpenRequest` for the project that contains the path.
+//! Requests that arrive before the main window listens are queued for
+//! `take_open_requests`; later ones are emitted as `open-request` and the
+//! main window is brought to the front.
+
+use crate::project::PROJECT_FILE;
+use crate::windows::MAIN_LABEL;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+pub const SCHEME: &str = "esengine";
+
+const PROJECT_EXTENSION: &str = "esproject";
+const SCENE_EXTENSION: &str = "esscene";
+/// How many folders above a scene are searched for its project.
+const MAX_PROJECT_DEPTH: usize = 16;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+    /// Path of the `project.esproject` file.
+    pub project: String,
+    /// Scene to open, relative to the project folder with `/` separators.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene: Option<String>,
+}
+
+/// Requests received before the main window asked for them; `None` once
+/// it has.
+pub struct LaunchRequests(Mutex<Option<Vec<OpenRequest>>>);
+
+impl Default for LaunchRequests {
+    fn default() -> Self {
+        Self(Mutex::new(Some(Vec::new())))
+    }
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+/// The request for a project file, scene file or project folder.
+fn request_for(path: &Path) -> Result<OpenRequest, String> {
+    let path = std::fs::canonicalize(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    if path.is_dir() {
+        let project = path.join(PROJECT_FILE);
+        if !project.is_file() {
+            return Err(format!("{} is not a project folder", path.display()));
+        }
+        return Ok(OpenRequest { project: display(&project), scene: None });
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(PROJECT_EXTENSION) => Ok(OpenRequest { project: display(&path), scene: None }),
+        Some(SCENE_EXTENSION) => {
+            let project_dir = path
+                .ancestors()
+                .skip(1)
+                .take(MAX_PROJECT_DEPTH)
+                .find(|dir| dir.join(PROJECT_FILE).is_file())
+                .ok_or_else(|| format!("{} is not inside a project", path.display()))?;
+            let scene = path.strip_prefix(project_dir).map_err(|e| e.to_string())?;
+            let scene = scene.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            Ok(OpenRequest { project: display(&project_dir.join(PROJECT_FILE)), scene: Some(scene) })
+        }
+        _ => Err(format!("{} is not a project or scene", path.display())),
+    }
+}
+
+/// Without the `\\?\` prefix `canonicalize` adds on Windows.
+fn display(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix(r"\\?\").unwrap_or(&path).to_string()
+}
+
+/// The request for an `esengine://open?path=...` link or a `file://` URL.
+fn request_for_url(url: &Url) -> Result<OpenRequest, String> {
+    match url.scheme() {
+        "file" => request_for(&url.to_file_path().map_err(|_| format!("Invalid file URL {}", url))?),
+        SCHEME => {
+            // `esengine://open?...` parses with `open` as the host.
+            let action = url.host_str().unwrap_or_else(|| url.path().trim_matches('/'));
+            if action != "open" {
+                return Err(format!("Unsupported link {}", url));
+            }
+            let path = url
+                .query_pairs()
+                .find(|(key, _)| key == "path")
+                .map(|(_, value)| PathBuf::from(value.as_ref()))
+                .ok_or_else(|| format!("{} has no path", url))?;
+            request_for(&path)
+        }
+        other => Err(format!("Unsupported scheme '{}' in {}", other, url)),
+    }
+}
+
+/// Requests in the arguments of an editor launch; `cwd` resolves relative
+/// paths. Flags and links, which come through the deep-link plugin, are
+/// skipped.
+pub fn requests_from_args(args: &[String], cwd: &Path) -> Vec<OpenRequest> {
+    args.iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .filter_map(|arg| {
+            let result = match Url::parse(arg) {
+                Ok(url) if url.scheme() == SCHEME => return None,
+                Ok(url) if url.scheme() == "file" => request_for_url(&url),
+                _ => request_for(&cwd.join(arg)),
+            };
+            result.map_err(|e| eprintln!("[launch] {}", e)).ok()
+        })
+        .collect()
+}
+
+pub fn requests_from_urls(urls: &[Url]) -> Vec<OpenRequest> {
+    urls.iter().filter_map(|url| request_for_url(url).map_err(|e| eprintln!("[launch] {}", e)).ok()).collect()
+}
+
+// =============================================================================
+// Delivery
+// =============================================================================
+
+/// Queues `requests` until the main window is ready, then emits them.
+pub fn dispatch(app: &AppHandle, requests: Vec<OpenRequest>) {
+    if requests.is_empty() {
+        return;
+    }
+    if let Some(queue) = app.state::<LaunchRequests>().0.lock().unwrap().as_mut() {
+        queue.extend(requests);
+        return;
+    }
+    for request in requests {
+        let _ = app.emit_to(MAIN_LABEL, "open-request", request);
+    }
+    focus_main(app);
+}
+
+/// Brings the main window to the front, as a second launch should.
+pub fn focus_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Queues what this launch was asked to open and listens for links.
+pub fn init(app: &AppHandle) {
+    // Installers register the scheme; AppImages and development builds
+    // have to do it themselves.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        eprintln!("[launch] Failed to register {} links: {}", SCHEME, e);
+    }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let args: Vec<String> = std::env::args().collect();
+    dispatch(app, requests_from_args(&args, &cwd));
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        dispatch(app, requests_from_urls(&urls));
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| dispatch(&handle, requests_from_urls(&event.urls())));
+}
+
+/// Handles a second launch of the editor, which exits after handing over
+/// its arguments.
+pub fn handle_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    dispatch(app, requests_from_args(&args, Path::new(&cwd)));
+    focus_main(app);
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Returns the requests received so far; later ones arrive as
+/// `open-request` events.
+#[tauri::command]
+pub fn take_open_requests(state: State<LaunchRequests>) -> Vec<OpenRequest> {
+    state.0.lock().unwrap().take().unwrap_or_default()
+}
@@ -1,28 +1,121 @@
 //! ESEngine Editor Library
 
 mod bridge_server;
+mod bundler;
+mod cloud_sync;
 mod compiler;
+mod context_menu;
+mod crash;
+mod deploy;
+mod diagnostics;
+mod docs;
+mod editor_settings;
 mod embedded_assets;
+mod engines;
+mod export;
+mod external_editors;
+mod files;
+mod format;
+mod global_shortcuts;
+mod headless;
+mod i18n;
+mod itch;
+mod jobs;
+mod language_server;
+mod launch;
+mod mcp_server;
+mod menu;
+mod migrate;
+mod notify;
+mod package;
+mod packaging;
+mod palettes;
+mod plugins;
 mod preview_server;
+mod process;
+mod project;
+mod reveal;
+mod runtimes;
+mod scene;
+mod search;
+mod storage;
+mod tasks;
+mod telemetry;
+mod terminal;
+mod toolchains;
+mod tray;
+mod updates;
+mod vcs;
+mod wechat_ci;
+mod windows;
+mod workspace;
 
 use bridge_server::BridgeServer;
+use i18n::BackendError;
+use mcp_server::McpServer;
 use preview_server::PreviewServer;
+use std::collections::HashMap;
 use std::io::Read as _;
-use std::path::PathBuf;
-use std::process::Stdio;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager, State};
-use tauri_plugin_updater::UpdaterExt;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_global_shortcut::ShortcutState;
 
 // =============================================================================
 // State
 // =============================================================================
 
+/// Preview, bridge and MCP servers, one per open project. The bridge
+/// started before a project is chosen is keyed by the empty string. `jobs`
+/// schedules the background work of all projects.
+#[derive(Default)]
 struct AppState {
-    preview_server: Mutex<Option<PreviewServer>>,
-    bridge_server: Mutex<BridgeServer>,
+    preview_servers: Mutex<HashMap<PathBuf, PreviewServer>>,
+    bridge_servers: Mutex<HashMap<String, BridgeServer>>,
+    mcp_servers: Mutex<HashMap<PathBuf, McpServer>>,
+    jobs: jobs::JobQueue,
+}
+
+impl AppState {
+    /// Stops the servers of a project closed in the workspace.
+    fn stop_project(&self, project_dir: &Path) {
+        if let Some(mut server) = self.preview_servers.lock().unwrap().remove(project_dir) {
+            server.stop();
+        }
+        let key = project_dir.to_string_lossy().to_string();
+        if let Some(mut bridge) = self.bridge_servers.lock().unwrap().remove(&key) {
+            bridge.stop();
+        }
+        if let Some(mut server) = self.mcp_servers.lock().unwrap().remove(project_dir) {
+            server.stop();
+        }
+    }
+
+    fn stop_all(&self) {
+        if let Ok(mut servers) = self.preview_servers.lock() {
+            for (_, mut server) in servers.drain() {
+                server.stop();
+            }
+        }
+        if let Ok(mut bridges) = self.bridge_servers.lock() {
+            for (_, mut bridge) in bridges.drain() {
+                bridge.stop();
+            }
+        }
+        if let Ok(mut servers) = self.mcp_servers.lock() {
+            for (_, mut server) in servers.drain() {
+                server.stop();
+            }
+        }
+    }
+}
+
+/// A line of tool output, emitted as `compile-output`, `export-output` and
+/// `wechat-ci-output`.
+#[derive(Clone, serde::Serialize)]
+pub(crate) struct CommandOutput {
+    stream: String,
+    data: String,
 }
 
 // =============================================================================
@@ -31,7 +124,7 @@ struct AppState {
 
 #[tauri::command]
 fn toggle_devtools(app: AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
+    if let Some(window) = app.get_webview_window(windows::MAIN_LABEL) {
         if window.is_devtools_open() {
             window.close_devtools();
         } else {
@@ -42,42 +135,53 @@ fn toggle_devtools(app: AppHandle) {
 
 #[tauri::command]
 fn start_preview_server(
+    app: AppHandle,
     state: State<AppState>,
     project_dir: String,
     port: u16,
-) -> Result<u16, String> {
-    let mut server_lock = state.preview_server.lock().unwrap();
-    let new_dir = PathBuf::from(&project_dir);
+) -> Result<u16, BackendError> {
+    let mut servers = state.preview_servers.lock().unwrap();
+    let dir = PathBuf::from(&project_dir);
 
-    if let Some(ref server) = *server_lock {
+    if let Some(server) = servers.get(&dir) {
         if server.is_running() {
-            if server.project_dir() != new_dir {
-                server.set_project_dir(new_dir);
-            }
             return Ok(server.port());
         }
     }
 
-    let mut server = PreviewServer::new(new_dir, port);
+    let mut server = PreviewServer::new(dir.clone(), port);
     let port = server.start()?;
-    *server_lock = Some(server);
+    servers.insert(dir, server);
+    drop(servers);
+    tray::refresh(&app);
     Ok(port)
 }
 
+/// Stops the preview server of `project_dir`, or every preview server.
 #[tauri::command]
-fn stop_preview_server(state: State<AppState>) {
-    let mut server_lock = state.preview_server.lock().unwrap();
-    if let Some(ref mut server) = *server_lock {
-        server.stop();
+fn stop_preview_server(app: AppHandle, state: State<AppState>, project_dir: Option<String>) {
+    let mut servers = state.preview_servers.lock().unwrap();
+    let stopped: Vec<PathBuf> = match project_dir {
+        Some(dir) => vec![PathBuf::from(dir)],
+        None => servers.keys().cloned().collect(),
+    };
+    for dir in stopped {
+        if let Some(mut server) = servers.remove(&dir) {
+            server.stop();
+        }
     }
-    *server_lock = None;
+    drop(servers);
+    tray::refresh(&app);
 }
 
+/// Reloads the previews of `project_dir`, or of every project.
 #[tauri::command]
-fn notify_preview_reload(state: State<AppState>) {
-    let server_lock = state.preview_server.lock().unwrap();
-    if let Some(ref server) = *server_lock {
-        server.notify_reload();
+fn notify_preview_reload(state: State<AppState>, project_dir: Option<String>) {
+    let servers = state.preview_servers.lock().unwrap();
+    for (dir, server) in servers.iter() {
+        if project_dir.as_ref().is_none_or(|p| Path::new(p) == dir) {
+            server.notify_reload();
+        }
     }
 }
 
@@ -86,15 +190,22 @@ fn start_bridge_server(
     state: State<AppState>,
     app: AppHandle,
     project_path: Option<String>,
-) -> Result<u16, String> {
-    let mut bridge = state.bridge_server.lock().unwrap();
+) -> Result<u16, BackendError> {
+    let mut bridges = state.bridge_servers.lock().unwrap();
+    let bridge = bridges
+        .entry(project_path.clone().unwrap_or_default())
+        .or_insert_with(BridgeServer::new);
     bridge.start(app, project_path)
 }
 
+/// Points the bridge started without a project at `project_path`.
 #[tauri::command]
 fn update_bridge_project(state: State<AppState>, project_path: String) {
-    let mut bridge = state.bridge_server.lock().unwrap();
-    bridge.update_project_path(&project_path);
+    let mut bridges = state.bridge_servers.lock().unwrap();
+    if let Some(mut bridge) = bridges.remove("") {
+        bridge.update_project_path(&project_path);
+        bridges.entry(project_path).or_insert(bridge);
+    }
 }
 
 #[tauri::command]
@@ -144,167 +255,82 @@ fn open_folder(path: String) -> Result<(), String> {
     open::that(&path).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn get_embedded_asset(name: String) -> Result<Vec<u8>, String> {
-    match name.as_str() {
-        // Editor runtime
-        "engine.js" => Ok(embedded_assets::ENGINE_JS.to_vec()),
-        "engine.wasm" => Ok(embedded_assets::ENGINE_WASM.to_vec()),
-        // SDK
-        "sdk.wechat.js" => Ok(embedded_assets::SDK_WECHAT_JS.to_vec()),
-        "sdk.esm.js" => Ok(embedded_assets::SDK_ESM_JS.to_vec()),
-        "sdk.esm.dts" => Ok(embedded_assets::SDK_ESM_DTS.to_vec()),
-        "sdk.wasm.js" => Ok(embedded_assets::SDK_WASM_JS.to_vec()),
-        "sdk.wasm.dts" => Ok(embedded_assets::SDK_WASM_DTS.to_vec()),
-        "sdk.shared.wasm.dts" => Ok(embedded_assets::SDK_SHARED_WASM_DTS.to_vec()),
-        "sdk.shared.app.dts" => Ok(embedded_assets::SDK_SHARED_APP_DTS.to_vec()),
-        "sdk.physics.dts" => Ok(embedded_assets::SDK_PHYSICS_DTS.to_vec()),
-        "sdk.spine.dts" => Ok(embedded_assets::SDK_SPINE_DTS.to_vec()),
-        "editor.dts" => Ok(embedded_assets::EDITOR_DTS.to_vec()),
-        "esbuild.wasm" => Ok(embedded_assets::ESBUILD_WASM.to_vec()),
-        // Build assets served from public/wasm/ via HTTP, not embedded
-        "engine.single.js" | "engine.wxgame.js" | "engine.wxgame.wasm"
-        | "spine38.js" | "spine38.wasm" | "spine41.js" | "spine41.wasm"
-        | "spine42.js" | "spine42.wasm" | "physics.js" | "physics.wasm" => {
-            Err(format!("Asset '{}' is served from public/wasm/, not embedded.", name))
-        }
-        _ => Err(format!("Unknown embedded asset: {}", name)),
-    }
-}
-
-#[derive(Clone, serde::Serialize)]
-struct CommandOutput {
-    stream: String,
-    data: String,
-}
-
-#[derive(serde::Serialize)]
-struct CommandResult {
-    code: i32,
-}
-
-#[tauri::command]
-async fn execute_command(
-    app: AppHandle,
-    cmd: String,
-    args: Vec<String>,
-    cwd: String,
-) -> Result<CommandResult, String> {
-    let mut child = Command::new(&cmd)
-        .args(&args)
-        .current_dir(&cwd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| e.to_string())?;
-
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-
-    let app_stdout = app.clone();
-    let stdout_handle = tokio::spawn(async move {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            let _ = app_stdout.emit("command-output", CommandOutput {
-                stream: "stdout".to_string(),
-                data: line,
-            });
-        }
-    });
-
-    let app_stderr = app.clone();
-    let stderr_handle = tokio::spawn(async move {
-        let reader = BufReader::new(stderr);
-        let mut lines = reader.lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            let _ = app_stderr.emit("command-output", CommandOutput {
-                stream: "stderr".to_string(),
-                data: line,
-            });
-        }
-    });
-
-    let _ = tokio::join!(stdout_handle, stderr_handle);
-
-    let status = child.wait().await.map_err(|e| e.to_string())?;
-
-    Ok(CommandResult {
-        code: status.code().unwrap_or(-1),
-    })
-}
-
-// =============================================================================
-// Update (proxy-aware)
-// =============================================================================
-
-fn resolve_proxy(explicit: Option<String>) -> Option<url::Url> {
-    explicit
-        .filter(|s| !s.is_empty())
-        .or_else(|| {
-            ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
-                .iter()
-                .find_map(|key| std::env::var(key).ok())
-        })
-        .and_then(|s| url::Url::parse(&s).ok())
-}
-
-#[derive(serde::Serialize)]
-struct UpdateInfo {
-    version: String,
-    body: Option<String>,
-}
-
-#[tauri::command]
-async fn check_update(app: AppHandle, proxy: Option<String>) -> Result<Option<UpdateInfo>, String> {
-    let mut builder = app.updater_builder();
-    if let Some(proxy_url) = resolve_proxy(proxy) {
-        builder = builder.proxy(proxy_url);
-    }
-    let updater = builder.build().map_err(|e| e.to_string())?;
-    match updater.check().await {
-        Ok(Some(update)) => Ok(Some(UpdateInfo {
-            version: update.version.clone(),
-            body: update.body.clone(),
-        })),
-        Ok(None) => Ok(None),
-        Err(e) => Err(e.to_string()),
-    }
-}
-
-#[tauri::command]
-async fn install_update(app: AppHandle, proxy: Option<String>) -> Result<(), String> {
-    let mut builder = app.updater_builder();
-    if let Some(proxy_url) = resolve_proxy(proxy) {
-        builder = builder.proxy(proxy_url);
-    }
-    let updater = builder.build().map_err(|e| e.to_string())?;
-    let update = updater
-        .check()
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "No update available".to_string())?;
-    update
-        .download_and_install(|_, _| {}, || {})
-        .await
-        .map_err(|e| e.to_string())
-}
-
 // =============================================================================
 // Entry Point
 // =============================================================================
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == crash::MONITOR_FLAG) {
+        std::process::exit(crash::run_monitor(&args));
+    }
+    crash::install_panic_hook();
+    if args.iter().any(|a| a == headless::FLAG) {
+        std::process::exit(headless::run(&args));
+    }
+
     tauri::Builder::default()
+        // Registered first, so a second launch exits before doing anything.
+        .plugin(tauri_plugin_single_instance::init(launch::handle_second_instance))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .manage(AppState {
-            preview_server: Mutex::new(None),
-            bridge_server: Mutex::new(BridgeServer::new()),
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        global_shortcuts::handle(app, shortcut);
+                    }
+                })
+                .build(),
+        )
+        .manage(AppState::default())
+        .manage(process::ProcessRegistry::default())
+        .manage(files::TrashRegistry::default())
+        .manage(files::import::DropTarget::default())
+        .manage(search::SearchRegistry::default())
+        .manage(project::lock::ProjectLocks::default())
+        .manage(scene::prefab::PrefabCache::default())
+        .manage(scene::undo_journal::UndoJournals::default())
+        .manage(workspace::Workspace::default())
+        .manage(toolchains::ToolchainCache::default())
+        .manage(bundler::watch::ScriptWatchers::default())
+        .manage(context_menu::ContextMenus::default())
+        .manage(launch::LaunchRequests::default())
+        .manage(tray::TrayState::default())
+        .manage(updates::UpdateState::default())
+        .manage(telemetry::Telemetry::default())
+        .manage(plugins::Plugins::default())
+        .manage(language_server::LanguageServers::default())
+        .manage(global_shortcuts::GlobalShortcuts::default())
+        .manage(external_editors::ExternalEditors::default())
+        .on_menu_event(menu::handle_event)
+        .register_asynchronous_uri_scheme_protocol(embedded_assets::PROTOCOL, |_, request, responder| {
+            // Decompressing, hashing and reading docs or engine files must
+            // not hold up the webview's thread.
+            tauri::async_runtime::spawn_blocking(move || responder.respond(embedded_assets::serve(&request)));
+        })
+        .setup(|app| {
+            toolchains::register_managed(app.handle());
+            engines::init(app.handle());
+            runtimes::init(app.handle());
+            docs::init(app.handle());
+            crash::init(app.handle());
+            menu::init(app.handle());
+            launch::init(app.handle());
+            tray::init(app.handle());
+            notify::init(app.handle());
+            i18n::init(app.handle());
+            global_shortcuts::init(app.handle());
+            telemetry::init(app.handle());
+            storage::auto_cleanup(app.handle(), None);
+            windows::restore_main_window(app.handle());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             toggle_devtools,
@@ -314,32 +340,231 @@ pub fn run() {
             open_preview_in_browser,
             start_bridge_server,
             update_bridge_project,
+            mcp_server::start_mcp_server,
+            mcp_server::stop_mcp_server,
+            i18n::get_message_catalog,
+            global_shortcuts::list_global_shortcuts,
             open_folder,
+            reveal::reveal_in_file_manager,
+            terminal::open_terminal,
+            external_editors::open_in_external_editor,
+            external_editors::list_external_editors,
+            context_menu::show_context_menu,
             unzip_to_directory,
-            execute_command,
-            get_embedded_asset,
-            check_update,
-            install_update,
+            process::execute_command,
+            process::list_running_commands,
+            process::kill_command,
+            process::output::get_command_log,
+            process::ansi::parse_ansi,
+            process::resolve_command,
+            process::pty::execute_command_pty,
+            process::write_command_stdin,
+            process::close_command_stdin,
+            process::pty::resize_command,
+            process::limits::get_process_stats,
+            embedded_assets::get_embedded_asset,
+            embedded_assets::list_embedded_assets,
+            embedded_assets::get_asset_manifest,
+            embedded_assets::get_engine_changes,
+            docs::extract_docs,
+            crash::list_crash_reports,
+            crash::upload_crash_report,
+            crash::dismiss_crash_report,
+            crash::delete_crash_report,
+            crash::get_crash_reporting,
+            crash::set_crash_reporting,
+            editor_settings::get_editor_settings,
+            editor_settings::update_editor_settings,
+            editor_settings::reset_editor_settings,
+            editor_settings::export_editor_settings,
+            editor_settings::import_editor_settings,
+            palettes::list_palettes,
+            palettes::save_palette,
+            palettes::delete_palette,
+            palettes::add_palette_color,
+            palettes::import_palette,
+            palettes::export_palette,
+            launch::take_open_requests,
+            tray::close_to_tray,
+            windows::open_panel_window,
+            windows::open_play_window,
+            windows::close_panel_window,
+            windows::close_panel_windows,
+            windows::list_editor_windows,
+            windows::restore_panel_windows,
+            engines::list_engine_versions,
+            engines::install_engine_version,
+            engines::remove_engine_version,
+            engines::get_engine_dev_dir,
+            engines::set_engine_dev_dir,
+            runtimes::list_runtimes,
+            runtimes::download_runtime,
+            runtimes::get_runtime_file,
+            updates::check_update,
+            updates::download_update,
+            updates::get_pending_update,
+            updates::install_update,
+            diagnostics::get_system_info,
+            telemetry::record_telemetry_event,
+            telemetry::get_telemetry_log,
+            telemetry::get_telemetry_status,
+            telemetry::flush_telemetry,
+            telemetry::clear_telemetry_data,
+            storage::get_storage_usage,
+            storage::prune_storage,
+            plugins::load_plugins,
+            plugins::list_plugins,
+            plugins::unload_plugins,
+            plugins::run_plugin_command,
+            plugins::import_with_plugin,
+            plugins::set_plugin_enabled,
+            plugins::marketplace::search_plugin_marketplace,
+            plugins::marketplace::install_plugin,
+            plugins::marketplace::uninstall_plugin,
+            plugins::marketplace::check_plugin_updates,
             compiler::get_toolchain_status,
             compiler::set_emsdk_path,
             compiler::install_emsdk,
             compiler::compile_wasm,
             compiler::clear_build_cache,
+            bundler::bundle_scripts,
+            bundler::watch::start_script_watch,
+            bundler::watch::stop_script_watch,
+            bundler::watch::list_script_watches,
+            language_server::start_language_server,
+            language_server::send_language_server_message,
+            language_server::stop_language_server,
+            export::build_export,
+            export::archive::package_build,
+            export::targets::list_export_targets,
+            export::reproducible::verify_reproducible,
+            deploy::deploy_build,
+            itch::get_butler_status,
+            itch::install_butler,
+            itch::itch_login,
+            itch::publish_itch,
+            migrate::detect_project_engine,
+            migrate::migrate_project,
+            files::delete_to_trash,
+            files::restore_from_trash,
+            files::import::set_drop_target,
+            files::import::import_external_files,
+            files::clipboard::paste_clipboard_image,
+            files::folders::duplicate_path,
+            files::folders::move_paths,
+            files::folders::get_folder_size,
+            workspace::open_workspace_project,
+            workspace::close_workspace_project,
+            workspace::list_workspace_projects,
+            workspace::transfer::copy_assets_between_projects,
+            package::export_package,
+            package::read_package_manifest,
+            package::import_package,
+            package::manager::get_packages,
+            package::manager::set_package_registry,
+            package::manager::add_package,
+            package::manager::remove_package,
+            package::manager::update_packages,
+            package::manager::install_packages,
+            cloud_sync::sync_project,
+            search::search_project,
+            search::cancel_search,
+            search::replace::preview_replace,
+            search::replace::apply_replace,
+            format::format_files,
+            scene::save_scene,
+            scene::autosave_scene,
+            scene::list_scene_recovery,
+            scene::restore_scene_version,
+            scene::discard_scene_recovery,
+            scene::undo_journal::open_undo_journal,
+            scene::undo_journal::append_undo_journal,
+            scene::undo_journal::close_undo_journal,
+            scene::prefab::resolve_prefab,
+            scene::prefab::diff_prefab_instance,
+            vcs::get_vcs_status,
+            vcs::get_file_diff,
+            vcs::history::vcs_commit,
+            vcs::history::vcs_log,
+            vcs::history::vcs_revert_files,
+            vcs::lfs::check_lfs,
+            vcs::lfs::setup_lfs,
+            vcs::merge::merge_scene_json,
+            vcs::merge::merge_scene_conflict,
+            vcs::merge::resolve_scene_merge,
+            process::list_processes,
+            process::kill_process,
+            process::kill_project_processes,
+            jobs::list_jobs,
+            jobs::cancel_job,
+            tasks::list_tasks,
+            tasks::run_tasks,
+            toolchains::detect_toolchains,
+            toolchains::install_node,
+            project::list_project_templates,
+            project::create_project,
+            project::script_templates::list_script_templates,
+            project::script_templates::create_script,
+            project::recent::list_recent_projects,
+            project::recent::add_recent_project,
+            project::recent::remove_recent_project,
+            project::recent::set_recent_project_pinned,
+            project::recent::reorder_recent_projects,
+            project::recent::clear_recent_projects,
+            project::check::check_project,
+            project::lock::get_project_lock_status,
+            project::lock::acquire_project_lock,
+            project::lock::release_project_lock,
+            project::stats::get_project_stats,
+            project::snapshot::create_snapshot,
+            project::snapshot::list_snapshots,
+            project::snapshot::delete_snapshot,
+            project::snapshot::restore_snapshot,
+            project::snapshot::diff_snapshot,
+            project::settings::get_project_settings,
+            project::settings::update_project_settings,
+            project::settings::validate_project_settings,
+            project::typings::sync_project_types,
+            project::registry::get_template_registry_url,
+            project::registry::set_template_registry_url,
+            project::registry::list_remote_templates,
+            project::registry::clear_template_cache,
+            export::profiles::get_build_profiles,
+            export::profiles::save_build_profiles,
+            export::profiles::run_build,
+            wechat_ci::wechat_preview,
+            wechat_ci::wechat_upload,
         ])
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::DragDrop(drop) = event {
+                files::import::handle_drag_drop(window.app_handle(), drop);
+            }
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                windows::handle_close_requested(window.app_handle(), window.label());
+            }
+            // Panel and play windows come and go; the editor shuts down
+            // with the main window.
             if let tauri::WindowEvent::Destroyed = event {
+                if window.label() != windows::MAIN_LABEL {
+                    return;
+                }
                 let app = window.app_handle();
+                windows::close_all(app);
                 if let Some(state) = app.try_state::<AppState>() {
-                    if let Ok(mut server_lock) = state.preview_server.lock() {
-                        if let Some(ref mut server) = *server_lock {
-                            server.stop();
-                        }
-                        *server_lock = None;
-                    }
-                    if let Ok(mut bridge) = state.bridge_server.lock() {
-                        bridge.stop();
-                    }
+                    state.stop_all();
+                }
+                if let Some(registry) = app.try_state::<process::ProcessRegistry>() {
+                    registry.kill_all();
+                    registry.remove_logs();
+                }
+                if let Some(locks) = app.try_state::<project::lock::ProjectLocks>() {
+                    locks.release_all();
+                }
+                if let Some(journals) = app.try_state::<scene::undo_journal::UndoJournals>() {
+                    journals.close_all();
                 }
+                telemetry::shutdown(app);
+                updates::install_pending(app);
             }
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,567 @@
+//! MCP server — Model Context Protocol tools for AI assistants
+//!
+//! An opt-in server (setting `mcp.enabled`) that lets an AI assistant work
+//! on a project directly, without the editor frontend in the loop. It
+//! speaks MCP's Streamable HTTP transport in its simplest form: JSON-RPC
+//! requests are POSTed to `http://127.0.0.1:<port>/mcp` and answered with
+//! JSON. There is one server per open project, started next to the bridge
+//! server.
+//!
+//! Tools:
+//! - `list_scenes` → the project's scenes with their names and entity counts
+//! - `read_scene {path}` → the scene's JSON
+//! - `search_assets {query, type?, limit?}` → assets whose path contains
+//!   `query`, with their uuid and type from the `.meta` file
+//! - `modify_entity {scene, entity, name?, visible?, components?, removeComponents?}`
+//!   renames, shows or hides an entity and merges or removes components;
+//!   the scene is saved like the editor saves it
+//! - `run_build {profile?}` exports the project with a build profile
+//!
+//! Every request must carry `Authorization: Bearer <token>`, with the token
+//! the server was started with; it is new each time and shown in the
+//! editor's output, so other programs on the machine cannot read the
+//! project. Reading tools run without asking. Tools that change the project
+//! ask the user first with a native dialog, unless `mcp.confirmWrites` is
+//! off; the dialog does not name the client, since it names itself. A scene
+//! that changed while the dialog was open is not modified. Requests from
+//! web pages other than localhost are refused.
+
+use crate::deploy::hex;
+use crate::editor_settings;
+use crate::export::profiles::resolve_profile_options;
+use crate::export::{run_export, JobListener};
+use crate::i18n::BackendError;
+use crate::jobs::{JobCategory, JobSpec};
+use crate::scene;
+use crate::AppState;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tiny_http::{Header, Response, Server};
+
+const DEFAULT_PORT: u16 = 9930;
+const MAX_PORT_ATTEMPTS: u16 = 10;
+const ENABLED_SETTING: &str = "mcp.enabled";
+const PORT_SETTING: &str = "mcp.port";
+const CONFIRM_SETTING: &str = "mcp.confirmWrites";
+/// Newest first; a client asking for another version gets the newest.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 500;
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// =============================================================================
+// Server
+// =============================================================================
+
+/// Where a running server is reached.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpEndpoint {
+    pub port: u16,
+    /// Sent by clients as `Authorization: Bearer <token>`.
+    pub token: String,
+}
+
+/// What the request threads of one server share.
+struct Context {
+    app: AppHandle,
+    project_dir: PathBuf,
+    token: String,
+    /// One confirmation dialog at a time.
+    confirming: Mutex<()>,
+}
+
+#[derive(Default)]
+pub struct McpServer {
+    server: Option<Arc<Server>>,
+    worker_handle: Option<thread::JoinHandle<()>>,
+    shutdown: Arc<AtomicBool>,
+    port: u16,
+    token: String,
+}
+
+impl McpServer {
+    pub fn start(&mut self, app: AppHandle, project_dir: PathBuf, base_port: u16) -> Result<McpEndpoint, BackendError> {
+        if self.server.is_some() {
+            return Ok(McpEndpoint { port: self.port, token: self.token.clone() });
+        }
+
+        let token = new_token()?;
+        let server = try_bind(base_port)?;
+        self.port = server.server_addr().to_ip().map(|a| a.port()).unwrap_or(base_port);
+        self.token = token.clone();
+        let server = Arc::new(server);
+        self.server = Some(server.clone());
+        self.shutdown.store(false, Ordering::SeqCst);
+
+        let shutdown = self.shutdown.clone();
+        let context = Arc::new(Context { app, project_dir, token: token.clone(), confirming: Mutex::new(()) });
+        self.worker_handle = Some(thread::spawn(move || worker_loop(server, shutdown, context)));
+        eprintln!("[mcp] Listening on http://127.0.0.1:{}/mcp", self.port);
+        Ok(McpEndpoint { port: self.port, token })
+    }
+
+    pub fn stop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(ref server) = self.server {
+            server.unblock();
+        }
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+        }
+        self.server = None;
+    }
+}
+
+impl Drop for McpServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Starts the MCP server of `project_dir` and returns its port and token,
+/// or stops it and returns `None` when `mcp.enabled` is off.
+#[tauri::command]
+pub fn start_mcp_server(
+    app: AppHandle,
+    state: State<AppState>,
+    project_dir: String,
+) -> Result<Option<McpEndpoint>, BackendError> {
+    let values = editor_settings::load(&app)?.values;
+    let dir = PathBuf::from(&project_dir);
+    let mut servers = state.mcp_servers.lock().unwrap();
+    if !values.get(ENABLED_SETTING).and_then(Value::as_bool).unwrap_or(false) {
+        if let Some(mut server) = servers.remove(&dir) {
+            server.stop();
+        }
+        return Ok(None);
+    }
+    let port = values
+        .get(PORT_SETTING)
+        .and_then(Value::as_u64)
+        .and_then(|port| u16::try_from(port).ok())
+        .unwrap_or(DEFAULT_PORT);
+    servers.entry(dir.clone()).or_default().start(app, dir, port).map(Some)
+}
+
+#[tauri::command]
+pub fn stop_mcp_server(state: State<AppState>, project_dir: String) {
+    if let Some(mut server) = state.mcp_servers.lock().unwrap().remove(Path::new(&project_dir)) {
+        server.stop();
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+fn worker_loop(server: Arc<Server>, shutdown: Arc<AtomicBool>, context: Arc<Context>) {
+    while !shutdown.load(Ordering::SeqCst) {
+        let request = match server.recv_timeout(Duration::from_millis(500)) {
+            Ok(Some(r)) => r,
+            Ok(None) => continue,
+            Err(_) => break,
+        };
+        // Builds and confirmations take long; other requests go on meanwhile.
+        let context = context.clone();
+        thread::spawn(move || handle_request(request, &context));
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, context: &Context) {
+    let origin = request.headers().iter().find(|h| h.field.equiv("Origin")).map(|h| h.value.to_string());
+    if origin.is_some_and(|origin| !is_local_origin(&origin)) {
+        let _ = request.respond(Response::from_string("Forbidden").with_status_code(403));
+        return;
+    }
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+    if path != "/mcp" {
+        let _ = request.respond(Response::from_string("Not found").with_status_code(404));
+        return;
+    }
+    let authorization = request.headers().iter().find(|h| h.field.equiv("Authorization")).map(|h| h.value.as_str());
+    if authorization.and_then(|value| value.strip_prefix("Bearer ")) != Some(context.token.as_str()) {
+        let challenge = Header::from_bytes(&b"WWW-Authenticate"[..], &b"Bearer"[..]).unwrap();
+        let _ = request.respond(Response::from_string("Unauthorized").with_status_code(401).with_header(challenge));
+        return;
+    }
+    if request.method() != &tiny_http::Method::Post {
+        // No server-initiated messages, so no event stream to GET.
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(405));
+        return;
+    }
+
+    let mut body = String::new();
+    let message = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => serde_json::from_str::<Value>(&body),
+        Err(e) => {
+            let _ = respond_json(request, 400, &rpc_error(Value::Null, PARSE_ERROR, e.to_string()));
+            return;
+        }
+    };
+    let message = match message {
+        Ok(message) => message,
+        Err(e) => {
+            let _ = respond_json(request, 400, &rpc_error(Value::Null, PARSE_ERROR, format!("Invalid JSON: {}", e)));
+            return;
+        }
+    };
+
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response to a request we never send, or a batch.
+        let _ = respond_json(request, 400, &rpc_error(Value::Null, INVALID_REQUEST, "Expected a JSON-RPC request"));
+        return;
+    };
+    let Some(id) = message.get("id").cloned() else {
+        // Notifications such as `notifications/initialized` need no answer.
+        let _ = request.respond(Response::empty(202));
+        return;
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let response = match dispatch(context, method, &params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => rpc_error(id, code, message),
+    };
+    let _ = respond_json(request, 200, &response);
+}
+
+fn dispatch(context: &Context, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str).unwrap_or_default();
+            let version = PROTOCOL_VERSIONS.iter().find(|v| **v == requested).unwrap_or(&PROTOCOL_VERSIONS[0]);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "esengine-editor", "version": env!("CARGO_PKG_VERSION") },
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_list() })),
+        "tools/call" => {
+            let name =
+                params.get("name").and_then(Value::as_str).ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+            let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            let result = match name {
+                "list_scenes" => list_scenes(&context.project_dir),
+                "read_scene" => read_scene(&context.project_dir, &args),
+                "search_assets" => search_assets(&context.project_dir, &args),
+                "modify_entity" => modify_entity(context, &args),
+                "run_build" => run_build(context, &args),
+                _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+            };
+            // Tool failures are results the assistant can read and react to.
+            Ok(match result {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+                    "isError": false,
+                }),
+                Err(message) => json!({ "content": [{ "type": "text", "text": message }], "isError": true }),
+            })
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    }
+}
+
+fn tool_list() -> Value {
+    json!([
+        {
+            "name": "list_scenes",
+            "description": "Lists the scenes of the project with their names and entity counts.",
+            "inputSchema": { "type": "object", "properties": {} },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "read_scene",
+            "description": "Returns the JSON of a scene or prefab: its entities with ids, names, parents and components.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": { "type": "string", "description": "Scene path relative to the project" } },
+                "required": ["path"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "search_assets",
+            "description": "Finds project assets whose path contains the query, with their uuid and type.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Case-insensitive part of the asset path" },
+                    "type": { "type": "string", "description": "Asset type such as texture, scene or audio" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT },
+                },
+                "required": ["query"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "modify_entity",
+            "description": "Changes an entity in a scene file and saves the scene. Component data is merged field by \
+                            field; a component the entity lacks is added. The user is asked to confirm.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scene": { "type": "string", "description": "Scene path relative to the project" },
+                    "entity": { "type": ["integer", "string"], "description": "Entity id, or its name if unique" },
+                    "name": { "type": "string", "description": "New name" },
+                    "visible": { "type": "boolean" },
+                    "components": {
+                        "type": "object",
+                        "description": "Component type to the data fields to set, e.g. {\"Transform\": {\"position\": {\"x\": 0, \"y\": 0, \"z\": 0}}}",
+                    },
+                    "removeComponents": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["scene", "entity"],
+            },
+            "annotations": { "readOnlyHint": false, "destructiveHint": true },
+        },
+        {
+            "name": "run_build",
+            "description": "Builds the project with a build profile and returns the result. The user is asked to confirm.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "profile": { "type": "string", "description": "Profile name; the active one if omitted" } },
+            },
+            "annotations": { "readOnlyHint": false },
+        },
+    ])
+}
+
+// =============================================================================
+// Tools
+// =============================================================================
+
+fn list_scenes(project_dir: &Path) -> Result<Value, String> {
+    let mut scenes: Vec<Value> = project_files(project_dir, |path| path.extension().is_some_and(|e| e == "esscene"))
+        .into_iter()
+        .map(|(rel, path)| {
+            let scene = std::fs::read_to_string(&path).ok().and_then(|s| serde_json::from_str::<Value>(&s).ok());
+            let name = scene.as_ref().and_then(|s| s.get("name")).and_then(Value::as_str).unwrap_or_default();
+            let entities = scene.as_ref().and_then(|s| s.get("entities")).and_then(Value::as_array).map(Vec::len);
+            json!({ "path": rel, "name": name, "entities": entities })
+        })
+        .collect();
+    scenes.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+    Ok(Value::Array(scenes))
+}
+
+fn read_scene(project_dir: &Path, args: &Value) -> Result<Value, String> {
+    let rel = string_arg(args, "path")?;
+    let path = scene::scene_path(project_dir, rel)?;
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid scene {}: {}", rel, e))
+}
+
+fn search_assets(project_dir: &Path, args: &Value) -> Result<Value, String> {
+    let query = string_arg(args, "query")?.to_lowercase();
+    let kind = args.get("type").and_then(Value::as_str);
+    let limit = args
+        .get("limit")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_SEARCH_LIMIT, |n| (n as usize).clamp(1, MAX_SEARCH_LIMIT));
+
+    let mut assets = Vec::new();
+    for (rel, path) in project_files(project_dir, |path| path.extension().is_some_and(|e| e == "meta")) {
+        let asset = rel.trim_end_matches(".meta");
+        if !asset.to_lowercase().contains(&query) {
+            continue;
+        }
+        let Some(meta) = std::fs::read_to_string(&path).ok().and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        else {
+            continue;
+        };
+        let asset_type = meta.get("type").and_then(Value::as_str).unwrap_or_default();
+        if kind.is_some_and(|kind| !kind.eq_ignore_ascii_case(asset_type)) {
+            continue;
+        }
+        assets.push(json!({ "path": asset, "uuid": meta.get("uuid"), "type": asset_type }));
+    }
+    assets.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+    let truncated = assets.len() > limit;
+    assets.truncate(limit);
+    Ok(json!({ "assets": assets, "truncated": truncated }))
+}
+
+fn modify_entity(context: &Context, args: &Value) -> Result<Value, String> {
+    let rel = string_arg(args, "scene")?;
+    let target = args.get("entity").filter(|e| e.is_i64() || e.is_string()).ok_or("Missing entity id or name")?;
+    let path = scene::scene_path(&context.project_dir, rel)?;
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+    let mut doc: Value = serde_json::from_str(&text).map_err(|e| format!("Invalid scene {}: {}", rel, e))?;
+
+    let entities = doc.get_mut("entities").and_then(Value::as_array_mut).ok_or("The scene has no entities")?;
+    let matches: Vec<usize> = entities
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| match target {
+            Value::String(name) => e.get("name").and_then(Value::as_str) == Some(name),
+            _ => e.get("id").and_then(Value::as_i64) == target.as_i64(),
+        })
+        .map(|(i, _)| i)
+        .collect();
+    let index = match matches[..] {
+        [index] => index,
+        [] => return Err(format!("No entity {} in {}", target, rel)),
+        _ => return Err(format!("Several entities are named {}; pass the entity id", target)),
+    };
+
+    let label = entities[index].get("name").and_then(Value::as_str).unwrap_or("the entity").to_string();
+    if !confirm(context, &format!("modify \"{}\" in {}", label, rel)) {
+        return Err("The user declined the change".to_string());
+    }
+    // The scene may have been saved while the dialog was open; writing the
+    // copy read before would undo that.
+    let current = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+    if Sha256::digest(&current) != Sha256::digest(text.as_bytes()) {
+        return Err(format!("{} changed while waiting for confirmation; read it again and retry", rel));
+    }
+
+    let entity = entities[index].as_object_mut().ok_or("Invalid entity")?;
+    if let Some(name) = args.get("name").and_then(Value::as_str) {
+        entity.insert("name".into(), name.into());
+    }
+    if let Some(visible) = args.get("visible").and_then(Value::as_bool) {
+        entity.insert("visible".into(), visible.into());
+    }
+    let components = entity.entry("components").or_insert_with(|| json!([]));
+    let components = components.as_array_mut().ok_or("Invalid components")?;
+    if let Some(removed) = args.get("removeComponents").and_then(Value::as_array) {
+        components.retain(|c| !removed.contains(c.get("type").unwrap_or(&Value::Null)));
+    }
+    if let Some(changes) = args.get("components").and_then(Value::as_object) {
+        for (kind, fields) in changes {
+            let fields = fields.as_object().ok_or_else(|| format!("Data of {} must be an object", kind))?;
+            match components.iter_mut().find(|c| c.get("type").and_then(Value::as_str) == Some(kind)) {
+                Some(component) => {
+                    let data = component.as_object_mut().ok_or("Invalid component")?;
+                    let data = data.entry("data").or_insert_with(|| json!({}));
+                    let data = data.as_object_mut().ok_or_else(|| format!("Invalid data of {}", kind))?;
+                    data.extend(fields.clone());
+                }
+                None => components.push(json!({ "type": kind, "data": fields.clone() })),
+            }
+        }
+    }
+    let entity = Value::Object(entity.clone());
+
+    let content = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+    scene::save(&context.project_dir, rel, &content)?;
+    // The editor reloads the scene if it has it open without changes.
+    let _ = context.app.emit("mcp-scene-modified", json!({ "path": path.to_string_lossy() }));
+    Ok(entity)
+}
+
+fn run_build(context: &Context, args: &Value) -> Result<Value, String> {
+    let profile = args.get("profile").and_then(Value::as_str);
+    let options = resolve_profile_options(&context.project_dir, profile)?;
+    let action = match profile {
+        Some(profile) => format!("build the project with the \"{}\" profile", profile),
+        None => "build the project with the active build profile".to_string(),
+    };
+    if !confirm(context, &action) {
+        return Err("The user declined the build".to_string());
+    }
+
+    let app = context.app.clone();
+    let spec = JobSpec::new(JobCategory::Build, format!("Export {}", options.target)).project(&options.project_dir);
+    let listener_app = app.clone();
+    let result = tauri::async_runtime::block_on(
+        app.state::<AppState>()
+            .jobs
+            .run(&app, spec, move |job| run_export(&JobListener { app: &listener_app, job }, &options)),
+    )?;
+    serde_json::to_value(&result).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Asks the user to allow `action`, e.g. "build the project".
+fn confirm(context: &Context, action: &str) -> bool {
+    let values = editor_settings::load(&context.app).map(|settings| settings.values).unwrap_or_default();
+    if !values.get(CONFIRM_SETTING).and_then(Value::as_bool).unwrap_or(true) {
+        return true;
+    }
+    let _guard = context.confirming.lock().unwrap_or_else(|e| e.into_inner());
+    context
+        .app
+        .dialog()
+        .message(format!("An AI assistant connected to the MCP server wants to {}.", action))
+        .title("Allow AI Assistant")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Allow".to_string(), "Deny".to_string()))
+        .blocking_show()
+}
+
+/// Files of the project matching `filter`, skipping ignored and hidden
+/// folders, as (path relative to the project with forward slashes, path).
+fn project_files(project_dir: &Path, filter: impl Fn(&Path) -> bool) -> Vec<(String, PathBuf)> {
+    WalkBuilder::new(project_dir)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()) && filter(entry.path()))
+        .filter_map(|entry| {
+            let rel = entry.path().strip_prefix(project_dir).ok()?.to_string_lossy().replace('\\', "/");
+            Some((rel, entry.into_path()))
+        })
+        .collect()
+}
+
+/// A random session token, hex encoded.
+fn new_token() -> Result<String, BackendError> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate the MCP token: {}", e))?;
+    Ok(hex(&bytes))
+}
+
+fn string_arg<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    args.get(key).and_then(Value::as_str).ok_or_else(|| format!("Missing {}", key))
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    let host = origin.split("://").nth(1).unwrap_or_default();
+    let host = host.rsplit_once(':').map_or(host, |(host, _)| host);
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]") || origin.starts_with("tauri://")
+}
+
+fn rpc_error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+fn respond_json(request: tiny_http::Request, status: u16, body: &Value) -> Result<(), std::io::Error> {
+    let data = serde_json::to_vec(body).unwrap_or_default();
+    let response = Response::from_data(data)
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+    request.respond(response)
+}
+
+fn try_bind(base_port: u16) -> Result<Server, BackendError> {
+    let mut last_error = String::new();
+    for port in (0..MAX_PORT_ATTEMPTS).filter_map(|offset| base_port.checked_add(offset)) {
+        match Server::http(format!("127.0.0.1:{}", port)) {
+            Ok(server) => return Ok(server),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(BackendError::new("mcp.bindFailed", [("start", base_port.to_string()), ("error", last_error)]))
+}
@@ -0,0 +1,195 @@
+//! Native application menu
+//!
+//! On macOS the editor gets the menu bar users expect: an app menu with
+//! Settings (Cmd+,) and Quit (Cmd+Q), and File/Edit/View/Help menus whose
+//! items carry the ids of the editor's own menu items. Choosing one emits
+//! `menu-action` with that id to the main window, which runs the item as if
+//! it had been clicked in the editor's menu bar.
+//!
+//! File > Open Recent lists the recent projects and is rebuilt whenever
+//! that list changes; choosing one emits `menu-open-project` with the path
+//! of its project file. Windows and Linux keep the editor's in-window menu
+//! bar and get no native one.
+
+use crate::context_menu;
+use crate::project::recent;
+use crate::tray;
+use crate::windows::MAIN_LABEL;
+use tauri::menu::{Menu, MenuEvent, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu, SubmenuBuilder};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+const RECENT_MENU: &str = "file.recent";
+const RECENT_PREFIX: &str = "recent:";
+const CLEAR_RECENT: &str = "recent.clear";
+const QUIT: &str = "app.quit";
+
+// =============================================================================
+// Menu
+// =============================================================================
+
+/// Editor menu items as `(id, label, accelerator)`, in menu order. `None`
+/// stands for a separator.
+type Items = &'static [Option<(&'static str, &'static str, Option<&'static str>)>];
+
+const FILE_ITEMS: Items =
+    &[Some(("file.new", "New Scene", Some("CmdOrCtrl+N"))), Some(("file.open", "Open...", Some("CmdOrCtrl+O")))];
+
+const FILE_SAVE_ITEMS: Items = &[
+    None,
+    Some(("file.save", "Save", Some("CmdOrCtrl+S"))),
+    Some(("file.save-as", "Save As...", Some("CmdOrCtrl+Shift+S"))),
+    None,
+    Some(("file.preview", "Preview", Some("F5"))),
+    Some(("file.build-settings", "Build Settings...", Some("CmdOrCtrl+Shift+B"))),
+];
+
+const EDIT_ITEMS: Items = &[
+    None,
+    Some(("edit.delete", "Delete", None)),
+    Some(("edit.duplicate", "Duplicate", Some("CmdOrCtrl+D"))),
+    Some(("edit.create-entity", "Create Entity", None)),
+    None,
+    Some(("edit.command-palette", "Command Palette", Some("CmdOrCtrl+K"))),
+];
+
+const VIEW_ITEMS: Items = &[
+    Some(("view.hierarchy", "Hierarchy", Some("CmdOrCtrl+Shift+H"))),
+    Some(("view.inspector", "Inspector", Some("CmdOrCtrl+Shift+I"))),
+    Some(("view.toggle-bottom", "Toggle Bottom Panel", Some("CmdOrCtrl+J"))),
+    Some(("view.content-browser", "Content Browser", None)),
+    Some(("view.output", "Output", None)),
+    Some(("view.game", "Game", None)),
+    Some(("view.profiler", "Profiler", None)),
+    Some(("view.timeline", "Timeline", None)),
+    Some(("view.frame-debugger", "Frame Debugger", None)),
+    None,
+    Some(("view.toggle-sidebars", "Toggle Sidebars", Some("CmdOrCtrl+\\"))),
+    Some(("view.reset-layout", "Reset Layout", None)),
+    None,
+    Some(("view.reload", "Reload", Some("CmdOrCtrl+R"))),
+    Some(("view.devtools", "Developer Tools", Some("F12"))),
+];
+
+const HELP_ITEMS: Items =
+    &[Some(("help.docs", "Documentation", None)), Some(("help.shortcuts", "Keyboard Shortcuts", None))];
+
+fn add_items<'m>(
+    app: &AppHandle,
+    mut menu: SubmenuBuilder<'m, Wry, AppHandle>,
+    items: Items,
+) -> tauri::Result<SubmenuBuilder<'m, Wry, AppHandle>> {
+    for item in items {
+        menu = match item {
+            Some((id, label, accelerator)) => menu.item(&MenuItem::with_id(app, *id, *label, true, *accelerator)?),
+            None => menu.separator(),
+        };
+    }
+    Ok(menu)
+}
+
+fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let name = app.package_info().name.clone();
+    let app_menu = SubmenuBuilder::new(app, &name)
+        .text("help.about", format!("About {}", name))
+        .separator()
+        .item(&MenuItem::with_id(app, "edit.preferences", "Settings...", true, Some("CmdOrCtrl+,"))?)
+        .separator()
+        .services()
+        .separator()
+        .hide()
+        .hide_others()
+        .show_all()
+        .separator()
+        .item(&MenuItem::with_id(app, QUIT, format!("Quit {}", name), true, Some("CmdOrCtrl+Q"))?)
+        .build()?;
+
+    let recent = SubmenuBuilder::with_id(app, RECENT_MENU, "Open Recent").build()?;
+    fill_recent(app, &recent)?;
+    let file = add_items(app, SubmenuBuilder::new(app, "File"), FILE_ITEMS)?.item(&recent);
+    let file = add_items(app, file, FILE_SAVE_ITEMS)?.separator().close_window().build()?;
+
+    // The predefined items keep undo and the clipboard working in text
+    // fields; the editor handles the same shortcuts for the scene.
+    let edit = SubmenuBuilder::new(app, "Edit").undo().redo().separator().cut().copy().paste().select_all();
+    let edit = add_items(app, edit, EDIT_ITEMS)?.build()?;
+    let view = add_items(app, SubmenuBuilder::new(app, "View"), VIEW_ITEMS)?.separator().fullscreen().build()?;
+    let window = SubmenuBuilder::new(app, "Window").minimize().maximize().build()?;
+    let help = add_items(app, SubmenuBuilder::new(app, "Help"), HELP_ITEMS)?.build()?;
+
+    Menu::with_items(app, &[&app_menu, &file, &edit, &view, &window, &help])
+}
+
+/// Installs the menu bar. Only macOS has one outside the window.
+pub fn init(app: &AppHandle) {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+    match build(app) {
+        Ok(menu) => {
+            if let Err(e) = app.set_menu(menu) {
+                eprintln!("[menu] Failed to set the application menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("[menu] Failed to build the application menu: {}", e),
+    }
+}
+
+// =============================================================================
+// Recent projects
+// =============================================================================
+
+fn fill_recent(app: &AppHandle, menu: &Submenu<Wry>) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+    let projects = recent::load(app);
+    for (index, project) in projects.iter().enumerate() {
+        let id = format!("{}{}", RECENT_PREFIX, index);
+        menu.append(&MenuItem::with_id(app, id, &project.name, true, None::<&str>)?)?;
+    }
+    if !projects.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    menu.append(&MenuItem::with_id(app, CLEAR_RECENT, "Clear Recent", !projects.is_empty(), None::<&str>)?)?;
+    Ok(())
+}
+
+/// Rebuilds File > Open Recent from the recent projects list.
+pub fn refresh_recent(app: &AppHandle) {
+    // `Menu::get` only looks at the top level; Open Recent is in File.
+    let recent =
+        app.menu().and_then(|menu| menu.items().ok()?.into_iter().find_map(|item| item.as_submenu()?.get(RECENT_MENU)));
+    let Some(MenuItemKind::Submenu(menu)) = recent else {
+        return;
+    };
+    if let Err(e) = fill_recent(app, &menu) {
+        eprintln!("[menu] Failed to update recent projects: {}", e);
+    }
+}
+
+// =============================================================================
+// Events
+// =============================================================================
+
+pub fn handle_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if context_menu::handle_event(app, id) || tray::handle_event(app, id) {
+        return;
+    }
+    if id == QUIT {
+        // Closing goes through the main window's unsaved-changes prompt.
+        if let Some(window) = app.get_webview_window(MAIN_LABEL) {
+            let _ = window.close();
+        }
+    } else if id == CLEAR_RECENT {
+        if let Err(e) = recent::clear_recent_projects(app.clone(), false) {
+            eprintln!("[menu] Failed to clear recent projects: {}", e);
+        }
+    } else if let Some(index) = id.strip_prefix(RECENT_PREFIX).and_then(|i| i.parse::<usize>().ok()) {
+        if let Some(project) = recent::load(app).into_iter().nth(index) {
+            let _ = app.emit_to(MAIN_LABEL, "menu-open-project", project.path);
+        }
+    } else {
+        let _ = app.emit_to(MAIN_LABEL, "menu-action", id);
+    }
+}
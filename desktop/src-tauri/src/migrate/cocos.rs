@@ -0,0 +1,424 @@
+//! Cocos Creator importer — 2.x (`.fire`) and 3.x (`.scene`) projects
+//!
+//! Scenes and prefabs are arrays of serialized objects linked by `__id__`;
+//! assets are referenced by `__uuid__`, resolved through the `.meta` files.
+//! Sprite frames that cover part of an image (trimmed sprites and `.plist`
+//! atlases) are cropped into their own textures, and 2.x sprite-frame
+//! animation clips become frame animations.
+
+use super::{
+    add_camera, is_texture, slice_atlas, sprite, sprite_animator, text, transform, Migration, Placement,
+    SceneBuilder,
+};
+use crate::export::assets;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Components converted, or deliberately dropped because the new project
+/// provides an equivalent.
+const HANDLED: &[&str] = &[
+    "cc.UITransform",
+    "cc.Sprite",
+    "cc.Label",
+    "cc.Canvas",
+    "cc.Camera",
+    "cc.Animation",
+    "cc.UIOpacity",
+];
+/// `WrapMode` values that repeat.
+const LOOPING_WRAP_MODES: &[i64] = &[2, 22, 38];
+
+pub fn detect(source: &Path) -> bool {
+    let read = |name: &str| {
+        std::fs::read_to_string(source.join(name))
+            .ok()
+            .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+    };
+    // 2.x: `project.json` names the engine; 3.x: `package.json` has a `creator` section.
+    let creator2 = read("project.json")
+        .is_some_and(|v| v["engine"].as_str().is_some_and(|e| e.starts_with("cocos")));
+    let creator3 = read("package.json").is_some_and(|v| v.get("creator").is_some());
+    source.join("assets").is_dir() && (creator2 || creator3)
+}
+
+#[derive(Debug, Clone)]
+struct Texture {
+    uuid: String,
+    size: (f64, f64),
+}
+
+/// A sprite frame: its page image and, for partial frames, the crop rect.
+#[derive(Debug, Clone)]
+struct Frame {
+    image: PathBuf,
+    name: String,
+    rect: Option<[u32; 4]>,
+    rotated: bool,
+}
+
+#[derive(Default)]
+struct CocosImporter {
+    /// Asset UUID -> source file.
+    files: HashMap<String, PathBuf>,
+    /// Sprite frame or texture UUID -> frame.
+    frames: HashMap<String, Frame>,
+    resolved: HashMap<String, Texture>,
+    /// Animation clip UUID -> generated clip path.
+    clips: HashMap<String, String>,
+}
+
+pub fn import(m: &mut Migration) -> Result<(), String> {
+    let root = m.source.join("assets");
+    m.copy_media(&root, "")?;
+
+    let mut importer = CocosImporter::default();
+    importer.index(m, &root);
+    importer.convert_clips(m, &root)?;
+
+    let files: Vec<PathBuf> = assets::walk_files(&root)
+        .into_iter()
+        .filter(|p| matches!(ext(p).as_str(), "fire" | "scene" | "prefab"))
+        .collect();
+    let total = files.len().max(1) as f32;
+    for (i, file) in files.iter().enumerate() {
+        m.progress("scenes", &format!("Converting {}", m.source_rel(file)), 0.5 + 0.4 * (i as f32 / total));
+        importer.convert_file(m, &root, file)?;
+    }
+    m.note_scripts(&root);
+    Ok(())
+}
+
+impl CocosImporter {
+    /// Reads every `.meta`: the asset's own UUID plus sprite-frame sub-assets.
+    fn index(&mut self, m: &mut Migration, root: &Path) {
+        let mut pending = Vec::new();
+        for meta_path in assets::walk_files(root).into_iter().filter(|p| ext(p) == "meta") {
+            let Some(meta) = read_json(&meta_path) else { continue };
+            let file = meta_path.with_extension("");
+            if let Some(uuid) = meta["uuid"].as_str() {
+                self.files.insert(uuid.to_string(), file.clone());
+                if is_texture(&file.to_string_lossy()) {
+                    let name = stem(&file);
+                    self.frames.insert(uuid.to_string(), Frame { image: file.clone(), name, rect: None, rotated: false });
+                }
+            }
+            let Some(subs) = meta["subMetas"].as_object() else { continue };
+            for (name, sub) in subs {
+                let Some(uuid) = sub["uuid"].as_str() else { continue };
+                // 3.x nests frame data in `userData`.
+                let data = sub.get("userData").filter(|d| d.is_object()).unwrap_or(sub);
+                pending.push((uuid.to_string(), name.clone(), data.clone(), file.clone()));
+            }
+        }
+
+        for (uuid, name, data, owner) in pending {
+            let image = match data["rawTextureUuid"].as_str().or_else(|| data["imageUuidOrDatabaseUri"].as_str()) {
+                Some(raw) => match self.files.get(raw.split('@').next().unwrap_or(raw)) {
+                    Some(path) => path.clone(),
+                    None => continue,
+                },
+                None if is_texture(&owner.to_string_lossy()) => owner.clone(),
+                None => continue,
+            };
+            let field = |k: &str| data[k].as_f64().unwrap_or(0.0);
+            let (w, h) = (field("width"), field("height"));
+            let full = w <= 0.0 || (w == field("rawWidth") && h == field("rawHeight") && field("trimX") == 0.0 && field("trimY") == 0.0);
+            let rect = (!full).then(|| [field("trimX") as u32, field("trimY") as u32, w as u32, h as u32]);
+            let rotated = data["rotated"].as_bool().unwrap_or(false);
+            if rotated {
+                let owner_rel = m.source_rel(&owner);
+                m.manual(&owner_rel, format!("Rotated atlas frame '{}' was not cropped", name));
+            }
+            let name = if is_texture(&owner.to_string_lossy()) { stem(&owner) } else { name };
+            self.frames.insert(uuid, Frame { image, name, rect, rotated });
+        }
+    }
+
+    fn texture(&mut self, m: &mut Migration, root: &Path, uuid: &str) -> Option<Texture> {
+        if let Some(texture) = self.resolved.get(uuid) {
+            return Some(texture.clone());
+        }
+        let frame = self.frames.get(uuid)?.clone();
+        let rel = format!("assets/{}", assets::relative_path(root, &frame.image));
+        let texture = match frame.rect.filter(|_| !frame.rotated) {
+            None => {
+                let uuid = m.copy_asset(&frame.image, &rel).ok()?;
+                let size = image::image_dimensions(&frame.image).map(|(w, h)| (w as f64, h as f64)).ok()?;
+                Texture { uuid, size }
+            }
+            Some(rect) => {
+                let dir = rel.rsplit_once('.').map_or(rel.as_str(), |(base, _)| base);
+                let name = frame.name.trim_end_matches(".png");
+                let target = format!("{}/{}.png", dir, name);
+                let uuid = slice_atlas(m, &frame.image, &[(target, rect)]).ok()?.pop().flatten()?;
+                Texture { uuid, size: (rect[2] as f64, rect[3] as f64) }
+            }
+        };
+        self.resolved.insert(uuid.to_string(), texture.clone());
+        Some(texture)
+    }
+
+    /// 2.x clips animating `cc.Sprite.spriteFrame` become frame animations.
+    fn convert_clips(&mut self, m: &mut Migration, root: &Path) -> Result<(), String> {
+        for file in assets::walk_files(root).into_iter().filter(|p| ext(p) == "anim") {
+            let rel = m.source_rel(&file);
+            let Some(clip) = read_json(&file).filter(|c| c.is_object()) else {
+                m.manual(&rel, "Animation clip format not supported; recreate it in the editor");
+                continue;
+            };
+            let keys = clip["curveData"]["comps"]["cc.Sprite"]["spriteFrame"].as_array().cloned().unwrap_or_default();
+            let other_curves = clip["curveData"]["props"].as_object().is_some_and(|p| !p.is_empty());
+            if keys.is_empty() {
+                m.manual(&rel, "Property animation; recreate it as a timeline");
+                continue;
+            }
+            if other_curves {
+                m.manual(&rel, "Only the sprite frame track was converted; property tracks were dropped");
+            }
+
+            let duration = clip["_duration"].as_f64().unwrap_or(0.0);
+            let sample = clip["sample"].as_f64().filter(|s| *s > 0.0).unwrap_or(60.0);
+            let mut frames = Vec::new();
+            for (i, key) in keys.iter().enumerate() {
+                let Some(texture) = key["value"]["__uuid__"].as_str().and_then(|u| self.texture(m, root, u)) else {
+                    continue;
+                };
+                let start = key["frame"].as_f64().unwrap_or(0.0);
+                let end = keys.get(i + 1).and_then(|k| k["frame"].as_f64()).unwrap_or(duration.max(start + 1.0 / sample));
+                frames.push((texture.uuid, Some(end - start)));
+            }
+            if frames.is_empty() {
+                m.manual(&rel, "Sprite frames of this clip could not be resolved");
+                continue;
+            }
+            let looped = LOOPING_WRAP_MODES.contains(&clip["wrapMode"].as_i64().unwrap_or(1));
+            let target = format!("assets/animations/{}.esanim", stem(&file));
+            m.add_animation(&rel, &target, &frames, sample, looped)?;
+            if let Some(uuid) = self.files.iter().find(|(_, p)| **p == file).map(|(u, _)| u.clone()) {
+                self.clips.insert(uuid, target);
+            }
+        }
+        Ok(())
+    }
+
+    fn convert_file(&mut self, m: &mut Migration, root: &Path, file: &Path) -> Result<(), String> {
+        let rel = m.source_rel(file);
+        let Some(objects) = read_json(file).and_then(|v| v.as_array().cloned()) else {
+            m.manual(&rel, "Not a serialized Cocos Creator asset");
+            return Ok(());
+        };
+        let doc = Doc { objects: &objects };
+        let is_prefab = ext(file) == "prefab";
+        let head = doc.objects.first().cloned().unwrap_or(Value::Null);
+        let Some(top) = doc.deref(if is_prefab { &head["data"] } else { &head["scene"] }) else {
+            m.manual(&rel, "Root node not found");
+            return Ok(());
+        };
+
+        let mut state = FileState::default();
+        let mut scene = SceneBuilder::default();
+        let name = top["_name"].as_str().map(String::from).unwrap_or_else(|| stem(file));
+        if is_prefab {
+            let frame = NodeFrame { size: (0.0, 0.0), anchor: (0.5, 0.5) };
+            self.convert_node(m, root, &doc, &mut scene, &mut state, top, None, frame, true);
+        } else {
+            for canvas in doc.all("cc.Canvas") {
+                let res = &canvas["_designResolution"];
+                if let (Some(w), Some(h)) = (res["width"].as_f64(), res["height"].as_f64()) {
+                    m.design = (w, h);
+                }
+            }
+            add_camera(&mut scene, m.design);
+            // Scene children are placed from the bottom-left corner.
+            let frame = NodeFrame { size: m.design, anchor: (0.0, 0.0) };
+            for child in doc.children(top) {
+                self.convert_node(m, root, &doc, &mut scene, &mut state, child, None, frame, false);
+            }
+        }
+
+        for kind in &state.unsupported {
+            m.manual(&rel, format!("{} components were not converted", kind));
+        }
+        if state.scripts > 0 {
+            m.manual(&rel, format!("{} custom script component(s) not converted", state.scripts));
+        }
+
+        let dir = assets::parent_dir(&assets::relative_path(root, file));
+        let base = if is_prefab { "assets/prefabs" } else { "assets/scenes" };
+        let out = if dir.is_empty() { format!("{}/{}", base, stem(file)) } else { format!("{}/{}/{}", base, dir, stem(file)) };
+        if is_prefab {
+            m.add_prefab(&rel, &format!("{}.esprefab", out), scene, &name)
+        } else {
+            m.add_scene(&rel, &format!("{}.esscene", out), scene, &name)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn convert_node(
+        &mut self,
+        m: &mut Migration,
+        root: &Path,
+        doc: &Doc,
+        scene: &mut SceneBuilder,
+        state: &mut FileState,
+        node: &Value,
+        parent: Option<usize>,
+        parent_frame: NodeFrame,
+        is_root: bool,
+    ) {
+        let components: Vec<&Value> = node["_components"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| doc.deref(c))
+            .collect();
+        let component = |kind: &str| components.iter().find(|c| c["__type__"] == kind).copied();
+
+        for c in &components {
+            let kind = c["__type__"].as_str().unwrap_or("");
+            if !kind.contains('.') {
+                state.scripts += 1;
+            } else if !HANDLED.contains(&kind) {
+                state.unsupported.insert(kind.to_string());
+            }
+        }
+
+        // 3.x keeps size and anchor on `cc.UITransform`, 2.x on the node.
+        let layout = component("cc.UITransform").unwrap_or(node);
+        let size = (
+            layout["_contentSize"]["width"].as_f64().unwrap_or(0.0),
+            layout["_contentSize"]["height"].as_f64().unwrap_or(0.0),
+        );
+        let anchor = (
+            layout["_anchorPoint"]["x"].as_f64().unwrap_or(0.5),
+            layout["_anchorPoint"]["y"].as_f64().unwrap_or(0.5),
+        );
+        let (position, rotation, scale) = local_transform(node);
+        let placement = if is_root {
+            Placement { x: 0.0, y: 0.0, rotation: 0.0, scale_x: scale.0, scale_y: scale.1 }
+        } else {
+            Placement {
+                x: position.0 + (0.5 - anchor.0) * size.0 - (0.5 - parent_frame.anchor.0) * parent_frame.size.0,
+                y: position.1 + (0.5 - anchor.1) * size.1 - (0.5 - parent_frame.anchor.1) * parent_frame.size.1,
+                rotation,
+                scale_x: scale.0,
+                scale_y: scale.1,
+            }
+        };
+
+        let opacity = component("cc.UIOpacity")
+            .and_then(|o| o["_opacity"].as_f64())
+            .or_else(|| node["_opacity"].as_f64())
+            .unwrap_or(255.0)
+            / 255.0;
+        let mut out = vec![transform(placement, 0.0)];
+        if let Some(sprite_comp) = component("cc.Sprite") {
+            let texture = sprite_comp["_spriteFrame"]["__uuid__"].as_str().and_then(|u| self.texture(m, root, u));
+            let color = color_of(sprite_comp, node, opacity);
+            let (w, h) = if size.0 > 0.0 { size } else { texture.as_ref().map_or((0.0, 0.0), |t| t.size) };
+            out.push(sprite(texture.as_ref().map(|t| t.uuid.as_str()), w, h, color));
+        }
+        if let Some(label) = component("cc.Label") {
+            let content = label["_string"].as_str().or_else(|| label["_N$string"].as_str()).unwrap_or("");
+            let font_size = label["_fontSize"].as_f64().or_else(|| label["_N$fontSize"].as_f64()).unwrap_or(40.0);
+            let color = color_of(label, node, opacity).unwrap_or([1.0, 1.0, 1.0, 1.0]);
+            out.push(text(content, font_size, color));
+        }
+        if let Some(animation) = component("cc.Animation") {
+            let clip = animation["_defaultClip"]["__uuid__"]
+                .as_str()
+                .or_else(|| animation["_clips"][0]["__uuid__"].as_str())
+                .and_then(|u| self.clips.get(u));
+            match clip {
+                Some(clip) => out.push(sprite_animator(clip)),
+                None => {
+                    state.unsupported.insert("cc.Animation (non sprite-frame clip)".to_string());
+                }
+            }
+        }
+
+        let name = node["_name"].as_str().unwrap_or("Node");
+        let visible = node["_active"].as_bool().unwrap_or(true);
+        let id = scene.add(name, parent, visible, out);
+        let frame = NodeFrame { size, anchor };
+        for child in doc.children(node) {
+            self.convert_node(m, root, doc, scene, state, child, Some(id), frame, false);
+        }
+    }
+}
+
+#[derive(Default)]
+struct FileState {
+    unsupported: BTreeSet<String>,
+    scripts: usize,
+}
+
+/// Size and anchor of a parent node, which child positions are relative to.
+#[derive(Clone, Copy)]
+struct NodeFrame {
+    size: (f64, f64),
+    anchor: (f64, f64),
+}
+
+struct Doc<'a> {
+    objects: &'a [Value],
+}
+
+impl<'a> Doc<'a> {
+    fn deref(&self, reference: &Value) -> Option<&'a Value> {
+        self.objects.get(reference["__id__"].as_u64()? as usize)
+    }
+
+    fn children(&self, node: &Value) -> Vec<&'a Value> {
+        node["_children"].as_array().into_iter().flatten().filter_map(|c| self.deref(c)).collect()
+    }
+
+    fn all(&self, kind: &str) -> impl Iterator<Item = &'a Value> + '_ {
+        let kind = kind.to_string();
+        self.objects.iter().filter(move |o| o["__type__"] == kind.as_str())
+    }
+}
+
+/// Local position, rotation (degrees, counter-clockwise) and scale.
+fn local_transform(node: &Value) -> ((f64, f64), f64, (f64, f64)) {
+    // 2.x: `_trs` typed array [x, y, z, qx, qy, qz, qw, sx, sy, sz].
+    if let Some(trs) = node["_trs"]["array"].as_array() {
+        let v = |i: usize| trs.get(i).and_then(Value::as_f64).unwrap_or(0.0);
+        let angle = 2.0 * v(5).atan2(v(6)).to_degrees();
+        return ((v(0), v(1)), angle, (v(7), v(8)));
+    }
+    // 3.x: `_lpos`, `_lrot` quaternion, `_lscale`; older 2.x: `_position`.
+    let pos = node.get("_lpos").or_else(|| node.get("_position")).cloned().unwrap_or(Value::Null);
+    let rot = &node["_lrot"];
+    let angle = match (rot["z"].as_f64(), rot["w"].as_f64()) {
+        (Some(z), Some(w)) => 2.0 * z.atan2(w).to_degrees(),
+        _ => node["_eulerAngles"]["z"].as_f64().unwrap_or(0.0),
+    };
+    let scale = node.get("_lscale").or_else(|| node.get("_scale")).cloned().unwrap_or(Value::Null);
+    (
+        (pos["x"].as_f64().unwrap_or(0.0), pos["y"].as_f64().unwrap_or(0.0)),
+        angle,
+        (scale["x"].as_f64().unwrap_or(1.0), scale["y"].as_f64().unwrap_or(1.0)),
+    )
+}
+
+/// Tint from the component (3.x) or node (2.x), with 0-255 channels.
+fn color_of(component: &Value, node: &Value, opacity: f64) -> Option<[f64; 4]> {
+    let color = component.get("_color").filter(|c| c.is_object()).or_else(|| node.get("_color"))?;
+    let channel = |k: &str| color[k].as_f64().unwrap_or(255.0) / 255.0;
+    let rgba = [channel("r"), channel("g"), channel("b"), channel("a") * opacity];
+    (rgba != [1.0, 1.0, 1.0, 1.0]).then_some(rgba)
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn ext(path: &Path) -> String {
+    assets::extension_of(&path.to_string_lossy())
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
@@ -0,0 +1,134 @@
+//! Egret importer — `egretProperties.json` projects
+//!
+//! Egret builds its display lists in code, so there are no scenes to convert.
+//! Media under `resource/` is copied, sprite sheets are sliced into frames
+//! and MovieClip data becomes frame animations. EUI skins (`.exml`) and
+//! scripts are reported for manual work.
+
+use super::{num, slice_atlas, Migration};
+use crate::export::assets;
+use serde_json::Value;
+use std::path::Path;
+
+const DEFAULT_FRAME_RATE: f64 = 24.0;
+
+pub fn detect(source: &Path) -> bool {
+    source.join("egretProperties.json").is_file()
+}
+
+pub fn import(m: &mut Migration) -> Result<(), String> {
+    if let Some(design) = design_resolution(&m.source.join("index.html")) {
+        m.design = design;
+    }
+    let root = m.source.join("resource");
+    m.copy_media(&root, "")?;
+
+    let files = assets::walk_files(&root);
+    let total = files.len().max(1) as f32;
+    for (i, file) in files.iter().enumerate() {
+        let rel = m.source_rel(file);
+        m.progress("assets", &format!("Converting {}", rel), 0.5 + 0.4 * (i as f32 / total));
+        match assets::extension_of(&rel).as_str() {
+            "exml" => m.manual(&rel, "EUI skin; rebuild it as a prefab in the editor"),
+            "json" => {
+                let Some(data) = std::fs::read_to_string(file).ok().and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                else {
+                    continue;
+                };
+                if data.get("mc").is_some() {
+                    convert_movie_clips(m, &root, file, &data)?;
+                } else if data["file"].is_string() && data["frames"].is_object() {
+                    convert_sheet(m, &root, file, &data)?;
+                } else if data["groups"].is_array() && data["resources"].is_array() {
+                    m.mapped(&rel, None, "Resource config; assets are addressed by path in ESEngine");
+                }
+            }
+            _ => {}
+        }
+    }
+    m.note_scripts(&m.source.join("src"));
+    Ok(())
+}
+
+/// Reads `data-content-width` / `data-content-height` from the player div.
+fn design_resolution(index: &Path) -> Option<(f64, f64)> {
+    let html = std::fs::read_to_string(index).ok()?;
+    let attribute = |name: &str| {
+        let start = html.find(name)? + name.len();
+        let value = html[start..].trim_start_matches(['=', '"', '\'']);
+        value.split(['"', '\'']).next()?.trim().parse::<f64>().ok()
+    };
+    Some((attribute("data-content-width")?, attribute("data-content-height")?))
+}
+
+/// Slices a TexturePacker-style sheet (`{ file, frames: { name: {x, y, w, h} } }`)
+/// into `assets/<sheet>/<name>.png`.
+fn convert_sheet(m: &mut Migration, root: &Path, file: &Path, data: &Value) -> Result<(), String> {
+    let rel = m.source_rel(file);
+    let page = file.with_file_name(data["file"].as_str().unwrap_or_default());
+    let dir = assets::relative_path(root, file);
+    let dir = dir.strip_suffix(".json").unwrap_or(&dir);
+    let frames = rects(&data["frames"], |name| format!("assets/{}/{}.png", dir, name.trim_end_matches(".png")));
+    let written = slice_atlas(m, &page, &frames)?;
+    let count = written.iter().flatten().count();
+    m.mapped(&rel, Some(&format!("assets/{}/", dir)), format!("Sprite sheet sliced into {} frames", count));
+    if count < frames.len() {
+        m.manual(&rel, format!("{} frame(s) lie outside the sheet image", frames.len() - count));
+    }
+    Ok(())
+}
+
+/// Converts MovieClip data (`{ mc: { clip: { frameRate, frames } }, res }`)
+/// into one clip per entry; each frame shows a region of the paired image.
+fn convert_movie_clips(m: &mut Migration, root: &Path, file: &Path, data: &Value) -> Result<(), String> {
+    let rel = m.source_rel(file);
+    let page = file.with_extension("png");
+    if !page.is_file() {
+        m.manual(&rel, "MovieClip image not found next to its data file");
+        return Ok(());
+    }
+    let dir = assets::relative_path(root, file);
+    let dir = dir.strip_suffix(".json").unwrap_or(&dir).to_string();
+    let regions = rects(&data["res"], |name| format!("assets/{}/{}.png", dir, name));
+    let written = slice_atlas(m, &page, &regions)?;
+    let textures: Vec<(String, String)> = regions
+        .iter()
+        .zip(written)
+        .filter_map(|((path, _), uuid)| Some((assets::file_stem(path), uuid?)))
+        .collect();
+
+    for (name, clip) in data["mc"].as_object().into_iter().flatten() {
+        let fps = clip["frameRate"].as_f64().filter(|f| *f > 0.0).unwrap_or(DEFAULT_FRAME_RATE);
+        let mut frames = Vec::new();
+        for frame in clip["frames"].as_array().into_iter().flatten() {
+            let res = frame["res"].as_str().unwrap_or_default();
+            let Some((_, uuid)) = textures.iter().find(|(n, _)| n == res) else {
+                continue;
+            };
+            let duration = frame["duration"].as_f64().filter(|d| *d > 1.0).map(|d| d / fps);
+            frames.push((uuid.clone(), duration));
+        }
+        if frames.is_empty() {
+            m.manual(&format!("{}#{}", rel, name), "MovieClip has no resolvable frames");
+            continue;
+        }
+        if clip["labels"].as_array().is_some_and(|l| !l.is_empty()) {
+            m.manual(&format!("{}#{}", rel, name), "Frame labels were dropped");
+        }
+        let target = format!("assets/animations/{}.esanim", name);
+        m.add_animation(&format!("{}#{}", rel, name), &target, &frames, fps, true)?;
+    }
+    Ok(())
+}
+
+/// `{ name: { x, y, w, h } }` regions with their destination paths.
+fn rects(map: &Value, target: impl Fn(&str) -> String) -> Vec<(String, [u32; 4])> {
+    map.as_object()
+        .into_iter()
+        .flatten()
+        .map(|(name, r)| {
+            let v = |k: &str| num(&r[k]).unwrap_or(0.0).max(0.0) as u32;
+            (target(name), [v("x"), v("y"), v("w"), v("h")])
+        })
+        .collect()
+}
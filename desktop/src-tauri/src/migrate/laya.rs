@@ -0,0 +1,439 @@
+//! LayaAir importer — 2.x (`laya/pages/*.scene`) and 3.x (`assets/*.ls`) projects
+//!
+//! 2D node trees become entities with sprites and text; published `.atlas`
+//! files are sliced back into frames, and `Animation` nodes playing an atlas
+//! become frame animation clips. 3D content, timeline animations (`.ani`)
+//! and scripts are reported for manual work.
+
+use super::{
+    add_camera, is_texture, num, parse_hex_color, slice_atlas, sprite, sprite_animator, text, transform, Migration,
+    Placement, SceneBuilder,
+};
+use crate::export::assets;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Node types converted without loss beyond their visuals.
+const CONTAINERS: &[&str] = &["Scene", "Scene2D", "View", "Dialog", "Box", "Sprite", "Panel", "HBox", "VBox"];
+const IMAGES: &[&str] = &["Image", "Sprite", "Clip", "Button", "CheckBox", "Radio"];
+const TEXTS: &[&str] = &["Label", "Text", "TextInput", "TextArea"];
+
+pub fn detect(source: &Path) -> bool {
+    source.join("laya").is_dir()
+        || std::fs::read_dir(source).is_ok_and(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .any(|e| e.path().extension().is_some_and(|x| x == "laya"))
+        })
+}
+
+/// Resolved texture: UUID and pixel size.
+#[derive(Debug, Clone)]
+struct Texture {
+    uuid: String,
+    size: (f64, f64),
+}
+
+struct LayaImporter {
+    /// Folders skins are resolved against, in lookup order.
+    roots: Vec<PathBuf>,
+    /// LayaAir 3 asset UUID -> source file.
+    uuids: HashMap<String, PathBuf>,
+    /// Atlas frame (`prefix/name.png`) -> sliced texture.
+    frames: HashMap<String, Texture>,
+    /// Atlas path relative to its root -> frame keys in order.
+    atlases: BTreeMap<String, Vec<String>>,
+    /// Animation source -> generated clip path.
+    clips: HashMap<String, String>,
+}
+
+pub fn import(m: &mut Migration) -> Result<(), String> {
+    let v3 = !m.source.join("laya").is_dir();
+    let roots: Vec<PathBuf> = if v3 {
+        vec![m.source.join("assets")]
+    } else {
+        vec![m.source.join("laya/assets"), m.source.join("bin")]
+    };
+    let mut importer = LayaImporter {
+        roots: roots.iter().filter(|r| r.is_dir()).cloned().collect(),
+        uuids: HashMap::new(),
+        frames: HashMap::new(),
+        atlases: BTreeMap::new(),
+        clips: HashMap::new(),
+    };
+
+    for root in importer.roots.clone() {
+        m.copy_media(&root, "")?;
+        if v3 {
+            importer.index_uuids(&root);
+        }
+    }
+    importer.slice_atlases(m)?;
+
+    let pages: Vec<PathBuf> = if v3 {
+        assets::walk_files(&m.source.join("assets"))
+    } else {
+        assets::walk_files(&m.source.join("laya"))
+    };
+    let pages: Vec<PathBuf> = pages
+        .into_iter()
+        .filter(|p| matches!(ext(p).as_str(), "scene" | "ui" | "prefab" | "ls" | "lh"))
+        .collect();
+    let total = pages.len().max(1) as f32;
+    for (i, page) in pages.iter().enumerate() {
+        m.progress("scenes", &format!("Converting {}", m.source_rel(page)), 0.5 + 0.4 * (i as f32 / total));
+        importer.convert_page(m, page)?;
+    }
+
+    for file in assets::walk_files(&m.source).into_iter().filter(|p| ext(p) == "ani") {
+        let rel = m.source_rel(&file);
+        m.manual(&rel, "Timeline animation; recreate it as a timeline or frame animation");
+    }
+    m.note_scripts(&m.source.join("src"));
+    Ok(())
+}
+
+impl LayaImporter {
+    /// LayaAir 3 `.meta` files carry the UUIDs used by `res://` references.
+    fn index_uuids(&mut self, root: &Path) {
+        for meta in assets::walk_files(root).into_iter().filter(|p| ext(p) == "meta") {
+            let uuid = std::fs::read_to_string(&meta)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v["uuid"].as_str().map(String::from));
+            if let Some(uuid) = uuid {
+                self.uuids.insert(uuid, meta.with_extension(""));
+            }
+        }
+    }
+
+    /// Slices published atlases (`{frames, meta: {image, prefix}}`) into one
+    /// texture per frame, named after the skin path that referenced it.
+    fn slice_atlases(&mut self, m: &mut Migration) -> Result<(), String> {
+        for root in self.roots.clone() {
+            for atlas in assets::walk_files(&root).into_iter().filter(|p| ext(p) == "atlas") {
+                let rel = assets::relative_path(&root, &atlas);
+                let Some(json) = read_json(&atlas) else {
+                    m.manual(&m.source_rel(&atlas), "Atlas is not in LayaAir JSON format");
+                    continue;
+                };
+                let dir = atlas.parent().unwrap_or(&root).to_path_buf();
+                let prefix = json["meta"]["prefix"].as_str().unwrap_or("").to_string();
+                let pages: Vec<PathBuf> = json["meta"]["image"]
+                    .as_str()
+                    .unwrap_or("")
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| dir.join(s))
+                    .collect();
+                let Some(frames) = json["frames"].as_object() else { continue };
+
+                let mut by_page: BTreeMap<usize, Vec<(String, [u32; 4])>> = BTreeMap::new();
+                let mut keys = Vec::new();
+                for (name, frame) in frames {
+                    let key = format!("{}{}", prefix, name);
+                    keys.push(key.clone());
+                    if self.frames.contains_key(&key) || self.existing(&key).is_some() {
+                        continue;
+                    }
+                    let rect = &frame["frame"];
+                    let [x, y, w, h] = ["x", "y", "w", "h"].map(|k| num(&rect[k]).unwrap_or(0.0) as u32);
+                    let page = num(&frame["idx"]).unwrap_or(0.0) as usize;
+                    by_page.entry(page).or_default().push((key, [x, y, w, h]));
+                }
+                for (page, list) in by_page {
+                    let Some(image) = pages.get(page).filter(|p| p.is_file()) else {
+                        m.manual(&m.source_rel(&atlas), format!("Atlas page {} is missing", page));
+                        continue;
+                    };
+                    let targets: Vec<(String, [u32; 4])> =
+                        list.iter().map(|(key, rect)| (format!("assets/{}", key), *rect)).collect();
+                    let uuids = slice_atlas(m, image, &targets)?;
+                    for ((key, [_, _, w, h]), uuid) in list.into_iter().zip(uuids) {
+                        if let Some(uuid) = uuid {
+                            self.frames.insert(key, Texture { uuid, size: (w as f64, h as f64) });
+                        }
+                    }
+                }
+                m.mapped(&m.source_rel(&atlas), None, format!("Atlas sliced into {} frame(s)", keys.len()));
+                self.atlases.insert(rel, keys);
+            }
+        }
+        Ok(())
+    }
+
+    /// Source file for a skin path, searched across the asset roots.
+    fn existing(&self, skin: &str) -> Option<(PathBuf, String)> {
+        let skin = skin.trim_start_matches('/');
+        self.roots.iter().find_map(|root| {
+            let path = root.join(skin);
+            path.is_file().then(|| (path, format!("assets/{}", skin)))
+        })
+    }
+
+    fn texture(&mut self, m: &mut Migration, skin: &Value) -> Option<Texture> {
+        let reference = match skin {
+            Value::String(s) => s.clone(),
+            Value::Object(o) => o.get("_$uuid").and_then(|u| u.as_str()).map(|u| format!("res://{}", u))?,
+            _ => return None,
+        };
+        if reference.is_empty() {
+            return None;
+        }
+        if let Some(frame) = self.frames.get(&reference) {
+            return Some(frame.clone());
+        }
+        let (path, rel) = match reference.strip_prefix("res://") {
+            Some(uuid) => {
+                let path = self.uuids.get(uuid)?.clone();
+                let root = self.roots.iter().find(|r| path.starts_with(r))?;
+                let rel = format!("assets/{}", assets::relative_path(root, &path));
+                (path, rel)
+            }
+            None => self.existing(&reference)?,
+        };
+        if !is_texture(&rel) {
+            return None;
+        }
+        let uuid = m.copy_asset(&path, &rel).ok()?;
+        let size = image::image_dimensions(&path).map(|(w, h)| (w as f64, h as f64)).unwrap_or((0.0, 0.0));
+        let texture = Texture { uuid, size };
+        self.frames.insert(reference, texture.clone());
+        Some(texture)
+    }
+
+    fn convert_page(&mut self, m: &mut Migration, page: &Path) -> Result<(), String> {
+        let rel = m.source_rel(page);
+        let Some(root) = read_json(page) else {
+            m.manual(&rel, "Not a JSON page; older XML pages must be re-saved in LayaAir 2 first");
+            return Ok(());
+        };
+        let node = Node(&root);
+        let name = node.str("name").unwrap_or_else(|| stem(page));
+        let size = (node.num("width").unwrap_or(0.0), node.num("height").unwrap_or(0.0));
+        let is_prefab = matches!(ext(page).as_str(), "prefab" | "lh");
+
+        let mut page_state = PageState::default();
+        let mut scene = SceneBuilder::default();
+        if is_prefab {
+            self.convert_node(m, &mut scene, &mut page_state, node, None, size);
+        } else {
+            if m.design == (960.0, 640.0) && size.0 > 0.0 && size.1 > 0.0 {
+                m.design = size;
+            }
+            let parent = if size.0 > 0.0 { size } else { m.design };
+            add_camera(&mut scene, m.design);
+            for child in node.children() {
+                self.convert_node(m, &mut scene, &mut page_state, child, None, parent);
+            }
+        }
+
+        for kind in &page_state.unsupported {
+            m.manual(&rel, format!("{} nodes were converted as plain entities; rebuild their behavior", kind));
+        }
+        if page_state.scripts > 0 {
+            m.manual(&rel, format!("{} attached script(s) not converted", page_state.scripts));
+        }
+        if page_state.three_d {
+            m.manual(&rel, "3D content is not supported and was skipped");
+        }
+
+        let dir = assets::parent_dir(&rel.replace("laya/pages/", "").replace("assets/", ""));
+        let target_dir = if is_prefab { "assets/prefabs" } else { "assets/scenes" };
+        let out = match dir.is_empty() {
+            true => format!("{}/{}", target_dir, stem(page)),
+            false => format!("{}/{}/{}", target_dir, dir, stem(page)),
+        };
+        if is_prefab {
+            m.add_prefab(&rel, &format!("{}.esprefab", out), scene, &name)
+        } else {
+            m.add_scene(&rel, &format!("{}.esscene", out), scene, &name)
+        }
+    }
+
+    fn convert_node(
+        &mut self,
+        m: &mut Migration,
+        scene: &mut SceneBuilder,
+        page: &mut PageState,
+        node: Node,
+        parent: Option<usize>,
+        parent_size: (f64, f64),
+    ) {
+        let kind = node.kind();
+        match kind.as_str() {
+            "Script" => {
+                page.scripts += 1;
+                return;
+            }
+            "Scene3D" | "Sprite3D" | "Camera" | "DirectionLight" | "MeshSprite3D" => {
+                page.three_d = true;
+                return;
+            }
+            _ => {}
+        }
+        page.scripts += node.0["_$comp"].as_array().map_or(0, Vec::len);
+        if node.0.get("_$prefab").is_some() {
+            page.unsupported.insert("Nested prefab instance".to_string());
+        }
+
+        let skin = node.get("skin").or_else(|| node.get("texture")).cloned();
+        let texture = skin.and_then(|s| self.texture(m, &s));
+        let natural = texture.as_ref().map_or((0.0, 0.0), |t| t.size);
+        let size = (
+            node.num("width").unwrap_or(natural.0),
+            node.num("height").unwrap_or(natural.1),
+        );
+        let alpha = node.num("alpha").unwrap_or(1.0);
+
+        let mut components = vec![transform(node.placement(size, parent_size), 0.0)];
+        if TEXTS.contains(&kind.as_str()) {
+            let color = node.str("color").and_then(|c| parse_hex_color(&c)).unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            components.push(text(
+                &node.str("text").unwrap_or_default(),
+                node.num("fontSize").unwrap_or(12.0),
+                [color[0], color[1], color[2], color[3] * alpha],
+            ));
+        } else if kind == "Animation" {
+            match self.animation(m, &node) {
+                Some((clip, first)) => {
+                    components.push(sprite(Some(&first.uuid), size.0.max(first.size.0), size.1.max(first.size.1), None));
+                    components.push(sprite_animator(&clip));
+                }
+                None => {
+                    page.unsupported.insert("Animation (non-atlas source)".to_string());
+                }
+            }
+        } else if let Some(texture) = &texture {
+            let color = (alpha < 1.0).then_some([1.0, 1.0, 1.0, alpha]);
+            components.push(sprite(Some(&texture.uuid), size.0, size.1, color));
+        }
+        // Interactive controls keep only their visuals.
+        let converted = CONTAINERS.contains(&kind.as_str())
+            || IMAGES.contains(&kind.as_str())
+            || TEXTS.contains(&kind.as_str())
+            || kind == "Animation";
+        if !converted || matches!(kind.as_str(), "Button" | "CheckBox" | "Radio" | "TextInput" | "TextArea") {
+            page.unsupported.insert(kind.clone());
+        }
+
+        let name = node.str("name").or_else(|| node.str("var")).unwrap_or(kind);
+        let visible = node.get("visible").and_then(Value::as_bool).unwrap_or(true);
+        let id = scene.add(&name, parent, visible, components);
+        for child in node.children() {
+            self.convert_node(m, scene, page, child, Some(id), size);
+        }
+    }
+
+    /// Clip for an `Animation` node whose `source` is an atlas or an image list.
+    fn animation(&mut self, m: &mut Migration, node: &Node) -> Option<(String, Texture)> {
+        let source = node.str("source")?;
+        let interval = node.num("interval").filter(|i| *i > 0.0).unwrap_or(50.0);
+        if let Some(clip) = self.clips.get(&source).cloned() {
+            let first = self.first_frame(m, &source)?;
+            return Some((clip, first));
+        }
+
+        let keys: Vec<String> = if source.ends_with(".atlas") {
+            self.atlases.iter().find(|(rel, _)| rel.ends_with(&source))?.1.clone()
+        } else if source.ends_with(".ani") {
+            return None;
+        } else {
+            source.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+        };
+        let frames: Vec<Texture> = keys.iter().filter_map(|k| self.texture(m, &Value::String(k.clone()))).collect();
+        let first = frames.first()?.clone();
+
+        let clip = format!("assets/animations/{}.esanim", assets::file_stem(&source.replace(',', "_")));
+        let uuids: Vec<(String, Option<f64>)> = frames.iter().map(|f| (f.uuid.clone(), None)).collect();
+        m.add_animation(&source, &clip, &uuids, (1000.0 / interval).round(), true).ok()?;
+        self.clips.insert(source, clip.clone());
+        Some((clip, first))
+    }
+
+    fn first_frame(&mut self, m: &mut Migration, source: &str) -> Option<Texture> {
+        let key = match self.atlases.iter().find(|(rel, _)| rel.ends_with(source)) {
+            Some((_, keys)) => keys.first()?.clone(),
+            None => source.split(',').next()?.trim().to_string(),
+        };
+        self.texture(m, &Value::String(key))
+    }
+}
+
+#[derive(Default)]
+struct PageState {
+    unsupported: BTreeSet<String>,
+    scripts: usize,
+    three_d: bool,
+}
+
+/// A page node; 2.x keeps properties under `props` and children under
+/// `child`, 3.x stores them inline and under `_$child`.
+#[derive(Clone, Copy)]
+struct Node<'a>(&'a Value);
+
+impl<'a> Node<'a> {
+    fn kind(&self) -> String {
+        self.0["type"].as_str().or_else(|| self.0["_$type"].as_str()).unwrap_or("Sprite").to_string()
+    }
+
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.0["props"].get(key).or_else(|| self.0.get(key)).filter(|v| !v.is_null())
+    }
+
+    fn num(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(num)
+    }
+
+    fn str(&self, key: &str) -> Option<String> {
+        self.get(key).and_then(Value::as_str).map(String::from)
+    }
+
+    fn children(&self) -> impl Iterator<Item = Node<'a>> {
+        let list = self.0.get("child").or_else(|| self.0.get("_$child"));
+        list.and_then(Value::as_array).into_iter().flatten().map(Node)
+    }
+
+    /// LayaAir positions the pivot relative to the parent's top-left corner
+    /// with y down; relative layout (`left`, `centerX`, ...) takes precedence.
+    fn placement(&self, size: (f64, f64), parent: (f64, f64)) -> Placement {
+        let pivot_x = self.num("pivotX").or_else(|| self.num("anchorX").map(|a| a * size.0)).unwrap_or(0.0);
+        let pivot_y = self.num("pivotY").or_else(|| self.num("anchorY").map(|a| a * size.1)).unwrap_or(0.0);
+        let mut cx = self.num("x").unwrap_or(0.0) - pivot_x + size.0 / 2.0;
+        let mut cy = self.num("y").unwrap_or(0.0) - pivot_y + size.1 / 2.0;
+        if let Some(center) = self.num("centerX") {
+            cx = parent.0 / 2.0 + center;
+        } else if let Some(left) = self.num("left") {
+            cx = left + size.0 / 2.0;
+        } else if let Some(right) = self.num("right") {
+            cx = parent.0 - right - size.0 / 2.0;
+        }
+        if let Some(center) = self.num("centerY") {
+            cy = parent.1 / 2.0 + center;
+        } else if let Some(top) = self.num("top") {
+            cy = top + size.1 / 2.0;
+        } else if let Some(bottom) = self.num("bottom") {
+            cy = parent.1 - bottom - size.1 / 2.0;
+        }
+        Placement {
+            x: cx - parent.0 / 2.0,
+            y: parent.1 / 2.0 - cy,
+            rotation: -self.num("rotation").unwrap_or(0.0),
+            scale_x: self.num("scaleX").unwrap_or(1.0),
+            scale_y: self.num("scaleY").unwrap_or(1.0),
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    std::fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn ext(path: &Path) -> String {
+    assets::extension_of(&path.to_string_lossy())
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
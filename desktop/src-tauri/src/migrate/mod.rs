@@ -0,0 +1,593 @@
+//! Project migration — converts LayaAir, Cocos Creator and Egret projects
+//!
+//! Each importer reads its engine's project layout and writes an ESEngine
+//! project skeleton through `Migration`: assets are copied with generated
+//! `.meta` files, scenes and prefabs are rebuilt as entity hierarchies, and
+//! texture atlases and frame animations are converted where the source format
+//! allows. Everything that could not be mapped is listed in the conversion
+//! report, also saved as `MIGRATION.md` in the new project.
+//!
+//! Asset UUIDs are derived from the destination path, so migrating the same
+//! project again produces the same references.
+
+mod cocos;
+mod egret;
+mod laya;
+
+use crate::export::assets;
+use crate::project;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const ENGINES: &[&str] = &["laya", "cocos", "egret"];
+const REPORT_FILE: &str = "MIGRATION.md";
+
+const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "wav", "m4a"];
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "woff", "woff2", "fnt"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MigrateOptions {
+    /// Root folder of the project to convert.
+    pub source: String,
+    /// Folder for the new project; must be empty or missing.
+    pub destination: String,
+    /// Project name; defaults to the source folder name.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// `laya`, `cocos` or `egret`; detected when omitted.
+    #[serde(default)]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    pub engine: String,
+    pub project_dir: String,
+    pub assets: usize,
+    pub scenes: usize,
+    pub prefabs: usize,
+    pub animations: usize,
+    /// Source items converted automatically.
+    pub mapped: Vec<ReportEntry>,
+    /// Source items that need manual work.
+    pub manual: Vec<ReportEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportEntry {
+    pub source: String,
+    pub target: Option<String>,
+    pub note: String,
+}
+
+#[derive(Clone, Serialize)]
+struct MigrateProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn detect_project_engine(source: String) -> Option<String> {
+    detect(Path::new(&source)).map(String::from)
+}
+
+#[tauri::command]
+pub async fn migrate_project(app: AppHandle, options: MigrateOptions) -> Result<MigrationReport, String> {
+    tokio::task::spawn_blocking(move || migrate(&app, &options))
+        .await
+        .map_err(|e| format!("Migration task failed: {}", e))?
+}
+
+fn migrate(app: &AppHandle, options: &MigrateOptions) -> Result<MigrationReport, String> {
+    let source = PathBuf::from(&options.source);
+    if !source.is_dir() {
+        return Err(format!("Source project not found: {}", source.display()));
+    }
+    let engine = match &options.engine {
+        Some(engine) if ENGINES.contains(&engine.as_str()) => engine.clone(),
+        Some(engine) => return Err(format!("Unknown engine '{}'. Expected one of: {}", engine, ENGINES.join(", "))),
+        None => detect(&source)
+            .ok_or("Could not detect the source engine; expected a LayaAir, Cocos Creator or Egret project")?
+            .to_string(),
+    };
+    let name = options
+        .name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .or_else(|| source.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Migrated Project".to_string());
+
+    let mut migration = Migration::new(app, &source, Path::new(&options.destination), &engine)?;
+    migration.progress("scan", &format!("Reading {} project...", engine), 0.05);
+    match engine.as_str() {
+        "laya" => laya::import(&mut migration)?,
+        "cocos" => cocos::import(&mut migration)?,
+        _ => egret::import(&mut migration)?,
+    }
+    migration.finish(&name)
+}
+
+/// Identifies the engine from marker files in the project root.
+fn detect(source: &Path) -> Option<&'static str> {
+    if laya::detect(source) {
+        Some("laya")
+    } else if cocos::detect(source) {
+        Some("cocos")
+    } else if egret::detect(source) {
+        Some("egret")
+    } else {
+        None
+    }
+}
+
+// =============================================================================
+// Migration
+// =============================================================================
+
+pub(crate) struct Migration<'a> {
+    app: &'a AppHandle,
+    pub source: PathBuf,
+    pub dest: PathBuf,
+    /// Width and height the source project was designed for.
+    pub design: (f64, f64),
+    /// Source file -> destination path of copied assets.
+    copied: BTreeMap<PathBuf, String>,
+    scenes: Vec<String>,
+    report: MigrationReport,
+}
+
+impl<'a> Migration<'a> {
+    fn new(app: &'a AppHandle, source: &Path, dest: &Path, engine: &str) -> Result<Self, String> {
+        if dest.starts_with(source) {
+            return Err("The new project must be outside the source project".to_string());
+        }
+        if dest.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(format!("Destination is not empty: {}", dest.display()));
+        }
+        for dir in ["assets/scenes", "assets/prefabs", "assets/animations", "src", ".esengine"] {
+            std::fs::create_dir_all(dest.join(dir)).map_err(|e| e.to_string())?;
+        }
+        Ok(Self {
+            app,
+            source: source.to_path_buf(),
+            dest: dest.to_path_buf(),
+            design: (960.0, 640.0),
+            copied: BTreeMap::new(),
+            scenes: Vec::new(),
+            report: MigrationReport {
+                engine: engine.to_string(),
+                project_dir: dest.to_string_lossy().to_string(),
+                ..Default::default()
+            },
+        })
+    }
+
+    pub fn progress(&self, stage: &str, message: &str, progress: f32) {
+        let _ = self.app.emit(
+            "migrate-progress",
+            MigrateProgress {
+                stage: stage.to_string(),
+                message: message.to_string(),
+                progress,
+            },
+        );
+    }
+
+    /// Path relative to the source root, for the report.
+    pub fn source_rel(&self, path: &Path) -> String {
+        assets::relative_path(&self.source, path)
+    }
+
+    pub fn mapped(&mut self, source: &str, target: Option<&str>, note: impl Into<String>) {
+        self.report.mapped.push(ReportEntry {
+            source: source.to_string(),
+            target: target.map(String::from),
+            note: note.into(),
+        });
+    }
+
+    pub fn manual(&mut self, source: &str, note: impl Into<String>) {
+        self.report.manual.push(ReportEntry {
+            source: source.to_string(),
+            target: None,
+            note: note.into(),
+        });
+    }
+
+    /// Copies a source file to `rel` in the new project and returns its UUID.
+    /// Copying the same file again returns the existing asset.
+    pub fn copy_asset(&mut self, src: &Path, rel: &str) -> Result<String, String> {
+        if let Some(existing) = self.copied.get(src) {
+            return Ok(asset_uuid(existing));
+        }
+        let data = std::fs::read(src).map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+        let uuid = self.write_asset(rel, &data)?;
+        self.copied.insert(src.to_path_buf(), rel.to_string());
+        self.report.assets += 1;
+        Ok(uuid)
+    }
+
+    /// Writes a generated asset with its `.meta` and returns its UUID.
+    pub fn write_asset(&mut self, rel: &str, data: &[u8]) -> Result<String, String> {
+        let path = self.dest.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", rel, e))?;
+
+        let uuid = asset_uuid(rel);
+        let meta = json!({
+            "uuid": uuid,
+            "version": "2.0",
+            "type": asset_type(rel),
+            "importer": default_importer(rel),
+        });
+        let meta = serde_json::to_vec_pretty(&meta).map_err(|e| e.to_string())?;
+        std::fs::write(format!("{}.meta", path.display()), meta).map_err(|e| e.to_string())?;
+        Ok(uuid)
+    }
+
+    pub fn write_json(&mut self, rel: &str, value: &Value) -> Result<String, String> {
+        let data = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+        self.write_asset(rel, &data)
+    }
+
+    pub fn add_scene(&mut self, source: &str, rel: &str, scene: SceneBuilder, name: &str) -> Result<(), String> {
+        let count = scene.len();
+        self.write_json(rel, &scene.into_scene(name))?;
+        self.scenes.push(rel.to_string());
+        self.report.scenes += 1;
+        self.mapped(source, Some(rel), format!("Scene with {} entities", count));
+        Ok(())
+    }
+
+    pub fn add_prefab(&mut self, source: &str, rel: &str, prefab: SceneBuilder, name: &str) -> Result<(), String> {
+        let count = prefab.len();
+        self.write_json(rel, &prefab.into_prefab(name))?;
+        self.report.prefabs += 1;
+        self.mapped(source, Some(rel), format!("Prefab with {} entities", count));
+        Ok(())
+    }
+
+    /// Writes a frame animation clip; `frames` are texture UUIDs with an
+    /// optional duration in seconds (`1 / fps` when omitted).
+    pub fn add_animation(
+        &mut self,
+        source: &str,
+        rel: &str,
+        frames: &[(String, Option<f64>)],
+        fps: f64,
+        looped: bool,
+    ) -> Result<(), String> {
+        let frames: Vec<Value> = frames
+            .iter()
+            .map(|(texture, duration)| match duration {
+                Some(duration) => json!({ "texture": texture, "duration": round(*duration) }),
+                None => json!({ "texture": texture }),
+            })
+            .collect();
+        let clip = json!({ "fps": fps, "loop": looped, "frames": frames });
+        self.write_json(rel, &clip)?;
+        self.report.animations += 1;
+        self.mapped(source, Some(rel), format!("Frame animation, {} frames", frames.len()));
+        Ok(())
+    }
+
+    /// Copies every texture, audio and font file under `root` into
+    /// `assets/<prefix>`, keeping the folder structure.
+    pub fn copy_media(&mut self, root: &Path, prefix: &str) -> Result<(), String> {
+        let files: Vec<PathBuf> = assets::walk_files(root)
+            .into_iter()
+            .filter(|p| is_media(&p.to_string_lossy()))
+            .collect();
+        let total = files.len().max(1) as f32;
+        for (i, file) in files.iter().enumerate() {
+            let rel = assets::relative_path(root, file);
+            self.copy_asset(file, &format!("assets/{}{}", prefix, rel))?;
+            if i % 32 == 0 {
+                self.progress("assets", &format!("Copying {}", rel), 0.1 + 0.3 * (i as f32 / total));
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists source scripts, which are never converted.
+    pub fn note_scripts(&mut self, root: &Path) {
+        let scripts = assets::walk_files(root)
+            .into_iter()
+            .filter(|p| matches!(assets::extension_of(&p.to_string_lossy()).as_str(), "ts" | "js"))
+            .filter(|p| !p.to_string_lossy().ends_with(".d.ts"))
+            .count();
+        if scripts > 0 {
+            let rel = self.source_rel(root);
+            self.manual(
+                &rel,
+                format!("{} script file(s); game logic must be ported to ESEngine systems in src/", scripts),
+            );
+        }
+    }
+
+    fn finish(mut self, name: &str) -> Result<MigrationReport, String> {
+        self.progress("project", "Writing project files...", 0.95);
+        let default_scene = self.scenes.first().cloned();
+        let project_file = project::write_project_file(&self.dest, name, default_scene.as_deref(), self.design)?;
+        project::write_settings(&self.dest, default_scene.as_deref())?;
+        project::write_common_files(&self.dest)?;
+        project::write_sdk(&self.dest)?;
+        write_file(
+            &self.dest.join("src/main.ts"),
+            format!(
+                "// Migrated from {}. Port the game logic listed in {} as systems\n\
+                 // and register them with addSystemToSchedule from 'esengine'.\n\
+                 export {{}};\n",
+                self.report.engine, REPORT_FILE
+            )
+            .as_bytes(),
+        )?;
+        if self.scenes.is_empty() {
+            self.manual("", "No scenes were converted; create a scene in the editor");
+        }
+        write_file(&self.dest.join(REPORT_FILE), render_report(&self.report).as_bytes())?;
+        project::recent::register(self.app, name, &project_file);
+        self.progress("complete", "Migration complete", 1.0);
+        Ok(self.report)
+    }
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn render_report(report: &MigrationReport) -> String {
+    let mut out = format!(
+        "# Migration report\n\nConverted from **{}**: {} assets, {} scenes, {} prefabs, {} animations.\n",
+        report.engine, report.assets, report.scenes, report.prefabs, report.animations
+    );
+    out.push_str(&format!("\n## Needs manual work ({})\n\n", report.manual.len()));
+    for entry in &report.manual {
+        out.push_str(&format!("- `{}`: {}\n", entry.source, entry.note));
+    }
+    out.push_str(&format!("\n## Converted ({})\n\n", report.mapped.len()));
+    for entry in &report.mapped {
+        match &entry.target {
+            Some(target) => out.push_str(&format!("- `{}` → `{}`: {}\n", entry.source, target, entry.note)),
+            None => out.push_str(&format!("- `{}`: {}\n", entry.source, entry.note)),
+        }
+    }
+    out
+}
+
+// =============================================================================
+// Assets
+// =============================================================================
+
+pub(crate) fn is_texture(path: &str) -> bool {
+    TEXTURE_EXTENSIONS.contains(&assets::extension_of(path).as_str())
+}
+
+fn is_media(path: &str) -> bool {
+    let ext = assets::extension_of(path);
+    TEXTURE_EXTENSIONS.contains(&ext.as_str())
+        || AUDIO_EXTENSIONS.contains(&ext.as_str())
+        || FONT_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// Name-based UUID of a destination path.
+pub(crate) fn asset_uuid(rel: &str) -> String {
+    let hash = Sha256::digest(format!("esengine-migrate:{}", rel).as_bytes());
+    let mut b = [0u8; 16];
+    b.copy_from_slice(&hash[..16]);
+    b[6] = (b[6] & 0x0f) | 0x50;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h: String = b.iter().map(|x| format!("{:02x}", x)).collect();
+    format!("{}-{}-{}-{}-{}", &h[..8], &h[8..12], &h[12..16], &h[16..20], &h[20..])
+}
+
+/// Unknown types are detected by the editor when it scans the project.
+fn asset_type(rel: &str) -> &'static str {
+    let ext = assets::extension_of(rel);
+    match ext.as_str() {
+        "esscene" => "scene",
+        "esprefab" => "prefab",
+        "esanim" => "animclip",
+        _ if TEXTURE_EXTENSIONS.contains(&ext.as_str()) => "texture",
+        _ if AUDIO_EXTENSIONS.contains(&ext.as_str()) => "audio",
+        _ => "unknown",
+    }
+}
+
+fn default_importer(rel: &str) -> Value {
+    match asset_type(rel) {
+        "texture" => json!({
+            "maxSize": 2048,
+            "filterMode": "linear",
+            "wrapMode": "repeat",
+            "premultiplyAlpha": false,
+            "sliceBorder": { "left": 0, "right": 0, "top": 0, "bottom": 0 },
+        }),
+        "audio" => json!({ "sampleRate": 44100, "channels": 2, "quality": 0.8 }),
+        "scene" | "prefab" => json!({ "autoMigrate": true }),
+        _ => json!({}),
+    }
+}
+
+/// Crops `(x, y, w, h)` regions out of an atlas page into separate PNGs.
+/// Returns the UUID of each written frame, `None` for frames out of bounds.
+pub(crate) fn slice_atlas(
+    migration: &mut Migration,
+    page: &Path,
+    frames: &[(String, [u32; 4])],
+) -> Result<Vec<Option<String>>, String> {
+    let image = image::open(page).map_err(|e| format!("Failed to decode {}: {}", page.display(), e))?;
+    let mut uuids = Vec::with_capacity(frames.len());
+    for (rel, [x, y, w, h]) in frames {
+        if *w == 0 || *h == 0 || x + w > image.width() || y + h > image.height() {
+            uuids.push(None);
+            continue;
+        }
+        let mut png = Vec::new();
+        image
+            .crop_imm(*x, *y, *w, *h)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        uuids.push(Some(migration.write_asset(rel, &png)?));
+        migration.report.assets += 1;
+    }
+    Ok(uuids)
+}
+
+// =============================================================================
+// Scene building
+// =============================================================================
+
+/// Collects entities for a scene or prefab; ids are indices.
+#[derive(Default)]
+pub(crate) struct SceneBuilder {
+    entities: Vec<Value>,
+}
+
+impl SceneBuilder {
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn add(&mut self, name: &str, parent: Option<usize>, visible: bool, components: Vec<Value>) -> usize {
+        let id = self.entities.len();
+        if let Some(children) = parent
+            .and_then(|p| self.entities.get_mut(p))
+            .and_then(|e| e["children"].as_array_mut())
+        {
+            children.push(json!(id));
+        }
+        self.entities.push(json!({
+            "id": id,
+            "name": name,
+            "parent": parent,
+            "children": [],
+            "components": components,
+            "visible": visible,
+        }));
+        id
+    }
+
+    fn into_scene(self, name: &str) -> Value {
+        json!({ "version": "1.0", "name": name, "entities": self.entities })
+    }
+
+    fn into_prefab(self, name: &str) -> Value {
+        let entities: Vec<Value> = self
+            .entities
+            .into_iter()
+            .map(|mut e| {
+                if let Some(map) = e.as_object_mut() {
+                    if let Some(id) = map.remove("id") {
+                        map.insert("prefabEntityId".to_string(), id);
+                    }
+                }
+                e
+            })
+            .collect();
+        json!({ "version": "1.0", "name": name, "rootEntityId": 0, "entities": entities })
+    }
+}
+
+/// Node placement in ESEngine terms: centre offset from the parent's centre,
+/// y up, rotation counter-clockwise in degrees.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Placement {
+    pub x: f64,
+    pub y: f64,
+    pub rotation: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+}
+
+pub(crate) fn transform(p: Placement, z: f64) -> Value {
+    let half = p.rotation.to_radians() / 2.0;
+    let mut data = json!({ "position": { "x": round(p.x), "y": round(p.y), "z": z } });
+    if p.rotation != 0.0 {
+        data["rotation"] = json!({ "w": round(half.cos()), "x": 0, "y": 0, "z": round(half.sin()) });
+    }
+    if p.scale_x != 1.0 || p.scale_y != 1.0 {
+        data["scale"] = json!({ "x": round(p.scale_x), "y": round(p.scale_y), "z": 1 });
+    }
+    json!({ "type": "Transform", "data": data })
+}
+
+pub(crate) fn sprite(texture: Option<&str>, width: f64, height: f64, color: Option<[f64; 4]>) -> Value {
+    let mut data = json!({ "size": { "x": round(width), "y": round(height) } });
+    if let Some(texture) = texture {
+        data["texture"] = json!(texture);
+    }
+    if let Some(color) = color {
+        data["color"] = rgba(color);
+    }
+    json!({ "type": "Sprite", "data": data })
+}
+
+pub(crate) fn text(content: &str, font_size: f64, color: [f64; 4]) -> Value {
+    json!({
+        "type": "Text",
+        "data": { "content": content, "fontSize": font_size, "color": rgba(color), "align": 1, "verticalAlign": 1 },
+    })
+}
+
+pub(crate) fn sprite_animator(clip: &str) -> Value {
+    json!({ "type": "SpriteAnimator", "data": { "clip": clip } })
+}
+
+/// Orthographic camera framing the design resolution.
+pub(crate) fn add_camera(scene: &mut SceneBuilder, design: (f64, f64)) {
+    scene.add(
+        "Camera",
+        None,
+        true,
+        vec![
+            json!({ "type": "Transform", "data": { "position": { "x": 0, "y": 0, "z": 10 } } }),
+            json!({ "type": "Camera", "data": { "projectionType": 1, "orthoSize": design.1 / 2.0, "isActive": true, "priority": 0 } }),
+        ],
+    );
+}
+
+fn rgba(c: [f64; 4]) -> Value {
+    json!({ "r": round(c[0]), "g": round(c[1]), "b": round(c[2]), "a": round(c[3]) })
+}
+
+/// `#rgb`, `#rrggbb` or `#rrggbbaa` as 0-1 RGBA.
+pub(crate) fn parse_hex_color(s: &str) -> Option<[f64; 4]> {
+    let hex = s.trim().trim_start_matches('#');
+    let hex = if hex.len() == 3 {
+        hex.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        hex.to_string()
+    };
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|v| v as f64 / 255.0);
+    match hex.len() {
+        6 => Some([channel(0)?, channel(2)?, channel(4)?, 1.0]),
+        8 => Some([channel(0)?, channel(2)?, channel(4)?, channel(6)?]),
+        _ => None,
+    }
+}
+
+fn round(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+/// Reads a number that may be stored as a string (LayaAir props often are).
+pub(crate) fn num(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
@@ -0,0 +1,102 @@
+//! Notifications — telling the user a long operation finished
+//!
+//! Exports, imports and uploads can take minutes, and the user has usually
+//! switched to another app by the time they end. When one finishes while
+//! no editor window has focus, an OS notification reports its success or
+//! failure:
+//!
+//! - builds and imports are followed through the job queue's `job-status`
+//!   events;
+//! - uploads (WeChat, itch.io, deploys) are not jobs and report through
+//!   `upload_finished`.
+//!
+//! Each topic can be turned off in the settings (`notifications.build`,
+//! `notifications.import`, `notifications.upload`). Clicking a notification
+//! brings the editor to the front: macOS activates the app, and on Windows
+//! and Linux the launch it causes is handed to the running editor, which
+//! focuses its main window.
+
+use crate::editor_settings;
+use crate::jobs::{JobCategory, JobInfo, JobStatus};
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// What a notification is about; each has its own setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Build,
+    Import,
+    Upload,
+}
+
+impl Topic {
+    fn for_category(category: JobCategory) -> Option<Self> {
+        match category {
+            JobCategory::Build => Some(Topic::Build),
+            JobCategory::Import => Some(Topic::Import),
+            // Searches and scans finish while the user watches.
+            JobCategory::Search | JobCategory::Thumbnail | JobCategory::Io => None,
+        }
+    }
+
+    fn setting(self) -> &'static str {
+        match self {
+            Topic::Build => "notifications.build",
+            Topic::Import => "notifications.import",
+            Topic::Upload => "notifications.upload",
+        }
+    }
+}
+
+fn enabled(app: &AppHandle, topic: Topic) -> bool {
+    editor_settings::load(app)
+        .ok()
+        .and_then(|settings| settings.values.get(topic.setting()).and_then(|value| value.as_bool()))
+        .unwrap_or(true)
+}
+
+/// Whether the user is looking at the editor, so a notification would only
+/// repeat what the editor shows.
+fn editor_focused(app: &AppHandle) -> bool {
+    app.webview_windows().values().any(|window| window.is_focused().unwrap_or(false))
+}
+
+fn show(app: &AppHandle, topic: Topic, title: &str, body: &str) {
+    if editor_focused(app) || !enabled(app, topic) {
+        return;
+    }
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("[notify] Failed to show notification: {}", e);
+    }
+}
+
+/// Notifies about a finished upload.
+pub fn upload_finished<T>(app: &AppHandle, label: &str, result: &Result<T, String>) {
+    match result {
+        Ok(_) => show(app, Topic::Upload, &format!("{} finished", label), "The upload completed successfully."),
+        Err(e) => show(app, Topic::Upload, &format!("{} failed", label), e),
+    }
+}
+
+fn job_finished(app: &AppHandle, job: &JobInfo) {
+    let Some(topic) = Topic::for_category(job.category) else {
+        return;
+    };
+    match job.status {
+        JobStatus::Done => show(app, topic, &format!("{} finished", job.label), "Completed successfully."),
+        JobStatus::Failed => {
+            let error = job.error.as_deref().unwrap_or("Unknown error");
+            show(app, topic, &format!("{} failed", job.label), error);
+        }
+        // Cancelling is the user's own doing.
+        JobStatus::Queued | JobStatus::Running | JobStatus::Cancelled => {}
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("job-status", move |event| match serde_json::from_str::<JobInfo>(event.payload()) {
+        Ok(job) => job_finished(&handle, &job),
+        Err(e) => eprintln!("[notify] Unexpected job-status payload: {}", e),
+    });
+}
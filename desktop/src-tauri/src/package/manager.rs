@@ -0,0 +1,557 @@
+//! Per-project package manager
+//!
+//! `packages.json` at the project root lists the packages a project wants,
+//! either as a version requirement looked up in a registry or as a local
+//! `.espkg` (`file:../ui-kit.espkg`). Resolving walks the dependencies of
+//! every package, picking the newest version that satisfies all
+//! requirements seen so far; packages already in `packages.lock.json` keep
+//! their locked version unless they are being updated, so a fresh clone
+//! installs exactly what its author had.
+//!
+//! A registry is a URL or local folder holding `<name>/index.json` per
+//! package. Packages install into `assets/packages/<name>` with their
+//! UUIDs intact, so replacing a package with a newer version keeps the
+//! project's references to it valid. Downloads are cached under
+//! `.esengine/packages`.
+
+use super::{install, read_manifest, validate_package_name, PACKAGE_EXTENSION};
+use crate::deploy::hex;
+use crate::files::import::ImportResult;
+use crate::project::write_atomic;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const PACKAGES_FILE: &str = "packages.json";
+pub const LOCK_FILE: &str = "packages.lock.json";
+const LOCKFILE_VERSION: u32 = 1;
+const INSTALL_DIR: &str = "assets/packages";
+const CACHE_DIR: &str = ".esengine/packages";
+const FILE_PREFIX: &str = "file:";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// `packages.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackagesFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Package name -> version requirement or `file:` path.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// `packages.lock.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockFile {
+    pub lockfile_version: u32,
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedPackage {
+    pub version: String,
+    /// Archive URL, or `file:` path relative to the project.
+    pub resolved: String,
+    pub sha256: String,
+    /// Install folder, relative to the project.
+    pub folder: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// `<registry>/<name>/index.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryIndex {
+    versions: BTreeMap<String, RegistryVersion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryVersion {
+    /// Archive URL or path, relative to the index.
+    archive: String,
+    sha256: String,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackagesStatus {
+    pub registry: Option<String>,
+    pub dependencies: BTreeMap<String, String>,
+    pub packages: BTreeMap<String, LockedPackage>,
+    /// Whether `install_packages` has work to do: a dependency that is not
+    /// locked, or a locked package whose folder is missing.
+    pub out_of_sync: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    /// `name@version` of packages installed or replaced.
+    pub installed: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Clone, Serialize)]
+struct PackagesProgress {
+    stage: String,
+    message: String,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_packages(project_dir: String) -> Result<PackagesStatus, String> {
+    let project_dir = Path::new(&project_dir);
+    let file = read_packages(project_dir)?;
+    let lock = read_lock(project_dir)?;
+    let out_of_sync = file.dependencies.keys().any(|name| !lock.contains_key(name))
+        || lock.values().any(|p| !project_dir.join(&p.folder).is_dir());
+    Ok(PackagesStatus {
+        registry: file.registry,
+        dependencies: file.dependencies,
+        packages: lock,
+        out_of_sync,
+    })
+}
+
+#[tauri::command]
+pub fn set_package_registry(project_dir: String, registry: Option<String>) -> Result<(), String> {
+    let project_dir = Path::new(&project_dir);
+    let mut file = read_packages(project_dir)?;
+    file.registry = registry.filter(|r| !r.trim().is_empty());
+    write_packages(project_dir, &file)
+}
+
+/// Adds a package by registry name and version requirement (`^1.2.0`,
+/// default `*`) or by the path of a local `.espkg`, then installs it.
+#[tauri::command]
+pub async fn add_package(
+    app: AppHandle,
+    project_dir: String,
+    name: Option<String>,
+    spec: Option<String>,
+) -> Result<SyncReport, String> {
+    let root = PathBuf::from(&project_dir);
+    let spec = spec.unwrap_or_else(|| "*".to_string());
+    let local = spec.strip_prefix(FILE_PREFIX).unwrap_or(&spec).to_string();
+    let (name, spec) = if spec.starts_with(FILE_PREFIX) || local.ends_with(&format!(".{}", PACKAGE_EXTENSION)) {
+        let path = root.join(&local);
+        let manifest = read_manifest(&path)?;
+        let relative = pathdiff(&path, &root);
+        (manifest.name, format!("{}{}", FILE_PREFIX, relative))
+    } else {
+        let name = name.ok_or("A package name is required")?;
+        semver::VersionReq::parse(&spec).map_err(|e| format!("Invalid version requirement '{}': {}", spec, e))?;
+        (name, spec)
+    };
+    validate_package_name(&name)?;
+
+    let mut file = read_packages(&root)?;
+    file.dependencies.insert(name.clone(), spec);
+    let report = sync(&app, &root, &file, Some(std::slice::from_ref(&name))).await?;
+    write_packages(&root, &file)?;
+    Ok(report)
+}
+
+/// Removes a package and any dependencies nothing else needs.
+#[tauri::command]
+pub async fn remove_package(app: AppHandle, project_dir: String, name: String) -> Result<SyncReport, String> {
+    let root = PathBuf::from(&project_dir);
+    let mut file = read_packages(&root)?;
+    if file.dependencies.remove(&name).is_none() {
+        return Err(format!("'{}' is not a dependency of this project", name));
+    }
+    let report = sync(&app, &root, &file, None).await?;
+    write_packages(&root, &file)?;
+    Ok(report)
+}
+
+/// Re-resolves `names` (every package when empty) to the newest versions
+/// their requirements allow.
+#[tauri::command]
+pub async fn update_packages(
+    app: AppHandle,
+    project_dir: String,
+    names: Option<Vec<String>>,
+) -> Result<SyncReport, String> {
+    let root = PathBuf::from(&project_dir);
+    let file = read_packages(&root)?;
+    let names = names
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| read_lock(&root).map(|l| l.into_keys().collect()).unwrap_or_default());
+    sync(&app, &root, &file, Some(names.as_slice())).await
+}
+
+/// Installs what `packages.json` and the lockfile describe, e.g. after a
+/// fresh clone.
+#[tauri::command]
+pub async fn install_packages(app: AppHandle, project_dir: String) -> Result<SyncReport, String> {
+    let root = PathBuf::from(&project_dir);
+    let file = read_packages(&root)?;
+    sync(&app, &root, &file, None).await
+}
+
+// =============================================================================
+// Sync
+// =============================================================================
+
+/// Resolves, downloads, installs, removes orphans and writes the lockfile.
+/// Packages named in `update` ignore their locked version.
+async fn sync(
+    app: &AppHandle,
+    project_dir: &Path,
+    file: &PackagesFile,
+    update: Option<&[String]>,
+) -> Result<SyncReport, String> {
+    let locked = read_lock(project_dir)?;
+    emit_progress(app, "resolve", "Resolving packages...");
+    let mut resolver = Resolver {
+        project_dir,
+        registry: file.registry.clone(),
+        locked: &locked,
+        update: update.map(|names| names.iter().cloned().collect()).unwrap_or_default(),
+        indexes: HashMap::new(),
+        resolved: BTreeMap::new(),
+        required_by: HashMap::new(),
+    };
+    for (name, spec) in &file.dependencies {
+        resolver.require(name, spec, "packages.json").await?;
+    }
+    let resolved = resolver.resolved;
+
+    // Fetch archives first, so a failed download changes nothing.
+    let mut archives = Vec::new();
+    for (name, package) in &resolved {
+        let current = locked.get(name) == Some(package) && project_dir.join(&package.folder).is_dir();
+        if !current {
+            emit_progress(app, "download", &format!("Fetching {}@{}...", name, package.version));
+            archives.push((name.clone(), fetch(project_dir, name, package).await?));
+        }
+    }
+
+    let project = project_dir.to_path_buf();
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut report = SyncReport::default();
+        for (name, package) in &locked {
+            if !resolved.contains_key(name) {
+                remove_folder(&project, &package.folder)?;
+                report.removed.push(format!("{}@{}", name, package.version));
+            }
+        }
+        for (name, package) in &resolved {
+            let Some((_, archive)) = archives.iter().find(|(n, _)| n == name) else {
+                report.unchanged.push(format!("{}@{}", name, package.version));
+                continue;
+            };
+            emit_progress(&app, "install", &format!("Installing {}@{}...", name, package.version));
+            if let Some(old) = locked.get(name) {
+                remove_folder(&project, &old.folder)?;
+            }
+            remove_folder(&project, &package.folder)?;
+            let installed = install(&project, archive, &package.folder)?;
+            if let Some(failure) = installed.failed.first() {
+                return Err(format!("Failed to install {}: {}: {}", name, failure.path, failure.error));
+            }
+            let _ = app.emit(
+                "assets-imported",
+                ImportResult {
+                    project_dir: project.to_string_lossy().to_string(),
+                    folder: installed.folder.clone(),
+                    imported: vec![installed.folder.clone()],
+                    files: installed.files,
+                    failed: Vec::new(),
+                },
+            );
+            report.installed.push(format!("{}@{}", name, package.version));
+        }
+        write_lock(&project, &resolved)?;
+        report.packages = resolved;
+        emit_progress(&app, "done", "Packages are up to date");
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Package task failed: {}", e))?
+}
+
+// =============================================================================
+// Resolution
+// =============================================================================
+
+struct Resolver<'a> {
+    project_dir: &'a Path,
+    registry: Option<String>,
+    locked: &'a BTreeMap<String, LockedPackage>,
+    update: BTreeSet<String>,
+    indexes: HashMap<String, (String, RegistryIndex)>,
+    resolved: BTreeMap<String, LockedPackage>,
+    /// Name -> (requirement, who asked), for conflict messages.
+    required_by: HashMap<String, Vec<(String, String)>>,
+}
+
+impl Resolver<'_> {
+    /// Resolves `name` against `spec` and then its own dependencies. A
+    /// package already resolved must satisfy every later requirement; there
+    /// is no backtracking, so conflicts are reported for the user to settle
+    /// in `packages.json`.
+    async fn require(&mut self, name: &str, spec: &str, by: &str) -> Result<(), String> {
+        let mut pending = vec![(name.to_string(), spec.to_string(), by.to_string())];
+        while let Some((name, spec, by)) = pending.pop() {
+            self.required_by
+                .entry(name.clone())
+                .or_default()
+                .push((spec.clone(), by.clone()));
+
+            if let Some(local) = spec.strip_prefix(FILE_PREFIX) {
+                let package = self.resolve_local(&name, local)?;
+                if let Some(existing) = self.resolved.get(&name) {
+                    if existing.resolved != package.resolved {
+                        return Err(self.conflict(&name));
+                    }
+                    continue;
+                }
+                pending.extend(package.dependencies.iter().map(|(n, s)| (n.clone(), s.clone(), name.clone())));
+                self.resolved.insert(name, package);
+                continue;
+            }
+
+            let requirement = semver::VersionReq::parse(&spec)
+                .map_err(|e| format!("Invalid version requirement for '{}' in {}: {}", name, by, e))?;
+            if let Some(existing) = self.resolved.get(&name) {
+                if !semver::Version::parse(&existing.version).is_ok_and(|v| requirement.matches(&v)) {
+                    return Err(self.conflict(&name));
+                }
+                continue;
+            }
+
+            let locked = self.locked.get(&name).filter(|p| {
+                !self.update.contains(&name)
+                    && !p.resolved.starts_with(FILE_PREFIX)
+                    && semver::Version::parse(&p.version).is_ok_and(|v| requirement.matches(&v))
+            });
+            let package = match locked {
+                Some(package) => package.clone(),
+                None => self.resolve_registry(&name, &requirement).await?,
+            };
+            pending.extend(package.dependencies.iter().map(|(n, s)| (n.clone(), s.clone(), name.clone())));
+            self.resolved.insert(name, package);
+        }
+        Ok(())
+    }
+
+    fn resolve_local(&self, name: &str, path: &str) -> Result<LockedPackage, String> {
+        let full = self.project_dir.join(path);
+        let manifest = read_manifest(&full)?;
+        if manifest.name != name {
+            return Err(format!("{} contains '{}', not '{}'", path, manifest.name, name));
+        }
+        let data = std::fs::read(&full).map_err(|e| format!("Failed to read {}: {}", full.display(), e))?;
+        Ok(LockedPackage {
+            version: manifest.version,
+            resolved: format!("{}{}", FILE_PREFIX, path),
+            sha256: hex(&Sha256::digest(&data)),
+            folder: install_folder(name),
+            dependencies: manifest.dependencies,
+        })
+    }
+
+    async fn resolve_registry(&mut self, name: &str, requirement: &semver::VersionReq) -> Result<LockedPackage, String> {
+        let registry = self
+            .registry
+            .clone()
+            .ok_or_else(|| format!("'{}' needs a registry; set one in {}", name, PACKAGES_FILE))?;
+        if !self.indexes.contains_key(name) {
+            let location = join_location(&format!("{}/", registry.trim_end_matches('/')), &format!("{}/index.json", name))?;
+            let data = read_location(&location).await?;
+            let index: RegistryIndex = serde_json::from_slice(&data)
+                .map_err(|e| format!("Invalid registry index for '{}': {}", name, e))?;
+            self.indexes.insert(name.to_string(), (location, index));
+        }
+        let (location, index) = &self.indexes[name];
+        let (version, entry) = index
+            .versions
+            .iter()
+            .filter(|(_, entry)| !entry.yanked)
+            .filter_map(|(version, entry)| Some((semver::Version::parse(version).ok()?, entry)))
+            .filter(|(version, _)| requirement.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .ok_or_else(|| format!("No version of '{}' matches {}", name, requirement))?;
+        Ok(LockedPackage {
+            version: version.to_string(),
+            resolved: join_location(location, &entry.archive)?,
+            sha256: entry.sha256.to_lowercase(),
+            folder: install_folder(name),
+            dependencies: entry.dependencies.clone(),
+        })
+    }
+
+    fn conflict(&self, name: &str) -> String {
+        let requirements = self.required_by[name]
+            .iter()
+            .map(|(spec, by)| format!("{} requires {}", by, spec))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Conflicting requirements for '{}': {}", name, requirements)
+    }
+}
+
+// =============================================================================
+// Fetching
+// =============================================================================
+
+/// Returns the path of a verified archive for `package`, downloading it
+/// into the cache if needed.
+async fn fetch(project_dir: &Path, name: &str, package: &LockedPackage) -> Result<PathBuf, String> {
+    let path = match package.resolved.strip_prefix(FILE_PREFIX) {
+        Some(local) => project_dir.join(local),
+        None => {
+            let cached = project_dir
+                .join(CACHE_DIR)
+                .join(format!("{}-{}.{}", name.replace('/', "+"), package.version, PACKAGE_EXTENSION));
+            if !cached.is_file() || file_sha256(&cached).as_deref() != Some(package.sha256.as_str()) {
+                let data = read_location(&package.resolved).await?;
+                if let Some(parent) = cached.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                write_atomic(&cached, &data)?;
+            }
+            cached
+        }
+    };
+    if file_sha256(&path).as_deref() != Some(package.sha256.as_str()) {
+        return Err(format!(
+            "Checksum mismatch for {}@{} ({}); the lockfile or registry is out of date",
+            name, package.version, package.resolved
+        ));
+    }
+    Ok(path)
+}
+
+/// Reads a URL or local path.
+pub(crate) async fn read_location(location: &str) -> Result<Vec<u8>, String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let response = reqwest::get(location)
+            .await
+            .map_err(|e| format!("Download failed: {}: {}", location, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed: {}: HTTP {}", location, response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| format!("Download interrupted: {}", e))?;
+        Ok(bytes.to_vec())
+    } else {
+        std::fs::read(location).map_err(|e| format!("Failed to read {}: {}", location, e))
+    }
+}
+
+/// Resolves `reference` against a URL or local path.
+pub(crate) fn join_location(base: &str, reference: &str) -> Result<String, String> {
+    if reference.contains("://") {
+        return Ok(reference.to_string());
+    }
+    if base.starts_with("http://") || base.starts_with("https://") {
+        let base = url::Url::parse(base).map_err(|e| format!("Invalid registry URL '{}': {}", base, e))?;
+        let joined = base.join(reference).map_err(|e| e.to_string())?;
+        return Ok(joined.to_string());
+    }
+    let base = Path::new(base);
+    let dir = if base.to_string_lossy().ends_with('/') { base } else { base.parent().unwrap_or(base) };
+    Ok(dir.join(reference).to_string_lossy().to_string())
+}
+
+fn file_sha256(path: &Path) -> Option<String> {
+    std::fs::read(path).ok().map(|data| hex(&Sha256::digest(&data)))
+}
+
+// =============================================================================
+// Files
+// =============================================================================
+
+fn read_packages(project_dir: &Path) -> Result<PackagesFile, String> {
+    let path = project_dir.join(PACKAGES_FILE);
+    if !path.exists() {
+        return Ok(PackagesFile::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", PACKAGES_FILE, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", PACKAGES_FILE, e))
+}
+
+fn write_packages(project_dir: &Path, file: &PackagesFile) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(file).map_err(|e| e.to_string())?;
+    write_atomic(&project_dir.join(PACKAGES_FILE), &json)
+}
+
+fn read_lock(project_dir: &Path) -> Result<BTreeMap<String, LockedPackage>, String> {
+    let path = project_dir.join(LOCK_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", LOCK_FILE, e))?;
+    let lock: LockFile = serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", LOCK_FILE, e))?;
+    if lock.lockfile_version > LOCKFILE_VERSION {
+        return Err(format!("{} was written by a newer editor", LOCK_FILE));
+    }
+    Ok(lock.packages)
+}
+
+fn write_lock(project_dir: &Path, packages: &BTreeMap<String, LockedPackage>) -> Result<(), String> {
+    let lock = LockFile {
+        lockfile_version: LOCKFILE_VERSION,
+        packages: packages.clone(),
+    };
+    let json = serde_json::to_vec_pretty(&lock).map_err(|e| e.to_string())?;
+    write_atomic(&project_dir.join(LOCK_FILE), &json)
+}
+
+fn install_folder(name: &str) -> String {
+    format!("{}/{}", INSTALL_DIR, name)
+}
+
+/// Removes an installed package folder and its sidecar; only folders under
+/// `assets/packages` are ever touched.
+fn remove_folder(project_dir: &Path, folder: &str) -> Result<(), String> {
+    if !folder.starts_with(&format!("{}/", INSTALL_DIR)) || folder.split('/').any(|p| p == "..") {
+        return Err(format!("Refusing to remove {}: not a package folder", folder));
+    }
+    let path = project_dir.join(folder);
+    if path.is_dir() {
+        std::fs::remove_dir_all(&path).map_err(|e| format!("Failed to remove {}: {}", folder, e))?;
+    }
+    let _ = std::fs::remove_file(project_dir.join(format!("{}.meta", folder)));
+    Ok(())
+}
+
+/// `path` relative to `base` with forward slashes, using `..` as needed.
+fn pathdiff(path: &Path, base: &Path) -> String {
+    let path: Vec<_> = path.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); base.len() - common];
+    parts.extend(path[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()));
+    parts.join("/")
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str) {
+    let _ = app.emit(
+        "packages-progress",
+        PackagesProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+        },
+    );
+}
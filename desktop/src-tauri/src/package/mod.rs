@@ -0,0 +1,444 @@
+//! `.espkg` packages: sharing folders of assets between projects
+//!
+//! A package is a zip holding `espkg.json` and the packaged folder under
+//! `files/`, `.meta` sidecars included, so prefabs keep referencing their
+//! textures by UUID. Installing keeps those UUIDs unless the project already
+//! uses one for a different asset; colliding UUIDs get fresh ones and every
+//! JSON asset in the package is rewritten to match. Path references into the
+//! package are moved to the folder it is installed in. Per-project package
+//! dependencies, registries and the lockfile are in `manager`.
+
+pub mod manager;
+
+use crate::deploy::hex;
+use crate::export::assets::{
+    collect_json_refs, collect_references, is_json_asset, relative_path, remap_paths, walk_files,
+    AssetDatabase,
+};
+use crate::files::folders::new_uuid;
+use crate::files::import::{unique_destination, ImportFailure, ImportResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const MANIFEST_FILE: &str = "espkg.json";
+pub const PACKAGE_EXTENSION: &str = "espkg";
+const FORMAT_VERSION: u32 = 1;
+const FILES_DIR: &str = "files/";
+const SKIPPED_FILES: &[&str] = &[".DS_Store", "Thumbs.db", "desktop.ini"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// `espkg.json`: what a package is and what it contains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageManifest {
+    pub format_version: u32,
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// Engine version the package was made with.
+    pub engine: String,
+    pub created: String,
+    /// Name of the packaged folder; the default install folder.
+    pub root: String,
+    /// Project-relative folder the package was made from, for moving path
+    /// references to the install folder.
+    pub source: String,
+    /// Other packages this one needs, name -> version requirement.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+    pub files: Vec<PackageFile>,
+    /// References to assets outside the package, which the installing
+    /// project has to provide.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFile {
+    /// Path relative to the packaged folder.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportPackageOptions {
+    pub project_dir: String,
+    /// Folder to package, relative to the project.
+    pub folder: String,
+    /// Output `.espkg` path.
+    pub output: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedPackage {
+    pub path: String,
+    pub files: usize,
+    pub size: u64,
+    pub sha256: String,
+    /// Referenced assets left out of the package.
+    pub external_refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstalledPackage {
+    pub name: String,
+    pub version: String,
+    /// Install folder, relative to the project.
+    pub folder: String,
+    pub files: Vec<String>,
+    /// UUIDs that collided with the project, old -> new.
+    pub remapped: BTreeMap<String, String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Packages a project folder into an `.espkg` archive.
+#[tauri::command]
+pub async fn export_package(options: ExportPackageOptions) -> Result<ExportedPackage, String> {
+    tokio::task::spawn_blocking(move || export(&options))
+        .await
+        .map_err(|e| format!("Package task failed: {}", e))?
+}
+
+/// Reads the manifest of an `.espkg` without installing it.
+#[tauri::command]
+pub async fn read_package_manifest(path: String) -> Result<PackageManifest, String> {
+    tokio::task::spawn_blocking(move || read_manifest(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Package task failed: {}", e))?
+}
+
+/// Installs an `.espkg` into `folder` of the project (default: a new folder
+/// named after the package root under `assets`).
+#[tauri::command]
+pub async fn import_package(
+    app: AppHandle,
+    project_dir: String,
+    path: String,
+    folder: Option<String>,
+) -> Result<InstalledPackage, String> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = PathBuf::from(project_dir);
+        let manifest = read_manifest(Path::new(&path))?;
+        let folder = match folder {
+            Some(folder) => sanitize_folder(&folder)?,
+            None => relative_path(&project_dir, &unique_destination(&project_dir.join("assets").join(&manifest.root))),
+        };
+        let installed = install(&project_dir, Path::new(&path), &folder)?;
+        let _ = app.emit(
+            "assets-imported",
+            ImportResult {
+                project_dir: project_dir.to_string_lossy().to_string(),
+                folder: installed.folder.clone(),
+                imported: vec![installed.folder.clone()],
+                files: installed.files.clone(),
+                failed: installed.failed.clone(),
+            },
+        );
+        Ok(installed)
+    })
+    .await
+    .map_err(|e| format!("Package task failed: {}", e))?
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+fn export(options: &ExportPackageOptions) -> Result<ExportedPackage, String> {
+    validate_package_name(&options.name)?;
+    semver::Version::parse(&options.version)
+        .map_err(|e| format!("Invalid package version '{}': {}", options.version, e))?;
+    for (name, requirement) in &options.dependencies {
+        semver::VersionReq::parse(requirement)
+            .map_err(|e| format!("Invalid version requirement for '{}': {}", name, e))?;
+    }
+    let project_dir = Path::new(&options.project_dir);
+    let folder = sanitize_folder(&options.folder)?;
+    let root = project_dir.join(&folder);
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", folder));
+    }
+
+    let files: Vec<PathBuf> = walk_files(&root)
+        .into_iter()
+        .filter(|f| {
+            let name = f.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            !SKIPPED_FILES.contains(&name.as_str())
+        })
+        .collect();
+    let db = AssetDatabase::scan(project_dir);
+    let mut entries = Vec::new();
+    for file in &files {
+        let data = std::fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let project_rel = relative_path(project_dir, file);
+        entries.push(PackageFile {
+            path: relative_path(&root, file),
+            uuid: db.uuid_of(&project_rel).map(String::from),
+            size: data.len() as u64,
+            sha256: hex(&Sha256::digest(&data)),
+        });
+    }
+
+    let prefix = format!("{}/", folder);
+    let asset_roots: Vec<String> = files
+        .iter()
+        .map(|f| relative_path(project_dir, f))
+        .filter(|rel| !rel.ends_with(".meta"))
+        .collect();
+    let external_refs: Vec<String> = collect_references(project_dir, &db, &asset_roots)
+        .into_iter()
+        .filter(|rel| !rel.starts_with(&prefix))
+        .collect();
+
+    let manifest = PackageManifest {
+        format_version: FORMAT_VERSION,
+        name: options.name.clone(),
+        version: options.version.clone(),
+        description: options.description.clone(),
+        author: options.author.clone(),
+        engine: env!("CARGO_PKG_VERSION").to_string(),
+        created: crate::project::now_iso8601(),
+        root: root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        source: folder.clone(),
+        dependencies: options.dependencies.clone(),
+        files: entries,
+        external_refs: external_refs.clone(),
+    };
+
+    let mut output = PathBuf::from(&options.output);
+    if output.extension().is_none() {
+        output.set_extension(PACKAGE_EXTENSION);
+    }
+    if output.starts_with(&root) {
+        return Err("The package cannot be written inside the packaged folder".to_string());
+    }
+    write_archive(&root, &files, &manifest, &output)?;
+    let data = std::fs::read(&output).map_err(|e| e.to_string())?;
+    Ok(ExportedPackage {
+        path: output.to_string_lossy().to_string(),
+        files: files.len(),
+        size: data.len() as u64,
+        sha256: hex(&Sha256::digest(&data)),
+        external_refs,
+    })
+}
+
+fn write_archive(root: &Path, files: &[PathBuf], manifest: &PackageManifest, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut writer = zip::ZipWriter::new(BufWriter::new(file));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default());
+
+    let json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    writer
+        .start_file(MANIFEST_FILE, options)
+        .and_then(|()| writer.write_all(&json).map_err(Into::into))
+        .map_err(|e| format!("Failed to write package: {}", e))?;
+    for path in files {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        writer
+            .start_file(
+                format!("{}{}", FILES_DIR, relative_path(root, path)),
+                options.large_file(size >= u32::MAX as u64),
+            )
+            .map_err(|e| format!("Failed to write package: {}", e))?;
+        let mut source = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        std::io::copy(&mut source, &mut writer).map_err(|e| format!("Failed to write package: {}", e))?;
+    }
+    writer
+        .finish()
+        .and_then(|mut w| w.flush().map_err(Into::into))
+        .map_err(|e| format!("Failed to write package: {}", e))
+}
+
+// =============================================================================
+// Install
+// =============================================================================
+
+pub(crate) fn read_manifest(path: &Path) -> Result<PackageManifest, String> {
+    let mut archive = open_archive(path)?;
+    let mut text = String::new();
+    archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| format!("Not a package (no {}): {}", MANIFEST_FILE, path.display()))?
+        .read_to_string(&mut text)
+        .map_err(|e| e.to_string())?;
+    let manifest: PackageManifest =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Package '{}' needs a newer editor (format {})",
+            manifest.name, manifest.format_version
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Whether `path` is relative and names only plain folders and files: no
+/// root, drive, `.` or `..`.
+fn is_plain_relative(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Extracts the package at `path` into `folder` (relative to the project),
+/// which must not exist yet.
+pub(crate) fn install(project_dir: &Path, path: &Path, folder: &str) -> Result<InstalledPackage, String> {
+    let manifest = read_manifest(path)?;
+    if !is_plain_relative(folder) {
+        return Err(format!("Install folder must be inside the project: {}", folder));
+    }
+    let target = project_dir.join(folder);
+    if target.exists() {
+        return Err(format!("Install folder already exists: {}", folder));
+    }
+
+    // Read and verify everything before writing anything.
+    let mut archive = open_archive(path)?;
+    let mut contents = Vec::new();
+    for entry in &manifest.files {
+        if !is_plain_relative(&entry.path) {
+            return Err(format!("Package contains an unsafe path: {}", entry.path));
+        }
+        let rel = entry.path.clone();
+        let mut data = Vec::new();
+        archive
+            .by_name(&format!("{}{}", FILES_DIR, entry.path))
+            .map_err(|_| format!("Package is missing {}", entry.path))?
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+        if hex(&Sha256::digest(&data)) != entry.sha256 {
+            return Err(format!("Package is corrupt: checksum mismatch for {}", entry.path));
+        }
+        contents.push((rel, data));
+    }
+
+    // UUIDs already used by other assets of the project get fresh ones.
+    let db = AssetDatabase::scan(project_dir);
+    let mut remap: HashMap<String, String> = HashMap::new();
+    let mut remapped = BTreeMap::new();
+    for uuid in manifest.files.iter().filter_map(|f| f.uuid.as_ref()) {
+        if db.path_of(uuid).is_some() {
+            let new = new_uuid(uuid);
+            remapped.insert(uuid.clone(), new.clone());
+            remap.insert(uuid.clone(), new);
+        }
+    }
+    for (rel, _) in &contents {
+        if !rel.ends_with(".meta") {
+            remap.insert(format!("{}/{}", manifest.source, rel), format!("{}/{}", folder, rel));
+        }
+    }
+
+    let mut installed = InstalledPackage {
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        folder: folder.to_string(),
+        remapped,
+        ..InstalledPackage::default()
+    };
+    for (rel, data) in contents {
+        let destination = target.join(&rel);
+        let data = if rel.ends_with(".meta") || is_json_asset(&rel) {
+            rewrite(&data, &remap).unwrap_or(data)
+        } else {
+            data
+        };
+        let written = destination
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&destination, &data));
+        match written {
+            Ok(()) => installed.files.push(format!("{}/{}", folder, rel)),
+            Err(e) => installed.failed.push(ImportFailure {
+                path: rel,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(installed)
+}
+
+/// Applies `remap` to a JSON file, sidecars included; `None` when nothing
+/// changed or it is not JSON.
+fn rewrite(data: &[u8], remap: &HashMap<String, String>) -> Option<Vec<u8>> {
+    let mut value = serde_json::from_slice::<Value>(data).ok()?;
+    let mut refs = Vec::new();
+    collect_json_refs(&value, &mut refs);
+    if !refs.iter().any(|r| remap.contains_key(r)) {
+        return None;
+    }
+    remap_paths(&mut value, remap);
+    serde_json::to_vec_pretty(&value).ok()
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn open_archive(path: &Path) -> Result<zip::ZipArchive<File>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("Not a package: {}: {}", path.display(), e))
+}
+
+/// Package names: lowercase letters, digits, `-`, `_` and `.`, optionally
+/// scoped as `@team/name`.
+pub(crate) fn validate_package_name(name: &str) -> Result<(), String> {
+    let parts: Vec<&str> = match name.strip_prefix('@') {
+        Some(scoped) => scoped.splitn(2, '/').collect(),
+        None => vec![name],
+    };
+    let valid = |part: &&str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'))
+    };
+    if (name.starts_with('@') && parts.len() != 2) || !parts.iter().all(valid) {
+        return Err(format!(
+            "Invalid package name '{}': use lowercase letters, digits, '-', '_' and '.'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn sanitize_folder(folder: &str) -> Result<String, String> {
+    let folder = folder.replace('\\', "/").trim_matches('/').to_string();
+    if folder.is_empty() || folder.split('/').any(|part| part == "..") {
+        return Err(format!("Folder is outside the project: {}", folder));
+    }
+    Ok(folder)
+}
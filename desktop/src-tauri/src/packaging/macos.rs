@@ -0,0 +1,128 @@
+//! macOS desktop layout: signed `<Name>.app` bundle with Info.plist and icns icon
+
+use super::{payload, DesktopPackage};
+use crate::export::icons;
+use std::path::Path;
+
+/// ICNS entry types holding PNG data, by pixel size.
+const ICNS_ENTRIES: &[(&[u8; 4], u32)] = &[
+    (b"icp4", 16),
+    (b"icp5", 32),
+    (b"icp6", 64),
+    (b"ic07", 128),
+    (b"ic08", 256),
+    (b"ic09", 512),
+    (b"ic10", 1024),
+];
+
+/// The payload sits in `Contents/Resources` rather than after the binary:
+/// codesign refuses a Mach-O with trailing data, and the bundle is signed
+/// once laid out.
+pub fn package(package: &DesktopPackage, shell: &[u8], payload: &[u8]) -> Result<Vec<String>, String> {
+    let name = super::file_safe_name(package.app_name);
+    let app = package.output_dir.join(format!("{}.app", name));
+    let contents = app.join("Contents");
+    let macos_dir = contents.join("MacOS");
+    let resources_dir = contents.join("Resources");
+    std::fs::create_dir_all(&macos_dir).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&resources_dir).map_err(|e| e.to_string())?;
+
+    let binary = macos_dir.join(&name);
+    std::fs::write(&binary, shell).map_err(|e| format!("Failed to write {}: {}", binary.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| e.to_string())?;
+    }
+    let payload_path = resources_dir.join(payload::BUNDLE_FILE);
+    std::fs::write(&payload_path, payload)
+        .map_err(|e| format!("Failed to write {}: {}", payload_path.display(), e))?;
+
+    if let Some(icon) = package.icon {
+        std::fs::write(resources_dir.join("icon.icns"), encode_icns(icon)?).map_err(|e| e.to_string())?;
+    }
+
+    let plist = info_plist(&name, package, package.icon.is_some());
+    std::fs::write(contents.join("Info.plist"), plist).map_err(|e| e.to_string())?;
+    sign(&app, package.signing_identity)
+}
+
+/// Signs the bundle with `identity`, ad-hoc when unset, so the resources
+/// are sealed and Apple silicon Macs will run it.
+fn sign(app: &Path, identity: Option<&str>) -> Result<Vec<String>, String> {
+    let Some(codesign) = crate::compiler::which_sync("codesign") else {
+        return Ok(vec![format!(
+            "codesign not found; sign {} on a Mac before distributing it",
+            app.file_name().unwrap_or_default().to_string_lossy()
+        )]);
+    };
+    let output = std::process::Command::new(codesign)
+        .args(["--force", "--sign", identity.unwrap_or("-")])
+        .arg(app)
+        .output()
+        .map_err(|e| format!("Failed to run codesign: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("codesign failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(Vec::new())
+}
+
+fn encode_icns(icon: &image::DynamicImage) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    for (kind, size) in ICNS_ENTRIES {
+        let png = icons::render_png(icon, *size)?;
+        body.extend_from_slice(*kind);
+        body.extend_from_slice(&((png.len() + 8) as u32).to_be_bytes());
+        body.extend_from_slice(&png);
+    }
+    let mut out = Vec::with_capacity(body.len() + 8);
+    out.extend_from_slice(b"icns");
+    out.extend_from_slice(&((body.len() + 8) as u32).to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+fn info_plist(executable: &str, package: &DesktopPackage, has_icon: bool) -> String {
+    let icon_entry = if has_icon {
+        "    <key>CFBundleIconFile</key>\n    <string>icon</string>\n"
+    } else {
+        ""
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleName</key>
+    <string>{name}</string>
+    <key>CFBundleDisplayName</key>
+    <string>{name}</string>
+    <key>CFBundleExecutable</key>
+    <string>{executable}</string>
+    <key>CFBundleIdentifier</key>
+    <string>{identifier}</string>
+    <key>CFBundleVersion</key>
+    <string>{version}</string>
+    <key>CFBundleShortVersionString</key>
+    <string>{version}</string>
+    <key>CFBundlePackageType</key>
+    <string>APPL</string>
+{icon_entry}    <key>LSMinimumSystemVersion</key>
+    <string>10.15</string>
+    <key>NSHighResolutionCapable</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        name = xml_escape(package.app_name),
+        executable = xml_escape(executable),
+        identifier = xml_escape(package.identifier),
+        version = xml_escape(package.version),
+        icon_entry = icon_entry,
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,98 @@
+//! Packaging — assembles platform-specific distributables from an exported build
+//!
+//! Desktop games are produced by stamping a prebuilt runtime shell (a minimal
+//! WebView host shipped with the editor, built from `desktop/runtime-shell`)
+//! with the game's web build and window configuration, then laying out the
+//! platform bundle around it.
+
+mod macos;
+mod payload;
+mod windows;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellWindowConfig {
+    /// Window title; the game title is used when empty.
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    pub resizable: bool,
+    pub fullscreen: bool,
+}
+
+impl Default for ShellWindowConfig {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            width: 1280,
+            height: 720,
+            resizable: true,
+            fullscreen: false,
+        }
+    }
+}
+
+/// Inputs for stamping a desktop runtime shell.
+pub struct DesktopPackage<'a> {
+    /// Prebuilt runtime shell executable for the target platform.
+    pub shell: &'a Path,
+    /// Directory holding the exported web build (must contain index.html).
+    pub web_root: &'a Path,
+    pub output_dir: &'a Path,
+    pub app_name: &'a str,
+    pub identifier: &'a str,
+    pub version: &'a str,
+    pub icon: Option<&'a image::DynamicImage>,
+    pub window: &'a ShellWindowConfig,
+    /// macOS code signing identity; the bundle is signed ad-hoc when unset.
+    pub signing_identity: Option<&'a str>,
+}
+
+// =============================================================================
+// Desktop
+// =============================================================================
+
+/// Platforms the runtime shell is built for.
+pub const DESKTOP_PLATFORMS: &[&str] = &["windows", "macos"];
+
+/// Location of the bundled runtime shell within the editor resources.
+pub fn shell_resource_path(platform: &str) -> PathBuf {
+    let binary = if platform == "windows" { "esengine-runtime.exe" } else { "esengine-runtime" };
+    PathBuf::from("toolchain/runtime-shell").join(platform).join(binary)
+}
+
+/// Builds the platform distributable; returns non-fatal warnings.
+pub fn package_desktop(platform: &str, package: &DesktopPackage) -> Result<Vec<String>, String> {
+    if !package.web_root.join("index.html").exists() {
+        return Err("Web build is missing index.html".to_string());
+    }
+    let shell = std::fs::read(package.shell)
+        .map_err(|e| format!("Failed to read runtime shell {}: {}", package.shell.display(), e))?;
+    let payload = payload::build(package.web_root, package.window)?;
+
+    match platform {
+        "windows" => windows::package(package, &shell, &payload),
+        "macos" => macos::package(package, &shell, &payload),
+        other => Err(format!("Unsupported desktop platform: {}", other)),
+    }
+}
+
+pub(crate) fn file_safe_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim().to_string();
+    if cleaned.is_empty() {
+        "Game".to_string()
+    } else {
+        cleaned
+    }
+}
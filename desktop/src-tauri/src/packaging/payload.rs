@@ -0,0 +1,68 @@
+//! Runtime shell payload stamping
+//!
+//! The game travels with the shell as one payload:
+//!
+//! ```text
+//! [zip of web build][config JSON][zip len: u64 LE][config len: u64 LE][MAGIC]
+//! ```
+//!
+//! On Windows it is appended to the executable, after resource editing, so
+//! a single file carries both. A macOS bundle keeps it in
+//! `Contents/Resources/BUNDLE_FILE` instead, leaving the binary as signed.
+//! The shell looks for the trailer at the end of its own executable first,
+//! then for the bundle file.
+
+use super::ShellWindowConfig;
+use crate::export::assets::{relative_path, walk_files};
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+pub const MAGIC: &[u8; 8] = b"ESPAYLD1";
+
+/// Payload file name within a macOS bundle's `Contents/Resources`.
+pub const BUNDLE_FILE: &str = "game.espayload";
+
+/// Packs the web build and window configuration into a payload.
+pub fn build(web_root: &Path, window: &ShellWindowConfig) -> Result<Vec<u8>, String> {
+    let archive = zip_directory(web_root)?;
+    let config = serde_json::to_vec(window).map_err(|e| e.to_string())?;
+
+    let mut out = Vec::with_capacity(archive.len() + config.len() + 24);
+    out.extend_from_slice(&archive);
+    out.extend_from_slice(&config);
+    out.extend_from_slice(&(archive.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(config.len() as u64).to_le_bytes());
+    out.extend_from_slice(MAGIC);
+    Ok(out)
+}
+
+/// Appends `payload` to the shell executable.
+pub fn stamp(shell: &[u8], payload: &[u8]) -> Result<Vec<u8>, String> {
+    if shell.ends_with(MAGIC) {
+        return Err("Runtime shell already carries a payload".to_string());
+    }
+    let mut out = Vec::with_capacity(shell.len() + payload.len());
+    out.extend_from_slice(shell);
+    out.extend_from_slice(payload);
+    Ok(out)
+}
+
+fn zip_directory(root: &Path) -> Result<Vec<u8>, String> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    // Fixed timestamps keep the stamped executable identical across builds.
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default());
+
+    for file in walk_files(root) {
+        let rel = relative_path(root, &file);
+        let data = std::fs::read(&file).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        writer.start_file(rel.as_str(), options).map_err(|e| e.to_string())?;
+        writer.write_all(&data).map_err(|e| e.to_string())?;
+    }
+
+    writer
+        .finish()
+        .map(|cursor| cursor.into_inner())
+        .map_err(|e| format!("Failed to pack game payload: {}", e))
+}
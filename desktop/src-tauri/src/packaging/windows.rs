@@ -0,0 +1,66 @@
+//! Windows desktop layout: `<Name>.exe` plus its icon
+
+use super::{payload, DesktopPackage};
+use crate::export::icons;
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::ExtendedColorType;
+
+const ICO_SIZES: &[u32] = &[16, 32, 48, 64, 128, 256];
+
+/// Resources are edited on the plain shell: rcedit rewrites the executable
+/// and would drop anything appended to it, so the payload goes on last.
+pub fn package(package: &DesktopPackage, shell: &[u8], payload: &[u8]) -> Result<Vec<String>, String> {
+    let mut warnings = Vec::new();
+    let name = super::file_safe_name(package.app_name);
+    let exe_path = package.output_dir.join(format!("{}.exe", name));
+    std::fs::write(&exe_path, shell).map_err(|e| format!("Failed to write {}: {}", exe_path.display(), e))?;
+
+    if let Some(icon) = package.icon {
+        let ico_path = package.output_dir.join("icon.ico");
+        std::fs::write(&ico_path, encode_ico(icon)?).map_err(|e| e.to_string())?;
+
+        // Resource editing needs rcedit; without it the icon ships alongside the exe.
+        match crate::compiler::which_sync("rcedit") {
+            Some(rcedit) => {
+                let status = std::process::Command::new(rcedit)
+                    .arg(&exe_path)
+                    .args(["--set-icon", &ico_path.to_string_lossy()])
+                    .args(["--set-version-string", "ProductName", package.app_name])
+                    .args(["--set-version-string", "FileDescription", package.app_name])
+                    .args(["--set-file-version", package.version])
+                    .args(["--set-product-version", package.version])
+                    .status()
+                    .map_err(|e| format!("Failed to run rcedit: {}", e))?;
+                if status.success() {
+                    let _ = std::fs::remove_file(&ico_path);
+                } else {
+                    warnings.push("rcedit failed; icon.ico was left next to the executable".to_string());
+                }
+            }
+            None => warnings.push(
+                "rcedit not found; the executable keeps the default icon (icon.ico written alongside)".to_string(),
+            ),
+        }
+    }
+
+    let edited = std::fs::read(&exe_path).map_err(|e| format!("Failed to read {}: {}", exe_path.display(), e))?;
+    let stamped = payload::stamp(&edited, payload)?;
+    std::fs::write(&exe_path, stamped).map_err(|e| format!("Failed to write {}: {}", exe_path.display(), e))?;
+    Ok(warnings)
+}
+
+fn encode_ico(icon: &image::DynamicImage) -> Result<Vec<u8>, String> {
+    let frames = ICO_SIZES
+        .iter()
+        .map(|&size| {
+            let png = icons::render_png(icon, size)?;
+            IcoFrame::with_encoded(png, size, size, ExtendedColorType::Rgba8).map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut out = Vec::new();
+    IcoEncoder::new(&mut out)
+        .encode_images(&frames)
+        .map_err(|e| format!("Failed to encode icon.ico: {}", e))?;
+    Ok(out)
+}
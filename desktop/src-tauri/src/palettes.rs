@@ -0,0 +1,415 @@
+//! Color palettes — named swatch libraries shared by every project
+//!
+//! Palettes are part of the editor settings (`palettes` in
+//! `settings.json`), so they follow the user across projects, are validated
+//! and broadcast like any other setting, and travel with settings bundles.
+//! The color picker reads and edits them through these commands:
+//!
+//! - `save_palette`, `delete_palette` and `add_palette_color`; colors may be
+//!   pasted as `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, with or without
+//!   the `#`, and are stored as lowercase `#rrggbb[aa]`
+//! - `import_palette` and `export_palette` read and write GIMP (`.gpl`) and
+//!   Adobe Swatch Exchange (`.ase`) files. Neither format has alpha, so it is
+//!   dropped on export; ASE groups are flattened and Lab colors skipped.
+
+use crate::editor_settings;
+use crate::project::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, WebviewWindow};
+
+const MAX_PALETTES: usize = 64;
+const MAX_SWATCHES: usize = 512;
+const GPL_HEADER: &str = "GIMP Palette";
+const ASE_SIGNATURE: &[u8] = b"ASEF";
+const ASE_GROUP_START: u16 = 0xC001;
+const ASE_COLOR: u16 = 0x0001;
+/// Color type of a swatch that is neither global nor spot.
+const ASE_NORMAL: u16 = 2;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    pub swatches: Vec<Swatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Swatch {
+    /// `#rrggbb` or `#rrggbbaa`.
+    pub color: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+}
+
+#[derive(Clone, Copy)]
+enum SwatchFormat {
+    Gpl,
+    Ase,
+}
+
+impl SwatchFormat {
+    fn of(path: &Path) -> Result<Self, String> {
+        match path.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+            Some("gpl") => Ok(SwatchFormat::Gpl),
+            Some("ase") => Ok(SwatchFormat::Ase),
+            _ => Err(format!("{} is not a .gpl or .ase swatch file", path.display())),
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_palettes(app: AppHandle) -> Result<Vec<Palette>, String> {
+    Ok(editor_settings::load(&app)?.palettes)
+}
+
+/// Adds `palette`, or replaces the one named `previous_name` (default: its
+/// own name), keeping its position.
+#[tauri::command]
+pub fn save_palette(
+    app: AppHandle,
+    window: WebviewWindow,
+    palette: Palette,
+    previous_name: Option<String>,
+) -> Result<Vec<Palette>, String> {
+    let palette = normalize(palette)?;
+    let previous = previous_name.unwrap_or_else(|| palette.name.clone());
+    let settings = editor_settings::modify(&app, Some(&window), |settings| {
+        match settings.palettes.iter().position(|p| p.name == previous) {
+            Some(index) => settings.palettes[index] = palette,
+            None => settings.palettes.push(palette),
+        }
+        Ok(())
+    })?;
+    Ok(settings.palettes)
+}
+
+#[tauri::command]
+pub fn delete_palette(app: AppHandle, window: WebviewWindow, name: String) -> Result<Vec<Palette>, String> {
+    let settings = editor_settings::modify(&app, Some(&window), |settings| {
+        let count = settings.palettes.len();
+        settings.palettes.retain(|p| p.name != name);
+        if settings.palettes.len() == count {
+            return Err(format!("No palette named '{}'", name));
+        }
+        Ok(())
+    })?;
+    Ok(settings.palettes)
+}
+
+/// Appends a color, e.g. a pasted hex value, to `palette`, creating the
+/// palette if there is none of that name.
+#[tauri::command]
+pub fn add_palette_color(
+    app: AppHandle,
+    window: WebviewWindow,
+    palette: String,
+    color: String,
+    name: Option<String>,
+) -> Result<Vec<Palette>, String> {
+    let swatch = Swatch {
+        color: parse_hex(&color).ok_or_else(|| format!("'{}' is not a hex color", color.trim()))?,
+        name: name.unwrap_or_default().trim().to_string(),
+    };
+    let palette = palette.trim().to_string();
+    if palette.is_empty() {
+        return Err("Palette name is empty".to_string());
+    }
+    let settings = editor_settings::modify(&app, Some(&window), |settings| {
+        match settings.palettes.iter_mut().find(|p| p.name == palette) {
+            Some(existing) => existing.swatches.push(swatch),
+            None => settings.palettes.push(Palette { name: palette, swatches: vec![swatch] }),
+        }
+        Ok(())
+    })?;
+    Ok(settings.palettes)
+}
+
+/// Adds the palette in a `.gpl` or `.ase` file, renamed if its name is
+/// taken.
+#[tauri::command]
+pub fn import_palette(app: AppHandle, window: WebviewWindow, path: String) -> Result<Palette, String> {
+    let path = Path::new(&path);
+    let format = SwatchFormat::of(path)?;
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let fallback = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let mut palette = match format {
+        SwatchFormat::Gpl => parse_gpl(&String::from_utf8_lossy(&data), &fallback)?,
+        SwatchFormat::Ase => parse_ase(&data, &fallback)?,
+    };
+    palette.swatches.truncate(MAX_SWATCHES);
+
+    let mut imported = None;
+    editor_settings::modify(&app, Some(&window), |settings| {
+        let base = palette.name.clone();
+        let mut n = 1;
+        while settings.palettes.iter().any(|p| p.name == palette.name) {
+            n += 1;
+            palette.name = format!("{} ({})", base, n);
+        }
+        settings.palettes.push(palette.clone());
+        imported = Some(palette);
+        Ok(())
+    })?;
+    let palette = imported.ok_or("Palette was not imported")?;
+    eprintln!("[palettes] Imported '{}' with {} colors", palette.name, palette.swatches.len());
+    Ok(palette)
+}
+
+#[tauri::command]
+pub fn export_palette(app: AppHandle, name: String, path: String) -> Result<(), String> {
+    let path = Path::new(&path);
+    let format = SwatchFormat::of(path)?;
+    let palette = editor_settings::load(&app)?
+        .palettes
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("No palette named '{}'", name))?;
+    let data = match format {
+        SwatchFormat::Gpl => write_gpl(&palette).into_bytes(),
+        SwatchFormat::Ase => write_ase(&palette),
+    };
+    write_atomic(path, &data)
+}
+
+// =============================================================================
+// Colors
+// =============================================================================
+
+/// Problems with `palettes`, for the editor settings validation.
+pub(crate) fn validate(palettes: &[Palette], issues: &mut Vec<String>) {
+    if palettes.len() > MAX_PALETTES {
+        issues.push(format!("palettes: at most {} palettes", MAX_PALETTES));
+    }
+    for (i, palette) in palettes.iter().enumerate() {
+        if palette.name.trim().is_empty() {
+            issues.push(format!("palettes[{}]: name must not be empty", i));
+        } else if palettes[..i].iter().any(|p| p.name == palette.name) {
+            issues.push(format!("palettes[{}]: '{}' is already used by another palette", i, palette.name));
+        }
+        if palette.swatches.len() > MAX_SWATCHES {
+            issues.push(format!("palettes[{}]: at most {} colors", i, MAX_SWATCHES));
+        }
+        for swatch in &palette.swatches {
+            if parse_hex(&swatch.color).as_deref() != Some(swatch.color.as_str()) {
+                issues.push(format!("palettes[{}]: '{}' is not a #rrggbb or #rrggbbaa color", i, swatch.color));
+            }
+        }
+    }
+}
+
+/// `#rrggbb` or `#rrggbbaa`, lowercase, from the hex notations people paste.
+/// An opaque alpha is dropped.
+pub(crate) fn parse_hex(input: &str) -> Option<String> {
+    let hex = input.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = hex.to_ascii_lowercase();
+    let full = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex,
+        _ => return None,
+    };
+    Some(format!("#{}", full.strip_suffix("ff").filter(|rgb| rgb.len() == 6).unwrap_or(full.as_str())))
+}
+
+fn normalize(palette: Palette) -> Result<Palette, String> {
+    let name = palette.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Palette name is empty".to_string());
+    }
+    let swatches = palette
+        .swatches
+        .into_iter()
+        .map(|swatch| {
+            let color = parse_hex(&swatch.color).ok_or_else(|| format!("'{}' is not a hex color", swatch.color))?;
+            Ok(Swatch { color, name: swatch.name.trim().to_string() })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Palette { name, swatches })
+}
+
+fn rgb_bytes(color: &str) -> [u8; 3] {
+    let channel = |i: usize| color.get(1 + i * 2..3 + i * 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0);
+    [channel(0), channel(1), channel(2)]
+}
+
+fn hex_of(rgb: [f32; 3]) -> String {
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(rgb[0]), byte(rgb[1]), byte(rgb[2]))
+}
+
+// =============================================================================
+// GIMP palettes
+// =============================================================================
+
+fn parse_gpl(text: &str, fallback_name: &str) -> Result<Palette, String> {
+    let mut lines = text.lines();
+    if lines.next().map(|line| line.trim_start_matches('\u{feff}').trim()) != Some(GPL_HEADER) {
+        return Err("Not a GIMP palette".to_string());
+    }
+    let mut name = fallback_name.to_string();
+    let mut swatches = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Name:") {
+            name = value.trim().to_string();
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let channels: Vec<u8> = parts.by_ref().take(3).filter_map(|v| v.parse().ok()).collect();
+        let [r, g, b] = channels[..] else {
+            return Err(format!("Invalid color line: {}", line));
+        };
+        let label = parts.collect::<Vec<_>>().join(" ");
+        let color = format!("#{:02x}{:02x}{:02x}", r, g, b);
+        // GIMP names unnamed colors "Untitled" or after their hex value.
+        let label = if label == "Untitled" || label.trim_start_matches('#').eq_ignore_ascii_case(&color[1..]) {
+            String::new()
+        } else {
+            label
+        };
+        swatches.push(Swatch { color, name: label });
+    }
+    Ok(Palette { name, swatches })
+}
+
+fn write_gpl(palette: &Palette) -> String {
+    let mut out = format!("{}\nName: {}\nColumns: 8\n#\n", GPL_HEADER, palette.name);
+    for swatch in &palette.swatches {
+        let [r, g, b] = rgb_bytes(&swatch.color);
+        let label = if swatch.name.is_empty() { &swatch.color[..7] } else { swatch.name.as_str() };
+        out.push_str(&format!("{:3} {:3} {:3}\t{}\n", r, g, b, label));
+    }
+    out
+}
+
+// =============================================================================
+// Adobe Swatch Exchange
+// =============================================================================
+
+struct AseReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> AseReader<'a> {
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.bytes(4).map(|b| f32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// A length-prefixed, null-terminated UTF-16BE string.
+    fn name(&mut self) -> Option<String> {
+        let units = self.u16()? as usize;
+        let data = self.bytes(units * 2)?;
+        let chars: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        Some(String::from_utf16_lossy(&chars).trim_end_matches('\0').to_string())
+    }
+}
+
+fn parse_ase(data: &[u8], fallback_name: &str) -> Result<Palette, String> {
+    let invalid = || "Invalid or truncated swatch exchange file".to_string();
+    let mut reader = AseReader { data, pos: 0 };
+    if reader.bytes(4) != Some(ASE_SIGNATURE) {
+        return Err("Not an Adobe Swatch Exchange file".to_string());
+    }
+    reader.u32().ok_or_else(invalid)?;
+    let blocks = reader.u32().ok_or_else(invalid)?;
+
+    let mut name = None;
+    let mut swatches = Vec::new();
+    let mut skipped = 0;
+    for _ in 0..blocks {
+        let kind = reader.u16().ok_or_else(invalid)?;
+        let length = reader.u32().ok_or_else(invalid)? as usize;
+        let mut block = AseReader { data: reader.bytes(length).ok_or_else(invalid)?, pos: 0 };
+        match kind {
+            ASE_GROUP_START => {
+                let group = block.name().ok_or_else(invalid)?;
+                name.get_or_insert(group);
+            }
+            ASE_COLOR => {
+                let label = block.name().ok_or_else(invalid)?;
+                let model = block.bytes(4).ok_or_else(invalid)?;
+                let mut values = |n: usize| (0..n).map(|_| block.f32()).collect::<Option<Vec<f32>>>();
+                let rgb = match model {
+                    b"RGB " => values(3).map(|v| [v[0], v[1], v[2]]),
+                    b"CMYK" => values(4).map(|v| {
+                        let k = 1.0 - v[3];
+                        [(1.0 - v[0]) * k, (1.0 - v[1]) * k, (1.0 - v[2]) * k]
+                    }),
+                    b"Gray" => values(1).map(|v| [v[0]; 3]),
+                    _ => {
+                        skipped += 1;
+                        continue;
+                    }
+                };
+                let color = hex_of(rgb.ok_or_else(invalid)?);
+                swatches.push(Swatch { color, name: label });
+            }
+            // Group ends, and blocks of later versions.
+            _ => {}
+        }
+    }
+    if skipped > 0 {
+        eprintln!("[palettes] Skipped {} Lab colors", skipped);
+    }
+    if swatches.is_empty() {
+        return Err("The file has no RGB, CMYK or gray colors".to_string());
+    }
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| fallback_name.to_string());
+    Ok(Palette { name, swatches })
+}
+
+fn write_ase(palette: &Palette) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(ASE_SIGNATURE);
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes());
+    out.extend_from_slice(&(palette.swatches.len() as u32).to_be_bytes());
+    for swatch in &palette.swatches {
+        let label = if swatch.name.is_empty() { &swatch.color[..7] } else { swatch.name.as_str() };
+        let units: Vec<u16> = label.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut block = Vec::new();
+        block.extend_from_slice(&(units.len() as u16).to_be_bytes());
+        for unit in units {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(b"RGB ");
+        for channel in rgb_bytes(&swatch.color) {
+            block.extend_from_slice(&(f32::from(channel) / 255.0).to_be_bytes());
+        }
+        block.extend_from_slice(&ASE_NORMAL.to_be_bytes());
+
+        out.extend_from_slice(&ASE_COLOR.to_be_bytes());
+        out.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        out.extend_from_slice(&block);
+    }
+    out
+}
@@ -0,0 +1,99 @@
+//! Sidecar approval — asking before a project's own programs run
+//!
+//! A sidecar is an ordinary process with the user's rights, and a project
+//! plugin comes with whatever project was opened. So a project sidecar only
+//! starts once the user allowed it for that project:
+//!
+//! - approvals live in `<app data>/plugins/approved-sidecars.json`, outside
+//!   any project, so a project cannot approve itself
+//! - an approval is for the plugin folder as it was: the fingerprint covers
+//!   every file in it, and any change asks again
+//! - a refusal is not remembered; the plugin is listed with an error and
+//!   the next `load_plugins` asks again
+
+use super::{plugin_root, PluginManifest, PluginScope};
+use crate::export::assets::{relative_path, walk_files};
+use crate::project::write_atomic;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+const APPROVALS_FILE: &str = "approved-sidecars.json";
+
+/// Fingerprints of approved plugin folders, by plugin id, by project.
+type Approvals = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Whether the project sidecar in `dir` may run, asking the user when it
+/// was not approved as it is now.
+pub async fn ensure_approved(
+    app: &AppHandle,
+    project_dir: &Path,
+    dir: &Path,
+    manifest: &PluginManifest,
+) -> Result<(), String> {
+    let file = approvals_file(app)?;
+    let project = project_key(project_dir);
+    let fingerprint = fingerprint(dir)?;
+    let mut approvals = read_approvals(&file);
+    if approvals.get(&project).and_then(|plugins| plugins.get(&manifest.id)) == Some(&fingerprint) {
+        return Ok(());
+    }
+
+    let message = format!(
+        "The project at {} includes the plugin \"{}\" ({}), which runs {} as a program with your permissions.\n\n\
+         Only allow it if you trust where this project came from.",
+        project_dir.display(),
+        manifest.name,
+        manifest.id,
+        manifest.entry,
+    );
+    let dialog = app
+        .dialog()
+        .message(message)
+        .title("Run Project Plugin?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Allow".to_string(), "Don't Allow".to_string()));
+    let allowed = tauri::async_runtime::spawn_blocking(move || dialog.blocking_show())
+        .await
+        .map_err(|e| format!("Approval dialog failed: {}", e))?;
+    if !allowed {
+        return Err("Not allowed to run in this project".to_string());
+    }
+
+    approvals.entry(project).or_default().insert(manifest.id.clone(), fingerprint);
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_vec_pretty(&approvals).map_err(|e| e.to_string())?;
+    write_atomic(&file, &json)
+}
+
+fn approvals_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(plugin_root(app, PluginScope::Global, None)?.join(APPROVALS_FILE))
+}
+
+fn read_approvals(file: &Path) -> Approvals {
+    std::fs::read_to_string(file).ok().and_then(|text| serde_json::from_str(&text).ok()).unwrap_or_default()
+}
+
+fn project_key(project_dir: &Path) -> String {
+    std::fs::canonicalize(project_dir).unwrap_or_else(|_| project_dir.to_path_buf()).to_string_lossy().to_string()
+}
+
+/// SHA-256 over the relative path and content of every file in `dir`.
+fn fingerprint(dir: &Path) -> Result<String, String> {
+    let mut files: Vec<(String, PathBuf)> =
+        walk_files(dir).into_iter().map(|path| (relative_path(dir, &path), path)).collect();
+    files.sort();
+    let mut hasher = Sha256::new();
+    for (rel, path) in files {
+        let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        hasher.update((rel.len() as u64).to_le_bytes());
+        hasher.update(rel.as_bytes());
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -0,0 +1,123 @@
+//! Host API — what a plugin may ask of the editor
+//!
+//! Requests are `{method, params}`; paths are relative to the project and
+//! must stay inside it. Absolute paths, `..` and symbolic links leading out
+//! of the project are rejected.
+//!
+//! - `log {message}`: shown in the editor's output as `plugin-log`
+//! - `fs/read {path, encoding?}` → the contents, as text or with
+//!   `encoding: "base64"` as base64
+//! - `fs/write {path, data, encoding?}`: creates missing folders
+//! - `fs/list {path}` → `[{name, dir}]`
+//! - `fs/exists {path}` → bool
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Clone, Serialize)]
+struct PluginLog<'a> {
+    plugin: &'a str,
+    message: &'a str,
+}
+
+/// The editor as one plugin of one project sees it.
+pub(crate) struct Host {
+    app: AppHandle,
+    plugin: String,
+    project_dir: PathBuf,
+}
+
+impl Host {
+    pub fn new(app: &AppHandle, plugin: &str, project_dir: &Path) -> Self {
+        Self { app: app.clone(), plugin: plugin.to_string(), project_dir: project_dir.to_path_buf() }
+    }
+
+    pub fn log(&self, message: &str) {
+        eprintln!("[plugins] {}: {}", self.plugin, message);
+        let _ = self.app.emit("plugin-log", PluginLog { plugin: &self.plugin, message });
+    }
+
+    pub fn handle(&self, method: &str, params: &Value) -> Result<Value, String> {
+        let text = |key: &str| params.get(key).and_then(Value::as_str).ok_or_else(|| format!("Missing {}", key));
+        let base64 = params.get("encoding").and_then(Value::as_str) == Some("base64");
+        match method {
+            "log" => {
+                self.log(text("message")?);
+                Ok(Value::Null)
+            }
+            "fs/read" => {
+                let rel = text("path")?;
+                let data = std::fs::read(self.resolve(rel)?).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+                if base64 {
+                    return Ok(BASE64.encode(data).into());
+                }
+                String::from_utf8(data).map(Value::from).map_err(|_| format!("{} is not text; read it as base64", rel))
+            }
+            "fs/write" => {
+                let rel = text("path")?;
+                let data = text("data")?;
+                let data = if base64 {
+                    BASE64.decode(data).map_err(|e| format!("Invalid base64: {}", e))?
+                } else {
+                    data.as_bytes().to_vec()
+                };
+                let path = self.resolve(rel)?;
+                path.parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&path, data))
+                    .map_err(|e| format!("Failed to write {}: {}", rel, e))?;
+                Ok(Value::Null)
+            }
+            "fs/list" => {
+                let rel = params.get("path").and_then(Value::as_str).unwrap_or_default();
+                let entries =
+                    std::fs::read_dir(self.resolve(rel)?).map_err(|e| format!("Failed to list {}: {}", rel, e))?;
+                let mut list: Vec<(String, bool)> = entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path().is_dir()))
+                    .collect();
+                list.sort();
+                Ok(list.into_iter().map(|(name, dir)| json!({ "name": name, "dir": dir })).collect())
+            }
+            "fs/exists" => Ok(self.resolve(text("path")?)?.exists().into()),
+            _ => Err(format!("Unknown host method: {}", method)),
+        }
+    }
+
+    /// `handle` for a raw `{method, params}` request, answering
+    /// `{result}` or `{error}`.
+    pub fn handle_json(&self, request: &[u8]) -> String {
+        let response = match serde_json::from_slice::<Value>(request) {
+            Ok(request) => {
+                let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+                self.handle(method, request.get("params").unwrap_or(&Value::Null))
+            }
+            Err(e) => Err(format!("Invalid host request: {}", e)),
+        };
+        match response {
+            Ok(result) => json!({ "result": result }),
+            Err(error) => json!({ "error": error }),
+        }
+        .to_string()
+    }
+
+    /// The project file `rel` names, if it is inside the project.
+    fn resolve(&self, rel: &str) -> Result<PathBuf, String> {
+        let outside = || format!("Path outside the project: {}", rel);
+        if !Path::new(rel).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(outside());
+        }
+        let path = self.project_dir.join(rel);
+        // A symbolic link inside the project may still lead out of it.
+        let existing = path.ancestors().find(|p| p.exists()).unwrap_or(&self.project_dir);
+        let root = self.project_dir.canonicalize().map_err(|e| e.to_string())?;
+        match existing.canonicalize() {
+            Ok(real) if real.starts_with(&root) => Ok(path),
+            _ => Err(outside()),
+        }
+    }
+}
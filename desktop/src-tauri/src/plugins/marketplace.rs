@@ -0,0 +1,370 @@
+//! Plugin marketplace — finding, installing and updating plugins
+//!
+//! The marketplace is an index (`plugins.marketplace`, or the one built in
+//! with `ESENGINE_PLUGIN_MARKETPLACE`) listing the versions of each plugin:
+//!
+//! ```json
+//! { "plugins": [{ "id": "acme-tools", "name": "ACME Tools", "description": "...", "author": "ACME",
+//!   "versions": { "1.2.0": { "url": "acme-tools-1.2.0.zip", "sha256": "...", "signature": "...", "editor": ">=0.9" } } }] }
+//! ```
+//!
+//! A package is a zip of the plugin folder with `plugin.json` at its root;
+//! `url` may be relative to the index. Before anything is extracted, the
+//! download must match `sha256` and `signature` must be a valid Ed25519
+//! signature of it (base64) by a trusted key: the ones built in with
+//! `ESENGINE_PLUGIN_KEYS` or listed in `plugins.trustedKeys`, both
+//! comma-separated base64 public keys. Versions whose `editor` requirement
+//! this editor does not meet are never offered.
+//!
+//! Plugins install into the project's `.esengine/plugins/<id>` or into
+//! `<app data>/plugins/<id>` for every project, replacing an older version
+//! in one step. Installing or removing unloads the plugin; `load_plugins`
+//! starts the new version.
+
+use super::{plugin_root, PluginManifest, PluginScope, Plugins, MANIFEST_FILE};
+use crate::deploy::hex;
+use crate::editor_settings;
+use crate::package::manager::{join_location, read_location};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+const MARKETPLACE_SETTING: &str = "plugins.marketplace";
+const TRUSTED_KEYS_SETTING: &str = "plugins.trustedKeys";
+const DEFAULT_MARKETPLACE: Option<&str> = option_env!("ESENGINE_PLUGIN_MARKETPLACE");
+const DEFAULT_KEYS: Option<&str> = option_env!("ESENGINE_PLUGIN_KEYS");
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct MarketplaceIndex {
+    plugins: Vec<IndexPlugin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexPlugin {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    author: String,
+    versions: BTreeMap<String, IndexVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexVersion {
+    url: String,
+    sha256: String,
+    signature: String,
+    /// Editor versions the plugin works with, e.g. `>=0.9`.
+    #[serde(default)]
+    editor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplacePlugin {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    /// Versions this editor can run, newest first.
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledPlugin {
+    pub id: String,
+    pub version: String,
+    pub scope: PluginScope,
+    pub dir: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdate {
+    pub id: String,
+    pub name: String,
+    pub scope: PluginScope,
+    pub current: String,
+    pub latest: String,
+}
+
+impl IndexPlugin {
+    /// Versions this editor can run, newest first.
+    fn compatible(&self) -> Vec<(semver::Version, &IndexVersion)> {
+        let editor = semver::Version::parse(env!("CARGO_PKG_VERSION")).ok();
+        let mut versions: Vec<_> = self
+            .versions
+            .iter()
+            .filter_map(|(version, entry)| Some((semver::Version::parse(version).ok()?, entry)))
+            .filter(|(_, entry)| match (&entry.editor, &editor) {
+                (None, _) => true,
+                (Some(requirement), Some(editor)) => {
+                    semver::VersionReq::parse(requirement).is_ok_and(|req| req.matches(editor))
+                }
+                (Some(_), None) => false,
+            })
+            .collect();
+        versions.sort_by(|a, b| b.0.cmp(&a.0));
+        versions
+    }
+}
+
+// =============================================================================
+// Index and verification
+// =============================================================================
+
+fn setting(app: &AppHandle, id: &str) -> Option<String> {
+    let settings = editor_settings::load(app).ok()?;
+    settings.values.get(id).and_then(|v| v.as_str()).map(str::trim).filter(|v| !v.is_empty()).map(String::from)
+}
+
+/// The index and the URL it was read from, for relative package URLs.
+async fn fetch_index(app: &AppHandle) -> Result<(String, MarketplaceIndex), String> {
+    let url = setting(app, MARKETPLACE_SETTING)
+        .or_else(|| DEFAULT_MARKETPLACE.map(String::from))
+        .ok_or("No plugin marketplace is configured")?;
+    let data = read_location(&url).await?;
+    let index = serde_json::from_slice(&data).map_err(|e| format!("Invalid marketplace index: {}", e))?;
+    Ok((url, index))
+}
+
+fn trusted_keys(app: &AppHandle) -> Vec<VerifyingKey> {
+    let configured = setting(app, TRUSTED_KEYS_SETTING).unwrap_or_default();
+    DEFAULT_KEYS
+        .unwrap_or_default()
+        .split(',')
+        .chain(configured.split(','))
+        .filter_map(|key| {
+            let bytes: [u8; 32] = BASE64.decode(key.trim()).ok()?.try_into().ok()?;
+            VerifyingKey::from_bytes(&bytes).ok()
+        })
+        .collect()
+}
+
+/// Checks a downloaded package against its hash and signature.
+fn verify(app: &AppHandle, id: &str, data: &[u8], entry: &IndexVersion) -> Result<(), String> {
+    if hex(&Sha256::digest(data)) != entry.sha256.to_lowercase() {
+        return Err(format!("Checksum mismatch for {}; the download is damaged or the index is out of date", id));
+    }
+    let signature = BASE64
+        .decode(entry.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| format!("{} has an invalid signature", id))?;
+    let keys = trusted_keys(app);
+    if keys.is_empty() {
+        return Err("No trusted plugin keys are configured".to_string());
+    }
+    if !keys.iter().any(|key| key.verify_strict(data, &signature).is_ok()) {
+        return Err(format!("{} is not signed by a trusted key", id));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Installing
+// =============================================================================
+
+/// Plugin ids name folders, so they are kept to plain names.
+fn validate_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && !id.starts_with('.')
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid plugin id: {}", id))
+    }
+}
+
+fn unpack(data: &[u8], dir: &Path, id: &str) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Invalid package: {}", e))?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| format!("Invalid package: {}", e))?;
+        let name = file.enclosed_name().ok_or_else(|| format!("Unsafe path in package: {}", file.name()))?;
+        let path = dir.join(name);
+        if file.is_dir() {
+            std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = std::fs::File::create(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        std::io::copy(&mut file, &mut out).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    let manifest = PluginManifest::load(dir)?;
+    if manifest.id != id {
+        return Err(format!("Package contains plugin '{}', not '{}'", manifest.id, id));
+    }
+    Ok(())
+}
+
+/// Extracts `data` into `<root>/<id>`, replacing what is there only once
+/// the whole package is out.
+fn extract(data: &[u8], root: &Path, id: &str) -> Result<PathBuf, String> {
+    let dest = root.join(id);
+    let staging = root.join(format!(".{}.installing", id));
+    let old = root.join(format!(".{}.old", id));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+    if let Err(e) = unpack(data, &staging, id) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let _ = std::fs::remove_dir_all(&old);
+    if dest.exists() {
+        std::fs::rename(&dest, &old).map_err(|e| format!("Failed to replace {}: {}", dest.display(), e))?;
+    }
+    if let Err(e) = std::fs::rename(&staging, &dest) {
+        let _ = std::fs::rename(&old, &dest);
+        return Err(format!("Failed to install {}: {}", id, e));
+    }
+    let _ = std::fs::remove_dir_all(&old);
+    Ok(dest)
+}
+
+/// Plugins installed in `root`.
+fn installed(root: &Path) -> Vec<PluginManifest> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.join(MANIFEST_FILE).is_file())
+        .filter_map(|p| PluginManifest::load(&p).ok())
+        .collect()
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Plugins in the marketplace whose id, name or description contains
+/// `query`, with the versions this editor can run.
+#[tauri::command]
+pub async fn search_plugin_marketplace(
+    app: AppHandle,
+    query: Option<String>,
+) -> Result<Vec<MarketplacePlugin>, String> {
+    let (_, index) = fetch_index(&app).await?;
+    let query = query.unwrap_or_default().trim().to_lowercase();
+    Ok(index
+        .plugins
+        .iter()
+        .filter(|plugin| {
+            query.is_empty()
+                || [&plugin.id, &plugin.name, &plugin.description].iter().any(|s| s.to_lowercase().contains(&query))
+        })
+        .map(|plugin| MarketplacePlugin {
+            id: plugin.id.clone(),
+            name: plugin.name.clone(),
+            description: plugin.description.clone(),
+            author: plugin.author.clone(),
+            versions: plugin.compatible().iter().map(|(version, _)| version.to_string()).collect(),
+        })
+        .filter(|plugin| !plugin.versions.is_empty())
+        .collect())
+}
+
+/// Downloads, verifies and installs a plugin: `version`, or the newest
+/// this editor can run. Also used to update.
+#[tauri::command]
+pub async fn install_plugin(
+    app: AppHandle,
+    plugins: State<'_, Plugins>,
+    id: String,
+    version: Option<String>,
+    scope: PluginScope,
+    project_dir: Option<String>,
+) -> Result<InstalledPlugin, String> {
+    validate_id(&id)?;
+    let root = plugin_root(&app, scope, project_dir.as_deref().map(Path::new))?;
+    let (url, index) = fetch_index(&app).await?;
+    let plugin =
+        index.plugins.iter().find(|p| p.id == id).ok_or_else(|| format!("{} is not in the marketplace", id))?;
+    let compatible = plugin.compatible();
+    let found = match &version {
+        Some(wanted) => compatible.iter().find(|(v, _)| v.to_string() == *wanted),
+        None => compatible.first(),
+    };
+    let (chosen, entry) = found.ok_or_else(|| match &version {
+        Some(wanted) => format!("{} {} does not exist or does not run in this editor", id, wanted),
+        None => format!("No version of {} runs in this editor", id),
+    })?;
+
+    let data = read_location(&join_location(&url, &entry.url)?).await?;
+    verify(&app, &id, &data, entry)?;
+    plugins.remove(&id);
+    let dir = tokio::task::spawn_blocking({
+        let id = id.clone();
+        move || extract(&data, &root, &id)
+    })
+    .await
+    .map_err(|e| format!("Install task failed: {}", e))??;
+    Ok(InstalledPlugin { id, version: chosen.to_string(), scope, dir: dir.to_string_lossy().to_string() })
+}
+
+#[tauri::command]
+pub fn uninstall_plugin(
+    app: AppHandle,
+    plugins: State<'_, Plugins>,
+    id: String,
+    scope: PluginScope,
+    project_dir: Option<String>,
+) -> Result<(), String> {
+    validate_id(&id)?;
+    let dir = plugin_root(&app, scope, project_dir.as_deref().map(Path::new))?.join(&id);
+    if !dir.join(MANIFEST_FILE).is_file() {
+        return Err(format!("{} is not installed", id));
+    }
+    plugins.remove(&id);
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))
+}
+
+/// Installed plugins with a newer version in the marketplace: the global
+/// ones, and the project's when `project_dir` is given.
+#[tauri::command]
+pub async fn check_plugin_updates(app: AppHandle, project_dir: Option<String>) -> Result<Vec<PluginUpdate>, String> {
+    let (_, index) = fetch_index(&app).await?;
+    let mut scopes = vec![PluginScope::Global];
+    if project_dir.is_some() {
+        scopes.insert(0, PluginScope::Project);
+    }
+    let mut updates = Vec::new();
+    for scope in scopes {
+        let root = plugin_root(&app, scope, project_dir.as_deref().map(Path::new))?;
+        for manifest in installed(&root) {
+            let Some(plugin) = index.plugins.iter().find(|p| p.id == manifest.id) else {
+                continue;
+            };
+            let Some((latest, _)) = plugin.compatible().into_iter().next() else {
+                continue;
+            };
+            if semver::Version::parse(&manifest.version).map_or(true, |current| latest > current) {
+                updates.push(PluginUpdate {
+                    id: manifest.id,
+                    name: manifest.name,
+                    scope,
+                    current: manifest.version,
+                    latest: latest.to_string(),
+                });
+            }
+        }
+    }
+    Ok(updates)
+}
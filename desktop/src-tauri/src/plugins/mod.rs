@@ -0,0 +1,482 @@
+//! Editor plugins — studio tools that add commands and asset importers
+//!
+//! A plugin is a folder with a `plugin.json` manifest:
+//!
+//! ```json
+//! { "id": "acme-tools", "name": "ACME Tools", "version": "1.0.0", "entry": "acme_tools.wasm" }
+//! ```
+//!
+//! Plugins are looked up in the project's `.esengine/plugins/`, then in
+//! `<app data>/plugins/` and the folders listed in `ESENGINE_PLUGIN_PATH`;
+//! a project plugin hides a global one with the same id. A `plugins.json`
+//! next to them switches plugins off (`disabled`) or, in a project, back
+//! on (`enabled`); the project's choice wins. Plugins are installed from
+//! a marketplace through `marketplace`.
+//!
+//! `entry` is a `.wasm` module (see `wasm`) or a program started with
+//! `args` (see `sidecar`). Both answer the same JSON-RPC 2.0 methods:
+//!
+//! - `initialize {abi, editorVersion}` → `{commands, importers}`, what the
+//!   plugin registers: commands as `{id, title, description?}`, importers
+//!   as `{id, name, extensions}`;
+//! - `command/run {command, args}` → any JSON;
+//! - `importer/run {importer, source, folder}` → `{files}`: imports the
+//!   project file `source` into the project folder `folder` and lists the
+//!   files it wrote.
+//!
+//! Plugins reach the project through the host API (see `host`), which keeps
+//! them inside the project folder. That is the whole of a WASM module's
+//! reach. A sidecar is an ordinary process with the user's rights, so a
+//! project sidecar only starts once the user approved it for that project
+//! (see `approval`).
+
+mod approval;
+mod host;
+pub mod marketplace;
+mod sidecar;
+mod wasm;
+
+use crate::files::import::ImportResult;
+use crate::project::write_atomic;
+use host::Host;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sidecar::Sidecar;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use wasm::WasmPlugin;
+
+pub const ABI_VERSION: u32 = 1;
+pub const MANIFEST_FILE: &str = "plugin.json";
+pub const PROJECT_PLUGIN_DIR: &str = ".esengine/plugins";
+pub const GLOBAL_PLUGIN_DIR: &str = "plugins";
+const PLUGIN_PATH_ENV: &str = "ESENGINE_PLUGIN_PATH";
+const TOGGLES_FILE: &str = "plugins.json";
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Commands and imports may do real work, but not hang forever.
+const CALL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub entry: String,
+    /// Arguments of a sidecar program.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl PluginManifest {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(MANIFEST_FILE);
+        let manifest: Self = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e)))?;
+        if manifest.id.is_empty() {
+            return Err("Plugin id must not be empty".to_string());
+        }
+        Ok(manifest)
+    }
+
+    fn kind(&self) -> PluginKind {
+        if self.entry.ends_with(".wasm") {
+            PluginKind::Wasm
+        } else {
+            PluginKind::Sidecar
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    Wasm,
+    Sidecar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginScope {
+    /// In the project's `.esengine/plugins/`.
+    Project,
+    /// Installed for every project.
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginImporter {
+    pub id: String,
+    pub name: String,
+    /// File extensions without the dot, e.g. `psd`.
+    pub extensions: Vec<String>,
+}
+
+/// `plugins.json` in a plugin folder: plugins switched on or off there.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Toggles {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    enabled: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    disabled: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Registrations {
+    #[serde(default)]
+    commands: Vec<PluginCommand>,
+    #[serde(default)]
+    importers: Vec<PluginImporter>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub kind: PluginKind,
+    pub scope: PluginScope,
+    pub dir: String,
+    pub enabled: bool,
+    pub commands: Vec<PluginCommand>,
+    pub importers: Vec<PluginImporter>,
+    /// Why the plugin could not be loaded.
+    pub error: Option<String>,
+}
+
+enum Runtime {
+    Wasm(WasmPlugin),
+    Sidecar(Sidecar),
+}
+
+struct LoadedPlugin {
+    info: PluginInfo,
+    runtime: Option<Runtime>,
+}
+
+/// Loaded plugins by project directory.
+#[derive(Default)]
+pub struct Plugins {
+    projects: Mutex<HashMap<PathBuf, Vec<Arc<LoadedPlugin>>>>,
+}
+
+impl Plugins {
+    /// Unloads the plugins of a closed project, ending their sidecars.
+    pub fn unload(&self, project_dir: &Path) -> bool {
+        self.projects.lock().unwrap_or_else(|e| e.into_inner()).remove(project_dir).is_some()
+    }
+
+    /// Unloads plugin `id` from every project, before its folder changes.
+    fn remove(&self, id: &str) {
+        for loaded in self.projects.lock().unwrap_or_else(|e| e.into_inner()).values_mut() {
+            loaded.retain(|plugin| plugin.info.id != id);
+        }
+    }
+
+    fn get(&self, project_dir: &Path, plugin: &str) -> Result<Arc<LoadedPlugin>, String> {
+        let projects = self.projects.lock().unwrap_or_else(|e| e.into_inner());
+        projects
+            .get(project_dir)
+            .and_then(|plugins| plugins.iter().find(|p| p.info.id == plugin))
+            .cloned()
+            .ok_or_else(|| format!("Plugin not loaded: {}", plugin))
+    }
+}
+
+// =============================================================================
+// JSON-RPC
+// =============================================================================
+
+/// The `result` of a JSON-RPC response, or its error message.
+fn rpc_result(mut response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+        let message = error.get("message").and_then(Value::as_str);
+        return Err(message.map(String::from).unwrap_or_else(|| error.to_string()));
+    }
+    Ok(response.get_mut("result").map(Value::take).unwrap_or_default())
+}
+
+impl Runtime {
+    async fn call(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        match self {
+            Runtime::Sidecar(sidecar) => sidecar.call(method, params, timeout).await,
+            Runtime::Wasm(plugin) => {
+                let plugin = plugin.clone();
+                let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+                let raw = tauri::async_runtime::spawn_blocking(move || plugin.call(&request))
+                    .await
+                    .map_err(|e| format!("Plugin task failed: {}", e))??;
+                rpc_result(serde_json::from_str(&raw).map_err(|e| format!("Invalid response: {}", e))?)
+            }
+        }
+    }
+}
+
+impl LoadedPlugin {
+    async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let runtime = self.runtime.as_ref().ok_or_else(|| match &self.info.error {
+            Some(error) => format!("{} failed to load: {}", self.info.name, error),
+            None => format!("{} is disabled", self.info.name),
+        })?;
+        runtime.call(method, params, CALL_TIMEOUT).await
+    }
+}
+
+// =============================================================================
+// Discovery and loading
+// =============================================================================
+
+/// The folder plugins of `scope` are installed in; project plugins need
+/// `project_dir`.
+pub(crate) fn plugin_root(app: &AppHandle, scope: PluginScope, project_dir: Option<&Path>) -> Result<PathBuf, String> {
+    match scope {
+        PluginScope::Project => project_dir
+            .map(|dir| dir.join(PROJECT_PLUGIN_DIR))
+            .ok_or_else(|| "A project folder is required".to_string()),
+        PluginScope::Global => {
+            app.path().app_data_dir().map(|dir| dir.join(GLOBAL_PLUGIN_DIR)).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Plugin folders with their scope, project plugins first.
+pub(crate) fn plugin_dirs(app: &AppHandle, project_dir: &Path) -> Vec<(PathBuf, PluginScope)> {
+    let mut roots = vec![(project_dir.join(PROJECT_PLUGIN_DIR), PluginScope::Project)];
+    if let Ok(dir) = plugin_root(app, PluginScope::Global, None) {
+        roots.push((dir, PluginScope::Global));
+    }
+    if let Some(paths) = std::env::var_os(PLUGIN_PATH_ENV) {
+        roots.extend(std::env::split_paths(&paths).map(|dir| (dir, PluginScope::Global)));
+    }
+
+    let mut dirs = Vec::new();
+    for (root, scope) in roots {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> =
+            entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.join(MANIFEST_FILE).is_file()).collect();
+        found.sort();
+        dirs.extend(found.into_iter().map(|dir| (dir, scope)));
+    }
+    dirs
+}
+
+fn read_toggles(root: &Path) -> Toggles {
+    std::fs::read_to_string(root.join(TOGGLES_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Whether plugin `id` runs in the project; the project's choice wins over
+/// the global one.
+fn is_enabled(app: &AppHandle, project_dir: &Path, id: &str) -> bool {
+    let project = read_toggles(&project_dir.join(PROJECT_PLUGIN_DIR));
+    if project.enabled.contains(id) || project.disabled.contains(id) {
+        return project.enabled.contains(id);
+    }
+    plugin_root(app, PluginScope::Global, None).map_or(true, |root| !read_toggles(&root).disabled.contains(id))
+}
+
+async fn start(
+    app: &AppHandle,
+    project_dir: &Path,
+    dir: &Path,
+    scope: PluginScope,
+    manifest: &PluginManifest,
+) -> Result<(Runtime, Registrations), String> {
+    if manifest.kind() == PluginKind::Sidecar && scope == PluginScope::Project {
+        approval::ensure_approved(app, project_dir, dir, manifest).await?;
+    }
+    let host = Arc::new(Host::new(app, &manifest.id, project_dir));
+    let runtime = match manifest.kind() {
+        PluginKind::Wasm => Runtime::Wasm(WasmPlugin::load(&dir.join(&manifest.entry), host)?),
+        PluginKind::Sidecar => {
+            Runtime::Sidecar(Sidecar::start(dir, &manifest.entry, &manifest.args, project_dir, host)?)
+        }
+    };
+    let params = json!({ "abi": ABI_VERSION, "editorVersion": env!("CARGO_PKG_VERSION") });
+    let registered = runtime.call("initialize", params, INITIALIZE_TIMEOUT).await?;
+    let registrations = serde_json::from_value(registered).map_err(|e| format!("Invalid initialize result: {}", e))?;
+    Ok((runtime, registrations))
+}
+
+async fn load(app: &AppHandle, project_dir: &Path, dir: PathBuf, scope: PluginScope) -> Result<LoadedPlugin, String> {
+    let manifest = PluginManifest::load(&dir)?;
+    let mut info = PluginInfo {
+        id: manifest.id.clone(),
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        description: manifest.description.clone(),
+        kind: manifest.kind(),
+        scope,
+        dir: dir.to_string_lossy().to_string(),
+        enabled: is_enabled(app, project_dir, &manifest.id),
+        commands: Vec::new(),
+        importers: Vec::new(),
+        error: None,
+    };
+    if !info.enabled {
+        return Ok(LoadedPlugin { info, runtime: None });
+    }
+    let runtime = match start(app, project_dir, &dir, scope, &manifest).await {
+        Ok((runtime, registrations)) => {
+            info.commands = registrations.commands;
+            info.importers = registrations.importers;
+            Some(runtime)
+        }
+        Err(e) => {
+            eprintln!("[plugins] Failed to load {}: {}", manifest.id, e);
+            info.error = Some(e);
+            None
+        }
+    };
+    Ok(LoadedPlugin { info, runtime })
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Loads the plugins of a project, replacing those loaded before. Plugins
+/// that fail to start are listed with their error.
+#[tauri::command]
+pub async fn load_plugins(
+    app: AppHandle,
+    plugins: State<'_, Plugins>,
+    project_dir: String,
+) -> Result<Vec<PluginInfo>, String> {
+    let project = PathBuf::from(&project_dir);
+    plugins.unload(&project);
+    let mut loaded: Vec<Arc<LoadedPlugin>> = Vec::new();
+    for (dir, scope) in plugin_dirs(&app, &project) {
+        match load(&app, &project, dir.clone(), scope).await {
+            Ok(plugin) if loaded.iter().any(|p| p.info.id == plugin.info.id) => {
+                eprintln!("[plugins] Ignoring {}: plugin '{}' is already loaded", dir.display(), plugin.info.id);
+            }
+            Ok(plugin) => loaded.push(Arc::new(plugin)),
+            Err(e) => eprintln!("[plugins] Skipping {}: {}", dir.display(), e),
+        }
+    }
+    let infos = loaded.iter().map(|p| p.info.clone()).collect();
+    plugins.projects.lock().unwrap_or_else(|e| e.into_inner()).insert(project, loaded);
+    Ok(infos)
+}
+
+#[tauri::command]
+pub fn list_plugins(plugins: State<'_, Plugins>, project_dir: String) -> Vec<PluginInfo> {
+    let projects = plugins.projects.lock().unwrap_or_else(|e| e.into_inner());
+    projects
+        .get(Path::new(&project_dir))
+        .map(|loaded| loaded.iter().map(|p| p.info.clone()).collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn unload_plugins(plugins: State<'_, Plugins>, project_dir: String) -> bool {
+    plugins.unload(Path::new(&project_dir))
+}
+
+/// Runs a command a plugin registered and returns its result.
+#[tauri::command]
+pub async fn run_plugin_command(
+    plugins: State<'_, Plugins>,
+    project_dir: String,
+    plugin: String,
+    command: String,
+    args: Option<Value>,
+) -> Result<Value, String> {
+    let loaded = plugins.get(Path::new(&project_dir), &plugin)?;
+    if !loaded.info.commands.iter().any(|c| c.id == command) {
+        return Err(format!("{} has no command '{}'", loaded.info.name, command));
+    }
+    loaded.call("command/run", json!({ "command": command, "args": args.unwrap_or_default() })).await
+}
+
+/// Imports the project file `source` into `folder` with the plugin
+/// importer registered for its extension, emitting `assets-imported` like
+/// any other import.
+#[tauri::command]
+pub async fn import_with_plugin(
+    app: AppHandle,
+    plugins: State<'_, Plugins>,
+    project_dir: String,
+    source: String,
+    folder: String,
+) -> Result<ImportResult, String> {
+    let extension = Path::new(&source)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .ok_or_else(|| format!("{} has no extension", source))?;
+    let found = {
+        let projects = plugins.projects.lock().unwrap_or_else(|e| e.into_inner());
+        projects.get(Path::new(&project_dir)).and_then(|loaded| {
+            loaded.iter().find_map(|plugin| {
+                let importer = plugin.info.importers.iter().find(|importer| {
+                    importer.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+                })?;
+                Some((plugin.clone(), importer.id.clone()))
+            })
+        })
+    };
+    let (plugin, importer) = found.ok_or_else(|| format!("No plugin imports .{} files", extension))?;
+
+    let result =
+        plugin.call("importer/run", json!({ "importer": importer, "source": source, "folder": folder })).await?;
+    let files: Vec<String> = result
+        .get("files")
+        .and_then(Value::as_array)
+        .map(|files| files.iter().filter_map(Value::as_str).map(String::from).collect())
+        .unwrap_or_default();
+    let result = ImportResult { project_dir, folder, imported: files.clone(), files, failed: Vec::new() };
+    let _ = app.emit("assets-imported", &result);
+    Ok(result)
+}
+
+/// Switches a plugin on or off: for the project when `project_dir` is
+/// given, otherwise for every project. Takes effect on the next
+/// `load_plugins`.
+#[tauri::command]
+pub fn set_plugin_enabled(
+    app: AppHandle,
+    project_dir: Option<String>,
+    id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let scope = if project_dir.is_some() { PluginScope::Project } else { PluginScope::Global };
+    let root = plugin_root(&app, scope, project_dir.as_deref().map(Path::new))?;
+    let mut toggles = read_toggles(&root);
+    toggles.enabled.remove(&id);
+    toggles.disabled.remove(&id);
+    match (scope, enabled) {
+        (_, false) => toggles.disabled.insert(id),
+        // Globally, enabled is the default; a project records it to
+        // override a global `disabled`.
+        (PluginScope::Project, true) => toggles.enabled.insert(id),
+        (PluginScope::Global, true) => false,
+    };
+    std::fs::create_dir_all(&root).map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+    let json = serde_json::to_vec_pretty(&toggles).map_err(|e| e.to_string())?;
+    write_atomic(&root.join(TOGGLES_FILE), &json)
+}
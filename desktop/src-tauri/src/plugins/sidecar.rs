@@ -0,0 +1,140 @@
+//! Sidecar plugins — programs speaking JSON-RPC over stdio
+//!
+//! The program runs in its plugin folder and exchanges JSON-RPC 2.0
+//! messages with the editor, one per line on stdin and stdout; what it
+//! writes to stderr is logged. Besides answering the editor's requests it
+//! may send its own, which go to the host API. It runs until its project is
+//! closed or the plugins are reloaded.
+
+use super::host::Host;
+use super::rpc_result;
+use crate::process::env::{login_path_default, CommandEnv};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+pub(crate) struct Sidecar {
+    writer: mpsc::UnboundedSender<String>,
+    pending: Pending,
+    next_id: AtomicU64,
+    /// Dropping the sidecar kills the process.
+    _child: Child,
+}
+
+impl Sidecar {
+    /// Starts `entry` from `dir`; a bare name not found there is looked up
+    /// on the PATH, so `node` with `args: ["main.js"]` works.
+    pub fn start(
+        dir: &Path,
+        entry: &str,
+        args: &[String],
+        project_dir: &Path,
+        host: Arc<Host>,
+    ) -> Result<Self, String> {
+        let env = CommandEnv::new(HashMap::new(), Some(project_dir), login_path_default());
+        let local = dir.join(entry);
+        let program = if local.is_file() { local } else { env.resolve(entry) };
+        let mut command = Command::new(&program);
+        command
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        env.apply(&mut command);
+        #[cfg(windows)]
+        command.creation_flags(crate::process::CREATE_NO_WINDOW);
+        let mut child = command.spawn().map_err(|e| format!("Failed to start {}: {}", program.display(), e))?;
+
+        let (Some(mut stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err("Failed to open the plugin's stdio".to_string());
+        };
+        let (writer, mut outgoing) = mpsc::unbounded_channel::<String>();
+        tauri::async_runtime::spawn(async move {
+            while let Some(line) = outgoing.recv().await {
+                if stdin.write_all(line.as_bytes()).await.is_err() || stdin.write_all(b"\n").await.is_err() {
+                    break;
+                }
+                let _ = stdin.flush().await;
+            }
+        });
+
+        let stderr_host = host.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                stderr_host.log(&line);
+            }
+        });
+
+        let pending = Pending::default();
+        let responses = pending.clone();
+        let replies = writer.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    host.log(&line);
+                    continue;
+                };
+                if let Some(method) = message.get("method").and_then(Value::as_str).map(String::from) {
+                    let host = host.clone();
+                    let replies = replies.clone();
+                    tauri::async_runtime::spawn_blocking(move || {
+                        let result = host.handle(&method, message.get("params").unwrap_or(&Value::Null));
+                        // Notifications (no id) get no answer.
+                        let Some(id) = message.get("id") else {
+                            return;
+                        };
+                        let response = match result {
+                            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                            Err(error) => {
+                                json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32000, "message": error } })
+                            }
+                        };
+                        let _ = replies.send(response.to_string());
+                    });
+                } else if let Some(id) = message.get("id").and_then(Value::as_u64) {
+                    if let Some(sender) = responses.lock().unwrap_or_else(|e| e.into_inner()).remove(&id) {
+                        let _ = sender.send(message);
+                    }
+                }
+            }
+            // The process ended; callers still waiting get an error.
+            responses.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        });
+
+        Ok(Self { writer, pending, next_id: AtomicU64::new(0), _child: child })
+    }
+
+    /// Sends a request and waits up to `timeout` for its result.
+    pub async fn call(&self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).insert(id, sender);
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if self.writer.send(request.to_string()).is_err() {
+            self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+            return Err("The plugin has exited".to_string());
+        }
+        let response = tokio::time::timeout(timeout, receiver).await;
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+        match response {
+            Ok(Ok(response)) => rpc_result(response),
+            Ok(Err(_)) => Err("The plugin has exited".to_string()),
+            Err(_) => Err(format!("The plugin did not answer {} in time", method)),
+        }
+    }
+}
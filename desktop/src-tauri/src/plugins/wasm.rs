@@ -0,0 +1,77 @@
+//! WASM plugins — sandboxed modules run by wasmi
+//!
+//! A module exports `memory`, `esengine_alloc(len: i32) -> i32` and
+//! `esengine_plugin_call(ptr: i32, len: i32) -> i64`, which takes a
+//! JSON-RPC request and returns the response as `ptr << 32 | len`. The only
+//! import it may have is `esengine.host_call(ptr, len) -> i64`, which takes
+//! a host API request and returns `{result}` or `{error}` the same way.
+//!
+//! Every call runs in a fresh instance with a fuel budget: a plugin keeps
+//! no state between calls, and a runaway loop traps instead of hanging the
+//! editor.
+
+use super::host::Host;
+use crate::export::plugin::{read_guest, write_guest};
+use std::path::Path;
+use std::sync::Arc;
+use wasmi::{Caller, Config, Engine, Linker, Module, Store};
+
+/// Instructions a single call may execute, roughly.
+const FUEL: u64 = 20_000_000_000;
+
+#[derive(Clone)]
+pub(crate) struct WasmPlugin {
+    engine: Engine,
+    module: Arc<Module>,
+    host: Arc<Host>,
+}
+
+impl WasmPlugin {
+    pub fn load(path: &Path, host: Arc<Host>) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &bytes[..]).map_err(|e| format!("Invalid WASM module: {}", e))?;
+        Ok(Self { engine, module: Arc::new(module), host })
+    }
+
+    /// Runs a JSON-RPC `request` and returns the response.
+    pub fn call(&self, request: &str) -> Result<String, String> {
+        let mut store = Store::new(&self.engine, self.host.clone());
+        store.set_fuel(FUEL).map_err(|e| e.to_string())?;
+
+        let mut linker = Linker::<Arc<Host>>::new(&self.engine);
+        linker
+            .func_wrap("esengine", "host_call", |mut caller: Caller<'_, Arc<Host>>, ptr: i32, len: i32| -> i64 {
+                let Some(request) = read_guest(&mut caller, ptr, len) else {
+                    return -1;
+                };
+                let response = caller.data().handle_json(&request);
+                write_guest(&mut caller, response.as_bytes()).unwrap_or(-1)
+            })
+            .map_err(|e| e.to_string())?;
+
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| format!("Failed to instantiate plugin: {}", e))?;
+        let call = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "esengine_plugin_call")
+            .map_err(|e| format!("Missing esengine_plugin_call: {}", e))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "esengine_alloc")
+            .map_err(|e| format!("Missing esengine_alloc: {}", e))?;
+        let memory = instance.get_memory(&store, "memory").ok_or("Missing exported memory")?;
+
+        let len = request.len() as i32;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        memory.write(&mut store, ptr as usize, request.as_bytes()).map_err(|e| e.to_string())?;
+        let packed = call.call(&mut store, (ptr, len)).map_err(|e| format!("Plugin trapped: {}", e))?;
+
+        let (ptr, len) = ((packed as u64 >> 32) as usize, (packed as u64 & 0xffff_ffff) as usize);
+        let mut response = vec![0; len];
+        memory.read(&store, ptr, &mut response).map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(&response).to_string())
+    }
+}
@@ -1,9 +1,17 @@
 //! HTTP server for game preview with SSE live reload
-
-use crate::embedded_assets;
+//!
+//! The page is the built-in preview template, or the project's own
+//! `preview_template.html` wrapped around the built-in parts.
+
+use crate::embedded_assets::{self, Embedded};
+use crate::engines::{self, Engine};
+use crate::i18n::BackendError;
+use crate::runtimes;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread;
@@ -83,7 +91,7 @@ impl PreviewServer {
         }
     }
 
-    pub fn start(&mut self) -> Result<u16, String> {
+    pub fn start(&mut self) -> Result<u16, BackendError> {
         if self.server.is_some() {
             return Ok(self.port);
         }
@@ -112,12 +120,17 @@ impl PreviewServer {
                 }
 
                 let current_dir = project_dir.read().unwrap().clone();
+                let if_none_match = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("If-None-Match"))
+                    .map(|h| h.value.as_str().to_string());
 
                 let response = match path {
-                    "" | "index.html" => serve_html(),
+                    "" | "index.html" => serve_html(&current_dir),
                     "favicon.ico" => serve_empty(),
                     _ if path.starts_with("wasm/") || path.starts_with("sdk/") =>
-                        serve_public_or_embedded(&public_dir, path),
+                        serve_engine_file(&current_dir, &public_dir, path, if_none_match.as_deref()),
                     _ => serve_project_file(&current_dir, path),
                 };
 
@@ -152,20 +165,13 @@ impl PreviewServer {
         self.port
     }
 
-    pub fn project_dir(&self) -> PathBuf {
-        self.project_dir.read().unwrap().clone()
-    }
-
-    pub fn set_project_dir(&self, dir: PathBuf) {
-        *self.project_dir.write().unwrap() = dir;
-    }
 }
 
 // =============================================================================
 // Port Binding
 // =============================================================================
 
-fn try_bind(starting_port: u16) -> Result<(Server, u16), String> {
+fn try_bind(starting_port: u16) -> Result<(Server, u16), BackendError> {
     let mut last_err = String::new();
     for offset in 0..MAX_PORT_ATTEMPTS {
         let port = starting_port + offset;
@@ -184,12 +190,14 @@ fn try_bind(starting_port: u16) -> Result<(Server, u16), String> {
             let port = server.server_addr().to_ip().map(|a| a.port()).unwrap_or(0);
             Ok((server, port))
         }
-        Err(e) => Err(format!(
-            "Failed to bind ports {}-{} ({}), and auto-assign also failed: {}",
-            starting_port,
-            starting_port + MAX_PORT_ATTEMPTS - 1,
-            last_err,
-            e
+        Err(e) => Err(BackendError::new(
+            "preview.bindFailed",
+            [
+                ("start", starting_port.to_string()),
+                ("end", (starting_port + MAX_PORT_ATTEMPTS - 1).to_string()),
+                ("error", last_err),
+                ("fallbackError", e.to_string()),
+            ],
         )),
     }
 }
@@ -238,27 +246,74 @@ fn handle_sse(request: tiny_http::Request, signal: Arc<ReloadSignal>) {
 }
 
 // =============================================================================
-// Response Builders
+// Preview Template
 // =============================================================================
 
-fn serve_html() -> Response<std::io::Cursor<Vec<u8>>> {
-    let data = embedded_assets::PREVIEW_HTML.as_bytes().to_vec();
-    Response::from_data(data)
-        .with_header(content_type("text/html"))
-        .with_header(no_cache())
-        .with_header(cors())
+/// A project's own preview page, in its folder, replacing the built-in one,
+/// e.g. to stub platform APIs or add an analytics snippet.
+pub const PREVIEW_TEMPLATE_FILE: &str = "preview_template.html";
+
+/// Where a project template gets the built-in page's import map and
+/// styles, and its canvas, overlays and boot script. Each must appear once.
+const HEAD_PLACEHOLDER: &str = "{{ESENGINE_HEAD}}";
+const BODY_PLACEHOLDER: &str = "{{ESENGINE_BODY}}";
+
+/// The part of the built-in page between `start` and `end`, without `end`.
+fn builtin_section(start: &str, end: &str) -> &'static str {
+    let html = embedded_assets::PREVIEW_HTML;
+    let from = html.find(start).unwrap_or(html.len());
+    let to = html[from..].rfind(end).map_or(html.len(), |i| from + i);
+    &html[from..to]
 }
 
-fn serve_embedded(data: &[u8], content_type_str: &str) -> Response<std::io::Cursor<Vec<u8>>> {
-    Response::from_data(data.to_vec())
-        .with_header(content_type(content_type_str))
+/// The preview page of `project_dir`: its `preview_template.html` with the
+/// placeholders filled in, or the built-in page when it has none.
+pub fn render_preview_html(project_dir: &Path) -> Result<Cow<'static, str>, String> {
+    let path = project_dir.join(PREVIEW_TEMPLATE_FILE);
+    let template = match std::fs::read_to_string(&path) {
+        Ok(template) => template,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Cow::Borrowed(embedded_assets::PREVIEW_HTML)),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let wrong: Vec<String> = [HEAD_PLACEHOLDER, BODY_PLACEHOLDER]
+        .iter()
+        .filter_map(|placeholder| match template.matches(placeholder).count() {
+            1 => None,
+            0 => Some(format!("{} is missing", placeholder)),
+            n => Some(format!("{} appears {} times", placeholder, n)),
+        })
+        .collect();
+    if !wrong.is_empty() {
+        return Err(format!("{}: {}", PREVIEW_TEMPLATE_FILE, wrong.join("; ")));
+    }
+    let head = builtin_section("<script type=\"importmap\">", "</head>");
+    let body = builtin_section("<canvas", "</body>");
+    Ok(Cow::Owned(template.replace(HEAD_PLACEHOLDER, head).replace(BODY_PLACEHOLDER, body)))
+}
+
+// =============================================================================
+// Response Builders
+// =============================================================================
+
+fn serve_html(project_dir: &Path) -> Response<std::io::Cursor<Vec<u8>>> {
+    let (html, status) = match render_preview_html(project_dir) {
+        Ok(html) => (html.into_owned(), 200),
+        Err(e) => {
+            eprintln!("[Preview] {}", e);
+            let message = e.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            (format!("<!DOCTYPE html><pre style=\"color:#ff6b6b\">{}</pre>", message), 500)
+        }
+    };
+    Response::from_data(html.into_bytes())
+        .with_status_code(status)
+        .with_header(content_type("text/html"))
         .with_header(no_cache())
         .with_header(cors())
 }
 
 struct EmbeddedAsset {
     url_path: &'static str,
-    data: &'static [u8],
+    data: Embedded,
 }
 
 const EMBEDDED_ASSETS: &[EmbeddedAsset] = &[
@@ -288,28 +343,91 @@ fn resolve_disk_path(url_path: &str) -> String {
     }
 }
 
-fn find_embedded(url_path: &str) -> Option<&'static [u8]> {
+fn find_embedded(url_path: &str) -> Option<Embedded> {
     EMBEDDED_ASSETS.iter()
         .find(|a| a.url_path == url_path)
         .map(|a| a.data)
 }
 
-fn serve_public_or_embedded(public_dir: &PathBuf, path: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+/// An engine or SDK file and, for embedded ones, its precomputed hash.
+type EngineFile = (Cow<'static, [u8]>, Option<&'static str>);
+
+/// Finds an engine or SDK file in the local engine build, else in the
+/// engine the project selects. An installed version only serves its own
+/// files, so versions never mix.
+fn find_engine_file(project_dir: &Path, public_dir: &Path, path: &str) -> Result<Option<EngineFile>, String> {
+    // `wasm/physics.js` and `.wasm` are the module of the project's
+    // physics backend.
+    let path = &match path.strip_prefix("wasm/") {
+        Some(name) => format!("wasm/{}", runtimes::physics_file(project_dir, name)),
+        None => path.to_string(),
+    };
     let disk_path = resolve_disk_path(path);
-    let full_path = public_dir.join(&disk_path);
-    if full_path.starts_with(public_dir) {
-        if let Ok(data) = std::fs::read(&full_path) {
-            return Response::from_data(data)
-                .with_header(content_type(get_mime_type(path)))
-                .with_header(no_cache())
-                .with_header(cors());
+    if let Some(data) = engines::dev_file(&disk_path) {
+        return Ok(Some((Cow::Owned(data), None)));
+    }
+    let dir = match Engine::for_project(project_dir)? {
+        Engine::Installed { dir, .. } => dir,
+        Engine::Embedded => {
+            let full_path = public_dir.join(&disk_path);
+            if full_path.starts_with(public_dir) {
+                if let Ok(data) = std::fs::read(&full_path) {
+                    return Ok(Some((Cow::Owned(data), None)));
+                }
+            }
+            if let Some(file) = find_embedded(path) {
+                return Ok(Some((Cow::Borrowed(file.bytes()), Some(file.sha256()))));
+            }
+            return match path.strip_prefix("wasm/").filter(|name| runtimes::file(name).is_some()) {
+                // A runtime the editor was built without is fetched here on
+                // first use; later requests read the downloaded copy.
+                Some(name) => tauri::async_runtime::block_on(runtimes::load(None, name)).map(|data| Some((data, None))),
+                None => Ok(None),
+            };
         }
+    };
+    let full_path = dir.join(&disk_path);
+    if !full_path.starts_with(&dir) {
+        return Ok(None);
     }
+    Ok(std::fs::read(&full_path).ok().map(|data| (Cow::Owned(data), None)))
+}
 
-    if let Some(data) = find_embedded(path) {
-        return serve_embedded(data, get_mime_type(path));
+/// Serves an engine or SDK file with a strong ETag, its SHA-256, and
+/// answers 304 when the browser already has that version.
+fn serve_engine_file(
+    project_dir: &Path,
+    public_dir: &Path,
+    path: &str,
+    if_none_match: Option<&str>,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    let (data, hash) = match find_engine_file(project_dir, public_dir, path) {
+        Ok(Some(file)) => file,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return Response::from_string(e)
+                .with_status_code(404)
+                .with_header(content_type("text/plain"))
+                .with_header(cors())
+        }
+    };
+    let etag = match hash {
+        Some(hash) => format!("\"{}\"", hash),
+        None => format!("\"{:x}\"", Sha256::digest(&data)),
+    };
+    let etag_header = Header::from_bytes("ETag", etag.as_bytes()).unwrap();
+    if if_none_match.is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag)) {
+        return Response::from_data(Vec::new())
+            .with_status_code(304)
+            .with_header(etag_header)
+            .with_header(no_cache())
+            .with_header(cors());
     }
-    not_found()
+    Response::from_data(data.into_owned())
+        .with_header(content_type(get_mime_type(path)))
+        .with_header(etag_header)
+        .with_header(no_cache())
+        .with_header(cors())
 }
 
 fn serve_project_file(project_dir: &PathBuf, path: &str) -> Response<std::io::Cursor<Vec<u8>>> {
@@ -423,15 +541,25 @@ fn no_cache() -> Header {
     Header::from_bytes("Cache-Control", "no-cache").unwrap()
 }
 
-fn get_mime_type(path: &str) -> &'static str {
+pub(crate) fn get_mime_type(path: &str) -> &'static str {
     match path.rsplit('.').next() {
         Some("html") => "text/html",
-        Some("js") => "application/javascript",
+        Some("js") | Some("mjs") => "application/javascript",
         Some("wasm") => "application/wasm",
         Some("json") => "application/json",
         Some("css") => "text/css",
         Some("png") => "image/png",
         Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("webmanifest") => "application/manifest+json",
+        Some("txt") => "text/plain",
         Some("mp3") => "audio/mpeg",
         Some("wav") => "audio/wav",
         Some("ogg") => "audio/ogg",
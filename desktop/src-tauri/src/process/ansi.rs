@@ -0,0 +1,319 @@
+//! ANSI escape parsing — coloured tool output for the console panel
+//!
+//! Only SGR sequences (`ESC [ … m`) mean anything to the console; they turn
+//! into styled spans. Every other escape (cursor movement, window titles,
+//! hyperlinks) is dropped. As in a terminal, a style stays in effect across
+//! lines until it is reset.
+
+use serde::{Deserialize, Serialize};
+
+/// The 16 basic colours, as the console panel's dark theme shows them.
+const PALETTE: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5", "#666666", "#f14c4c",
+    "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// What happens to escape codes in command output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnsiMode {
+    /// Passed through unchanged.
+    #[default]
+    Raw,
+    /// Removed; only the text is kept.
+    Strip,
+    /// Removed, with the styled spans sent alongside the text.
+    Spans,
+    /// Rendered as escaped HTML with inline-styled `<span>`s.
+    Html,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AnsiStyle {
+    /// CSS colour, e.g. `#cd3131`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub dim: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub underline: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnsiSpan {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: AnsiStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Color {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct State {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+}
+
+/// Parses a stream of lines, carrying the current style between them.
+#[derive(Debug, Default)]
+pub struct AnsiParser {
+    state: State,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+impl AnsiParser {
+    pub fn parse(&mut self, input: &str) -> Vec<AnsiSpan> {
+        let mut spans = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                if !c.is_control() || c == '\t' {
+                    text.push(c);
+                }
+                continue;
+            }
+            match chars.next() {
+                // CSI: parameters, then a final byte in @..~
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut command = None;
+                    for ch in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&ch) {
+                            command = Some(ch);
+                            break;
+                        }
+                        params.push(ch);
+                    }
+                    if command == Some('m') {
+                        self.push_span(&mut spans, &mut text);
+                        self.apply_sgr(&params);
+                    }
+                }
+                // OSC: ends with BEL or ESC \
+                Some(']') => {
+                    while let Some(ch) = chars.next() {
+                        if ch == '\x07' {
+                            break;
+                        }
+                        if ch == '\x1b' {
+                            chars.next_if_eq(&'\\');
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.push_span(&mut spans, &mut text);
+        spans
+    }
+
+    fn push_span(&self, spans: &mut Vec<AnsiSpan>, text: &mut String) {
+        if text.is_empty() {
+            return;
+        }
+        let style = self.style();
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.text.push_str(text),
+            _ => spans.push(AnsiSpan { text: text.clone(), style }),
+        }
+        text.clear();
+    }
+
+    fn style(&self) -> AnsiStyle {
+        let state = &self.state;
+        let (fg, bg) = if state.inverse {
+            // Unset colours swap as the panel's defaults would.
+            (state.bg.or(Some(Color::Indexed(0))), state.fg.or(Some(Color::Indexed(7))))
+        } else {
+            (state.fg, state.bg)
+        };
+        AnsiStyle {
+            fg: fg.map(css_color),
+            bg: bg.map(css_color),
+            bold: state.bold,
+            dim: state.dim,
+            italic: state.italic,
+            underline: state.underline,
+        }
+    }
+
+    fn apply_sgr(&mut self, params: &str) {
+        let groups: Vec<&str> = if params.is_empty() { vec!["0"] } else { params.split(';').collect() };
+        let state = &mut self.state;
+        let mut i = 0;
+        while i < groups.len() {
+            let mut parts = groups[i].split(':');
+            match parts.next().and_then(|code| code.parse::<u32>().ok()).unwrap_or(0) {
+                0 => *state = State::default(),
+                1 => state.bold = true,
+                2 => state.dim = true,
+                3 => state.italic = true,
+                4 => state.underline = true,
+                7 => state.inverse = true,
+                22 => {
+                    state.bold = false;
+                    state.dim = false;
+                }
+                23 => state.italic = false,
+                24 => state.underline = false,
+                27 => state.inverse = false,
+                code @ 30..=37 => state.fg = Some(Color::Indexed((code - 30) as u8)),
+                39 => state.fg = None,
+                code @ 40..=47 => state.bg = Some(Color::Indexed((code - 40) as u8)),
+                49 => state.bg = None,
+                code @ 90..=97 => state.fg = Some(Color::Indexed((code - 90 + 8) as u8)),
+                code @ 100..=107 => state.bg = Some(Color::Indexed((code - 100 + 8) as u8)),
+                code @ (38 | 48) => {
+                    // `38:5:n` keeps its arguments in the group, `38;5;n`
+                    // in the groups that follow.
+                    let sub: Vec<&str> = parts.collect();
+                    let color = if sub.is_empty() {
+                        let (color, used) = extended_color(&groups[i + 1..], false);
+                        i += used;
+                        color
+                    } else {
+                        extended_color(&sub, true).0
+                    };
+                    let target = if code == 38 { &mut state.fg } else { &mut state.bg };
+                    if color.is_some() {
+                        *target = color;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// The colour after 38 or 48: `5, n` or `2, r, g, b`. Returns it and how
+/// many parameters it took.
+fn extended_color(parts: &[&str], colon: bool) -> (Option<Color>, usize) {
+    let value = |i: usize| parts.get(i).and_then(|v| v.parse::<u32>().ok()).map(|v| v.min(255) as u8);
+    match parts.first().copied() {
+        Some("5") => (value(1).map(Color::Indexed), 2.min(parts.len())),
+        Some("2") => {
+            // The colon form may put a colour space id before r, g, b.
+            let start = if colon && parts.len() >= 5 { 2 } else { 1 };
+            let color = match (value(start), value(start + 1), value(start + 2)) {
+                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                _ => None,
+            };
+            (color, (start + 3).min(parts.len()))
+        }
+        _ => (None, 0),
+    }
+}
+
+fn css_color(color: Color) -> String {
+    match color {
+        Color::Indexed(n @ 0..=15) => PALETTE[n as usize].to_string(),
+        Color::Indexed(n @ 16..=231) => {
+            let n = n - 16;
+            let (r, g, b) = (CUBE_LEVELS[(n / 36) as usize], CUBE_LEVELS[(n / 6 % 6) as usize], CUBE_LEVELS[(n % 6) as usize]);
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        }
+        Color::Indexed(n) => {
+            let level = 8 + 10 * (n - 232);
+            format!("#{:02x}{:02x}{:02x}", level, level, level)
+        }
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+pub fn plain_text(spans: &[AnsiSpan]) -> String {
+    spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+/// Escaped HTML; styled text is wrapped in `<span style="…">`.
+pub fn to_html(spans: &[AnsiSpan]) -> String {
+    let mut html = String::new();
+    for span in spans {
+        let text = escape_html(&span.text);
+        let style = &span.style;
+        let mut css = Vec::new();
+        if let Some(fg) = &style.fg {
+            css.push(format!("color:{}", fg));
+        }
+        if let Some(bg) = &style.bg {
+            css.push(format!("background-color:{}", bg));
+        }
+        if style.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if style.dim {
+            css.push("opacity:0.7".to_string());
+        }
+        if style.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if style.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        if css.is_empty() {
+            html.push_str(&text);
+        } else {
+            html.push_str(&format!("<span style=\"{}\">{}</span>", css.join(";"), text));
+        }
+    }
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Splits text with escape codes, e.g. a saved command log, into spans.
+#[tauri::command]
+pub fn parse_ansi(text: String) -> Vec<Vec<AnsiSpan>> {
+    let mut parser = AnsiParser::default();
+    text.lines().map(|line| parser.parse(line)).collect()
+}
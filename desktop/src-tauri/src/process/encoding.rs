@@ -0,0 +1,75 @@
+//! Output decoding — tool output to text
+//!
+//! Tools write UTF-8 almost everywhere, but on Windows many still write in
+//! the system code page: GBK on Chinese systems, Shift_JIS on Japanese ones.
+//! Each line is taken as UTF-8 when it is valid, otherwise decoded with the
+//! console (OEM) code page, then the ANSI one, and only then lossily.
+
+use encoding_rs::Encoding;
+
+pub fn decode_line(bytes: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    for encoding in system_encodings() {
+        if let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+            return text.into_owned();
+        }
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[cfg(windows)]
+fn system_encodings() -> &'static [&'static Encoding] {
+    use std::sync::OnceLock;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetOEMCP() -> u32;
+        fn GetACP() -> u32;
+    }
+
+    static ENCODINGS: OnceLock<Vec<&'static Encoding>> = OnceLock::new();
+    ENCODINGS.get_or_init(|| {
+        // SAFETY: both only read the system locale.
+        let pages = unsafe { [GetOEMCP(), GetACP()] };
+        let mut encodings: Vec<&'static Encoding> = Vec::new();
+        for encoding in pages.into_iter().filter_map(for_code_page) {
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding);
+            }
+        }
+        encodings
+    })
+}
+
+#[cfg(not(windows))]
+fn system_encodings() -> &'static [&'static Encoding] {
+    &[]
+}
+
+/// Windows code pages that have an encoding_rs equivalent. The DOS code
+/// pages of Western systems (437, 850) do not; their ANSI page is used.
+#[cfg(windows)]
+fn for_code_page(code_page: u32) -> Option<&'static Encoding> {
+    use encoding_rs::*;
+    Some(match code_page {
+        866 => IBM866,
+        874 => WINDOWS_874,
+        932 => SHIFT_JIS,
+        936 => GBK,
+        949 => EUC_KR,
+        950 => BIG5,
+        1250 => WINDOWS_1250,
+        1251 => WINDOWS_1251,
+        1252 => WINDOWS_1252,
+        1253 => WINDOWS_1253,
+        1254 => WINDOWS_1254,
+        1255 => WINDOWS_1255,
+        1256 => WINDOWS_1256,
+        1257 => WINDOWS_1257,
+        1258 => WINDOWS_1258,
+        54936 => GB18030,
+        _ => return None,
+    })
+}
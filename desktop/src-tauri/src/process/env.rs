@@ -0,0 +1,193 @@
+//! Command environment — variables, PATH and tool locations for commands
+//!
+//! GUI apps on macOS start with launchd's minimal PATH instead of the one the
+//! user's shell profile builds, so tools installed through Homebrew, nvm or
+//! volta are not found. The login shell's PATH is read once and put in front
+//! of the inherited one. A project can also pin tools to specific
+//! executables and add its own search directories through the `tools` key of
+//! `project.esproject`. Toolchains the editor installs itself, such as a
+//! portable Node, are searched last.
+
+use crate::project::settings::{ProjectSettings, ToolSettings};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use tokio::process::Command;
+
+/// A login shell that takes longer than this (e.g. waits for input) is
+/// abandoned and the inherited PATH is used as is.
+#[cfg(unix)]
+const LOGIN_SHELL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+#[cfg(unix)]
+const PATH_MARKER: &str = "__ESENGINE_PATH__";
+
+static LOGIN_PATH: OnceLock<Option<OsString>> = OnceLock::new();
+static MANAGED_DIRS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// Environment for one command, resolved before it is spawned.
+pub struct CommandEnv {
+    vars: HashMap<String, String>,
+    path: OsString,
+    overrides: BTreeMap<String, PathBuf>,
+}
+
+impl CommandEnv {
+    /// `vars` are set on top of the inherited environment; a `PATH` entry
+    /// replaces the inherited PATH. `login_path` adds the login shell's PATH.
+    pub fn new(mut vars: HashMap<String, String>, project_dir: Option<&Path>, login_path: bool) -> Self {
+        let tools = project_dir
+            .and_then(|dir| ProjectSettings::load(dir).ok())
+            .map(|(settings, _)| settings.tools)
+            .unwrap_or_default();
+        let base = project_dir.unwrap_or_else(|| Path::new(""));
+
+        let mut dirs: Vec<PathBuf> = tools.search_path.iter().map(|dir| base.join(dir)).collect();
+        match vars.remove("PATH") {
+            Some(path) => dirs.extend(std::env::split_paths(&path)),
+            None => {
+                if login_path {
+                    if let Some(path) = login_shell_path() {
+                        dirs.extend(std::env::split_paths(path));
+                    }
+                }
+                if let Some(path) = std::env::var_os("PATH") {
+                    dirs.extend(std::env::split_paths(&path));
+                }
+                if let Ok(managed) = MANAGED_DIRS.read() {
+                    dirs.extend(managed.iter().cloned());
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        dirs.retain(|dir| !dir.as_os_str().is_empty() && seen.insert(dir.clone()));
+        let path = std::env::join_paths(&dirs).unwrap_or_else(|_| std::env::var_os("PATH").unwrap_or_default());
+
+        Self { vars, path, overrides: overrides(&tools, base) }
+    }
+
+    /// The executable `cmd` runs: the project's override, the first match on
+    /// the resolved PATH, or `cmd` itself when it is a path or not found.
+    pub fn resolve(&self, cmd: &str) -> PathBuf {
+        self.find(cmd).unwrap_or_else(|| PathBuf::from(cmd))
+    }
+
+    /// Like `resolve`, but `None` when a bare command is not on the PATH.
+    pub fn find(&self, cmd: &str) -> Option<PathBuf> {
+        if let Some(path) = self.overrides.get(cmd) {
+            return Some(path.clone());
+        }
+        if cmd.contains(['/', '\\']) {
+            return Some(PathBuf::from(cmd));
+        }
+        std::env::split_paths(&self.path).find_map(|dir| find_executable(&dir, cmd))
+    }
+
+    /// Variables to set on the child, PATH included.
+    pub fn vars(&self) -> impl Iterator<Item = (&OsStr, &OsStr)> {
+        self.vars
+            .iter()
+            .map(|(key, value)| (OsStr::new(key), OsStr::new(value)))
+            .chain(std::iter::once((OsStr::new("PATH"), self.path.as_os_str())))
+    }
+
+    pub fn apply(&self, command: &mut Command) {
+        command.envs(self.vars());
+    }
+}
+
+fn overrides(tools: &ToolSettings, base: &Path) -> BTreeMap<String, PathBuf> {
+    tools
+        .paths
+        .iter()
+        .filter(|(_, path)| !path.trim().is_empty())
+        .map(|(tool, path)| (tool.clone(), base.join(path)))
+        .collect()
+}
+
+#[cfg(windows)]
+fn find_executable(dir: &Path, cmd: &str) -> Option<PathBuf> {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    if Path::new(cmd).extension().is_some() {
+        let path = dir.join(cmd);
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| dir.join(format!("{}{}", cmd, ext.to_ascii_lowercase())))
+        .find(|path| path.is_file())
+}
+
+#[cfg(not(windows))]
+fn find_executable(dir: &Path, cmd: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(cmd);
+    let metadata = std::fs::metadata(&path).ok()?;
+    (metadata.is_file() && metadata.permissions().mode() & 0o111 != 0).then_some(path)
+}
+
+/// Adds a directory of editor-installed tools to every command's PATH,
+/// after the user's own directories.
+pub fn add_managed_dir(dir: PathBuf) {
+    if let Ok(mut dirs) = MANAGED_DIRS.write() {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+}
+
+/// Whether commands get the login shell's PATH unless told otherwise.
+pub fn login_path_default() -> bool {
+    cfg!(target_os = "macos")
+}
+
+/// The PATH an interactive terminal would have; resolved on first use.
+pub fn login_shell_path() -> Option<&'static OsString> {
+    LOGIN_PATH.get_or_init(read_login_path).as_ref()
+}
+
+#[cfg(unix)]
+fn read_login_path() -> Option<OsString> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let shell = std::env::var("SHELL").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| "/bin/sh".to_string());
+    // fish keeps PATH as a list.
+    let script = if shell.ends_with("fish") {
+        format!("printf '%s%s' {} (string join : $PATH)", PATH_MARKER)
+    } else {
+        format!("printf '%s%s' {} \"$PATH\"", PATH_MARKER)
+    };
+    let mut child = std::process::Command::new(&shell)
+        .args(["-l", "-c", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Profiles may print banners before the marker, or never exit.
+    let mut stdout = child.stdout.take()?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        let _ = tx.send(output);
+    });
+    let output = rx.recv_timeout(LOGIN_SHELL_TIMEOUT);
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let output = output.ok()?;
+    let (_, path) = output.rsplit_once(PATH_MARKER)?;
+    let path = path.trim();
+    (!path.is_empty()).then(|| OsString::from(path))
+}
+
+#[cfg(not(unix))]
+fn read_login_path() -> Option<OsString> {
+    None
+}
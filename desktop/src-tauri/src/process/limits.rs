@@ -0,0 +1,198 @@
+//! Resource limits and usage of managed processes
+//!
+//! A process can be started with a lower scheduling priority, so a runaway
+//! asset script leaves the editor responsive, and on Linux with a memory
+//! limit. Both are inherited by everything the process starts. On Windows
+//! the priority becomes the process's priority class; memory limits are
+//! not applied there or on macOS.
+//!
+//! `get_process_stats` reports the CPU and memory use of a running process
+//! together with its children, for the tasks panel.
+
+use super::ProcessRegistry;
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+use tokio::process::Command;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    /// Yields to the editor and other normal work.
+    Low,
+    /// Runs only when nothing else wants the CPU.
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    #[serde(default)]
+    pub priority: Option<ProcessPriority>,
+    /// Heap limit of each process in the tree; Linux only.
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessStats {
+    pub id: u64,
+    pub pid: u32,
+    /// Since the previous sample; 100 is one core fully busy.
+    pub cpu_percent: f32,
+    /// Resident memory.
+    pub memory_bytes: u64,
+    /// The process and its descendants that were counted.
+    pub processes: usize,
+    pub limits: ResourceLimits,
+}
+
+impl ProcessPriority {
+    #[cfg(unix)]
+    fn nice(self) -> libc::c_int {
+        match self {
+            ProcessPriority::Normal => 0,
+            ProcessPriority::Low => 10,
+            ProcessPriority::Idle => 19,
+        }
+    }
+
+    /// The `CreateProcess` priority class flag; none for normal.
+    #[cfg(windows)]
+    pub(super) fn creation_flag(self) -> u32 {
+        match self {
+            ProcessPriority::Normal => 0,
+            ProcessPriority::Low => 0x0000_4000,
+            ProcessPriority::Idle => 0x0000_0040,
+        }
+    }
+}
+
+// =============================================================================
+// Limits
+// =============================================================================
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.priority.unwrap_or_default() == ProcessPriority::Normal && self.memory_limit_mb.is_none()
+    }
+
+    /// Makes `command` apply the limits in the child before it runs. The
+    /// Windows priority class is set with the other creation flags.
+    pub(super) fn apply(&self, command: &mut Command, name: &str) {
+        if self.is_empty() {
+            return;
+        }
+        #[cfg(not(target_os = "linux"))]
+        if self.memory_limit_mb.is_some() {
+            eprintln!("[Process] Memory limits are not supported on this platform; {} runs without one", name);
+        }
+        #[cfg(not(unix))]
+        let _ = command;
+        #[cfg(unix)]
+        {
+            let _ = name;
+            let nice = self.priority.unwrap_or_default().nice();
+            #[cfg(target_os = "linux")]
+            let memory = self.memory_limit_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+            // SAFETY: the hook only makes async-signal-safe calls between
+            // fork and exec and allocates nothing.
+            unsafe {
+                command.pre_exec(move || {
+                    if nice != 0 && libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    // RLIMIT_DATA rather than RLIMIT_AS: V8 and wasm runtimes
+                    // reserve far more address space than they ever use.
+                    #[cfg(target_os = "linux")]
+                    if let Some(bytes) = memory {
+                        let limit = libc::rlimit { rlim_cur: bytes as libc::rlim_t, rlim_max: bytes as libc::rlim_t };
+                        if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Usage
+// =============================================================================
+
+impl ProcessRegistry {
+    /// Samples the process tree of `id`. CPU use needs two samples, so the
+    /// first call waits briefly and takes both.
+    fn stats(&self, id: u64) -> Result<ProcessStats, String> {
+        let (pid, limits) = {
+            let processes = self.processes.lock().map_err(|e| e.to_string())?;
+            let process = processes.get(&id).ok_or_else(|| format!("No running process with id {}", id))?;
+            (process.pid.ok_or_else(|| format!("Process {} has no pid", id))?, process.limits)
+        };
+
+        let mut system = self.system.lock().map_err(|e| e.to_string())?;
+        let refresh = |system: &mut System| {
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::new().with_cpu().with_memory(),
+            );
+        };
+        let system = match &mut *system {
+            Some(system) => {
+                refresh(system);
+                system
+            }
+            empty => {
+                let mut fresh = System::new();
+                refresh(&mut fresh);
+                std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+                refresh(&mut fresh);
+                empty.insert(fresh)
+            }
+        };
+
+        let root = Pid::from_u32(pid);
+        let root_process = system.process(root).ok_or_else(|| format!("Process {} has exited", id))?;
+        let mut stats = ProcessStats {
+            id,
+            pid,
+            cpu_percent: root_process.cpu_usage(),
+            memory_bytes: root_process.memory(),
+            processes: 1,
+            limits,
+        };
+        // Breadth-first over parent links; a pid reused by an unrelated
+        // process cannot be its own ancestor, so this ends.
+        let mut tree = vec![root];
+        let mut next = 0;
+        while next < tree.len() {
+            let parent = tree[next];
+            next += 1;
+            for (child_pid, child) in system.processes() {
+                if child.parent() == Some(parent) && !tree.contains(child_pid) {
+                    tree.push(*child_pid);
+                    stats.cpu_percent += child.cpu_usage();
+                    stats.memory_bytes += child.memory();
+                    stats.processes += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// CPU and memory use of a running command or tool, children included.
+#[tauri::command]
+pub async fn get_process_stats(app: AppHandle, id: u64) -> Result<ProcessStats, String> {
+    tokio::task::spawn_blocking(move || app.state::<ProcessRegistry>().stats(id))
+        .await
+        .map_err(|e| format!("Process stats task failed: {}", e))?
+}
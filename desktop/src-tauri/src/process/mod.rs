@@ -0,0 +1,726 @@
+//! Managed processes — external tools spawned by the editor
+//!
+//! Long-running tools (butler, CLIs) run through `run`, which registers the
+//! child so the editor can list and cancel it, streams every output line as a
+//! `process-output` event and hands lines to the caller for progress parsing.
+//! Commands the frontend starts with `execute_command` are registered the
+//! same way but return their id at once; their output arrives in batches
+//! as `command-output` (see `output`) and their end as `command-exit`,
+//! followed by a `command-finished` summary for task history. `execute_command_pty`
+//! runs them on a pseudo terminal instead (see `pty`). Either kind can be
+//! sent input with `write_command_stdin` and `close_command_stdin`; a plain
+//! command only keeps its stdin open when started with `stdin: true`.
+//!
+//! A process can belong to a project and carry a timeout. Closing the
+//! project or the editor, or running past the timeout, kills it together
+//! with everything it started, so a hung `npm install` does not outlive it.
+//! It can also run at a lower priority or under a memory limit, and its
+//! CPU and memory use can be sampled (see `limits`).
+//!
+//! On Windows, children get no console window, `npm` resolves to `npm.cmd`
+//! through PATHEXT (see `env`), and output in the system code page is
+//! decoded before it is emitted (see `encoding`).
+
+pub mod ansi;
+pub mod encoding;
+pub mod env;
+pub mod limits;
+pub mod output;
+pub mod pty;
+pub mod retry;
+
+use crate::i18n::BackendError;
+use ansi::AnsiMode;
+use env::{login_path_default, CommandEnv};
+use limits::ResourceLimits;
+use retry::FailureKind;
+use output::{OutputBatch, OutputKind};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{mpsc, oneshot};
+
+/// Output lines kept for error reporting.
+const TAIL_LINES: usize = 50;
+/// `CREATE_NO_WINDOW`: console programs started from the GUI would
+/// otherwise each flash a console window.
+#[cfg(windows)]
+pub(crate) const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+/// How long the editor waits on exit for killed processes to be reaped.
+const EXIT_GRACE: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Default)]
+pub struct ProcessRegistry {
+    next_id: AtomicU64,
+    processes: Mutex<HashMap<u64, ManagedProcess>>,
+    /// Terminals of commands started through `execute_command_pty`.
+    terminals: Mutex<HashMap<u64, pty::Terminal>>,
+    /// Stdin writers of commands started with `stdin: true`.
+    stdins: Mutex<HashMap<u64, mpsc::UnboundedSender<Vec<u8>>>>,
+    /// Output logs of recent commands, oldest first.
+    logs: Mutex<VecDeque<(u64, PathBuf)>>,
+    /// Process table kept between `get_process_stats` samples.
+    system: Mutex<Option<sysinfo::System>>,
+}
+
+struct ManagedProcess {
+    name: String,
+    pid: Option<u32>,
+    cancel: Option<oneshot::Sender<()>>,
+    /// Killed when this project is closed.
+    project: Option<PathBuf>,
+    /// Set for processes started through `execute_command`.
+    command: Option<RunningCommand>,
+    limits: ResourceLimits,
+}
+
+/// How a process is tied to the editor's lifetime.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    /// Killed when this project is closed.
+    pub project: Option<PathBuf>,
+    /// Killed when still running after this long.
+    pub timeout: Option<Duration>,
+    /// Keeps stdin open for `write_command_stdin`; otherwise the process
+    /// reads end of file at once.
+    pub stdin: bool,
+    pub limits: ResourceLimits,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub id: u64,
+    pub name: String,
+    pub pid: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+struct ProcessOutput {
+    id: u64,
+    name: String,
+    stream: String,
+    data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningCommand {
+    pub id: u64,
+    pub cmd: String,
+    pub args: Vec<String>,
+    pub cwd: String,
+    pub pid: Option<u32>,
+    pub started: String,
+    /// File receiving the complete output.
+    pub log: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ProcessTimeout {
+    id: u64,
+    name: String,
+    timeout_secs: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct CommandExit {
+    id: u64,
+    /// `None` when the command was cancelled or could not be waited for.
+    code: Option<i32>,
+    cancelled: bool,
+    timed_out: bool,
+    error: Option<String>,
+}
+
+/// Everything known about a finished command, for task history.
+#[derive(Clone, Serialize)]
+struct CommandFinished {
+    id: u64,
+    cmd: String,
+    args: Vec<String>,
+    cwd: String,
+    started: String,
+    code: Option<i32>,
+    /// The signal that ended it, as the system describes it; Unix only.
+    signal: Option<String>,
+    duration_ms: u64,
+    /// Output written to stdout and stderr, or to the terminal.
+    output_bytes: u64,
+    /// Killed through `kill_command`, its project closing or its timeout.
+    killed: bool,
+    timed_out: bool,
+    error: Option<String>,
+    /// Why it failed, judged from its output; `None` when it succeeded.
+    failure: Option<FailureKind>,
+    log: Option<String>,
+}
+
+/// A registered child whose output has not been consumed yet.
+pub struct RunningProcess {
+    app: AppHandle,
+    id: u64,
+    name: String,
+    child: Child,
+    cancelled: oneshot::Receiver<()>,
+    timeout: Option<Duration>,
+    /// Whether lines are also emitted as `process-output`.
+    process_output: bool,
+}
+
+pub struct ProcessExit {
+    pub code: i32,
+    /// The signal that ended the process, as the system describes it.
+    pub signal: Option<String>,
+    /// Last output lines, stdout and stderr interleaved.
+    pub tail: Vec<String>,
+}
+
+impl CommandExit {
+    fn new(id: u64, code: Option<i32>) -> Self {
+        Self { id, code, cancelled: false, timed_out: false, error: None }
+    }
+}
+
+impl ProcessExit {
+    pub fn success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+pub enum WaitError {
+    /// The process was killed through `kill_process` or `kill_command`, or
+    /// because its project or the editor was closed.
+    Cancelled,
+    /// The process ran past its timeout and was killed.
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl ProcessRegistry {
+    fn register(
+        &self,
+        name: &str,
+        pid: Option<u32>,
+        project: Option<PathBuf>,
+        limits: ResourceLimits,
+    ) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (cancel, cancelled) = oneshot::channel();
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.insert(
+                id,
+                ManagedProcess { name: name.to_string(), pid, cancel: Some(cancel), project, command: None, limits },
+            );
+        }
+        (id, cancelled)
+    }
+
+    fn set_command(&self, info: &RunningCommand) {
+        if let Ok(mut processes) = self.processes.lock() {
+            if let Some(managed) = processes.get_mut(&info.id) {
+                managed.command = Some(info.clone());
+            }
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut processes) = self.processes.lock() {
+            processes.remove(&id);
+        }
+        if let Ok(mut stdins) = self.stdins.lock() {
+            stdins.remove(&id);
+        }
+    }
+
+    fn cancel(&self, id: u64) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let process = processes.get_mut(&id).ok_or_else(|| format!("No running process with id {}", id))?;
+        if let Some(cancel) = process.cancel.take() {
+            let _ = cancel.send(());
+        }
+        Ok(())
+    }
+
+    /// Kills every process started for `project_dir`; returns how many.
+    pub fn kill_project(&self, project_dir: &Path) -> usize {
+        self.kill_where(|p| p.project.as_deref() == Some(project_dir))
+    }
+
+    /// Kills every process and waits briefly for them to be reaped; called
+    /// when the editor exits.
+    pub fn kill_all(&self) {
+        if self.kill_where(|_| true) == 0 {
+            return;
+        }
+        let deadline = std::time::Instant::now() + EXIT_GRACE;
+        while std::time::Instant::now() < deadline && self.processes.lock().is_ok_and(|p| !p.is_empty()) {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// Kills the process trees directly as well as signalling their waiters,
+    /// which may never run again once the editor is shutting down.
+    fn kill_where(&self, mut matches: impl FnMut(&ManagedProcess) -> bool) -> usize {
+        let Ok(mut processes) = self.processes.lock() else {
+            return 0;
+        };
+        let mut killed = 0;
+        for process in processes.values_mut().filter(|p| matches(p)) {
+            if let Some(cancel) = process.cancel.take() {
+                let _ = cancel.send(());
+            }
+            if let Some(pid) = process.pid {
+                kill_tree(pid);
+            }
+            killed += 1;
+        }
+        killed
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_processes(registry: State<ProcessRegistry>) -> Vec<ProcessInfo> {
+    let Ok(processes) = registry.processes.lock() else {
+        return Vec::new();
+    };
+    let mut list: Vec<ProcessInfo> = processes
+        .iter()
+        .map(|(id, p)| ProcessInfo { id: *id, name: p.name.clone(), pid: p.pid })
+        .collect();
+    list.sort_by_key(|p| p.id);
+    list
+}
+
+#[tauri::command]
+pub fn kill_process(registry: State<ProcessRegistry>, id: u64) -> Result<(), String> {
+    registry.cancel(id)
+}
+
+/// Kills every process started for a project, e.g. when it is closed.
+#[tauri::command]
+pub fn kill_project_processes(registry: State<ProcessRegistry>, project_dir: String) -> usize {
+    registry.kill_project(Path::new(&project_dir))
+}
+
+/// Starts `cmd` and returns its id without waiting for it to finish.
+/// `env` is added to the inherited environment. `project_dir` applies the
+/// project's tool overrides; `login_path` (default on macOS) adds the login
+/// shell's PATH so tools installed through the user's profile are found.
+/// The command is killed when `project_dir` is closed or after
+/// `timeout_secs`. `ansi` says what happens to escape codes in its output.
+/// With `stdin`, input can be sent through `write_command_stdin` until
+/// `close_command_stdin`. `limits` lowers its priority or caps its memory.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_command(
+    app: AppHandle,
+    cmd: String,
+    args: Vec<String>,
+    cwd: String,
+    env: Option<HashMap<String, String>>,
+    project_dir: Option<String>,
+    login_path: Option<bool>,
+    timeout_secs: Option<u64>,
+    ansi: Option<AnsiMode>,
+    stdin: Option<bool>,
+    limits: Option<ResourceLimits>,
+) -> Result<RunningCommand, BackendError> {
+    let options = ProcessOptions {
+        project: project_dir.as_ref().map(PathBuf::from),
+        timeout: timeout_secs.map(Duration::from_secs),
+        stdin: stdin.unwrap_or(false),
+        limits: limits.unwrap_or_default(),
+    };
+    let (command_env, program) = prepare(&cmd, env, project_dir, login_path).await?;
+    let mut command = Command::new(program);
+    command.args(&args).current_dir(&cwd);
+    command_env.apply(&mut command);
+    let mut process = start_with(&app, &cmd, command, options)?;
+    // Batched as `command-output` instead.
+    process.process_output = false;
+    let registry = app.state::<ProcessRegistry>();
+    let log = registry.new_log(process.id);
+    let info = RunningCommand {
+        id: process.id,
+        cmd,
+        args,
+        cwd,
+        pid: process.child.id(),
+        started: crate::project::now_iso8601(),
+        log: log.as_ref().map(|path| path.to_string_lossy().into_owned()),
+    };
+    registry.set_command(&info);
+
+    let id = info.id;
+    let started = Instant::now();
+    let output = OutputBatch::start(&app, id, OutputKind::Lines(ansi.unwrap_or_default()), log);
+    let running = info.clone();
+    tokio::spawn(async move {
+        let mut output_bytes = 0;
+        let exit = process
+            .wait(|stream, data| {
+                output_bytes += data.len() as u64 + 1;
+                output.send(stream, data.to_string());
+            })
+            .await;
+        output.finish().await;
+        let (event, signal, tail) = match exit {
+            Ok(exit) => (CommandExit::new(id, Some(exit.code)), exit.signal, exit.tail),
+            Err(WaitError::Cancelled) => (CommandExit { cancelled: true, ..CommandExit::new(id, None) }, None, Vec::new()),
+            Err(WaitError::TimedOut(_)) => {
+                (CommandExit { timed_out: true, ..CommandExit::new(id, None) }, None, Vec::new())
+            }
+            Err(WaitError::Failed(e)) => (CommandExit { error: Some(e), ..CommandExit::new(id, None) }, None, Vec::new()),
+        };
+        emit_exit(&app, &running, event, signal, &tail, started, output_bytes);
+    });
+    Ok(info)
+}
+
+/// Commands started through `execute_command` that are still running.
+#[tauri::command]
+pub fn list_running_commands(registry: State<ProcessRegistry>) -> Vec<RunningCommand> {
+    let Ok(processes) = registry.processes.lock() else {
+        return Vec::new();
+    };
+    let mut list: Vec<RunningCommand> = processes.values().filter_map(|p| p.command.clone()).collect();
+    list.sort_by_key(|c| c.id);
+    list
+}
+
+/// The executable `execute_command` would run for `cmd`, or `None` when it
+/// is not found.
+#[tauri::command]
+pub async fn resolve_command(
+    cmd: String,
+    project_dir: Option<String>,
+    login_path: Option<bool>,
+) -> Result<Option<String>, String> {
+    let (_, program) = prepare(&cmd, None, project_dir, login_path).await?;
+    Ok(program.is_file().then(|| program.to_string_lossy().into_owned()))
+}
+
+/// Kills a command started through `execute_command`; its `command-exit`
+/// event reports it as cancelled.
+#[tauri::command]
+pub fn kill_command(registry: State<ProcessRegistry>, id: u64) -> Result<(), String> {
+    registry.cancel(id)
+}
+
+/// Sends input, e.g. an answer to a prompt or keystrokes from the terminal
+/// panel, to a running command. Nothing is appended; a line needs its `\n`.
+#[tauri::command]
+pub fn write_command_stdin(registry: State<ProcessRegistry>, id: u64, data: String) -> Result<(), String> {
+    if let Some(result) = registry.write_terminal(id, data.as_bytes()) {
+        return result;
+    }
+    let stdins = registry.stdins.lock().map_err(|e| e.to_string())?;
+    let stdin = stdins.get(&id).ok_or_else(|| format!("Command {} does not accept input", id))?;
+    stdin.send(data.into_bytes()).map_err(|_| format!("Command {} closed its input", id))
+}
+
+/// Closes a command's input so tools reading to the end of it can finish.
+#[tauri::command]
+pub fn close_command_stdin(registry: State<ProcessRegistry>, id: u64) -> Result<(), String> {
+    if registry.close_terminal_input(id) {
+        return Ok(());
+    }
+    // Dropping the sender ends the writer task, which closes the pipe.
+    let removed = registry.stdins.lock().map_err(|e| e.to_string())?.remove(&id);
+    removed.map(|_| ()).ok_or_else(|| format!("Command {} does not accept input", id))
+}
+
+// =============================================================================
+// Running
+// =============================================================================
+
+/// Builds the environment for a frontend command and resolves its
+/// executable; may start the login shell, so it runs off the async runtime.
+async fn prepare(
+    cmd: &str,
+    env: Option<HashMap<String, String>>,
+    project_dir: Option<String>,
+    login_path: Option<bool>,
+) -> Result<(CommandEnv, PathBuf), String> {
+    let cmd = cmd.to_string();
+    tokio::task::spawn_blocking(move || {
+        let command_env = CommandEnv::new(
+            env.unwrap_or_default(),
+            project_dir.as_deref().map(Path::new),
+            login_path.unwrap_or_else(login_path_default),
+        );
+        let program = command_env.resolve(&cmd);
+        (command_env, program)
+    })
+    .await
+    .map_err(|e| format!("Command setup task failed: {}", e))
+}
+
+/// Runs `command` to completion as a managed process. `on_line` receives
+/// `(stream, line)` for every output line. Cancellation kills the child and
+/// returns an error.
+pub async fn run(
+    app: &AppHandle,
+    name: &str,
+    command: Command,
+    on_line: impl FnMut(&str, &str),
+) -> Result<ProcessExit, String> {
+    start(app, name, command)?.wait(on_line).await.map_err(|e| match e {
+        WaitError::Cancelled => format!("{} was cancelled", name),
+        WaitError::TimedOut(timeout) => format!("{} timed out after {} s", name, timeout.as_secs()),
+        WaitError::Failed(e) => e,
+    })
+}
+
+/// Spawns and registers `command` without waiting for it.
+pub fn start(app: &AppHandle, name: &str, command: Command) -> Result<RunningProcess, String> {
+    start_with(app, name, command, ProcessOptions::default()).map_err(String::from)
+}
+
+/// `start` for a process tied to a project or limited by a timeout.
+pub fn start_with(
+    app: &AppHandle,
+    name: &str,
+    mut command: Command,
+    options: ProcessOptions,
+) -> Result<RunningProcess, BackendError> {
+    // Its own process group, so the whole tree can be killed at once.
+    #[cfg(unix)]
+    command.process_group(0);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW | options.limits.priority.unwrap_or_default().creation_flag());
+    options.limits.apply(&mut command, name);
+    let mut child = command
+        .stdin(if options.stdin { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            BackendError::new("process.startFailed", [("name", name.to_string()), ("error", e.to_string())])
+        })?;
+    let registry = app.state::<ProcessRegistry>();
+    let (id, cancelled) = registry.register(name, child.id(), options.project, options.limits);
+    if let Some(stdin) = child.stdin.take() {
+        if let Ok(mut stdins) = registry.stdins.lock() {
+            stdins.insert(id, pipe_stdin(stdin));
+        }
+    }
+    Ok(RunningProcess {
+        app: app.clone(),
+        id,
+        name: name.to_string(),
+        child,
+        cancelled,
+        timeout: options.timeout,
+        process_output: true,
+    })
+}
+
+impl RunningProcess {
+    /// Streams output until the child exits, then unregisters it.
+    pub async fn wait(mut self, mut on_line: impl FnMut(&str, &str)) -> Result<ProcessExit, WaitError> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        if let Some(stdout) = self.child.stdout.take() {
+            tokio::spawn(forward_lines(stdout, "stdout", tx.clone()));
+        }
+        if let Some(stderr) = self.child.stderr.take() {
+            tokio::spawn(forward_lines(stderr, "stderr", tx.clone()));
+        }
+        drop(tx);
+
+        let (app, id, name) = (self.app.clone(), self.id, self.name.clone());
+        let deadline = self.timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        let mut tail = Vec::new();
+        let mut stopped = None;
+        loop {
+            tokio::select! {
+                line = rx.recv() => {
+                    let Some((stream, data)) = line else { break };
+                    if self.process_output {
+                        let _ = app.emit(
+                            "process-output",
+                            ProcessOutput { id, name: name.to_string(), stream: stream.to_string(), data: data.clone() },
+                        );
+                    }
+                    on_line(stream, &data);
+                    if tail.len() == TAIL_LINES {
+                        tail.remove(0);
+                    }
+                    tail.push(data);
+                }
+                _ = &mut self.cancelled, if stopped.is_none() => {
+                    stopped = Some(WaitError::Cancelled);
+                    self.kill();
+                }
+                _ = sleep_until(deadline), if stopped.is_none() && deadline.is_some() => {
+                    let timeout = self.timeout.unwrap_or_default();
+                    emit_timeout(&app, id, &name, timeout);
+                    stopped = Some(WaitError::TimedOut(timeout));
+                    self.kill();
+                }
+            }
+        }
+
+        let status = self.child.wait().await;
+        app.state::<ProcessRegistry>().remove(id);
+        if let Some(reason) = stopped {
+            return Err(reason);
+        }
+        let status = status.map_err(|e| WaitError::Failed(e.to_string()))?;
+        Ok(ProcessExit { code: status.code().unwrap_or(-1), signal: exit_signal(&status), tail })
+    }
+}
+
+impl RunningProcess {
+    fn kill(&mut self) {
+        if let Some(pid) = self.child.id() {
+            kill_tree(pid);
+        }
+        let _ = self.child.start_kill();
+    }
+}
+
+/// Kills `pid` and every process it started. Unix children run in their own
+/// process group, which is signalled as a whole.
+pub(crate) fn kill_tree(pid: u32) {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill(2) has no memory effects; a stale group id fails
+        // with ESRCH.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        let _ = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Feeds what `write_command_stdin` sends to the child, in order, until the
+/// sender is dropped or the child stops reading.
+fn pipe_stdin(mut stdin: ChildStdin) -> mpsc::UnboundedSender<Vec<u8>> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    tokio::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            if stdin.write_all(&data).await.is_err() || stdin.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+    tx
+}
+
+/// Resolves at `deadline`, or never without one.
+async fn sleep_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Emits `command-exit` and then the `command-finished` summary; a failure
+/// is classified from `tail`, the last output lines.
+fn emit_exit(
+    app: &AppHandle,
+    info: &RunningCommand,
+    exit: CommandExit,
+    signal: Option<String>,
+    tail: &[String],
+    started: Instant,
+    output_bytes: u64,
+) {
+    let failure = if exit.cancelled {
+        Some(FailureKind::Cancelled)
+    } else if exit.timed_out {
+        Some(FailureKind::Timeout)
+    } else if exit.error.is_some() {
+        Some(FailureKind::Unknown)
+    } else {
+        (exit.code != Some(0)).then(|| retry::classify(tail, &[]))
+    };
+    let finished = CommandFinished {
+        id: info.id,
+        cmd: info.cmd.clone(),
+        args: info.args.clone(),
+        cwd: info.cwd.clone(),
+        started: info.started.clone(),
+        code: exit.code,
+        signal,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output_bytes,
+        killed: exit.cancelled || exit.timed_out,
+        timed_out: exit.timed_out,
+        error: exit.error.clone(),
+        failure,
+        log: info.log.clone(),
+    };
+    let _ = app.emit("command-exit", exit);
+    let _ = app.emit("command-finished", finished);
+}
+
+/// Describes the signal that ended a process, e.g. `Segmentation fault`.
+#[cfg(unix)]
+fn exit_signal(status: &std::process::ExitStatus) -> Option<String> {
+    use std::os::unix::process::ExitStatusExt;
+    let signal = status.signal()?;
+    // SAFETY: strsignal returns a pointer to a static or thread-local
+    // string, copied before anything else can change it.
+    let name = unsafe {
+        let name = libc::strsignal(signal);
+        (!name.is_null()).then(|| std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned())
+    };
+    Some(name.unwrap_or_else(|| format!("signal {}", signal)))
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &std::process::ExitStatus) -> Option<String> {
+    None
+}
+
+fn emit_timeout(app: &AppHandle, id: u64, name: &str, timeout: Duration) {
+    let _ = app.emit("process-timeout", ProcessTimeout { id, name: name.to_string(), timeout_secs: timeout.as_secs() });
+}
+
+async fn forward_lines<R: AsyncRead + Unpin>(
+    reader: R,
+    stream: &'static str,
+    tx: mpsc::UnboundedSender<(&'static str, String)>,
+) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let end = line.iter().rposition(|b| !matches!(b, b'\n' | b'\r')).map_or(0, |i| i + 1);
+                if tx.send((stream, encoding::decode_line(&line[..end]))).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Command output batching — keeps verbose tools from flooding the webview
+//!
+//! Output of frontend commands is collected and emitted as one
+//! `command-output` (or, for terminals, `command-data`) event every
+//! `FLUSH_INTERVAL`, or sooner once `FLUSH_LINES` lines are waiting. Each
+//! interval has a budget; what a tool prints beyond it is left out of the
+//! events and replaced with a truncation marker. Everything is written to a
+//! log file in the temp folder, which `get_command_log` returns in full.
+//! Escape codes in lines are kept, stripped or rendered per `AnsiMode`; the
+//! log always has them as the tool wrote them.
+
+use super::ansi::{self, AnsiMode, AnsiParser, AnsiSpan};
+use super::ProcessRegistry;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+const FLUSH_INTERVAL: Duration = Duration::from_millis(30);
+const FLUSH_LINES: usize = 500;
+/// Per-interval budget; about 60k lines or 8 MB a second.
+const MAX_INTERVAL_LINES: usize = 2000;
+const MAX_INTERVAL_BYTES: usize = 256 * 1024;
+/// Logs of finished commands kept for `get_command_log`.
+const KEPT_LOGS: usize = 20;
+pub(crate) const LOG_DIR: &str = "esengine-command-logs";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum OutputKind {
+    /// Lines from stdout and stderr, emitted as `command-output`.
+    Lines(AnsiMode),
+    /// Raw terminal data, emitted as `command-data`.
+    Terminal,
+}
+
+#[derive(Clone, Serialize)]
+struct OutputLine {
+    /// `stdout`, `stderr`, or `truncated` for a marker.
+    stream: String,
+    data: String,
+    /// Styled parts of `data` in `AnsiMode::Spans`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spans: Option<Vec<AnsiSpan>>,
+}
+
+#[derive(Clone, Serialize)]
+struct CommandOutput {
+    id: u64,
+    lines: Vec<OutputLine>,
+}
+
+#[derive(Clone, Serialize)]
+struct CommandData {
+    id: u64,
+    data: String,
+}
+
+/// Feeds a command's output to its batching task.
+pub(super) struct OutputBatch {
+    tx: mpsc::UnboundedSender<(String, String)>,
+    task: JoinHandle<()>,
+}
+
+impl OutputBatch {
+    pub fn start(app: &AppHandle, id: u64, kind: OutputKind, log: Option<PathBuf>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let batcher = Batcher {
+            app: app.clone(),
+            id,
+            kind,
+            lines: Vec::new(),
+            lines_sent: 0,
+            bytes: 0,
+            omitted_lines: 0,
+            omitted_bytes: 0,
+            ansi: AnsiParser::default(),
+            log: log.and_then(|path| File::create(path).ok()).map(BufWriter::new),
+        };
+        Self { tx, task: tokio::spawn(batcher.run(rx)) }
+    }
+
+    pub fn send(&self, stream: &str, data: String) {
+        let _ = self.tx.send((stream.to_string(), data));
+    }
+
+    /// Emits what is still waiting; returns once the last event is out.
+    pub async fn finish(self) {
+        drop(self.tx);
+        let _ = self.task.await;
+    }
+}
+
+struct Batcher {
+    app: AppHandle,
+    id: u64,
+    kind: OutputKind,
+    lines: Vec<OutputLine>,
+    /// Lines and bytes emitted or waiting in this interval.
+    lines_sent: usize,
+    bytes: usize,
+    omitted_lines: usize,
+    omitted_bytes: usize,
+    ansi: AnsiParser,
+    log: Option<BufWriter<File>>,
+}
+
+impl Batcher {
+    async fn run(mut self, mut rx: mpsc::UnboundedReceiver<(String, String)>) {
+        let mut tick = tokio::time::interval(FLUSH_INTERVAL);
+        tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                output = rx.recv() => {
+                    let Some((stream, data)) = output else { break };
+                    self.push(stream, data);
+                    if self.lines.len() >= FLUSH_LINES {
+                        self.flush();
+                    }
+                }
+                _ = tick.tick() => {
+                    self.flush();
+                    self.lines_sent = 0;
+                    self.bytes = 0;
+                }
+            }
+        }
+        self.flush();
+        if let Some(log) = &mut self.log {
+            let _ = log.flush();
+        }
+    }
+
+    fn push(&mut self, stream: String, data: String) {
+        if self.kind == OutputKind::Terminal && data.is_empty() {
+            return;
+        }
+        if let Some(log) = &mut self.log {
+            let _ = match self.kind {
+                OutputKind::Lines(_) => writeln!(log, "{}", data),
+                OutputKind::Terminal => log.write_all(data.as_bytes()),
+            };
+        }
+        if self.lines_sent >= MAX_INTERVAL_LINES || self.bytes + data.len() > MAX_INTERVAL_BYTES {
+            self.omitted_lines += 1;
+            self.omitted_bytes += data.len();
+            return;
+        }
+        self.lines_sent += 1;
+        self.bytes += data.len();
+        let (data, spans) = match self.kind {
+            OutputKind::Lines(AnsiMode::Strip) => (ansi::plain_text(&self.ansi.parse(&data)), None),
+            OutputKind::Lines(AnsiMode::Spans) => {
+                let spans = self.ansi.parse(&data);
+                (ansi::plain_text(&spans), Some(spans))
+            }
+            OutputKind::Lines(AnsiMode::Html) => (ansi::to_html(&self.ansi.parse(&data)), None),
+            _ => (data, None),
+        };
+        self.lines.push(OutputLine { stream, data, spans });
+    }
+
+    fn flush(&mut self) {
+        if self.omitted_lines > 0 {
+            let data = match self.kind {
+                OutputKind::Lines(_) => format!("[{} lines truncated; the full output is in the log]", self.omitted_lines),
+                OutputKind::Terminal => {
+                    format!("\r\n[{} bytes truncated; the full output is in the log]\r\n", self.omitted_bytes)
+                }
+            };
+            self.lines.push(OutputLine { stream: "truncated".to_string(), data, spans: None });
+            self.omitted_lines = 0;
+            self.omitted_bytes = 0;
+        }
+        if self.lines.is_empty() {
+            return;
+        }
+        let lines = std::mem::take(&mut self.lines);
+        let _ = match self.kind {
+            OutputKind::Lines(_) => self.app.emit("command-output", CommandOutput { id: self.id, lines }),
+            OutputKind::Terminal => {
+                let data = lines.into_iter().map(|line| line.data).collect();
+                self.app.emit("command-data", CommandData { id: self.id, data })
+            }
+        };
+        if let Some(log) = &mut self.log {
+            let _ = log.flush();
+        }
+    }
+}
+
+// =============================================================================
+// Logs
+// =============================================================================
+
+impl ProcessRegistry {
+    /// Reserves the log file of command `id`, dropping the oldest logs
+    /// beyond `KEPT_LOGS`.
+    pub(super) fn new_log(&self, id: u64) -> Option<PathBuf> {
+        let dir = std::env::temp_dir().join(LOG_DIR);
+        std::fs::create_dir_all(&dir).ok()?;
+        let path = dir.join(format!("command-{}-{}.log", std::process::id(), id));
+        let mut logs = self.logs.lock().ok()?;
+        logs.push_back((id, path.clone()));
+        while logs.len() > KEPT_LOGS {
+            if let Some((_, old)) = logs.pop_front() {
+                let _ = std::fs::remove_file(old);
+            }
+        }
+        Some(path)
+    }
+
+    /// Deletes every command log; called when the editor exits.
+    pub fn remove_logs(&self) {
+        if let Ok(mut logs) = self.logs.lock() {
+            for (_, path) in logs.drain(..) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// The complete output of a running or recently finished command.
+#[tauri::command]
+pub async fn get_command_log(registry: State<'_, ProcessRegistry>, id: u64) -> Result<String, String> {
+    let path = registry
+        .logs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .find(|(log_id, _)| *log_id == id)
+        .map(|(_, path)| path.clone())
+        .ok_or_else(|| format!("No log for command {}", id))?;
+    tokio::task::spawn_blocking(move || {
+        std::fs::read(&path)
+            .map(|data| String::from_utf8_lossy(&data).into_owned())
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    })
+    .await
+    .map_err(|e| format!("Log read task failed: {}", e))?
+}
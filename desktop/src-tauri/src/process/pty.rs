@@ -0,0 +1,269 @@
+//! PTY-backed commands — for tools that only show colours, progress bars and
+//! prompts when attached to a terminal
+//!
+//! `execute_command_pty` runs the command on a pseudo terminal and emits its
+//! raw output, escape sequences included, in batched `command-data` events
+//! for the terminal panel to render. Keystrokes go back through `write_command_stdin` and the
+//! terminal follows the panel's size through `resize_command`. `close_command_stdin` sends
+//! end of file, as Ctrl-D would. The command is
+//! registered like any other, so `kill_command`, `list_running_commands` and
+//! `command-exit` and `command-finished` work unchanged.
+
+use super::limits::ResourceLimits;
+use super::output::{OutputBatch, OutputKind};
+use super::{
+    emit_exit, emit_timeout, kill_tree, prepare, sleep_until, CommandExit, ProcessRegistry, RunningCommand, WaitError,
+};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::mpsc;
+
+/// How long output still buffered in the terminal is forwarded after exit.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+const READ_BUFFER: usize = 8192;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+pub(super) struct Terminal {
+    master: Box<dyn MasterPty + Send>,
+    /// Dropped to send end of file.
+    writer: Option<Box<dyn Write + Send>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TerminalSize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+impl From<TerminalSize> for PtySize {
+    fn from(size: TerminalSize) -> Self {
+        PtySize { rows: size.rows.max(1), cols: size.cols.max(1), pixel_width: 0, pixel_height: 0 }
+    }
+}
+
+/// Decodes raw output, holding back a multi-byte character split between
+/// two reads until the rest of it arrives.
+#[derive(Default)]
+struct Utf8Stream {
+    pending: Vec<u8>,
+}
+
+impl Utf8Stream {
+    fn push(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => self.pending.len(),
+        };
+        let rest = self.pending.split_off(complete);
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending = rest;
+        text
+    }
+
+    fn finish(&mut self) -> String {
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        text
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Like `execute_command`, but on a pseudo terminal of `size` (80x24 by
+/// default). Output arrives as `command-data`; there is no `command-output`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_command_pty(
+    app: AppHandle,
+    cmd: String,
+    args: Vec<String>,
+    cwd: String,
+    env: Option<HashMap<String, String>>,
+    project_dir: Option<String>,
+    login_path: Option<bool>,
+    timeout_secs: Option<u64>,
+    size: Option<TerminalSize>,
+) -> Result<RunningCommand, String> {
+    let project = project_dir.as_ref().map(PathBuf::from);
+    let (command_env, program) = prepare(&cmd, env, project_dir, login_path).await?;
+
+    let pair = native_pty_system()
+        .openpty(size.unwrap_or_default().into())
+        .map_err(|e| format!("Failed to open a terminal: {}", e))?;
+    let reader = pair.master.try_clone_reader().map_err(|e| format!("Failed to open a terminal: {}", e))?;
+    let writer = pair.master.take_writer().map_err(|e| format!("Failed to open a terminal: {}", e))?;
+
+    let mut builder = CommandBuilder::new(program);
+    builder.args(&args);
+    builder.cwd(&cwd);
+    // GUI apps have no TERM; without it tools assume a dumb terminal.
+    builder.env("TERM", "xterm-256color");
+    for (key, value) in command_env.vars() {
+        builder.env(key, value);
+    }
+    let mut child = pair.slave.spawn_command(builder).map_err(|e| format!("Failed to start {}: {}", cmd, e))?;
+    // The child holds its own handle; ours would keep the terminal open
+    // after it exits.
+    drop(pair.slave);
+    let mut killer = child.clone_killer();
+
+    let pid = child.process_id();
+    let (info, mut cancelled, log) = {
+        let registry = app.state::<ProcessRegistry>();
+        let (id, cancelled) = registry.register(&cmd, pid, project, ResourceLimits::default());
+        let log = registry.new_log(id);
+        let info = RunningCommand {
+            id,
+            cmd,
+            args,
+            cwd,
+            pid,
+            started: crate::project::now_iso8601(),
+            log: log.as_ref().map(|path| path.to_string_lossy().into_owned()),
+        };
+        registry.set_command(&info);
+        if let Ok(mut terminals) = registry.terminals.lock() {
+            terminals.insert(id, Terminal { master: pair.master, writer: Some(writer) });
+        }
+        (info, cancelled, log)
+    };
+    let id = info.id;
+    let name = info.cmd.clone();
+    let started = Instant::now();
+    let running = info.clone();
+    let output = OutputBatch::start(&app, id, OutputKind::Terminal, log);
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        let mut reader = reader;
+        let mut buffer = [0u8; READ_BUFFER];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if tx.send(buffer[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut decoder = Utf8Stream::default();
+        let mut output_bytes = 0;
+        let mut wait = tokio::task::spawn_blocking(move || child.wait());
+        let timeout = timeout_secs.map(Duration::from_secs);
+        let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        let mut stopped = None;
+        let mut kill = || {
+            // The child leads its own session, so its group is the whole tree.
+            if let Some(pid) = pid {
+                kill_tree(pid);
+            }
+            let _ = killer.kill();
+        };
+        let status = loop {
+            tokio::select! {
+                Some(chunk) = rx.recv() => {
+                    output_bytes += chunk.len() as u64;
+                    output.send("terminal", decoder.push(&chunk));
+                }
+                _ = &mut cancelled, if stopped.is_none() => {
+                    stopped = Some(WaitError::Cancelled);
+                    kill();
+                }
+                _ = sleep_until(deadline), if stopped.is_none() && deadline.is_some() => {
+                    let timeout = timeout.unwrap_or_default();
+                    emit_timeout(&app, id, &name, timeout);
+                    stopped = Some(WaitError::TimedOut(timeout));
+                    kill();
+                }
+                status = &mut wait => break status,
+            }
+        };
+
+        // Closing the terminal ends the reader once the rest is read.
+        let registry = app.state::<ProcessRegistry>();
+        if let Ok(mut terminals) = registry.terminals.lock() {
+            terminals.remove(&id);
+        }
+        while let Ok(Some(chunk)) = tokio::time::timeout(DRAIN_TIMEOUT, rx.recv()).await {
+            output_bytes += chunk.len() as u64;
+            output.send("terminal", decoder.push(&chunk));
+        }
+        output.send("terminal", decoder.finish());
+        output.finish().await;
+        registry.remove(id);
+
+        let mut signal = None;
+        let event = match (stopped, status) {
+            (Some(WaitError::TimedOut(_)), _) => CommandExit { timed_out: true, ..CommandExit::new(id, None) },
+            (Some(_), _) => CommandExit { cancelled: true, ..CommandExit::new(id, None) },
+            (None, Ok(Ok(status))) => {
+                signal = status.signal().map(str::to_string);
+                CommandExit::new(id, Some(status.exit_code() as i32))
+            }
+            (None, Ok(Err(e))) => CommandExit { error: Some(e.to_string()), ..CommandExit::new(id, None) },
+            (None, Err(e)) => CommandExit { error: Some(format!("Wait task failed: {}", e)), ..CommandExit::new(id, None) },
+        };
+        emit_exit(&app, &running, event, signal, &[], started, output_bytes);
+    });
+    Ok(info)
+}
+
+impl ProcessRegistry {
+    /// Writes to command `id`'s terminal; `None` when it has none.
+    pub(super) fn write_terminal(&self, id: u64, data: &[u8]) -> Option<Result<(), String>> {
+        let mut terminals = self.terminals.lock().ok()?;
+        let terminal = terminals.get_mut(&id)?;
+        let Some(writer) = terminal.writer.as_mut() else {
+            return Some(Err(format!("Command {} closed its input", id)));
+        };
+        Some(
+            writer
+                .write_all(data)
+                .and_then(|_| writer.flush())
+                .map_err(|e| format!("Failed to write to command {}: {}", id, e)),
+        )
+    }
+
+    /// Sends end of file to command `id`'s terminal; false when it has none.
+    pub(super) fn close_terminal_input(&self, id: u64) -> bool {
+        let Ok(mut terminals) = self.terminals.lock() else {
+            return false;
+        };
+        match terminals.get_mut(&id) {
+            // portable-pty writes the EOF character when its writer is dropped.
+            Some(terminal) => {
+                terminal.writer = None;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn resize_command(registry: State<ProcessRegistry>, id: u64, size: TerminalSize) -> Result<(), String> {
+    let terminals = registry.terminals.lock().map_err(|e| e.to_string())?;
+    let terminal = terminals.get(&id).ok_or_else(|| format!("Command {} has no terminal", id))?;
+    terminal.master.resize(size.into()).map_err(|e| format!("Failed to resize command {}: {}", id, e))
+}
@@ -0,0 +1,129 @@
+//! Failure classification and retries for external commands
+//!
+//! When a command fails, its last output lines are matched against pattern
+//! rules to tell a network hiccup (a dropped connection during
+//! `npm install`) from a compile error or a missing tool. Projects can add
+//! their own rules ahead of the built-in ones. A `RetryPolicy` then decides
+//! whether the failure is worth another attempt and how long to wait first;
+//! the wait doubles with every attempt.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Longest wait between two attempts.
+const MAX_DELAY: Duration = Duration::from_secs(60);
+const DEFAULT_DELAY_MS: u64 = 2000;
+
+/// Built-in rules, checked in order; the first kind with a matching line
+/// wins.
+const BUILTIN_RULES: &[(FailureKind, &str)] = &[
+    (
+        FailureKind::MissingTool,
+        r"command not found|is not recognized as an internal or external command|spawn \S+ ENOENT",
+    ),
+    (
+        FailureKind::Compile,
+        r"error TS\d+:|\berror\[E\d{4}\]|^\S+?:\d+(:\d+)?:? (fatal )?error\b|SyntaxError|^ERROR in |\[ERROR\]|\b(compilation|build) failed\b",
+    ),
+    (
+        FailureKind::Network,
+        r"\b(ETIMEDOUT|ECONNRESET|ECONNREFUSED|ECONNABORTED|EAI_AGAIN|ENOTFOUND|ENETUNREACH|EHOSTUNREACH|ERR_SOCKET_TIMEOUT)\b|socket hang up|network (is )?unreachable|getaddrinfo|could not resolve host|fetch failed|tls handshake|connection (timed out|reset|refused)|\b50[234] (bad gateway|service unavailable|gateway time-?out)",
+    ),
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The network or a server failed; usually worth retrying.
+    Network,
+    /// The code or data the command processed is broken.
+    Compile,
+    /// The command or a tool it runs is not installed.
+    MissingTool,
+    Timeout,
+    Cancelled,
+    Unknown,
+}
+
+/// A project's own pattern: output lines matching `pattern` (a
+/// case-insensitive regex) mean a failure of `kind`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureRule {
+    pub pattern: String,
+    pub kind: FailureKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included.
+    pub attempts: u32,
+    /// Wait before the second attempt; doubles for every one after it.
+    #[serde(default = "default_delay_ms")]
+    pub delay_ms: u64,
+    /// Failures worth another attempt; network failures when unset.
+    #[serde(default = "default_retry_on")]
+    pub on: Vec<FailureKind>,
+}
+
+fn default_delay_ms() -> u64 {
+    DEFAULT_DELAY_MS
+}
+
+fn default_retry_on() -> Vec<FailureKind> {
+    vec![FailureKind::Network]
+}
+
+// =============================================================================
+// Classification
+// =============================================================================
+
+fn builtin_rules() -> &'static [(FailureKind, Regex)] {
+    static RULES: OnceLock<Vec<(FailureKind, Regex)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        BUILTIN_RULES
+            .iter()
+            .filter_map(|(kind, pattern)| Some((*kind, compile(pattern)?)))
+            .collect()
+    })
+}
+
+fn compile(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern).case_insensitive(true).multi_line(true).build().ok()
+}
+
+/// Classifies a failed command by its output, trying `rules` before the
+/// built-in ones. Invalid project patterns are skipped.
+pub fn classify(lines: &[String], rules: &[FailureRule]) -> FailureKind {
+    let matches = |regex: &Regex| lines.iter().any(|line| regex.is_match(line));
+    for rule in rules {
+        match compile(&rule.pattern) {
+            Some(regex) if matches(&regex) => return rule.kind,
+            Some(_) => {}
+            None => eprintln!("[Process] Invalid failure pattern '{}'", rule.pattern),
+        }
+    }
+    builtin_rules().iter().find(|(_, regex)| matches(regex)).map_or(FailureKind::Unknown, |(kind, _)| *kind)
+}
+
+// =============================================================================
+// Retrying
+// =============================================================================
+
+impl RetryPolicy {
+    /// How long to wait before trying again after attempt `attempt`
+    /// (counting from 1) failed with `failure`; `None` to give up.
+    pub fn retry_after(&self, attempt: u32, failure: FailureKind) -> Option<Duration> {
+        if attempt >= self.attempts || !self.on.contains(&failure) {
+            return None;
+        }
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Some(Duration::from_millis(self.delay_ms.saturating_mul(factor)).min(MAX_DELAY))
+    }
+}
@@ -0,0 +1,334 @@
+//! Project health check — static validation for the Problems panel
+//!
+//! Catches the mistakes that otherwise surface only at preview or deploy
+//! time: references to deleted assets, a start scene that no longer exists
+//! (which previews as a black screen), paths whose case differs from the file
+//! on disk (fine on Windows and macOS, broken on case-sensitive CDNs and
+//! mini-game platforms), `.meta` files left behind by deletions, scenes
+//! that no build profile includes, and a project preview template without
+//! its placeholders.
+
+use super::settings::ProjectSettings;
+use crate::export::assets::{self, AssetDatabase};
+use crate::export::profiles::{load_profiles, resolve_profile_options};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectProblem {
+    pub severity: Severity,
+    /// Stable identifier of the check, e.g. `missing-reference`.
+    pub code: &'static str,
+    pub message: String,
+    /// Project-relative file the problem was found in.
+    pub file: Option<String>,
+    /// The offending reference as written in `file`.
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectCheckReport {
+    pub problems: Vec<ProjectProblem>,
+    pub errors: usize,
+    pub warnings: usize,
+    pub files_checked: usize,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn check_project(project_dir: String) -> Result<ProjectCheckReport, String> {
+    tokio::task::spawn_blocking(move || run_checks(Path::new(&project_dir)))
+        .await
+        .map_err(|e| format!("Check task failed: {}", e))?
+}
+
+pub fn run_checks(project_dir: &Path) -> Result<ProjectCheckReport, String> {
+    if !project_dir.is_dir() {
+        return Err(format!("Project folder not found: {}", project_dir.display()));
+    }
+    let mut check = Check {
+        project_dir,
+        db: AssetDatabase::scan(project_dir),
+        files: BTreeSet::new(),
+        lowercase: HashMap::new(),
+        report: ProjectCheckReport::default(),
+    };
+    for path in assets::walk_files(&project_dir.join("assets")) {
+        let rel = assets::relative_path(project_dir, &path);
+        check.lowercase.insert(rel.to_lowercase(), rel.clone());
+        check.files.insert(rel);
+    }
+
+    let default_scene = check.start_scene();
+    check.references();
+    check.meta_files();
+    check.build_list(default_scene);
+    check.preview_template();
+
+    let mut report = check.report;
+    report.problems.sort_by(|a, b| (a.severity, &a.file).cmp(&(b.severity, &b.file)));
+    report.errors = report.problems.iter().filter(|p| p.severity == Severity::Error).count();
+    report.warnings = report.problems.iter().filter(|p| p.severity == Severity::Warning).count();
+    Ok(report)
+}
+
+// =============================================================================
+// Checks
+// =============================================================================
+
+struct Check<'a> {
+    project_dir: &'a Path,
+    db: AssetDatabase,
+    /// Project-relative paths of every file under `assets/`.
+    files: BTreeSet<String>,
+    lowercase: HashMap<String, String>,
+    report: ProjectCheckReport,
+}
+
+impl Check<'_> {
+    fn problem(
+        &mut self,
+        severity: Severity,
+        code: &'static str,
+        message: String,
+        file: Option<&str>,
+        reference: Option<&str>,
+    ) {
+        self.report.problems.push(ProjectProblem {
+            severity,
+            code,
+            message,
+            file: file.map(String::from),
+            reference: reference.map(String::from),
+        });
+    }
+
+    /// Validates the project's start scene and returns it.
+    fn start_scene(&mut self) -> Option<String> {
+        let settings = match ProjectSettings::load(self.project_dir) {
+            Ok((settings, _)) => settings,
+            Err(e) => {
+                self.problem(Severity::Error, "project-file", e, Some(super::PROJECT_FILE), None);
+                return None;
+            }
+        };
+        let Some(scene) = settings.default_scene else {
+            self.problem(
+                Severity::Error,
+                "start-scene-missing",
+                "No start scene is set; preview and builds will show a black screen".to_string(),
+                Some(super::PROJECT_FILE),
+                None,
+            );
+            return None;
+        };
+        match self.lookup(&scene) {
+            Lookup::Found => {}
+            Lookup::CaseMismatch(actual) => self.problem(
+                Severity::Warning,
+                "case-mismatch",
+                format!("Start scene '{}' only matches '{}' when case is ignored", scene, actual),
+                Some(super::PROJECT_FILE),
+                Some(&scene),
+            ),
+            Lookup::Missing => self.problem(
+                Severity::Error,
+                "start-scene-missing",
+                format!("Start scene '{}' does not exist; preview will show a black screen", scene),
+                Some(super::PROJECT_FILE),
+                Some(&scene),
+            ),
+        }
+        Some(scene)
+    }
+
+    /// Unresolvable and case-mismatched references in JSON assets, Spine
+    /// atlases and BMFont descriptors.
+    fn references(&mut self) {
+        let files: Vec<String> = self.files.iter().cloned().collect();
+        for rel in files {
+            let refs = if assets::is_json_asset(&rel) {
+                let Some(value) = std::fs::read_to_string(self.project_dir.join(&rel))
+                    .ok()
+                    .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                else {
+                    self.problem(
+                        Severity::Error,
+                        "invalid-json",
+                        "File is not valid JSON and cannot be loaded".to_string(),
+                        Some(&rel),
+                        None,
+                    );
+                    continue;
+                };
+                let mut refs = Vec::new();
+                assets::collect_json_refs(&value, &mut refs);
+                refs
+            } else if matches!(assets::extension_of(&rel).as_str(), "atlas" | "fnt") {
+                assets::text_asset_deps(&self.project_dir.join(&rel), &rel)
+            } else {
+                continue;
+            };
+            self.report.files_checked += 1;
+
+            let base_dir = assets::parent_dir(&rel);
+            let unique: BTreeSet<String> = refs.into_iter().collect();
+            for reference in unique {
+                self.reference(&rel, &base_dir, &reference);
+            }
+        }
+    }
+
+    fn reference(&mut self, rel: &str, base_dir: &str, reference: &str) {
+        if assets::is_uuid(reference) {
+            if self.db.path_of(reference).is_none() {
+                self.problem(
+                    Severity::Error,
+                    "missing-reference",
+                    format!("References an asset that no longer exists (UUID {})", reference),
+                    Some(rel),
+                    Some(reference),
+                );
+            }
+            return;
+        }
+        let cleaned = reference.trim_start_matches("./").trim_start_matches('/');
+        let candidates =
+            [assets::normalize_path(cleaned), assets::normalize_path(&format!("{}/{}", base_dir, cleaned))];
+        let mut mismatch = None;
+        for candidate in candidates.iter().flatten() {
+            match self.lookup(candidate) {
+                Lookup::Found => return,
+                Lookup::CaseMismatch(actual) => mismatch = mismatch.or(Some(actual)),
+                Lookup::Missing => {}
+            }
+        }
+        match mismatch {
+            Some(actual) => self.problem(
+                Severity::Warning,
+                "case-mismatch",
+                format!(
+                    "'{}' differs in case from '{}' and will not load on case-sensitive hosts",
+                    reference, actual
+                ),
+                Some(rel),
+                Some(reference),
+            ),
+            None => self.problem(
+                Severity::Error,
+                "missing-reference",
+                format!("References '{}', which does not exist", reference),
+                Some(rel),
+                Some(reference),
+            ),
+        }
+    }
+
+    /// `.meta` files without their asset, and UUIDs claimed twice.
+    fn meta_files(&mut self) {
+        let metas: Vec<String> = self.files.iter().filter(|f| f.ends_with(".meta")).cloned().collect();
+        let mut uuids: HashMap<String, String> = HashMap::new();
+        for meta in metas {
+            let asset = &meta[..meta.len() - ".meta".len()];
+            if !self.files.contains(asset) && !self.project_dir.join(asset).is_dir() {
+                self.problem(
+                    Severity::Warning,
+                    "orphaned-meta",
+                    format!("'{}' has no matching asset and can be deleted", meta),
+                    Some(&meta),
+                    None,
+                );
+                continue;
+            }
+            let uuid = std::fs::read_to_string(self.project_dir.join(&meta))
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v.get("uuid").and_then(Value::as_str).map(String::from));
+            if let Some(uuid) = uuid {
+                if let Some(first) = uuids.insert(uuid.clone(), asset.to_string()) {
+                    self.problem(
+                        Severity::Error,
+                        "duplicate-uuid",
+                        format!("Shares UUID {} with '{}'; references to either are ambiguous", uuid, first),
+                        Some(&meta),
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Scenes in the project that no build profile exports.
+    /// A broken `preview_template.html` fails every preview.
+    fn preview_template(&mut self) {
+        if let Err(e) = crate::preview_server::render_preview_html(self.project_dir) {
+            let file = crate::preview_server::PREVIEW_TEMPLATE_FILE;
+            self.problem(Severity::Error, "preview-template", e, Some(file), None);
+        }
+    }
+
+    fn build_list(&mut self, default_scene: Option<String>) {
+        let mut included: BTreeSet<String> = default_scene.into_iter().collect();
+        if let Ok(profiles) = load_profiles(self.project_dir) {
+            for profile in &profiles.profiles {
+                if let Ok(options) = resolve_profile_options(self.project_dir, Some(&profile.name)) {
+                    included.extend(options.scenes);
+                }
+            }
+        }
+        let roots: Vec<String> = included.iter().cloned().collect();
+        let reachable = assets::collect_references(self.project_dir, &self.db, &roots);
+
+        let scenes: Vec<String> = self
+            .files
+            .iter()
+            .filter(|f| assets::extension_of(f) == "esscene" && !reachable.contains(*f))
+            .cloned()
+            .collect();
+        for scene in scenes {
+            self.problem(
+                Severity::Info,
+                "scene-not-in-build",
+                "Scene is not part of any build profile and will not be exported".to_string(),
+                Some(&scene),
+                None,
+            );
+        }
+    }
+
+    fn lookup(&self, rel: &str) -> Lookup {
+        if self.files.contains(rel) {
+            return Lookup::Found;
+        }
+        match self.lowercase.get(&rel.to_lowercase()) {
+            Some(actual) => Lookup::CaseMismatch(actual.clone()),
+            // Files outside `assets/` are not indexed; fall back to the disk.
+            None if self.project_dir.join(rel).is_file() => Lookup::Found,
+            None => Lookup::Missing,
+        }
+    }
+}
+
+enum Lookup {
+    Found,
+    /// Only a file differing in case exists, carrying its actual path.
+    CaseMismatch(String),
+    Missing,
+}
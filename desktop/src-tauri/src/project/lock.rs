@@ -0,0 +1,286 @@
+//! Project lock — keeps two editor instances from editing the same project
+//!
+//! Opening a project creates `.esengine/project.lock` with the owner's PID,
+//! hostname and a per-instance id; the file is created exclusively, so of
+//! two instances opening the project at once only one gets it. An existing
+//! lock is only overwritten when it is stale, ours, or taken over, and is
+//! read back afterwards. While held, a background thread refreshes
+//! the lock's heartbeat. A lock is stale when its process is gone (same host)
+//! or its heartbeat has not moved for `STALE_AFTER` (other hosts, e.g. a
+//! project on a network share); stale locks are taken over silently, live
+//! ones only when the user asks for a takeover. An instance that loses its
+//! lock to a takeover gets a `project-lock-lost` event.
+
+use super::{now_iso8601, write_atomic};
+use crate::bridge_server::is_process_alive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+
+const LOCK_FILE: &str = ".esengine/project.lock";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+const STALE_AFTER: Duration = Duration::from_secs(120);
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub hostname: String,
+    /// Distinguishes editor instances, since PIDs are reused.
+    pub instance: String,
+    pub acquired: String,
+    /// Unix seconds of the last heartbeat.
+    pub heartbeat: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockStatus {
+    /// This instance holds the lock.
+    pub acquired: bool,
+    /// The other holder, when the project is locked by someone else.
+    pub holder: Option<LockInfo>,
+    /// The holder's lock is abandoned and will be taken over on acquire.
+    pub stale: bool,
+}
+
+/// Project folders whose lock this instance holds.
+#[derive(Default)]
+pub struct ProjectLocks {
+    held: Mutex<HashSet<PathBuf>>,
+    heartbeat: OnceLock<()>,
+}
+
+impl ProjectLocks {
+    /// Removes every lock this instance holds; called on exit.
+    pub fn release_all(&self) {
+        let held = std::mem::take(&mut *self.held.lock().unwrap_or_else(|e| e.into_inner()));
+        for dir in held {
+            release(&dir);
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Reports who, if anyone, holds the project's lock.
+#[tauri::command]
+pub fn get_project_lock_status(project_dir: String) -> LockStatus {
+    let project_dir = Path::new(&project_dir);
+    match read(project_dir) {
+        Some(lock) if owned(&lock) => LockStatus {
+            acquired: true,
+            holder: None,
+            stale: false,
+        },
+        Some(lock) => LockStatus {
+            stale: is_stale(&lock),
+            acquired: false,
+            holder: Some(lock),
+        },
+        None => LockStatus {
+            acquired: false,
+            holder: None,
+            stale: false,
+        },
+    }
+}
+
+/// Acquires the lock. A live lock held by another instance is only taken
+/// over with `takeover`; otherwise its holder is returned.
+#[tauri::command]
+pub fn acquire_project_lock(
+    app: AppHandle,
+    locks: State<'_, ProjectLocks>,
+    project_dir: String,
+    takeover: bool,
+) -> Result<LockStatus, String> {
+    let dir = PathBuf::from(&project_dir);
+    let acquired = now_iso8601();
+    match create(&dir, &acquired) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            match read(&dir) {
+                Some(lock) if !owned(&lock) && !takeover && !is_stale(&lock) => {
+                    return Ok(LockStatus {
+                        acquired: false,
+                        holder: Some(lock),
+                        stale: false,
+                    });
+                }
+                // Being written by another instance right now.
+                None if !takeover && !is_stale_file(&dir) => {
+                    return Err("The project is being opened by another editor; try again".to_string());
+                }
+                _ => {}
+            }
+            write(&dir, &acquired)?;
+            // Another instance may have overwritten the same stale lock.
+            if let Some(lock) = read(&dir).filter(|lock| !owned(lock)) {
+                return Ok(LockStatus {
+                    acquired: false,
+                    holder: Some(lock),
+                    stale: false,
+                });
+            }
+        }
+        Err(e) => return Err(format!("Failed to create {}: {}", dir.join(LOCK_FILE).display(), e)),
+    }
+
+    crate::storage::auto_cleanup(&app, Some(dir.clone()));
+    locks.held.lock().unwrap_or_else(|e| e.into_inner()).insert(dir);
+    locks.heartbeat.get_or_init(|| spawn_heartbeat(app));
+    Ok(LockStatus {
+        acquired: true,
+        holder: None,
+        stale: false,
+    })
+}
+
+#[tauri::command]
+pub fn release_project_lock(locks: State<'_, ProjectLocks>, project_dir: String) {
+    let dir = PathBuf::from(&project_dir);
+    if locks.held.lock().unwrap_or_else(|e| e.into_inner()).remove(&dir) {
+        release(&dir);
+    }
+}
+
+// =============================================================================
+// Lock file
+// =============================================================================
+
+fn read(project_dir: &Path) -> Option<LockInfo> {
+    let text = std::fs::read_to_string(project_dir.join(LOCK_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn lock_data(acquired: &str) -> Vec<u8> {
+    let lock = LockInfo {
+        pid: std::process::id(),
+        hostname: hostname().to_string(),
+        instance: instance_id().to_string(),
+        acquired: acquired.to_string(),
+        heartbeat: unix_now(),
+    };
+    serde_json::to_vec_pretty(&lock).unwrap_or_default()
+}
+
+/// Creates the lock file; fails with `AlreadyExists` when there is one.
+fn create(project_dir: &Path, acquired: &str) -> std::io::Result<()> {
+    let path = project_dir.join(LOCK_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+    let written = file.write_all(&lock_data(acquired)).and_then(|()| file.sync_all());
+    if written.is_err() {
+        let _ = std::fs::remove_file(&path);
+    }
+    written
+}
+
+/// Replaces the lock file; only for stale, own or taken-over locks.
+fn write(project_dir: &Path, acquired: &str) -> Result<(), String> {
+    write_atomic(&project_dir.join(LOCK_FILE), &lock_data(acquired))
+}
+
+/// Deletes the lock file if this instance still owns it.
+fn release(project_dir: &Path) {
+    if read(project_dir).is_some_and(|lock| owned(&lock)) {
+        let _ = std::fs::remove_file(project_dir.join(LOCK_FILE));
+    }
+}
+
+fn owned(lock: &LockInfo) -> bool {
+    lock.instance == instance_id()
+}
+
+/// Whether an unreadable lock file was left behind rather than being written.
+fn is_stale_file(project_dir: &Path) -> bool {
+    let modified = std::fs::metadata(project_dir.join(LOCK_FILE)).and_then(|m| m.modified());
+    modified.map_or(true, |modified| modified.elapsed().unwrap_or_default() > STALE_AFTER)
+}
+
+fn is_stale(lock: &LockInfo) -> bool {
+    if lock.hostname == hostname() && !is_process_alive(lock.pid) {
+        return true;
+    }
+    unix_now().saturating_sub(lock.heartbeat) > STALE_AFTER.as_secs()
+}
+
+/// Refreshes held locks; a lock overwritten by another instance is dropped
+/// and reported to the frontend.
+fn spawn_heartbeat(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEARTBEAT_INTERVAL);
+        let Some(locks) = tauri::Manager::try_state::<ProjectLocks>(&app) else {
+            continue;
+        };
+        let mut held = locks.held.lock().unwrap_or_else(|e| e.into_inner());
+        held.retain(|dir| match read(dir) {
+            Some(lock) if owned(&lock) => {
+                let _ = write(dir, &lock.acquired);
+                true
+            }
+            other => {
+                let _ = app.emit(
+                    "project-lock-lost",
+                    serde_json::json!({
+                        "project_dir": dir.to_string_lossy(),
+                        "holder": other,
+                    }),
+                );
+                false
+            }
+        });
+    });
+}
+
+// =============================================================================
+// Identity
+// =============================================================================
+
+fn instance_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("{:x}-{:x}", std::process::id(), nanos)
+    })
+}
+
+pub(crate) fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .or_else(|| {
+                std::process::Command::new("hostname")
+                    .output()
+                    .ok()
+                    .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+            })
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
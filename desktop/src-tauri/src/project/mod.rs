@@ -0,0 +1,281 @@
+//! Project scaffolding — creates new projects from embedded templates
+//!
+//! `create_project` lays out the folder structure, writes `project.esproject`,
+//! editor settings and the template's scene and scripts, materializes the
+//! SDK and editor type definitions into `.esengine/` (`typings` keeps a
+//! committed copy in `types/`), and records the project in the recent
+//! projects list (see `recent`).
+//! Template ids that are not built in are resolved through the remote
+//! registry (see `registry`).
+
+pub(crate) mod check;
+pub(crate) mod lock;
+pub(crate) mod recent;
+pub(crate) mod registry;
+pub(crate) mod script_templates;
+pub(crate) mod settings;
+pub(crate) mod snapshot;
+pub(crate) mod stats;
+pub(crate) mod templates;
+pub(crate) mod typings;
+
+use crate::embedded_assets::{self, Embedded};
+use crate::engines::Engine;
+use crate::export::build_info::iso8601_utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+pub const PROJECT_FILE: &str = "project.esproject";
+const DEFAULT_SCENE: &str = "assets/scenes/main.esscene";
+
+const DIRECTORIES: &[&str] = &["src", "assets", "assets/scenes", "assets/textures", "assets/audio", ".esengine"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedProject {
+    pub project_dir: String,
+    pub project_file: String,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_project_templates() -> Vec<TemplateInfo> {
+    templates::TEMPLATES
+        .iter()
+        .map(|t| TemplateInfo {
+            id: t.id.to_string(),
+            name: t.name.to_string(),
+            description: t.description.to_string(),
+        })
+        .collect()
+}
+
+/// Creates `<path>/<name>` from a template (`empty` when omitted).
+#[tauri::command]
+pub async fn create_project(
+    app: AppHandle,
+    name: String,
+    path: String,
+    template: Option<String>,
+) -> Result<CreatedProject, String> {
+    validate_name(&name)?;
+    let project_dir = Path::new(&path).join(&name);
+    if project_dir.exists() {
+        return Err(format!("Project directory already exists: {}", project_dir.display()));
+    }
+
+    let id = template.as_deref().unwrap_or(templates::DEFAULT_TEMPLATE);
+    let template = match templates::find(id) {
+        Some(template) => template.resolve(),
+        None => registry::resolve(&app, id).await?,
+    };
+
+    let result = scaffold(&project_dir, &name, &template);
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(&project_dir);
+    }
+    let project_file = result?;
+    recent::register(&app, &name, &project_file);
+
+    Ok(CreatedProject {
+        project_dir: project_dir.to_string_lossy().to_string(),
+        project_file: project_file.to_string_lossy().to_string(),
+    })
+}
+
+fn scaffold(project_dir: &Path, name: &str, template: &templates::TemplateFiles) -> Result<PathBuf, String> {
+    for dir in DIRECTORIES {
+        std::fs::create_dir_all(project_dir.join(dir))
+            .map_err(|e| format!("Failed to create directory {}: {}", dir, e))?;
+    }
+    for (rel, data) in &template.files {
+        write_file(&project_dir.join(rel), data)?;
+    }
+    let design = (template.design.0 as f64, template.design.1 as f64);
+    let project_file = write_project_file(project_dir, name, Some(DEFAULT_SCENE), design)?;
+    write_settings(project_dir, Some(DEFAULT_SCENE))?;
+    write_common_files(project_dir)?;
+    write_sdk(project_dir)?;
+    Ok(project_file)
+}
+
+// =============================================================================
+// Project files
+// =============================================================================
+
+/// Mirrors the launcher's rules, so names valid there are valid here.
+pub(crate) fn validate_name(name: &str) -> Result<(), String> {
+    const RESERVED: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+    let upper = name.to_ascii_uppercase();
+    let reserved = RESERVED.contains(&upper.as_str())
+        || ((upper.starts_with("COM") || upper.starts_with("LPT"))
+            && upper.len() == 4
+            && upper.as_bytes()[3].is_ascii_digit());
+    let invalid = name.chars().any(|c| matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c < ' ');
+    if name.trim().is_empty() || invalid || reserved || name.ends_with('.') || name.ends_with(' ') {
+        return Err("Invalid project name: contains illegal characters or is a reserved name".to_string());
+    }
+    Ok(())
+}
+
+/// Writes `project.esproject` and returns its path.
+pub(crate) fn write_project_file(
+    project_dir: &Path,
+    name: &str,
+    default_scene: Option<&str>,
+    design: (f64, f64),
+) -> Result<PathBuf, String> {
+    let settings = settings::ProjectSettings::new(name, default_scene, design);
+    let path = project_dir.join(PROJECT_FILE);
+    write_atomic(&path, &serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?)?;
+    Ok(path)
+}
+
+/// Editor settings kept with the project (`.esengine/settings.json`).
+pub(crate) fn write_settings(project_dir: &Path, last_scene: Option<&str>) -> Result<(), String> {
+    write_json(&project_dir.join(".esengine/settings.json"), &json!({ "lastOpenedScene": last_scene }))
+}
+
+/// `tsconfig.json` and `.gitignore`.
+pub(crate) fn write_common_files(project_dir: &Path) -> Result<(), String> {
+    write_tsconfig(project_dir)?;
+    write_file(&project_dir.join(".gitignore"), GITIGNORE.as_bytes())
+}
+
+/// The scaffolded `tsconfig.json`, resolving `esengine` to `.esengine/sdk`.
+pub(crate) fn write_tsconfig(project_dir: &Path) -> Result<(), String> {
+    write_file(&project_dir.join("tsconfig.json"), TSCONFIG.as_bytes())
+}
+
+/// Materializes the SDK of the project's engine and the editor type
+/// definitions into `.esengine/`, the same layout the editor refreshes when
+/// it opens a project.
+pub(crate) fn write_sdk(project_dir: &Path) -> Result<(), String> {
+    let engine = Engine::for_project(project_dir)?;
+    let sdk = project_dir.join(".esengine/sdk");
+    let files: &[(&str, Embedded)] = &[
+        ("index.js", embedded_assets::SDK_ESM_JS),
+        ("index.d.ts", embedded_assets::SDK_ESM_DTS),
+        ("wasm.js", embedded_assets::SDK_WASM_JS),
+        ("wasm.d.ts", embedded_assets::SDK_WASM_DTS),
+        ("index.wechat.js", embedded_assets::SDK_WECHAT_JS),
+        ("shared/wasm.d.ts", embedded_assets::SDK_SHARED_WASM_DTS),
+        ("shared/app.d.ts", embedded_assets::SDK_SHARED_APP_DTS),
+        ("physics/index.d.ts", embedded_assets::SDK_PHYSICS_DTS),
+        ("spine/index.d.ts", embedded_assets::SDK_SPINE_DTS),
+    ];
+    for (rel, data) in files {
+        write_file(&sdk.join(rel), &engine.read(*data)?)?;
+    }
+    write_file(&sdk.join("version.txt"), engine.name().as_bytes())?;
+    write_file(&project_dir.join(".esengine/editor/index.d.ts"), embedded_assets::EDITOR_DTS.bytes())
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_file(path, &data)
+}
+
+pub(crate) fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Writes to a sibling temp file and renames it over `path`, so readers see
+/// either the old or the new contents.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    write_file(&tmp, data)?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to replace {}: {}", path.display(), e)
+    })
+}
+
+/// Like `write_atomic`, but the data and the rename are flushed to disk
+/// before returning, so a crash or power loss cannot leave a truncated file.
+pub(crate) fn write_durable(path: &Path, data: &[u8]) -> Result<(), String> {
+    use std::io::Write as _;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let written = std::fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))
+        .and_then(|()| {
+            std::fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+        });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    // Persist the directory entry too; not possible (nor needed) on Windows.
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn now_iso8601() -> String {
+    iso8601_utc(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    )
+}
+
+const GITIGNORE: &str = "node_modules/\ndist/\n.vscode/\n.idea/\n.DS_Store\nThumbs.db\n.esengine/cache/\n";
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ESNext",
+    "moduleResolution": "bundler",
+    "strict": true,
+    "esModuleInterop": true,
+    "skipLibCheck": true,
+    "forceConsistentCasingInFileNames": true,
+    "declaration": false,
+    "outDir": "./dist",
+    "rootDir": ".",
+    "baseUrl": ".",
+    "paths": {
+      "esengine": ["./.esengine/sdk/index.d.ts"],
+      "esengine/wasm": ["./.esengine/sdk/wasm.d.ts"],
+      "@esengine/editor": ["./.esengine/editor/index.d.ts"]
+    }
+  },
+  "include": ["src/**/*"],
+  "exclude": ["node_modules"]
+}
+"#;
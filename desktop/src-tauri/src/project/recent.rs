@@ -0,0 +1,222 @@
+//! Recent projects — persisted in the app config directory
+//!
+//! Entries are validated on every query: a project whose file was moved or
+//! deleted is reported as `missing`, and one saved by a newer editor as
+//! `newer_engine`, instead of silently failing when opened. Pinned entries
+//! sort first and never fall off the end of the list.
+
+use super::{now_iso8601, write_file, PROJECT_FILE};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const RECENT_FILE: &str = "recent_projects.json";
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// Serializes read-modify-write cycles on the recent projects file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub name: String,
+    /// Path of the `project.esproject` file.
+    pub path: String,
+    pub last_opened: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    Ok,
+    Missing,
+    Invalid,
+    NewerEngine,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentProjectEntry {
+    #[serde(flatten)]
+    pub project: RecentProject,
+    pub status: ProjectStatus,
+    /// Engine version recorded in the project file.
+    pub engine: Option<String>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_recent_projects(app: AppHandle) -> Vec<RecentProjectEntry> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load(&app)
+        .into_iter()
+        .map(|project| {
+            let (status, engine) = validate(Path::new(&project.path));
+            RecentProjectEntry { project, status, engine }
+        })
+        .collect()
+}
+
+/// Adds or refreshes a project; `path` may be the project file or its folder.
+#[tauri::command]
+pub fn add_recent_project(app: AppHandle, path: String) -> Result<RecentProjectEntry, String> {
+    let project_file = project_file(Path::new(&path));
+    let (status, engine) = validate(&project_file);
+    match status {
+        ProjectStatus::Missing => return Err(format!("Project not found: {}", project_file.display())),
+        ProjectStatus::Invalid => return Err(format!("Not a valid project file: {}", project_file.display())),
+        _ => {}
+    }
+    let name = read_project(&project_file)
+        .and_then(|config| config["name"].as_str().map(String::from))
+        .unwrap_or_else(|| folder_name(&project_file));
+    let project = register(&app, &name, &project_file);
+    Ok(RecentProjectEntry { project, status, engine })
+}
+
+#[tauri::command]
+pub fn remove_recent_project(app: AppHandle, path: String) -> Result<(), String> {
+    update(&app, |projects| projects.retain(|p| p.path != path))
+}
+
+#[tauri::command]
+pub fn set_recent_project_pinned(app: AppHandle, path: String, pinned: bool) -> Result<(), String> {
+    update(&app, |projects| {
+        if let Some(project) = projects.iter_mut().find(|p| p.path == path) {
+            project.pinned = pinned;
+        }
+    })
+}
+
+/// Reorders the list to follow `paths`; entries not listed keep their
+/// relative order after the listed ones. Pinned entries still sort first.
+#[tauri::command]
+pub fn reorder_recent_projects(app: AppHandle, paths: Vec<String>) -> Result<(), String> {
+    update(&app, |projects| {
+        projects.sort_by_key(|p| paths.iter().position(|path| *path == p.path).unwrap_or(usize::MAX));
+    })
+}
+
+/// Clears the list; pinned entries are kept unless `include_pinned` is set.
+#[tauri::command]
+pub fn clear_recent_projects(app: AppHandle, include_pinned: bool) -> Result<(), String> {
+    update(&app, |projects| projects.retain(|p| p.pinned && !include_pinned))
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+/// Moves the project to the front of the recent list, keeping its pin.
+/// Write failures are ignored; the list is a convenience.
+pub(crate) fn register(app: &AppHandle, name: &str, project_file: &Path) -> RecentProject {
+    let mut project = RecentProject {
+        name: name.to_string(),
+        path: project_file.to_string_lossy().to_string(),
+        last_opened: now_iso8601(),
+        pinned: false,
+    };
+    let _ = update(app, |projects| {
+        if let Some(index) = projects.iter().position(|p| p.path == project.path) {
+            project.pinned = projects.remove(index).pinned;
+        }
+        projects.insert(0, project.clone());
+    });
+    project
+}
+
+fn update(app: &AppHandle, apply: impl FnOnce(&mut Vec<RecentProject>)) -> Result<(), String> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut projects = load(app);
+    apply(&mut projects);
+    normalize(&mut projects);
+    let data = serde_json::to_vec_pretty(&projects).map_err(|e| e.to_string())?;
+    write_file(&recent_path(app), &data)?;
+    crate::menu::refresh_recent(app);
+    Ok(())
+}
+
+/// Pinned first, then at most `MAX_RECENT_PROJECTS` unpinned entries.
+fn normalize(projects: &mut Vec<RecentProject>) {
+    projects.sort_by_key(|p| !p.pinned);
+    let mut unpinned = 0;
+    projects.retain(|p| {
+        if p.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= MAX_RECENT_PROJECTS
+    });
+}
+
+pub(crate) fn load(app: &AppHandle) -> Vec<RecentProject> {
+    std::fs::read_to_string(recent_path(app))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn recent_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_config_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(RECENT_FILE)
+}
+
+// =============================================================================
+// Validation
+// =============================================================================
+
+fn validate(project_file: &Path) -> (ProjectStatus, Option<String>) {
+    if !project_file.is_file() {
+        return (ProjectStatus::Missing, None);
+    }
+    let Some(config) = read_project(project_file) else {
+        return (ProjectStatus::Invalid, None);
+    };
+    let engine = config["engine"].as_str().map(String::from);
+    let status = match engine.as_deref() {
+        Some(version) if is_newer(version, env!("CARGO_PKG_VERSION")) => ProjectStatus::NewerEngine,
+        _ => ProjectStatus::Ok,
+    };
+    (status, engine)
+}
+
+fn read_project(project_file: &Path) -> Option<Value> {
+    let text = std::fs::read_to_string(project_file).ok()?;
+    serde_json::from_str::<Value>(&text).ok().filter(Value::is_object)
+}
+
+/// Compares `major.minor`; patch releases stay compatible both ways.
+fn is_newer(version: &str, current: &str) -> bool {
+    let parse = |v: &str| -> (u64, u64) {
+        let mut parts = v.split(['.', '-']).map(|p| p.parse::<u64>().unwrap_or(0));
+        (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+    };
+    parse(version) > parse(current)
+}
+
+fn project_file(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(PROJECT_FILE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn folder_name(project_file: &Path) -> String {
+    project_file
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
@@ -0,0 +1,270 @@
+//! Remote template registry — community starters downloaded on demand
+//!
+//! The registry is a JSON index listing template archives with their SHA-256.
+//! The index and every verified archive are cached under the app data
+//! directory, so templates stay available offline once fetched. Archives are
+//! zips whose entries are relative to the new project root; a single
+//! top-level folder is stripped, like the toolchain archives.
+
+use super::templates::TemplateFiles;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_REGISTRY_URL: &str = "https://raw.githubusercontent.com/esengine/estella-templates/main/index.json";
+const CONFIG_FILE: &str = "registry.json";
+const INDEX_FILE: &str = "index.json";
+const MAX_ARCHIVE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Files that `create_project` writes itself and a template cannot override.
+const RESERVED: &[&str] = &["project.esproject", "tsconfig.json", ".gitignore", ".esengine/sdk/", ".esengine/editor/"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryIndex {
+    #[serde(default)]
+    pub templates: Vec<RemoteTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    pub version: String,
+    /// Archive URL, absolute or relative to the index URL.
+    pub url: String,
+    pub sha256: String,
+    #[serde(default)]
+    pub design: Option<(u32, u32)>,
+    /// Set on query when the archive is already in the local cache.
+    #[serde(default)]
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryConfig {
+    url: String,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn get_template_registry_url(app: AppHandle) -> String {
+    registry_url(&app)
+}
+
+/// Sets the registry index URL; an empty string restores the default.
+#[tauri::command]
+pub fn set_template_registry_url(app: AppHandle, url: String) -> Result<(), String> {
+    let url = url.trim();
+    if !url.is_empty() {
+        reqwest::Url::parse(url).map_err(|e| format!("Invalid registry URL: {}", e))?;
+    }
+    let dir = cache_dir(&app);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let config = RegistryConfig { url: url.to_string() };
+    std::fs::write(dir.join(CONFIG_FILE), serde_json::to_vec_pretty(&config).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    // The cached index belongs to the previous registry.
+    let _ = std::fs::remove_file(dir.join(INDEX_FILE));
+    Ok(())
+}
+
+/// Fetches the registry index, falling back to the cached copy when offline.
+#[tauri::command]
+pub async fn list_remote_templates(app: AppHandle) -> Result<Vec<RemoteTemplate>, String> {
+    let index = match fetch_index(&app).await {
+        Ok(index) => index,
+        Err(e) => load_cached_index(&app).ok_or(e)?,
+    };
+    Ok(index
+        .templates
+        .into_iter()
+        .map(|mut t| {
+            t.cached = archive_path(&app, &t).is_file();
+            t
+        })
+        .collect())
+}
+
+/// Removes cached archives and the cached index.
+#[tauri::command]
+pub fn clear_template_cache(app: AppHandle) -> Result<(), String> {
+    let dir = cache_dir(&app);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+    for entry in entries.flatten() {
+        if entry.file_name() != CONFIG_FILE {
+            std::fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Resolution
+// =============================================================================
+
+/// Looks up `id` in the registry and returns the template's files,
+/// downloading and verifying the archive unless a valid copy is cached.
+pub(crate) async fn resolve(app: &AppHandle, id: &str) -> Result<TemplateFiles, String> {
+    let index = match load_cached_index(app) {
+        Some(index) if index.templates.iter().any(|t| t.id == id) => index,
+        _ => fetch_index(app).await?,
+    };
+    let template = index
+        .templates
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Unknown project template: {}", id))?;
+
+    let path = archive_path(app, &template);
+    let data = match std::fs::read(&path) {
+        Ok(data) if verify(&data, &template.sha256) => data,
+        _ => {
+            let url = archive_url(&registry_url(app), &template.url)?;
+            let data = download(&url).await?;
+            if !verify(&data, &template.sha256) {
+                return Err(format!("Checksum mismatch for template '{}'", template.id));
+            }
+            std::fs::create_dir_all(cache_dir(app)).map_err(|e| e.to_string())?;
+            std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+            data
+        }
+    };
+
+    let files = tokio::task::spawn_blocking(move || read_archive(&data))
+        .await
+        .map_err(|e| format!("Extract task failed: {}", e))??;
+    Ok(TemplateFiles {
+        design: template.design.unwrap_or((1920, 1080)),
+        files,
+    })
+}
+
+fn read_archive(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut archive =
+        zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(|e| format!("Invalid template archive: {}", e))?;
+    let mut names = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| e.to_string())?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file
+            .enclosed_name()
+            .ok_or_else(|| format!("Template archive contains an unsafe path: {}", file.name()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        names.push((i, name));
+    }
+
+    let prefix = common_top_dir(names.iter().map(|(_, n)| n.as_str()));
+    let mut files = Vec::new();
+    for (i, name) in names {
+        let rel = name[prefix.len()..].to_string();
+        if rel.is_empty() || RESERVED.iter().any(|r| rel == *r || (r.ends_with('/') && rel.starts_with(r))) {
+            continue;
+        }
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let mut buf = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+        files.push((rel, buf));
+    }
+    if files.is_empty() {
+        return Err("Template archive is empty".to_string());
+    }
+    Ok(files)
+}
+
+/// `"name/"` when every entry sits under the same wrapper folder, otherwise `""`.
+fn common_top_dir<'a>(mut names: impl Iterator<Item = &'a str>) -> String {
+    let first = match names.next().and_then(|n| n.split_once('/')) {
+        Some((top, _)) if !matches!(top, "src" | "assets" | ".esengine") => format!("{}/", top),
+        _ => return String::new(),
+    };
+    if names.all(|n| n.starts_with(&first)) {
+        first
+    } else {
+        String::new()
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn cache_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("templates")
+}
+
+fn registry_url(app: &AppHandle) -> String {
+    std::fs::read_to_string(cache_dir(app).join(CONFIG_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str::<RegistryConfig>(&s).ok())
+        .map(|c| c.url)
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string())
+}
+
+fn archive_path(app: &AppHandle, template: &RemoteTemplate) -> PathBuf {
+    let safe = |s: &str| s.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '.', "_");
+    cache_dir(app).join(format!("{}-{}.zip", safe(&template.id), safe(&template.version)))
+}
+
+fn archive_url(index_url: &str, url: &str) -> Result<String, String> {
+    let base = reqwest::Url::parse(index_url).map_err(|e| format!("Invalid registry URL: {}", e))?;
+    base.join(url)
+        .map(|u| u.to_string())
+        .map_err(|e| format!("Invalid template URL '{}': {}", url, e))
+}
+
+fn verify(data: &[u8], expected: &str) -> bool {
+    format!("{:x}", Sha256::digest(data)).eq_ignore_ascii_case(expected.trim())
+}
+
+fn load_cached_index(app: &AppHandle) -> Option<RegistryIndex> {
+    std::fs::read_to_string(cache_dir(app).join(INDEX_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+}
+
+async fn fetch_index(app: &AppHandle) -> Result<RegistryIndex, String> {
+    let url = registry_url(app);
+    let data = download(&url).await?;
+    let index: RegistryIndex =
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid template registry index: {}", e))?;
+    let dir = cache_dir(app);
+    if std::fs::create_dir_all(&dir).is_ok() {
+        let _ = std::fs::write(dir.join(INDEX_FILE), &data);
+    }
+    Ok(index)
+}
+
+async fn download(url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {} ({})", response.status(), url));
+    }
+    if response.content_length().unwrap_or(0) > MAX_ARCHIVE_SIZE {
+        return Err(format!("Download too large: {}", url));
+    }
+    let data = response.bytes().await.map_err(|e| format!("Download interrupted: {}", e))?;
+    Ok(data.to_vec())
+}
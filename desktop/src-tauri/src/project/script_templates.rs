@@ -0,0 +1,298 @@
+//! Script templates — scaffolding for "New Script"
+//!
+//! `create_script` writes a new script from a template and registers it in
+//! the project's script index:
+//!
+//! - built-in templates (component, system, UI behaviour) are embedded from
+//!   `src-tauri/templates/scripts/`
+//! - every `.ts` file in `.esengine/script-templates/` is a project template,
+//!   listed by its file name; one named like a built-in replaces it
+//! - `{{ClassName}}`, `{{camelName}}`, `{{fileName}}`, `{{author}}`,
+//!   `{{date}}` and `{{year}}` are substituted; the author is git's
+//!   `user.name`, else the OS user
+//! - the index is `src/index.ts`, else `src/main.ts`; a side-effect import of
+//!   the new script is added after its imports. Scripts are bundled either
+//!   way, the import keeps their load order explicit.
+
+use super::{write_atomic, write_durable};
+use crate::export::build_info::iso8601_utc;
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+const PROJECT_TEMPLATE_DIR: &str = ".esengine/script-templates";
+const INDEX_FILES: &[&str] = &["src/index.ts", "src/main.ts"];
+
+struct BuiltinTemplate {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    source: &'static str,
+}
+
+const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "component",
+        name: "Component",
+        description: "Data attached to entities",
+        source: include_str!("../../templates/scripts/component.ts"),
+    },
+    BuiltinTemplate {
+        id: "system",
+        name: "System",
+        description: "Logic run every frame over a query",
+        source: include_str!("../../templates/scripts/system.ts"),
+    },
+    BuiltinTemplate {
+        id: "ui-behaviour",
+        name: "UI Behaviour",
+        description: "A component and a system reacting to UI events",
+        source: include_str!("../../templates/scripts/ui-behaviour.ts"),
+    },
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptTemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// `builtin`, or `project` for one in `.esengine/script-templates/`.
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedScript {
+    pub path: String,
+    pub class_name: String,
+    /// The index the script was imported from, if any.
+    pub registered_in: Option<String>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_script_templates(project_dir: String) -> Vec<ScriptTemplateInfo> {
+    let custom = project_templates(Path::new(&project_dir));
+    let mut templates: Vec<ScriptTemplateInfo> = BUILTIN_TEMPLATES
+        .iter()
+        .filter(|t| !custom.iter().any(|(id, _)| id == t.id))
+        .map(|t| ScriptTemplateInfo {
+            id: t.id.to_string(),
+            name: t.name.to_string(),
+            description: t.description.to_string(),
+            source: "builtin".to_string(),
+        })
+        .collect();
+    templates.extend(custom.into_iter().map(|(id, path)| ScriptTemplateInfo {
+        name: title_case(&id),
+        description: format!("{}/{}", PROJECT_TEMPLATE_DIR, path.file_name().unwrap_or_default().to_string_lossy()),
+        id,
+        source: "project".to_string(),
+    }));
+    templates
+}
+
+/// Creates `<folder>/<name>.ts` from `template` (default `component`).
+/// Fails if the file exists.
+#[tauri::command]
+pub fn create_script(
+    project_dir: String,
+    folder: String,
+    name: String,
+    template: Option<String>,
+) -> Result<CreatedScript, String> {
+    let project_dir = PathBuf::from(project_dir);
+    let template = template.unwrap_or_else(|| "component".to_string());
+    let source = template_source(&project_dir, &template)?;
+
+    let stem = name.trim().trim_end_matches(".ts");
+    if stem.is_empty() || stem.contains(['/', '\\']) {
+        return Err(format!("Invalid script name: {}", name));
+    }
+    let class_name = class_name(stem);
+    if class_name.is_empty() {
+        return Err(format!("Invalid script name: {}", name));
+    }
+    let path = PathBuf::from(folder).join(format!("{}.ts", stem));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    let date = iso8601_utc(now_secs());
+    let text = source
+        .replace("{{ClassName}}", &class_name)
+        .replace("{{camelName}}", &camel_name(&class_name))
+        .replace("{{fileName}}", stem)
+        .replace("{{author}}", &author(&project_dir))
+        .replace("{{date}}", &date[..10])
+        .replace("{{year}}", &date[..4]);
+    write_durable(&path, text.as_bytes())?;
+
+    let registered_in = match register(&project_dir, &path) {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("[script-templates] Failed to register {}: {}", path.display(), e);
+            None
+        }
+    };
+    eprintln!("[script-templates] Created {} from {}", path.display(), template);
+    Ok(CreatedScript {
+        path: path.to_string_lossy().to_string(),
+        class_name,
+        registered_in: registered_in.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+// =============================================================================
+// Templates
+// =============================================================================
+
+/// Project templates as `(id, path)`, sorted by id.
+fn project_templates(project_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(project_dir.join(PROJECT_TEMPLATE_DIR)) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "ts"))
+        .filter_map(|path| Some((path.file_stem()?.to_string_lossy().to_string(), path)))
+        .collect();
+    templates.sort();
+    templates
+}
+
+fn template_source(project_dir: &Path, id: &str) -> Result<String, String> {
+    if let Some((_, path)) = project_templates(project_dir).into_iter().find(|(t, _)| t == id) {
+        return std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+    }
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|t| t.id == id)
+        .map(|t| t.source.to_string())
+        .ok_or_else(|| format!("Unknown script template: {}", id))
+}
+
+/// `player-controller`, `player_controller` and `playerController` all
+/// become `PlayerController`.
+fn class_name(stem: &str) -> String {
+    stem.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase()).into_iter();
+            first.chain(chars).collect::<String>()
+        })
+        .collect::<String>()
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .to_string()
+}
+
+fn camel_name(class_name: &str) -> String {
+    let mut chars = class_name.chars();
+    chars.next().map(|c| c.to_ascii_lowercase()).into_iter().chain(chars).collect()
+}
+
+fn title_case(id: &str) -> String {
+    id.split(['-', '_', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn author(project_dir: &Path) -> String {
+    let config = git2::Repository::discover(project_dir)
+        .and_then(|repo| repo.config())
+        .or_else(|_| git2::Config::open_default());
+    config
+        .and_then(|config| config.get_string("user.name"))
+        .ok()
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_default()
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+// =============================================================================
+// Script index
+// =============================================================================
+
+/// Imports `script` from the project's script index. Returns the index, or
+/// `None` when there is none, the script is outside its folder or is
+/// already imported.
+fn register(project_dir: &Path, script: &Path) -> Result<Option<PathBuf>, String> {
+    let Some(index) = INDEX_FILES.iter().map(|rel| project_dir.join(rel)).find(|path| path.is_file()) else {
+        return Ok(None);
+    };
+    let Some(specifier) = import_specifier(&index, script) else {
+        return Ok(None);
+    };
+    let text = std::fs::read_to_string(&index).map_err(|e| format!("Failed to read {}: {}", index.display(), e))?;
+    if text.contains(&format!("'{}'", specifier)) || text.contains(&format!("\"{}\"", specifier)) {
+        return Ok(None);
+    }
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    let at = imports_end(&lines);
+    let import = format!("import '{}';", specifier);
+    lines.insert(at, &import);
+    let mut updated = lines.join("\n");
+    if text.ends_with('\n') {
+        updated.push('\n');
+    }
+    write_atomic(&index, updated.as_bytes())?;
+    Ok(Some(index))
+}
+
+/// `./systems/player` for `src/systems/player.ts` imported from `src/main.ts`.
+fn import_specifier(index: &Path, script: &Path) -> Option<String> {
+    if index == script {
+        return None;
+    }
+    let rel = script.with_extension("").strip_prefix(index.parent()?).ok()?.to_path_buf();
+    let parts: Option<Vec<String>> = rel
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    Some(format!("./{}", parts?.join("/")))
+}
+
+/// The line after the leading import statements.
+fn imports_end(lines: &[&str]) -> usize {
+    let mut end = 0;
+    let mut in_import = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if in_import {
+            if trimmed.ends_with(';') {
+                in_import = false;
+                end = i + 1;
+            }
+        } else if trimmed.starts_with("import ") || trimmed.starts_with("import{") {
+            if trimmed.ends_with(';') {
+                end = i + 1;
+            } else {
+                in_import = true;
+            }
+        } else if !(trimmed.is_empty() || trimmed.starts_with("//")) {
+            break;
+        }
+    }
+    end
+}
@@ -0,0 +1,418 @@
+//! Project settings — typed model of `project.esproject`
+//!
+//! Settings are migrated to the current schema on load, validated before
+//! every write, and written atomically (temp file + rename) so a crash never
+//! leaves a truncated project file. Keys without a typed model here (runtime,
+//! atlas and collision-layer settings) are carried through unchanged.
+
+use super::{now_iso8601, write_atomic, PROJECT_FILE};
+use crate::export::profiles::merge_json;
+use crate::export::targets::TargetRegistry;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Version 1 is the original, unversioned project file.
+pub const SCHEMA_VERSION: u32 = 2;
+
+const MAX_DESIGN_SIZE: f64 = 16384.0;
+const SPINE_VERSIONS: &[&str] = &["none", "3.8", "4.1", "4.2"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSettings {
+    pub schema_version: u32,
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    /// Editor version that last saved the project.
+    #[serde(default)]
+    pub engine: String,
+    /// Engine build the project runs on, when not the one built into the
+    /// editor; see `engines`. Empty to go back to the built-in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine_version: Option<String>,
+    /// Start scene, relative to the project folder.
+    #[serde(default)]
+    pub default_scene: Option<String>,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub modified: String,
+    #[serde(default = "default_spine_version")]
+    pub spine_version: String,
+    #[serde(default)]
+    pub design_resolution: DesignResolution,
+    /// Export target ids the project is built for.
+    #[serde(default = "default_platforms")]
+    pub platforms: Vec<String>,
+    #[serde(default, skip_serializing_if = "ToolSettings::is_empty")]
+    pub tools: ToolSettings,
+    #[serde(flatten)]
+    pub physics: PhysicsSettings,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DesignResolution {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for DesignResolution {
+    fn default() -> Self {
+        Self { width: 1920.0, height: 1080.0 }
+    }
+}
+
+/// Stored as flat `physics*` keys, the layout the editor already reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSettings {
+    #[serde(rename = "enablePhysics")]
+    pub enabled: bool,
+    #[serde(rename = "physicsGravityX")]
+    pub gravity_x: f64,
+    #[serde(rename = "physicsGravityY")]
+    pub gravity_y: f64,
+    #[serde(rename = "physicsFixedTimestep")]
+    pub fixed_timestep: f64,
+    #[serde(rename = "physicsSubStepCount")]
+    pub sub_step_count: u32,
+    #[serde(rename = "physicsContactHertz")]
+    pub contact_hertz: f64,
+    #[serde(rename = "physicsContactDampingRatio")]
+    pub contact_damping_ratio: f64,
+    #[serde(rename = "physicsContactSpeed")]
+    pub contact_speed: f64,
+    #[serde(rename = "physicsBackend")]
+    pub backend: PhysicsBackend,
+}
+
+/// Physics engine the project runs on. Both are served as the same
+/// `physics.js` / `physics.wasm` pair; see `runtimes`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhysicsBackend {
+    #[default]
+    Box2d,
+    /// Deterministic across platforms, for lockstep networking.
+    Rapier,
+}
+
+impl PhysicsBackend {
+    /// The backend `project_dir` selects; Box2D when the project file
+    /// cannot be read.
+    pub fn for_project(project_dir: &Path) -> Self {
+        ProjectSettings::load(project_dir).map(|(settings, _)| settings.physics.backend).unwrap_or_default()
+    }
+
+    /// The runtime (see `runtimes`) holding this backend's module.
+    pub fn runtime_id(self) -> &'static str {
+        match self {
+            PhysicsBackend::Box2d => "physics",
+            PhysicsBackend::Rapier => "physics-rapier",
+        }
+    }
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gravity_x: 0.0,
+            gravity_y: -9.81,
+            fixed_timestep: 1.0 / 60.0,
+            sub_step_count: 4,
+            contact_hertz: 30.0,
+            contact_damping_ratio: 10.0,
+            contact_speed: 3.0,
+            backend: PhysicsBackend::default(),
+        }
+    }
+}
+
+/// Where build scripts find external tools. Relative paths are resolved
+/// against the project folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ToolSettings {
+    /// Executable to run for a command name, e.g. `"npm": "/opt/node/bin/npm"`.
+    pub paths: BTreeMap<String, String>,
+    /// Directories searched before PATH.
+    pub search_path: Vec<String>,
+}
+
+impl ToolSettings {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.search_path.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsIssue {
+    /// Key path of the offending value, e.g. `designResolution.width`.
+    pub field: String,
+    pub message: String,
+}
+
+fn default_version() -> String {
+    "0.1.0".to_string()
+}
+
+fn default_spine_version() -> String {
+    "none".to_string()
+}
+
+fn default_platforms() -> Vec<String> {
+    vec!["web".to_string()]
+}
+
+impl ProjectSettings {
+    pub fn new(name: &str, default_scene: Option<&str>, design: (f64, f64)) -> Self {
+        let now = now_iso8601();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            name: name.to_string(),
+            version: default_version(),
+            engine: env!("CARGO_PKG_VERSION").to_string(),
+            engine_version: None,
+            default_scene: default_scene.map(String::from),
+            created: now.clone(),
+            modified: now,
+            spine_version: default_spine_version(),
+            design_resolution: DesignResolution { width: design.0, height: design.1 },
+            platforms: default_platforms(),
+            tools: ToolSettings::default(),
+            physics: PhysicsSettings::default(),
+            extra: Map::new(),
+        }
+    }
+
+    /// Reads the project file, migrating older schema versions.
+    /// Returns the settings and whether a migration was applied.
+    pub fn load(project_dir: &Path) -> Result<(Self, bool), String> {
+        let path = project_dir.join(PROJECT_FILE);
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut value: Value =
+            serde_json::from_str(&text).map_err(|e| format!("Invalid project file {}: {}", path.display(), e))?;
+        let migrated = migrate(&mut value)?;
+        let settings = Self::from_value(value)?;
+        Ok((settings, migrated))
+    }
+
+    /// Validates and writes the project file atomically.
+    pub fn save(&self, project_dir: &Path) -> Result<(), String> {
+        let issues = self.validate(project_dir);
+        if !issues.is_empty() {
+            return Err(describe(&issues));
+        }
+        let data = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        write_atomic(&project_dir.join(PROJECT_FILE), &data)
+    }
+
+    fn from_value(value: Value) -> Result<Self, String> {
+        if !value.is_object() {
+            return Err("Invalid project settings: expected a JSON object".to_string());
+        }
+        serde_json::from_value(value).map_err(|e| format!("Invalid project settings: {}", e))
+    }
+
+    pub fn validate(&self, project_dir: &Path) -> Vec<SettingsIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |field: &str, message: String| {
+            issues.push(SettingsIssue { field: field.to_string(), message })
+        };
+
+        if self.name.trim().is_empty() {
+            issue("name", "must not be empty".to_string());
+        }
+        if self.version.trim().is_empty() {
+            issue("version", "must not be empty".to_string());
+        }
+        if let Some(version) = &self.engine_version {
+            if let Err(e) = crate::engines::parse_version(version) {
+                issue("engineVersion", e);
+            }
+        }
+        if let Some(scene) = &self.default_scene {
+            if !scene.ends_with(".esscene") {
+                issue("defaultScene", format!("'{}' is not a scene file (.esscene)", scene));
+            } else if !project_dir.join(scene).is_file() {
+                issue("defaultScene", format!("scene '{}' does not exist", scene));
+            }
+        }
+        if !SPINE_VERSIONS.contains(&self.spine_version.as_str()) {
+            issue(
+                "spineVersion",
+                format!("'{}' is not supported; use one of {}", self.spine_version, SPINE_VERSIONS.join(", ")),
+            );
+        }
+        for (field, size) in [
+            ("designResolution.width", self.design_resolution.width),
+            ("designResolution.height", self.design_resolution.height),
+        ] {
+            if !(1.0..=MAX_DESIGN_SIZE).contains(&size) {
+                issue(field, format!("must be between 1 and {}, got {}", MAX_DESIGN_SIZE, size));
+            }
+        }
+
+        if self.platforms.is_empty() {
+            issue("platforms", "select at least one platform".to_string());
+        }
+        let targets = TargetRegistry::discover(project_dir, |_| {});
+        for platform in &self.platforms {
+            if targets.get(platform).is_none() {
+                issue("platforms", format!("unknown export target '{}'", platform));
+            }
+        }
+
+        for (tool, path) in &self.tools.paths {
+            if tool.is_empty() || tool.contains(['/', '\\']) {
+                issue("tools.paths", format!("'{}' is not a command name", tool));
+            } else if path.trim().is_empty() {
+                issue(&format!("tools.paths.{}", tool), "must not be empty".to_string());
+            }
+        }
+        if self.tools.search_path.iter().any(|dir| dir.trim().is_empty()) {
+            issue("tools.searchPath", "must not contain empty entries".to_string());
+        }
+
+        let physics = &self.physics;
+        if !(physics.fixed_timestep > 0.0 && physics.fixed_timestep <= 1.0) {
+            issue(
+                "physicsFixedTimestep",
+                format!("must be greater than 0 and at most 1 second, got {}", physics.fixed_timestep),
+            );
+        }
+        if !(1..=64).contains(&physics.sub_step_count) {
+            issue("physicsSubStepCount", format!("must be between 1 and 64, got {}", physics.sub_step_count));
+        }
+        for (field, value) in [
+            ("physicsGravityX", physics.gravity_x),
+            ("physicsGravityY", physics.gravity_y),
+        ] {
+            if !value.is_finite() {
+                issue(field, "must be a finite number".to_string());
+            }
+        }
+        for (field, value) in [
+            ("physicsContactHertz", physics.contact_hertz),
+            ("physicsContactDampingRatio", physics.contact_damping_ratio),
+            ("physicsContactSpeed", physics.contact_speed),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                issue(field, format!("must be zero or positive, got {}", value));
+            }
+        }
+        issues
+    }
+}
+
+// =============================================================================
+// Migration
+// =============================================================================
+
+/// Upgrades `value` to `SCHEMA_VERSION` in place; returns whether it changed.
+fn migrate(value: &mut Value) -> Result<bool, String> {
+    let Some(config) = value.as_object_mut() else {
+        return Err("Invalid project settings: expected a JSON object".to_string());
+    };
+    let mut version = config.get("schemaVersion").and_then(Value::as_u64).unwrap_or(1) as u32;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "This project uses settings version {}, but this editor supports up to {}. Update the editor to open it.",
+            version, SCHEMA_VERSION
+        ));
+    }
+    let migrated = version < SCHEMA_VERSION;
+    while version < SCHEMA_VERSION {
+        match version {
+            1 => migrate_v1(config),
+            _ => unreachable!(),
+        }
+        version += 1;
+        config.insert("schemaVersion".to_string(), json!(version));
+    }
+    Ok(migrated)
+}
+
+/// v1 → v2: design resolution and platforms become required, and the
+/// launcher's optional keys get explicit values.
+fn migrate_v1(config: &mut Map<String, Value>) {
+    let default = DesignResolution::default();
+    let design = config.entry("designResolution").or_insert_with(|| json!({}));
+    if let Some(design) = design.as_object_mut() {
+        design.entry("width").or_insert(json!(default.width));
+        design.entry("height").or_insert(json!(default.height));
+    }
+    config.entry("platforms").or_insert_with(|| json!(default_platforms()));
+    config.entry("spineVersion").or_insert_with(|| json!(default_spine_version()));
+    config.entry("version").or_insert_with(|| json!(default_version()));
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Loads the project settings; a migrated file is written back immediately.
+#[tauri::command]
+pub fn get_project_settings(project_dir: String) -> Result<ProjectSettings, String> {
+    let project_dir = Path::new(&project_dir);
+    let (settings, migrated) = ProjectSettings::load(project_dir)?;
+    if migrated {
+        let data = serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?;
+        write_atomic(&project_dir.join(PROJECT_FILE), &data)?;
+    }
+    Ok(settings)
+}
+
+/// Merges `changes` (a partial settings object) into the project file.
+/// Nothing is written when the result fails validation.
+#[tauri::command]
+pub fn update_project_settings(project_dir: String, changes: Value) -> Result<ProjectSettings, String> {
+    let project_dir = Path::new(&project_dir);
+    let (settings, _) = ProjectSettings::load(project_dir)?;
+    let previous_engine = settings.engine_version.clone();
+    let mut value = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    merge_json(&mut value, &changes);
+
+    let mut settings = ProjectSettings::from_value(value)?;
+    // A patch cannot remove a key, so an empty version selects the built-in
+    // engine.
+    settings.engine_version = settings.engine_version.filter(|v| !v.trim().is_empty());
+    settings.schema_version = SCHEMA_VERSION;
+    settings.engine = env!("CARGO_PKG_VERSION").to_string();
+    settings.modified = now_iso8601();
+    settings.save(project_dir)?;
+    if settings.engine_version != previous_engine {
+        // The settings are saved either way; stale types are redone on the
+        // next open.
+        if let Err(e) = super::typings::sync(project_dir, false) {
+            eprintln!("[Typings] Failed to re-sync types: {}", e);
+        }
+    }
+    Ok(settings)
+}
+
+/// Checks a full or partial settings object against the current file
+/// without writing anything.
+#[tauri::command]
+pub fn validate_project_settings(project_dir: String, changes: Value) -> Result<Vec<SettingsIssue>, String> {
+    let project_dir = Path::new(&project_dir);
+    let (settings, _) = ProjectSettings::load(project_dir)?;
+    let mut value = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    merge_json(&mut value, &changes);
+    Ok(ProjectSettings::from_value(value)?.validate(project_dir))
+}
+
+fn describe(issues: &[SettingsIssue]) -> String {
+    let lines: Vec<String> = issues.iter().map(|i| format!("- {}: {}", i.field, i.message)).collect();
+    format!("Invalid project settings:\n{}", lines.join("\n"))
+}
@@ -0,0 +1,319 @@
+//! Project snapshots — local restore points for scenes, prefabs and settings
+//!
+//! A snapshot is a zip in `.esengine/snapshots/` holding the project file,
+//! the editor settings under `.esengine/`, and every JSON asset (scenes,
+//! prefabs, materials, animations...) with its `.meta`. Binary assets and
+//! caches are left out, which keeps snapshots small enough to take before
+//! every bulk edit. Each zip carries a `snapshot.json` manifest with the
+//! label and per-file hashes, used for listing and diffing without
+//! extracting anything.
+
+use super::{now_iso8601, write_atomic, PROJECT_FILE};
+use crate::export::assets;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_DIR: &str = ".esengine/snapshots";
+const MANIFEST_FILE: &str = "snapshot.json";
+const DEFAULT_MAX_SNAPSHOTS: usize = 20;
+
+/// Editor files under `.esengine/` included in every snapshot.
+const SETTINGS_FILES: &[&str] = &[
+    ".esengine/settings.json",
+    ".esengine/build-profiles.json",
+    ".esengine/asset-export.json",
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub label: String,
+    pub created: String,
+    /// SHA-256 per project-relative path.
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub label: String,
+    pub created: String,
+    pub file_count: usize,
+    /// Size of the zip in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotChange {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResult {
+    pub restored: Vec<String>,
+    /// Snapshot of the state before the restore, so the restore can be undone.
+    pub backup: SnapshotInfo,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Snapshots the project; the oldest snapshots beyond `max_snapshots`
+/// (20 by default) are deleted.
+#[tauri::command]
+pub async fn create_snapshot(
+    project_dir: String,
+    label: String,
+    max_snapshots: Option<usize>,
+) -> Result<SnapshotInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = Path::new(&project_dir);
+        let info = create(project_dir, &label)?;
+        rotate(project_dir, max_snapshots.unwrap_or(DEFAULT_MAX_SNAPSHOTS).max(1))?;
+        Ok(info)
+    })
+    .await
+    .map_err(|e| format!("Snapshot task failed: {}", e))?
+}
+
+/// Snapshots, newest first.
+#[tauri::command]
+pub fn list_snapshots(project_dir: String) -> Result<Vec<SnapshotInfo>, String> {
+    list(Path::new(&project_dir))
+}
+
+#[tauri::command]
+pub fn delete_snapshot(project_dir: String, id: String) -> Result<(), String> {
+    let path = snapshot_path(Path::new(&project_dir), &id)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot {}: {}", id, e))
+}
+
+/// Restores `paths` from the snapshot, or every file in it when omitted.
+/// The current state is snapshotted first. Files created after the snapshot
+/// are left in place.
+#[tauri::command]
+pub async fn restore_snapshot(
+    project_dir: String,
+    id: String,
+    paths: Option<Vec<String>>,
+) -> Result<RestoreResult, String> {
+    tokio::task::spawn_blocking(move || restore(Path::new(&project_dir), &id, paths.as_deref()))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))?
+}
+
+/// Changes from snapshot `id` to snapshot `against`, or to the current
+/// project files when `against` is omitted.
+#[tauri::command]
+pub fn diff_snapshot(project_dir: String, id: String, against: Option<String>) -> Result<Vec<SnapshotChange>, String> {
+    let project_dir = Path::new(&project_dir);
+    let (_, base) = open(&snapshot_path(project_dir, &id)?)?;
+    let target = match against {
+        Some(other) => open(&snapshot_path(project_dir, &other)?)?.1.files,
+        None => hash_files(project_dir, &collect_files(project_dir))?,
+    };
+
+    let mut changes = Vec::new();
+    for (path, hash) in &base.files {
+        match target.get(path) {
+            None => changes.push(SnapshotChange { path: path.clone(), kind: ChangeKind::Removed }),
+            Some(other) if other != hash => changes.push(SnapshotChange { path: path.clone(), kind: ChangeKind::Modified }),
+            Some(_) => {}
+        }
+    }
+    for path in target.keys().filter(|p| !base.files.contains_key(*p)) {
+        changes.push(SnapshotChange { path: path.clone(), kind: ChangeKind::Added });
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+// =============================================================================
+// Snapshots
+// =============================================================================
+
+fn create(project_dir: &Path, label: &str) -> Result<SnapshotInfo, String> {
+    if !project_dir.join(PROJECT_FILE).is_file() {
+        return Err(format!("Not a project folder: {}", project_dir.display()));
+    }
+    let files = collect_files(project_dir);
+    let created = now_iso8601();
+    let dir = project_dir.join(SNAPSHOT_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let stamp: String = created.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let mut id = stamp.clone();
+    let mut n = 1;
+    while dir.join(format!("{}.zip", id)).exists() {
+        n += 1;
+        id = format!("{}-{}", stamp, n);
+    }
+
+    let mut manifest = SnapshotManifest {
+        label: label.trim().to_string(),
+        created,
+        files: BTreeMap::new(),
+    };
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let mut writer = zip::ZipWriter::new(&mut buffer);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for rel in &files {
+        let data = std::fs::read(project_dir.join(rel)).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        manifest.files.insert(rel.clone(), format!("{:x}", Sha256::digest(&data)));
+        writer.start_file(rel.as_str(), options).map_err(|e| e.to_string())?;
+        writer.write_all(&data).map_err(|e| e.to_string())?;
+    }
+    writer.start_file(MANIFEST_FILE, options).map_err(|e| e.to_string())?;
+    writer
+        .write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| format!("Failed to write snapshot: {}", e))?;
+
+    let path = dir.join(format!("{}.zip", id));
+    write_atomic(&path, buffer.get_ref())?;
+    Ok(info(&id, &manifest, buffer.get_ref().len() as u64))
+}
+
+fn list(project_dir: &Path) -> Result<Vec<SnapshotInfo>, String> {
+    let Ok(entries) = std::fs::read_dir(project_dir.join(SNAPSHOT_DIR)) else {
+        return Ok(Vec::new());
+    };
+    let mut snapshots = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("zip") {
+            continue;
+        }
+        let Some(id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        // Unreadable zips are skipped rather than failing the whole list.
+        if let Ok((_, manifest)) = open(&path) {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            snapshots.push(info(&id, &manifest, size));
+        }
+    }
+    snapshots.sort_by(|a, b| (&b.created, &b.id).cmp(&(&a.created, &a.id)));
+    Ok(snapshots)
+}
+
+fn rotate(project_dir: &Path, max: usize) -> Result<(), String> {
+    for old in list(project_dir)?.iter().skip(max) {
+        let path = snapshot_path(project_dir, &old.id)?;
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot {}: {}", old.id, e))?;
+    }
+    Ok(())
+}
+
+fn restore(project_dir: &Path, id: &str, paths: Option<&[String]>) -> Result<RestoreResult, String> {
+    let (mut archive, manifest) = open(&snapshot_path(project_dir, id)?)?;
+    let selected: Vec<String> = match paths {
+        Some(paths) => {
+            if let Some(missing) = paths.iter().find(|p| !manifest.files.contains_key(*p)) {
+                return Err(format!("'{}' is not in snapshot {}", missing, id));
+            }
+            paths.to_vec()
+        }
+        None => manifest.files.keys().cloned().collect(),
+    };
+
+    let label = if manifest.label.is_empty() { id.to_string() } else { manifest.label.clone() };
+    let backup = create(project_dir, &format!("Before restoring \"{}\"", label))?;
+
+    let mut restored = Vec::new();
+    for rel in selected {
+        let mut file = archive
+            .by_name(&rel)
+            .map_err(|e| format!("Snapshot {} is missing {}: {}", id, rel, e))?;
+        let mut data = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        write_atomic(&project_dir.join(&rel), &data)?;
+        restored.push(rel);
+    }
+    Ok(RestoreResult { restored, backup })
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Project-relative paths included in a snapshot.
+fn collect_files(project_dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = std::iter::once(PROJECT_FILE)
+        .chain(SETTINGS_FILES.iter().copied())
+        .filter(|rel| project_dir.join(rel).is_file())
+        .map(String::from)
+        .collect();
+    for path in assets::walk_files(&project_dir.join("assets")) {
+        let rel = assets::relative_path(project_dir, &path);
+        let asset = rel.strip_suffix(".meta").unwrap_or(&rel);
+        if assets::is_json_asset(asset) {
+            files.push(rel);
+        }
+    }
+    files
+}
+
+fn hash_files(project_dir: &Path, files: &[String]) -> Result<BTreeMap<String, String>, String> {
+    files
+        .iter()
+        .map(|rel| {
+            let data = std::fs::read(project_dir.join(rel)).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            Ok((rel.clone(), format!("{:x}", Sha256::digest(&data))))
+        })
+        .collect()
+}
+
+type SnapshotArchive = zip::ZipArchive<std::io::BufReader<std::fs::File>>;
+
+fn open(path: &Path) -> Result<(SnapshotArchive, SnapshotManifest), String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open snapshot: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(|e| format!("Invalid snapshot: {}", e))?;
+    let manifest = {
+        let mut entry = archive
+            .by_name(MANIFEST_FILE)
+            .map_err(|_| format!("Snapshot has no {}", MANIFEST_FILE))?;
+        let mut text = String::new();
+        entry.read_to_string(&mut text).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid snapshot manifest: {}", e))?
+    };
+    Ok((archive, manifest))
+}
+
+fn snapshot_path(project_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+    let path = project_dir.join(SNAPSHOT_DIR).join(format!("{}.zip", id));
+    if !path.is_file() {
+        return Err(format!("Snapshot not found: {}", id));
+    }
+    Ok(path)
+}
+
+fn info(id: &str, manifest: &SnapshotManifest, size: u64) -> SnapshotInfo {
+    SnapshotInfo {
+        id: id.to_string(),
+        label: manifest.label.clone(),
+        created: manifest.created.clone(),
+        file_count: manifest.files.len(),
+        size,
+    }
+}
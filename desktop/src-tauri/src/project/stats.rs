@@ -0,0 +1,297 @@
+//! Project statistics for the dashboard panel
+//!
+//! Per-file facts (entity counts, texture dimensions, audio durations, script
+//! line counts) are cached in `.esengine/cache/stats.json` keyed by size and
+//! modification time, so reopening the dashboard only re-reads files that
+//! changed. Texture memory is estimated as uncompressed RGBA8; audio
+//! durations come from the container headers (WAV, Ogg, and a bitrate
+//! estimate for MP3), without decoding.
+
+use crate::export::assets;
+use crate::jobs::{JobCategory, JobPriority, JobSpec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, State};
+
+const CACHE_FILE: &str = ".esengine/cache/stats.json";
+const CACHE_VERSION: u32 = 1;
+const LARGEST_ASSETS: usize = 10;
+
+const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "ogg", "mp3"];
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "js", "mjs"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectStats {
+    pub scenes: usize,
+    pub prefabs: usize,
+    pub scripts: usize,
+    pub textures: usize,
+    pub audio_clips: usize,
+    /// All files under `assets/`, `.meta` sidecars excluded.
+    pub assets: usize,
+    /// Entities across all scenes.
+    pub entities: usize,
+    pub prefab_entities: usize,
+    /// Estimated GPU memory of all textures, in bytes.
+    pub texture_memory: u64,
+    pub audio_duration_secs: f64,
+    pub script_lines: usize,
+    pub total_asset_size: u64,
+    pub largest_assets: Vec<AssetSize>,
+    /// Files whose stats came from the cache.
+    pub cached_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetSize {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StatsCache {
+    version: u32,
+    files: HashMap<String, FileStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FileStats {
+    size: u64,
+    modified: u64,
+    #[serde(default)]
+    entities: usize,
+    #[serde(default)]
+    texture_bytes: u64,
+    #[serde(default)]
+    duration_secs: f64,
+    #[serde(default)]
+    lines: usize,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn get_project_stats(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    project_dir: String,
+) -> Result<ProjectStats, String> {
+    let spec = JobSpec::new(JobCategory::Io, "Project statistics").priority(JobPriority::Low).project(&project_dir);
+    state.jobs.run(&app, spec, move |_| collect(Path::new(&project_dir))).await
+}
+
+// =============================================================================
+// Collection
+// =============================================================================
+
+fn collect(project_dir: &Path) -> Result<ProjectStats, String> {
+    if !project_dir.join(super::PROJECT_FILE).is_file() {
+        return Err(format!("Not a project folder: {}", project_dir.display()));
+    }
+    let cache_path = project_dir.join(CACHE_FILE);
+    let old = std::fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<StatsCache>(&s).ok())
+        .filter(|c| c.version == CACHE_VERSION)
+        .unwrap_or_default();
+    let mut cache = StatsCache {
+        version: CACHE_VERSION,
+        files: HashMap::new(),
+    };
+
+    let mut stats = ProjectStats::default();
+    let mut sizes = Vec::new();
+    let files = assets::walk_files(&project_dir.join("assets"))
+        .into_iter()
+        .chain(assets::walk_files(&project_dir.join("src")));
+    for path in files {
+        let rel = assets::relative_path(project_dir, &path);
+        if rel.ends_with(".meta") {
+            continue;
+        }
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let size = metadata.len();
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let ext = assets::extension_of(&rel);
+
+        let file = match old.files.get(&rel) {
+            Some(cached) if cached.size == size && cached.modified == modified => {
+                stats.cached_files += 1;
+                cached.clone()
+            }
+            _ => FileStats {
+                size,
+                modified,
+                ..analyze(&path, &ext)
+            },
+        };
+
+        match ext.as_str() {
+            "esscene" => {
+                stats.scenes += 1;
+                stats.entities += file.entities;
+            }
+            "esprefab" => {
+                stats.prefabs += 1;
+                stats.prefab_entities += file.entities;
+            }
+            e if TEXTURE_EXTENSIONS.contains(&e) => {
+                stats.textures += 1;
+                stats.texture_memory += file.texture_bytes;
+            }
+            e if AUDIO_EXTENSIONS.contains(&e) => {
+                stats.audio_clips += 1;
+                stats.audio_duration_secs += file.duration_secs;
+            }
+            e if SCRIPT_EXTENSIONS.contains(&e) && !rel.ends_with(".d.ts") => {
+                stats.scripts += 1;
+                stats.script_lines += file.lines;
+            }
+            _ => {}
+        }
+        if rel.starts_with("assets/") {
+            stats.assets += 1;
+            stats.total_asset_size += size;
+            sizes.push(AssetSize { path: rel.clone(), size });
+        }
+        cache.files.insert(rel, file);
+    }
+
+    sizes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    sizes.truncate(LARGEST_ASSETS);
+    stats.largest_assets = sizes;
+
+    // The cache only speeds up the next call; failing to write it is harmless.
+    if let Ok(data) = serde_json::to_vec(&cache) {
+        let _ = super::write_file(&cache_path, &data);
+    }
+    Ok(stats)
+}
+
+fn analyze(path: &Path, ext: &str) -> FileStats {
+    let mut stats = FileStats::default();
+    match ext {
+        "esscene" | "esprefab" => {
+            stats.entities = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v.get("entities").and_then(Value::as_array).map(Vec::len))
+                .unwrap_or(0);
+        }
+        e if TEXTURE_EXTENSIONS.contains(&e) => {
+            if let Ok((width, height)) = image::image_dimensions(path) {
+                stats.texture_bytes = width as u64 * height as u64 * 4;
+            }
+        }
+        "wav" => stats.duration_secs = wav_duration(path).unwrap_or(0.0),
+        "ogg" => stats.duration_secs = ogg_duration(path).unwrap_or(0.0),
+        "mp3" => stats.duration_secs = mp3_duration(path).unwrap_or(0.0),
+        e if SCRIPT_EXTENSIONS.contains(&e) => {
+            stats.lines = std::fs::read_to_string(path).map(|s| s.lines().count()).unwrap_or(0);
+        }
+        _ => {}
+    }
+    stats
+}
+
+// =============================================================================
+// Audio durations
+// =============================================================================
+
+/// Data chunk size divided by the byte rate from the `fmt ` chunk.
+fn wav_duration(path: &Path) -> Option<f64> {
+    let data = std::fs::read(path).ok()?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+    let mut byte_rate = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        if id == b"fmt " && body + 12 <= data.len() {
+            byte_rate = Some(u32::from_le_bytes(data[body + 8..body + 12].try_into().ok()?));
+        } else if id == b"data" {
+            let rate = byte_rate.filter(|r| *r > 0)?;
+            let size = size.min(data.len() - body);
+            return Some(size as f64 / rate as f64);
+        }
+        pos = body + size + (size & 1);
+    }
+    None
+}
+
+/// Granule position of the last Ogg page divided by the Vorbis or Opus
+/// sample rate.
+fn ogg_duration(path: &Path) -> Option<f64> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut head = vec![0u8; 4096];
+    let n = file.read(&mut head).ok()?;
+    head.truncate(n);
+
+    let rate = if let Some(i) = find(&head, b"\x01vorbis") {
+        u32::from_le_bytes(head.get(i + 12..i + 16)?.try_into().ok()?) as f64
+    } else if find(&head, b"OpusHead").is_some() {
+        // Opus granule positions always count 48 kHz samples.
+        48_000.0
+    } else {
+        return None;
+    };
+
+    let len = file.seek(SeekFrom::End(0)).ok()?;
+    let tail_len = len.min(65_536);
+    file.seek(SeekFrom::Start(len - tail_len)).ok()?;
+    let mut tail = vec![0u8; tail_len as usize];
+    file.read_exact(&mut tail).ok()?;
+    let page = rfind(&tail, b"OggS")?;
+    let granule = i64::from_le_bytes(tail.get(page + 6..page + 14)?.try_into().ok()?);
+    (granule > 0 && rate > 0.0).then(|| granule as f64 / rate)
+}
+
+/// Estimate from the first frame's bitrate; exact for constant-bitrate files.
+fn mp3_duration(path: &Path) -> Option<f64> {
+    const BITRATES: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+
+    let data = std::fs::read(path).ok()?;
+    let mut start = 0;
+    if data.starts_with(b"ID3") && data.len() > 10 {
+        // Synchsafe tag size.
+        let size = data[6..10].iter().fold(0usize, |acc, b| (acc << 7) | (*b as usize & 0x7f));
+        start = 10 + size;
+    }
+    let frame = (start..data.len().saturating_sub(4)).find(|&i| data[i] == 0xff && data[i + 1] & 0xe0 == 0xe0)?;
+    // MPEG-1 Layer III only; other layers and versions are rare in games.
+    let header = &data[frame..frame + 4];
+    if (header[1] >> 3) & 0x3 != 0x3 || (header[1] >> 1) & 0x3 != 0x1 {
+        return None;
+    }
+    let bitrate = *BITRATES.get((header[2] >> 4) as usize)? as f64 * 1000.0;
+    (bitrate > 0.0).then(|| (data.len() - frame) as f64 * 8.0 / bitrate)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
@@ -0,0 +1,99 @@
+//! Built-in project templates, embedded in the editor binary
+//!
+//! Platformer and UI demo reuse the example projects; the empty and WeChat
+//! starters live in `src-tauri/templates/`. File paths are relative to the
+//! new project root. `project.esproject`, `tsconfig.json`, `.gitignore` and
+//! the SDK are written by `create_project`, never by a template.
+
+pub(crate) struct Template {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub design: (u32, u32),
+    pub files: &'static [(&'static str, &'static [u8])],
+}
+
+/// Files of a template resolved for instantiation, built-in or downloaded.
+pub(crate) struct TemplateFiles {
+    pub design: (u32, u32),
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl Template {
+    pub(crate) fn resolve(&self) -> TemplateFiles {
+        TemplateFiles {
+            design: self.design,
+            files: self.files.iter().map(|(rel, data)| (rel.to_string(), data.to_vec())).collect(),
+        }
+    }
+}
+
+macro_rules! file {
+    ($rel:literal, $path:literal) => {
+        ($rel, include_bytes!($path) as &[u8])
+    };
+}
+
+pub(crate) const DEFAULT_TEMPLATE: &str = "empty";
+
+pub(crate) const TEMPLATES: &[Template] = &[
+    Template {
+        id: "empty",
+        name: "Empty",
+        description: "A camera, a canvas and a startup script",
+        design: (1920, 1080),
+        files: &[
+            file!("src/main.ts", "../../templates/empty/src/main.ts"),
+            file!("assets/scenes/main.esscene", "../../templates/empty/assets/scenes/main.esscene"),
+        ],
+    },
+    Template {
+        id: "platformer",
+        name: "Platformer",
+        description: "Side-scrolling player with jumping, platforms and coins",
+        design: (800, 600),
+        files: &[
+            file!("src/main.ts", "../../../../examples/platformer/src/main.ts"),
+            file!("src/components.ts", "../../../../examples/platformer/src/components.ts"),
+            file!("src/systems/coin.ts", "../../../../examples/platformer/src/systems/coin.ts"),
+            file!("src/systems/player.ts", "../../../../examples/platformer/src/systems/player.ts"),
+            file!("assets/scenes/main.esscene", "../../../../examples/platformer/assets/scenes/main.esscene"),
+            file!("assets/textures/coin.png", "../../../../examples/platformer/assets/textures/coin.png"),
+            file!("assets/textures/coin.png.meta", "../../../../examples/platformer/assets/textures/coin.png.meta"),
+            file!("assets/textures/ground.png", "../../../../examples/platformer/assets/textures/ground.png"),
+            file!("assets/textures/ground.png.meta", "../../../../examples/platformer/assets/textures/ground.png.meta"),
+            file!("assets/textures/platform.png", "../../../../examples/platformer/assets/textures/platform.png"),
+            file!("assets/textures/platform.png.meta", "../../../../examples/platformer/assets/textures/platform.png.meta"),
+            file!("assets/textures/player.png", "../../../../examples/platformer/assets/textures/player.png"),
+            file!("assets/textures/player.png.meta", "../../../../examples/platformer/assets/textures/player.png.meta"),
+            file!("assets/textures/sky.png", "../../../../examples/platformer/assets/textures/sky.png"),
+            file!("assets/textures/sky.png.meta", "../../../../examples/platformer/assets/textures/sky.png.meta"),
+        ],
+    },
+    Template {
+        id: "ui-demo",
+        name: "UI Demo",
+        description: "Canvas layout with draggable and focusable widgets",
+        design: (800, 600),
+        files: &[
+            file!("src/main.ts", "../../../../examples/ui-interaction/src/main.ts"),
+            file!("src/systems/dragFocus.ts", "../../../../examples/ui-interaction/src/systems/dragFocus.ts"),
+            file!("assets/scenes/main.esscene", "../../../../examples/ui-interaction/assets/scenes/main.esscene"),
+        ],
+    },
+    Template {
+        id: "wechat",
+        name: "WeChat Starter",
+        description: "Portrait mini game with touch input and WeChat build profiles",
+        design: (750, 1334),
+        files: &[
+            file!("src/main.ts", "../../templates/wechat/src/main.ts"),
+            file!("assets/scenes/main.esscene", "../../templates/wechat/assets/scenes/main.esscene"),
+            file!(".esengine/build-profiles.json", "../../templates/wechat/.esengine/build-profiles.json"),
+        ],
+    },
+];
+
+pub(crate) fn find(id: &str) -> Option<&'static Template> {
+    TEMPLATES.iter().find(|t| t.id == id)
+}
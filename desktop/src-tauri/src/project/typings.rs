@@ -0,0 +1,134 @@
+//! Project typings — SDK and editor type definitions kept in the project
+//!
+//! `.esengine/sdk` is rewritten by whichever editor opens the project, so
+//! IntelliSense follows the editor rather than the engine the project runs
+//! on. `sync_project_types` copies the type definitions of the project's
+//! engine (see `engines`) and of the editor API into `types/`, where they
+//! can be committed, stamped with the versions they came from:
+//!
+//! ```text
+//! types/
+//!   version.json          { "sdk": "0.5.0", "editor": "0.5.2" }
+//!   esengine/index.d.ts   (+ wasm.d.ts, shared/, physics/, spine/)
+//!   editor/index.d.ts
+//! ```
+//!
+//! Once materialized, the folder is re-synced when the project's engine
+//! version changes and when a project is opened by a different editor.
+
+use super::{write_atomic, write_file};
+use crate::embedded_assets::{self, Embedded};
+use crate::engines::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const TYPES_DIR: &str = "types";
+const STAMP_FILE: &str = "version.json";
+
+/// SDK type definitions, by their path under `types/esengine/`.
+const SDK_TYPES: &[(&str, Embedded)] = &[
+    ("index.d.ts", embedded_assets::SDK_ESM_DTS),
+    ("wasm.d.ts", embedded_assets::SDK_WASM_DTS),
+    ("shared/wasm.d.ts", embedded_assets::SDK_SHARED_WASM_DTS),
+    ("shared/app.d.ts", embedded_assets::SDK_SHARED_APP_DTS),
+    ("physics/index.d.ts", embedded_assets::SDK_PHYSICS_DTS),
+    ("spine/index.d.ts", embedded_assets::SDK_SPINE_DTS),
+];
+
+/// `tsconfig.json` paths of the scaffolded project, and their replacement.
+const TSCONFIG_PATHS: &[(&str, &str)] =
+    &[("./.esengine/sdk/", "./types/esengine/"), ("./.esengine/editor/", "./types/editor/")];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// The versions `types/` was generated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Stamp {
+    sdk: String,
+    editor: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypesInfo {
+    pub sdk_version: String,
+    pub editor_version: String,
+    /// Whether the files were (re)written by this call.
+    pub updated: bool,
+}
+
+// =============================================================================
+// Sync
+// =============================================================================
+
+fn read_stamp(project_dir: &Path) -> Option<Stamp> {
+    let data = std::fs::read(project_dir.join(TYPES_DIR).join(STAMP_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Writes the type definitions unless `types/` already holds these
+/// versions. With `create` false, a project without `types/` is left alone.
+pub fn sync(project_dir: &Path, create: bool) -> Result<TypesInfo, String> {
+    let engine = Engine::for_project(project_dir)?;
+    let stamp = Stamp { sdk: engine.name().to_string(), editor: env!("CARGO_PKG_VERSION").to_string() };
+    let current = read_stamp(project_dir);
+    let updated = match &current {
+        Some(current) => *current != stamp,
+        None => create,
+    };
+    if updated {
+        write_types(project_dir, &engine, &stamp)?;
+        eprintln!("[Typings] Wrote SDK {} types to {}", stamp.sdk, project_dir.join(TYPES_DIR).display());
+    }
+    Ok(TypesInfo { sdk_version: stamp.sdk, editor_version: stamp.editor, updated })
+}
+
+fn write_types(project_dir: &Path, engine: &Engine, stamp: &Stamp) -> Result<(), String> {
+    let dir = project_dir.join(TYPES_DIR);
+    let header = |source: &str| {
+        format!("// {} — copied by the ESEngine editor; re-synced when the engine version changes.\n", source)
+    };
+    for (rel, file) in SDK_TYPES {
+        let mut data = header(&format!("esengine SDK {}", stamp.sdk)).into_bytes();
+        data.extend_from_slice(&engine.read(*file)?);
+        write_file(&dir.join("esengine").join(rel), &data)?;
+    }
+    let mut data = header(&format!("ESEngine editor API {}", stamp.editor)).into_bytes();
+    data.extend_from_slice(embedded_assets::EDITOR_DTS.bytes());
+    write_file(&dir.join("editor/index.d.ts"), &data)?;
+    // Written last: an interrupted sync leaves a stale stamp and is redone.
+    let stamp = serde_json::to_vec_pretty(stamp).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(STAMP_FILE), &stamp)?;
+    point_tsconfig(project_dir)
+}
+
+/// Repoints the scaffolded `tsconfig.json` paths at `types/`. Edited as
+/// text so the user's formatting and key order survive; paths the user
+/// changed are left alone.
+fn point_tsconfig(project_dir: &Path) -> Result<(), String> {
+    let path = project_dir.join("tsconfig.json");
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let updated = TSCONFIG_PATHS.iter().fold(text.clone(), |text, (from, to)| text.replace(from, to));
+    if updated != text {
+        write_atomic(&path, updated.as_bytes())?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Materializes the project's type definitions into `types/`, or refreshes
+/// them when they came from another engine or editor version. With
+/// `create` false (the check on opening a project), only an existing
+/// `types/` folder is refreshed.
+#[tauri::command]
+pub async fn sync_project_types(project_dir: String, create: Option<bool>) -> Result<TypesInfo, String> {
+    tokio::task::spawn_blocking(move || sync(Path::new(&project_dir), create.unwrap_or(true)))
+        .await
+        .map_err(|e| format!("Typings task failed: {}", e))?
+}
@@ -0,0 +1,112 @@
+//! Reveal in file manager — shows a file selected in its folder
+//!
+//! `open_folder` only opens a folder; `reveal_in_file_manager` opens the
+//! folder of a file with the file selected: `explorer /select` on Windows,
+//! `open -R` on macOS, and the freedesktop `FileManager1` D-Bus interface
+//! on Linux, which Nautilus, Dolphin, Nemo and most others implement. File
+//! managers without it get the folder opened instead.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(not(windows))]
+use std::process::Stdio;
+
+/// The absolute path of an existing file or folder.
+fn resolve(path: &str) -> Result<PathBuf, String> {
+    let path = std::fs::canonicalize(path).map_err(|e| format!("Cannot reveal {}: {}", path, e))?;
+    // Explorer does not understand the `\\?\` prefix `canonicalize` adds.
+    #[cfg(windows)]
+    let path = match path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path,
+    };
+    Ok(path)
+}
+
+#[cfg(windows)]
+fn reveal(path: &Path) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    // `/select,"<path>"` must reach Explorer as is; the usual argument
+    // quoting would wrap the whole switch in quotes.
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .map_err(|e| format!("Failed to start Explorer: {}", e))?;
+    // Explorer exits with 1 even when it succeeded, so there is nothing to
+    // wait for.
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), String> {
+    let status = Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to start Finder: {}", e))?;
+    if !status.success() {
+        return Err(format!("Finder could not reveal {}", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal(path: &Path) -> Result<(), String> {
+    let uri = url::Url::from_file_path(path).map_err(|_| format!("Cannot reveal {}", path.display()))?;
+    // `ShowItems(uris, startup_id)`, through whichever D-Bus client exists.
+    let dbus_send = [
+        "dbus-send",
+        "--session",
+        "--print-reply",
+        "--dest=org.freedesktop.FileManager1",
+        "/org/freedesktop/FileManager1",
+        "org.freedesktop.FileManager1.ShowItems",
+    ];
+    let uris = format!("array:string:{}", uri);
+    let gdbus = [
+        "gdbus",
+        "call",
+        "--session",
+        "--dest",
+        "org.freedesktop.FileManager1",
+        "--object-path",
+        "/org/freedesktop/FileManager1",
+        "--method",
+        "org.freedesktop.FileManager1.ShowItems",
+    ];
+    let gdbus_uris = format!("['{}']", uri.as_str().replace('\'', "%27"));
+    let attempts: [Vec<&str>; 2] = [
+        dbus_send.iter().copied().chain([uris.as_str(), "string:"]).collect(),
+        gdbus.iter().copied().chain([gdbus_uris.as_str(), ""]).collect(),
+    ];
+    for args in attempts {
+        let status = Command::new(args[0])
+            .args(&args[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if status.is_ok_and(|status| status.success()) {
+            return Ok(());
+        }
+    }
+    // No file manager answered; opening the folder is the next best thing.
+    let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+    open::that(folder).map_err(|e| e.to_string())
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Opens the folder of `path` in the system file manager with `path`
+/// selected.
+#[tauri::command]
+pub async fn reveal_in_file_manager(path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || reveal(&resolve(&path)?))
+        .await
+        .map_err(|e| format!("Reveal task failed: {}", e))?
+}
@@ -0,0 +1,241 @@
+//! Optional runtimes — the Spine and physics modules
+//!
+//! Most projects use at most one Spine version and many no physics, so the
+//! modules are optional. Each has a cargo feature (`spine38`, `spine41`,
+//! `spine42`, `physics` and `rapier`; all but `rapier` on by default) that
+//! embeds it in the editor.
+//! A runtime built without its feature is downloaded the first time it is
+//! needed, from the release of the embedded engine, checked against the
+//! published SHA-256 and kept in `<app data>/runtimes/<engine version>/`.
+//!
+//! The editor loads a module with `get_runtime_file`; the preview server
+//! serves the same files under `wasm/`. A local engine build (see
+//! `engines`) replaces them like the other engine files.
+//!
+//! The physics module comes in two backends, Box2D (`physics`) and Rapier
+//! (`physics-rapier`). Games always load it as `physics.js` and
+//! `physics.wasm`; `physics_file` maps those names to the project's backend.
+
+use crate::embedded_assets::{self, Embedded};
+use crate::engines;
+use crate::project::settings::PhysicsBackend;
+use crate::project::write_atomic;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{ipc, AppHandle, Emitter, Manager};
+
+pub(crate) const RUNTIMES_DIR: &str = "runtimes";
+
+/// `<app data>/runtimes/<engine version>/`, set on startup.
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Held while downloading, so concurrent first uses fetch a runtime once.
+static DOWNLOADS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+/// The `.js` and `.wasm` file of a runtime.
+type RuntimeFiles = Option<(Embedded, Embedded)>;
+
+#[cfg(feature = "spine38")]
+const SPINE38: RuntimeFiles = Some((embedded_assets::SPINE38_JS, embedded_assets::SPINE38_WASM));
+#[cfg(not(feature = "spine38"))]
+const SPINE38: RuntimeFiles = None;
+#[cfg(feature = "spine41")]
+const SPINE41: RuntimeFiles = Some((embedded_assets::SPINE41_JS, embedded_assets::SPINE41_WASM));
+#[cfg(not(feature = "spine41"))]
+const SPINE41: RuntimeFiles = None;
+#[cfg(feature = "spine42")]
+const SPINE42: RuntimeFiles = Some((embedded_assets::SPINE42_JS, embedded_assets::SPINE42_WASM));
+#[cfg(not(feature = "spine42"))]
+const SPINE42: RuntimeFiles = None;
+#[cfg(feature = "physics")]
+const PHYSICS: RuntimeFiles = Some((embedded_assets::PHYSICS_JS, embedded_assets::PHYSICS_WASM));
+#[cfg(not(feature = "physics"))]
+const PHYSICS: RuntimeFiles = None;
+#[cfg(feature = "rapier")]
+const RAPIER: RuntimeFiles = Some((embedded_assets::RAPIER_JS, embedded_assets::RAPIER_WASM));
+#[cfg(not(feature = "rapier"))]
+const RAPIER: RuntimeFiles = None;
+
+pub struct Runtime {
+    /// Also the stem of its files: `spine38.js`, `spine38.wasm`.
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Compiled-in files, when the cargo feature of the same name is on.
+    embedded: RuntimeFiles,
+}
+
+pub const RUNTIMES: &[Runtime] = &[
+    Runtime { id: "spine38", name: "Spine 3.8", embedded: SPINE38 },
+    Runtime { id: "spine41", name: "Spine 4.1", embedded: SPINE41 },
+    Runtime { id: "spine42", name: "Spine 4.2", embedded: SPINE42 },
+    Runtime { id: "physics", name: "Physics (Box2D)", embedded: PHYSICS },
+    Runtime { id: "physics-rapier", name: "Physics (Rapier)", embedded: RAPIER },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeInfo {
+    pub id: String,
+    pub name: String,
+    /// Built into the editor.
+    pub embedded: bool,
+    /// Downloaded before and available offline.
+    pub downloaded: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct RuntimeProgress {
+    id: String,
+    stage: String,
+    message: String,
+}
+
+impl Runtime {
+    fn file_names(&self) -> [String; 2] {
+        [format!("{}.js", self.id), format!("{}.wasm", self.id)]
+    }
+
+    fn embedded_file(&self, name: &str) -> Option<Embedded> {
+        let (js, wasm) = self.embedded?;
+        if name.ends_with(".wasm") {
+            Some(wasm)
+        } else {
+            Some(js)
+        }
+    }
+
+    fn is_downloaded(&self) -> bool {
+        CACHE_DIR.get().is_some_and(|dir| self.file_names().iter().all(|name| dir.join(name).is_file()))
+    }
+
+    fn info(&self) -> RuntimeInfo {
+        RuntimeInfo {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            embedded: self.embedded.is_some(),
+            downloaded: self.is_downloaded(),
+        }
+    }
+}
+
+/// The runtime a file such as `spine41.wasm` belongs to.
+pub fn file(name: &str) -> Option<&'static Runtime> {
+    let stem = name.strip_suffix(".js").or_else(|| name.strip_suffix(".wasm"))?;
+    RUNTIMES.iter().find(|runtime| runtime.id == stem)
+}
+
+/// The file of `project_dir`'s physics backend that a game requesting
+/// `physics.js` or `physics.wasm` gets; other names are returned as is.
+pub fn physics_file(project_dir: &Path, name: &str) -> String {
+    match name.strip_prefix("physics.") {
+        Some(ext @ ("js" | "wasm")) => format!("{}.{}", PhysicsBackend::for_project(project_dir).runtime_id(), ext),
+        _ => name.to_string(),
+    }
+}
+
+fn find(id: &str) -> Result<&'static Runtime, String> {
+    RUNTIMES.iter().find(|runtime| runtime.id == id).ok_or_else(|| format!("Unknown runtime: {}", id))
+}
+
+// =============================================================================
+// Loading
+// =============================================================================
+
+/// Records where downloaded runtimes are kept; called on startup.
+pub fn init(app: &AppHandle) {
+    if let Ok(dir) = app.path().app_data_dir() {
+        let _ = CACHE_DIR.set(dir.join(RUNTIMES_DIR).join(engines::embedded_version()));
+    }
+}
+
+/// A runtime file that is at hand: from the local engine build, the
+/// binary or an earlier download. `None` when it must be downloaded first.
+pub fn read(name: &str) -> Result<Option<Cow<'static, [u8]>>, String> {
+    let runtime = file(name).ok_or_else(|| format!("Unknown runtime file: {}", name))?;
+    if let Some(data) = engines::dev_file(&format!("wasm/{}", name)) {
+        return Ok(Some(Cow::Owned(data)));
+    }
+    if let Some(file) = runtime.embedded_file(name) {
+        return Ok(Some(Cow::Borrowed(file.bytes())));
+    }
+    Ok(CACHE_DIR.get().and_then(|dir| std::fs::read(dir.join(name)).ok()).map(Cow::Owned))
+}
+
+/// A runtime file, downloading its runtime first when needed.
+pub async fn load(app: Option<&AppHandle>, name: &str) -> Result<Cow<'static, [u8]>, String> {
+    if let Some(data) = read(name)? {
+        return Ok(data);
+    }
+    download(app, file(name).ok_or_else(|| format!("Unknown runtime file: {}", name))?).await?;
+    read(name)?.ok_or_else(|| format!("Runtime file {} is missing after its download", name))
+}
+
+fn emit_progress(app: Option<&AppHandle>, id: &str, stage: &str, message: &str) {
+    if let Some(app) = app {
+        let event = RuntimeProgress { id: id.to_string(), stage: stage.to_string(), message: message.to_string() };
+        let _ = app.emit("runtime-progress", event);
+    }
+}
+
+/// Fetches both files of `runtime` from the embedded engine's release.
+async fn download(app: Option<&AppHandle>, runtime: &Runtime) -> Result<(), String> {
+    let _guard = DOWNLOADS.lock().await;
+    if runtime.is_downloaded() {
+        return Ok(());
+    }
+    let dir = CACHE_DIR.get().ok_or("No folder for downloaded runtimes")?;
+    let version = engines::embedded_version();
+    emit_progress(app, runtime.id, "download", &format!("Downloading {}...", runtime.name));
+    for name in runtime.file_names() {
+        let url = engines::release_url(version, &name);
+        let expected = engines::published_checksum(&url).await?;
+        let response = reqwest::get(&url).await.map_err(|e| format!("Download failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download of {} failed: HTTP {}", name, response.status()));
+        }
+        let data = response.bytes().await.map_err(|e| format!("Download interrupted: {}", e))?;
+        let actual = format!("{:x}", Sha256::digest(&data));
+        if actual != expected {
+            return Err(format!("Checksum mismatch for {}: expected {}, got {}", name, expected, actual));
+        }
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        write_atomic(&dir.join(&name), &data)?;
+    }
+    emit_progress(app, runtime.id, "complete", &format!("{} downloaded", runtime.name));
+    eprintln!("[Runtimes] Downloaded {} {} to {}", runtime.id, version, dir.display());
+    Ok(())
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_runtimes() -> Vec<RuntimeInfo> {
+    RUNTIMES.iter().map(Runtime::info).collect()
+}
+
+/// Downloads a runtime the editor was built without, e.g. before going
+/// offline. Progress is reported as `runtime-progress` events.
+#[tauri::command]
+pub async fn download_runtime(app: AppHandle, id: String) -> Result<RuntimeInfo, String> {
+    let runtime = find(&id)?;
+    if runtime.embedded.is_none() {
+        download(Some(&app), runtime).await?;
+    }
+    Ok(runtime.info())
+}
+
+/// A runtime file such as `spine42.wasm` as raw bytes, downloaded first
+/// when the editor was built without it.
+#[tauri::command]
+pub async fn get_runtime_file(app: AppHandle, name: String) -> Result<ipc::Response, String> {
+    let data = load(Some(&app), &name).await?;
+    Ok(ipc::Response::new(data.into_owned()))
+}
@@ -0,0 +1,396 @@
+//! Scene saving, autosave and crash recovery
+//!
+//! Saves go through `save_scene`: the editor's JSON is validated, a journal
+//! entry is written to `.esengine/journal/`, the new contents are written
+//! durably to a temp file next to the scene and renamed over it, and the
+//! journal entry is removed. A journal entry that survives therefore marks
+//! a save that was interrupted. Independently, `autosave_scene` keeps
+//! rolling copies of the in-memory scene under `.esengine/autosave/`. After
+//! an unclean shutdown `list_scene_recovery` reports both, and
+//! `restore_scene_version` puts one back. Prefab resolution lives in
+//! `prefab`, the undo history kept across crashes in `undo_journal`.
+
+pub mod prefab;
+pub mod undo_journal;
+
+use crate::export::build_info::iso8601_utc;
+use crate::format;
+use crate::project::write_durable;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const JOURNAL_DIR: &str = ".esengine/journal";
+pub(crate) const AUTOSAVE_DIR: &str = ".esengine/autosave";
+/// Describes the scene an autosave folder belongs to.
+pub(crate) const AUTOSAVE_INDEX: &str = "index.json";
+/// Suffix of the temp file `write_durable` renames over the scene.
+const SAVING_SUFFIX: &str = ".tmp";
+const DEFAULT_AUTOSAVE_INTERVAL_MINUTES: u64 = 5;
+const DEFAULT_AUTOSAVE_KEEP: usize = 10;
+const SCENE_EXTENSIONS: &[&str] = &["esscene", "esprefab"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    scene: String,
+    sha256: String,
+    started: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AutosaveIndex {
+    scene: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryKind {
+    /// The complete contents of a save that crashed before the rename.
+    InterruptedSave,
+    Autosave,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryVersion {
+    /// Scene path relative to the project.
+    pub scene: String,
+    pub kind: RecoveryKind,
+    /// Pass to `restore_scene_version`.
+    pub file: String,
+    pub saved: String,
+    pub size: u64,
+    /// The version is newer than the scene on disk and differs from it.
+    pub unsaved: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneRecovery {
+    /// Interrupted saves or autosaves with unsaved work were found.
+    pub unclean_shutdown: bool,
+    /// Scenes whose file on disk is not valid JSON.
+    pub corrupt_scenes: Vec<String>,
+    /// Newest first.
+    pub versions: Vec<RecoveryVersion>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Crash-safe save of a scene or prefab, canonicalized first when
+/// `format.onSave` is set.
+#[tauri::command]
+pub async fn save_scene(app: AppHandle, project_dir: String, path: String, content: String) -> Result<(), String> {
+    let canonical = format::format_on_save(&app);
+    tokio::task::spawn_blocking(move || {
+        let content = if canonical { format::canonicalize_scene(&content).unwrap_or(content) } else { content };
+        save(Path::new(&project_dir), &path, &content)
+    })
+    .await
+    .map_err(|e| format!("Save task failed: {}", e))?
+}
+
+/// Stores an autosave copy unless the newest one is younger than
+/// `interval_minutes` or has the same contents. Returns whether a copy was
+/// written; only the newest `keep` copies per scene are kept.
+#[tauri::command]
+pub async fn autosave_scene(
+    project_dir: String,
+    path: String,
+    content: String,
+    interval_minutes: Option<u64>,
+    keep: Option<usize>,
+) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || {
+        autosave(
+            Path::new(&project_dir),
+            &path,
+            &content,
+            interval_minutes.unwrap_or(DEFAULT_AUTOSAVE_INTERVAL_MINUTES),
+            keep.unwrap_or(DEFAULT_AUTOSAVE_KEEP).max(1),
+        )
+    })
+    .await
+    .map_err(|e| format!("Autosave task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn list_scene_recovery(project_dir: String) -> Result<SceneRecovery, String> {
+    tokio::task::spawn_blocking(move || recovery(Path::new(&project_dir)))
+        .await
+        .map_err(|e| format!("Recovery task failed: {}", e))?
+}
+
+/// Replaces the scene with a recovered version. The scene's current
+/// contents are autosaved first, so the restore itself can be undone.
+#[tauri::command]
+pub async fn restore_scene_version(project_dir: String, scene: String, file: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || restore(Path::new(&project_dir), &scene, Path::new(&file)))
+        .await
+        .map_err(|e| format!("Recovery task failed: {}", e))?
+}
+
+/// Drops the interrupted save and autosaves of a scene, e.g. after the user
+/// declined to recover it.
+#[tauri::command]
+pub async fn discard_scene_recovery(project_dir: String, scene: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = Path::new(&project_dir);
+        let scene_path = scene_path(project_dir, &scene)?;
+        clear_journal(project_dir, &scene, &scene_path);
+        let dir = autosave_dir(project_dir, &scene);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove autosaves: {}", e))?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Recovery task failed: {}", e))?
+}
+
+// =============================================================================
+// Save
+// =============================================================================
+
+pub(crate) fn save(project_dir: &Path, scene: &str, content: &str) -> Result<(), String> {
+    let path = scene_path(project_dir, scene)?;
+    serde_json::from_str::<Value>(content).map_err(|e| format!("Refusing to save invalid scene JSON: {}", e))?;
+
+    let journal = journal_path(project_dir, scene);
+    let entry = JournalEntry {
+        scene: scene.to_string(),
+        sha256: hash(content.as_bytes()),
+        started: crate::project::now_iso8601(),
+    };
+    let data = serde_json::to_vec_pretty(&entry).map_err(|e| e.to_string())?;
+    write_durable(&journal, &data)?;
+
+    write_durable(&path, content.as_bytes())?;
+    let _ = std::fs::remove_file(&journal);
+    Ok(())
+}
+
+fn clear_journal(project_dir: &Path, scene: &str, scene_path: &Path) {
+    let _ = std::fs::remove_file(journal_path(project_dir, scene));
+    let _ = std::fs::remove_file(saving_path(scene_path));
+}
+
+// =============================================================================
+// Autosave
+// =============================================================================
+
+fn autosave(project_dir: &Path, scene: &str, content: &str, interval_minutes: u64, keep: usize) -> Result<bool, String> {
+    scene_path(project_dir, scene)?;
+    let dir = autosave_dir(project_dir, scene);
+    let copies = autosave_copies(&dir);
+    let now = unix_millis();
+
+    if let Some((stamp, newest)) = copies.first() {
+        if now.saturating_sub(*stamp) < interval_minutes * 60_000 {
+            return Ok(false);
+        }
+        if std::fs::read(newest).is_ok_and(|data| data == content.as_bytes()) {
+            return Ok(false);
+        }
+    }
+
+    let index = serde_json::to_vec(&AutosaveIndex {
+        scene: scene.to_string(),
+    })
+    .map_err(|e| e.to_string())?;
+    write_durable(&dir.join(AUTOSAVE_INDEX), &index)?;
+    write_durable(&dir.join(format!("{}.{}", now, extension(scene))), content.as_bytes())?;
+
+    for (_, old) in copies.iter().skip(keep - 1) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(true)
+}
+
+/// Autosave copies in `dir` with their millisecond stamps, newest first.
+fn autosave_copies(dir: &Path) -> Vec<(u64, PathBuf)> {
+    let mut copies: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stamp = path.file_stem()?.to_str()?.parse().ok()?;
+            Some((stamp, path))
+        })
+        .collect();
+    copies.sort_by_key(|copy| std::cmp::Reverse(copy.0));
+    copies
+}
+
+// =============================================================================
+// Recovery
+// =============================================================================
+
+fn recovery(project_dir: &Path) -> Result<SceneRecovery, String> {
+    let mut versions = Vec::new();
+
+    for entry in std::fs::read_dir(project_dir.join(JOURNAL_DIR)).into_iter().flatten().flatten() {
+        let Some(journal) = std::fs::read_to_string(entry.path())
+            .ok()
+            .and_then(|s| serde_json::from_str::<JournalEntry>(&s).ok())
+        else {
+            continue;
+        };
+        let Ok(path) = scene_path(project_dir, &journal.scene) else {
+            continue;
+        };
+        let saving = saving_path(&path);
+        match std::fs::read(&saving) {
+            // The rename may have happened right before the crash.
+            Ok(data) if hash(&data) == journal.sha256 && std::fs::read(&path).ok() != Some(data.clone()) => {
+                versions.push(RecoveryVersion {
+                    scene: journal.scene.clone(),
+                    kind: RecoveryKind::InterruptedSave,
+                    file: saving.to_string_lossy().to_string(),
+                    saved: journal.started.clone(),
+                    size: data.len() as u64,
+                    unsaved: true,
+                });
+            }
+            // Nothing complete to offer; the old scene is still intact.
+            _ => clear_journal(project_dir, &journal.scene, &path),
+        }
+    }
+
+    for entry in std::fs::read_dir(project_dir.join(AUTOSAVE_DIR)).into_iter().flatten().flatten() {
+        let dir = entry.path();
+        let Some(index) = std::fs::read_to_string(dir.join(AUTOSAVE_INDEX))
+            .ok()
+            .and_then(|s| serde_json::from_str::<AutosaveIndex>(&s).ok())
+        else {
+            continue;
+        };
+        let Ok(path) = scene_path(project_dir, &index.scene) else {
+            continue;
+        };
+        let scene_modified = modified_millis(&path);
+        let current = std::fs::read(&path).ok();
+        for (stamp, copy) in autosave_copies(&dir) {
+            let Ok(data) = std::fs::read(&copy) else {
+                continue;
+            };
+            versions.push(RecoveryVersion {
+                scene: index.scene.clone(),
+                kind: RecoveryKind::Autosave,
+                file: copy.to_string_lossy().to_string(),
+                saved: iso8601_utc(stamp / 1000),
+                size: data.len() as u64,
+                unsaved: stamp > scene_modified && current.as_ref() != Some(&data),
+            });
+        }
+    }
+
+    versions.sort_by(|a, b| b.saved.cmp(&a.saved).then_with(|| a.scene.cmp(&b.scene)));
+    let mut corrupt_scenes: Vec<String> = versions
+        .iter()
+        .map(|v| v.scene.clone())
+        .filter(|scene| {
+            scene_path(project_dir, scene)
+                .ok()
+                .and_then(|p| std::fs::read(p).ok())
+                .is_some_and(|data| serde_json::from_slice::<Value>(&data).is_err())
+        })
+        .collect();
+    corrupt_scenes.sort();
+    corrupt_scenes.dedup();
+    Ok(SceneRecovery {
+        unclean_shutdown: versions.iter().any(|v| v.unsaved) || !corrupt_scenes.is_empty(),
+        corrupt_scenes,
+        versions,
+    })
+}
+
+fn restore(project_dir: &Path, scene: &str, file: &Path) -> Result<(), String> {
+    let path = scene_path(project_dir, scene)?;
+    let allowed = [saving_path(&path), autosave_dir(project_dir, scene)];
+    // Compared by component, so a `..` after an allowed directory cannot leave it.
+    if !allowed.iter().any(|a| file.strip_prefix(a).is_ok_and(is_relative_inside)) {
+        return Err(format!("{} is not a recovery copy of {}", file.display(), scene));
+    }
+    let content = std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+
+    if let Ok(current) = std::fs::read_to_string(&path) {
+        if current != content {
+            autosave(project_dir, scene, &current, 0, DEFAULT_AUTOSAVE_KEEP)?;
+        }
+    }
+    save(project_dir, scene, &content)
+}
+
+// =============================================================================
+// Paths
+// =============================================================================
+
+/// Validates a project-relative scene path and returns its absolute path.
+pub(crate) fn scene_path(project_dir: &Path, scene: &str) -> Result<PathBuf, String> {
+    let scene = scene.replace('\\', "/");
+    if !is_relative_inside(Path::new(&scene)) {
+        return Err(format!("Scene path is outside the project: {}", scene));
+    }
+    if !SCENE_EXTENSIONS.contains(&extension(&scene).as_str()) {
+        return Err(format!("Not a scene or prefab: {}", scene));
+    }
+    Ok(project_dir.join(scene))
+}
+
+/// Whether `path` only names entries below the directory it is joined to:
+/// no root, drive prefix, `.` or `..`.
+fn is_relative_inside(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Stable file name for per-scene state.
+fn scene_key(scene: &str) -> String {
+    hash(scene.replace('\\', "/").as_bytes())[..16].to_string()
+}
+
+fn journal_path(project_dir: &Path, scene: &str) -> PathBuf {
+    project_dir.join(JOURNAL_DIR).join(format!("{}.json", scene_key(scene)))
+}
+
+fn autosave_dir(project_dir: &Path, scene: &str) -> PathBuf {
+    project_dir.join(AUTOSAVE_DIR).join(scene_key(scene))
+}
+
+fn saving_path(scene_path: &Path) -> PathBuf {
+    let mut path = scene_path.as_os_str().to_owned();
+    path.push(SAVING_SUFFIX);
+    PathBuf::from(path)
+}
+
+fn extension(scene: &str) -> String {
+    crate::export::assets::extension_of(scene)
+}
+
+fn hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn modified_millis(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
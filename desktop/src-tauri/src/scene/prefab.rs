@@ -0,0 +1,508 @@
+//! Prefab resolution: variants, nested prefabs and override chains
+//!
+//! A port of the SDK's `flattenPrefab` (sdk/src/prefab) so large prefabs can
+//! be resolved without round-trips through the webview. Prefab files are
+//! parsed once and cached by path and modification time. Variants resolve
+//! to their base prefab with the variant's overrides applied before the
+//! instance's; nested prefabs are flattened in place with their own
+//! overrides. `PrefabResolver` is shared with the export pipeline; the
+//! commands below serve the editor.
+
+use crate::export::assets::{self, AssetDatabase};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+
+const MAX_NESTING_DEPTH: usize = 10;
+
+/// Component fields holding entity ids, as registered through `entityFields`
+/// in the SDK; they are remapped when prefab entities get scene ids.
+const ENTITY_FIELDS: &[(&str, &[&str])] = &[
+    ("ScrollView", &["contentEntity"]),
+    ("Toggle", &["graphicEntity", "group"]),
+    ("Dropdown", &["listEntity", "labelEntity"]),
+    ("Slider", &["fillEntity", "handleEntity"]),
+    ("ProgressBar", &["fillEntity"]),
+    ("RevoluteJoint", &["connectedEntity"]),
+    ("DistanceJoint", &["connectedEntity"]),
+    ("PrismaticJoint", &["connectedEntity"]),
+    ("WeldJoint", &["connectedEntity"]),
+    ("WheelJoint", &["connectedEntity"]),
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefabData {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub name: String,
+    pub root_entity_id: u64,
+    pub entities: Vec<PrefabEntity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_prefab: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<PrefabOverride>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefabEntity {
+    pub prefab_entity_id: u64,
+    #[serde(default)]
+    pub name: String,
+    pub parent: Option<u64>,
+    #[serde(default)]
+    pub children: Vec<u64>,
+    #[serde(default)]
+    pub components: Vec<ComponentData>,
+    #[serde(default = "visible_default")]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nested_prefab: Option<NestedPrefabRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NestedPrefabRef {
+    pub prefab_path: String,
+    #[serde(default)]
+    pub overrides: Vec<PrefabOverride>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentData {
+    #[serde(rename = "type")]
+    pub component_type: String,
+    #[serde(default)]
+    pub data: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideKind {
+    Property,
+    ComponentAdded,
+    ComponentRemoved,
+    Name,
+    Visibility,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefabOverride {
+    pub prefab_entity_id: u64,
+    #[serde(rename = "type")]
+    pub kind: OverrideKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_data: Option<ComponentData>,
+}
+
+/// A prefab entity with its scene id, matching the SDK's `ProcessedEntity`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedEntity {
+    pub id: u64,
+    pub prefab_entity_id: u64,
+    pub name: String,
+    pub parent: Option<u64>,
+    pub children: Vec<u64>,
+    pub components: Vec<ComponentData>,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedPrefab {
+    pub root_id: u64,
+    pub entities: Vec<ProcessedEntity>,
+    /// First id not used by `entities`.
+    pub next_id: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstanceDiff {
+    /// Overrides that turn the source prefab into the instance.
+    pub overrides: Vec<PrefabOverride>,
+    /// Instance entities with no counterpart in the prefab.
+    pub added_entities: Vec<u64>,
+    /// Prefab entity ids missing from the instance.
+    pub removed_entities: Vec<u64>,
+}
+
+/// A parsed prefab with the modification time it was read at.
+type CachedPrefab = (Option<SystemTime>, Arc<PrefabData>);
+
+/// Parsed prefab files keyed by absolute path.
+#[derive(Default)]
+pub struct PrefabCache {
+    entries: Mutex<HashMap<PathBuf, CachedPrefab>>,
+}
+
+fn visible_default() -> bool {
+    true
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Flattens a prefab (path or asset UUID) with `overrides`, numbering its
+/// entities from `first_id`.
+#[tauri::command]
+pub async fn resolve_prefab(
+    app: AppHandle,
+    project_dir: String,
+    path: String,
+    overrides: Option<Vec<PrefabOverride>>,
+    first_id: Option<u64>,
+) -> Result<ResolvedPrefab, String> {
+    tokio::task::spawn_blocking(move || {
+        let cache = app.state::<PrefabCache>();
+        let mut resolver = PrefabResolver::new(Path::new(&project_dir), &cache);
+        resolver.resolve(&path, &overrides.unwrap_or_default(), first_id.unwrap_or(1))
+    })
+    .await
+    .map_err(|e| format!("Prefab task failed: {}", e))?
+}
+
+/// Compares the entities of a scene instance (scene entity JSON with their
+/// `prefab` links) against the prefab they came from.
+#[tauri::command]
+pub async fn diff_prefab_instance(
+    app: AppHandle,
+    project_dir: String,
+    path: String,
+    entities: Vec<Value>,
+) -> Result<InstanceDiff, String> {
+    tokio::task::spawn_blocking(move || {
+        let cache = app.state::<PrefabCache>();
+        let mut resolver = PrefabResolver::new(Path::new(&project_dir), &cache);
+        let source = resolver.resolve(&path, &[], 1)?;
+        Ok(diff_instance(&source.entities, &entities))
+    })
+    .await
+    .map_err(|e| format!("Prefab task failed: {}", e))?
+}
+
+// =============================================================================
+// Resolver
+// =============================================================================
+
+pub(crate) struct PrefabResolver<'a> {
+    project_dir: &'a Path,
+    cache: &'a PrefabCache,
+    /// Scanned on the first UUID reference.
+    db: Option<AssetDatabase>,
+}
+
+impl<'a> PrefabResolver<'a> {
+    pub fn new(project_dir: &'a Path, cache: &'a PrefabCache) -> Self {
+        Self {
+            project_dir,
+            cache,
+            db: None,
+        }
+    }
+
+    pub fn resolve(&mut self, reference: &str, overrides: &[PrefabOverride], first_id: u64) -> Result<ResolvedPrefab, String> {
+        let (path, prefab) = self.load(reference)?;
+        let mut next_id = first_id;
+        let mut chain = vec![path];
+        let (entities, root_id) = self.flatten(&prefab, overrides, &mut next_id, &mut chain)?;
+        Ok(ResolvedPrefab {
+            root_id,
+            entities,
+            next_id,
+        })
+    }
+
+    /// Loads a prefab by project-relative path or UUID, returning its path.
+    fn load(&mut self, reference: &str) -> Result<(String, Arc<PrefabData>), String> {
+        let rel = if assets::is_uuid(reference) {
+            let db = self.db.get_or_insert_with(|| AssetDatabase::scan(self.project_dir));
+            db.path_of(reference)
+                .map(str::to_string)
+                .ok_or_else(|| format!("Unknown prefab asset: {}", reference))?
+        } else {
+            assets::normalize_path(reference.trim_start_matches("./"))
+                .ok_or_else(|| format!("Prefab path is outside the project: {}", reference))?
+        };
+        let full = self.project_dir.join(&rel);
+        let modified = std::fs::metadata(&full).and_then(|m| m.modified()).ok();
+
+        let mut entries = self.cache.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_modified, prefab)) = entries.get(&full) {
+            if *cached_modified == modified && modified.is_some() {
+                return Ok((rel, prefab.clone()));
+            }
+        }
+        let text = std::fs::read_to_string(&full).map_err(|e| format!("Failed to load prefab {}: {}", rel, e))?;
+        let prefab: PrefabData =
+            serde_json::from_str(&text).map_err(|e| format!("Invalid prefab {}: {}", rel, e))?;
+        let prefab = Arc::new(prefab);
+        entries.insert(full, (modified, prefab.clone()));
+        Ok((rel, prefab))
+    }
+
+    /// `chain` holds the prefabs being flattened, outermost first, to catch
+    /// circular references.
+    fn flatten(
+        &mut self,
+        prefab: &PrefabData,
+        overrides: &[PrefabOverride],
+        next_id: &mut u64,
+        chain: &mut Vec<String>,
+    ) -> Result<(Vec<ProcessedEntity>, u64), String> {
+        if chain.len() > MAX_NESTING_DEPTH + 1 {
+            return Err(format!(
+                "Prefab nesting depth exceeded {}. Check for deep or circular nesting in: {}",
+                MAX_NESTING_DEPTH, prefab.name
+            ));
+        }
+
+        if let Some(base) = &prefab.base_prefab {
+            let (base_path, base_prefab) = self.enter(base, chain)?;
+            let combined: Vec<PrefabOverride> = prefab.overrides.iter().chain(overrides).cloned().collect();
+            let result = self.flatten(&base_prefab, &combined, next_id, chain);
+            chain.retain(|p| *p != base_path);
+            return result;
+        }
+
+        let mut ids: HashMap<u64, u64> = HashMap::new();
+        for pe in prefab.entities.iter().filter(|pe| pe.nested_prefab.is_none()) {
+            ids.insert(pe.prefab_entity_id, *next_id);
+            *next_id += 1;
+        }
+
+        // Nested prefabs first, so every id is known when children lists
+        // and entity references are mapped.
+        let mut nested_entities: HashMap<u64, Vec<ProcessedEntity>> = HashMap::new();
+        for pe in &prefab.entities {
+            let Some(nested) = &pe.nested_prefab else {
+                continue;
+            };
+            let (nested_path, nested_prefab) = self.enter(&nested.prefab_path, chain)?;
+            let (entities, root_id) = self.flatten(&nested_prefab, &nested.overrides, next_id, chain)?;
+            chain.retain(|p| *p != nested_path);
+            ids.insert(pe.prefab_entity_id, root_id);
+            nested_entities.insert(pe.prefab_entity_id, entities);
+        }
+
+        let mut result = Vec::new();
+        for pe in &prefab.entities {
+            if let Some(mut entities) = nested_entities.remove(&pe.prefab_entity_id) {
+                let root_id = ids[&pe.prefab_entity_id];
+                if let Some(root) = entities.iter_mut().find(|e| e.id == root_id) {
+                    root.parent = pe.parent.and_then(|p| ids.get(&p).copied());
+                }
+                result.append(&mut entities);
+                continue;
+            }
+
+            let is_root = pe.prefab_entity_id == prefab.root_entity_id;
+            let mut entity = ProcessedEntity {
+                id: ids[&pe.prefab_entity_id],
+                prefab_entity_id: pe.prefab_entity_id,
+                name: pe.name.clone(),
+                parent: if is_root { None } else { pe.parent.and_then(|p| ids.get(&p).copied()) },
+                children: pe.children.iter().filter_map(|c| ids.get(c).copied()).collect(),
+                components: pe.components.clone(),
+                visible: pe.visible,
+            };
+            remap_entity_refs(&mut entity.components, |id| ids.get(&id).copied());
+            apply_overrides(&mut entity, overrides);
+            result.push(entity);
+        }
+
+        let root_id = ids
+            .get(&prefab.root_entity_id)
+            .copied()
+            .ok_or_else(|| "Failed to resolve prefab root entity".to_string())?;
+        Ok((result, root_id))
+    }
+
+    fn enter(&mut self, reference: &str, chain: &mut Vec<String>) -> Result<(String, Arc<PrefabData>), String> {
+        let (path, prefab) = self.load(reference)?;
+        if chain.contains(&path) {
+            return Err(format!(
+                "Circular prefab reference: \"{}\" is already being instantiated in {}",
+                path,
+                chain.join(" -> ")
+            ));
+        }
+        chain.push(path.clone());
+        Ok((path, prefab))
+    }
+}
+
+fn apply_overrides(entity: &mut ProcessedEntity, overrides: &[PrefabOverride]) {
+    for o in overrides.iter().filter(|o| o.prefab_entity_id == entity.prefab_entity_id) {
+        match o.kind {
+            OverrideKind::Property => {
+                let (Some(ty), Some(name)) = (&o.component_type, &o.property_name) else {
+                    continue;
+                };
+                if let Some(component) = entity.components.iter_mut().find(|c| c.component_type == *ty) {
+                    component.data.insert(name.clone(), o.value.clone().unwrap_or(Value::Null));
+                }
+            }
+            OverrideKind::Name => {
+                if let Some(Value::String(name)) = &o.value {
+                    entity.name = name.clone();
+                }
+            }
+            OverrideKind::Visibility => {
+                if let Some(Value::Bool(visible)) = &o.value {
+                    entity.visible = *visible;
+                }
+            }
+            OverrideKind::ComponentAdded => {
+                if let Some(data) = &o.component_data {
+                    if !entity.components.iter().any(|c| c.component_type == data.component_type) {
+                        entity.components.push(data.clone());
+                    }
+                }
+            }
+            OverrideKind::ComponentRemoved => {
+                if let Some(ty) = &o.component_type {
+                    entity.components.retain(|c| c.component_type != *ty);
+                }
+            }
+        }
+    }
+}
+
+/// Rewrites the entity-id fields of `components` through `map`; 0 means
+/// "no entity" and unmapped ids are left alone.
+fn remap_entity_refs(components: &mut [ComponentData], map: impl Fn(u64) -> Option<u64>) {
+    for component in components {
+        let Some(fields) = entity_fields(&component.component_type) else {
+            continue;
+        };
+        for field in fields {
+            if let Some(id) = component.data.get(*field).and_then(Value::as_u64).filter(|id| *id != 0) {
+                if let Some(mapped) = map(id) {
+                    component.data.insert(field.to_string(), Value::from(mapped));
+                }
+            }
+        }
+    }
+}
+
+fn entity_fields(component_type: &str) -> Option<&'static [&'static str]> {
+    ENTITY_FIELDS.iter().find(|(ty, _)| *ty == component_type).map(|(_, fields)| *fields)
+}
+
+// =============================================================================
+// Instance diff
+// =============================================================================
+
+fn diff_instance(source: &[ProcessedEntity], instance: &[Value]) -> InstanceDiff {
+    let mut diff = InstanceDiff::default();
+
+    // Entity references are compared by prefab entity id on both sides.
+    let source_ids: HashMap<u64, u64> = source.iter().map(|e| (e.id, e.prefab_entity_id)).collect();
+    let mut instance_ids: HashMap<u64, u64> = HashMap::new();
+    let mut linked: HashMap<u64, &Value> = HashMap::new();
+    for entity in instance {
+        let id = entity.get("id").and_then(Value::as_u64);
+        let prefab_id = entity.pointer("/prefab/prefabEntityId").and_then(Value::as_u64);
+        match (id, prefab_id) {
+            (Some(id), Some(prefab_id)) if source.iter().any(|e| e.prefab_entity_id == prefab_id) => {
+                instance_ids.insert(id, prefab_id);
+                linked.insert(prefab_id, entity);
+            }
+            (Some(id), _) => diff.added_entities.push(id),
+            _ => {}
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for source_entity in source {
+        let prefab_id = source_entity.prefab_entity_id;
+        if !seen.insert(prefab_id) {
+            continue;
+        }
+        let Some(entity) = linked.get(&prefab_id) else {
+            diff.removed_entities.push(prefab_id);
+            continue;
+        };
+        let simple = |kind, value| PrefabOverride {
+            prefab_entity_id: prefab_id,
+            kind,
+            component_type: None,
+            property_name: None,
+            value: Some(value),
+            component_data: None,
+        };
+        if let Some(name) = entity.get("name").and_then(Value::as_str) {
+            if name != source_entity.name {
+                diff.overrides.push(simple(OverrideKind::Name, Value::from(name)));
+            }
+        }
+        if let Some(visible) = entity.get("visible").and_then(Value::as_bool) {
+            if visible != source_entity.visible {
+                diff.overrides.push(simple(OverrideKind::Visibility, Value::from(visible)));
+            }
+        }
+
+        // `raw` keeps scene ids for the override values; the remapped copies
+        // compare by prefab entity id.
+        let raw: Vec<ComponentData> = entity
+            .get("components")
+            .and_then(|c| serde_json::from_value(c.clone()).ok())
+            .unwrap_or_default();
+        let mut components = raw.clone();
+        remap_entity_refs(&mut components, |id| instance_ids.get(&id).copied());
+        let mut original = source_entity.components.clone();
+        remap_entity_refs(&mut original, |id| source_ids.get(&id).copied());
+
+        for component in &original {
+            let ty = &component.component_type;
+            let Some(index) = components.iter().position(|c| c.component_type == *ty) else {
+                diff.overrides.push(PrefabOverride {
+                    component_type: Some(ty.clone()),
+                    value: None,
+                    ..simple(OverrideKind::ComponentRemoved, Value::Null)
+                });
+                continue;
+            };
+            let mut keys: Vec<&String> = components[index].data.keys().collect();
+            keys.sort();
+            for key in keys {
+                if component.data.get(key) != components[index].data.get(key) {
+                    diff.overrides.push(PrefabOverride {
+                        component_type: Some(ty.clone()),
+                        property_name: Some(key.clone()),
+                        ..simple(OverrideKind::Property, raw[index].data[key].clone())
+                    });
+                }
+            }
+        }
+        for (index, component) in components.iter().enumerate() {
+            if !original.iter().any(|c| c.component_type == component.component_type) {
+                diff.overrides.push(PrefabOverride {
+                    component_data: Some(raw[index].clone()),
+                    value: None,
+                    ..simple(OverrideKind::ComponentAdded, Value::Null)
+                });
+            }
+        }
+    }
+    diff.added_entities.sort_unstable();
+    diff
+}
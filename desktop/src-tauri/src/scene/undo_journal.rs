@@ -0,0 +1,304 @@
+//! Undo journal — the undo history of an open scene, kept across crashes
+//!
+//! While a scene is open the editor appends every change to its history to
+//! `.esengine/undo/<scene key>.jsonl`, one entry per line:
+//!
+//! - `execute` with the command's serialized form; `null` when it cannot be
+//!   serialized, which cuts the history there, and `merged` when it replaced
+//!   the top of the undo stack
+//! - `undo`, `redo` and `clear`
+//! - `save` once the scene is written; the hash of the file on disk is
+//!   recorded with it
+//! - `snapshot`, the stacks written when the journal is compacted
+//!
+//! Closing the scene, its project or the editor deletes the journal, so one
+//! found by `open_undo_journal` was left by an unclean shutdown. It is
+//! replayed up to the last save that matches the scene on disk, and the undo
+//! and redo stacks at that point are handed back; edits after it never
+//! reached the file, so nothing after it can be replayed. Each save, and
+//! growing past `MAX_JOURNAL_BYTES`, compacts the journal to snapshots,
+//! dropping the oldest steps beyond `MAX_STEPS`.
+
+use super::{hash, scene_key, scene_path};
+use crate::project::write_durable;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const UNDO_DIR: &str = ".esengine/undo";
+const MAX_JOURNAL_BYTES: u64 = 4 * 1024 * 1024;
+/// Steps kept by compaction, like the editor's history size.
+const MAX_STEPS: usize = 100;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JournalOp {
+    Execute {
+        command: Option<Value>,
+        #[serde(default)]
+        merged: bool,
+    },
+    Undo,
+    Redo,
+    Clear,
+    Save {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+    Snapshot {
+        undo: Vec<Value>,
+        redo: Vec<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+}
+
+/// Serialized commands, oldest first: the last of `undo` is undone next,
+/// the last of `redo` redone next.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UndoHistory {
+    pub undo: Vec<Value>,
+    pub redo: Vec<Value>,
+}
+
+struct Replay {
+    /// The stacks at the last save, with the hash of what was saved.
+    saved: Option<(String, UndoHistory)>,
+    current: UndoHistory,
+}
+
+/// Journals opened by this editor session.
+#[derive(Default)]
+pub struct UndoJournals {
+    open: Mutex<HashSet<PathBuf>>,
+}
+
+impl UndoJournals {
+    /// Deletes the journals of a project closed in the workspace.
+    pub fn close_project(&self, project_dir: &Path) {
+        let dir = project_dir.join(UNDO_DIR);
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        open.retain(|journal| {
+            if !journal.starts_with(&dir) {
+                return true;
+            }
+            let _ = std::fs::remove_file(journal);
+            false
+        });
+    }
+
+    /// Deletes every journal on a clean shutdown.
+    pub fn close_all(&self) {
+        for journal in self.open.lock().unwrap_or_else(|e| e.into_inner()).drain() {
+            let _ = std::fs::remove_file(journal);
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Starts the journal of a scene the editor opened. Returns the history
+/// left by an unclean shutdown when it matches the scene on disk; the new
+/// journal starts from it.
+#[tauri::command]
+pub async fn open_undo_journal(
+    app: AppHandle,
+    project_dir: String,
+    scene: String,
+) -> Result<Option<UndoHistory>, String> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = Path::new(&project_dir);
+        let path = scene_path(project_dir, &scene)?;
+        let journal = journal_path(project_dir, &scene);
+        let journals = app.state::<UndoJournals>();
+        let mut open = journals.open.lock().unwrap_or_else(|e| e.into_inner());
+
+        let disk = std::fs::read(&path).map(|data| hash(&data)).ok();
+        let restored = match (&disk, read(&journal)) {
+            (Some(disk), Some(ops)) if !open.contains(&journal) => replay(ops)
+                .saved
+                .filter(|(sha, history)| sha == disk && *history != UndoHistory::default())
+                .map(|(_, history)| history),
+            _ => None,
+        };
+        if let Some(history) = &restored {
+            eprintln!(
+                "[undo-journal] Restored {} undo and {} redo steps of {}",
+                history.undo.len(),
+                history.redo.len(),
+                scene
+            );
+        }
+
+        let start = restored.clone().unwrap_or_default();
+        write(&journal, &[snapshot(start, disk)])?;
+        open.insert(journal);
+        Ok(restored)
+    })
+    .await
+    .map_err(|e| format!("Undo journal task failed: {}", e))?
+}
+
+/// Appends history changes, in order. `save` entries are sent after the
+/// scene was written.
+#[tauri::command]
+pub async fn append_undo_journal(
+    app: AppHandle,
+    project_dir: String,
+    scene: String,
+    ops: Vec<JournalOp>,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let project_dir = Path::new(&project_dir);
+        let path = scene_path(project_dir, &scene)?;
+        let journal = journal_path(project_dir, &scene);
+        let journals = app.state::<UndoJournals>();
+        let open = journals.open.lock().unwrap_or_else(|e| e.into_inner());
+        if !open.contains(&journal) {
+            return Err(format!("No undo journal is open for {}", scene));
+        }
+
+        let mut saved = false;
+        let mut lines = String::new();
+        for op in ops {
+            let op = match op {
+                JournalOp::Snapshot { .. } => return Err("Snapshots are written by compaction".to_string()),
+                JournalOp::Save { .. } => {
+                    saved = true;
+                    JournalOp::Save { sha256: std::fs::read(&path).map(|data| hash(&data)).ok() }
+                }
+                op => op,
+            };
+            lines.push_str(&serde_json::to_string(&op).map_err(|e| e.to_string())?);
+            lines.push('\n');
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .map_err(|e| format!("Failed to append to {}: {}", journal.display(), e))?;
+
+        let size = std::fs::metadata(&journal).map(|m| m.len()).unwrap_or(0);
+        if saved || size > MAX_JOURNAL_BYTES {
+            compact(&journal)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Undo journal task failed: {}", e))?
+}
+
+/// Deletes the journal of a scene the editor closed cleanly.
+#[tauri::command]
+pub fn close_undo_journal(app: AppHandle, project_dir: String, scene: String) {
+    let journal = journal_path(Path::new(&project_dir), &scene);
+    let journals = app.state::<UndoJournals>();
+    let mut open = journals.open.lock().unwrap_or_else(|e| e.into_inner());
+    if open.remove(&journal) {
+        let _ = std::fs::remove_file(&journal);
+    }
+}
+
+// =============================================================================
+// Journal
+// =============================================================================
+
+fn journal_path(project_dir: &Path, scene: &str) -> PathBuf {
+    project_dir.join(UNDO_DIR).join(format!("{}.jsonl", scene_key(scene)))
+}
+
+/// The entries of a journal; a line cut off by a crash ends it.
+fn read(journal: &Path) -> Option<Vec<JournalOp>> {
+    let text = std::fs::read_to_string(journal).ok()?;
+    Some(text.lines().map_while(|line| serde_json::from_str(line).ok()).collect())
+}
+
+fn write(journal: &Path, ops: &[JournalOp]) -> Result<(), String> {
+    let mut text = String::new();
+    for op in ops {
+        text.push_str(&serde_json::to_string(op).map_err(|e| e.to_string())?);
+        text.push('\n');
+    }
+    write_durable(journal, text.as_bytes())
+}
+
+fn replay(ops: Vec<JournalOp>) -> Replay {
+    let mut saved = None;
+    let mut current = UndoHistory::default();
+    for op in ops {
+        match op {
+            JournalOp::Execute { command: Some(command), merged } => {
+                current.redo.clear();
+                match current.undo.last_mut() {
+                    Some(top) if merged => *top = command,
+                    _ => current.undo.push(command),
+                }
+            }
+            JournalOp::Execute { command: None, .. } | JournalOp::Clear => current = UndoHistory::default(),
+            JournalOp::Undo => {
+                if let Some(command) = current.undo.pop() {
+                    current.redo.push(command);
+                }
+            }
+            JournalOp::Redo => {
+                if let Some(command) = current.redo.pop() {
+                    current.undo.push(command);
+                }
+            }
+            JournalOp::Save { sha256 } => {
+                saved = sha256.map(|sha| (sha, current.clone()));
+            }
+            JournalOp::Snapshot { undo, redo, sha256 } => {
+                current = UndoHistory { undo, redo };
+                if let Some(sha) = sha256 {
+                    saved = Some((sha, current.clone()));
+                }
+            }
+        }
+    }
+    Replay { saved, current }
+}
+
+/// Rewrites the journal as the stacks at the last save and, if the history
+/// moved on since, the current stacks.
+fn compact(journal: &Path) -> Result<(), String> {
+    let Replay { saved, current } = replay(read(journal).unwrap_or_default());
+    let mut ops = Vec::new();
+    let unsaved = match saved {
+        Some((sha, history)) => {
+            let moved = history != current;
+            ops.push(snapshot(history, Some(sha)));
+            moved
+        }
+        None => true,
+    };
+    if unsaved {
+        ops.push(snapshot(current, None));
+    }
+    write(journal, &ops)
+}
+
+/// A snapshot entry, trimmed to `MAX_STEPS` and a share of the size limit.
+fn snapshot(mut history: UndoHistory, sha256: Option<String>) -> JournalOp {
+    let budget = MAX_JOURNAL_BYTES as usize / 4;
+    let size = |commands: &[Value]| commands.iter().map(|c| c.to_string().len() + 1).sum::<usize>();
+    while !history.undo.is_empty()
+        && (history.undo.len() + history.redo.len() > MAX_STEPS || size(&history.undo) + size(&history.redo) > budget)
+    {
+        history.undo.remove(0);
+    }
+    while !history.redo.is_empty() && (history.redo.len() > MAX_STEPS || size(&history.redo) > budget) {
+        history.redo.remove(0);
+    }
+    JournalOp::Snapshot { undo: history.undo, redo: history.redo, sha256 }
+}
@@ -0,0 +1,375 @@
+//! Project-wide content search
+//!
+//! Files are walked in parallel with the `ignore` crate, so `.gitignore`,
+//! `.ignore` and hidden folders (including `.esengine/`) are skipped, and
+//! searched line by line with ripgrep's matcher. Matches are streamed to the
+//! frontend as batched `search-results` events while the command runs; the
+//! command itself resolves with a summary once the walk finishes or the
+//! search is cancelled. Replacing is layered on top in `replace`.
+
+pub mod replace;
+
+use crate::jobs::{JobCategory, JobPriority, JobSpec};
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::sinks::UTF8;
+use grep_searcher::{BinaryDetection, SearcherBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::{WalkBuilder, WalkState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+const BATCH_SIZE: usize = 200;
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_PREVIEW_CHARS: usize = 240;
+/// Folders never searched even when not ignored.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "build", "dist"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchOptions {
+    /// Identifies the search in events and for `cancel_search`.
+    #[serde(default)]
+    pub search_id: String,
+    pub root: String,
+    pub query: String,
+    /// Treats `query` as a regular expression instead of literal text.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    /// File extensions to search, e.g. `["esscene", "esprefab"]`; all text
+    /// files when empty.
+    #[serde(default)]
+    pub file_types: Vec<String>,
+    /// Globs relative to `root`; when non-empty only matching files are searched.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    /// Path relative to the search root, with forward slashes.
+    pub file: String,
+    /// 1-based line number.
+    pub line: u64,
+    /// The matched line, trimmed and truncated for display.
+    pub preview: String,
+    /// Character ranges of the matches within `preview`.
+    pub ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Serialize)]
+struct SearchResults {
+    search_id: String,
+    matches: Vec<SearchMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchSummary {
+    pub search_id: String,
+    pub files_searched: usize,
+    pub files_matched: usize,
+    pub matches: usize,
+    /// Set when `max_results` was reached before the walk finished.
+    pub truncated: bool,
+    pub cancelled: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Cancellation flags of running searches.
+#[derive(Default)]
+pub struct SearchRegistry {
+    active: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn search_project(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    registry: State<'_, SearchRegistry>,
+    options: SearchOptions,
+) -> Result<SearchSummary, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    registry
+        .active
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(options.search_id.clone(), cancel.clone());
+
+    let id = options.search_id.clone();
+    let spec = JobSpec::new(JobCategory::Search, format!("Search \"{}\"", options.query))
+        .priority(JobPriority::High)
+        .cancel_flag(cancel.clone());
+    let emitter_app = app.clone();
+    let result = state
+        .jobs
+        .run(&app, spec, move |_| {
+            let emitter = BatchEmitter::new(emitter_app, options.search_id.clone());
+            let summary = search(&options, &cancel, |m| emitter.push(m));
+            emitter.flush();
+            summary
+        })
+        .await;
+
+    registry.active.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+    result
+}
+
+#[tauri::command]
+pub fn cancel_search(registry: State<'_, SearchRegistry>, search_id: String) {
+    if let Some(flag) = registry.active.lock().unwrap_or_else(|e| e.into_inner()).get(&search_id) {
+        flag.store(true, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// Search
+// =============================================================================
+
+/// Runs the search, handing each matching line to `on_match` from the
+/// walker threads.
+pub(crate) fn search(
+    options: &SearchOptions,
+    cancel: &AtomicBool,
+    on_match: impl Fn(SearchMatch) + Sync,
+) -> Result<SearchSummary, String> {
+    let started = Instant::now();
+    let root = PathBuf::from(&options.root);
+    if !root.is_dir() {
+        return Err(format!("Search folder not found: {}", root.display()));
+    }
+    let matcher = build_matcher(options)?;
+    let walker = build_walker(&root, options)?;
+    let types: Vec<String> = options.file_types.iter().map(|t| t.trim_start_matches('.').to_ascii_lowercase()).collect();
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+    let files_searched = AtomicUsize::new(0);
+    let files_matched = AtomicUsize::new(0);
+    let matches = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+
+    walker.run(|| {
+        let matcher = &matcher;
+        let root = &root;
+        let types = &types;
+        let on_match = &on_match;
+        let (files_searched, files_matched, matches, truncated) = (&files_searched, &files_matched, &matches, &truncated);
+        let mut searcher = SearcherBuilder::new()
+            .binary_detection(BinaryDetection::quit(b'\x00'))
+            .line_number(true)
+            .build();
+
+        Box::new(move |entry| {
+            if cancel.load(Ordering::Relaxed) || truncated.load(Ordering::Relaxed) {
+                return WalkState::Quit;
+            }
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|t| t.is_file()) || !has_type(entry.path(), types) {
+                return WalkState::Continue;
+            }
+            files_searched.fetch_add(1, Ordering::Relaxed);
+
+            let file = relative(root, entry.path());
+            let mut found = false;
+            let _ = searcher.search_path(
+                matcher,
+                entry.path(),
+                UTF8(|line, text| {
+                    let Some(m) = match_line(matcher, &file, line, text) else {
+                        return Ok(true);
+                    };
+                    if matches.fetch_add(1, Ordering::Relaxed) >= max_results {
+                        truncated.store(true, Ordering::Relaxed);
+                        return Ok(false);
+                    }
+                    found = true;
+                    on_match(m);
+                    Ok(!cancel.load(Ordering::Relaxed))
+                }),
+            );
+            if found {
+                files_matched.fetch_add(1, Ordering::Relaxed);
+            }
+            WalkState::Continue
+        })
+    });
+
+    Ok(SearchSummary {
+        search_id: options.search_id.clone(),
+        files_searched: files_searched.into_inner(),
+        files_matched: files_matched.into_inner(),
+        matches: matches.into_inner().min(max_results),
+        truncated: truncated.into_inner(),
+        cancelled: cancel.load(Ordering::Relaxed),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+pub(crate) fn build_matcher(options: &SearchOptions) -> Result<RegexMatcher, String> {
+    if options.query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    RegexMatcherBuilder::new()
+        .case_insensitive(!options.case_sensitive)
+        .word(options.whole_word)
+        .fixed_strings(!options.regex)
+        .build(&options.query)
+        .map_err(|e| format!("Invalid search pattern: {}", e))
+}
+
+pub(crate) fn build_walker(root: &Path, options: &SearchOptions) -> Result<ignore::WalkParallel, String> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &options.include {
+        overrides.add(glob).map_err(|e| format!("Invalid include pattern '{}': {}", glob, e))?;
+    }
+    for glob in &options.exclude {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| format!("Invalid exclude pattern '{}': {}", glob, e))?;
+    }
+    let overrides = overrides.build().map_err(|e| e.to_string())?;
+
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .overrides(overrides)
+        // Honour .gitignore even when the project is not a git repository.
+        .require_git(false)
+        .filter_entry(|entry| {
+            let skipped = entry.depth() > 0
+                && entry.file_type().is_some_and(|t| t.is_dir())
+                && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref());
+            !skipped
+        });
+    Ok(builder.build_parallel())
+}
+
+pub(crate) fn has_type(path: &Path, types: &[String]) -> bool {
+    if types.is_empty() {
+        return true;
+    }
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| types.contains(&ext))
+}
+
+pub(crate) fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+fn match_line(matcher: &RegexMatcher, file: &str, line: u64, text: &str) -> Option<SearchMatch> {
+    let text = text.trim_end_matches(['\r', '\n']);
+    let indent = text.len() - text.trim_start().len();
+    let mut ranges = Vec::new();
+    let _ = matcher.find_iter(text.as_bytes(), |m| {
+        let start = m.start().max(indent);
+        if start < m.end() {
+            ranges.push((start, m.end()));
+        }
+        true
+    });
+    if ranges.is_empty() {
+        return None;
+    }
+
+    // Keep the first match visible in long lines (minified JSON scenes).
+    let trimmed = &text[indent..];
+    let first = ranges[0].0 - indent;
+    let mut offset = 0;
+    if first > MAX_PREVIEW_CHARS / 2 {
+        offset = first - MAX_PREVIEW_CHARS / 4;
+        while !trimmed.is_char_boundary(offset) {
+            offset -= 1;
+        }
+    }
+    let visible = &trimmed[offset..];
+    let preview: String = visible.chars().take(MAX_PREVIEW_CHARS).collect();
+    let to_chars = |byte: usize| visible[..byte.min(visible.len())].chars().count();
+    let ranges = ranges
+        .into_iter()
+        .filter(|(start, _)| *start >= indent + offset)
+        .map(|(start, end)| (to_chars(start - indent - offset), to_chars(end - indent - offset)))
+        .filter(|(start, _)| *start < MAX_PREVIEW_CHARS)
+        .map(|(start, end)| (start, end.min(MAX_PREVIEW_CHARS)))
+        .collect();
+
+    Some(SearchMatch {
+        file: file.to_string(),
+        line,
+        preview: if offset > 0 { format!("…{}", preview) } else { preview },
+        ranges,
+    })
+}
+
+// =============================================================================
+// Event batching
+// =============================================================================
+
+/// Groups matches into `search-results` events, flushing every
+/// `BATCH_SIZE` matches or `BATCH_INTERVAL`, whichever comes first.
+struct BatchEmitter {
+    app: AppHandle,
+    search_id: String,
+    pending: Mutex<(Vec<SearchMatch>, Instant)>,
+}
+
+impl BatchEmitter {
+    fn new(app: AppHandle, search_id: String) -> Self {
+        Self {
+            app,
+            search_id,
+            pending: Mutex::new((Vec::new(), Instant::now())),
+        }
+    }
+
+    fn push(&self, m: SearchMatch) {
+        let batch = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.0.push(m);
+            if pending.0.len() < BATCH_SIZE && pending.1.elapsed() < BATCH_INTERVAL {
+                return;
+            }
+            pending.1 = Instant::now();
+            std::mem::take(&mut pending.0)
+        };
+        self.emit(batch);
+    }
+
+    fn flush(&self) {
+        let batch = std::mem::take(&mut self.pending.lock().unwrap_or_else(|e| e.into_inner()).0);
+        if !batch.is_empty() {
+            self.emit(batch);
+        }
+    }
+
+    fn emit(&self, matches: Vec<SearchMatch>) {
+        let _ = self.app.emit(
+            "search-results",
+            SearchResults {
+                search_id: self.search_id.clone(),
+                matches,
+            },
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Find-and-replace across project files
+//!
+//! `preview_replace` is a dry run: it returns every change as line hunks
+//! together with a hash of each file it read. `apply_replace` takes those
+//! hashes back and only rewrites files that are unchanged since the preview,
+//! backing each one up under `.esengine/replace-backups/` before an atomic
+//! write. Only text asset types are ever touched, never binaries.
+
+use super::{build_walker, has_type, relative, SearchOptions};
+use crate::jobs::{JobCategory, JobPriority, JobSpec};
+use crate::project::{now_iso8601, write_atomic};
+use ignore::WalkState;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+/// Extensions replace may modify.
+const TEXT_TYPES: &[&str] = &[
+    "esscene", "esprefab", "esmaterial", "esanim", "estimeline", "esshader", "tmj", "bmfont",
+    "json", "ts", "js", "mjs", "atlas", "fnt", "glsl", "txt", "md", "html", "css",
+];
+const BACKUP_DIR: &str = ".esengine/replace-backups";
+/// Hunks returned per file in a preview; the count covers all of them.
+const MAX_PREVIEW_HUNKS: usize = 100;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceOptions {
+    #[serde(flatten)]
+    pub search: SearchOptions,
+    /// Replacement text; `$1`/`${name}` expand capture groups in regex mode.
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceHunk {
+    /// 1-based line where the hunk starts.
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReplacement {
+    pub file: String,
+    pub replacements: usize,
+    pub hunks: Vec<ReplaceHunk>,
+    /// SHA-256 of the file as previewed; pass back to `apply_replace`.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePreview {
+    pub files: Vec<FileReplacement>,
+    pub replacements: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceConflict {
+    pub file: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceResult {
+    pub changed: Vec<String>,
+    pub replacements: usize,
+    /// Files left untouched, e.g. because they changed after the preview.
+    pub conflicts: Vec<ReplaceConflict>,
+    /// Folder holding the original of every changed file.
+    pub backup_dir: Option<String>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn preview_replace(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    options: ReplaceOptions,
+) -> Result<ReplacePreview, String> {
+    let spec = JobSpec::new(JobCategory::Search, "Preview replace").priority(JobPriority::High);
+    state.jobs.run(&app, spec, move |_| preview(&options)).await
+}
+
+/// Applies the replacement to the previewed files in `expected` (file →
+/// SHA-256 from `preview_replace`); deselected files are simply left out.
+#[tauri::command]
+pub async fn apply_replace(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    options: ReplaceOptions,
+    expected: BTreeMap<String, String>,
+) -> Result<ReplaceResult, String> {
+    let spec = JobSpec::new(JobCategory::Search, "Replace in files").priority(JobPriority::High);
+    state.jobs.run(&app, spec, move |_| apply(&options, &expected)).await
+}
+
+// =============================================================================
+// Replace
+// =============================================================================
+
+fn preview(options: &ReplaceOptions) -> Result<ReplacePreview, String> {
+    let regex = build_regex(&options.search)?;
+    let root = PathBuf::from(&options.search.root);
+    let mut files = Vec::new();
+    let mut total = 0;
+    for path in candidate_files(&root, &options.search)? {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(plan) = plan(&regex, options, &text) else {
+            continue;
+        };
+        total += plan.count;
+        let mut hunks = plan.hunks;
+        hunks.truncate(MAX_PREVIEW_HUNKS);
+        files.push(FileReplacement {
+            file: relative(&root, &path),
+            replacements: plan.count,
+            hunks,
+            sha256: hash(&text),
+        });
+    }
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+    Ok(ReplacePreview { files, replacements: total })
+}
+
+fn apply(options: &ReplaceOptions, expected: &BTreeMap<String, String>) -> Result<ReplaceResult, String> {
+    let regex = build_regex(&options.search)?;
+    let root = PathBuf::from(&options.search.root);
+    let stamp: String = now_iso8601().chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let backup_dir = root.join(BACKUP_DIR).join(stamp);
+
+    let mut result = ReplaceResult {
+        changed: Vec::new(),
+        replacements: 0,
+        conflicts: Vec::new(),
+        backup_dir: None,
+    };
+    let mut conflict = |file: &str, reason: &str| {
+        result.conflicts.push(ReplaceConflict {
+            file: file.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    let mut planned = Vec::new();
+    for (file, sha256) in expected {
+        let path = root.join(file);
+        if !is_text_type(&path) || file.split('/').any(|part| part == "..") {
+            conflict(file, "not a text asset in the search folder");
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            conflict(file, "file could not be read");
+            continue;
+        };
+        if hash(&text) != *sha256 {
+            conflict(file, "file changed since the preview");
+            continue;
+        }
+        match plan(&regex, options, &text) {
+            Some(plan) => planned.push((file.clone(), path, text, plan)),
+            None => conflict(file, "no longer matches"),
+        }
+    }
+
+    for (file, path, original, plan) in planned {
+        let backup = backup_dir.join(&file);
+        if let Some(parent) = backup.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&backup, &original).map_err(|e| format!("Failed to back up {}: {}", file, e))?;
+        write_atomic(&path, plan.text.as_bytes())?;
+        result.replacements += plan.count;
+        result.changed.push(file);
+    }
+    if !result.changed.is_empty() {
+        result.backup_dir = Some(backup_dir.to_string_lossy().to_string());
+    }
+    Ok(result)
+}
+
+struct Plan {
+    text: String,
+    count: usize,
+    hunks: Vec<ReplaceHunk>,
+}
+
+/// Replaces every match in `text`, recording each changed line span.
+fn plan(regex: &Regex, options: &ReplaceOptions, text: &str) -> Option<Plan> {
+    let mut edits = Vec::new();
+    for caps in regex.captures_iter(text) {
+        let m = caps.get(0)?;
+        if m.start() == m.end() {
+            continue;
+        }
+        let replacement = if options.search.regex {
+            let mut expanded = String::new();
+            caps.expand(&options.replacement, &mut expanded);
+            expanded
+        } else {
+            options.replacement.clone()
+        };
+        if replacement != m.as_str() {
+            edits.push((m.start(), m.end(), replacement));
+        }
+    }
+    if edits.is_empty() {
+        return None;
+    }
+
+    // Group edits into hunks covering whole lines.
+    let line_start = |i: usize| text[..i].rfind('\n').map_or(0, |p| p + 1);
+    let line_end = |i: usize| text[i..].find('\n').map_or(text.len(), |p| i + p);
+    let mut spans: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+    for (index, (start, end, _)) in edits.iter().enumerate() {
+        let (from, to) = (line_start(*start), line_end(*end));
+        match spans.last_mut() {
+            Some(last) if from <= last.1 => {
+                last.1 = last.1.max(to);
+                last.2.push(index);
+            }
+            _ => spans.push((from, to, vec![index])),
+        }
+    }
+
+    let splice = |from: usize, to: usize, indices: &[usize]| {
+        let mut out = String::new();
+        let mut cursor = from;
+        for &i in indices {
+            let (start, end, replacement) = &edits[i];
+            out.push_str(&text[cursor..*start]);
+            out.push_str(replacement);
+            cursor = *end;
+        }
+        out.push_str(&text[cursor..to]);
+        out
+    };
+    let all: Vec<usize> = (0..edits.len()).collect();
+    let hunks = spans
+        .iter()
+        .map(|(from, to, indices)| ReplaceHunk {
+            line: text[..*from].matches('\n').count() + 1,
+            before: text[*from..*to].to_string(),
+            after: splice(*from, *to, indices),
+        })
+        .collect();
+
+    Some(Plan {
+        text: splice(0, text.len(), &all),
+        count: edits.len(),
+        hunks,
+    })
+}
+
+fn build_regex(options: &SearchOptions) -> Result<Regex, String> {
+    if options.query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let pattern = if options.regex {
+        options.query.clone()
+    } else {
+        regex::escape(&options.query)
+    };
+    let pattern = if options.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))
+}
+
+/// Text files under the search root that pass the search filters.
+fn candidate_files(root: &Path, options: &SearchOptions) -> Result<Vec<PathBuf>, String> {
+    let types: Vec<String> = options.file_types.iter().map(|t| t.trim_start_matches('.').to_ascii_lowercase()).collect();
+    let found = Mutex::new(Vec::new());
+    build_walker(root, options)?.run(|| {
+        let found = &found;
+        let types = &types;
+        Box::new(move |entry| {
+            if let Ok(entry) = entry {
+                let path = entry.path();
+                if entry.file_type().is_some_and(|t| t.is_file()) && is_text_type(path) && has_type(path, types) {
+                    found.lock().unwrap_or_else(|e| e.into_inner()).push(path.to_path_buf());
+                }
+            }
+            WalkState::Continue
+        })
+    });
+    let mut files = found.into_inner().unwrap_or_else(|e| e.into_inner());
+    files.sort();
+    Ok(files)
+}
+
+fn is_text_type(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| TEXT_TYPES.contains(&ext.as_str()))
+}
+
+fn hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
@@ -0,0 +1,322 @@
+//! Storage manager — disk usage of caches, autosaves and logs, and cleanup
+//!
+//! Caches, build output, autosaves, backups and logs otherwise grow without
+//! bound. Each kind of regenerable data is a `StorageCategory` with the
+//! folders that hold it, in the project's `.esengine/` or the editor's own
+//! data:
+//!
+//! - `get_storage_usage` reports the size of each category;
+//! - `prune_storage` removes files older than a given age, then the oldest
+//!   files of a category beyond a given size;
+//! - with `storage.autoCleanup` on (the default), the editor applies the
+//!   policy from the settings (`storage.maxAgeDays`, `storage.maxSizeMb`)
+//!   to its own data on startup and to a project when it is opened.
+//!
+//! Files in use are never removed: the logs of this editor process, the
+//! runtimes of the embedded engine version and autosave indexes. Snapshots,
+//! source maps and the scene journal are the user's data and not touched.
+
+use crate::editor_settings;
+use crate::engines;
+use crate::jobs::{JobCategory, JobPriority, JobSpec};
+use crate::process::output::LOG_DIR;
+use crate::runtimes::RUNTIMES_DIR;
+use crate::scene::{AUTOSAVE_DIR, AUTOSAVE_INDEX};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
+
+const AUTO_CLEANUP_SETTING: &str = "storage.autoCleanup";
+const MAX_AGE_SETTING: &str = "storage.maxAgeDays";
+const MAX_SIZE_SETTING: &str = "storage.maxSizeMb";
+const DEFAULT_MAX_AGE_DAYS: u64 = 30;
+const DEFAULT_MAX_SIZE_MB: u64 = 1024;
+
+const DAY: u64 = 24 * 60 * 60;
+const MB: u64 = 1024 * 1024;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageCategory {
+    /// Project caches such as statistics.
+    Cache,
+    /// Incremental export data and bundled scripts.
+    Build,
+    /// Rolling autosave copies of scenes.
+    Autosave,
+    /// Backups taken before a replace in files.
+    Backups,
+    /// Downloaded package archives.
+    Packages,
+    /// Output logs of commands run from the editor.
+    Logs,
+    /// Runtimes downloaded for other engine versions.
+    Runtimes,
+}
+
+const CATEGORIES: &[StorageCategory] = &[
+    StorageCategory::Cache,
+    StorageCategory::Build,
+    StorageCategory::Autosave,
+    StorageCategory::Backups,
+    StorageCategory::Packages,
+    StorageCategory::Logs,
+    StorageCategory::Runtimes,
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: usize,
+    /// Modification time of the oldest file, as seconds since the epoch.
+    pub oldest: Option<u64>,
+    pub folders: Vec<String>,
+}
+
+/// What `prune_storage` removes; without either limit nothing is.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunePolicy {
+    /// Removes files not modified for this many days; 0 removes all.
+    pub max_age_days: Option<u64>,
+    /// Then removes the oldest files until the category fits.
+    pub max_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneResult {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+struct Entry {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+// =============================================================================
+// Categories
+// =============================================================================
+
+impl StorageCategory {
+    /// The folders of the category; project categories need `project_dir`.
+    fn folders(self, app: &AppHandle, project_dir: Option<&Path>) -> Vec<PathBuf> {
+        let in_project = |dirs: &[&str]| -> Vec<PathBuf> {
+            project_dir.map(|project| dirs.iter().map(|dir| project.join(dir)).collect()).unwrap_or_default()
+        };
+        match self {
+            StorageCategory::Cache => in_project(&[".esengine/cache"]),
+            StorageCategory::Build => in_project(&[".esengine/export-cache", ".esengine/build"]),
+            StorageCategory::Autosave => in_project(&[AUTOSAVE_DIR]),
+            StorageCategory::Backups => in_project(&[".esengine/replace-backups"]),
+            StorageCategory::Packages => in_project(&[".esengine/packages"]),
+            StorageCategory::Logs => vec![std::env::temp_dir().join(LOG_DIR)],
+            StorageCategory::Runtimes => {
+                app.path().app_data_dir().map(|dir| vec![dir.join(RUNTIMES_DIR)]).unwrap_or_default()
+            }
+        }
+    }
+
+    /// Whether `path` is in use and must stay.
+    fn keeps(self, folder: &Path, path: &Path) -> bool {
+        match self {
+            StorageCategory::Autosave => path.file_name().is_some_and(|name| name == AUTOSAVE_INDEX),
+            StorageCategory::Logs => path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&format!("command-{}-", std::process::id()))),
+            StorageCategory::Runtimes => path.starts_with(folder.join(engines::embedded_version())),
+            _ => false,
+        }
+    }
+
+    fn is_project(self) -> bool {
+        !matches!(self, StorageCategory::Logs | StorageCategory::Runtimes)
+    }
+}
+
+fn walk(dir: &Path, entries: &mut Vec<Entry>) {
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk(&path, entries);
+        } else {
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push(Entry { path, bytes: meta.len(), modified });
+        }
+    }
+}
+
+/// Files of the category that may be removed, oldest first.
+fn entries(category: StorageCategory, folders: &[PathBuf]) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for folder in folders {
+        let mut found = Vec::new();
+        walk(folder, &mut found);
+        entries.extend(found.into_iter().filter(|entry| !category.keeps(folder, &entry.path)));
+    }
+    entries.sort_by_key(|entry| entry.modified);
+    entries
+}
+
+/// Removes folders left empty, below but not including `root`.
+fn remove_empty_dirs(root: &Path) {
+    for entry in std::fs::read_dir(root).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path);
+            let _ = std::fs::remove_dir(&path);
+        }
+    }
+}
+
+// =============================================================================
+// Usage and pruning
+// =============================================================================
+
+fn usage(app: &AppHandle, project_dir: Option<&Path>) -> Vec<CategoryUsage> {
+    CATEGORIES
+        .iter()
+        .filter(|category| project_dir.is_some() || !category.is_project())
+        .map(|&category| {
+            let folders = category.folders(app, project_dir);
+            let mut all = Vec::new();
+            for folder in &folders {
+                walk(folder, &mut all);
+            }
+            CategoryUsage {
+                category,
+                bytes: all.iter().map(|entry| entry.bytes).sum(),
+                files: all.len(),
+                oldest: all
+                    .iter()
+                    .map(|entry| entry.modified)
+                    .min()
+                    .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|age| age.as_secs()),
+                folders: folders.iter().map(|folder| folder.to_string_lossy().to_string()).collect(),
+            }
+        })
+        .collect()
+}
+
+fn prune(
+    app: &AppHandle,
+    project_dir: Option<&Path>,
+    categories: &[StorageCategory],
+    policy: PrunePolicy,
+) -> PruneResult {
+    let mut result = PruneResult::default();
+    let now = SystemTime::now();
+    let max_age = policy.max_age_days.map(|days| Duration::from_secs(days.saturating_mul(DAY)));
+    let max_size = policy.max_size_mb.map(|mb| mb.saturating_mul(MB));
+    for &category in categories {
+        let folders = category.folders(app, project_dir);
+        let entries = entries(category, &folders);
+        let mut size: u64 = entries.iter().map(|entry| entry.bytes).sum();
+        for entry in entries {
+            let expired = max_age.is_some_and(|age| now.duration_since(entry.modified).unwrap_or_default() >= age);
+            let oversized = max_size.is_some_and(|max| size > max);
+            if (expired || oversized) && std::fs::remove_file(&entry.path).is_ok() {
+                size -= entry.bytes;
+                result.files += 1;
+                result.bytes += entry.bytes;
+            }
+        }
+        for folder in &folders {
+            remove_empty_dirs(folder);
+        }
+    }
+    if result.files > 0 {
+        eprintln!("[storage] Removed {} file(s), {} bytes", result.files, result.bytes);
+    }
+    result
+}
+
+fn auto_policy(app: &AppHandle) -> Option<PrunePolicy> {
+    let values = editor_settings::load(app).map(|settings| settings.values).unwrap_or_default();
+    if !values.get(AUTO_CLEANUP_SETTING).and_then(|v| v.as_bool()).unwrap_or(true) {
+        return None;
+    }
+    Some(PrunePolicy {
+        max_age_days: Some(values.get(MAX_AGE_SETTING).and_then(|v| v.as_u64()).unwrap_or(DEFAULT_MAX_AGE_DAYS)),
+        max_size_mb: Some(values.get(MAX_SIZE_SETTING).and_then(|v| v.as_u64()).unwrap_or(DEFAULT_MAX_SIZE_MB)),
+    })
+}
+
+/// Applies the automatic cleanup policy in the background: to the
+/// editor's own data, and to the project when `project_dir` is given.
+pub fn auto_cleanup(app: &AppHandle, project_dir: Option<PathBuf>) {
+    let Some(policy) = auto_policy(app) else {
+        return;
+    };
+    // A setting of 0 would empty the caches on every launch.
+    let policy = PrunePolicy { max_age_days: policy.max_age_days.map(|days| days.max(1)), ..policy };
+    let categories: Vec<StorageCategory> =
+        CATEGORIES.iter().copied().filter(|category| category.is_project() == project_dir.is_some()).collect();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut spec = JobSpec::new(JobCategory::Io, "Clean up storage").priority(JobPriority::Low);
+        if let Some(dir) = &project_dir {
+            spec = spec.project(dir);
+        }
+        let job_app = app.clone();
+        let result = app
+            .state::<AppState>()
+            .jobs
+            .run(&app, spec, move |_| Ok(prune(&job_app, project_dir.as_deref(), &categories, policy)))
+            .await;
+        if let Err(e) = result {
+            eprintln!("[storage] Automatic cleanup failed: {}", e);
+        }
+    });
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Disk usage per category; project categories only with `project_dir`.
+#[tauri::command]
+pub async fn get_storage_usage(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+) -> Result<Vec<CategoryUsage>, String> {
+    let spec = JobSpec::new(JobCategory::Io, "Storage usage").priority(JobPriority::High);
+    let job_app = app.clone();
+    state.jobs.run(&app, spec, move |_| Ok(usage(&job_app, project_dir.as_deref().map(Path::new)))).await
+}
+
+/// Removes files of `categories` by age and size.
+#[tauri::command]
+pub async fn prune_storage(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+    categories: Vec<StorageCategory>,
+    policy: PrunePolicy,
+) -> Result<PruneResult, String> {
+    if project_dir.is_none() && categories.iter().any(|category| category.is_project()) {
+        return Err("Project categories need a project folder".to_string());
+    }
+    let spec = JobSpec::new(JobCategory::Io, "Clean up storage");
+    let job_app = app.clone();
+    state
+        .jobs
+        .run(&app, spec, move |_| Ok(prune(&job_app, project_dir.as_deref().map(Path::new), &categories, policy)))
+        .await
+}
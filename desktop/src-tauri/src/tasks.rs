@@ -0,0 +1,534 @@
+//! Project tasks — named build steps from `tasks.json`
+//!
+//! A project lists its tasks (compile scripts, pack atlases, deploy a test
+//! build) in `tasks.json` at its root:
+//!
+//! ```json
+//! {
+//!   "concurrency": 2,
+//!   "tasks": {
+//!     "compile-scripts": { "command": "npm", "args": ["run", "build"],
+//!                          "inputs": ["src/*.ts"], "outputs": ["build/js/*"] },
+//!     "pack-atlases": { "command": "node", "args": ["pack.js"],
+//!                       "priority": "low", "memoryLimitMb": 2048 },
+//!     "deploy-test": { "command": "node", "args": ["deploy.js"],
+//!                      "dependsOn": ["compile-scripts"], "env": { "TARGET": "test" } }
+//!   }
+//! }
+//! ```
+//!
+//! `run_tasks` runs the requested tasks after their dependencies, at most
+//! `concurrency` at a time, as managed processes tied to the project. A task
+//! with `inputs` is skipped when neither its definition nor any input file
+//! changed since its last successful run and all its `outputs` exist; the
+//! fingerprints are kept in `.esengine/task-cache.json`. Status changes are
+//! emitted as `task-status`; output arrives as `process-output` under the
+//! name `task:<name>`.
+//!
+//! A failed task is classified by its output (see `process::retry`; the
+//! file's `failurePatterns` add project rules). A task with a `retry`
+//! policy, e.g. `{ "attempts": 3, "on": ["network"] }`, runs again after a
+//! growing delay, each failed attempt reported as `retrying`.
+
+use crate::deploy::wildcard_match;
+use crate::export::archive::sha256_file;
+use crate::export::assets::{relative_path, walk_files};
+use crate::process::env::{login_path_default, CommandEnv};
+use crate::process::limits::{ProcessPriority, ResourceLimits};
+use crate::process::retry::{self, FailureKind, FailureRule, RetryPolicy};
+use crate::process::{self, ProcessOptions};
+use crate::project::write_atomic;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+
+const TASKS_FILE: &str = "tasks.json";
+const CACHE_FILE: &str = ".esengine/task-cache.json";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksFile {
+    /// Tasks running at the same time; 1 when unset.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub tasks: BTreeMap<String, TaskDef>,
+    /// Project rules for classifying failures, tried before the built-in
+    /// ones.
+    #[serde(default)]
+    pub failure_patterns: Vec<FailureRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDef {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Run `command` through the system shell (`sh -c` / `cmd /C`), so it
+    /// may chain commands; `args` are ignored.
+    #[serde(default)]
+    pub shell: bool,
+    /// Working directory relative to the project; the project by default.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Project-relative patterns (`*` matches anything) of the files the
+    /// task reads; without them the task always runs.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Patterns of the files it produces; each must match for a skip.
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// `normal`, `low` or `idle`; heavy asset steps run best at `low`.
+    #[serde(default)]
+    pub priority: Option<ProcessPriority>,
+    /// Memory limit of each process the task starts; Linux only.
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+    /// Runs the task again after failures the policy names.
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub depends_on: Vec<String>,
+    /// Has `inputs`, so it can be skipped when up to date.
+    pub cacheable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Succeeded,
+    /// Up to date; not run.
+    Cached,
+    Failed,
+    /// An attempt failed; the task runs again after a delay.
+    Retrying,
+    /// Not run because a dependency failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskResult {
+    pub name: String,
+    pub status: TaskStatus,
+    pub code: Option<i32>,
+    pub error: Option<String>,
+    /// What kind of failure ended a failed task.
+    pub failure: Option<FailureKind>,
+    /// Times the task was started; 0 when it was not.
+    pub attempts: u32,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRun {
+    pub success: bool,
+    /// In the order the tasks finished.
+    pub results: Vec<TaskResult>,
+}
+
+#[derive(Clone, Serialize)]
+struct TaskStatusEvent {
+    project_dir: String,
+    name: String,
+    status: TaskStatus,
+    error: Option<String>,
+    failure: Option<FailureKind>,
+    /// The attempt that finished, for `retrying`, `failed` and `succeeded`.
+    attempt: Option<u32>,
+    /// Delay before the next attempt, for `retrying`.
+    retry_in_ms: Option<u64>,
+}
+
+impl TaskStatusEvent {
+    fn new(project_dir: &str, name: &str, status: TaskStatus, error: Option<String>) -> Self {
+        Self {
+            project_dir: project_dir.to_string(),
+            name: name.to_string(),
+            status,
+            error,
+            failure: None,
+            attempt: None,
+            retry_in_ms: None,
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub fn list_tasks(project_dir: String) -> Result<Vec<TaskInfo>, String> {
+    let file = load(Path::new(&project_dir))?;
+    Ok(file
+        .tasks
+        .iter()
+        .map(|(name, task)| TaskInfo {
+            name: name.clone(),
+            description: task.description.clone(),
+            depends_on: task.depends_on.clone(),
+            cacheable: !task.inputs.is_empty(),
+        })
+        .collect())
+}
+
+/// Runs `tasks` and everything they depend on. `force` runs cached tasks
+/// anyway.
+#[tauri::command]
+pub async fn run_tasks(
+    app: AppHandle,
+    project_dir: String,
+    tasks: Vec<String>,
+    force: Option<bool>,
+) -> Result<TaskRun, String> {
+    let dir = PathBuf::from(&project_dir);
+    let file = load(&dir)?;
+    let order = resolve(&file, &tasks)?;
+    let concurrency = file.concurrency.unwrap_or(1).max(1);
+    let force = force.unwrap_or(false);
+
+    let emit = |name: &str, status: TaskStatus, error: Option<String>| {
+        let _ = app.emit("task-status", TaskStatusEvent::new(&project_dir, name, status, error));
+    };
+    let mut status: HashMap<&str, TaskStatus> = order.iter().map(|name| (name.as_str(), TaskStatus::Pending)).collect();
+    for name in &order {
+        emit(name, TaskStatus::Pending, None);
+    }
+
+    let mut results = Vec::new();
+    let mut running = FuturesUnordered::new();
+    loop {
+        for name in &order {
+            if status[name.as_str()] != TaskStatus::Pending {
+                continue;
+            }
+            let task = &file.tasks[name];
+            let deps: Vec<TaskStatus> = task.depends_on.iter().map(|dep| status[dep.as_str()]).collect();
+            if deps.iter().any(|s| matches!(s, TaskStatus::Failed | TaskStatus::Skipped)) {
+                status.insert(name.as_str(), TaskStatus::Skipped);
+                emit(name, TaskStatus::Skipped, None);
+                results.push(TaskResult {
+                    name: name.clone(),
+                    status: TaskStatus::Skipped,
+                    code: None,
+                    error: Some("a dependency failed".to_string()),
+                    failure: None,
+                    attempts: 0,
+                    duration_ms: 0,
+                });
+                continue;
+            }
+            if running.len() >= concurrency || !deps.iter().all(|s| matches!(s, TaskStatus::Succeeded | TaskStatus::Cached)) {
+                continue;
+            }
+            status.insert(name.as_str(), TaskStatus::Running);
+            emit(name, TaskStatus::Running, None);
+            running.push(run_task(&app, &dir, name, task, &file.failure_patterns, force));
+        }
+        let Some((result, fingerprint)) = running.next().await else {
+            break;
+        };
+        // Written here rather than in the tasks, which may finish together.
+        if let Some(hash) = fingerprint {
+            if let Err(e) = write_cache(&dir, &result.name, &hash) {
+                eprintln!("[Tasks] Failed to update {}: {}", CACHE_FILE, e);
+            }
+        }
+        if let Some(name) = order.iter().find(|name| **name == result.name) {
+            status.insert(name, result.status);
+        }
+        let event = TaskStatusEvent {
+            failure: result.failure,
+            attempt: (result.attempts > 0).then_some(result.attempts),
+            ..TaskStatusEvent::new(&project_dir, &result.name, result.status, result.error.clone())
+        };
+        let _ = app.emit("task-status", event);
+        results.push(result);
+    }
+
+    let success = results.iter().all(|r| matches!(r.status, TaskStatus::Succeeded | TaskStatus::Cached));
+    Ok(TaskRun { success, results })
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+fn load(project_dir: &Path) -> Result<TasksFile, String> {
+    let path = project_dir.join(TASKS_FILE);
+    if !path.is_file() {
+        return Ok(TasksFile::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", TASKS_FILE, e))?;
+    let file: TasksFile = serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", TASKS_FILE, e))?;
+    for (name, task) in &file.tasks {
+        if task.command.trim().is_empty() {
+            return Err(format!("Task '{}' has no command", name));
+        }
+        if let Some(dep) = task.depends_on.iter().find(|dep| !file.tasks.contains_key(*dep)) {
+            return Err(format!("Task '{}' depends on unknown task '{}'", name, dep));
+        }
+    }
+    Ok(file)
+}
+
+/// `requested` and their dependencies, dependencies first.
+fn resolve(file: &TasksFile, requested: &[String]) -> Result<Vec<String>, String> {
+    fn visit(
+        file: &TasksFile,
+        name: &str,
+        path: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if order.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|n| n == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(format!("Task dependency cycle: {}", cycle.join(" -> ")));
+        }
+        let task = file.tasks.get(name).ok_or_else(|| format!("Unknown task '{}'", name))?;
+        path.push(name.to_string());
+        for dep in &task.depends_on {
+            visit(file, dep, path, order)?;
+        }
+        path.pop();
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    if requested.is_empty() {
+        return Err("No task given".to_string());
+    }
+    let mut order = Vec::new();
+    for name in requested {
+        visit(file, name, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+// =============================================================================
+// Running
+// =============================================================================
+
+/// Runs one task; also returns the fingerprint to cache when it succeeded.
+async fn run_task(
+    app: &AppHandle,
+    project_dir: &Path,
+    name: &str,
+    task: &TaskDef,
+    rules: &[FailureRule],
+    force: bool,
+) -> (TaskResult, Option<String>) {
+    let started = Instant::now();
+    let result = |status, code, error| TaskResult {
+        name: name.to_string(),
+        status,
+        code,
+        error,
+        failure: None,
+        attempts: 0,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    let failed = |error: String| (result(TaskStatus::Failed, None, Some(error)), None);
+
+    let fingerprint = if task.inputs.is_empty() {
+        None
+    } else {
+        let (dir, task_def) = (project_dir.to_path_buf(), task.clone());
+        match tokio::task::spawn_blocking(move || fingerprint(&dir, &task_def)).await {
+            Ok(Ok(hash)) => Some(hash),
+            Ok(Err(e)) => return failed(e),
+            Err(e) => return failed(format!("Fingerprint task failed: {}", e)),
+        }
+    };
+    if !force {
+        if let Some(hash) = &fingerprint {
+            if read_cache(project_dir).get(name) == Some(hash) && outputs_exist(project_dir, &task.outputs) {
+                return (result(TaskStatus::Cached, None, None), None);
+            }
+        }
+    }
+
+    let options = ProcessOptions {
+        project: Some(project_dir.to_path_buf()),
+        timeout: task.timeout_secs.map(Duration::from_secs),
+        limits: ResourceLimits { priority: task.priority, memory_limit_mb: task.memory_limit_mb },
+        ..ProcessOptions::default()
+    };
+    let label = format!("task:{}", name);
+    let mut attempt = 1;
+    loop {
+        let command = match build_command(project_dir, task).await {
+            Ok(command) => command,
+            Err(e) => return failed(e),
+        };
+        let exit = match process::start_with(app, &label, command, options.clone()) {
+            Ok(running) => running.wait(|_, _| {}).await,
+            Err(e) => return failed(e.into()),
+        };
+        let (code, error, failure) = match exit {
+            Ok(exit) if exit.success() => {
+                let done = TaskResult { attempts: attempt, ..result(TaskStatus::Succeeded, Some(exit.code), None) };
+                return (done, fingerprint);
+            }
+            Ok(exit) => {
+                let error = exit.tail.last().cloned().unwrap_or_else(|| format!("exited with code {}", exit.code));
+                (Some(exit.code), error, retry::classify(&exit.tail, rules))
+            }
+            Err(process::WaitError::Cancelled) => (None, "cancelled".to_string(), FailureKind::Cancelled),
+            Err(process::WaitError::TimedOut(timeout)) => {
+                (None, format!("timed out after {} s", timeout.as_secs()), FailureKind::Timeout)
+            }
+            Err(process::WaitError::Failed(e)) => (None, e, FailureKind::Unknown),
+        };
+
+        let Some(delay) = task.retry.as_ref().and_then(|policy| policy.retry_after(attempt, failure)) else {
+            let last = TaskResult {
+                failure: Some(failure),
+                attempts: attempt,
+                ..result(TaskStatus::Failed, code, Some(error))
+            };
+            return (last, None);
+        };
+        eprintln!("[Tasks] {} failed ({:?}), retrying in {} ms: {}", name, failure, delay.as_millis(), error);
+        let project = project_dir.to_string_lossy();
+        let event = TaskStatusEvent {
+            failure: Some(failure),
+            attempt: Some(attempt),
+            retry_in_ms: Some(delay.as_millis() as u64),
+            ..TaskStatusEvent::new(&project, name, TaskStatus::Retrying, Some(error))
+        };
+        let _ = app.emit("task-status", event);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+async fn build_command(project_dir: &Path, task: &TaskDef) -> Result<Command, String> {
+    let (dir, task) = (project_dir.to_path_buf(), task.clone());
+    tokio::task::spawn_blocking(move || {
+        let env: HashMap<String, String> = task.env.clone().into_iter().collect();
+        let command_env = CommandEnv::new(env, Some(&dir), login_path_default());
+        let mut command = if task.shell {
+            shell_command(&task.command)
+        } else {
+            let mut command = Command::new(command_env.resolve(&task.command));
+            command.args(&task.args);
+            command
+        };
+        let cwd = task.cwd.as_ref().map_or(dir.clone(), |cwd| dir.join(cwd));
+        if !cwd.is_dir() {
+            return Err(format!("Working directory not found: {}", cwd.display()));
+        }
+        command.current_dir(cwd);
+        command_env.apply(&mut command);
+        Ok(command)
+    })
+    .await
+    .map_err(|e| format!("Task setup failed: {}", e))?
+}
+
+#[cfg(windows)]
+fn shell_command(script: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", script]);
+    command
+}
+
+#[cfg(not(windows))]
+fn shell_command(script: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", script]);
+    command
+}
+
+// =============================================================================
+// Cache
+// =============================================================================
+
+/// Hash of the task definition and the contents of every input file.
+fn fingerprint(project_dir: &Path, task: &TaskDef) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(task).map_err(|e| e.to_string())?);
+    for path in matching_files(project_dir, &task.inputs) {
+        hasher.update(relative_path(project_dir, &path).as_bytes());
+        hasher.update(sha256_file(&path)?.as_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn outputs_exist(project_dir: &Path, outputs: &[String]) -> bool {
+    outputs
+        .iter()
+        .all(|pattern| !matching_files(project_dir, std::slice::from_ref(pattern)).is_empty())
+}
+
+/// Files matching any of `patterns`, walking only below their fixed prefix.
+fn matching_files(project_dir: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./");
+        let prefix = pattern.split('*').next().unwrap_or_default();
+        if prefix.len() == pattern.len() {
+            let path = project_dir.join(pattern);
+            if path.is_file() {
+                files.push(path);
+            }
+            continue;
+        }
+        let root = project_dir.join(prefix.rsplit_once('/').map_or("", |(dir, _)| dir));
+        files.extend(
+            walk_files(&root)
+                .into_iter()
+                .filter(|path| wildcard_match(pattern, &relative_path(project_dir, path))),
+        );
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn read_cache(project_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(project_dir.join(CACHE_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(project_dir: &Path, name: &str, hash: &str) -> Result<(), String> {
+    let path = project_dir.join(CACHE_FILE);
+    let mut cache = read_cache(project_dir);
+    cache.insert(name.to_string(), hash.to_string());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_vec_pretty(&cache).map_err(|e| e.to_string())?;
+    write_atomic(&path, &json)
+}
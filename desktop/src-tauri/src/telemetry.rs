@@ -0,0 +1,356 @@
+//! Telemetry — anonymous usage statistics, only for users who opt in
+//!
+//! - Off until turned on in the settings (`privacy.telemetry`). While off,
+//!   nothing is recorded, queued or sent, and the queue of a user who opts
+//!   out is deleted.
+//! - An event is a kind (`feature` or `error`), an id such as `file.save`
+//!   or `job.build`, the platform, the editor version and a session id
+//!   that lives only as long as the process. Ids may only use
+//!   `a-z0-9._-`, so paths, names and messages cannot slip in.
+//! - Events are batched in memory, queued in
+//!   `<app data>/telemetry/queue.jsonl` and posted every few minutes to
+//!   the endpoint (`privacy.telemetryEndpoint`, or
+//!   `ESENGINE_TELEMETRY_ENDPOINT` at build time). Offline, the queue
+//!   waits for the next attempt.
+//! - Every recorded event is also written to `log.jsonl`, which
+//!   `get_telemetry_log` returns so users can see what was collected.
+
+use crate::editor_settings;
+use crate::jobs::{JobInfo, JobStatus};
+use crate::project::{now_iso8601, write_atomic};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager, State};
+
+const ENABLED_SETTING: &str = "privacy.telemetry";
+const ENDPOINT_SETTING: &str = "privacy.telemetryEndpoint";
+const DEFAULT_ENDPOINT: Option<&str> = option_env!("ESENGINE_TELEMETRY_ENDPOINT");
+
+const TELEMETRY_DIR: &str = "telemetry";
+const QUEUE_FILE: &str = "queue.jsonl";
+const LOG_FILE: &str = "log.jsonl";
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Events per request, and buffered events that trigger an early flush.
+const BATCH_SIZE: usize = 100;
+/// Oldest queued events are dropped beyond this, e.g. after weeks offline.
+const MAX_QUEUED: usize = 5000;
+/// The log is trimmed to this many events on startup.
+const MAX_LOGGED: usize = 10_000;
+const MAX_NAME_LEN: usize = 64;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// A menu item, command or shortcut was used.
+    Feature,
+    /// Something failed; the name says what kind of thing, not why.
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryEvent {
+    pub kind: EventKind,
+    pub name: String,
+    pub time: String,
+    pub session: String,
+    pub os: String,
+    pub arch: String,
+    pub editor_version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    /// Where events go; `None` when no endpoint is configured, in which
+    /// case events stay in the queue.
+    pub endpoint: Option<String>,
+    pub queued: usize,
+}
+
+/// Events not yet queued on disk, and the session they belong to.
+pub struct Telemetry {
+    session: String,
+    buffer: Mutex<Vec<TelemetryEvent>>,
+    sending: AtomicBool,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        let session = format!("{:016x}", RandomState::new().build_hasher().finish());
+        Self { session, buffer: Mutex::new(Vec::new()), sending: AtomicBool::new(false) }
+    }
+}
+
+// =============================================================================
+// Settings
+// =============================================================================
+
+struct Settings {
+    enabled: bool,
+    endpoint: Option<String>,
+}
+
+fn settings(app: &AppHandle) -> Settings {
+    let values = editor_settings::load(app).map(|settings| settings.values).unwrap_or_default();
+    let enabled = values.get(ENABLED_SETTING).and_then(|v| v.as_bool()).unwrap_or(false);
+    let endpoint = values
+        .get(ENDPOINT_SETTING)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .or(DEFAULT_ENDPOINT)
+        .map(String::from);
+    Settings { enabled, endpoint }
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+fn dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(TELEMETRY_DIR))
+}
+
+fn read_events(path: &Path) -> Vec<TelemetryEvent> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write_events(path: &Path, events: &[TelemetryEvent]) -> Result<(), String> {
+    let mut data = Vec::new();
+    for event in events {
+        serde_json::to_writer(&mut data, event).map_err(|e| e.to_string())?;
+        data.push(b'\n');
+    }
+    write_atomic(path, &data)
+}
+
+fn append_events(path: &Path, events: &[TelemetryEvent]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+    for event in events {
+        let line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Moves buffered events to the queue file.
+fn persist(app: &AppHandle) -> Result<(), String> {
+    let events = std::mem::take(&mut *app.state::<Telemetry>().buffer.lock().unwrap());
+    if events.is_empty() {
+        return Ok(());
+    }
+    let path = dir(app)?.join(QUEUE_FILE);
+    append_events(&path, &events)?;
+    let queued = read_events(&path);
+    if queued.len() > MAX_QUEUED {
+        write_events(&path, &queued[queued.len() - MAX_QUEUED..])?;
+    }
+    Ok(())
+}
+
+/// Deletes queued and buffered events, e.g. after opting out.
+fn discard_queue(app: &AppHandle) {
+    app.state::<Telemetry>().buffer.lock().unwrap().clear();
+    if let Ok(dir) = dir(app) {
+        let _ = std::fs::remove_file(dir.join(QUEUE_FILE));
+    }
+}
+
+// =============================================================================
+// Recording
+// =============================================================================
+
+/// Lowercases `name` and checks it is a plain id.
+fn sanitize(name: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'));
+    valid.then_some(name)
+}
+
+/// Records an event when the user opted in.
+pub fn record(app: &AppHandle, kind: EventKind, name: &str) -> Result<(), String> {
+    let name = sanitize(name).ok_or_else(|| format!("Invalid telemetry event name '{}'", name))?;
+    if !settings(app).enabled {
+        return Ok(());
+    }
+    let telemetry = app.state::<Telemetry>();
+    let event = TelemetryEvent {
+        kind,
+        name,
+        time: now_iso8601(),
+        session: telemetry.session.clone(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        editor_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    append_events(&dir(app)?.join(LOG_FILE), std::slice::from_ref(&event))?;
+    let full = {
+        let mut buffer = telemetry.buffer.lock().unwrap();
+        buffer.push(event);
+        buffer.len() >= BATCH_SIZE
+    };
+    if full {
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move { flush(&handle).await });
+    }
+    Ok(())
+}
+
+async fn post(endpoint: &str, events: &[TelemetryEvent]) -> Result<(), String> {
+    let body = serde_json::json!({ "events": events });
+    let response = reqwest::Client::new()
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(&body).map_err(|e| e.to_string())?)
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Sends the queue in batches. Batches that fail stay queued.
+async fn flush(app: &AppHandle) {
+    let Settings { enabled, endpoint } = settings(app);
+    if !enabled {
+        discard_queue(app);
+        return;
+    }
+    if let Err(e) = persist(app) {
+        eprintln!("[telemetry] Failed to queue events: {}", e);
+    }
+    let Some(endpoint) = endpoint else {
+        return;
+    };
+    let telemetry = app.state::<Telemetry>();
+    if telemetry.sending.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Ok(path) = dir(app).map(|dir| dir.join(QUEUE_FILE)) {
+        let queued = read_events(&path);
+        let mut sent = 0;
+        for batch in queued.chunks(BATCH_SIZE) {
+            if let Err(e) = post(&endpoint, batch).await {
+                // Offline or refused; the rest waits for the next flush.
+                eprintln!("[telemetry] Failed to send events: {}", e);
+                break;
+            }
+            sent += batch.len();
+        }
+        if sent > 0 {
+            // Events recorded meanwhile were appended after the ones read.
+            let remaining: Vec<_> = read_events(&path).into_iter().skip(sent).collect();
+            if let Err(e) = write_events(&path, &remaining) {
+                eprintln!("[telemetry] Failed to update the queue: {}", e);
+            }
+        }
+    }
+    telemetry.sending.store(false, Ordering::SeqCst);
+}
+
+/// Queues what is still buffered; called when the editor quits.
+pub fn shutdown(app: &AppHandle) {
+    if !settings(app).enabled {
+        return;
+    }
+    if let Err(e) = persist(app) {
+        eprintln!("[telemetry] Failed to queue events: {}", e);
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    if let Ok(path) = dir(app).map(|dir| dir.join(LOG_FILE)) {
+        let logged = read_events(&path);
+        if logged.len() > MAX_LOGGED {
+            let _ = write_events(&path, &logged[logged.len() - MAX_LOGGED..]);
+        }
+    }
+    let handle = app.clone();
+    app.listen_any("job-status", move |event| {
+        let Ok(job) = serde_json::from_str::<JobInfo>(event.payload()) else {
+            return;
+        };
+        if job.status == JobStatus::Failed {
+            let name = format!("job.{:?}", job.category).to_ascii_lowercase();
+            let _ = record(&handle, EventKind::Error, &name);
+        }
+    });
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            flush(&handle).await;
+        }
+    });
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Records a feature use or error category; ignored unless the user opted
+/// in. Fails for names that are not plain ids.
+#[tauri::command]
+pub fn record_telemetry_event(app: AppHandle, kind: EventKind, name: String) -> Result<(), String> {
+    record(&app, kind, &name)
+}
+
+/// Recorded events, newest first, so users can see what was collected.
+#[tauri::command]
+pub fn get_telemetry_log(app: AppHandle, limit: Option<usize>) -> Result<Vec<TelemetryEvent>, String> {
+    let mut events = read_events(&dir(&app)?.join(LOG_FILE));
+    events.reverse();
+    events.truncate(limit.unwrap_or(usize::MAX));
+    Ok(events)
+}
+
+#[tauri::command]
+pub fn get_telemetry_status(app: AppHandle, telemetry: State<Telemetry>) -> Result<TelemetryStatus, String> {
+    let Settings { enabled, endpoint } = settings(&app);
+    let buffered = telemetry.buffer.lock().unwrap().len();
+    let queued = read_events(&dir(&app)?.join(QUEUE_FILE)).len() + buffered;
+    Ok(TelemetryStatus { enabled, endpoint, queued })
+}
+
+/// Sends queued events now.
+#[tauri::command]
+pub async fn flush_telemetry(app: AppHandle) -> Result<(), String> {
+    flush(&app).await;
+    Ok(())
+}
+
+/// Deletes the log and every event not yet sent.
+#[tauri::command]
+pub fn clear_telemetry_data(app: AppHandle) -> Result<(), String> {
+    app.state::<Telemetry>().buffer.lock().unwrap().clear();
+    let dir = dir(&app)?;
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
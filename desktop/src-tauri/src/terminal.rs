@@ -0,0 +1,119 @@
+//! System terminal — opens the user's own terminal in a folder
+//!
+//! `open_terminal` starts the platform's terminal application in a project
+//! or build folder: Windows Terminal or a console window on Windows, iTerm
+//! or Terminal.app on macOS, and the desktop's preferred emulator on Linux.
+//! The terminal is not managed; it outlives the project and the editor.
+
+use crate::process::env::{login_path_default, CommandEnv};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(not(windows))]
+use std::process::Stdio;
+
+/// Emulators tried on Linux, after `$TERMINAL`.
+#[cfg(all(unix, not(target_os = "macos")))]
+const LINUX_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "kitty",
+    "alacritty",
+    "wezterm",
+    "xterm",
+];
+
+/// The folder to open for `path`: itself, or the folder of a file.
+fn working_dir(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        Ok(path.to_path_buf())
+    } else if path.is_file() {
+        path.parent().map(Path::to_path_buf).ok_or_else(|| format!("{} has no folder", path.display()))
+    } else {
+        Err(format!("Path not found: {}", path.display()))
+    }
+}
+
+/// The command that opens `terminal`, or the platform default, in `dir`.
+#[cfg(windows)]
+fn terminal_command(dir: &Path, terminal: Option<&str>, env: &CommandEnv) -> Result<Command, String> {
+    use std::os::windows::process::CommandExt;
+    /// `CREATE_NEW_CONSOLE`: the shell gets a window of its own.
+    const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+
+    if let Some(terminal) = terminal {
+        let mut command = Command::new(env.find(terminal).ok_or_else(|| format!("{} not found", terminal))?);
+        command.creation_flags(CREATE_NEW_CONSOLE);
+        return Ok(command);
+    }
+    if let Some(wt) = env.find("wt") {
+        let mut command = Command::new(wt);
+        command.arg("-d").arg(dir);
+        return Ok(command);
+    }
+    let mut command = Command::new("cmd.exe");
+    command.creation_flags(CREATE_NEW_CONSOLE);
+    Ok(command)
+}
+
+#[cfg(target_os = "macos")]
+fn terminal_command(dir: &Path, terminal: Option<&str>, _env: &CommandEnv) -> Result<Command, String> {
+    // Users who installed iTerm generally want it over Terminal.app.
+    let app = terminal.unwrap_or_else(|| {
+        let user_apps = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Applications"));
+        let iterm = [Some(PathBuf::from("/Applications")), user_apps]
+            .into_iter()
+            .flatten()
+            .any(|apps| apps.join("iTerm.app").is_dir());
+        if iterm {
+            "iTerm"
+        } else {
+            "Terminal"
+        }
+    });
+    let mut command = Command::new("open");
+    command.arg("-a").arg(app).arg(dir);
+    Ok(command)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn terminal_command(_dir: &Path, terminal: Option<&str>, env: &CommandEnv) -> Result<Command, String> {
+    // Every emulator listed starts its shell in its working directory.
+    let configured = terminal.map(str::to_string).or_else(|| std::env::var("TERMINAL").ok());
+    let program = configured
+        .iter()
+        .map(String::as_str)
+        .chain(LINUX_TERMINALS.iter().copied())
+        .find_map(|name| env.find(name))
+        .ok_or_else(|| "No terminal emulator found; set $TERMINAL".to_string())?;
+    Ok(Command::new(program))
+}
+
+/// Opens a terminal in `path`, or in the folder of `path` when it is a
+/// file. `terminal` picks the application instead of the platform default:
+/// an app name on macOS, a program elsewhere.
+#[tauri::command]
+pub async fn open_terminal(path: String, terminal: Option<String>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let dir = working_dir(&path)?;
+        let env = CommandEnv::new(HashMap::new(), None, login_path_default());
+        let mut command = terminal_command(&dir, terminal.as_deref(), &env)?;
+        command.current_dir(&dir);
+        // A Windows console shell reads its new console; redirected, it
+        // would exit at once.
+        #[cfg(not(windows))]
+        command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        let mut child = command.spawn().map_err(|e| format!("Failed to open a terminal: {}", e))?;
+        eprintln!("[Terminal] Opened a terminal in {}", dir.display());
+        // Reaped when it exits, so it does not linger as a zombie.
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Terminal task failed: {}", e))?
+}
@@ -0,0 +1,601 @@
+//! External toolchains — Node, Java, Gradle, the Android SDK and the WeChat
+//! devtools that export targets and publishing rely on
+//!
+//! `detect_toolchains` finds each tool the way a command would (project
+//! overrides, PATH, the usual environment variables and install locations),
+//! asks it for its version and reports per export target what is missing,
+//! so an export can say so up front instead of failing halfway. Results are
+//! cached for `CACHE_TTL`. Node can also be installed by the editor itself:
+//! `install_node` unpacks the official portable build into the app data dir,
+//! and its directory is added to the PATH of every command.
+
+use crate::process::env::{self, CommandEnv};
+use futures_util::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::process::Command;
+
+const NODE_VERSION: &str = "20.18.0";
+const NODE_DOWNLOAD_BASE: &str = "https://nodejs.org/dist";
+const NODE_DIR: &str = "node";
+
+const MIN_NODE_VERSION: &str = "16.0";
+const MIN_JAVA_VERSION: &str = "17";
+
+const CACHE_TTL: Duration = Duration::from_secs(300);
+/// Gradle in particular can take a while to start on a cold machine.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Tools each built-in target needs (required, optional). Targets not
+/// listed need nothing outside the editor.
+const REQUIREMENTS: &[(&str, &[&str], &[&str])] = &[
+    ("wechat", &["node"], &["npm", "miniprogram-ci", "wechat-devtools"]),
+    ("android", &["java", "gradle", "android-sdk"], &[]),
+    ("desktop", &[], &["rcedit"]),
+];
+const TARGETS: &[&str] = &["web", "single-file", "offline", "wechat", "douyin", "pwa", "desktop", "android"];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolInfo {
+    pub id: String,
+    pub name: String,
+    pub found: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub min_version: Option<String>,
+    /// Found, and at least `min_version`.
+    pub ok: bool,
+    /// `managed`, `env`, `path` or `default` (a standard install location).
+    pub source: Option<String>,
+    /// Whether `install_node`-style managed installation is available.
+    pub installable: bool,
+    /// What to do when the tool is missing or too old.
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetToolchains {
+    pub target: String,
+    pub required: Vec<String>,
+    pub optional: Vec<String>,
+    /// Required tools that are missing or too old.
+    pub missing: Vec<String>,
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolchainReport {
+    pub tools: Vec<ToolInfo>,
+    pub targets: Vec<TargetToolchains>,
+}
+
+#[derive(Clone, Serialize)]
+struct InstallProgress {
+    tool: String,
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+/// Detected tools by project (`""` for none) and tool id.
+#[derive(Default)]
+pub struct ToolchainCache {
+    entries: Mutex<HashMap<(String, String), (Instant, ToolInfo)>>,
+}
+
+impl ToolchainCache {
+    fn get(&self, project: &str, tool: &str) -> Option<ToolInfo> {
+        let entries = self.entries.lock().ok()?;
+        let (at, info) = entries.get(&(project.to_string(), tool.to_string()))?;
+        (at.elapsed() < CACHE_TTL).then(|| info.clone())
+    }
+
+    fn put(&self, project: &str, info: &ToolInfo) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert((project.to_string(), info.id.clone()), (Instant::now(), info.clone()));
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+impl ToolInfo {
+    fn missing(id: &str, name: &str, hint: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            found: false,
+            path: None,
+            version: None,
+            min_version: None,
+            ok: false,
+            source: None,
+            installable: false,
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    fn found(id: &str, name: &str, path: &Path, source: &str, version: Option<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            found: true,
+            path: Some(path.to_string_lossy().to_string()),
+            version,
+            min_version: None,
+            ok: true,
+            source: Some(source.to_string()),
+            installable: false,
+            hint: None,
+        }
+    }
+
+    /// Marks the tool not ok when its version is unknown or below `min`.
+    fn require(mut self, min: &str, hint: &str) -> Self {
+        self.min_version = Some(min.to_string());
+        if self.found && !self.version.as_deref().is_some_and(|v| crate::compiler::version_ge(v, min)) {
+            self.ok = false;
+            self.hint = Some(hint.to_string());
+        }
+        self
+    }
+
+    fn installable(mut self) -> Self {
+        self.installable = true;
+        self
+    }
+}
+
+// =============================================================================
+// Managed toolchains
+// =============================================================================
+
+fn managed_node_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(NODE_DIR)
+}
+
+/// Where the portable build keeps `node` and `npm`.
+fn node_bin_dir(node_dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        node_dir.to_path_buf()
+    } else {
+        node_dir.join("bin")
+    }
+}
+
+/// Puts managed toolchains that are installed on the PATH of commands;
+/// called on startup and after an install.
+pub fn register_managed(app: &AppHandle) {
+    let bin = node_bin_dir(&managed_node_dir(app));
+    if bin.join(if cfg!(windows) { "node.exe" } else { "node" }).is_file() {
+        env::add_managed_dir(bin);
+    }
+}
+
+/// The portable Node archive for this platform and whether it is a zip.
+fn node_archive() -> Result<(String, bool), String> {
+    let os = if cfg!(target_os = "windows") {
+        "win"
+    } else if cfg!(target_os = "macos") {
+        "darwin"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        return Err("No portable Node build for this platform".to_string());
+    };
+    let arch = if cfg!(target_arch = "x86_64") {
+        "x64"
+    } else if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else {
+        return Err("No portable Node build for this architecture".to_string());
+    };
+    let ext = if cfg!(windows) { "zip" } else { "tar.gz" };
+    Ok((format!("node-v{}-{}-{}.{}", NODE_VERSION, os, arch, ext), cfg!(windows)))
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "toolchain-progress",
+        InstallProgress {
+            tool: "node".to_string(),
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
+
+/// The published SHA-256 of `file` from the release's SHASUMS256.txt.
+async fn node_checksum(file: &str) -> Result<String, String> {
+    let url = format!("{}/v{}/SHASUMS256.txt", NODE_DOWNLOAD_BASE, NODE_VERSION);
+    let response = reqwest::get(&url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("Download failed: {}", e))?;
+    text.lines()
+        .filter_map(|line| line.split_once("  "))
+        .find(|(_, name)| name.trim() == file)
+        .map(|(hash, _)| hash.trim().to_ascii_lowercase())
+        .ok_or_else(|| format!("No checksum published for {}", file))
+}
+
+async fn download_node(app: &AppHandle, url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {}", response.status()));
+    }
+
+    let total_size = response.content_length().unwrap_or(0);
+    let mut data = Vec::with_capacity(total_size as usize);
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download interrupted: {}", e))?;
+        data.extend_from_slice(&chunk);
+        if total_size > 0 {
+            let pct = (data.len() as f32 / total_size as f32).min(1.0);
+            emit_progress(
+                app,
+                "download",
+                &format!(
+                    "Downloading Node.js... {:.0}/{:.0} MB",
+                    data.len() as f32 / 1_048_576.0,
+                    total_size as f32 / 1_048_576.0
+                ),
+                0.05 + pct * 0.75,
+            );
+        }
+    }
+    Ok(data)
+}
+
+// =============================================================================
+// Detection
+// =============================================================================
+
+/// Runs `program args` and returns what it printed, stdout then stderr.
+async fn probe(program: &Path, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args).stdin(std::process::Stdio::null()).kill_on_drop(true);
+    #[cfg(windows)]
+    command.creation_flags(crate::process::CREATE_NO_WINDOW);
+    let output = tokio::time::timeout(PROBE_TIMEOUT, command.output()).await.ok()?.ok()?;
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push('\n');
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+/// The first version-like word: `v20.1.0`, `"17.0.8"`, `1.8.0_381` → `1.8.0`.
+fn parse_version(text: &str) -> Option<String> {
+    text.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| c == '"' || c == '\'');
+        let word = word.strip_prefix('v').unwrap_or(word);
+        if !word.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let version: String = word.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        let version = version.trim_end_matches('.');
+        version.contains('.').then(|| version.to_string()).or_else(|| {
+            // `openjdk 21 2023-09-19` has a bare major version.
+            (version.len() <= 3 && !version.is_empty()).then(|| version.to_string())
+        })
+    })
+}
+
+fn source_of(path: &Path, managed: &Path) -> &'static str {
+    if path.starts_with(managed) {
+        "managed"
+    } else {
+        "path"
+    }
+}
+
+async fn detect_node(env: &CommandEnv, managed: &Path) -> ToolInfo {
+    let hint = "Install Node.js from https://nodejs.org, or let the editor install a managed copy.";
+    let Some(path) = env.find("node") else {
+        return ToolInfo::missing("node", "Node.js", hint).installable();
+    };
+    let version = probe(&path, &["--version"]).await.as_deref().and_then(parse_version);
+    ToolInfo::found("node", "Node.js", &path, source_of(&path, managed), version)
+        .require(
+            MIN_NODE_VERSION,
+            "This Node.js is too old. Update it, or install a managed copy and take the old one off the PATH.",
+        )
+        .installable()
+}
+
+async fn detect_npm(env: &CommandEnv, managed: &Path) -> ToolInfo {
+    let Some(path) = env.find("npm") else {
+        return ToolInfo::missing("npm", "npm", "npm comes with Node.js; reinstall Node.js.").installable();
+    };
+    let version = probe(&path, &["--version"]).await.as_deref().and_then(parse_version);
+    ToolInfo::found("npm", "npm", &path, source_of(&path, managed), version).installable()
+}
+
+fn detect_miniprogram_ci(env: &CommandEnv, managed: &Path) -> ToolInfo {
+    // Not probed: it is a Node script that takes seconds to load.
+    match env.find("miniprogram-ci") {
+        Some(path) => ToolInfo::found("miniprogram-ci", "miniprogram-ci", &path, source_of(&path, managed), None),
+        None => ToolInfo::missing(
+            "miniprogram-ci",
+            "miniprogram-ci",
+            "Uploads and previews run it through npx, which downloads it on first use. Run `npm i -g miniprogram-ci` to install it once.",
+        ),
+    }
+}
+
+fn detect_wechat_devtools() -> ToolInfo {
+    let mut candidates = Vec::new();
+    if cfg!(target_os = "macos") {
+        candidates.push(PathBuf::from("/Applications/wechatwebdevtools.app/Contents/MacOS/cli"));
+    }
+    if cfg!(windows) {
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Ok(dir) = std::env::var(var) {
+                candidates.push(Path::new(&dir).join("Tencent").join("微信web开发者工具").join("cli.bat"));
+            }
+        }
+    }
+    // Launching the CLI starts the IDE, so it is only located.
+    match candidates.into_iter().find(|path| path.is_file()) {
+        Some(path) => ToolInfo::found("wechat-devtools", "WeChat DevTools", &path, "default", None),
+        None => ToolInfo::missing(
+            "wechat-devtools",
+            "WeChat DevTools",
+            "Install WeChat DevTools from https://developers.weixin.qq.com/miniprogram/dev/devtools/download.html to open exports in the simulator.",
+        ),
+    }
+}
+
+async fn detect_java(env: &CommandEnv) -> ToolInfo {
+    let hint = "Install JDK 17 or newer (e.g. from https://adoptium.net) and set JAVA_HOME.";
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let located = std::env::var_os("JAVA_HOME")
+        .map(|home| PathBuf::from(home).join("bin").join(java))
+        .filter(|path| path.is_file())
+        .map(|path| (path, "env"))
+        .or_else(|| env.find("java").map(|path| (path, "path")));
+    let Some((path, source)) = located else {
+        return ToolInfo::missing("java", "Java (JDK)", hint);
+    };
+    // `java -version` prints to stderr. macOS ships a stub that fails
+    // without a JDK, so no version means no Java.
+    let Some(version) = probe(&path, &["-version"]).await.as_deref().and_then(parse_version) else {
+        return ToolInfo::missing("java", "Java (JDK)", hint);
+    };
+    ToolInfo::found("java", "Java (JDK)", &path, source, Some(version))
+        .require(MIN_JAVA_VERSION, "The Android build needs JDK 17 or newer; point JAVA_HOME at one.")
+}
+
+async fn detect_gradle(env: &CommandEnv) -> ToolInfo {
+    let hint = "Install Gradle from https://gradle.org/install or configure its path in the Android export settings.";
+    let Some(path) = env.find("gradle") else {
+        return ToolInfo::missing("gradle", "Gradle", hint);
+    };
+    let version = probe(&path, &["--version"])
+        .await
+        .and_then(|text| text.lines().find_map(|line| line.trim().strip_prefix("Gradle ").and_then(parse_version)));
+    ToolInfo::found("gradle", "Gradle", &path, "path", version)
+}
+
+/// The Android SDK: ANDROID_HOME, ANDROID_SDK_ROOT, then where Android
+/// Studio installs it. Returns the directory and where it was found.
+pub(crate) fn android_sdk_dir() -> Option<(PathBuf, &'static str)> {
+    let is_sdk = |dir: &Path| dir.join("platform-tools").is_dir() || dir.join("platforms").is_dir();
+    for var in ["ANDROID_HOME", "ANDROID_SDK_ROOT"] {
+        if let Some(dir) = std::env::var_os(var).map(PathBuf::from) {
+            if is_sdk(&dir) {
+                return Some((dir, "env"));
+            }
+        }
+    }
+    let default = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("Android").join("Sdk"))
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Android/sdk"))
+    } else {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Android/Sdk"))
+    };
+    default.filter(|dir| is_sdk(dir)).map(|dir| (dir, "default"))
+}
+
+fn detect_android_sdk() -> ToolInfo {
+    let Some((dir, source)) = android_sdk_dir() else {
+        return ToolInfo::missing(
+            "android-sdk",
+            "Android SDK",
+            "Install Android Studio or the command-line tools and set ANDROID_HOME.",
+        );
+    };
+    // The highest installed platform, e.g. `android-34` → API 34.
+    let api = std::fs::read_dir(dir.join("platforms"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("android-")?.parse::<u32>().ok())
+        .max();
+    let mut info = ToolInfo::found("android-sdk", "Android SDK", &dir, source, api.map(|api| api.to_string()));
+    if api.is_none() {
+        info.ok = false;
+        info.hint = Some("No SDK platform is installed; add one with the SDK Manager.".to_string());
+    }
+    info
+}
+
+fn detect_rcedit(env: &CommandEnv) -> ToolInfo {
+    match env.find("rcedit") {
+        Some(path) => ToolInfo::found("rcedit", "rcedit", &path, "path", None),
+        None => ToolInfo::missing(
+            "rcedit",
+            "rcedit",
+            "Without rcedit, Windows executables keep the default icon. Get it from https://github.com/electron/rcedit.",
+        ),
+    }
+}
+
+async fn detect_tool(id: &str, env: &CommandEnv, managed: &Path) -> Option<ToolInfo> {
+    Some(match id {
+        "node" => detect_node(env, managed).await,
+        "npm" => detect_npm(env, managed).await,
+        "miniprogram-ci" => detect_miniprogram_ci(env, managed),
+        "wechat-devtools" => detect_wechat_devtools(),
+        "java" => detect_java(env).await,
+        "gradle" => detect_gradle(env).await,
+        "android-sdk" => detect_android_sdk(),
+        "rcedit" if cfg!(windows) => detect_rcedit(env),
+        _ => return None,
+    })
+}
+
+fn requirements(target: &str) -> (Vec<String>, Vec<String>) {
+    let (required, optional) = REQUIREMENTS
+        .iter()
+        .find(|(id, _, _)| *id == target)
+        .map(|(_, required, optional)| (*required, *optional))
+        .unwrap_or((&[], &[]));
+    let owned = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+    (owned(required), owned(optional))
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Tools needed by `targets` (all built-in targets by default), found as
+/// commands of `project_dir` would find them. Cached results younger than
+/// five minutes are reused unless `refresh` is set.
+#[tauri::command]
+pub async fn detect_toolchains(
+    app: AppHandle,
+    cache: State<'_, ToolchainCache>,
+    project_dir: Option<String>,
+    targets: Option<Vec<String>>,
+    refresh: Option<bool>,
+) -> Result<ToolchainReport, String> {
+    register_managed(&app);
+    let managed = node_bin_dir(&managed_node_dir(&app));
+    let key = project_dir.clone().unwrap_or_default();
+    let targets = targets.unwrap_or_else(|| TARGETS.iter().map(|t| t.to_string()).collect());
+
+    let mut ids: Vec<String> = Vec::new();
+    for target in &targets {
+        let (required, optional) = requirements(target);
+        for id in required.into_iter().chain(optional) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    let command_env = {
+        let project = project_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            CommandEnv::new(HashMap::new(), project.as_deref().map(Path::new), env::login_path_default())
+        })
+        .await
+        .map_err(|e| format!("Toolchain detection task failed: {}", e))?
+    };
+
+    let cached: Vec<Option<ToolInfo>> =
+        ids.iter().map(|id| if refresh == Some(true) { None } else { cache.get(&key, id) }).collect();
+    let probes = ids.iter().zip(&cached).map(|(id, cached)| {
+        let command_env = &command_env;
+        let managed = &managed;
+        async move {
+            match cached {
+                Some(info) => Some(info.clone()),
+                None => detect_tool(id, command_env, managed).await,
+            }
+        }
+    });
+    let tools: Vec<ToolInfo> = futures_util::future::join_all(probes).await.into_iter().flatten().collect();
+    for tool in &tools {
+        cache.put(&key, tool);
+    }
+
+    let targets = targets
+        .into_iter()
+        .map(|target| {
+            let (required, optional) = requirements(&target);
+            let optional: Vec<String> = optional.into_iter().filter(|id| tools.iter().any(|t| &t.id == id)).collect();
+            let missing: Vec<String> = required
+                .iter()
+                .filter(|id| !tools.iter().any(|t| &t.id == *id && t.ok))
+                .cloned()
+                .collect();
+            TargetToolchains { target, ready: missing.is_empty(), required, optional, missing }
+        })
+        .collect();
+    Ok(ToolchainReport { tools, targets })
+}
+
+/// Downloads portable Node.js into the app data dir, checks it against the
+/// published checksum and makes it available to commands. Progress is
+/// reported as `toolchain-progress` events.
+#[tauri::command]
+pub async fn install_node(app: AppHandle, cache: State<'_, ToolchainCache>) -> Result<ToolInfo, String> {
+    let (file, is_zip) = node_archive()?;
+    let url = format!("{}/v{}/{}", NODE_DOWNLOAD_BASE, NODE_VERSION, file);
+    let install_dir = managed_node_dir(&app);
+
+    emit_progress(&app, "download", "Downloading Node.js...", 0.05);
+    let expected = node_checksum(&file).await?;
+    let data = download_node(&app, &url).await?;
+    let actual = format!("{:x}", Sha256::digest(&data));
+    if actual != expected {
+        return Err(format!("Checksum mismatch for {}: expected {}, got {}", file, expected, actual));
+    }
+
+    emit_progress(&app, "extract", "Extracting Node.js...", 0.85);
+    let target = install_dir.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        // Unpacked beside the old copy, which is only replaced on success.
+        let staging = target.with_extension("partial");
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+        }
+        std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+        let extracted = if is_zip {
+            crate::compiler::extract_zip(&data, &staging)
+        } else {
+            crate::compiler::extract_tar_gz(&data, &staging)
+        };
+        if let Err(e) = extracted {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+        if target.exists() {
+            std::fs::remove_dir_all(&target).map_err(|e| format!("Failed to remove the old Node.js: {}", e))?;
+        }
+        std::fs::rename(&staging, &target).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Extract task failed: {}", e))??;
+
+    register_managed(&app);
+    cache.clear();
+    emit_progress(&app, "complete", &format!("Node.js {} installed", NODE_VERSION), 1.0);
+    eprintln!("[Toolchains] Installed Node.js {} to {}", NODE_VERSION, install_dir.display());
+
+    let managed = node_bin_dir(&install_dir);
+    let command_env = CommandEnv::new(HashMap::from([("PATH".to_string(), managed.to_string_lossy().to_string())]), None, false);
+    Ok(detect_node(&command_env, &managed).await)
+}
@@ -0,0 +1,260 @@
+//! Tray icon — build and preview status while the editor is out of sight
+//!
+//! The tray shows what runs in the background: active jobs from the job
+//! queue and preview servers. Its menu opens the editor, stops the preview
+//! servers, cancels running jobs and quits.
+//!
+//! With `closeToTray` on (the default), closing the editor while jobs are
+//! running hides its windows instead; the editor exits once the last job
+//! finishes, or when Quit is chosen from the tray. The main window asks
+//! `close_to_tray` after its unsaved-changes prompt, so nothing is left to
+//! save by then.
+
+use crate::editor_settings;
+use crate::jobs::{JobInfo, JobStatus};
+use crate::windows::MAIN_LABEL;
+use crate::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Listener, Manager, State, Wry};
+
+const TRAY_ID: &str = "main";
+const ID_PREFIX: &str = "tray.";
+const OPEN: &str = "tray.open";
+const STOP_PREVIEW: &str = "tray.stop-preview";
+const CANCEL_JOBS: &str = "tray.cancel-jobs";
+const CLOSE_TO_TRAY: &str = "tray.close-to-tray";
+const QUIT: &str = "tray.quit";
+
+/// Whether the editor was closed to the tray, and whether it is quitting.
+#[derive(Default)]
+pub struct TrayState {
+    hidden: AtomicBool,
+    quitting: AtomicBool,
+}
+
+// =============================================================================
+// Status
+// =============================================================================
+
+struct Status {
+    jobs: Vec<JobInfo>,
+    previews: Vec<u16>,
+}
+
+impl Status {
+    fn current(app: &AppHandle) -> Self {
+        let state = app.state::<AppState>();
+        let jobs = state
+            .jobs
+            .list()
+            .into_iter()
+            .filter(|job| matches!(job.status, JobStatus::Queued | JobStatus::Running))
+            .collect();
+        let previews = state
+            .preview_servers
+            .lock()
+            .map(|servers| servers.values().filter(|s| s.is_running()).map(|s| s.port()).collect())
+            .unwrap_or_default();
+        Self { jobs, previews }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.jobs.as_slice() {
+            [] => {}
+            [job] => lines.push(job_line(job)),
+            jobs => {
+                lines.push(format!("{} jobs", jobs.len()));
+                lines.extend(jobs.iter().map(job_line));
+            }
+        }
+        match self.previews.as_slice() {
+            [] => {}
+            [port] => lines.push(format!("Preview on port {}", port)),
+            ports => lines.push(format!("{} previews running", ports.len())),
+        }
+        if lines.is_empty() {
+            lines.push("Idle".to_string());
+        }
+        lines
+    }
+}
+
+fn job_line(job: &JobInfo) -> String {
+    match (job.status, job.progress) {
+        (JobStatus::Queued, _) => format!("{} (queued)", job.label),
+        (_, Some(progress)) => format!("{} ({:.0}%)", job.label, progress * 100.0),
+        _ => job.label.clone(),
+    }
+}
+
+// =============================================================================
+// Tray
+// =============================================================================
+
+fn build_menu(app: &AppHandle, status: &Status) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    for line in status.lines() {
+        menu.append(&MenuItem::new(app, line, false, None::<&str>)?)?;
+    }
+    let close_to_tray = editor_settings::load(app).map(|s| s.close_to_tray).unwrap_or(true);
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, OPEN, "Open Editor", true, None::<&str>)?)?;
+    let (has_previews, has_jobs) = (!status.previews.is_empty(), !status.jobs.is_empty());
+    menu.append(&MenuItem::with_id(app, STOP_PREVIEW, "Stop Preview Server", has_previews, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, CANCEL_JOBS, "Cancel Running Jobs", has_jobs, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    let keep_running = "Keep Running While Jobs Finish";
+    menu.append(&CheckMenuItem::with_id(app, CLOSE_TO_TRAY, keep_running, true, close_to_tray, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, QUIT, "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Updates the tray menu and tooltip from the jobs and preview servers.
+/// Exits when the editor was closed to the tray and nothing runs anymore.
+pub fn refresh(app: &AppHandle) {
+    let status = Status::current(app);
+    if status.jobs.is_empty() && app.state::<TrayState>().hidden.load(Ordering::Relaxed) {
+        exit(app);
+        return;
+    }
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = format!("{}\n{}", app.package_info().name, status.lines().join("\n"));
+    let _ = tray.set_tooltip(Some(tooltip));
+    match build_menu(app, &status) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("[tray] Failed to update the tray menu: {}", e),
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    let status = Status::current(app);
+    let menu = match build_menu(app, &status) {
+        Ok(menu) => menu,
+        Err(e) => {
+            eprintln!("[tray] Failed to build the tray menu: {}", e);
+            return;
+        }
+    };
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .tooltip(&app.package_info().name)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_editor(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    if let Err(e) = builder.build(app) {
+        eprintln!("[tray] Failed to create the tray icon: {}", e);
+        return;
+    }
+    // Progress events are not followed; the menu shows progress as of the
+    // last status change.
+    let handle = app.clone();
+    app.listen_any("job-status", move |_| refresh(&handle));
+}
+
+fn show_editor(app: &AppHandle) {
+    app.state::<TrayState>().hidden.store(false, Ordering::Relaxed);
+    for window in app.webview_windows().values() {
+        let _ = window.show();
+    }
+    if let Some(window) = app.get_webview_window(MAIN_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Destroys the main window, which stops everything else with it.
+fn exit(app: &AppHandle) {
+    let tray = app.state::<TrayState>();
+    tray.hidden.store(false, Ordering::Relaxed);
+    tray.quitting.store(true, Ordering::Relaxed);
+    match app.get_webview_window(MAIN_LABEL) {
+        Some(window) => {
+            let _ = window.destroy();
+        }
+        None => app.exit(0),
+    }
+}
+
+// =============================================================================
+// Events
+// =============================================================================
+
+/// Handles a choice from the tray menu. Returns false for other menus.
+pub fn handle_event(app: &AppHandle, id: &str) -> bool {
+    if !id.starts_with(ID_PREFIX) {
+        return false;
+    }
+    match id {
+        OPEN => show_editor(app),
+        STOP_PREVIEW => {
+            if let Ok(mut servers) = app.state::<AppState>().preview_servers.lock() {
+                for (_, mut server) in servers.drain() {
+                    server.stop();
+                }
+            }
+        }
+        CANCEL_JOBS => {
+            let state = app.state::<AppState>();
+            for job in Status::current(app).jobs {
+                state.jobs.cancel(app, job.id);
+            }
+        }
+        CLOSE_TO_TRAY => {
+            let result = editor_settings::modify(app, None, |settings| {
+                settings.close_to_tray = !settings.close_to_tray;
+                Ok(())
+            });
+            if let Err(e) = result {
+                eprintln!("[tray] Failed to save the setting: {}", e);
+            }
+        }
+        QUIT => {
+            if app.state::<TrayState>().hidden.load(Ordering::Relaxed) {
+                exit(app);
+            } else if let Some(window) = app.get_webview_window(MAIN_LABEL) {
+                // Goes through the unsaved-changes prompt.
+                app.state::<TrayState>().quitting.store(true, Ordering::Relaxed);
+                let _ = window.close();
+            }
+        }
+        _ => {}
+    }
+    refresh(app);
+    true
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Called by the main window when it is about to close. Hides the editor
+/// and returns true when jobs are running and `closeToTray` is on.
+#[tauri::command]
+pub fn close_to_tray(app: AppHandle, tray: State<TrayState>) -> bool {
+    if tray.quitting.load(Ordering::Relaxed) || app.tray_by_id(TRAY_ID).is_none() {
+        return false;
+    }
+    let enabled = editor_settings::load(&app).map(|s| s.close_to_tray).unwrap_or(true);
+    if !enabled || Status::current(&app).jobs.is_empty() {
+        return false;
+    }
+    tray.hidden.store(true, Ordering::Relaxed);
+    for window in app.webview_windows().values() {
+        let _ = window.hide();
+    }
+    refresh(&app);
+    true
+}
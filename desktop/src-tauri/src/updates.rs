@@ -0,0 +1,204 @@
+//! Updates — release channels, release notes and install on restart
+//!
+//! - The channel (`general.updateChannel`: stable, beta or nightly) picks
+//!   the feed the updater checks; each channel has its own endpoint.
+//! - `check_update` returns the new version with its release notes, so
+//!   they can be read before anything is downloaded.
+//! - `download_update` downloads in the background, reporting
+//!   `update-progress` and then `update-ready` or `update-failed`. The
+//!   downloaded update is installed when the editor quits, or right away
+//!   through `install_update`.
+//!
+//! Updates are full packages; the updater has no delta format, so a
+//! download costs the same on every channel.
+
+use crate::editor_settings;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use url::Url;
+
+const CHANNEL_SETTING: &str = "general.updateChannel";
+const RELEASES: &str = "https://github.com/esengine/estella/releases";
+const MANIFEST: &str = "latest.json";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl UpdateChannel {
+    fn from_settings(app: &AppHandle) -> Self {
+        let settings = editor_settings::load(app).ok();
+        match settings.as_ref().and_then(|s| s.values.get(CHANNEL_SETTING)).and_then(|v| v.as_str()) {
+            Some("beta") => UpdateChannel::Beta,
+            Some("nightly") => UpdateChannel::Nightly,
+            _ => UpdateChannel::Stable,
+        }
+    }
+
+    fn endpoint(self) -> String {
+        match self {
+            UpdateChannel::Stable => format!("{}/latest/download/{}", RELEASES, MANIFEST),
+            // Prereleases are republished under a fixed tag per channel.
+            UpdateChannel::Beta => format!("{}/download/beta/{}", RELEASES, MANIFEST),
+            UpdateChannel::Nightly => format!("{}/download/nightly/{}", RELEASES, MANIFEST),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release date as RFC 3339, when the feed has one.
+    pub date: Option<String>,
+    /// Release notes from the feed.
+    pub body: Option<String>,
+}
+
+impl UpdateInfo {
+    fn new(update: &Update, channel: UpdateChannel) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            channel,
+            date: update.raw_json.get("pub_date").and_then(|date| date.as_str()).map(String::from),
+            body: update.body.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct DownloadProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+struct Downloaded {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
+/// The update being downloaded or waiting to be installed.
+#[derive(Default)]
+pub struct UpdateState {
+    downloading: AtomicBool,
+    ready: Mutex<Option<Downloaded>>,
+}
+
+// =============================================================================
+// Updater
+// =============================================================================
+
+fn resolve_proxy(explicit: Option<String>) -> Option<Url> {
+    explicit
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+                .iter()
+                .find_map(|key| std::env::var(key).ok())
+        })
+        .and_then(|s| Url::parse(&s).ok())
+}
+
+async fn find_update(app: &AppHandle, proxy: Option<String>) -> Result<Option<(Update, UpdateChannel)>, String> {
+    let channel = UpdateChannel::from_settings(app);
+    let endpoint = Url::parse(&channel.endpoint()).map_err(|e| e.to_string())?;
+    let mut builder = app.updater_builder().endpoints(vec![endpoint]).map_err(|e| e.to_string())?;
+    if let Some(proxy_url) = resolve_proxy(proxy) {
+        builder = builder.proxy(proxy_url);
+    }
+    let updater = builder.build().map_err(|e| e.to_string())?;
+    let update = updater.check().await.map_err(|e| e.to_string())?;
+    Ok(update.map(|update| (update, channel)))
+}
+
+async fn download(app: &AppHandle, proxy: Option<String>) -> Result<UpdateInfo, String> {
+    let (update, channel) = find_update(app, proxy).await?.ok_or_else(|| "No update available".to_string())?;
+    let mut downloaded = 0u64;
+    let bytes = update
+        .download(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("update-progress", DownloadProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    let info = UpdateInfo::new(&update, channel);
+    *app.state::<UpdateState>().ready.lock().unwrap() = Some(Downloaded { update, bytes });
+    Ok(info)
+}
+
+/// Installs a downloaded update; called when the editor quits. On Windows
+/// the installer takes over and ends the process.
+pub fn install_pending(app: &AppHandle) {
+    let Some(Downloaded { update, bytes }) = app.state::<UpdateState>().ready.lock().unwrap().take() else {
+        return;
+    };
+    if let Err(e) = update.install(bytes) {
+        eprintln!("[updates] Failed to install {}: {}", update.version, e);
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn check_update(app: AppHandle, proxy: Option<String>) -> Result<Option<UpdateInfo>, String> {
+    Ok(find_update(&app, proxy).await?.map(|(update, channel)| UpdateInfo::new(&update, channel)))
+}
+
+/// Starts downloading the update in the background. Returns false when a
+/// download is already running.
+#[tauri::command]
+pub fn download_update(app: AppHandle, state: State<UpdateState>, proxy: Option<String>) -> bool {
+    if state.downloading.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    tauri::async_runtime::spawn(async move {
+        let result = download(&app, proxy).await;
+        app.state::<UpdateState>().downloading.store(false, Ordering::SeqCst);
+        match result {
+            Ok(info) => {
+                let _ = app.emit("update-ready", info);
+            }
+            Err(e) => {
+                let _ = app.emit("update-failed", e);
+            }
+        }
+    });
+    true
+}
+
+/// The downloaded update waiting for a restart, if any.
+#[tauri::command]
+pub fn get_pending_update(state: State<UpdateState>) -> Option<String> {
+    state.ready.lock().unwrap().as_ref().map(|ready| ready.update.version.clone())
+}
+
+/// Installs now: the downloaded update if there is one, otherwise the
+/// latest one on the channel. The caller relaunches the editor afterwards.
+#[tauri::command]
+pub async fn install_update(app: AppHandle, proxy: Option<String>) -> Result<(), String> {
+    let ready = app.state::<UpdateState>().ready.lock().unwrap().take();
+    if let Some(Downloaded { update, bytes }) = ready {
+        return update.install(bytes).map_err(|e| e.to_string());
+    }
+    let (update, _) = find_update(&app, proxy).await?.ok_or_else(|| "No update available".to_string())?;
+    update.download_and_install(|_, _| {}, || {}).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,316 @@
+//! Commit, history and file revert
+//!
+//! Staging and checkout can touch thousands of files in an asset-heavy
+//! project, so both report `vcs-progress` events while the command runs.
+//! Commits use the author from the git configuration, falling back to the
+//! one passed by the editor for teammates who never set up git.
+
+use super::ProjectRepo;
+use crate::export::build_info::iso8601_utc;
+use git2::build::CheckoutBuilder;
+use git2::{DiffOptions, ErrorCode, IndexAddOption, Oid, Repository, Signature, Sort};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+const DEFAULT_LOG_LIMIT: usize = 100;
+/// Files between two staging progress events.
+const PROGRESS_STEP: usize = 50;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author: String,
+    pub email: String,
+    pub time: String,
+    /// Files of the project changed by the commit.
+    pub files_changed: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct VcsProgress {
+    operation: &'static str,
+    path: Option<String>,
+    current: usize,
+    total: Option<usize>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Stages `paths` (deletions included) and commits them. With `all`, every
+/// change in the project folder is staged instead. Changes staged before
+/// the call are committed as well.
+#[tauri::command]
+pub async fn vcs_commit(
+    app: AppHandle,
+    project_dir: String,
+    message: String,
+    paths: Vec<String>,
+    all: bool,
+    author: Option<CommitAuthor>,
+) -> Result<CommitInfo, String> {
+    tokio::task::spawn_blocking(move || commit(&app, Path::new(&project_dir), &message, &paths, all, author))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// Commits touching the project, newest first; with `path`, only those
+/// touching that file or folder.
+#[tauri::command]
+pub async fn vcs_log(
+    project_dir: String,
+    path: Option<String>,
+    skip: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<CommitInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        log(
+            Path::new(&project_dir),
+            path.as_deref(),
+            skip.unwrap_or(0),
+            limit.unwrap_or(DEFAULT_LOG_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// Restores `paths` in the working tree and index to their state at
+/// `commit` (HEAD by default), discarding local changes. Files that did not
+/// exist at that commit are unstaged but kept on disk.
+#[tauri::command]
+pub async fn vcs_revert_files(
+    app: AppHandle,
+    project_dir: String,
+    paths: Vec<String>,
+    commit: Option<String>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || revert(&app, Path::new(&project_dir), &paths, commit.as_deref()))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+// =============================================================================
+// Commit
+// =============================================================================
+
+fn commit(
+    app: &AppHandle,
+    project_dir: &Path,
+    message: &str,
+    paths: &[String],
+    all: bool,
+    author: Option<CommitAuthor>,
+) -> Result<CommitInfo, String> {
+    if message.trim().is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+    let project = ProjectRepo::require(project_dir)?;
+    let repo = &project.repo;
+    let signature = match repo.signature() {
+        Ok(signature) => signature,
+        Err(_) => match &author {
+            Some(author) => Signature::now(&author.name, &author.email).map_err(|e| e.message().to_string())?,
+            None => return Err("No git author is configured; set user.name and user.email".to_string()),
+        },
+    };
+
+    let mut index = repo.index().map_err(|e| format!("Failed to open index: {}", e.message()))?;
+    let mut staged = 0;
+    let mut progress = |path: &Path| {
+        staged += 1;
+        if staged % PROGRESS_STEP == 0 {
+            emit(app, "stage", Some(path.to_string_lossy().to_string()), staged, None);
+        }
+        0
+    };
+    if all {
+        let spec = if project.prefix.is_empty() { "*".to_string() } else { project.prefix.clone() };
+        index
+            .add_all([spec.as_str()], IndexAddOption::DEFAULT, Some(&mut |p: &Path, _: &[u8]| progress(p)))
+            .map_err(|e| format!("Failed to stage changes: {}", e.message()))?;
+        // `add_all` skips deletions; `update_all` stages them.
+        index
+            .update_all([spec.as_str()], Some(&mut |p: &Path, _: &[u8]| progress(p)))
+            .map_err(|e| format!("Failed to stage changes: {}", e.message()))?;
+    } else {
+        let workdir = repo.workdir().ok_or("Repository has no working tree")?;
+        for path in paths {
+            let repo_path = project.to_repo(path)?;
+            let result = if workdir.join(&repo_path).exists() {
+                index.add_path(Path::new(&repo_path))
+            } else {
+                index.remove_path(Path::new(&repo_path))
+            };
+            result.map_err(|e| format!("Failed to stage {}: {}", path, e.message()))?;
+            progress(Path::new(path));
+        }
+    }
+    index.write().map_err(|e| format!("Failed to write index: {}", e.message()))?;
+    emit(app, "stage", None, staged, Some(staged));
+
+    let tree_id = index.write_tree().map_err(|e| format!("Failed to write tree: {}", e.message()))?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.message().to_string())?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().map_err(|e| e.message().to_string())?),
+        Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(format!("Failed to read HEAD: {}", e.message())),
+    };
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree_id) {
+        return Err("Nothing to commit".to_string());
+    }
+    let parents: Vec<_> = parent.iter().collect();
+    let id = repo
+        .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .map_err(|e| format!("Failed to commit: {}", e.message()))?;
+    info(&project, id, None)
+}
+
+// =============================================================================
+// History
+// =============================================================================
+
+fn log(project_dir: &Path, path: Option<&str>, skip: usize, limit: usize) -> Result<Vec<CommitInfo>, String> {
+    let project = ProjectRepo::require(project_dir)?;
+    let repo = &project.repo;
+    let spec = match path {
+        Some(path) => project.to_repo(path)?,
+        None => project.prefix.clone(),
+    };
+
+    let mut walk = repo.revwalk().map_err(|e| e.message().to_string())?;
+    match walk.push_head() {
+        Ok(()) => {}
+        Err(e) if e.code() == ErrorCode::UnbornBranch => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read history: {}", e.message())),
+    }
+    walk.set_sorting(Sort::TIME).map_err(|e| e.message().to_string())?;
+
+    let mut commits = Vec::new();
+    let mut skipped = 0;
+    for id in walk {
+        let id = id.map_err(|e| format!("Failed to read history: {}", e.message()))?;
+        let entry = info(&project, id, Some(&spec))?;
+        if entry.files_changed == 0 && !spec.is_empty() {
+            continue;
+        }
+        if skipped < skip {
+            skipped += 1;
+            continue;
+        }
+        commits.push(entry);
+        if commits.len() >= limit {
+            break;
+        }
+    }
+    Ok(commits)
+}
+
+/// Describes a commit; `files_changed` counts changes under `spec` (the
+/// whole project when `None`).
+fn info(project: &ProjectRepo, id: Oid, spec: Option<&str>) -> Result<CommitInfo, String> {
+    let repo = &project.repo;
+    let commit = repo.find_commit(id).map_err(|e| e.message().to_string())?;
+    let author = commit.author();
+    let id = commit.id().to_string();
+    Ok(CommitInfo {
+        short_id: id[..7].to_string(),
+        id,
+        summary: commit.summary().unwrap_or_default().to_string(),
+        message: commit.message().unwrap_or_default().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        email: author.email().unwrap_or_default().to_string(),
+        time: iso8601_utc(commit.time().seconds().max(0) as u64),
+        files_changed: files_changed(repo, &commit, spec.unwrap_or(&project.prefix))?,
+    })
+}
+
+fn files_changed(repo: &Repository, commit: &git2::Commit, spec: &str) -> Result<usize, String> {
+    let tree = commit.tree().map_err(|e| e.message().to_string())?;
+    let parent = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let mut options = DiffOptions::new();
+    if !spec.is_empty() {
+        options.pathspec(spec);
+    }
+    let diff = repo
+        .diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut options))
+        .map_err(|e| e.message().to_string())?;
+    Ok(diff.deltas().len())
+}
+
+// =============================================================================
+// Revert
+// =============================================================================
+
+fn revert(app: &AppHandle, project_dir: &Path, paths: &[String], commit: Option<&str>) -> Result<Vec<String>, String> {
+    let project = ProjectRepo::require(project_dir)?;
+    let repo = &project.repo;
+    let tree = match commit {
+        Some(rev) => repo
+            .revparse_single(rev)
+            .and_then(|o| o.peel_to_tree())
+            .map(Some)
+            .map_err(|e| format!("Unknown commit {}: {}", rev, e.message()))?,
+        None => project.head_tree()?,
+    };
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    let mut restored = Vec::new();
+    let mut unstaged = Vec::new();
+    for path in paths {
+        let repo_path = project.to_repo(path)?;
+        let tracked = tree.as_ref().is_some_and(|t| t.get_path(Path::new(&repo_path)).is_ok());
+        if tracked {
+            checkout.path(repo_path.as_str());
+            restored.push(path.clone());
+        } else {
+            unstaged.push(repo_path);
+        }
+    }
+
+    if let (Some(tree), false) = (&tree, restored.is_empty()) {
+        checkout.progress(|path, current, total| {
+            let path = path.map(|p| p.to_string_lossy().to_string());
+            emit(app, "checkout", path, current, Some(total));
+        });
+        repo.checkout_tree(tree.as_object(), Some(&mut checkout))
+            .map_err(|e| format!("Failed to revert files: {}", e.message()))?;
+    }
+    if !unstaged.is_empty() {
+        let mut index = repo.index().map_err(|e| format!("Failed to open index: {}", e.message()))?;
+        for path in &unstaged {
+            // Not staged at all is fine; the file is simply left alone.
+            let _ = index.remove_path(Path::new(path));
+        }
+        index.write().map_err(|e| format!("Failed to write index: {}", e.message()))?;
+    }
+    Ok(restored)
+}
+
+fn emit(app: &AppHandle, operation: &'static str, path: Option<String>, current: usize, total: Option<usize>) {
+    let _ = app.emit(
+        "vcs-progress",
+        VcsProgress {
+            operation,
+            path,
+            current,
+            total,
+        },
+    );
+}
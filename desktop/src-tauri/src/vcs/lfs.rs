@@ -0,0 +1,181 @@
+//! Git LFS detection and setup
+//!
+//! `check_lfs` reports which large binary asset types the repository's
+//! `.gitattributes` do not route through LFS and which such files already
+//! sit in the index as plain blobs. `setup_lfs` appends the missing patterns
+//! to the project's `.gitattributes` and installs the LFS hooks when the
+//! `git lfs` extension is available. Files committed before setup stay plain
+//! blobs until rewritten with `git lfs migrate`.
+
+use super::ProjectRepo;
+use git2::AttrCheckFlags;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+/// Asset types that belong in LFS.
+const LFS_TYPES: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "tga", "psd", "wav", "ogg", "mp3", "mp4", "webm", "mov", "ttf", "otf", "fbx",
+    "glb", "zip",
+];
+/// LFS pointer files are well below this size.
+const MAX_POINTER_SIZE: usize = 1024;
+const MAX_LISTED_FILES: usize = 50;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BinaryFile {
+    pub path: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LfsReport {
+    pub is_repo: bool,
+    /// The `git lfs` command is available.
+    pub lfs_available: bool,
+    pub lfs_version: Option<String>,
+    /// The repository's hooks call git-lfs, so pushes upload LFS objects.
+    pub hooks_installed: bool,
+    /// Entries of `LFS_TYPES` not tracked by LFS.
+    pub untracked_types: Vec<String>,
+    /// Largest binaries in the index stored as plain blobs.
+    pub plain_binaries: Vec<BinaryFile>,
+    pub plain_binaries_total: usize,
+    /// Any of the above needs fixing.
+    pub needs_setup: bool,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn check_lfs(project_dir: String) -> Result<LfsReport, String> {
+    tokio::task::spawn_blocking(move || check(Path::new(&project_dir)))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// Tracks the missing asset types in the project's `.gitattributes` and
+/// installs the LFS hooks for this repository. Returns the updated report.
+#[tauri::command]
+pub async fn setup_lfs(project_dir: String) -> Result<LfsReport, String> {
+    tokio::task::spawn_blocking(move || setup(Path::new(&project_dir)))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+// =============================================================================
+// Check
+// =============================================================================
+
+fn check(project_dir: &Path) -> Result<LfsReport, String> {
+    let Some(project) = ProjectRepo::open(project_dir)? else {
+        return Ok(LfsReport::default());
+    };
+    let repo = &project.repo;
+    let mut report = LfsReport {
+        is_repo: true,
+        lfs_version: lfs_version(project_dir),
+        ..LfsReport::default()
+    };
+    report.lfs_available = report.lfs_version.is_some();
+    report.hooks_installed = std::fs::read_to_string(repo.path().join("hooks/pre-push"))
+        .is_ok_and(|hook| hook.contains("git-lfs") || hook.contains("git lfs"));
+
+    // Ask libgit2 for a sample path per type so every .gitattributes level
+    // and macro is honoured.
+    report.untracked_types = LFS_TYPES
+        .iter()
+        .filter(|ext| !is_lfs_tracked(&project, &format!("{}file.{}", project.prefix, ext)))
+        .map(|ext| ext.to_string())
+        .collect();
+
+    let index = repo.index().map_err(|e| format!("Failed to open index: {}", e.message()))?;
+    let odb = repo.odb().map_err(|e| e.message().to_string())?;
+    let mut plain = Vec::new();
+    for entry in index.iter() {
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let Some(rel) = project.to_project(&path) else {
+            continue;
+        };
+        if !LFS_TYPES.contains(&crate::export::assets::extension_of(&rel).as_str()) {
+            continue;
+        }
+        if let Ok((size, _)) = odb.read_header(entry.id) {
+            if size > MAX_POINTER_SIZE {
+                plain.push(BinaryFile { path: rel, size });
+            }
+        }
+    }
+    plain.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    report.plain_binaries_total = plain.len();
+    plain.truncate(MAX_LISTED_FILES);
+    report.plain_binaries = plain;
+
+    report.needs_setup = !report.untracked_types.is_empty() || !report.plain_binaries.is_empty() || !report.hooks_installed;
+    Ok(report)
+}
+
+fn is_lfs_tracked(project: &ProjectRepo, path: &str) -> bool {
+    project
+        .repo
+        .get_attr(Path::new(path), "filter", AttrCheckFlags::FILE_THEN_INDEX)
+        .ok()
+        .flatten()
+        == Some("lfs")
+}
+
+// =============================================================================
+// Setup
+// =============================================================================
+
+fn setup(project_dir: &Path) -> Result<LfsReport, String> {
+    ProjectRepo::require(project_dir)?;
+    let report = check(project_dir)?;
+
+    if !report.untracked_types.is_empty() {
+        let path = project_dir.join(".gitattributes");
+        let mut text = std::fs::read_to_string(&path).unwrap_or_default();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str("\n# Binary assets (Git LFS)\n");
+        for ext in &report.untracked_types {
+            text.push_str(&format!("*.{} filter=lfs diff=lfs merge=lfs -text\n", ext));
+        }
+        crate::project::write_atomic(&path, text.as_bytes())?;
+    }
+
+    if !report.lfs_available {
+        return Err("Git LFS is not installed. Install it from https://git-lfs.com, then run the setup again; \
+                    .gitattributes has already been updated"
+            .to_string());
+    }
+    if !report.hooks_installed {
+        let output = Command::new("git")
+            .args(["lfs", "install", "--local"])
+            .current_dir(project_dir)
+            .output()
+            .map_err(|e| format!("Failed to run git lfs: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "git lfs install failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    check(project_dir)
+}
+
+fn lfs_version(project_dir: &Path) -> Option<String> {
+    let output = Command::new("git").args(["lfs", "version"]).current_dir(project_dir).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
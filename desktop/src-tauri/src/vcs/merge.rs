@@ -0,0 +1,387 @@
+//! Structural three-way merge for scenes and prefabs
+//!
+//! A line-based merge of `.esscene`/`.esprefab` files leaves conflict markers
+//! inside the JSON. Here entities are matched by `id` and components by
+//! `type`, so edits to different entities, components or fields merge
+//! cleanly no matter where they sit in the file. Edits that really overlap
+//! are returned as conflicts; the merged document keeps "ours" for each of
+//! them until the editor sends back the user's choices through
+//! `resolve_scene_merge`.
+
+use super::ProjectRepo;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// Both sides changed the value differently.
+    BothModified,
+    /// Both sides added the value with different content.
+    BothAdded,
+    /// We deleted what they modified.
+    DeletedByUs,
+    /// They deleted what we modified.
+    DeletedByThem,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneConflict {
+    /// Location in the document, keyed by entity id and component type,
+    /// e.g. `/entities/5/components/Transform/data/position/x`.
+    pub path: String,
+    /// Entity the conflict belongs to, if any.
+    pub entity: Option<Value>,
+    pub entity_name: Option<String>,
+    pub kind: ConflictKind,
+    /// `None` where the value does not exist on that side.
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneMerge {
+    /// Merged document; conflicts are filled in with "ours".
+    pub merged: Value,
+    pub conflicts: Vec<SceneConflict>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Merges three versions of a scene or prefab given as JSON text. `base` is
+/// `None` when the file was added on both sides.
+#[tauri::command]
+pub fn merge_scene_json(base: Option<String>, ours: String, theirs: String) -> Result<SceneMerge, String> {
+    let parse = |label: &str, text: &str| {
+        serde_json::from_str::<Value>(text).map_err(|e| format!("Invalid JSON in the {} version: {}", label, e))
+    };
+    let base = base.as_deref().map(|b| parse("base", b)).transpose()?;
+    Ok(merge(base.as_ref(), &parse("our", &ours)?, &parse("their", &theirs)?))
+}
+
+/// Merges a scene or prefab left conflicted by a git merge, reading the
+/// three versions from the index.
+#[tauri::command]
+pub async fn merge_scene_conflict(project_dir: String, path: String) -> Result<SceneMerge, String> {
+    tokio::task::spawn_blocking(move || merge_conflict(Path::new(&project_dir), &path))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// Writes the resolved document and marks the conflict as resolved.
+#[tauri::command]
+pub async fn resolve_scene_merge(project_dir: String, path: String, merged: Value) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || resolve(Path::new(&project_dir), &path, &merged))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+// =============================================================================
+// Git conflicts
+// =============================================================================
+
+fn merge_conflict(project_dir: &Path, path: &str) -> Result<SceneMerge, String> {
+    let project = ProjectRepo::require(project_dir)?;
+    let repo = &project.repo;
+    let repo_path = project.to_repo(path)?;
+    let index = repo.index().map_err(|e| format!("Failed to open index: {}", e.message()))?;
+    let conflicts = index.conflicts().map_err(|e| e.message().to_string())?;
+
+    for conflict in conflicts {
+        let conflict = conflict.map_err(|e| e.message().to_string())?;
+        let matches = [&conflict.ancestor, &conflict.our, &conflict.their]
+            .into_iter()
+            .flatten()
+            .any(|entry| entry.path == repo_path.as_bytes());
+        if !matches {
+            continue;
+        }
+        let read = |entry: &Option<git2::IndexEntry>| -> Result<Option<Value>, String> {
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+            let blob = repo.find_blob(entry.id).map_err(|e| e.message().to_string())?;
+            serde_json::from_slice(blob.content())
+                .map(Some)
+                .map_err(|e| format!("{} is not valid JSON in one of the merged versions: {}", path, e))
+        };
+        let base = read(&conflict.ancestor)?;
+        let (Some(ours), Some(theirs)) = (read(&conflict.our)?, read(&conflict.their)?) else {
+            return Err(format!("{} was deleted on one side; keep or delete the whole file", path));
+        };
+        return Ok(merge(base.as_ref(), &ours, &theirs));
+    }
+    Err(format!("{} has no merge conflict", path))
+}
+
+fn resolve(project_dir: &Path, path: &str, merged: &Value) -> Result<(), String> {
+    let project = ProjectRepo::require(project_dir)?;
+    let repo_path = project.to_repo(path)?;
+    let data = serde_json::to_string_pretty(merged).map_err(|e| e.to_string())?;
+    crate::project::write_atomic(&project_dir.join(path), data.as_bytes())?;
+
+    // Adding the path replaces its conflict stages with the resolved file.
+    let mut index = project
+        .repo
+        .index()
+        .map_err(|e| format!("Failed to open index: {}", e.message()))?;
+    index
+        .add_path(Path::new(&repo_path))
+        .map_err(|e| format!("Failed to stage {}: {}", path, e.message()))?;
+    index.write().map_err(|e| format!("Failed to write index: {}", e.message()))
+}
+
+// =============================================================================
+// Merge
+// =============================================================================
+
+fn merge(base: Option<&Value>, ours: &Value, theirs: &Value) -> SceneMerge {
+    let mut merger = Merger::default();
+    let mut merged = Map::new();
+
+    let (Some(o), Some(t)) = (ours.as_object(), theirs.as_object()) else {
+        let merged = merger.value("", base, Some(ours), Some(theirs)).unwrap_or(Value::Null);
+        return SceneMerge {
+            merged,
+            conflicts: merger.conflicts,
+        };
+    };
+    let empty = Map::new();
+    let b = base.and_then(Value::as_object).unwrap_or(&empty);
+
+    for key in union_keys(b, o, t) {
+        let path = format!("/{}", key);
+        let value = if key == "entities" {
+            merger.entities(b.get(key), o.get(key), t.get(key))
+        } else {
+            merger.value(&path, b.get(key), o.get(key), t.get(key))
+        };
+        if let Some(value) = value {
+            merged.insert(key.to_string(), value);
+        }
+    }
+    SceneMerge {
+        merged: Value::Object(merged),
+        conflicts: merger.conflicts,
+    }
+}
+
+#[derive(Default)]
+struct Merger {
+    conflicts: Vec<SceneConflict>,
+    /// Entity being merged, attached to the conflicts it produces.
+    entity: Option<(Value, Option<String>)>,
+}
+
+impl Merger {
+    /// Generic three-way merge: objects merge key by key, anything else
+    /// changed on both sides is a conflict resolved to "ours".
+    fn value(&mut self, path: &str, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+        if ours == theirs || base == theirs {
+            return ours.cloned();
+        }
+        if base == ours {
+            return theirs.cloned();
+        }
+        if let (Some(Value::Object(o)), Some(Value::Object(t))) = (ours, theirs) {
+            let empty = Map::new();
+            let b = match base {
+                Some(Value::Object(b)) => b,
+                _ => &empty,
+            };
+            let mut merged = Map::new();
+            for key in union_keys(b, o, t) {
+                let child = format!("{}/{}", path, key);
+                if let Some(value) = self.value(&child, b.get(key), o.get(key), t.get(key)) {
+                    merged.insert(key.to_string(), value);
+                }
+            }
+            return Some(Value::Object(merged));
+        }
+        self.conflict(path, base, ours, theirs);
+        ours.cloned()
+    }
+
+    fn conflict(&mut self, path: &str, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) {
+        let kind = match (base, ours, theirs) {
+            (None, _, _) => ConflictKind::BothAdded,
+            (Some(_), None, _) => ConflictKind::DeletedByUs,
+            (Some(_), _, None) => ConflictKind::DeletedByThem,
+            _ => ConflictKind::BothModified,
+        };
+        let (entity, entity_name) = self.entity.clone().unzip();
+        self.conflicts.push(SceneConflict {
+            path: path.to_string(),
+            entity,
+            entity_name: entity_name.flatten(),
+            kind,
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        });
+    }
+
+    /// Entities matched by `id`, in our order followed by entities only they
+    /// added.
+    fn entities(&mut self, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+        let none = Value::Array(Vec::new());
+        let (Some(b), Some(o), Some(t)) = (
+            keyed(base.unwrap_or(&none), "id"),
+            ours.and_then(|o| keyed(o, "id")),
+            theirs.and_then(|t| keyed(t, "id")),
+        ) else {
+            return self.value("/entities", base, ours, theirs);
+        };
+
+        let mut merged = Vec::new();
+        for id in ordered_keys(&o, &t) {
+            let (be, oe, te) = (find(&b, &id), find(&o, &id), find(&t, &id));
+            let entity = oe.or(te).and_then(|e| e.get("id")).cloned().unwrap_or(Value::Null);
+            let name = oe.or(te).and_then(|e| e.get("name")).and_then(Value::as_str).map(str::to_string);
+            self.entity = Some((entity, name));
+            if let Some(value) = self.entity(&format!("/entities/{}", id), be, oe, te) {
+                merged.push(value);
+            }
+        }
+        self.entity = None;
+        Some(Value::Array(fix_hierarchy(merged)))
+    }
+
+    fn entity(&mut self, path: &str, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+        let (Some(Value::Object(o)), Some(Value::Object(t))) = (ours, theirs) else {
+            return self.value(path, base, ours, theirs);
+        };
+        if ours == theirs || base == theirs {
+            return ours.cloned();
+        }
+        if base == ours {
+            return theirs.cloned();
+        }
+        let empty = Map::new();
+        let b = match base {
+            Some(Value::Object(b)) => b,
+            _ => &empty,
+        };
+        let mut merged = Map::new();
+        for key in union_keys(b, o, t) {
+            let child = format!("{}/{}", path, key);
+            let value = match key {
+                "components" => self.components(&child, b.get(key), o.get(key), t.get(key)),
+                "children" => self.children(&child, b.get(key), o.get(key), t.get(key)),
+                _ => self.value(&child, b.get(key), o.get(key), t.get(key)),
+            };
+            if let Some(value) = value {
+                merged.insert(key.to_string(), value);
+            }
+        }
+        Some(Value::Object(merged))
+    }
+
+    /// Components matched by `type`, merged field by field.
+    fn components(&mut self, path: &str, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+        let none = Value::Array(Vec::new());
+        let (Some(b), Some(o), Some(t)) = (
+            keyed(base.unwrap_or(&none), "type"),
+            ours.and_then(|o| keyed(o, "type")),
+            theirs.and_then(|t| keyed(t, "type")),
+        ) else {
+            return self.value(path, base, ours, theirs);
+        };
+        let merged = ordered_keys(&o, &t)
+            .into_iter()
+            .filter_map(|ty| {
+                let child = format!("{}/{}", path, ty);
+                self.value(&child, find(&b, &ty), find(&o, &ty), find(&t, &ty))
+            })
+            .collect();
+        Some(Value::Array(merged))
+    }
+
+    /// Child id lists merge as sets: additions and removals from both sides
+    /// apply, keeping our order.
+    fn children(&mut self, path: &str, base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Option<Value> {
+        let (Some(Value::Array(o)), Some(Value::Array(t))) = (ours, theirs) else {
+            return self.value(path, base, ours, theirs);
+        };
+        let b = match base {
+            Some(Value::Array(b)) => b.as_slice(),
+            _ => &[],
+        };
+        let mut merged: Vec<Value> = o.iter().filter(|c| t.contains(c) || !b.contains(c)).cloned().collect();
+        for child in t {
+            if !b.contains(child) && !merged.contains(child) {
+                merged.push(child.clone());
+            }
+        }
+        Some(Value::Array(merged))
+    }
+}
+
+/// Drops references to entities that no longer exist after the merge.
+fn fix_hierarchy(mut entities: Vec<Value>) -> Vec<Value> {
+    let ids: HashSet<String> = entities.iter().filter_map(|e| e.get("id")).map(Value::to_string).collect();
+    for entity in &mut entities {
+        if let Some(Value::Array(children)) = entity.get_mut("children") {
+            children.retain(|c| ids.contains(&c.to_string()));
+        }
+        if let Some(parent) = entity.get_mut("parent") {
+            if !parent.is_null() && !ids.contains(&parent.to_string()) {
+                *parent = Value::Null;
+            }
+        }
+    }
+    entities
+}
+
+/// `(key, element)` pairs of an array whose elements all have a distinct
+/// `field`; `None` when that does not hold.
+fn keyed<'a>(value: &'a Value, field: &str) -> Option<Vec<(String, &'a Value)>> {
+    let items = value.as_array()?;
+    let mut seen = HashSet::new();
+    let mut keyed = Vec::with_capacity(items.len());
+    for item in items {
+        let key = match item.get(field)? {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if !seen.insert(key.clone()) {
+            return None;
+        }
+        keyed.push((key, item));
+    }
+    Some(keyed)
+}
+
+fn find<'a>(items: &[(String, &'a Value)], key: &str) -> Option<&'a Value> {
+    items.iter().find(|(k, _)| k == key).map(|(_, v)| *v)
+}
+
+/// Our keys in order, then keys only they have.
+fn ordered_keys(ours: &[(String, &Value)], theirs: &[(String, &Value)]) -> Vec<String> {
+    let mut keys: Vec<String> = ours.iter().map(|(k, _)| k.clone()).collect();
+    for (key, _) in theirs {
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+fn union_keys<'a>(base: &'a Map<String, Value>, ours: &'a Map<String, Value>, theirs: &'a Map<String, Value>) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = Vec::new();
+    for key in ours.keys().chain(theirs.keys()).chain(base.keys()) {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+    keys
+}
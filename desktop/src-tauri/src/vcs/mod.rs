@@ -0,0 +1,316 @@
+//! Version control integration for the Content Browser
+//!
+//! Backed by libgit2 through `git2`, so no git installation is needed. The
+//! project folder may be anywhere inside a repository; every path crossing
+//! the command boundary is relative to the project folder with forward
+//! slashes, and files outside it are left out. Committing and history live
+//! in `history`, Git LFS setup in `lfs` and the scene merge in `merge`.
+
+pub mod history;
+pub mod lfs;
+pub mod merge;
+
+use git2::{BranchType, ErrorCode, Repository, Status, StatusOptions, Tree};
+use serde::Serialize;
+use std::path::Path;
+
+/// Largest file `get_file_diff` returns as text.
+const MAX_DIFF_BYTES: usize = 8 * 1024 * 1024;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileState {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStatus {
+    pub path: String,
+    pub state: FileState,
+    /// The change is in the index, i.e. it would be part of the next commit.
+    pub staged: bool,
+    /// Previous path of a renamed file.
+    pub old_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VcsStatus {
+    /// False when the project is not inside a git repository; all other
+    /// fields are empty then.
+    pub is_repo: bool,
+    /// Current branch, `None` when HEAD is detached.
+    pub branch: Option<String>,
+    /// Abbreviated commit id of HEAD, `None` before the first commit.
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    /// Commits on the branch not on its upstream.
+    pub ahead: usize,
+    /// Commits on the upstream not on the branch.
+    pub behind: usize,
+    pub files: Vec<FileStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Content at HEAD; `None` when the file is new.
+    pub old_text: Option<String>,
+    /// Content in the working tree; `None` when the file was deleted.
+    pub new_text: Option<String>,
+    /// Set when either side is binary or too large; both texts are `None`.
+    pub binary: bool,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Branch, ahead/behind counts and per-file status of the project.
+#[tauri::command]
+pub async fn get_vcs_status(project_dir: String) -> Result<VcsStatus, String> {
+    tokio::task::spawn_blocking(move || status(Path::new(&project_dir)))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+/// HEAD and working-tree versions of a file, for the scene diff view.
+#[tauri::command]
+pub async fn get_file_diff(project_dir: String, path: String) -> Result<FileDiff, String> {
+    tokio::task::spawn_blocking(move || diff(Path::new(&project_dir), &path))
+        .await
+        .map_err(|e| format!("Git task failed: {}", e))?
+}
+
+// =============================================================================
+// Repository
+// =============================================================================
+
+/// A repository opened for a project folder.
+pub(crate) struct ProjectRepo {
+    pub repo: Repository,
+    /// Project folder relative to the work tree, `""` or ending in `/`.
+    pub prefix: String,
+}
+
+impl ProjectRepo {
+    /// `Ok(None)` when the folder is not inside a git work tree.
+    pub fn open(project_dir: &Path) -> Result<Option<Self>, String> {
+        let repo = match Repository::discover(project_dir) {
+            Ok(repo) => repo,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to open repository: {}", e.message())),
+        };
+        let Some(workdir) = repo.workdir() else {
+            return Ok(None);
+        };
+        let workdir = workdir.canonicalize().map_err(|e| e.to_string())?;
+        let project = project_dir.canonicalize().map_err(|e| e.to_string())?;
+        let mut prefix = crate::search::relative(&workdir, &project);
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        Ok(Some(Self { repo, prefix }))
+    }
+
+    /// Like `open`, but a missing repository is an error.
+    pub fn require(project_dir: &Path) -> Result<Self, String> {
+        Self::open(project_dir)?.ok_or_else(|| "The project is not in a git repository".to_string())
+    }
+
+    /// Work-tree path of a project path.
+    pub fn to_repo(&self, path: &str) -> Result<String, String> {
+        let path = path.replace('\\', "/");
+        if path.starts_with('/') || path.split('/').any(|part| part == "..") {
+            return Err(format!("Path is outside the project: {}", path));
+        }
+        Ok(format!("{}{}", self.prefix, path.trim_start_matches("./")))
+    }
+
+    /// Project path of a work-tree path, `None` outside the project.
+    pub fn to_project(&self, path: &str) -> Option<String> {
+        path.strip_prefix(self.prefix.as_str()).map(str::to_string)
+    }
+
+    /// Tree of the HEAD commit, `None` before the first commit.
+    pub fn head_tree(&self) -> Result<Option<Tree<'_>>, String> {
+        match self.repo.head() {
+            Ok(head) => head
+                .peel_to_tree()
+                .map(Some)
+                .map_err(|e| format!("Failed to read HEAD: {}", e.message())),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => Ok(None),
+            Err(e) => Err(format!("Failed to read HEAD: {}", e.message())),
+        }
+    }
+}
+
+// =============================================================================
+// Status
+// =============================================================================
+
+fn status(project_dir: &Path) -> Result<VcsStatus, String> {
+    let Some(project) = ProjectRepo::open(project_dir)? else {
+        return Ok(VcsStatus::default());
+    };
+    let repo = &project.repo;
+    let mut result = VcsStatus {
+        is_repo: true,
+        ..VcsStatus::default()
+    };
+
+    match repo.head() {
+        Ok(head) => {
+            if head.is_branch() {
+                result.branch = head.shorthand().map(str::to_string);
+            }
+            if let Some(oid) = head.target() {
+                result.head = Some(oid.to_string()[..7].to_string());
+            }
+        }
+        // A fresh repository: HEAD names a branch that has no commits yet.
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            result.branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(|t| t.trim_start_matches("refs/heads/").to_string()));
+        }
+        Err(e) => return Err(format!("Failed to read HEAD: {}", e.message())),
+    }
+
+    if let Some(name) = &result.branch {
+        if let Ok(upstream) = repo.find_branch(name, BranchType::Local).and_then(|b| b.upstream()) {
+            result.upstream = upstream.name().ok().flatten().map(str::to_string);
+            let local = repo.head().ok().and_then(|h| h.target());
+            if let (Some(local), Some(remote)) = (local, upstream.get().target()) {
+                if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, remote) {
+                    result.ahead = ahead;
+                    result.behind = behind;
+                }
+            }
+        }
+    }
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+    if !project.prefix.is_empty() {
+        options.pathspec(&project.prefix);
+    }
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to read status: {}", e.message()))?;
+
+    for entry in statuses.iter() {
+        let Some((state, staged)) = classify(entry.status()) else {
+            continue;
+        };
+        let (old_path, new_path) = match state {
+            FileState::Renamed => {
+                let delta = entry.head_to_index().or_else(|| entry.index_to_workdir());
+                let old = delta.as_ref().and_then(|d| d.old_file().path()).map(|p| p.to_string_lossy().to_string());
+                let new = delta.as_ref().and_then(|d| d.new_file().path()).map(|p| p.to_string_lossy().to_string());
+                (old, new)
+            }
+            _ => (None, entry.path().map(str::to_string)),
+        };
+        let Some(path) = new_path.as_deref().and_then(|p| project.to_project(p)) else {
+            continue;
+        };
+        result.files.push(FileStatus {
+            path,
+            state,
+            staged,
+            old_path: old_path.and_then(|p| project.to_project(&p)),
+        });
+    }
+    result.files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(result)
+}
+
+/// Badge state of a status entry; the index side wins when a file is both
+/// staged and modified again.
+fn classify(status: Status) -> Option<(FileState, bool)> {
+    if status.is_conflicted() {
+        return Some((FileState::Conflicted, false));
+    }
+    let staged = status.intersects(
+        Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE,
+    );
+    let state = if status.contains(Status::INDEX_NEW) {
+        FileState::Added
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        FileState::Renamed
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        FileState::Deleted
+    } else if status.intersects(
+        Status::INDEX_MODIFIED | Status::INDEX_TYPECHANGE | Status::WT_MODIFIED | Status::WT_TYPECHANGE,
+    ) {
+        FileState::Modified
+    } else if status.contains(Status::WT_NEW) {
+        FileState::Untracked
+    } else {
+        return None;
+    };
+    Some((state, staged))
+}
+
+// =============================================================================
+// Diff
+// =============================================================================
+
+fn diff(project_dir: &Path, path: &str) -> Result<FileDiff, String> {
+    let project = ProjectRepo::require(project_dir)?;
+    let repo_path = project.to_repo(path)?;
+
+    let old = match project.head_tree()? {
+        Some(tree) => match tree.get_path(Path::new(&repo_path)) {
+            Ok(entry) => {
+                let blob = entry
+                    .to_object(&project.repo)
+                    .and_then(|o| o.peel_to_blob())
+                    .map_err(|e| format!("Failed to read {} at HEAD: {}", path, e.message()))?;
+                Some(blob.content().to_vec())
+            }
+            Err(e) if e.code() == ErrorCode::NotFound => None,
+            Err(e) => return Err(format!("Failed to read {} at HEAD: {}", path, e.message())),
+        },
+        None => None,
+    };
+    let file = project_dir.join(path);
+    let new = if file.is_file() {
+        Some(std::fs::read(&file).map_err(|e| format!("Failed to read {}: {}", path, e))?)
+    } else {
+        None
+    };
+
+    let binary = [&old, &new].into_iter().flatten().any(|data| !is_text(data));
+    let text = |data: Option<Vec<u8>>| {
+        if binary {
+            None
+        } else {
+            data.map(|d| String::from_utf8_lossy(&d).into_owned())
+        }
+    };
+    Ok(FileDiff {
+        path: path.to_string(),
+        old_text: text(old),
+        new_text: text(new),
+        binary,
+    })
+}
+
+fn is_text(data: &[u8]) -> bool {
+    data.len() <= MAX_DIFF_BYTES && !data[..data.len().min(8000)].contains(&0)
+}
@@ -0,0 +1,252 @@
+//! WeChat devtools integration — preview QR codes and uploads via miniprogram-ci
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use crate::notify;
+use crate::process::env::{self, CommandEnv};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeChatCiOptions {
+    /// Exported WeChat mini-game directory (contains project.config.json).
+    pub project_path: String,
+    pub app_id: String,
+    /// Code upload key downloaded from the WeChat admin console.
+    pub private_key_path: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_robot")]
+    pub robot: u8,
+}
+
+fn default_robot() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeChatPreviewResult {
+    pub qrcode_path: String,
+    /// Base64 encoded JPEG for display in the editor.
+    pub qrcode_base64: String,
+}
+
+#[derive(Clone, Serialize)]
+struct WeChatCiProgress {
+    stage: String,
+    message: String,
+    progress: f32,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+#[tauri::command]
+pub async fn wechat_preview(app: AppHandle, options: WeChatCiOptions) -> Result<WeChatPreviewResult, String> {
+    validate(&options)?;
+
+    let qrcode_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("wechat-ci");
+    std::fs::create_dir_all(&qrcode_dir).map_err(|e| e.to_string())?;
+    let qrcode_path = qrcode_dir.join("preview-qrcode.jpg");
+    let _ = std::fs::remove_file(&qrcode_path);
+
+    emit_progress(&app, "preview", "Uploading preview build...", 0.1);
+    let mut args = common_args("preview", &options);
+    args.extend([
+        "--qrcode-format".to_string(),
+        "image".to_string(),
+        "--qrcode-output-dest".to_string(),
+        qrcode_path.to_string_lossy().to_string(),
+    ]);
+    run_ci(&app, &args, Path::new(&options.project_path)).await?;
+
+    let data = std::fs::read(&qrcode_path)
+        .map_err(|e| format!("Preview finished but no QR code was produced: {}", e))?;
+    emit_progress(&app, "complete", "Preview QR code ready", 1.0);
+
+    Ok(WeChatPreviewResult {
+        qrcode_path: qrcode_path.to_string_lossy().to_string(),
+        qrcode_base64: BASE64.encode(data),
+    })
+}
+
+#[tauri::command]
+pub async fn wechat_upload(app: AppHandle, options: WeChatCiOptions) -> Result<(), String> {
+    let result = upload(&app, &options).await;
+    notify::upload_finished(&app, "WeChat upload", &result);
+    result
+}
+
+async fn upload(app: &AppHandle, options: &WeChatCiOptions) -> Result<(), String> {
+    validate(options)?;
+    if options.version.trim().is_empty() {
+        return Err("Upload requires a version".to_string());
+    }
+
+    emit_progress(app, "upload", &format!("Uploading version {}...", options.version), 0.1);
+    let args = common_args("upload", options);
+    run_ci(app, &args, Path::new(&options.project_path)).await?;
+    emit_progress(app, "complete", "Upload complete", 1.0);
+    Ok(())
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn validate(options: &WeChatCiOptions) -> Result<(), String> {
+    if options.app_id.is_empty() || options.app_id == "touristappid" {
+        return Err("A real WeChat AppID is required for preview and upload".to_string());
+    }
+    if !Path::new(&options.project_path).join("project.config.json").exists() {
+        return Err(format!(
+            "Not an exported WeChat project: {}",
+            options.project_path
+        ));
+    }
+    if !Path::new(&options.private_key_path).is_file() {
+        return Err(format!(
+            "Upload key not found: {}",
+            options.private_key_path
+        ));
+    }
+    Ok(())
+}
+
+fn common_args(action: &str, options: &WeChatCiOptions) -> Vec<String> {
+    let version = if options.version.is_empty() { "0.0.1" } else { &options.version };
+    let mut args = vec![
+        action.to_string(),
+        "--pp".to_string(),
+        options.project_path.clone(),
+        "--pkp".to_string(),
+        options.private_key_path.clone(),
+        "--appid".to_string(),
+        options.app_id.clone(),
+        "--uv".to_string(),
+        version.to_string(),
+        "-r".to_string(),
+        options.robot.to_string(),
+    ];
+    if !options.description.is_empty() {
+        args.push("--ud".to_string());
+        args.push(options.description.clone());
+    }
+    args
+}
+
+/// Locates miniprogram-ci, falling back to `npx` when it isn't installed globally.
+fn resolve_ci(env: &CommandEnv) -> Result<(PathBuf, Vec<String>), String> {
+    if let Some(path) = env.find("miniprogram-ci") {
+        return Ok((path, Vec::new()));
+    }
+    env.find("npx")
+        .map(|path| (path, vec!["--yes".to_string(), "miniprogram-ci".to_string()]))
+        .ok_or_else(|| "miniprogram-ci not found. Install Node.js and run `npm i -g miniprogram-ci`.".to_string())
+}
+
+async fn run_ci(app: &AppHandle, args: &[String], cwd: &Path) -> Result<(), String> {
+    // The PATH a terminal would have, plus the editor's managed Node.
+    let env = tokio::task::spawn_blocking(|| CommandEnv::new(HashMap::new(), None, env::login_path_default()))
+        .await
+        .map_err(|e| format!("Environment task failed: {}", e))?;
+    let (program, mut full_args) = resolve_ci(&env)?;
+    full_args.extend_from_slice(args);
+
+    let mut command = Command::new(&program);
+    env.apply(&mut command);
+    let mut child = command
+        .args(&full_args)
+        .current_dir(cwd)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn miniprogram-ci: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let transcript = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let out_handle = tokio::spawn(forward_lines(app.clone(), stdout, "stdout", transcript.clone()));
+    let err_handle = tokio::spawn(forward_lines(app.clone(), stderr, "stderr", transcript.clone()));
+    let _ = tokio::join!(out_handle, err_handle);
+
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    if status.success() {
+        return Ok(());
+    }
+
+    let lines = transcript.lock().map(|l| l.clone()).unwrap_or_default();
+    Err(describe_failure(&lines, status.code().unwrap_or(-1)))
+}
+
+async fn forward_lines<R: tokio::io::AsyncRead + Unpin>(
+    app: AppHandle,
+    reader: R,
+    stream: &'static str,
+    transcript: Arc<Mutex<Vec<String>>>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Ok(mut t) = transcript.lock() {
+            t.push(line.clone());
+        }
+        let _ = app.emit(
+            "wechat-ci-output",
+            super::CommandOutput {
+                stream: stream.to_string(),
+                data: line,
+            },
+        );
+    }
+}
+
+/// Turns common miniprogram-ci failures into actionable messages.
+fn describe_failure(lines: &[String], code: i32) -> String {
+    let text = lines.join("\n");
+    let lower = text.to_lowercase();
+    let hint = if lower.contains("invalid ip") || lower.contains("-10008") {
+        Some("This machine's IP is not whitelisted for the upload key. Add it in the WeChat admin console (Development > Development Settings).")
+    } else if lower.contains("private key") || lower.contains("privatekey") || lower.contains("-10007") {
+        Some("The upload key is invalid or does not belong to this AppID.")
+    } else if lower.contains("appid") && (lower.contains("invalid") || lower.contains("40013")) {
+        Some("The AppID is invalid.")
+    } else if lower.contains("80051") || lower.contains("exceed") {
+        Some("The package exceeds WeChat size limits.")
+    } else {
+        None
+    };
+
+    let last = lines.iter().rev().find(|l| !l.trim().is_empty()).cloned().unwrap_or_default();
+    match hint {
+        Some(hint) => format!("{} ({})", hint, last.trim()),
+        None => format!("miniprogram-ci failed (exit code: {}): {}", code, last.trim()),
+    }
+}
+
+fn emit_progress(app: &AppHandle, stage: &str, message: &str, progress: f32) {
+    let _ = app.emit(
+        "wechat-ci-progress",
+        WeChatCiProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            progress,
+        },
+    );
+}
@@ -0,0 +1,398 @@
+//! Editor windows — detached panels and the play window
+//!
+//! Panels can leave the dock for a window of their own, and the game can
+//! run in a dedicated play window, so the Scene view stays on one monitor
+//! while the Inspector or Console sits on another:
+//!
+//! - `open_panel_window` opens `panel-window.html` for a panel in a window
+//!   labelled `panel-<id>`; `open_play_window` opens the preview in the
+//!   `panel-game` window. Opening a panel that is already detached focuses
+//!   its window.
+//! - windows announce themselves on `editor:panel-opened` and
+//!   `editor:panel-closed`, the channels the dock and the main window
+//!   bridge already listen on; editor state follows over the bridge.
+//! - the position and size of every window are kept in the editor settings
+//!   under `windows`, keyed by panel id. Panels still detached when the
+//!   editor closes are reopened by `restore_panel_windows`.
+//!
+//! The main window is saved the same way when it closes, under
+//! `mainWindow`, once per monitor setup: docking a laptop brings back the
+//! layout used with the external display. It starts hidden and is shown
+//! by `restore_main_window` once it is back where it was.
+
+use crate::editor_settings;
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+
+pub const MAIN_LABEL: &str = "main";
+
+const LABEL_PREFIX: &str = "panel-";
+const PLAY_PANEL: &str = "game";
+const CHANNEL_PANEL_OPENED: &str = "editor:panel-opened";
+const CHANNEL_PANEL_CLOSED: &str = "editor:panel-closed";
+/// Size of the main window in `tauri.conf.json`.
+const DEFAULT_MAIN_SIZE: (f64, f64) = (1400.0, 900.0);
+/// How far below the top edge a window is grabbed to be moved.
+const TITLE_BAR_GRAB: i32 = 16;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Where a window was last seen, in logical pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayout {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub maximized: bool,
+    /// Still detached when the editor closed.
+    #[serde(default)]
+    pub open: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorWindow {
+    pub label: String,
+    pub panel_id: String,
+    pub title: String,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PanelMessage<'a> {
+    panel_id: &'a str,
+    window_label: &'a str,
+}
+
+/// Default size and minimum size of a panel's window.
+struct PanelSize {
+    size: (f64, f64),
+    min: (f64, f64),
+}
+
+fn panel_size(panel_id: &str) -> PanelSize {
+    match panel_id {
+        PLAY_PANEL => PanelSize { size: (800.0, 600.0), min: (400.0, 300.0) },
+        "profiler" => PanelSize { size: (780.0, 540.0), min: (500.0, 360.0) },
+        _ => PanelSize { size: (400.0, 600.0), min: (300.0, 200.0) },
+    }
+}
+
+// =============================================================================
+// Labels
+// =============================================================================
+
+fn label_of(panel_id: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, panel_id)
+}
+
+/// The panel shown by the window labelled `label`, if it is a panel window.
+pub fn panel_of(label: &str) -> Option<&str> {
+    label.strip_prefix(LABEL_PREFIX)
+}
+
+fn validate_panel_id(panel_id: &str) -> Result<(), String> {
+    let valid = !panel_id.is_empty() && panel_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid panel id '{}'", panel_id))
+    }
+}
+
+// =============================================================================
+// Windows
+// =============================================================================
+
+/// Opens `url` in the window of `panel_id` at its saved layout, or focuses
+/// the window when it is already open.
+fn open_window(app: &AppHandle, panel_id: &str, title: &str, url: WebviewUrl) -> Result<String, String> {
+    let label = label_of(panel_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(label);
+    }
+
+    let PanelSize { size, min } = panel_size(panel_id);
+    let layout = editor_settings::load(app)?.windows.get(panel_id).cloned();
+    let mut builder = WebviewWindowBuilder::new(app, &label, url)
+        .title(title)
+        .min_inner_size(min.0, min.1)
+        .resizable(true)
+        .decorations(true)
+        .disable_drag_drop_handler();
+    builder = match &layout {
+        Some(layout) => builder
+            .inner_size(layout.width.max(min.0), layout.height.max(min.1))
+            .position(layout.x, layout.y)
+            .maximized(layout.maximized),
+        None => builder.inner_size(size.0, size.1).center(),
+    };
+    let window = builder.build().map_err(|e| format!("Failed to open window for {}: {}", panel_id, e))?;
+    if layout.is_some() && !is_on_screen(&window) {
+        let _ = window.center();
+    }
+
+    let _ = app.emit(CHANNEL_PANEL_OPENED, PanelMessage { panel_id, window_label: &label });
+    Ok(label)
+}
+
+/// False when the title bar of the window is on no connected display, as
+/// when it was saved on a monitor that is gone.
+fn is_on_screen(window: &WebviewWindow) -> bool {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        return true;
+    };
+    let Ok(monitors) = window.available_monitors() else {
+        return true;
+    };
+    let grab_x = position.x + (size.width / 2) as i32;
+    let grab_y = position.y + TITLE_BAR_GRAB;
+    monitors.iter().any(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        (origin.x..origin.x + size.width as i32).contains(&grab_x)
+            && (origin.y..origin.y + size.height as i32).contains(&grab_y)
+    })
+}
+
+/// The connected displays, e.g. `0,0 2560x1440;2560,0 1920x1080`, so that
+/// each monitor setup keeps a main window layout of its own.
+fn monitor_setup(app: &AppHandle) -> String {
+    let mut monitors: Vec<String> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let (origin, size) = (monitor.position(), monitor.size());
+            format!("{},{} {}x{}", origin.x, origin.y, size.width, size.height)
+        })
+        .collect();
+    monitors.sort();
+    monitors.join(";")
+}
+
+/// A maximized window keeps the size it is restored to.
+fn keep_restored_size(saved: Option<&WindowLayout>, layout: WindowLayout) -> WindowLayout {
+    match saved {
+        Some(saved) if layout.maximized => WindowLayout { maximized: true, open: layout.open, ..saved.clone() },
+        _ => layout,
+    }
+}
+
+fn layout_of(window: &WebviewWindow, open: bool) -> Option<WindowLayout> {
+    let scale_factor = window.scale_factor().ok()?;
+    let position: LogicalPosition<f64> = window.outer_position().ok()?.to_logical(scale_factor);
+    let size: LogicalSize<f64> = window.inner_size().ok()?.to_logical(scale_factor);
+    Some(WindowLayout {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+        open,
+        title: window.title().unwrap_or_default(),
+    })
+}
+
+/// Saves the layouts of panel windows, keyed by panel id.
+fn save_layouts(app: &AppHandle, layouts: Vec<(String, WindowLayout)>) {
+    if layouts.is_empty() {
+        return;
+    }
+    let result = editor_settings::modify(app, None, |settings| {
+        for (panel_id, layout) in layouts {
+            let layout = keep_restored_size(settings.windows.get(&panel_id), layout);
+            settings.windows.insert(panel_id, layout);
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        eprintln!("[windows] Failed to save window layout: {}", e);
+    }
+}
+
+/// Remembers where a window was closed. For a panel window, also tells
+/// the main window the panel is back.
+pub fn handle_close_requested(app: &AppHandle, label: &str) {
+    if label == MAIN_LABEL {
+        save_main_window(app);
+        return;
+    }
+    let Some(panel_id) = panel_of(label) else {
+        return;
+    };
+    if let Some(layout) = app.get_webview_window(label).and_then(|window| layout_of(&window, false)) {
+        save_layouts(app, vec![(panel_id.to_string(), layout)]);
+    }
+    let _ = app.emit(CHANNEL_PANEL_CLOSED, PanelMessage { panel_id, window_label: label });
+}
+
+fn save_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_LABEL) else {
+        return;
+    };
+    // Minimized windows report a position far off screen.
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let Some(mut layout) = layout_of(&window, true) else {
+        return;
+    };
+    layout.title.clear();
+    let setup = monitor_setup(app);
+    let result = editor_settings::modify(app, None, |settings| {
+        let layout = keep_restored_size(settings.main_window.get(&setup), layout);
+        settings.main_window.insert(setup, layout);
+        Ok(())
+    });
+    if let Err(e) = result {
+        eprintln!("[windows] Failed to save window layout: {}", e);
+    }
+}
+
+/// Moves the main window to where it was last closed with the current
+/// monitor setup, or with any other setup it still fits on, then shows it.
+/// The window stays centered when no saved layout is on a display.
+pub fn restore_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_LABEL) else {
+        return;
+    };
+    let layouts = editor_settings::load(app).map(|settings| settings.main_window).unwrap_or_default();
+    let setup = monitor_setup(app);
+    let candidates = layouts
+        .get(&setup)
+        .into_iter()
+        .chain(layouts.iter().filter(|(key, _)| **key != setup).map(|(_, layout)| layout));
+    for layout in candidates {
+        let _ = window.set_size(LogicalSize::new(layout.width, layout.height));
+        let _ = window.set_position(LogicalPosition::new(layout.x, layout.y));
+        if is_on_screen(&window) {
+            if layout.maximized {
+                let _ = window.maximize();
+            }
+            let _ = window.show();
+            return;
+        }
+    }
+    let _ = window.set_size(LogicalSize::new(DEFAULT_MAIN_SIZE.0, DEFAULT_MAIN_SIZE.1));
+    let _ = window.center();
+    let _ = window.show();
+}
+
+/// Saves every panel window as still open and destroys it. Called when
+/// the main window goes away.
+pub fn close_all(app: &AppHandle) {
+    let windows: Vec<(String, WebviewWindow)> = app
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, window)| Some((panel_of(&label)?.to_string(), window)))
+        .collect();
+    let layouts =
+        windows.iter().filter_map(|(panel_id, window)| Some((panel_id.clone(), layout_of(window, true)?))).collect();
+    save_layouts(app, layouts);
+    for (_, window) in windows {
+        let _ = window.destroy();
+    }
+}
+
+fn open_panel(app: &AppHandle, panel_id: &str, title: &str, project_path: Option<&str>) -> Result<String, String> {
+    validate_panel_id(panel_id)?;
+    if panel_id == PLAY_PANEL {
+        return Err("The game view opens with open_play_window".to_string());
+    }
+    let mut url = format!("panel-window.html?panel={}", urlencoding::encode(panel_id));
+    if let Some(path) = project_path.filter(|p| !p.is_empty()) {
+        url.push_str(&format!("&projectPath={}", urlencoding::encode(path)));
+    }
+    open_window(app, panel_id, title, WebviewUrl::App(url.into()))
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+// Commands that create windows are async: building a window from a sync
+// command deadlocks on Windows.
+
+/// Detaches `panel_id` into its own window and returns the window label.
+#[tauri::command]
+pub async fn open_panel_window(
+    app: AppHandle,
+    panel_id: String,
+    title: String,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    open_panel(&app, &panel_id, &title, project_path.as_deref())
+}
+
+/// Opens the running preview at `url` in the play window.
+#[tauri::command]
+pub async fn open_play_window(app: AppHandle, url: String) -> Result<String, String> {
+    let parsed = url::Url::parse(&url).map_err(|e| format!("Invalid preview URL {}: {}", url, e))?;
+    if !matches!(parsed.host_str(), Some("127.0.0.1" | "localhost")) {
+        return Err(format!("The play window only shows local previews, not {}", url));
+    }
+    open_window(&app, PLAY_PANEL, "Game Preview", WebviewUrl::External(parsed))
+}
+
+/// Closes the window of `panel_id`, which returns the panel to the dock.
+#[tauri::command]
+pub fn close_panel_window(app: AppHandle, panel_id: String) -> Result<(), String> {
+    match app.get_webview_window(&label_of(&panel_id)) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Closes every panel window, remembering them for `restore_panel_windows`.
+#[tauri::command]
+pub fn close_panel_windows(app: AppHandle) {
+    close_all(&app);
+}
+
+#[tauri::command]
+pub fn list_editor_windows(app: AppHandle) -> Vec<EditorWindow> {
+    let mut windows: Vec<EditorWindow> = app
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, window)| {
+            let panel_id = panel_of(&label)?.to_string();
+            Some(EditorWindow {
+                title: window.title().unwrap_or_default(),
+                focused: window.is_focused().unwrap_or(false),
+                label,
+                panel_id,
+            })
+        })
+        .collect();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+}
+
+/// Reopens the panels that were detached when the editor last closed and
+/// returns their ids. The play window is not restored; it needs a preview.
+#[tauri::command]
+pub async fn restore_panel_windows(app: AppHandle, project_path: Option<String>) -> Result<Vec<String>, String> {
+    let settings = editor_settings::load(&app)?;
+    let mut restored = Vec::new();
+    for (panel_id, layout) in settings.windows.iter().filter(|(id, layout)| layout.open && *id != PLAY_PANEL) {
+        let title = if layout.title.is_empty() { panel_id } else { &layout.title };
+        match open_panel(&app, panel_id, title, project_path.as_deref()) {
+            Ok(_) => restored.push(panel_id.clone()),
+            Err(e) => eprintln!("[windows] Failed to restore {}: {}", panel_id, e),
+        }
+    }
+    Ok(restored)
+}
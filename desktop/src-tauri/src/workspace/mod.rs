@@ -0,0 +1,209 @@
+//! Workspace: several projects open in one editor session
+//!
+//! Each open project gets its own asset database, built lazily from the
+//! `.meta` sidecars and dropped again by a file watcher on `assets/` when
+//! anything there changes, so copies between projects always resolve
+//! against current UUIDs. Preview and bridge servers are per project too;
+//! they live in `AppState` and are stopped when the project is closed.
+//! Copying assets between projects is in `transfer`.
+
+pub mod transfer;
+
+use crate::export::assets::AssetDatabase;
+use crate::project::{now_iso8601, PROJECT_FILE};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Default)]
+pub struct Workspace {
+    projects: Mutex<Vec<OpenProject>>,
+}
+
+struct OpenProject {
+    dir: PathBuf,
+    name: String,
+    opened: String,
+    assets: Arc<Mutex<Option<Arc<AssetDatabase>>>>,
+    /// Kept alive for as long as the project is open.
+    watcher: Option<RecommendedWatcher>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceProject {
+    pub path: String,
+    pub name: String,
+    pub opened: String,
+    /// Whether asset changes are being watched; without a watcher the asset
+    /// database is rebuilt on every use.
+    pub watching: bool,
+}
+
+impl OpenProject {
+    fn info(&self) -> WorkspaceProject {
+        WorkspaceProject {
+            path: self.dir.to_string_lossy().to_string(),
+            name: self.name.clone(),
+            opened: self.opened.clone(),
+            watching: self.watcher.is_some(),
+        }
+    }
+}
+
+impl Workspace {
+    /// The asset database of `project_dir`, scanning it if the project is
+    /// not open or its cached database went stale.
+    pub(crate) fn asset_database(&self, project_dir: &Path) -> Arc<AssetDatabase> {
+        let cache = self
+            .projects
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|p| p.dir == project_dir && p.watcher.is_some())
+            .map(|p| Arc::clone(&p.assets));
+        let Some(cache) = cache else {
+            return Arc::new(AssetDatabase::scan(project_dir));
+        };
+        let mut cached = cache.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(cached.get_or_insert_with(|| Arc::new(AssetDatabase::scan(project_dir))))
+    }
+
+    /// Drops the cached asset database after the backend itself wrote to
+    /// `project_dir`, without waiting for the watcher.
+    pub(crate) fn invalidate(&self, project_dir: &Path) {
+        let projects = self.projects.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(project) = projects.iter().find(|p| p.dir == project_dir) {
+            *project.assets.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Adds a project to the workspace; opening one that is already open just
+/// returns it.
+#[tauri::command]
+pub fn open_workspace_project(
+    app: AppHandle,
+    workspace: State<'_, Workspace>,
+    project_dir: String,
+) -> Result<WorkspaceProject, String> {
+    let dir = PathBuf::from(&project_dir);
+    let project_file = dir.join(PROJECT_FILE);
+    if !project_file.is_file() {
+        return Err(format!("Not a project folder: {}", project_dir));
+    }
+    let mut projects = workspace.projects.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(project) = projects.iter().find(|p| p.dir == dir) {
+        return Ok(project.info());
+    }
+
+    let name = std::fs::read_to_string(&project_file)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        .and_then(|config| config["name"].as_str().map(String::from))
+        .unwrap_or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+    let assets = Arc::new(Mutex::new(None));
+    let watcher = match watch_assets(&app, &dir, Arc::clone(&assets)) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            eprintln!("[Workspace] Not watching {}: {}", dir.display(), e);
+            None
+        }
+    };
+    let project = OpenProject {
+        dir,
+        name,
+        opened: now_iso8601(),
+        assets,
+        watcher,
+    };
+    let info = project.info();
+    projects.push(project);
+    Ok(info)
+}
+
+/// Removes a project from the workspace, stops its preview, bridge and
+/// language servers, kills the processes started for it and unloads its
+/// plugins.
+#[tauri::command]
+pub fn close_workspace_project(app: AppHandle, workspace: State<'_, Workspace>, project_dir: String) {
+    let dir = PathBuf::from(&project_dir);
+    workspace
+        .projects
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|p| p.dir != dir);
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state.stop_project(&dir);
+    }
+    if let Some(registry) = app.try_state::<crate::process::ProcessRegistry>() {
+        registry.kill_project(&dir);
+    }
+    if let Some(watchers) = app.try_state::<crate::bundler::watch::ScriptWatchers>() {
+        watchers.stop(&dir);
+    }
+    if let Some(plugins) = app.try_state::<crate::plugins::Plugins>() {
+        plugins.unload(&dir);
+    }
+    if let Some(servers) = app.try_state::<crate::language_server::LanguageServers>() {
+        servers.stop(&dir);
+    }
+    if let Some(journals) = app.try_state::<crate::scene::undo_journal::UndoJournals>() {
+        journals.close_project(&dir);
+    }
+}
+
+/// Open projects, in the order they were opened.
+#[tauri::command]
+pub fn list_workspace_projects(workspace: State<'_, Workspace>) -> Vec<WorkspaceProject> {
+    workspace
+        .projects
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(OpenProject::info)
+        .collect()
+}
+
+// =============================================================================
+// Watching
+// =============================================================================
+
+/// Drops the cached asset database on any change under `assets/`, emitting
+/// `workspace-assets-changed` once per invalidation rather than per event.
+fn watch_assets(
+    app: &AppHandle,
+    project_dir: &Path,
+    assets: Arc<Mutex<Option<Arc<AssetDatabase>>>>,
+) -> Result<RecommendedWatcher, String> {
+    let assets_dir = project_dir.join("assets");
+    let app = app.clone();
+    let project = project_dir.to_string_lossy().to_string();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        let was_cached = assets.lock().unwrap_or_else(|e| e.into_inner()).take().is_some();
+        if was_cached {
+            let _ = app.emit("workspace-assets-changed", serde_json::json!({ "project_dir": project }));
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(&assets_dir, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+    Ok(watcher)
+}
@@ -0,0 +1,337 @@
+//! Copying assets between workspace projects
+//!
+//! Copies get fresh UUIDs so the same UI kit can be copied into a project
+//! twice without colliding. Every copied JSON asset then has its UUID and
+//! path references pointed at the copies. Dependencies that the target
+//! already has at the same path with the same contents are reused instead
+//! of copied again, which keeps shared textures from piling up as
+//! `name_1.png`, `name_2.png`, ...
+
+use super::Workspace;
+use crate::export::assets::{
+    collect_json_refs, collect_references, is_json_asset, is_uuid, normalize_path, parent_dir, relative_path,
+    remap_paths, resolve_ref, text_asset_deps, walk_files, AssetDatabase,
+};
+use crate::files::folders::new_uuid;
+use crate::files::import::{unique_destination, ImportFailure, ImportResult};
+use crate::project::PROJECT_FILE;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferredAsset {
+    /// Path in the source project.
+    pub source: String,
+    /// Path in the target project.
+    pub target: String,
+    /// UUID in the target project.
+    pub uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AssetTransfer {
+    pub copied: Vec<TransferredAsset>,
+    /// Dependencies the target already had with identical contents.
+    pub reused: Vec<TransferredAsset>,
+    /// `asset: reference` pairs left pointing at something the target
+    /// project does not have.
+    pub unresolved: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+// =============================================================================
+// Tauri commands
+// =============================================================================
+
+/// Copies `paths` (relative to `source_project`) into `folder` of
+/// `target_project`, together with everything they reference when
+/// `include_dependencies` is set.
+#[tauri::command]
+pub async fn copy_assets_between_projects(
+    app: AppHandle,
+    source_project: String,
+    target_project: String,
+    paths: Vec<String>,
+    folder: String,
+    include_dependencies: Option<bool>,
+) -> Result<AssetTransfer, String> {
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&source_project);
+        let target = Path::new(&target_project);
+        let workspace = app.state::<Workspace>();
+        let result = Transfer::new(&workspace, source, target, &folder)?.run(
+            &app,
+            &paths,
+            include_dependencies.unwrap_or(true),
+        );
+        workspace.invalidate(target);
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Copy task failed: {}", e))?
+}
+
+// =============================================================================
+// Transfer
+// =============================================================================
+
+struct Transfer<'a> {
+    source: &'a Path,
+    target: &'a Path,
+    folder: String,
+    source_db: Arc<AssetDatabase>,
+    target_db: Arc<AssetDatabase>,
+    /// Source path -> target path of everything copied or reused.
+    plan: BTreeMap<String, String>,
+    reused: HashSet<String>,
+}
+
+impl<'a> Transfer<'a> {
+    fn new(workspace: &Workspace, source: &'a Path, target: &'a Path, folder: &str) -> Result<Self, String> {
+        for dir in [source, target] {
+            if !dir.join(PROJECT_FILE).is_file() {
+                return Err(format!("Not a project folder: {}", dir.display()));
+            }
+        }
+        if source == target {
+            return Err("Source and target are the same project".to_string());
+        }
+        let folder = folder.replace('\\', "/").trim_matches('/').to_string();
+        if folder.split('/').any(|part| part == "..") {
+            return Err(format!("Target folder is outside the project: {}", folder));
+        }
+        if !target.join(&folder).is_dir() {
+            return Err(format!("Target folder does not exist: {}", folder));
+        }
+        Ok(Self {
+            source,
+            target,
+            folder,
+            source_db: workspace.asset_database(source),
+            target_db: workspace.asset_database(target),
+            plan: BTreeMap::new(),
+            reused: HashSet::new(),
+        })
+    }
+
+    fn run(mut self, app: &AppHandle, paths: &[String], include_dependencies: bool) -> AssetTransfer {
+        let mut result = AssetTransfer::default();
+        let mut imported = Vec::new();
+        for path in paths {
+            match self.plan_root(path) {
+                Ok(destination) => imported.push(destination),
+                Err(e) => result.failed.push(ImportFailure {
+                    path: path.clone(),
+                    error: e,
+                }),
+            }
+        }
+        if include_dependencies {
+            self.plan_dependencies(&mut result);
+        }
+
+        // Old UUID -> new UUID and old path -> new path, applied together.
+        let mut remap = HashMap::new();
+        let mut new_uuids = HashSet::new();
+        for (src, dst) in &self.plan {
+            let old_uuid = self.source_db.uuid_of(src).map(String::from);
+            let asset = if self.reused.contains(src) {
+                let uuid = self.target_db.uuid_of(dst).map(String::from);
+                result.reused.push(TransferredAsset {
+                    source: src.clone(),
+                    target: dst.clone(),
+                    uuid: uuid.clone(),
+                });
+                uuid
+            } else {
+                match self.copy(src, dst) {
+                    Ok(uuid) => {
+                        new_uuids.extend(uuid.clone());
+                        result.copied.push(TransferredAsset {
+                            source: src.clone(),
+                            target: dst.clone(),
+                            uuid: uuid.clone(),
+                        });
+                        uuid
+                    }
+                    Err(e) => {
+                        result.failed.push(ImportFailure {
+                            path: src.clone(),
+                            error: e,
+                        });
+                        continue;
+                    }
+                }
+            };
+            if let (Some(old), Some(new)) = (old_uuid, asset) {
+                remap.insert(old, new);
+            }
+            remap.insert(src.clone(), dst.clone());
+        }
+
+        for asset in &result.copied {
+            if is_json_asset(&asset.target) {
+                if let Err(e) = self.rewrite(&asset.target, &remap, &new_uuids, &mut result.unresolved) {
+                    result.failed.push(ImportFailure {
+                        path: asset.source.clone(),
+                        error: e,
+                    });
+                }
+            }
+        }
+
+        let _ = app.emit(
+            "assets-imported",
+            ImportResult {
+                project_dir: self.target.to_string_lossy().to_string(),
+                folder: self.folder.clone(),
+                imported,
+                files: result.copied.iter().map(|a| a.target.clone()).collect(),
+                failed: result.failed.clone(),
+            },
+        );
+        result
+    }
+
+    /// Plans a selected file or folder into the target folder, returning its
+    /// new top-level path.
+    fn plan_root(&mut self, path: &str) -> Result<String, String> {
+        let rel = normalize_path(&path.replace('\\', "/"))
+            .ok_or_else(|| format!("Not found in the source project: {}", path))?;
+        let full = self.source.join(&rel);
+        let name = full.file_name().ok_or_else(|| format!("Invalid path: {}", path))?;
+        if !full.exists() {
+            return Err(format!("Not found in the source project: {}", path));
+        }
+        let destination = relative_path(self.target, &unique_destination(&self.target.join(&self.folder).join(name)));
+        if full.is_dir() {
+            for file in walk_files(&full) {
+                let src = relative_path(self.source, &file);
+                if !src.ends_with(".meta") {
+                    let suffix = relative_path(&full, &file);
+                    self.plan.insert(src, format!("{}/{}", destination, suffix));
+                }
+            }
+        } else {
+            self.plan.insert(rel, destination.clone());
+        }
+        Ok(destination)
+    }
+
+    /// Adds everything the planned assets reference. Atlas and font pages
+    /// keep their position next to the descriptor; other dependencies keep
+    /// their project path, reusing the target's file when it is identical.
+    fn plan_dependencies(&mut self, result: &mut AssetTransfer) {
+        let planned: Vec<(String, String)> = self.plan.iter().map(|(s, d)| (s.clone(), d.clone())).collect();
+        for (src, dst) in &planned {
+            let base = parent_dir(src);
+            for page in text_asset_deps(&self.source.join(src), src) {
+                let Some(dep) = resolve_ref(self.source, &self.source_db, &base, &page) else {
+                    continue;
+                };
+                if self.plan.contains_key(&dep) || page.starts_with('/') {
+                    continue;
+                }
+                if let Some(destination) = normalize_path(&format!("{}/{}", parent_dir(dst), page)) {
+                    self.plan.insert(dep, destination);
+                }
+            }
+        }
+
+        let roots: Vec<String> = self.plan.keys().cloned().collect();
+        for dep in collect_references(self.source, &self.source_db, &roots) {
+            if self.plan.contains_key(&dep) {
+                continue;
+            }
+            if !dep.starts_with("assets/") {
+                result.unresolved.push(format!("{}: outside assets/", dep));
+                continue;
+            }
+            let existing = self.target.join(&dep);
+            let destination = if !existing.exists() {
+                dep.clone()
+            } else if same_contents(&self.source.join(&dep), &existing) {
+                self.reused.insert(dep.clone());
+                dep.clone()
+            } else {
+                relative_path(self.target, &unique_destination(&existing))
+            };
+            self.plan.insert(dep, destination);
+        }
+    }
+
+    /// Copies one asset and its sidecar, returning the copy's new UUID.
+    fn copy(&self, src: &str, dst: &str) -> Result<Option<String>, String> {
+        let destination = self.target.join(dst);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::copy(self.source.join(src), &destination).map_err(|e| e.to_string())?;
+
+        let meta = std::fs::read_to_string(self.source.join(format!("{}.meta", src)))
+            .ok()
+            .and_then(|s| serde_json::from_str::<Value>(&s).ok());
+        let Some(mut meta) = meta else {
+            // The editor creates a sidecar when it picks the file up.
+            return Ok(None);
+        };
+        let uuid = new_uuid(&destination.to_string_lossy());
+        meta["uuid"] = Value::String(uuid.clone());
+        let data = serde_json::to_vec_pretty(&meta).map_err(|e| e.to_string())?;
+        std::fs::write(self.target.join(format!("{}.meta", dst)), data).map_err(|e| e.to_string())?;
+        Ok(Some(uuid))
+    }
+
+    /// Points a copied JSON asset's references at the copies, recording
+    /// references the target cannot resolve.
+    fn rewrite(
+        &self,
+        dst: &str,
+        remap: &HashMap<String, String>,
+        new_uuids: &HashSet<String>,
+        unresolved: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let full = self.target.join(dst);
+        let text = std::fs::read_to_string(&full).map_err(|e| e.to_string())?;
+        let Ok(mut value) = serde_json::from_str::<Value>(&text) else {
+            return Ok(());
+        };
+        let original = value.clone();
+        remap_paths(&mut value, remap);
+
+        let mut refs = Vec::new();
+        collect_json_refs(&value, &mut refs);
+        let base = parent_dir(dst);
+        for reference in refs {
+            let resolved = if is_uuid(&reference) {
+                new_uuids.contains(&reference) || self.target_db.path_of(&reference).is_some()
+            } else {
+                resolve_ref(self.target, &self.target_db, &base, &reference).is_some()
+            };
+            if !resolved {
+                unresolved.push(format!("{}: {}", dst, reference));
+            }
+        }
+
+        if value != original {
+            let data = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+            std::fs::write(&full, data).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+fn same_contents(a: &Path, b: &Path) -> bool {
+    match (std::fs::read(a), std::fs::read(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
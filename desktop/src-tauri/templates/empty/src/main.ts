@@ -0,0 +1,11 @@
+import { addSystemToSchedule, defineSystem, Schedule } from 'esengine';
+
+const startupSystem = defineSystem(
+    [],
+    () => {
+        console.log('Hello, ESEngine!');
+    },
+    { name: 'StartupSystem' }
+);
+
+addSystemToSchedule(Schedule.Startup, startupSystem);
@@ -0,0 +1,6 @@
+// {{ClassName}} — created by {{author}} on {{date}}
+import { defineComponent } from 'esengine';
+
+export const {{ClassName}} = defineComponent('{{ClassName}}', {
+    value: 0,
+});
@@ -0,0 +1,16 @@
+// {{ClassName}} — created by {{author}} on {{date}}
+import {
+    addSystemToSchedule, defineSystem, Query, Mut, Res, Schedule, Time, Transform,
+} from 'esengine';
+
+export const {{camelName}} = defineSystem(
+    [Query(Mut(Transform)), Res(Time)],
+    (query, _time) => {
+        for (const [_entity, _transform] of query) {
+            // Runs every frame; _time.delta is the frame time in seconds.
+        }
+    },
+    { name: '{{ClassName}}' }
+);
+
+addSystemToSchedule(Schedule.Update, {{camelName}});
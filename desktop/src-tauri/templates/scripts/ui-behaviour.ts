@@ -0,0 +1,23 @@
+// {{ClassName}} — created by {{author}} on {{date}}
+import {
+    addSystemToSchedule, defineComponent, defineSystem, Query, Mut, Res, Schedule, UIEvents,
+} from 'esengine';
+import type { UIEventQueue } from 'esengine';
+
+export const {{ClassName}} = defineComponent('{{ClassName}}', {
+    clicks: 0,
+});
+
+export const {{camelName}}System = defineSystem(
+    [Query(Mut({{ClassName}})), Res(UIEvents)],
+    (query, events: UIEventQueue) => {
+        for (const [entity, behaviour] of query) {
+            if (events.hasEvent(entity, 'click')) {
+                behaviour.clicks++;
+            }
+        }
+    },
+    { name: '{{ClassName}}System' }
+);
+
+addSystemToSchedule(Schedule.Update, {{camelName}}System);
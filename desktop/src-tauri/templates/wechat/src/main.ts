@@ -0,0 +1,21 @@
+import {
+    addSystemToSchedule, defineComponent, defineSystem, Input, Mut, Query, Res, Schedule, Text,
+} from 'esengine';
+
+export const TapCounter = defineComponent('TapCounter', {
+    count: 0,
+});
+
+const tapSystem = defineSystem(
+    [Query(Mut(Text), Mut(TapCounter)), Res(Input)],
+    (labels, input) => {
+        if (!input.isMouseButtonPressed(0)) return;
+        for (const [_entity, text, counter] of labels) {
+            counter.count += 1;
+            text.content = `Taps: ${counter.count}`;
+        }
+    },
+    { name: 'TapSystem' }
+);
+
+addSystemToSchedule(Schedule.Update, tapSystem);
@@ -0,0 +1,40 @@
+/**
+ * @file    context-menu.ts
+ * @brief   Native context menus and suppression of the webview's own
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface NativeMenuItem {
+    id?: string;
+    label?: string;
+    separator?: boolean;
+    enabled?: boolean;
+    checked?: boolean;
+    accelerator?: string;
+    items?: NativeMenuItem[];
+}
+
+/**
+ * Shows a native context menu at the given window position, or at the
+ * cursor, and resolves to the chosen item id, or null when dismissed.
+ */
+export function showNativeContextMenu(items: NativeMenuItem[], x?: number, y?: number): Promise<string | null> {
+    return invoke<string | null>('show_context_menu', { items, x, y });
+}
+
+function isEditable(target: EventTarget | null): boolean {
+    return target instanceof HTMLInputElement
+        || target instanceof HTMLTextAreaElement
+        || (target instanceof HTMLElement && target.isContentEditable);
+}
+
+/**
+ * Hides the webview's "Reload / Save as / Inspect" menu everywhere the
+ * editor does not show its own. Text fields keep their cut/copy/paste menu.
+ */
+export function suppressDefaultContextMenu(): void {
+    window.addEventListener('contextmenu', (e) => {
+        if (!isEditable(e.target)) e.preventDefault();
+    });
+}
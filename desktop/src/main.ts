@@ -5,10 +5,13 @@
 
 import 'dockview-core/dist/styles/dockview.css';
 import '@esengine/editor/styles';
-import { createEditor, ProjectLauncher, setPlatformAdapter, setEditorContext, loadProjectConfig, showToast, dismissToast, showProgressToast, updateToast, getSettingsValue, type Editor } from '@esengine/editor';
+import { createEditor, ProjectLauncher, setPlatformAdapter, setEditorContext, setSettingsBackend, loadProjectConfig, showToast, dismissToast, showProgressToast, updateToast, getSettingsValue, getMenuItem, getEditorStore, getSceneService, saveEditorLocalSetting, showConfirmDialog, recordFeatureUsage, recordErrorCategory, type Editor } from '@esengine/editor';
 import { TauriPlatformAdapter } from './TauriPlatformAdapter';
-import { nativeFS, nativeShell } from './native-fs';
-import { invoke } from '@tauri-apps/api/core';
+import { nativeFS, nativeShell, physicsRuntime } from './native-fs';
+import { suppressDefaultContextMenu } from './context-menu';
+import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { getVersion } from '@tauri-apps/api/app';
 import { relaunch } from '@tauri-apps/plugin-process';
 import type { App, ESEngineModule } from 'esengine';
@@ -71,9 +74,13 @@ async function showLauncher(container: HTMLElement): Promise<void> {
     });
 }
 
-async function loadPhysicsFactory(editor: Editor): Promise<void> {
+async function loadPhysicsFactory(editor: Editor, backend?: string): Promise<void> {
     try {
-        const factory = await loadUmdModule('/wasm/physics.js', 'ESPhysicsModule');
+        // Served by the `esassets` protocol, next to its .wasm; editors built
+        // without the backend's runtime download it first.
+        const id = physicsRuntime(backend);
+        await invoke('download_runtime', { id });
+        const factory = await loadUmdModule(convertFileSrc(`${id}.js`, 'esassets'), 'ESPhysicsModule');
         editor.setPhysicsFactory(factory);
     } catch (e) {
         console.warn('Failed to load physics module:', e);
@@ -81,6 +88,7 @@ async function loadPhysicsFactory(editor: Editor): Promise<void> {
 }
 
 async function openEditor(container: HTMLElement, projectPath: string): Promise<void> {
+    currentProjectPath = projectPath;
     const editor = createEditor(container, { projectPath });
     console.log('ESEngine Editor opened project:', projectPath);
 
@@ -94,7 +102,13 @@ async function openEditor(container: HTMLElement, projectPath: string): Promise<
 
     const config = await loadProjectConfig(projectPath);
 
-    await loadPhysicsFactory(editor);
+    // Types committed in `types/` follow the project's engine, not this
+    // editor; refresh them when they were written by another version.
+    const projectDir = projectPath.replace(/[\\/][^\\/]+\.esproject$/, '');
+    invoke('sync_project_types', { projectDir, create: false })
+        .catch((e) => console.warn('Failed to sync project types:', e));
+
+    await loadPhysicsFactory(editor, config?.physicsBackend);
 }
 
 function loadUmdModule(url: string, globalName: string): Promise<any> {
@@ -113,6 +127,14 @@ function loadUmdModule(url: string, globalName: string): Promise<any> {
     });
 }
 
+interface UpdateInfo {
+    version: string;
+    currentVersion: string;
+    channel: 'stable' | 'beta' | 'nightly';
+    date: string | null;
+    body: string | null;
+}
+
 async function checkForUpdate(manual = false): Promise<void> {
     let tid: string | undefined;
     if (manual) {
@@ -120,8 +142,15 @@ async function checkForUpdate(manual = false): Promise<void> {
     }
 
     try {
+        const pending = await invoke<string | null>('get_pending_update');
+        if (pending) {
+            if (tid) dismissToast(tid);
+            showUpdateReady(pending);
+            return;
+        }
+
         const proxy = getSettingsValue<string>('network.proxy') || undefined;
-        const update = await invoke<{ version: string; body: string | null } | null>('check_update', { proxy });
+        const update = await invoke<UpdateInfo | null>('check_update', { proxy });
         if (!update) {
             if (tid) {
                 updateToast(tid, { type: 'success', title: 'You\'re up to date', message: 'No updates available.' });
@@ -132,26 +161,31 @@ async function checkForUpdate(manual = false): Promise<void> {
 
         if (tid) dismissToast(tid);
 
+        const channel = update.channel === 'stable' ? '' : ` (${update.channel})`;
         showToast({
             type: 'info',
             title: 'Update Available',
-            message: `New version ${update.version} is ready to install.`,
+            message: `Version ${update.version}${channel} is available.`,
             duration: 0,
-            actions: [{
-                label: 'Update',
-                primary: true,
-                onClick: async () => {
-                    const dlTid = showProgressToast('Downloading update...');
-                    try {
-                        const installProxy = getSettingsValue<string>('network.proxy') || undefined;
-                        await invoke('install_update', { proxy: installProxy });
-                        updateToast(dlTid, { type: 'success', title: 'Update complete', message: 'Restarting...' });
-                        setTimeout(() => relaunch(), 1000);
-                    } catch (e) {
-                        updateToast(dlTid, { type: 'error', title: 'Update failed', message: String(e) });
-                    }
+            actions: [
+                {
+                    label: 'Release Notes',
+                    onClick: async () => {
+                        const download = await showConfirmDialog({
+                            title: `What's new in ${update.version}`,
+                            message: update.body?.trim() || 'No release notes were published for this version.',
+                            confirmText: 'Download',
+                            cancelText: 'Later',
+                        });
+                        if (download) downloadUpdate();
+                    },
                 },
-            }],
+                {
+                    label: 'Download',
+                    primary: true,
+                    onClick: () => downloadUpdate(),
+                },
+            ],
         });
     } catch (e) {
         if (tid) {
@@ -163,9 +197,273 @@ async function checkForUpdate(manual = false): Promise<void> {
     }
 }
 
+/** Downloads in the background; the update installs when the editor quits. */
+async function downloadUpdate(): Promise<void> {
+    const tid = showProgressToast('Downloading update...');
+    const unlisteners = await Promise.all([
+        listen<{ downloaded: number; total: number | null }>('update-progress', (event) => {
+            const { downloaded, total } = event.payload;
+            if (total) {
+                updateToast(tid, { progress: Math.round(downloaded / total * 100) });
+            }
+        }),
+        listen<UpdateInfo>('update-ready', (event) => {
+            stop();
+            dismissToast(tid);
+            showUpdateReady(event.payload.version);
+        }),
+        listen<string>('update-failed', (event) => {
+            stop();
+            updateToast(tid, { type: 'error', title: 'Update failed', message: event.payload });
+        }),
+    ]);
+    const stop = () => unlisteners.forEach(unlisten => unlisten());
+
+    const proxy = getSettingsValue<string>('network.proxy') || undefined;
+    const started = await invoke<boolean>('download_update', { proxy }).catch((e) => {
+        updateToast(tid, { type: 'error', title: 'Update failed', message: String(e) });
+        return null;
+    });
+    if (started === false) {
+        // Already downloading; that download reports through its own toast.
+        stop();
+        dismissToast(tid);
+    } else if (started === null) {
+        stop();
+    }
+}
+
+function showUpdateReady(version: string): void {
+    showToast({
+        type: 'success',
+        title: 'Update Ready',
+        message: `Version ${version} will be installed when you quit the editor.`,
+        duration: 0,
+        actions: [{
+            label: 'Restart Now',
+            primary: true,
+            onClick: async () => {
+                const tid = showProgressToast('Installing update...');
+                try {
+                    await invoke('install_update', {});
+                    updateToast(tid, { type: 'success', title: 'Update complete', message: 'Restarting...' });
+                    setTimeout(() => relaunch(), 1000);
+                } catch (e) {
+                    updateToast(tid, { type: 'error', title: 'Update failed', message: String(e) });
+                }
+            },
+        }],
+    });
+}
+
+interface EngineChanges {
+    from_editor: string | null;
+    to_editor: string;
+    from_engine: string | null;
+    to_engine: string;
+    runtime_changed: boolean;
+    sdk_changed: boolean;
+    added: string[];
+    removed: string[];
+    changed: string[];
+}
+
+const ENGINE_CHANGES_SEEN_KEY = 'esengine.engineChangesSeen';
+
+/** After an update, says once whether the embedded engine changed with it. */
+async function showEngineChanges(): Promise<void> {
+    try {
+        const changes = await invoke<EngineChanges>('get_engine_changes');
+        if (!changes.from_editor || localStorage.getItem(ENGINE_CHANGES_SEEN_KEY) === changes.to_editor) return;
+        localStorage.setItem(ENGINE_CHANGES_SEEN_KEY, changes.to_editor);
+
+        const count = changes.added.length + changes.removed.length + changes.changed.length;
+        let message: string;
+        if (changes.runtime_changed) {
+            message = `The engine runtime changed (${changes.from_engine} → ${changes.to_engine}); re-test your games.`;
+        } else if (changes.sdk_changed) {
+            message = 'The engine runtime is unchanged; the SDK was updated.';
+        } else {
+            message = count > 0 ? 'The engine is unchanged; only editor files were updated.' : 'The engine is unchanged.';
+        }
+        showToast({
+            type: 'info',
+            title: `Updated from ${changes.from_editor} to ${changes.to_editor}`,
+            message,
+            duration: 10000,
+        });
+    } catch (e) {
+        console.warn('Failed to compare engine versions:', e);
+    }
+}
+
+interface CrashReport {
+    id: string;
+    kind: 'panic' | 'native';
+    time: string;
+    editorVersion: string;
+    message: string;
+    status: 'new' | 'dismissed' | 'uploaded';
+}
+
+/** Offers to send reports of crashes since the last launch. */
+async function showCrashRecovery(): Promise<void> {
+    try {
+        const reports = await invoke<CrashReport[]>('list_crash_reports');
+        const fresh = reports.filter((r) => r.status === 'new');
+        if (fresh.length === 0) return;
+
+        const dismissAll = () => Promise.all(fresh.map((r) => invoke('dismiss_crash_report', { id: r.id })));
+        const tid = showToast({
+            type: 'error',
+            title: 'The editor quit unexpectedly',
+            message: fresh.length === 1
+                ? `A crash report was saved (${fresh[0].message}). Send it to help us fix the problem?`
+                : `${fresh.length} crash reports were saved. Send them to help us fix the problem?`,
+            duration: 0,
+            actions: [
+                {
+                    label: 'Send Report',
+                    primary: true,
+                    onClick: async () => {
+                        dismissToast(tid);
+                        try {
+                            for (const report of fresh) {
+                                await invoke('upload_crash_report', { id: report.id });
+                            }
+                            showToast({ type: 'success', title: 'Crash report sent', message: 'Thank you!' });
+                        } catch (e) {
+                            showToast({ type: 'error', title: 'Failed to send the crash report', message: String(e) });
+                        }
+                    },
+                },
+                {
+                    label: 'Dismiss',
+                    onClick: () => {
+                        dismissToast(tid);
+                        dismissAll().catch(console.warn);
+                    },
+                },
+            ],
+        });
+    } catch (e) {
+        console.warn('Failed to read crash reports:', e);
+    }
+}
+
+interface EditorSettings {
+    values: Record<string, unknown>;
+}
+
+/** Keeps editor settings in the backend's settings file instead of localStorage. */
+async function useBackendSettings(): Promise<void> {
+    const label = getCurrentWindow().label;
+    try {
+        await setSettingsBackend({
+            load: async () => (await invoke<EditorSettings>('get_editor_settings')).values,
+            save: async (values) => { await invoke('update_editor_settings', { changes: { values } }); },
+            onExternalChange: (listener) => {
+                listen<{ settings: EditorSettings; source: string | null }>('editor-settings-changed', (e) => {
+                    if (e.payload.source !== label) listener(e.payload.settings.values);
+                });
+            },
+        });
+    } catch (e) {
+        console.warn('[init] Failed to load editor settings, keeping them in localStorage:', e);
+    }
+}
+
+interface OpenRequest {
+    /** Path of the project file. */
+    project: string;
+    /** Scene relative to the project folder. */
+    scene?: string;
+}
+
+let currentProjectPath: string | null = null;
+
+/** Opens a project, and optionally one of its scenes, asked for from outside the editor. */
+async function handleOpenRequest(container: HTMLElement, request: OpenRequest): Promise<void> {
+    if (request.project === currentProjectPath) {
+        if (request.scene) {
+            if (getEditorStore().isDirty && !await confirmDiscard('Open Scene', 'open the scene')) return;
+            await getSceneService().openSceneFromPath(request.scene);
+        }
+        return;
+    }
+    if (request.scene) {
+        await saveEditorLocalSetting(request.project, 'lastOpenedScene', request.scene);
+    }
+    if (currentLauncher) {
+        currentLauncher.dispose();
+        currentLauncher = null;
+        openEditor(container, request.project);
+        return;
+    }
+    // An open project is replaced by reloading the window with the new one.
+    if (getEditorStore().isDirty && !await confirmDiscard('Open Project', 'open the project')) return;
+    const url = new URL(window.location.href);
+    url.searchParams.set('project', request.project);
+    window.location.href = url.href;
+}
+
+function confirmDiscard(title: string, action: string): Promise<boolean> {
+    return showConfirmDialog({
+        title,
+        message: `The current scene has unsaved changes. Discard them and ${action}?`,
+        confirmText: 'Discard',
+    });
+}
+
+/** Runs what was chosen in the native menu bar (macOS). */
+function listenNativeMenu(container: HTMLElement): void {
+    listen<string>('menu-action', (e) => {
+        const item = getMenuItem(e.payload);
+        if (item && (item.enabled?.() ?? true)) {
+            recordFeatureUsage(item.id);
+            item.action();
+        }
+    });
+    listen<string>('menu-open-project', (e) => handleOpenRequest(container, { project: e.payload }));
+}
+
+interface GlobalShortcutFailed {
+    id: string;
+    label: string;
+    accelerator: string;
+    error: string;
+}
+
+/** Runs editor actions bound to global shortcuts, pressed while another app had focus. */
+function listenGlobalShortcuts(): void {
+    listen<string>('global-shortcut', (e) => {
+        const item = getMenuItem(e.payload);
+        if (item && (item.enabled?.() ?? true)) {
+            recordFeatureUsage(item.id);
+            item.action();
+        }
+    });
+    listen<GlobalShortcutFailed>('global-shortcut-failed', (e) => {
+        const { label, accelerator, error } = e.payload;
+        showToast({ type: 'error', title: `${accelerator} could not be set for ${label}`, message: error });
+    });
+}
+
+/** Opens projects and scenes from double-clicked files and esengine:// links. */
+async function listenOpenRequests(container: HTMLElement): Promise<void> {
+    listen<OpenRequest>('open-request', (e) => handleOpenRequest(container, e.payload));
+    // Only one project can be open; the last file asked for wins.
+    const requests = await invoke<OpenRequest[]>('take_open_requests');
+    const request = requests[requests.length - 1];
+    if (request) await handleOpenRequest(container, request);
+}
+
 async function init(): Promise<void> {
+    suppressDefaultContextMenu();
     const version = await getVersion();
     setPlatformAdapter(new TauriPlatformAdapter());
+    window.addEventListener('error', () => recordErrorCategory('editor.uncaught-error'));
+    window.addEventListener('unhandledrejection', () => recordErrorCategory('editor.unhandled-rejection'));
 
     let esbuildWasmURL = '/esbuild.wasm';
     try {
@@ -185,6 +483,7 @@ async function init(): Promise<void> {
         version,
         onCheckUpdate: () => checkForUpdate(true),
     });
+    await useBackendSettings();
 
     const container = document.getElementById('editor-root');
     if (!container) {
@@ -192,7 +491,19 @@ async function init(): Promise<void> {
         return;
     }
 
-    showLauncher(container);
+    listenNativeMenu(container);
+    listenGlobalShortcuts();
+    const projectPath = new URLSearchParams(window.location.search).get('project');
+    if (projectPath) {
+        invoke('add_recent_project', { path: projectPath }).catch(() => {});
+        openEditor(container, projectPath);
+    } else {
+        // The launcher is in place first, so a request can replace it.
+        showLauncher(container);
+    }
+    listenOpenRequests(container).catch((e) => console.warn('[init] Failed to read open requests:', e));
+    showEngineChanges();
+    showCrashRecovery();
     checkForUpdate();
 }
 
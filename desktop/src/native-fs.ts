@@ -22,7 +22,41 @@ import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { resourceDir } from '@tauri-apps/api/path';
 import { open as shellOpen } from '@tauri-apps/plugin-shell';
-import type { NativeShell } from '@esengine/editor';
+import type { ExecuteOptions, NativeShell } from '@esengine/editor';
+
+/**
+ * Fetches an asset embedded in the editor binary through the `esassets`
+ * protocol, which hands the webview the bytes without copying them through
+ * IPC.
+ */
+async function fetchEmbeddedAsset(name: string): Promise<Response> {
+    const res = await fetch(convertFileSrc(name, 'esassets'));
+    if (!res.ok) throw new Error(await res.text());
+    return res;
+}
+
+/**
+ * Loads a Spine or physics runtime file. Editors built without the runtime
+ * download it on first use.
+ */
+async function loadRuntimeFile(name: string): Promise<Uint8Array> {
+    const data = await invoke<ArrayBuffer>('get_runtime_file', { name });
+    return new Uint8Array(data);
+}
+
+/** The runtime holding a physics backend's module. */
+export function physicsRuntime(backend?: string): string {
+    return backend === 'rapier' ? 'physics-rapier' : 'physics';
+}
+
+function spineRuntime(version: string): string {
+    const versionMap: Record<string, string> = {
+        '3.8': 'spine38',
+        '4.1': 'spine41',
+        '4.2': 'spine42',
+    };
+    return versionMap[version] ?? 'spine42';
+}
 
 export function resolveFilePath(path: string): string {
     const normalized = path.replace(/\\/g, '/');
@@ -82,6 +116,8 @@ export interface NativeFS {
         options?: { recursive?: boolean }
     ): Promise<UnwatchFn>;
     openFolder(path: string): Promise<boolean>;
+    revealInFileManager(path: string): Promise<boolean>;
+    openTerminal(path: string): Promise<boolean>;
     getResourcePath(): Promise<string>;
     getEngineJs(): Promise<string>;
     getEngineWasm(): Promise<Uint8Array>;
@@ -100,8 +136,9 @@ export interface NativeFS {
     getEditorDts(): Promise<string>;
     getSpineJs(version: string): Promise<string>;
     getSpineWasm(version: string): Promise<Uint8Array>;
-    getPhysicsJs(): Promise<string>;
-    getPhysicsWasm(): Promise<Uint8Array>;
+    /** `backend` is the project's `physicsBackend`; Box2D when omitted. */
+    getPhysicsJs(backend?: string): Promise<string>;
+    getPhysicsWasm(backend?: string): Promise<Uint8Array>;
     getEsbuildWasm(): Promise<Uint8Array>;
     toAssetUrl?(path: string): string;
 }
@@ -337,6 +374,26 @@ export const nativeFS: NativeFS = {
         }
     },
 
+    async revealInFileManager(path: string) {
+        try {
+            await invoke('reveal_in_file_manager', { path });
+            return true;
+        } catch (err) {
+            console.error('Failed to reveal file:', err);
+            return false;
+        }
+    },
+
+    async openTerminal(path: string) {
+        try {
+            await invoke('open_terminal', { path });
+            return true;
+        } catch (err) {
+            console.error('Failed to open terminal:', err);
+            return false;
+        }
+    },
+
     async getResourcePath() {
         try {
             return await resourceDir();
@@ -346,119 +403,100 @@ export const nativeFS: NativeFS = {
     },
 
     async getEngineJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'engine.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('engine.js');
+        return res.text();
     },
 
     async getEngineWasm() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'engine.wasm' });
-        return new Uint8Array(data);
+        const res = await fetchEmbeddedAsset('engine.wasm');
+        return new Uint8Array(await res.arrayBuffer());
     },
 
     async getEngineSingleJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'engine.single.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('engine.single.js');
+        return res.text();
     },
 
     async getEngineWxgameJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'engine.wxgame.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('engine.wxgame.js');
+        return res.text();
     },
 
     async getEngineWxgameWasm() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'engine.wxgame.wasm' });
-        return new Uint8Array(data);
+        const res = await fetchEmbeddedAsset('engine.wxgame.wasm');
+        return new Uint8Array(await res.arrayBuffer());
     },
 
     async getSdkWechatJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.wechat.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.wechat.js');
+        return res.text();
     },
 
     async getSdkEsmJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.esm.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.esm.js');
+        return res.text();
     },
 
     async getSdkEsmDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.esm.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.esm.dts');
+        return res.text();
     },
 
     async getSdkWasmJs() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.wasm.js' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.wasm.js');
+        return res.text();
     },
 
     async getSdkWasmDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.wasm.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.wasm.dts');
+        return res.text();
     },
 
     async getSdkSharedWasmDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.shared.wasm.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.shared.wasm.dts');
+        return res.text();
     },
 
     async getSdkSharedAppDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.shared.app.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.shared.app.dts');
+        return res.text();
     },
 
     async getSdkPhysicsDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.physics.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.physics.dts');
+        return res.text();
     },
 
     async getSdkSpineDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'sdk.spine.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('sdk.spine.dts');
+        return res.text();
     },
 
     async getEditorDts() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'editor.dts' });
-        return new TextDecoder().decode(new Uint8Array(data));
+        const res = await fetchEmbeddedAsset('editor.dts');
+        return res.text();
     },
 
     async getSpineJs(version: string) {
-        const versionMap: Record<string, string> = {
-            '3.8': 'spine38',
-            '4.1': 'spine41',
-            '4.2': 'spine42',
-        };
-        const prefix = versionMap[version] ?? 'spine42';
-        const res = await fetch(`/wasm/${prefix}.js`);
-        if (!res.ok) throw new Error(`Failed to load spine JS: ${prefix}.js`);
-        return res.text();
+        const bytes = await loadRuntimeFile(`${spineRuntime(version)}.js`);
+        return new TextDecoder().decode(bytes);
     },
 
     async getSpineWasm(version: string) {
-        const versionMap: Record<string, string> = {
-            '3.8': 'spine38',
-            '4.1': 'spine41',
-            '4.2': 'spine42',
-        };
-        const prefix = versionMap[version] ?? 'spine42';
-        const res = await fetch(`/wasm/${prefix}.wasm`);
-        if (!res.ok) throw new Error(`Failed to load spine WASM: ${prefix}.wasm`);
-        return new Uint8Array(await res.arrayBuffer());
+        return loadRuntimeFile(`${spineRuntime(version)}.wasm`);
     },
 
-    async getPhysicsJs() {
-        const res = await fetch('/wasm/physics.js');
-        if (!res.ok) throw new Error('Failed to load physics.js');
-        return res.text();
+    async getPhysicsJs(backend?: string) {
+        return new TextDecoder().decode(await loadRuntimeFile(`${physicsRuntime(backend)}.js`));
     },
 
-    async getPhysicsWasm() {
-        const res = await fetch('/wasm/physics.wasm');
-        if (!res.ok) throw new Error('Failed to load physics.wasm');
-        return new Uint8Array(await res.arrayBuffer());
+    async getPhysicsWasm(backend?: string) {
+        return loadRuntimeFile(`${physicsRuntime(backend)}.wasm`);
     },
 
     async getEsbuildWasm() {
-        const data = await invoke<number[]>('get_embedded_asset', { name: 'esbuild.wasm' });
-        return new Uint8Array(data);
+        const res = await fetchEmbeddedAsset('esbuild.wasm');
+        return new Uint8Array(await res.arrayBuffer());
     },
 };
 
@@ -471,49 +509,80 @@ export const nativeShell: NativeShell = {
         await shellOpen(url);
     },
 
-    async openInEditor(projectPath: string, filePath: string): Promise<void> {
-        const isMac = navigator.platform.startsWith('Mac');
-        if (isMac) {
-            await invoke('execute_command', {
-                cmd: 'open',
-                args: ['-a', 'Visual Studio Code', projectPath, filePath],
-                cwd: projectPath,
-            });
-        } else {
-            await invoke('execute_command', {
-                cmd: 'cmd',
-                args: ['/c', 'code', projectPath, filePath],
-                cwd: projectPath,
-            });
-        }
+    async openInEditor(projectPath: string, filePath: string, line?: number): Promise<void> {
+        await invoke('open_in_external_editor', { path: filePath, line, projectDir: projectPath });
     },
 
     async execute(
         cmd: string,
         args: string[],
         cwd: string,
-        onOutput?: (stream: 'stdout' | 'stderr', data: string) => void
+        onOutput?: (stream: 'stdout' | 'stderr', data: string) => void,
+        options?: ExecuteOptions
     ): Promise<{ code: number }> {
-        let unlisten: UnlistenFn | null = null;
+        // The command starts before its id is known, so events are buffered
+        // until invoke returns.
+        let id: number | null = null;
+        const pending: { event: string; payload: any }[] = [];
+        let finish: (result: { code: number }) => void = () => {};
+        const done = new Promise<{ code: number }>((resolve) => { finish = resolve; });
+
+        const handle = (event: string, payload: any) => {
+            if (payload.id !== id) return;
+            if (event === 'command-output') {
+                for (const line of payload.lines) {
+                    // Truncation markers read as regular output.
+                    const stream = line.stream === 'stderr' ? 'stderr' : 'stdout';
+                    onOutput?.(stream, line.data);
+                }
+            } else {
+                finish({ code: payload.code ?? 1 });
+            }
+        };
+        const listener = (event: string) => (e: { payload: any }) => {
+            if (id === null) {
+                pending.push({ event, payload: e.payload });
+            } else {
+                handle(event, e.payload);
+            }
+        };
 
+        const unlisteners: UnlistenFn[] = [];
         try {
-            if (onOutput) {
-                unlisten = await listen<{ stream: string; data: string }>(
-                    'command-output',
-                    (event) => {
-                        onOutput(event.payload.stream as 'stdout' | 'stderr', event.payload.data);
-                    }
-                );
+            unlisteners.push(await listen('command-output', listener('command-output')));
+            unlisteners.push(await listen('command-exit', listener('command-exit')));
+
+            const started = await invoke<{ id: number }>('execute_command', {
+                cmd,
+                args,
+                cwd,
+                env: options?.env,
+                projectDir: options?.projectDir,
+                loginPath: options?.loginPath,
+                timeoutSecs: options?.timeoutSecs,
+                ansi: options?.ansi,
+                stdin: options?.stdin,
+                limits: options?.priority || options?.memoryLimitMb
+                    ? { priority: options.priority, memory_limit_mb: options.memoryLimitMb }
+                    : undefined,
+            });
+            id = started.id;
+            const commandId = started.id;
+            options?.onStart?.({
+                id: commandId,
+                write: (data) => invoke('write_command_stdin', { id: commandId, data }),
+                closeStdin: () => invoke('close_command_stdin', { id: commandId }),
+                kill: () => invoke('kill_command', { id: commandId }),
+                stats: () => invoke('get_process_stats', { id: commandId }),
+            });
+            for (const { event, payload } of pending.splice(0)) {
+                handle(event, payload);
             }
-
-            const result = await invoke<{ code: number }>('execute_command', { cmd, args, cwd });
-            return result;
+            return await done;
         } catch (err) {
             return { code: 1 };
         } finally {
-            if (unlisten) {
-                unlisten();
-            }
+            unlisteners.forEach((unlisten) => unlisten());
         }
     },
 };
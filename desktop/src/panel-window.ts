@@ -18,11 +18,13 @@ import {
 } from '@esengine/editor';
 import { TauriPlatformAdapter } from './TauriPlatformAdapter';
 import { nativeFS, nativeShell } from './native-fs';
+import { suppressDefaultContextMenu } from './context-menu';
 import { invoke } from '@tauri-apps/api/core';
 import { getVersion } from '@tauri-apps/api/app';
 import { listen } from '@tauri-apps/api/event';
 
 async function init(): Promise<void> {
+    suppressDefaultContextMenu();
     const params = new URLSearchParams(window.location.search);
     const panelId = params.get('panel');
     if (!panelId) {
@@ -1,5 +1,5 @@
 import { resolve } from 'path';
-import { readFileSync } from 'fs';
+import { readFileSync, rmSync } from 'fs';
 import { defineConfig, type Plugin } from 'vite';
 
 const tauriConf = JSON.parse(readFileSync(resolve(__dirname, 'src-tauri/tauri.conf.json'), 'utf-8'));
@@ -27,9 +27,32 @@ function tauriHtmlFixes(): Plugin {
   };
 }
 
+/**
+ * Spine and physics runtimes reach the editor through the backend (embedded
+ * by cargo feature or downloaded), so their copies in public/ stay out of
+ * the bundle.
+ */
+function dropOptionalRuntimes(): Plugin {
+  let outDir = '';
+  return {
+    name: 'drop-optional-runtimes',
+    apply: 'build',
+    configResolved(config) {
+      outDir = resolve(config.root, config.build.outDir);
+    },
+    closeBundle() {
+      for (const runtime of ['spine38', 'spine41', 'spine42', 'physics', 'physics-rapier']) {
+        for (const ext of ['js', 'wasm']) {
+          rmSync(resolve(outDir, 'wasm', `${runtime}.${ext}`), { force: true });
+        }
+      }
+    },
+  };
+}
+
 export default defineConfig({
   clearScreen: false,
-  plugins: [tauriHtmlFixes()],
+  plugins: [tauriHtmlFixes(), dropOptionalRuntimes()],
   define: {
     __ENGINE_VERSION__: JSON.stringify(tauriConf.version),
     __SDK_VERSION__: JSON.stringify(sdkPkg.version),
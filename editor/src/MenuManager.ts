@@ -2,6 +2,7 @@ import { getAllMenus, getMenuItems, getAllStatusbarItems } from './menus';
 import { ShortcutManager } from './menus/ShortcutManager';
 import { getPanelsByPosition } from './panels/PanelRegistry';
 import { icons } from './utils/icons';
+import { recordFeatureUsage } from './logging/telemetry';
 import type { EditorStore } from './store/EditorStore';
 
 export class MenuManager {
@@ -36,7 +37,10 @@ export class MenuManager {
             const items = getMenuItems(menu.id);
             for (const item of items) {
                 if (item.shortcut) {
-                    this.shortcutManager_.register(item.shortcut, item.action);
+                    this.shortcutManager_.register(item.shortcut, () => {
+                        recordFeatureUsage(item.id);
+                        item.action();
+                    });
                 }
             }
         }
@@ -165,6 +169,7 @@ export class MenuManager {
                 const items = getMenuItems(menu.id);
                 const found = items.find(i => i.id === actionId);
                 if (found) {
+                    recordFeatureUsage(found.id);
                     found.action();
                     return;
                 }
@@ -556,6 +556,63 @@ describe('CommandHistory', () => {
     });
   });
 
+  describe('restore', () => {
+    it('should restore undo and redo stacks at a save point', () => {
+      const applied1 = new MockCommand();
+      const applied2 = new MockCommand();
+      const undone = new MockCommand();
+      history.restore([applied1, applied2], [undone]);
+
+      expect(history.isAtSavePoint).toBe(true);
+      expect(history.peekUndo()).toBe(applied2);
+      expect(history.peekRedo()).toBe(undone);
+
+      history.redo();
+      expect(undone.executed).toBe(true);
+      history.undo();
+      history.undo();
+      expect(applied2.undone).toBe(true);
+      expect(history.peekUndo()).toBe(applied1);
+    });
+
+    it('should redo the last redo command first', () => {
+      const next = new MockCommand();
+      const later = new MockCommand();
+      history.restore([], [later, next]);
+
+      expect(history.peekRedo()).toBe(next);
+      history.redo();
+      expect(history.peekRedo()).toBe(later);
+    });
+  });
+
+  describe('observer', () => {
+    it('should report history changes', () => {
+      const observer = vi.fn();
+      history.setObserver(observer);
+
+      history.execute(new MockCommand());
+      history.undo();
+      history.redo();
+      history.markSaved();
+      history.clear();
+
+      expect(observer.mock.calls.map(([event]) => event.type)).toEqual([
+        'execute', 'undo', 'redo', 'save', 'clear',
+      ]);
+      expect(observer.mock.calls[0][0]).toEqual({ type: 'execute', command: null, merged: false });
+    });
+
+    it('should report merged commands', () => {
+      const observer = vi.fn();
+      history.execute(new MergeableCommand(1));
+      history.setObserver(observer);
+      history.execute(new MergeableCommand(2));
+
+      expect(observer).toHaveBeenCalledWith({ type: 'execute', command: null, merged: true });
+    });
+  });
+
   describe('structural flag', () => {
     it('should identify structural commands', () => {
       const cmd = new StructuralMockCommand();
@@ -0,0 +1,19 @@
+import { describe, it, expect } from 'vitest';
+import { errorMessage, isBackendError } from '../../utils/errors';
+
+describe('errorMessage', () => {
+  it('should use the localized message of a backend error', () => {
+    const err = { code: 'process.startFailed', params: { name: 'npm', error: 'not found' }, message: '无法启动 npm：not found' };
+    expect(isBackendError(err)).toBe(true);
+    expect(errorMessage(err)).toBe('无法启动 npm：not found');
+  });
+
+  it('should use the message of an Error', () => {
+    expect(errorMessage(new Error('boom'))).toBe('boom');
+  });
+
+  it('should stringify plain string errors', () => {
+    expect(isBackendError('Failed to read')).toBe(false);
+    expect(errorMessage('Failed to read')).toBe('Failed to read');
+  });
+});
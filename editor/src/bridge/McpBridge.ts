@@ -16,6 +16,7 @@ import { getSceneService, getClipboardService } from '../services';
 import { getEditorContext } from '../context/EditorContext';
 import type { SceneData, EntityData } from '../types/SceneTypes';
 import { RingBuffer } from './RingBuffer';
+import { getProjectDir, normalizePath } from '../utils/path';
 import { ENTITY_TEMPLATES } from '../panels/hierarchy/EntityTemplates';
 import { resolveUIParent } from '../panels/hierarchy/uiEntityUtils';
 import type { Entity } from 'esengine';
@@ -29,6 +30,11 @@ interface LogEntry {
     timestamp: number;
 }
 
+interface McpEndpoint {
+    port: number;
+    token: string;
+}
+
 interface McpRequest {
     id: string;
     method: string;
@@ -38,6 +44,7 @@ interface McpRequest {
 export class McpBridge {
     private logBuffer_ = new RingBuffer<LogEntry>(LOG_BUFFER_CAPACITY);
     private unlisten_: UnlistenFn | null = null;
+    private sceneUnlisten_: UnlistenFn | null = null;
     private logCleanup_: (() => void) | null = null;
 
     constructor(
@@ -54,6 +61,7 @@ export class McpBridge {
     dispose(): void {
         this.logCleanup_?.();
         this.unlisten_?.();
+        this.sceneUnlisten_?.();
     }
 
     private setupLogCapture_(): void {
@@ -77,6 +85,19 @@ export class McpBridge {
             }).catch((e) => {
                 console.warn('[McpBridge] Failed to start bridge server:', e);
             });
+            if (!this.projectPath_) return;
+            invoke<McpEndpoint | null>('start_mcp_server', {
+                projectDir: getProjectDir(this.projectPath_),
+            }).then((endpoint) => {
+                if (endpoint == null) return;
+                this.outputService_.appendOutput(
+                    `[MCP] Listening on http://127.0.0.1:${endpoint.port}/mcp; clients must send the header ` +
+                    `"Authorization: Bearer ${endpoint.token}"\n`,
+                    'stdout',
+                );
+            }).catch((e) => {
+                console.warn('[McpBridge] Failed to start MCP server:', e);
+            });
         }).catch((e) => {
             console.warn('[McpBridge] Failed to load Tauri API:', e);
         });
@@ -88,6 +109,22 @@ export class McpBridge {
         }).then((fn) => {
             this.unlisten_ = fn;
         });
+        listen<{ path: string }>('mcp-scene-modified', (event) => {
+            this.onSceneModified_(event.payload.path);
+        }).then((fn) => {
+            this.sceneUnlisten_ = fn;
+        });
+    }
+
+    private async onSceneModified_(path: string): Promise<void> {
+        const store = getEditorStore();
+        if (!store.filePath || normalizePath(store.filePath) !== normalizePath(path)) return;
+        if (store.isDirty) {
+            this.outputService_.appendOutput(`[MCP] ${path} was changed by an AI assistant; reopen it to see the changes\n`, 'stderr');
+            return;
+        }
+        await getSceneService().openSceneFromPath(path);
+        getSharedRenderContext().requestRender();
     }
 
     private async handleRequest_(req: McpRequest): Promise<void> {
@@ -3,7 +3,20 @@
  * @brief   Undo/Redo command history manager
  */
 
-import type { Command } from './Command';
+import type { Command, SerializedCommand } from './Command';
+
+// =============================================================================
+// Types
+// =============================================================================
+
+export type HistoryEvent =
+    | { type: 'execute'; command: SerializedCommand | null; merged: boolean }
+    | { type: 'undo' }
+    | { type: 'redo' }
+    | { type: 'clear' }
+    | { type: 'save' };
+
+export type HistoryObserver = (event: HistoryEvent) => void;
 
 // =============================================================================
 // Constants
@@ -21,6 +34,7 @@ export class CommandHistory {
     private index_ = -1;
     private savedIndex_ = -1;
     private maxSize_: number;
+    private observer_: HistoryObserver | null = null;
 
     constructor(maxSize: number = MAX_HISTORY_SIZE) {
         this.maxSize_ = maxSize;
@@ -37,12 +51,18 @@ export class CommandHistory {
         }
 
         const lastCommand = this.commands_[this.commands_.length - 1];
-        if (lastCommand && lastCommand.canMerge(command)) {
+        const merged = !!lastCommand && lastCommand.canMerge(command);
+        if (merged) {
             this.commands_[this.commands_.length - 1] = lastCommand.merge(command);
         } else {
             this.commands_.push(command);
             this.index_++;
         }
+        this.observer_?.({
+            type: 'execute',
+            command: this.commands_[this.commands_.length - 1].serialize(),
+            merged,
+        });
 
         while (this.commands_.length > this.maxSize_) {
             this.commands_.shift();
@@ -57,6 +77,7 @@ export class CommandHistory {
         const command = this.commands_[this.index_];
         command.undo();
         this.index_--;
+        this.observer_?.({ type: 'undo' });
         return true;
     }
 
@@ -66,6 +87,7 @@ export class CommandHistory {
         this.index_++;
         const command = this.commands_[this.index_];
         command.execute();
+        this.observer_?.({ type: 'redo' });
         return true;
     }
 
@@ -91,10 +113,27 @@ export class CommandHistory {
         this.commands_ = [];
         this.index_ = -1;
         this.savedIndex_ = -1;
+        this.observer_?.({ type: 'clear' });
     }
 
     markSaved(): void {
         this.savedIndex_ = this.index_;
+        this.observer_?.({ type: 'save' });
+    }
+
+    /**
+     * Replaces the history with commands that were already applied (`undo`,
+     * oldest first) and undone (`redo`, next to redo last). The result
+     * counts as saved.
+     */
+    restore(undo: Command[], redo: Command[]): void {
+        this.commands_ = [...undo, ...[...redo].reverse()];
+        this.index_ = undo.length - 1;
+        this.savedIndex_ = this.index_;
+    }
+
+    setObserver(observer: HistoryObserver | null): void {
+        this.observer_ = observer;
     }
 
     get isAtSavePoint(): boolean {
@@ -27,4 +27,4 @@ export {
 export { RenameEntityCommand } from './RenameEntityCommand';
 export { ToggleVisibilityCommand } from './ToggleVisibilityCommand';
 export { CompoundCommand } from './CompoundCommand';
-export { CommandHistory } from './CommandHistory';
+export { CommandHistory, type HistoryEvent, type HistoryObserver } from './CommandHistory';
@@ -4,15 +4,58 @@ import type { Editor } from '../Editor';
 export interface NativeShell {
     openFile(path: string): Promise<void>;
     openUrl(url: string): Promise<void>;
-    openInEditor(projectPath: string, filePath: string): Promise<void>;
+    /** Opens a file in the external editor set up for its asset type. */
+    openInEditor(projectPath: string, filePath: string, line?: number): Promise<void>;
     execute(
         cmd: string,
         args: string[],
         cwd: string,
-        onOutput?: (stream: 'stdout' | 'stderr', data: string) => void
+        onOutput?: (stream: 'stdout' | 'stderr', data: string) => void,
+        options?: ExecuteOptions
     ): Promise<{ code: number }>;
 }
 
+export interface ExecuteOptions {
+    /** Added to the inherited environment; a PATH entry replaces it. */
+    env?: Record<string, string>;
+    /** Applies the project's tool overrides from project.esproject. */
+    projectDir?: string;
+    /** Adds the login shell's PATH; defaults to true on macOS. */
+    loginPath?: boolean;
+    /** Kills the command when it runs longer than this. */
+    timeoutSecs?: number;
+    /** Escape codes in output: kept (`raw`), removed, or rendered as HTML. */
+    ansi?: 'raw' | 'strip' | 'html';
+    /** Keeps stdin open so input can be sent through the `onStart` handle. */
+    stdin?: boolean;
+    /** Runs the command below normal priority. */
+    priority?: 'normal' | 'low' | 'idle';
+    /** Memory limit of each process the command starts; Linux only. */
+    memoryLimitMb?: number;
+    /** Called once the command runs, with a handle to talk to it. */
+    onStart?: (command: CommandHandle) => void;
+}
+
+export interface CommandHandle {
+    id: number;
+    /** Sends input as is; end lines with `\n`. */
+    write(data: string): Promise<void>;
+    /** Closes stdin, for tools that read until end of input. */
+    closeStdin(): Promise<void>;
+    kill(): Promise<void>;
+    /** CPU and memory use of the command and its children. */
+    stats(): Promise<ProcessStats>;
+}
+
+export interface ProcessStats {
+    id: number;
+    pid: number;
+    /** 100 is one core fully busy. */
+    cpu_percent: number;
+    memory_bytes: number;
+    processes: number;
+}
+
 export interface EditorContextConfig {
     fs?: NativeFS;
     invoke?: (cmd: string, args?: Record<string, unknown>) => Promise<unknown>;
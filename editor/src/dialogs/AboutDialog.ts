@@ -1,8 +1,10 @@
 import { getEditorContext } from '../context/EditorContext';
 import { icons } from '../utils/icons';
+import { showDiagnosticsDialog } from './DiagnosticsDialog';
 
 export function showAboutDialog(): void {
     const hasUpdater = !!getEditorContext().onCheckUpdate;
+    const hasDiagnostics = !!getEditorContext().invoke;
     const overlay = document.createElement('div');
     overlay.className = 'es-dialog-overlay';
     overlay.innerHTML = `
@@ -20,6 +22,7 @@ export function showAboutDialog(): void {
                 </p>
             </div>
             <div class="es-dialog-footer" style="justify-content: center; gap: 8px;">
+                ${hasDiagnostics ? '<button class="es-dialog-btn" id="about-diagnostics">System Info</button>' : ''}
                 ${hasUpdater ? '<button class="es-dialog-btn" id="about-check-update">Check for Updates</button>' : ''}
                 <button class="es-dialog-btn es-dialog-btn-primary">OK</button>
             </div>
@@ -33,6 +36,10 @@ export function showAboutDialog(): void {
         close();
         getEditorContext().onCheckUpdate?.();
     });
+    overlay.querySelector('#about-diagnostics')?.addEventListener('click', () => {
+        close();
+        showDiagnosticsDialog().catch((e) => console.warn('Failed to read system information:', e));
+    });
     overlay.addEventListener('click', (e) => {
         if (e.target === overlay) close();
     });
@@ -0,0 +1,97 @@
+import { getEditorContext } from '../context/EditorContext';
+import { getProjectService } from '../services';
+import { escapeHtml } from '../utils/html';
+
+interface SystemInfo {
+    os: string;
+    osVersion: string | null;
+    kernelVersion: string | null;
+    arch: string;
+    cpu: { brand: string; physicalCores: number | null; logicalCores: number };
+    memory: { totalBytes: number; availableBytes: number };
+    gpus: string[];
+    webviewVersion: string | null;
+    editorVersion: string;
+    engineVersion: string;
+    disk: { mountPoint: string; totalBytes: number; availableBytes: number } | null;
+}
+
+function formatBytes(bytes: number): string {
+    const gb = bytes / (1024 * 1024 * 1024);
+    return gb >= 1 ? `${gb.toFixed(1)} GB` : `${Math.round(bytes / (1024 * 1024))} MB`;
+}
+
+function currentProjectPath(): string | null {
+    try {
+        return getProjectService().projectPath;
+    } catch {
+        // The launcher has no project.
+        return null;
+    }
+}
+
+function describe(info: SystemInfo): Array<[string, string]> {
+    const cores = info.cpu.physicalCores
+        ? `${info.cpu.physicalCores} cores, ${info.cpu.logicalCores} threads`
+        : `${info.cpu.logicalCores} threads`;
+    const rows: Array<[string, string]> = [
+        ['Editor', `${info.editorVersion} (engine ${info.engineVersion})`],
+        ['OS', `${info.osVersion ?? info.os} ${info.arch}`],
+        ['Kernel', info.kernelVersion ?? 'Unknown'],
+        ['CPU', `${info.cpu.brand || 'Unknown'} (${cores})`],
+        ['Memory', `${formatBytes(info.memory.availableBytes)} free of ${formatBytes(info.memory.totalBytes)}`],
+        ['GPU', info.gpus.length > 0 ? info.gpus.join(', ') : 'Unknown'],
+        ['WebView', info.webviewVersion ?? 'Unknown'],
+    ];
+    if (info.disk) {
+        rows.push(['Project Drive', `${formatBytes(info.disk.availableBytes)} free of ${formatBytes(info.disk.totalBytes)} (${info.disk.mountPoint})`]);
+    }
+    return rows;
+}
+
+/** Shows the system the editor runs on, ready to paste into a bug report. */
+export async function showDiagnosticsDialog(): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    if (!invoke) return;
+
+    const info = await invoke('get_system_info', { projectPath: currentProjectPath() }) as SystemInfo;
+    const rows = describe(info);
+    const text = rows.map(([label, value]) => `${label}: ${value}`).join('\n');
+
+    const overlay = document.createElement('div');
+    overlay.className = 'es-dialog-overlay';
+    overlay.innerHTML = `
+        <div class="es-dialog" style="max-width: 560px;">
+            <div class="es-dialog-header">
+                <span class="es-dialog-title">System Information</span>
+                <button class="es-dialog-close">&times;</button>
+            </div>
+            <div class="es-dialog-body" style="padding: 16px;">
+                <div style="display: grid; grid-template-columns: auto 1fr; gap: 6px 16px; font-size: 12px; user-select: text;">
+                    ${rows.map(([label, value]) => `
+                        <span style="color: var(--es-text-secondary);">${escapeHtml(label)}</span>
+                        <span style="color: var(--es-text-primary);">${escapeHtml(value)}</span>
+                    `).join('')}
+                </div>
+            </div>
+            <div class="es-dialog-footer" style="gap: 8px;">
+                <button class="es-dialog-btn" id="diagnostics-copy">Copy</button>
+                <button class="es-dialog-btn es-dialog-btn-primary">OK</button>
+            </div>
+        </div>
+    `;
+
+    const close = () => overlay.remove();
+    overlay.querySelector('.es-dialog-close')?.addEventListener('click', close);
+    overlay.querySelector('.es-dialog-btn-primary')?.addEventListener('click', close);
+    const copyButton = overlay.querySelector('#diagnostics-copy') as HTMLButtonElement | null;
+    copyButton?.addEventListener('click', async () => {
+        await navigator.clipboard.writeText(text);
+        copyButton.textContent = 'Copied';
+    });
+    overlay.addEventListener('click', (e) => {
+        if (e.target === overlay) close();
+    });
+
+    document.body.appendChild(overlay);
+}
@@ -0,0 +1,74 @@
+import { getEditorContext } from '../context/EditorContext';
+import { escapeHtml } from '../utils/html';
+
+interface TelemetryEvent {
+    kind: 'feature' | 'error';
+    name: string;
+    time: string;
+    session: string;
+    os: string;
+    arch: string;
+    editorVersion: string;
+}
+
+interface TelemetryStatus {
+    enabled: boolean;
+    endpoint: string | null;
+    queued: number;
+}
+
+const SHOWN_EVENTS = 500;
+
+function describeStatus(status: TelemetryStatus): string {
+    if (!status.enabled) {
+        return 'Sharing usage data is off. Nothing is recorded or sent.';
+    }
+    const destination = status.endpoint ? `sent to ${status.endpoint}` : 'kept locally; no endpoint is configured';
+    return `Sharing usage data is on. ${status.queued} event(s) waiting to be ${destination}.`;
+}
+
+/** Shows every telemetry event recorded on this machine, newest first. */
+export async function showTelemetryLogDialog(): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    if (!invoke) return;
+
+    const [status, events] = await Promise.all([
+        invoke('get_telemetry_status') as Promise<TelemetryStatus>,
+        invoke('get_telemetry_log', { limit: SHOWN_EVENTS }) as Promise<TelemetryEvent[]>,
+    ]);
+    const log = events.length > 0
+        ? events.map(e => JSON.stringify(e)).join('\n')
+        : 'No events have been recorded.';
+
+    const overlay = document.createElement('div');
+    overlay.className = 'es-dialog-overlay';
+    overlay.innerHTML = `
+        <div class="es-dialog" style="max-width: 720px;">
+            <div class="es-dialog-header">
+                <span class="es-dialog-title">Telemetry Log</span>
+                <button class="es-dialog-close">&times;</button>
+            </div>
+            <div class="es-dialog-body" style="padding: 16px;">
+                <p style="margin: 0 0 12px; color: var(--es-text-secondary);">${escapeHtml(describeStatus(status))}</p>
+                <pre style="margin: 0; max-height: 50vh; overflow: auto; font-size: 11px; user-select: text;">${escapeHtml(log)}</pre>
+            </div>
+            <div class="es-dialog-footer" style="gap: 8px;">
+                <button class="es-dialog-btn" id="telemetry-clear">Delete All Data</button>
+                <button class="es-dialog-btn es-dialog-btn-primary">OK</button>
+            </div>
+        </div>
+    `;
+
+    const close = () => overlay.remove();
+    overlay.querySelector('.es-dialog-close')?.addEventListener('click', close);
+    overlay.querySelector('.es-dialog-btn-primary')?.addEventListener('click', close);
+    overlay.querySelector('#telemetry-clear')?.addEventListener('click', async () => {
+        await invoke('clear_telemetry_data').catch((e) => console.warn('Failed to delete telemetry data:', e));
+        close();
+    });
+    overlay.addEventListener('click', (e) => {
+        if (e.target === overlay) close();
+    });
+
+    document.body.appendChild(overlay);
+}
@@ -20,7 +20,7 @@ export type { ErrorBoundaryOptions } from './error';
 // Logging
 // =============================================================================
 
-export { EditorLogger, createConsoleHandler, createToastHandler } from './logging';
+export { EditorLogger, createConsoleHandler, createToastHandler, recordFeatureUsage, recordErrorCategory } from './logging';
 export type { LogLevel, LogEntry, LogHandler } from './logging';
 
 // =============================================================================
@@ -119,6 +119,7 @@ export {
     registerStatusbarItem,
     getAllMenus,
     getMenuItems,
+    getMenuItem,
 } from './menus/MenuRegistry';
 
 export { ShortcutManager } from './menus/ShortcutManager';
@@ -278,6 +279,7 @@ export {
     selectProjectLocation,
     getRecentProjects,
     loadProjectConfig,
+    saveEditorLocalSetting,
     type ProjectLauncherOptions,
     type CreateProjectOptions,
     type ProjectServiceResult,
@@ -347,6 +349,9 @@ export {
     getEditorInstance,
     type EditorContextConfig,
     type NativeShell,
+    type ExecuteOptions,
+    type CommandHandle,
+    type ProcessStats,
 } from './context/EditorContext';
 
 export type { NativeFS, DirectoryEntry } from './scripting/types';
@@ -372,6 +377,7 @@ export {
     type SettingsItemType,
     type SettingsSectionDescriptor,
     type SettingsItemDescriptor,
+    type SettingsBackend,
     registerSettingsSection,
     registerSettingsItem,
     getSettingsValue,
@@ -380,6 +386,7 @@ export {
     getAllSections,
     getSectionItems,
     showSettingsDialog,
+    setSettingsBackend,
 } from './settings';
 
 // =============================================================================
@@ -402,7 +409,7 @@ export * as tokens from './container/tokens';
 
 export type { EditorPlugin, EditorPluginContext } from './plugins/EditorPlugin';
 export { builtinPlugins } from './plugins';
-export { OutputService, getOutputService } from './services';
+export { OutputService, getOutputService, getSceneService } from './services';
 
 // =============================================================================
 // SDK Re-exports
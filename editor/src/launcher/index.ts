@@ -19,6 +19,7 @@ export {
     removeRecentProject,
     clearRecentProjects,
     loadProjectConfig,
+    saveEditorLocalSetting,
 } from './ProjectService';
 export type { CreateProjectOptions, ProjectServiceResult } from './ProjectService';
 
@@ -11,3 +11,5 @@ export {
     type LogEntry,
     type LogHandler,
 } from './EditorLogger';
+
+export { recordFeatureUsage, recordErrorCategory } from './telemetry';
@@ -0,0 +1,23 @@
+/**
+ * @file    telemetry.ts
+ * @brief   Anonymous usage events, recorded only for users who opted in
+ */
+
+import { getEditorContext } from '../context/EditorContext';
+
+type TelemetryKind = 'feature' | 'error';
+
+function record(kind: TelemetryKind, name: string): void {
+    // The backend drops events unless telemetry is turned on.
+    getEditorContext().invoke?.('record_telemetry_event', { kind, name }).catch(() => {});
+}
+
+/** Records that a menu item or command, by id, was used. */
+export function recordFeatureUsage(id: string): void {
+    record('feature', id);
+}
+
+/** Records that something of a kind failed, never why. */
+export function recordErrorCategory(category: string): void {
+    record('error', category);
+}
@@ -4,6 +4,7 @@ import { MENU, MENU_ITEM } from '../container/tokens';
 import { getEditorContext } from '../context/EditorContext';
 import { showAboutDialog } from '../dialogs/AboutDialog';
 import { showShortcutHelpDialog } from '../dialogs/ShortcutHelpDialog';
+import { showTelemetryLogDialog } from '../dialogs/TelemetryLogDialog';
 import { showAddressableWindow } from '../dialogs/AddressableWindow';
 import { showStatusBarMessage } from './builtinStatusbar';
 import { showConfirmDialog } from '../ui/dialog';
@@ -19,8 +20,27 @@ import {
     getProjectService,
     getExtensionService,
 } from '../services';
+import { getProjectDir } from '../utils/path';
 import type { Entity } from 'esengine';
 
+interface FormatReport {
+    formatted: string[];
+    unchanged: number;
+    failed: { path: string; error: string }[];
+}
+
+async function formatProject(): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    const projectPath = getProjectService().projectPath;
+    if (!invoke || !projectPath) return;
+    const report = await invoke('format_files', { paths: [getProjectDir(projectPath)] }) as FormatReport;
+    for (const failure of report.failed) {
+        console.warn(`[Format] ${failure.path}: ${failure.error}`);
+    }
+    const failed = report.failed.length > 0 ? `, ${report.failed.length} failed` : '';
+    showStatusBarMessage(`Formatted ${report.formatted.length} file(s)${failed}`, 4000);
+}
+
 export function registerBuiltinMenus(registrar: PluginRegistrar): void {
     const registerMenu = (d: MenuDescriptor) => registrar.provide(MENU, d.id, d);
     const registerMenuItem = (d: MenuItemDescriptor) => registrar.provide(MENU_ITEM, d.id, d);
@@ -155,6 +175,13 @@ export function registerBuiltinMenus(registrar: PluginRegistrar): void {
         shortcut: 'Ctrl+K', order: 9, separator: true,
         action: () => showCommandPalette(),
     });
+    registerMenuItem({
+        id: 'edit.format-all', menu: 'edit', label: 'Format All Files', order: 9.5,
+        enabled: () => !!getEditorContext().invoke,
+        action: () => {
+            formatProject().catch((e) => showStatusBarMessage(`Format failed: ${e}`, 4000));
+        },
+    });
     registerMenuItem({
         id: 'edit.preferences', menu: 'edit', label: 'Settings...',
         shortcut: 'Ctrl+,', order: 10,
@@ -290,6 +317,13 @@ export function registerBuiltinMenus(registrar: PluginRegistrar): void {
         shortcut: '?',
         action: () => showShortcutHelpDialog(),
     });
+    registerMenuItem({
+        id: 'help.telemetry-log', menu: 'help', label: 'Telemetry Log', order: 0.75,
+        enabled: () => !!getEditorContext().invoke,
+        action: () => {
+            showTelemetryLogDialog().catch((e) => console.warn('Failed to read the telemetry log:', e));
+        },
+    });
     registerMenuItem({
         id: 'help.about', menu: 'help', label: 'About ESEngine', order: 1, separator: true,
         action: () => showAboutDialog(),
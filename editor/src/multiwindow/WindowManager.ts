@@ -1,102 +1,56 @@
-import { WebviewWindow } from '@tauri-apps/api/webviewWindow';
-import { emit } from '@tauri-apps/api/event';
-import { CHANNEL_PANEL_CLOSED, type PanelClosedMessage } from './protocol';
-
-interface DetachedPanel {
-    windowLabel: string;
-    panelId: string;
-    window: WebviewWindow;
-}
-
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import {
+    CHANNEL_PANEL_OPENED,
+    CHANNEL_PANEL_CLOSED,
+    type PanelOpenedMessage,
+    type PanelClosedMessage,
+} from './protocol';
+
+/**
+ * Detached panel and play windows. The backend creates the windows,
+ * remembers where they were and announces them on the panel channels;
+ * this keeps track of which panels are currently detached.
+ */
 export class WindowManager {
-    private detachedPanels_ = new Map<string, DetachedPanel>();
+    private detachedPanels_ = new Map<string, string>();
     private projectPath_: string | null = null;
+    private unlisteners_: UnlistenFn[] = [];
+
+    constructor() {
+        listen<PanelOpenedMessage>(CHANNEL_PANEL_OPENED, (event) => {
+            this.detachedPanels_.set(event.payload.panelId, event.payload.windowLabel);
+        }).then(fn => this.unlisteners_.push(fn));
+        listen<PanelClosedMessage>(CHANNEL_PANEL_CLOSED, (event) => {
+            this.detachedPanels_.delete(event.payload.panelId);
+        }).then(fn => this.unlisteners_.push(fn));
+    }
 
     setProjectPath(projectPath: string | null): void {
         this.projectPath_ = projectPath;
     }
 
     async detachPanel(panelId: string, title: string): Promise<void> {
-        const existing = this.detachedPanels_.get(panelId);
-        if (existing) {
-            await existing.window.setFocus();
-            return;
-        }
-
-        let url = `panel-window.html?panel=${encodeURIComponent(panelId)}`;
-        if (this.projectPath_) {
-            url += `&projectPath=${encodeURIComponent(this.projectPath_)}`;
-        }
-
-        const windowLabel = `panel-${panelId}-${Date.now()}`;
-        const isProfiler = panelId === 'profiler';
-        const webviewWindow = new WebviewWindow(windowLabel, {
-            url,
-            title,
-            width: isProfiler ? 780 : 400,
-            height: isProfiler ? 540 : 600,
-            minWidth: isProfiler ? 500 : 300,
-            minHeight: isProfiler ? 360 : 200,
-            center: true,
-            decorations: true,
-            resizable: true,
-        });
-
-        await this.waitForWindowCreation(webviewWindow);
-
-        const entry: DetachedPanel = {
-            windowLabel,
+        const windowLabel = await invoke<string>('open_panel_window', {
             panelId,
-            window: webviewWindow,
-        };
-
-        this.detachedPanels_.set(panelId, entry);
-
-        webviewWindow.once('tauri://close-requested', async () => {
-            this.detachedPanels_.delete(panelId);
-            const msg: PanelClosedMessage = { panelId, windowLabel };
-            await emit(CHANNEL_PANEL_CLOSED, msg);
-            await webviewWindow.destroy();
+            title,
+            projectPath: this.projectPath_,
         });
+        this.detachedPanels_.set(panelId, windowLabel);
     }
 
     async detachGameView(previewUrl: string): Promise<void> {
-        const panelId = 'game';
-        const existing = this.detachedPanels_.get(panelId);
-        if (existing) {
-            await existing.window.setFocus();
-            return;
-        }
-
-        const windowLabel = `panel-game-${Date.now()}`;
-        const webviewWindow = new WebviewWindow(windowLabel, {
-            url: previewUrl,
-            title: 'Game Preview',
-            width: 800,
-            height: 600,
-            minWidth: 400,
-            minHeight: 300,
-            center: true,
-            decorations: true,
-            resizable: true,
-        });
-
-        await this.waitForWindowCreation(webviewWindow);
-
-        const entry: DetachedPanel = {
-            windowLabel,
-            panelId,
-            window: webviewWindow,
-        };
-
-        this.detachedPanels_.set(panelId, entry);
+        const windowLabel = await invoke<string>('open_play_window', { url: previewUrl });
+        this.detachedPanels_.set('game', windowLabel);
+    }
 
-        webviewWindow.once('tauri://close-requested', async () => {
-            this.detachedPanels_.delete(panelId);
-            const msg: PanelClosedMessage = { panelId, windowLabel };
-            await emit(CHANNEL_PANEL_CLOSED, msg);
-            await webviewWindow.destroy();
-        });
+    /** Reopens the panels that were detached when the editor last closed. */
+    async restore(): Promise<string[]> {
+        const panelIds = await invoke<string[]>('restore_panel_windows', { projectPath: this.projectPath_ });
+        for (const panelId of panelIds) {
+            this.detachedPanels_.set(panelId, `panel-${panelId}`);
+        }
+        return panelIds;
     }
 
     isDetached(panelId: string): boolean {
@@ -104,25 +58,18 @@ export class WindowManager {
     }
 
     async closeAll(): Promise<void> {
-        const entries = Array.from(this.detachedPanels_.values());
         this.detachedPanels_.clear();
-        for (const entry of entries) {
-            try {
-                await entry.window.destroy();
-            } catch {
-                // window may already be closed
-            }
+        try {
+            await invoke('close_panel_windows');
+        } catch {
+            // windows may already be closed
         }
     }
 
-    private waitForWindowCreation(webviewWindow: WebviewWindow): Promise<void> {
-        return new Promise((resolve, reject) => {
-            webviewWindow.once('tauri://created', () => {
-                resolve();
-            });
-            webviewWindow.once('tauri://error', (e) => {
-                reject(new Error(`Failed to create window: ${e.payload}`));
-            });
-        });
+    dispose(): void {
+        for (const unlisten of this.unlisteners_) {
+            unlisten();
+        }
+        this.unlisteners_ = [];
     }
 }
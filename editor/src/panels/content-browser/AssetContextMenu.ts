@@ -8,6 +8,7 @@ import { getAssetLibrary } from '../../asset/AssetLibrary';
 import { showErrorToast } from '../../ui/Toast';
 import { createEmptyScene } from '../../types/SceneTypes';
 import { getSettingsValue } from '../../settings';
+import { getEditorContext } from '../../context/EditorContext';
 import { DEFAULT_DESIGN_WIDTH, DEFAULT_DESIGN_HEIGHT } from 'esengine';
 import type { AssetItem, ContentBrowserState } from './ContentBrowserTypes';
 import { getNativeFS } from './ContentBrowserTypes';
@@ -15,6 +16,10 @@ import { AssetType } from '../../constants/AssetTypes';
 import { getGlobalPathResolver, getAssetDatabase } from '../../asset';
 import { createVariantPrefab, serializePrefab } from '../../prefab';
 
+const REVEAL_LABEL = navigator.platform.includes('Mac')
+    ? 'Reveal in Finder'
+    : navigator.platform.includes('Win') ? 'Show in Explorer' : 'Show in File Manager';
+
 export function showAssetContextMenu(state: ContentBrowserState, e: MouseEvent, path: string, type: AssetItem['type']): void {
     const fs = getNativeFS();
     const parentPath = getParentDir(path);
@@ -22,10 +27,21 @@ export function showAssetContextMenu(state: ContentBrowserState, e: MouseEvent,
 
     const items: ContextMenuItem[] = [
         {
-            label: 'Show in Folder',
+            label: REVEAL_LABEL,
             icon: icons.folderOpen(14),
-            onClick: () => { fs?.openFolder(parentPath); },
+            onClick: () => {
+                if (fs?.revealInFileManager) {
+                    fs.revealInFileManager(path);
+                } else {
+                    fs?.openFolder(parentPath);
+                }
+            },
         },
+        ...(fs?.openTerminal ? [{
+            label: 'Open in Terminal',
+            icon: icons.terminal(14),
+            onClick: () => { fs?.openTerminal?.(parentPath); },
+        }] : []),
         {
             label: 'Copy Path',
             icon: icons.copy(14),
@@ -89,6 +105,7 @@ export function showMultiSelectContextMenu(state: ContentBrowserState, e: MouseE
 
 export function showFolderContextMenu(state: ContentBrowserState, e: MouseEvent, path: string): void {
     const fs = getNativeFS();
+    void loadScriptTemplates(state);
 
     const items: ContextMenuItem[] = [
         {
@@ -97,7 +114,15 @@ export function showFolderContextMenu(state: ContentBrowserState, e: MouseEvent,
             children: [
                 { label: 'Folder', icon: icons.folder(14), onClick: () => createNewFolder(state, path) },
                 { label: '', separator: true },
-                { label: 'Script', icon: icons.code(14), onClick: () => createNewScript(state, path) },
+                {
+                    label: 'Script',
+                    icon: icons.code(14),
+                    children: scriptTemplates.map(template => ({
+                        label: template.name,
+                        icon: icons.code(14),
+                        onClick: () => createNewScript(state, path, template.id),
+                    })),
+                },
                 { label: 'Material', icon: icons.settings(14), onClick: () => createNewMaterial(state, path) },
                 { label: 'Shader', icon: icons.code(14), onClick: () => createNewShader(state, path) },
                 { label: 'BitmapFont', icon: icons.type(14), onClick: () => createNewBitmapFont(state, path) },
@@ -113,6 +138,11 @@ export function showFolderContextMenu(state: ContentBrowserState, e: MouseEvent,
             icon: icons.folderOpen(14),
             onClick: () => { fs?.openFolder(path); },
         },
+        ...(fs?.openTerminal ? [{
+            label: 'Open in Terminal',
+            icon: icons.terminal(14),
+            onClick: () => { fs?.openTerminal?.(path); },
+        }] : []),
         {
             label: 'Copy Path',
             icon: icons.copy(14),
@@ -286,23 +316,49 @@ async function createNewFolder(state: ContentBrowserState, parentPath: string):
     }
 }
 
-async function createNewScript(state: ContentBrowserState, parentPath: string): Promise<void> {
-    const name = await promptFileName('NewScript', '.ts', parentPath);
-    if (!name) return;
+interface ScriptTemplateInfo {
+    id: string;
+    name: string;
+    description: string;
+    source: 'builtin' | 'project';
+}
 
-    const platform = getPlatformAdapter();
-    const filePath = `${parentPath}/${name}`;
+const BUILTIN_SCRIPT_TEMPLATES: ScriptTemplateInfo[] = [
+    { id: 'component', name: 'Component', description: '', source: 'builtin' },
+    { id: 'system', name: 'System', description: '', source: 'builtin' },
+    { id: 'ui-behaviour', name: 'UI Behaviour', description: '', source: 'builtin' },
+];
 
-    const className = toClassName(name);
-    const content = `import { defineComponent } from 'esengine';
+let scriptTemplates = BUILTIN_SCRIPT_TEMPLATES;
 
-export const ${className} = defineComponent('${className}', {
-    value: 0,
-});
-`;
+/** Refreshes the templates offered under Create > Script, including the project's own. */
+async function loadScriptTemplates(state: ContentBrowserState): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    if (!invoke || !state.projectPath) return;
+    try {
+        const templates = await invoke('list_script_templates', {
+            projectDir: getParentDir(state.projectPath),
+        }) as ScriptTemplateInfo[];
+        if (templates.length > 0) scriptTemplates = templates;
+    } catch (err) {
+        console.warn('Failed to list script templates:', err);
+    }
+}
+
+async function createNewScript(state: ContentBrowserState, parentPath: string, template: string): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    if (!invoke || !state.projectPath) return;
+
+    const name = await promptFileName('NewScript', '.ts', parentPath);
+    if (!name) return;
 
     try {
-        await platform.writeTextFile(filePath, content);
+        await invoke('create_script', {
+            projectDir: getParentDir(state.projectPath),
+            folder: parentPath,
+            name,
+            template,
+        });
         state.refresh();
     } catch (err) {
         console.error('Failed to create script:', err);
@@ -539,11 +595,6 @@ async function createAnimClipFromImages(state: ContentBrowserState, images: Asse
     }
 }
 
-function toClassName(filename: string): string {
-    const name = filename.replace(/\.(ts|js)$/, '');
-    return name.charAt(0).toUpperCase() + name.slice(1);
-}
-
 export async function importDroppedFiles(state: ContentBrowserState, files: FileList): Promise<void> {
     const fs = getNativeFS();
     if (!fs || !state.currentPath) return;
@@ -16,6 +16,11 @@ import {
 import { DisposableStore } from '../../utils/Disposable';
 import { getPrefabDependencyTracker } from '../../prefab';
 import { getNavigationService } from '../../services';
+import { showErrorToast } from '../../ui/Toast';
+import { errorMessage } from '../../utils/errors';
+
+/** Asset types double-click opens in an external editor (see `externalEditors.*`). */
+const EXTERNAL_EDITOR_TYPES = new Set(['script', 'shader', 'json', 'image', 'audio']);
 
 export class ContentBrowserPanel implements ContentBrowserState {
     container: HTMLElement;
@@ -712,15 +717,11 @@ export class ContentBrowserPanel implements ContentBrowserState {
         }
 
         const shell = getNativeShell();
-        if (!shell) return;
+        const projectDir = this.rootFolder?.path;
+        if (!shell || !projectDir || !EXTERNAL_EDITOR_TYPES.has(type)) return;
 
-        if (type === 'script') {
-            const projectDir = this.rootFolder?.path;
-            if (projectDir) {
-                shell.openInEditor(projectDir, path);
-            }
-        } else if (type === 'shader' || type === 'json') {
-            shell.openFile(path);
-        }
+        shell.openInEditor(projectDir, path).catch((err) => {
+            showErrorToast('Failed to open in external editor', errorMessage(err));
+        });
     }
 }
@@ -6,7 +6,7 @@ import { getDisplayType, getAssetTypeIcon } from '../../asset/AssetTypeLookup';
 
 export interface NativeShell {
     openFile(path: string): Promise<void>;
-    openInEditor(projectPath: string, filePath: string): Promise<void>;
+    openInEditor(projectPath: string, filePath: string, line?: number): Promise<void>;
 }
 
 export interface FolderNode {
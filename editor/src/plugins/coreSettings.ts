@@ -17,14 +17,23 @@ export const coreSettingsPlugin: EditorPlugin = {
         registerSettingsSection({ id: 'rendering', title: 'Rendering', icon: 'image', order: 2.5 });
         registerSettingsSection({ id: 'physics', title: 'Physics', icon: 'zap', order: 3 });
         registerSettingsSection({ id: 'build', title: 'Build', icon: 'package', order: 4 });
+        registerSettingsSection({ id: 'formatting', title: 'Formatting', icon: 'code', order: 4.5 });
         registerSettingsSection({ id: 'asset-loading', title: 'Asset Loading', icon: 'download', order: 6.5 });
         registerSettingsSection({ id: 'network', title: 'Network', icon: 'globe', order: 7 });
+        registerSettingsSection({ id: 'notifications', title: 'Notifications', icon: 'info', order: 7.5 });
+        registerSettingsSection({ id: 'external-editors', title: 'External Editors', icon: 'code', order: 7.55 });
+        registerSettingsSection({ id: 'global-shortcuts', title: 'Global Shortcuts', icon: 'link', order: 7.6 });
+        registerSettingsSection({ id: 'privacy', title: 'Privacy', icon: 'shield', order: 8 });
+        registerSettingsSection({ id: 'storage', title: 'Storage', icon: 'trash', order: 8.5 });
+        registerSettingsSection({ id: 'plugins', title: 'Plugins', icon: 'box', order: 8.7 });
+        registerSettingsSection({ id: 'mcp', title: 'AI Assistants', icon: 'terminal', order: 8.8 });
 
         registerSettingsGroup({ id: 'physics.collision-layers', section: 'physics', label: 'Collision Layers', order: 10, collapsed: true });
         registerSettingsGroup({ id: 'physics.collision-matrix', section: 'physics', label: 'Collision Matrix', order: 11 });
 
-        registerSettingsItem({ id: 'general.language', section: 'general', label: 'Language', type: 'select', defaultValue: 'en', order: 0, options: [{ label: 'English', value: 'en' }] });
+        registerSettingsItem({ id: 'general.language', section: 'general', label: 'Language', type: 'select', defaultValue: 'en', order: 0, options: [{ label: 'English', value: 'en' }, { label: '中文', value: 'zh' }] });
         registerSettingsItem({ id: 'general.previewPort', section: 'general', label: 'Preview Port', type: 'number', defaultValue: 3456, min: 1024, max: 65535, step: 1, order: 1 });
+        registerSettingsItem({ id: 'general.updateChannel', section: 'general', label: 'Update Channel', description: 'Beta and nightly builds get features earlier and are less tested', type: 'select', defaultValue: 'stable', order: 2, options: [{ label: 'Stable', value: 'stable' }, { label: 'Beta', value: 'beta' }, { label: 'Nightly', value: 'nightly' }] });
 
         registerSettingsItem({ id: 'project.spineVersion', section: 'project', label: 'Spine Version', type: 'select', defaultValue: 'none', order: 0, projectSync: true, options: [{ label: 'None', value: 'none' }, { label: 'Spine 4.2', value: '4.2' }, { label: 'Spine 4.1', value: '4.1' }, { label: 'Spine 3.8', value: '3.8' }] });
         registerSettingsItem({ id: 'project.name', section: 'project', label: 'Project Name', type: 'string', defaultValue: '', order: 1, projectSync: true });
@@ -45,6 +54,7 @@ export const coreSettingsPlugin: EditorPlugin = {
         }
 
         registerSettingsItem({ id: 'physics.collisionMatrix', section: 'physics', group: 'physics.collision-matrix', label: 'Collision Matrix', type: 'custom' as SettingsItemType, defaultValue: null, render: renderCollisionMatrix, tags: ['collision', 'matrix', 'layer'] });
+        registerSettingsItem({ id: 'physics.backend', section: 'physics', label: 'Backend', description: 'Rapier runs identically on every platform, for lockstep multiplayer', type: 'select', defaultValue: 'box2d', order: 0, projectSync: true, options: [{ label: 'Box2D', value: 'box2d' }, { label: 'Rapier', value: 'rapier' }] });
         registerSettingsItem({ id: 'physics.gravityX', section: 'physics', label: 'Gravity X', type: 'number', defaultValue: 0, step: 0.1, order: 1, projectSync: true });
         registerSettingsItem({ id: 'physics.gravityY', section: 'physics', label: 'Gravity Y', type: 'number', defaultValue: -9.81, step: 0.1, order: 2, projectSync: true });
         registerSettingsItem({ id: 'physics.fixedTimestep', section: 'physics', label: 'Fixed Timestep', description: 'Maximum simulation time step in seconds', type: 'number', defaultValue: 1 / 60, step: 0.001, min: 0.001, order: 3, projectSync: true });
@@ -60,5 +70,36 @@ export const coreSettingsPlugin: EditorPlugin = {
         registerSettingsItem({ id: 'asset.failureCooldown', section: 'asset-loading', label: 'Failure Cooldown', description: 'Time to wait before retrying a failed asset load in milliseconds', type: 'number', defaultValue: 5000, min: 0, max: 60000, step: 1000, order: 1, projectSync: true });
 
         registerSettingsItem({ id: 'network.proxy', section: 'network', label: 'HTTP Proxy', description: 'Proxy for update downloads (e.g. http://127.0.0.1:7890)', type: 'string', defaultValue: '', order: 0 });
+
+        registerSettingsItem({ id: 'notifications.build', section: 'notifications', label: 'Builds', description: 'Notify when an export or script bundle finishes while the editor is in the background', type: 'boolean', defaultValue: true, order: 0 });
+        registerSettingsItem({ id: 'notifications.import', section: 'notifications', label: 'Imports', description: 'Notify when an asset import finishes while the editor is in the background', type: 'boolean', defaultValue: true, order: 1 });
+        registerSettingsItem({ id: 'externalEditors.script', section: 'external-editors', label: 'Scripts', description: 'Command line for .ts and .js files; {file}, {line}, {dir} and {project} are filled in. Empty opens the system default app', type: 'string', defaultValue: 'code {project} --goto {file}:{line}', order: 0 });
+        registerSettingsItem({ id: 'externalEditors.shader', section: 'external-editors', label: 'Shaders', description: 'Command line for shader files, e.g. code --goto {file}:{line}. Empty opens the system default app', type: 'string', defaultValue: '', order: 1 });
+        registerSettingsItem({ id: 'externalEditors.data', section: 'external-editors', label: 'Data Files', description: 'Command line for JSON, YAML, CSV and text files. Empty opens the system default app', type: 'string', defaultValue: '', order: 2 });
+        registerSettingsItem({ id: 'externalEditors.image', section: 'external-editors', label: 'Images', description: 'Command line for images, e.g. aseprite {file}. Empty opens the system default app', type: 'string', defaultValue: '', order: 3 });
+        registerSettingsItem({ id: 'externalEditors.audio', section: 'external-editors', label: 'Audio', description: 'Command line for audio files. Empty opens the system default app', type: 'string', defaultValue: '', order: 4 });
+        registerSettingsItem({ id: 'globalShortcuts.app.focus-editor', section: 'global-shortcuts', label: 'Focus Editor', description: 'Bring the editor to the front from any app, e.g. the preview in a browser. Leave empty to turn off', type: 'string', defaultValue: 'CmdOrCtrl+Alt+Shift+E', order: 0 });
+        registerSettingsItem({ id: 'globalShortcuts.preview.reload', section: 'global-shortcuts', label: 'Reload Preview', description: 'Reload every open preview from any app. Leave empty to turn off', type: 'string', defaultValue: 'CmdOrCtrl+Alt+Shift+R', order: 1 });
+        registerSettingsItem({ id: 'globalShortcuts.view.devtools', section: 'global-shortcuts', label: 'Toggle Developer Tools', description: 'Open or close the developer tools of the editor window from any app. Leave empty to turn off', type: 'string', defaultValue: 'CmdOrCtrl+Alt+Shift+I', order: 2 });
+        registerSettingsItem({ id: 'globalShortcuts.file.preview', section: 'global-shortcuts', label: 'Preview', description: 'Start the preview from any app, e.g. CmdOrCtrl+Alt+Shift+P', type: 'string', defaultValue: '', order: 3 });
+        registerSettingsItem({ id: 'notifications.upload', section: 'notifications', label: 'Uploads', description: 'Notify when a WeChat upload, itch.io publish or deploy finishes while the editor is in the background', type: 'boolean', defaultValue: true, order: 2 });
+
+        registerSettingsItem({ id: 'format.onSave', section: 'formatting', label: 'Canonicalize Scenes on Save', description: 'Write scene and prefab keys in a fixed order, so diffs only show real changes', type: 'boolean', defaultValue: false, order: 0 });
+        registerSettingsItem({ id: 'format.indentWidth', section: 'formatting', label: 'Indent Width', description: 'Spaces per indentation level in scripts and JSON files', type: 'number', defaultValue: 4, min: 1, max: 8, step: 1, order: 1 });
+        registerSettingsItem({ id: 'format.lineWidth', section: 'formatting', label: 'Line Width', description: 'Column at which the formatter wraps lines', type: 'number', defaultValue: 120, min: 40, max: 400, step: 1, order: 2 });
+
+        registerSettingsItem({ id: 'privacy.telemetry', section: 'privacy', label: 'Share Anonymous Usage Data', description: 'Send which features are used and which kinds of errors occur, with no paths, names or project content. Help > Telemetry Log shows everything recorded', type: 'boolean', defaultValue: false, order: 0 });
+        registerSettingsItem({ id: 'privacy.telemetryEndpoint', section: 'privacy', label: 'Telemetry Endpoint', description: 'Where usage data is sent; empty for the default', type: 'string', defaultValue: '', order: 1 });
+
+        registerSettingsItem({ id: 'storage.autoCleanup', section: 'storage', label: 'Clean Up Automatically', description: 'Remove old caches, build files, autosaves and logs when the editor starts and when a project opens', type: 'boolean', defaultValue: true, order: 0 });
+        registerSettingsItem({ id: 'storage.maxAgeDays', section: 'storage', label: 'Keep Files For', description: 'Days before an unchanged cache, build, autosave or log file is removed', type: 'number', defaultValue: 30, min: 1, max: 365, step: 1, order: 1 });
+        registerSettingsItem({ id: 'storage.maxSizeMb', section: 'storage', label: 'Maximum Size per Category', description: 'Megabytes each kind of data may use before its oldest files are removed', type: 'number', defaultValue: 1024, min: 16, max: 65536, step: 16, order: 2 });
+
+        registerSettingsItem({ id: 'plugins.marketplace', section: 'plugins', label: 'Marketplace', description: 'URL of the plugin marketplace index; empty for the default', type: 'string', defaultValue: '', order: 0 });
+        registerSettingsItem({ id: 'plugins.trustedKeys', section: 'plugins', label: 'Trusted Keys', description: 'Comma-separated base64 Ed25519 public keys whose signed plugins may be installed, besides the built-in ones', type: 'string', defaultValue: '', order: 1 });
+
+        registerSettingsItem({ id: 'mcp.enabled', section: 'mcp', label: 'Enable MCP Server', description: 'Let AI assistants list and read scenes, search assets, edit entities and run builds over the Model Context Protocol. Takes effect when a project opens', type: 'boolean', defaultValue: false, order: 0 });
+        registerSettingsItem({ id: 'mcp.port', section: 'mcp', label: 'Port', description: 'First port tried for http://127.0.0.1:<port>/mcp', type: 'number', defaultValue: 9930, min: 1024, max: 65535, step: 1, order: 1 });
+        registerSettingsItem({ id: 'mcp.confirmWrites', section: 'mcp', label: 'Confirm Changes', description: 'Ask before an assistant edits a scene or starts a build', type: 'boolean', defaultValue: true, order: 2 });
     },
 };
@@ -3,6 +3,8 @@ import {
     type PropertyEditorInstance,
 } from '../PropertyEditor';
 import { colorToHex, hexToColor } from '../editorUtils';
+import { icons } from '../../utils/icons';
+import { formatHex, parseHexInput, showPaletteMenu, type ParsedHex } from './paletteMenu';
 
 type RGBA = { r: number; g: number; b: number; a: number };
 
@@ -72,22 +74,52 @@ export function createColorEditor(
         applyColor(colorInput.value, parseFloat(alphaInput.value) || 1);
     });
 
-    hexInput.addEventListener('keydown', (e) => {
-        if (e.key !== 'Enter') return;
-        let hex = hexInput.value.trim();
-        if (!hex.startsWith('#')) hex = '#' + hex;
-        if (/^#[0-9a-fA-F]{6}$/.test(hex)) {
-            colorInput.value = hex;
-            hexInput.value = hex.toUpperCase();
-            applyColor(hex, parseFloat(alphaInput.value) || 1);
+    const applyParsed = (parsed: ParsedHex) => {
+        const alpha = parsed.alpha ?? (parseFloat(alphaInput.value) || 1);
+        colorInput.value = parsed.hex;
+        hexInput.value = parsed.hex.toUpperCase();
+        alphaInput.value = String(Math.round(alpha * 100) / 100);
+        applyColor(parsed.hex, alpha);
+    };
+
+    const commitHexInput = () => {
+        const parsed = parseHexInput(hexInput.value);
+        if (parsed) {
+            applyParsed(parsed);
         } else {
             hexInput.value = colorInput.value.toUpperCase();
         }
+    };
+
+    hexInput.addEventListener('keydown', (e) => {
+        if (e.key !== 'Enter') return;
+        commitHexInput();
+    });
+
+    hexInput.addEventListener('paste', (e) => {
+        const text = e.clipboardData?.getData('text') ?? '';
+        if (!parseHexInput(text)) return;
+        e.preventDefault();
+        hexInput.value = text.trim();
+        commitHexInput();
+    });
+
+    const paletteBtn = document.createElement('button');
+    paletteBtn.className = 'es-btn es-btn-icon es-btn-palette';
+    paletteBtn.title = 'Palettes';
+    paletteBtn.innerHTML = icons.palette(12);
+    paletteBtn.addEventListener('click', () => {
+        showPaletteMenu(
+            paletteBtn,
+            () => formatHex(colorInput.value, parseFloat(alphaInput.value) || 1),
+            applyParsed,
+        );
     });
 
     wrapper.appendChild(swatch);
     wrapper.appendChild(hexInput);
     wrapper.appendChild(alphaInput);
+    wrapper.appendChild(paletteBtn);
     container.appendChild(wrapper);
 
     return {
@@ -0,0 +1,178 @@
+import { icons } from '../../utils/icons';
+import { showContextMenu, type ContextMenuItem } from '../../ui/ContextMenu';
+import { showInputDialog } from '../../ui/dialog';
+import { showErrorToast } from '../../ui/Toast';
+import { getEditorContext } from '../../context/EditorContext';
+import { getPlatformAdapter } from '../../platform/PlatformAdapter';
+
+// =============================================================================
+// Types
+// =============================================================================
+
+export interface Swatch {
+    color: string;
+    name?: string;
+}
+
+export interface Palette {
+    name: string;
+    swatches: Swatch[];
+}
+
+export interface ParsedHex {
+    /** `#rrggbb` */
+    hex: string;
+    /** Set when the input had an alpha channel. */
+    alpha: number | null;
+}
+
+const SWATCH_FILTERS = [{ name: 'Swatches', extensions: ['gpl', 'ase'] }];
+
+// =============================================================================
+// Hex
+// =============================================================================
+
+/** Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, with or without `#`. */
+export function parseHexInput(input: string): ParsedHex | null {
+    let hex = input.trim().replace(/^#/, '');
+    if (!/^[0-9a-fA-F]+$/.test(hex)) return null;
+    if (hex.length === 3 || hex.length === 4) {
+        hex = hex.split('').map(c => c + c).join('');
+    }
+    if (hex.length !== 6 && hex.length !== 8) return null;
+    const alpha = hex.length === 8 ? parseInt(hex.slice(6, 8), 16) / 255 : null;
+    return { hex: `#${hex.slice(0, 6).toLowerCase()}`, alpha };
+}
+
+/** `#rrggbb`, with `aa` appended when not opaque. */
+export function formatHex(hex: string, alpha: number): string {
+    if (alpha >= 1) return hex;
+    const a = Math.max(0, Math.min(255, Math.round(alpha * 255)));
+    return hex + a.toString(16).padStart(2, '0');
+}
+
+// =============================================================================
+// Menu
+// =============================================================================
+
+/**
+ * Shows the palette library under `anchor`: picking a swatch applies it,
+ * and the current color can be added to a palette.
+ */
+export async function showPaletteMenu(
+    anchor: HTMLElement,
+    currentColor: () => string,
+    apply: (parsed: ParsedHex) => void,
+): Promise<void> {
+    const invoke = getEditorContext().invoke;
+    if (!invoke) return;
+
+    let palettes: Palette[];
+    try {
+        palettes = await invoke('list_palettes') as Palette[];
+    } catch (err) {
+        showErrorToast('Failed to load palettes', String(err));
+        return;
+    }
+
+    const run = async (cmd: string, args: Record<string, unknown>, failure: string) => {
+        try {
+            await invoke(cmd, args);
+        } catch (err) {
+            showErrorToast(failure, String(err));
+        }
+    };
+
+    const addToNewPalette = async () => {
+        const name = await showInputDialog({
+            title: 'New Palette',
+            placeholder: 'Palette name',
+            confirmText: 'Create',
+            validator: async (value) => {
+                if (!value.trim()) return 'Name is required';
+                if (palettes.some(p => p.name === value.trim())) return 'A palette with this name already exists';
+                return null;
+            },
+        });
+        if (name) {
+            await run('add_palette_color', { palette: name.trim(), color: currentColor() }, 'Failed to add color');
+        }
+    };
+
+    const importPalette = async () => {
+        const path = await getPlatformAdapter().openFileDialog({ title: 'Import Palette', filters: SWATCH_FILTERS });
+        if (path) await run('import_palette', { path }, 'Failed to import palette');
+    };
+
+    const exportPalette = async (palette: Palette) => {
+        const path = await getPlatformAdapter().openSaveDialog({
+            title: 'Export Palette',
+            defaultPath: `${palette.name}.gpl`,
+            filters: SWATCH_FILTERS,
+        });
+        if (path) await run('export_palette', { name: palette.name, path }, 'Failed to export palette');
+    };
+
+    const items: ContextMenuItem[] = palettes.map(palette => ({
+        label: palette.name,
+        icon: icons.palette(14),
+        children: palette.swatches.length > 0
+            ? palette.swatches.map(swatch => ({
+                label: swatch.name || swatch.color.toUpperCase(),
+                icon: swatchIcon(swatch.color),
+                onClick: () => {
+                    const parsed = parseHexInput(swatch.color);
+                    if (parsed) apply(parsed);
+                },
+            }))
+            : [{ label: 'Empty', disabled: true }],
+    }));
+    if (items.length === 0) {
+        items.push({ label: 'No Palettes', disabled: true });
+    }
+
+    items.push(
+        { label: '', separator: true },
+        {
+            label: 'Add Color to Palette',
+            icon: icons.plus(14),
+            children: [
+                ...palettes.map(palette => ({
+                    label: palette.name,
+                    onClick: () => run(
+                        'add_palette_color',
+                        { palette: palette.name, color: currentColor() },
+                        'Failed to add color',
+                    ),
+                })),
+                ...(palettes.length > 0 ? [{ label: '', separator: true }] : []),
+                { label: 'New Palette...', onClick: addToNewPalette },
+            ],
+        },
+        { label: 'Import Palette...', onClick: importPalette },
+    );
+    if (palettes.length > 0) {
+        items.push(
+            {
+                label: 'Export Palette',
+                children: palettes.map(palette => ({ label: palette.name, onClick: () => exportPalette(palette) })),
+            },
+            {
+                label: 'Delete Palette',
+                children: palettes.map(palette => ({
+                    label: palette.name,
+                    onClick: () => run('delete_palette', { name: palette.name }, 'Failed to delete palette'),
+                })),
+            },
+        );
+    }
+
+    const rect = anchor.getBoundingClientRect();
+    showContextMenu({ x: rect.left, y: rect.bottom, items });
+}
+
+function swatchIcon(color: string): string {
+    const parsed = parseHexInput(color);
+    const fill = parsed ? parsed.hex : '#000000';
+    return `<span class="es-palette-swatch" style="background:${fill}"></span>`;
+}
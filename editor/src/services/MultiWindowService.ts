@@ -54,6 +54,12 @@ export class MultiWindowService {
             });
         }
 
+        this.windowManager_.restore().then((panelIds) => {
+            for (const panelId of panelIds) {
+                dockLayout?.removePanel(panelId);
+            }
+        }).catch((e) => console.warn('Failed to restore detached panels:', e));
+
         this.setupCloseHandler_(onUnsavedClose);
     }
 
@@ -69,6 +75,9 @@ export class MultiWindowService {
                         console.error('Close handler error:', e);
                     }
                 }
+                // Running jobs keep the editor alive in the tray until they finish.
+                const { invoke } = await import('@tauri-apps/api/core');
+                if (await invoke<boolean>('close_to_tray').catch(() => false)) return;
                 await this.windowManager_?.closeAll();
                 mainWindow.destroy();
             });
@@ -79,6 +88,7 @@ export class MultiWindowService {
         this.closeUnlisten_?.();
         this.closeUnlisten_ = null;
         this.windowManager_?.closeAll();
+        this.windowManager_?.dispose();
         this.mainWindowBridge_?.dispose();
     }
 }
@@ -2,6 +2,7 @@ import { PreviewManager } from '../PreviewManager';
 import { showToast, showErrorToast } from '../ui/Toast';
 import { hasFileHandle } from '../io/SceneSerializer';
 import type { EditorStore } from '../store/EditorStore';
+import { errorMessage } from '../utils/errors';
 import type { ScriptService } from './ScriptService';
 import type { SpineService } from './SpineService';
 
@@ -48,7 +49,7 @@ export class PreviewService {
                 this.updatePreviewUrl_();
             }
         } catch (err) {
-            showErrorToast(`Preview failed: ${err instanceof Error ? err.message : String(err)}`);
+            showErrorToast(`Preview failed: ${errorMessage(err)}`);
         }
     }
 
@@ -65,7 +66,7 @@ export class PreviewService {
             this.updatePreviewUrl_();
             return this.previewUrl_;
         } catch (err) {
-            showErrorToast(`Preview server failed: ${err instanceof Error ? err.message : String(err)}`);
+            showErrorToast(`Preview server failed: ${errorMessage(err)}`);
             return null;
         }
     }
@@ -3,6 +3,8 @@ import { isAbsolutePath, joinPath, getProjectDir } from '../utils/path';
 import { getAssetLibrary } from '../asset/AssetLibrary';
 import { getSettingsValue } from '../settings';
 import { showDialog } from '../ui/dialog';
+import { showToast } from '../ui/Toast';
+import { UndoJournal } from '../store/UndoJournal';
 import { loadEditorLocalSettings, saveEditorLocalSetting } from '../launcher/ProjectService';
 import type { EditorStore } from '../store/EditorStore';
 import type { PreviewService } from './PreviewService';
@@ -16,10 +18,12 @@ export class SceneService {
     private assetLibraryReady_: Promise<void> = Promise.resolve();
     private scriptsReady_: Promise<void> = Promise.resolve();
     private dirtyCheckers_: DirtyPanelChecker[] = [];
+    private undoJournal_: UndoJournal | null;
 
     constructor(store: EditorStore, projectPath: string | null) {
         this.store_ = store;
         this.projectPath_ = projectPath;
+        this.undoJournal_ = projectPath ? new UndoJournal(store, getProjectDir(projectPath)) : null;
     }
 
     registerDirtyChecker(checker: DirtyPanelChecker): () => void {
@@ -59,6 +63,7 @@ export class SceneService {
         }
         const w = getSettingsValue<number>('project.designWidth');
         const h = getSettingsValue<number>('project.designHeight');
+        this.undoJournal_?.close();
         this.store_.newScene('Untitled', { width: w, height: h });
         clearFileHandle();
     }
@@ -91,6 +96,7 @@ export class SceneService {
 
     async saveSceneAs(): Promise<void> {
         clearFileHandle();
+        this.undoJournal_?.close();
         const savedPath = await saveSceneToFile(this.store_.scene);
         if (savedPath) {
             this.store_.markSaved(savedPath);
@@ -107,6 +113,7 @@ export class SceneService {
         const scene = await loadSceneFromFile();
         if (scene) {
             await getAssetLibrary().migrateScene(scene);
+            this.undoJournal_?.close();
             this.store_.loadScene(scene);
         }
     }
@@ -129,9 +136,19 @@ export class SceneService {
             if (migrated) {
                 await saveSceneToPath(scene, resolvedPath);
             }
+            this.undoJournal_?.close();
             this.store_.loadScene(scene, resolvedPath);
             this.saveLastOpenedScene_(scenePath);
             console.log('Scene loaded:', resolvedPath);
+
+            const restored = await this.undoJournal_?.open(resolvedPath) ?? 0;
+            if (restored > 0) {
+                showToast({
+                    type: 'info',
+                    title: `Restored ${restored} undo step${restored === 1 ? '' : 's'} from the last session`,
+                    duration: 3000,
+                });
+            }
         }
     }
 
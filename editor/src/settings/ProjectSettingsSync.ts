@@ -2,7 +2,7 @@ import { getSettingsValue, setSettingsValue, onSettingsChange } from './Settings
 import { loadProjectConfig } from '../launcher/ProjectService';
 import { getEditorContext } from '../context/EditorContext';
 import { DEFAULT_DESIGN_WIDTH, DEFAULT_DESIGN_HEIGHT } from 'esengine';
-import type { ProjectConfig, SpineVersion } from '../types/ProjectTypes';
+import type { PhysicsBackend, ProjectConfig, SpineVersion } from '../types/ProjectTypes';
 import { MAX_COLLISION_LAYERS } from './collisionLayers';
 
 type SettingMapping = {
@@ -37,6 +37,11 @@ const SETTINGS_TO_PROJECT_MAP: SettingMapping[] = [
         read: c => c.enablePhysics ?? false,
         write: (c, v) => { c.enablePhysics = v as boolean; },
     },
+    {
+        settingId: 'physics.backend',
+        read: c => c.physicsBackend ?? 'box2d',
+        write: (c, v) => { c.physicsBackend = v as PhysicsBackend; },
+    },
     {
         settingId: 'physics.gravityX',
         read: c => c.physicsGravityX ?? 0,
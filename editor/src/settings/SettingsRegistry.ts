@@ -83,16 +83,65 @@ function loadFromStorage(): void {
     } catch (e) { console.warn('[Settings] Failed to load settings, using defaults:', e); }
 }
 
+/**
+ * Persists settings outside the webview, e.g. in the desktop backend, so
+ * they survive a cleared WebView cache.
+ */
+export interface SettingsBackend {
+    load(): Promise<Record<string, unknown> | null>;
+    save(values: Record<string, unknown>): Promise<void>;
+    /** Reports values changed elsewhere, e.g. in another window. */
+    onExternalChange?(listener: (values: Record<string, unknown>) => void): void;
+}
+
+let backend_: SettingsBackend | null = null;
+
 function saveToStorage(): void {
+    const data: Record<string, unknown> = {};
+    for (const [key, value] of values_) {
+        data[key] = value;
+    }
+    if (backend_) {
+        backend_.save(data).catch(e => console.warn('[Settings] Failed to save settings:', e));
+        return;
+    }
     try {
-        const data: Record<string, unknown> = {};
-        for (const [key, value] of values_) {
-            data[key] = value;
-        }
         localStorage.setItem(STORAGE_KEY, JSON.stringify(data));
     } catch (e) { console.warn('[Settings] Failed to save settings:', e); }
 }
 
+function applyValue(id: string, value: unknown): void {
+    const item = getEditorContainer().get(SETTINGS_ITEM, id);
+    if (item) value = validateValue(item, value);
+    if (values_.get(id) === value) return;
+    values_.set(id, value);
+    item?.onChange?.(value);
+    for (const listener of listeners_) {
+        listener(id, value);
+    }
+}
+
+/**
+ * Moves settings storage to `backend`. Values saved there win; on its first
+ * use it takes over the values from localStorage.
+ */
+export async function setSettingsBackend(backend: SettingsBackend): Promise<void> {
+    const stored = await backend.load();
+    backend_ = backend;
+    if (stored && Object.keys(stored).length > 0) {
+        for (const [id, value] of Object.entries(stored)) {
+            applyValue(id, value);
+        }
+    } else {
+        saveToStorage();
+    }
+    backend.onExternalChange?.((values) => {
+        for (const [id, value] of Object.entries(values)) {
+            applyValue(id, value);
+        }
+    });
+}
+
 loadFromStorage();
 migrateLegacySettings();
 
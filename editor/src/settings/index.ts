@@ -3,6 +3,7 @@ export {
     type SettingsSectionDescriptor,
     type SettingsGroupDescriptor,
     type SettingsItemDescriptor,
+    type SettingsBackend,
     registerSettingsSection,
     registerSettingsGroup,
     registerSettingsItem,
@@ -21,6 +22,7 @@ export {
     importSettings,
     getItemDescriptor,
     getGroupDescriptor,
+    setSettingsBackend,
 } from './SettingsRegistry';
 
 export { showSettingsDialog } from './SettingsDialog';
@@ -1,7 +1,7 @@
 import type { Entity } from 'esengine';
 import type { SceneData, EntityData, ComponentData } from '../types/SceneTypes';
 import { createEmptyScene } from '../types/SceneTypes';
-import { CommandHistory, type Command } from '../commands';
+import { CommandHistory, CommandRegistry, type Command, type HistoryObserver, type SerializedCommand } from '../commands';
 import { WorldTransformCache } from '../transform/WorldTransformCache';
 import type { TransformValue } from '../math/Transform';
 import { SelectionService } from './SelectionService';
@@ -613,6 +613,35 @@ export class EditorStore {
         }
     }
 
+    setHistoryObserver(observer: HistoryObserver | null): void {
+        this.history_.setObserver(observer);
+    }
+
+    /**
+     * Restores history serialized by an earlier session onto the open
+     * scene. A command that no longer deserializes cuts the history there.
+     * Returns the number of steps restored.
+     */
+    restoreHistory(undo: SerializedCommand[], redo: SerializedCommand[]): number {
+        const deserialize = (serialized: SerializedCommand) =>
+            CommandRegistry.deserialize(serialized, this.state_.scene, this.entityMap_);
+        const undoCommands: Command[] = [];
+        for (let i = undo.length - 1; i >= 0; i--) {
+            const cmd = deserialize(undo[i]);
+            if (!cmd) break;
+            undoCommands.unshift(cmd);
+        }
+        const redoCommands: Command[] = [];
+        for (let i = redo.length - 1; i >= 0; i--) {
+            const cmd = deserialize(redo[i]);
+            if (!cmd) break;
+            redoCommands.unshift(cmd);
+        }
+        this.history_.restore(undoCommands, redoCommands);
+        this.notify('scene');
+        return undoCommands.length + redoCommands.length;
+    }
+
     // =========================================================================
     // Subscription
     // =========================================================================
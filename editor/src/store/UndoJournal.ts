@@ -0,0 +1,127 @@
+/**
+ * @file    UndoJournal.ts
+ * @brief   Keeps the undo history of the open scene on disk across crashes
+ */
+
+import type { HistoryEvent, SerializedCommand } from '../commands';
+import { getEditorContext } from '../context/EditorContext';
+import { normalizePath } from '../utils/path';
+import type { EditorStore } from './EditorStore';
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+const FLUSH_DELAY = 500;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+interface UndoHistory {
+    undo: SerializedCommand[];
+    redo: SerializedCommand[];
+}
+
+// =============================================================================
+// UndoJournal
+// =============================================================================
+
+export class UndoJournal {
+    private store_: EditorStore;
+    private projectDir_: string;
+    private scene_: string | null = null;
+    private filePath_: string | null = null;
+    private pending_: HistoryEvent[] = [];
+    private flushTimer_: ReturnType<typeof setTimeout> | null = null;
+    private queue_: Promise<void> = Promise.resolve();
+
+    constructor(store: EditorStore, projectDir: string) {
+        this.store_ = store;
+        this.projectDir_ = normalizePath(projectDir);
+    }
+
+    /**
+     * Starts journaling the scene just loaded from `filePath`. If the editor
+     * did not shut down cleanly while it was open, the history it left is
+     * restored. Returns the number of restored steps.
+     */
+    async open(filePath: string): Promise<number> {
+        this.close();
+        const invoke = getEditorContext().invoke;
+        const scene = this.relativePath_(filePath);
+        if (!invoke || !scene) return 0;
+
+        this.scene_ = scene;
+        this.filePath_ = normalizePath(filePath);
+        let restored = 0;
+        await this.enqueue_(async () => {
+            const history = await invoke('open_undo_journal', {
+                projectDir: this.projectDir_,
+                scene,
+            }) as UndoHistory | null;
+            if (history && this.scene_ === scene) {
+                restored = this.store_.restoreHistory(history.undo, history.redo);
+            }
+        });
+        if (this.scene_ === scene) {
+            this.store_.setHistoryObserver(event => this.record_(event));
+        }
+        return restored;
+    }
+
+    /** Stops journaling; a scene closed this way leaves nothing to restore. */
+    close(): void {
+        const scene = this.scene_;
+        if (!scene) return;
+        this.store_.setHistoryObserver(null);
+        this.flush_();
+        this.scene_ = null;
+        this.filePath_ = null;
+
+        const invoke = getEditorContext().invoke;
+        if (invoke) {
+            this.enqueue_(() => invoke('close_undo_journal', { projectDir: this.projectDir_, scene }));
+        }
+    }
+
+    private record_(event: HistoryEvent): void {
+        // Prefab editing borrows the history; only its clearing concerns the scene.
+        const current = this.store_.filePath ? normalizePath(this.store_.filePath) : null;
+        if (current !== this.filePath_ && event.type !== 'clear') return;
+
+        this.pending_.push(event);
+        if (event.type === 'save') {
+            this.flush_();
+        } else if (!this.flushTimer_) {
+            this.flushTimer_ = setTimeout(() => this.flush_(), FLUSH_DELAY);
+        }
+    }
+
+    private flush_(): void {
+        if (this.flushTimer_) {
+            clearTimeout(this.flushTimer_);
+            this.flushTimer_ = null;
+        }
+        const invoke = getEditorContext().invoke;
+        if (!invoke || !this.scene_ || this.pending_.length === 0) return;
+
+        const args = { projectDir: this.projectDir_, scene: this.scene_, ops: this.pending_ };
+        this.pending_ = [];
+        this.enqueue_(() => invoke('append_undo_journal', args));
+    }
+
+    private enqueue_(task: () => Promise<unknown>): Promise<void> {
+        this.queue_ = this.queue_.then(task).then(
+            () => undefined,
+            (err) => console.warn('Undo journal:', err),
+        );
+        return this.queue_;
+    }
+
+    private relativePath_(filePath: string): string | null {
+        const path = normalizePath(filePath);
+        const prefix = this.projectDir_.endsWith('/') ? this.projectDir_ : `${this.projectDir_}/`;
+        return path.startsWith(prefix) ? path.slice(prefix.length) : null;
+    }
+}
@@ -34,6 +34,10 @@ export interface NativeFS {
         options?: { recursive?: boolean }
     ): Promise<() => void>;
     openFolder(path: string): Promise<boolean>;
+    /** Opens the folder of a file in the system file manager with the file selected. */
+    revealInFileManager?(path: string): Promise<boolean>;
+    /** Opens the system terminal in a folder, or in the folder of a file. */
+    openTerminal?(path: string): Promise<boolean>;
     openFile?(path: string): Promise<void>;
     showOpenDialog?(options: { title?: string; filters?: Array<{ name: string; extensions: string[] }> }): Promise<string[] | null>;
     getResourcePath(): Promise<string>;
@@ -54,8 +58,9 @@ export interface NativeFS {
     getEditorDts(): Promise<string>;
     getSpineJs(version: string): Promise<string>;
     getSpineWasm(version: string): Promise<Uint8Array>;
-    getPhysicsJs(): Promise<string>;
-    getPhysicsWasm(): Promise<Uint8Array>;
+    /** `backend` is the project's `physicsBackend`; Box2D when omitted. */
+    getPhysicsJs(backend?: string): Promise<string>;
+    getPhysicsWasm(backend?: string): Promise<Uint8Array>;
     toAssetUrl?(path: string): string;
     executeCommand?(command: string, args: string[], cwd: string): Promise<{ exitCode: number; stdout: string; stderr: string }>;
 }
@@ -9,10 +9,14 @@
 
 export type SpineVersion = 'none' | '3.8' | '4.1' | '4.2';
 
+export type PhysicsBackend = 'box2d' | 'rapier';
+
 export interface ProjectConfig {
     name: string;
     version: string;
     engine: string;
+    /** Engine build the project runs on; the editor's built-in one when unset. */
+    engineVersion?: string;
     defaultScene: string;
     created: string;
     modified: string;
@@ -25,6 +29,8 @@ export interface ProjectConfig {
     physicsContactHertz?: number;
     physicsContactDampingRatio?: number;
     physicsContactSpeed?: number;
+    /** Physics engine; Rapier is deterministic across platforms. */
+    physicsBackend?: PhysicsBackend;
     designResolution?: { width: number; height: number };
     atlasMaxSize?: number;
     atlasPadding?: number;
@@ -5,6 +5,7 @@ import { getEditorStore } from '../store';
 import { getAllMenus, getMenuItems } from '../menus/MenuRegistry';
 import { getAssetDatabase } from '../asset/AssetDatabase';
 import { getNavigationService } from '../services';
+import { recordFeatureUsage } from '../logging/telemetry';
 
 type ResultKind = 'entity' | 'asset' | 'action';
 
@@ -126,7 +127,10 @@ class CommandPaletteInstance {
                         icon: icons.cog(14),
                         score: m.score + 1,
                         matches: m.matches,
-                        execute: () => item.action(),
+                        execute: () => {
+                            recordFeatureUsage(item.id);
+                            item.action();
+                        },
                     });
                 }
             }
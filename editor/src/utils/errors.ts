@@ -0,0 +1,21 @@
+/**
+ * An error returned by a backend command: a stable code, its parameters and
+ * the message already formatted in the editor's language.
+ */
+export interface BackendError {
+    code: string;
+    params: Record<string, string>;
+    message: string;
+}
+
+export function isBackendError(err: unknown): err is BackendError {
+    return typeof err === 'object' && err !== null
+        && typeof (err as BackendError).code === 'string'
+        && typeof (err as BackendError).message === 'string';
+}
+
+/** The text to show for anything a command or promise rejected with. */
+export function errorMessage(err: unknown): string {
+    if (err instanceof Error || isBackendError(err)) return err.message;
+    return String(err);
+}
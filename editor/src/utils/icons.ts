@@ -38,6 +38,7 @@ import {
     RefreshCw,
     Layers,
     Code,
+    Terminal,
     Volume2,
     VolumeX,
     Braces,
@@ -180,6 +181,7 @@ export const icons = {
     refresh: (size?: number) => renderIcon(RefreshCw as IconNode, size),
     layers: (size?: number) => renderIcon(Layers as IconNode, size),
     code: (size?: number) => renderIcon(Code as IconNode, size),
+    terminal: (size?: number) => renderIcon(Terminal as IconNode, size),
     volume: (size?: number) => renderIcon(Volume2 as IconNode, size),
     volumeX: (size?: number) => renderIcon(VolumeX as IconNode, size),
     braces: (size?: number) => renderIcon(Braces as IconNode, size),
@@ -640,6 +640,8 @@ export interface RuntimeInitConfig {
     physicsConfig?: { gravity?: Vec2; fixedTimestep?: number; subStepCount?: number; contactHertz?: number; contactDampingRatio?: number; contactSpeed?: number };
     manifest?: AddressableManifest | null;
     aspectRatio?: number;
+    /** Runs before a scene loads, e.g. to fetch the subpackage holding its assets. */
+    beforeSceneLoad?: (name: string) => Promise<unknown>;
 }
 
 export async function initRuntime(config: RuntimeInitConfig): Promise<void> {
@@ -659,8 +661,17 @@ export async function initRuntime(config: RuntimeInitConfig): Promise<void> {
     };
 
     const mgr = app.getResource(SceneManager);
+    const { beforeSceneLoad } = config;
     for (const scene of config.scenes) {
-        mgr.register(createRuntimeSceneConfig(scene.name, scene.data, sceneOpts));
+        const sceneConfig = createRuntimeSceneConfig(scene.name, scene.data, sceneOpts);
+        if (beforeSceneLoad) {
+            const setup = sceneConfig.setup!;
+            sceneConfig.setup = async (ctx) => {
+                await beforeSceneLoad(scene.name);
+                await setup(ctx);
+            };
+        }
+        mgr.register(sceneConfig);
     }
 
     if (firstScene) {
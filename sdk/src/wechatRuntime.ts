@@ -122,6 +122,8 @@ export interface WeChatRuntimeConfig {
     physicsConfig?: { gravity?: Vec2; fixedTimestep?: number; subStepCount?: number };
     spineFactories?: Record<string, (opts: unknown) => Promise<SpineWasmModule>>;
     physicsFactory?: (opts: unknown) => Promise<PhysicsWasmModule>;
+    /** Runs before a scene loads, e.g. to fetch the subpackage holding its assets. */
+    beforeSceneLoad?: (name: string) => Promise<unknown>;
 }
 
 export async function initWeChatRuntime(config: WeChatRuntimeConfig): Promise<void> {
@@ -200,6 +202,7 @@ export async function initWeChatRuntime(config: WeChatRuntimeConfig): Promise<vo
         physicsConfig: config.physicsConfig,
         manifest,
         aspectRatio: canvas.width / canvas.height,
+        beforeSceneLoad: config.beforeSceneLoad,
     });
 
     app.run();